@@ -0,0 +1,127 @@
+use criterion::{
+    criterion_group, criterion_main, measurement::WallTime, BatchSize, BenchmarkGroup, BenchmarkId,
+    Criterion,
+};
+use std::hint::black_box;
+use tucanos::{
+    mesh::{test_meshes::test_mesh_3d, PartitionType, SimplexMesh, Tetrahedron},
+    metric::AnisoMetric3d,
+};
+
+/// Number of uniform refinements of the cube mesh (6 x 8^n tetrahedra)
+const N_SPLITS: [usize; 3] = [3, 4, 5];
+
+fn cube_mesh(n_splits: usize) -> SimplexMesh<3, Tetrahedron> {
+    let mut mesh = test_mesh_3d();
+    for _ in 0..n_splits {
+        mesh = mesh.split();
+    }
+    mesh
+}
+
+/// Benchmark an operation that modifies a copy of `mesh` (the copy is not timed)
+fn bench_on_copy<F: Fn(&mut SimplexMesh<3, Tetrahedron>)>(
+    group: &mut BenchmarkGroup<'_, WallTime>,
+    name: &str,
+    mesh: &SimplexMesh<3, Tetrahedron>,
+    f: F,
+) {
+    group.bench_with_input(BenchmarkId::new(name, mesh.n_elems()), mesh, |b, mesh| {
+        b.iter_batched(
+            || mesh.clone(),
+            |mut mesh| {
+                f(&mut mesh);
+                mesh
+            },
+            BatchSize::LargeInput,
+        );
+    });
+}
+
+fn connectivity(c: &mut Criterion) {
+    let mut group = c.benchmark_group("connectivity");
+    for n_splits in N_SPLITS {
+        let mesh = cube_mesh(n_splits);
+        bench_on_copy(&mut group, "faces", &mesh, |m| {
+            m.compute_face_to_elems();
+        });
+        bench_on_copy(&mut group, "vertex_to_elems", &mesh, |m| {
+            m.compute_vertex_to_elems();
+        });
+        bench_on_copy(&mut group, "elem_to_elems", &mesh, |m| {
+            m.compute_elem_to_elems();
+        });
+        bench_on_copy(&mut group, "edges", &mesh, |m| {
+            m.compute_edges();
+        });
+        bench_on_copy(&mut group, "vertex_to_vertices", &mesh, |m| {
+            m.compute_vertex_to_vertices();
+        });
+        bench_on_copy(&mut group, "topology", &mesh, |m| {
+            m.compute_topology();
+        });
+    }
+    group.finish();
+}
+
+fn metric(c: &mut Criterion) {
+    let mut group = c.benchmark_group("metric");
+    for n_splits in N_SPLITS {
+        let mut mesh = cube_mesh(n_splits);
+        mesh.compute_vertex_to_vertices();
+        mesh.compute_volumes();
+        let f = mesh
+            .verts()
+            .map(|p| (10.0 * p[0]).tanh() + p[1] * p[1] * p[2])
+            .collect::<Vec<_>>();
+        let n_elems = mesh.n_elems();
+        group.bench_with_input(BenchmarkId::new("hessian", n_elems), &mesh, |b, mesh| {
+            b.iter(|| mesh.hessian(black_box(&f), None, true).unwrap());
+        });
+        group.bench_with_input(BenchmarkId::new("from_field", n_elems), &mesh, |b, mesh| {
+            b.iter(|| {
+                mesh.metric_from_field::<AnisoMetric3d>(black_box(&f), 2.0, n_elems)
+                    .unwrap()
+            });
+        });
+    }
+    group.finish();
+}
+
+fn refinement(c: &mut Criterion) {
+    let mut group = c.benchmark_group("split");
+    for n_splits in N_SPLITS {
+        let mesh = cube_mesh(n_splits - 1);
+        group.bench_with_input(
+            BenchmarkId::from_parameter(mesh.n_elems()),
+            &mesh,
+            |b, mesh| b.iter(|| black_box(mesh).split()),
+        );
+    }
+    group.finish();
+}
+
+fn partitioning(c: &mut Criterion) {
+    let mut group = c.benchmark_group("partition");
+    for n_splits in N_SPLITS {
+        let mesh = cube_mesh(n_splits);
+        bench_on_copy(&mut group, "hilbert", &mesh, |m| {
+            m.partition(PartitionType::Hilbert(8)).unwrap();
+        });
+        bench_on_copy(&mut group, "rcb", &mesh, |m| {
+            m.partition(PartitionType::Rcb(8)).unwrap();
+        });
+        #[cfg(feature = "metis")]
+        bench_on_copy(&mut group, "metis", &mesh, |m| {
+            m.partition(PartitionType::MetisKWay(8)).unwrap();
+        });
+        #[cfg(feature = "scotch")]
+        bench_on_copy(&mut group, "scotch", &mesh, |m| {
+            m.partition(PartitionType::Scotch(8)).unwrap();
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, connectivity, metric, refinement, partitioning);
+criterion_main!(benches);
@@ -0,0 +1,111 @@
+use criterion::{criterion_group, criterion_main, BatchSize, BenchmarkId, Criterion};
+use tucanos::{
+    geometry::NoGeometry,
+    mesh::{
+        test_meshes::{h_2d, h_3d, test_mesh_2d, test_mesh_3d},
+        PartitionType,
+    },
+    metric::IsoMetric,
+    remesher::{ParallelRemesher, ParallelRemeshingParams, Remesher, RemesherParams},
+};
+
+/// Scaling factors applied to the analytical sizes (the # of elements scales as 1 / s^d)
+const SCALES: [f64; 3] = [1.0, 0.7, 0.5];
+
+/// # of threads used to measure the scaling of the parallel remesher
+const N_THREADS: [usize; 4] = [1, 2, 4, 8];
+
+fn remesh_2d(c: &mut Criterion) {
+    let mut group = c.benchmark_group("remesh_2d");
+    group.sample_size(10);
+
+    let mut mesh = test_mesh_2d().split().split();
+    mesh.add_boundary_faces();
+    mesh.compute_topology();
+    let geom = NoGeometry();
+
+    for s in SCALES {
+        let h = mesh
+            .verts()
+            .map(|p| IsoMetric::<2>::from(s * h_2d(&p)))
+            .collect::<Vec<_>>();
+        group.bench_function(BenchmarkId::from_parameter(s), |b| {
+            b.iter_batched(
+                || Remesher::new(&mesh, &h, &geom).unwrap(),
+                |mut remesher| {
+                    remesher.remesh(RemesherParams::default(), &geom).unwrap();
+                    remesher
+                },
+                BatchSize::LargeInput,
+            );
+        });
+    }
+    group.finish();
+}
+
+fn remesh_3d(c: &mut Criterion) {
+    let mut group = c.benchmark_group("remesh_3d");
+    group.sample_size(10);
+
+    let mut mesh = test_mesh_3d().split().split();
+    mesh.compute_topology();
+    let geom = NoGeometry();
+
+    for s in SCALES {
+        let h = mesh
+            .verts()
+            .map(|p| IsoMetric::<3>::from(s * h_3d(&p)))
+            .collect::<Vec<_>>();
+        group.bench_function(BenchmarkId::from_parameter(s), |b| {
+            b.iter_batched(
+                || Remesher::new(&mesh, &h, &geom).unwrap(),
+                |mut remesher| {
+                    remesher.remesh(RemesherParams::default(), &geom).unwrap();
+                    remesher
+                },
+                BatchSize::LargeInput,
+            );
+        });
+    }
+    group.finish();
+}
+
+/// Strong scaling of `ParallelRemesher`: the same mesh is partitioned into 8 parts and remeshed
+/// with an increasing number of threads
+fn remesh_parallel_3d(c: &mut Criterion) {
+    let mut group = c.benchmark_group("remesh_parallel_3d");
+    group.sample_size(10);
+
+    let mut mesh = test_mesh_3d().split().split().split();
+    mesh.mut_etags().for_each(|t| *t = 1);
+    mesh.compute_topology();
+    let geom = NoGeometry();
+    let dd = ParallelRemesher::new(mesh, PartitionType::Hilbert(8)).unwrap();
+    let h = (0..dd.n_verts())
+        .map(|i| IsoMetric::<3>::from(h_3d(&dd.partitionned_mesh().vert(i))))
+        .collect::<Vec<_>>();
+
+    for n_threads in N_THREADS {
+        let pool = rayon::ThreadPoolBuilder::new()
+            .num_threads(n_threads)
+            .build()
+            .unwrap();
+        group.bench_function(BenchmarkId::from_parameter(n_threads), |b| {
+            b.iter(|| {
+                pool.install(|| {
+                    dd.remesh(
+                        &h,
+                        &geom,
+                        RemesherParams::default(),
+                        ParallelRemeshingParams::new(2, 1, 0),
+                    )
+                    .unwrap()
+                })
+            });
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, remesh_2d, remesh_3d, remesh_parallel_3d);
+criterion_main!(benches);
@@ -1,5 +1,79 @@
+/// Compile the OpenCASCADE interface, OpenCASCADE being found in `OCC_INCLUDE_DIR` and
+/// `OCC_LIB_DIR` if they are set
+#[cfg(feature = "occ")]
+fn build_occ(rpath: &mut Vec<String>) {
+    let include_dir =
+        std::env::var("OCC_INCLUDE_DIR").unwrap_or_else(|_| "/usr/include/opencascade".into());
+    cc::Build::new()
+        .cpp(true)
+        .flag_if_supported("-std=c++11")
+        .include(include_dir)
+        .file("src/geometry/occ_shim.cpp")
+        .compile("tucanos_occ");
+    println!("cargo:rerun-if-changed=src/geometry/occ_shim.cpp");
+    println!("cargo:rerun-if-env-changed=OCC_INCLUDE_DIR");
+
+    if let Ok(lib_dir) = std::env::var("OCC_LIB_DIR") {
+        println!("cargo:rustc-link-search={lib_dir}");
+        rpath.push(lib_dir);
+    }
+    println!("cargo:rerun-if-env-changed=OCC_LIB_DIR");
+    for lib in [
+        "TKSTEP",
+        "TKSTEPAttr",
+        "TKSTEPBase",
+        "TKSTEP209",
+        "TKIGES",
+        "TKXSBase",
+        "TKShHealing",
+        "TKPrim",
+        "TKTopAlgo",
+        "TKGeomAlgo",
+        "TKBRep",
+        "TKGeomBase",
+        "TKG3d",
+        "TKG2d",
+        "TKMath",
+        "TKernel",
+    ] {
+        println!("cargo:rustc-link-lib={lib}");
+    }
+}
+
+/// Detect if CGNS was built without 64-bit support (`cgsize_t` is then a 32-bit integer), from
+/// `cgnsconfig.h` found in `CGNS_INCLUDE_DIR` (or `/usr/include`)
+#[cfg(feature = "cgns")]
+fn check_cgns() {
+    let include_dir = std::env::var("CGNS_INCLUDE_DIR").unwrap_or_else(|_| "/usr/include".into());
+    let header = std::path::Path::new(&include_dir).join("cgnsconfig.h");
+    match std::fs::read_to_string(&header) {
+        Ok(content) => {
+            let is_64bit = content.lines().any(|l| {
+                let words = l.split_whitespace().collect::<Vec<_>>();
+                words.len() >= 3 && words[..2] == ["#define", "CG_BUILD_64BIT"] && words[2] != "0"
+            });
+            if !is_64bit {
+                println!("cargo:rustc-cfg=cgns_32bit");
+            }
+        }
+        Err(_) => println!(
+            "cargo:warning={} not found, assuming that CGNS is built with 64-bit support",
+            header.display()
+        ),
+    }
+    println!("cargo:rerun-if-env-changed=CGNS_INCLUDE_DIR");
+}
+
 fn main() {
     let mut rpath = Vec::new();
+
+    #[cfg(feature = "occ")]
+    build_occ(&mut rpath);
+
+    println!("cargo:rustc-check-cfg=cfg(cgns_32bit)");
+    #[cfg(feature = "cgns")]
+    check_cgns();
+
     // Ensure transition of libOL1 RPATH to dependent crates
     // See https://github.com/jeromerobert/marechal-libol-sys#using
     if let Ok(ol_rpath) = std::env::var("DEP_OL_1_RPATH") {
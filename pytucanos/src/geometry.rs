@@ -0,0 +1,37 @@
+use crate::{
+    mesh::{Mesh21, Mesh22, Mesh32, Mesh33},
+    to_pyerr,
+};
+use pyo3::prelude::*;
+use tucanos::{
+    geometry::LinearGeometry,
+    mesh::{Edge, Triangle},
+};
+
+/// Create a python class for a `LinearGeometry<$dim, $etype>`
+macro_rules! create_geometry {
+    ($name: ident, $dim: expr, $etype: ident, $mesh: ident, $bdy: ident) => {
+        #[doc = concat!("Piecewise linear geometry in ", stringify!($dim), "D")]
+        #[pyclass]
+        pub struct $name {
+            pub geom: LinearGeometry<$dim, $etype>,
+        }
+
+        #[pymethods]
+        impl $name {
+            /// Create a geometry from the boundary of `mesh` (or from a finer representation of
+            /// the boundary `boundary`). The mesh topology is required
+            #[new]
+            #[pyo3(signature = (mesh, boundary=None))]
+            pub fn new(mesh: &$mesh, boundary: Option<PyRef<$bdy>>) -> PyResult<Self> {
+                let bdy = boundary.map_or_else(|| mesh.mesh.boundary().0, |b| b.mesh.clone());
+                LinearGeometry::new(&mesh.mesh, bdy)
+                    .map(|geom| Self { geom })
+                    .map_err(to_pyerr)
+            }
+        }
+    };
+}
+
+create_geometry!(LinearGeometry3d, 3, Triangle, Mesh33, Mesh32);
+create_geometry!(LinearGeometry2d, 2, Edge, Mesh22, Mesh21);
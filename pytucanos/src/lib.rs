@@ -0,0 +1,87 @@
+mod geometry;
+mod mesh;
+mod remesher;
+
+use numpy::{
+    ndarray::Dimension, Element, IntoPyArray, PyArray2, PyArrayMethods, PyReadonlyArray,
+    PyReadonlyArray2, PyReadonlyArrayDyn, PyUntypedArrayMethods,
+};
+use pyo3::{
+    exceptions::{PyRuntimeError, PyValueError},
+    prelude::*,
+};
+use std::collections::HashMap;
+use tucanos::metric::Metric;
+
+/// Convert a tucanos error into a Python `RuntimeError`
+fn to_pyerr(e: Box<dyn std::error::Error>) -> PyErr {
+    PyRuntimeError::new_err(e.to_string())
+}
+
+/// Get the data of a contiguous numpy array
+fn as_slice<'a, T: Element, D: Dimension>(arr: &'a PyReadonlyArray<'_, T, D>) -> PyResult<&'a [T]> {
+    arr.as_slice()
+        .map_err(|e| PyValueError::new_err(e.to_string()))
+}
+
+/// Create a (n, m) numpy array from a vector of size n * m
+fn to_numpy_2d<T: Element>(
+    py: Python<'_>,
+    vec: Vec<T>,
+    m: usize,
+) -> PyResult<Bound<'_, PyArray2<T>>> {
+    let n = vec.len() / m;
+    vec.into_pyarray(py).reshape([n, m])
+}
+
+/// Create a (n, N) numpy array from a vector of metrics
+fn metric_to_numpy<const D: usize, M: Metric<D>>(
+    py: Python<'_>,
+    m: &[M],
+) -> PyResult<Bound<'_, PyArray2<f64>>> {
+    to_numpy_2d(py, m.iter().copied().flatten().collect(), M::N)
+}
+
+/// Create metrics from a (n, N) numpy array
+fn metric_from_numpy<const D: usize, M: Metric<D>>(
+    m: &PyReadonlyArray2<'_, f64>,
+    n_verts: usize,
+) -> PyResult<Vec<M>> {
+    if m.shape() != [n_verts, M::N] {
+        return Err(PyValueError::new_err(format!(
+            "Invalid metric shape {:?}, expected ({n_verts}, {})",
+            m.shape(),
+            M::N
+        )));
+    }
+    Ok(as_slice(m)?.chunks(M::N).map(M::from_slice).collect())
+}
+
+/// Get the (named) field data passed to the vtk writers
+fn field_data<'a>(
+    data: &'a Option<HashMap<String, PyReadonlyArrayDyn<'_, f64>>>,
+) -> PyResult<Option<HashMap<String, &'a [f64]>>> {
+    data.as_ref()
+        .map(|data| {
+            data.iter()
+                .map(|(name, arr)| Ok((name.clone(), as_slice(arr)?)))
+                .collect()
+        })
+        .transpose()
+}
+
+/// Python bindings for tucanos
+#[pymodule]
+fn pytucanos(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    m.add_class::<mesh::Mesh21>()?;
+    m.add_class::<mesh::Mesh22>()?;
+    m.add_class::<mesh::Mesh32>()?;
+    m.add_class::<mesh::Mesh33>()?;
+    m.add_class::<geometry::LinearGeometry2d>()?;
+    m.add_class::<geometry::LinearGeometry3d>()?;
+    m.add_class::<remesher::Remesher2dIso>()?;
+    m.add_class::<remesher::Remesher2dAniso>()?;
+    m.add_class::<remesher::Remesher3dIso>()?;
+    m.add_class::<remesher::Remesher3dAniso>()?;
+    Ok(())
+}
@@ -0,0 +1,282 @@
+use crate::{as_slice, field_data, metric_to_numpy, to_numpy_2d, to_pyerr};
+use numpy::{
+    IntoPyArray, PyArray1, PyArray2, PyReadonlyArray1, PyReadonlyArray2, PyReadonlyArrayDyn,
+    PyUntypedArrayMethods,
+};
+use pyo3::{exceptions::PyValueError, prelude::*};
+use std::collections::HashMap;
+use tucanos::{
+    mesh::{Edge, Elem, PartitionType, Point, SimplexMesh, Tetrahedron, Triangle},
+    metric::{AnisoMetric2d, AnisoMetric3d},
+    Idx, Tag,
+};
+
+/// Get the partitioning method from its name
+fn partition_type(method: &str, n_parts: Idx) -> PyResult<PartitionType> {
+    match method {
+        "hilbert" => Ok(PartitionType::Hilbert(n_parts)),
+        "rcb" => Ok(PartitionType::Rcb(n_parts)),
+        "scotch" => Ok(PartitionType::Scotch(n_parts)),
+        "metis_recursive" => Ok(PartitionType::MetisRecursive(n_parts)),
+        "metis_kway" => Ok(PartitionType::MetisKWay(n_parts)),
+        _ => Err(PyValueError::new_err(format!(
+            "Invalid partitioning method {method}"
+        ))),
+    }
+}
+
+/// Create a python class for a `SimplexMesh<$dim, $etype>` with the methods common to all the
+/// meshes and additional `$methods`
+macro_rules! create_mesh {
+    ($name: ident, $dim: expr, $etype: ident, { $($methods: tt)* }) => {
+        #[doc = concat!(
+            "Mesh with ", stringify!($etype), " elements in ", stringify!($dim), "D"
+        )]
+        #[pyclass]
+        pub struct $name {
+            pub mesh: SimplexMesh<$dim, $etype>,
+        }
+
+        #[pymethods]
+        impl $name {
+            /// Create a new mesh from numpy arrays. The data is copied. The vertex indices must
+            /// have the same type as `Idx`
+            #[new]
+            pub fn new(
+                coords: PyReadonlyArray2<f64>,
+                elems: PyReadonlyArray2<Idx>,
+                etags: PyReadonlyArray1<Tag>,
+                faces: PyReadonlyArray2<Idx>,
+                ftags: PyReadonlyArray1<Tag>,
+            ) -> PyResult<Self> {
+                const N_ELEM: usize = <$etype as Elem>::N_VERTS as usize;
+                const N_FACE: usize = <<$etype as Elem>::Face as Elem>::N_VERTS as usize;
+                if coords.shape()[1] != $dim {
+                    return Err(PyValueError::new_err("Invalid dimension 1 for coords"));
+                }
+                if elems.shape()[1] != N_ELEM {
+                    return Err(PyValueError::new_err("Invalid dimension 1 for elems"));
+                }
+                if etags.shape()[0] != elems.shape()[0] {
+                    return Err(PyValueError::new_err("Invalid dimension 0 for etags"));
+                }
+                if faces.shape()[1] != N_FACE {
+                    return Err(PyValueError::new_err("Invalid dimension 1 for faces"));
+                }
+                if ftags.shape()[0] != faces.shape()[0] {
+                    return Err(PyValueError::new_err("Invalid dimension 0 for ftags"));
+                }
+                let n_verts = coords.shape()[0];
+                if as_slice(&elems)?.iter().any(|&i| i as usize >= n_verts) {
+                    return Err(PyValueError::new_err("Invalid vertex index in elems"));
+                }
+                if as_slice(&faces)?.iter().any(|&i| i as usize >= n_verts) {
+                    return Err(PyValueError::new_err("Invalid vertex index in faces"));
+                }
+
+                let verts = as_slice(&coords)?
+                    .chunks($dim)
+                    .map(Point::<$dim>::from_column_slice)
+                    .collect();
+                let elems = as_slice(&elems)?
+                    .chunks(N_ELEM)
+                    .map($etype::from_slice)
+                    .collect();
+                let faces = as_slice(&faces)?
+                    .chunks(N_FACE)
+                    .map(<<$etype as Elem>::Face as Elem>::from_slice)
+                    .collect();
+                let etags = as_slice(&etags)?.to_vec();
+                let ftags = as_slice(&ftags)?.to_vec();
+
+                Ok(Self {
+                    mesh: SimplexMesh::new(verts, elems, etags, faces, ftags),
+                })
+            }
+
+            /// Read a Gmsh MSH 4.1 file
+            #[staticmethod]
+            pub fn read_gmsh(file_name: &str) -> PyResult<Self> {
+                SimplexMesh::read_gmsh(file_name)
+                    .map(|mesh| Self { mesh })
+                    .map_err(to_pyerr)
+            }
+
+            /// Write the mesh in a Gmsh MSH 4.1 file
+            #[pyo3(signature = (file_name, binary=false))]
+            pub fn write_gmsh(&self, file_name: &str, binary: bool) -> PyResult<()> {
+                self.mesh.write_gmsh(file_name, binary).map_err(to_pyerr)
+            }
+
+            /// Write the mesh and vertex / element fields in a legacy .vtk file
+            #[pyo3(signature = (file_name, vertex_data=None, elem_data=None))]
+            pub fn write_vtk(
+                &self,
+                file_name: &str,
+                vertex_data: Option<HashMap<String, PyReadonlyArrayDyn<f64>>>,
+                elem_data: Option<HashMap<String, PyReadonlyArrayDyn<f64>>>,
+            ) -> PyResult<()> {
+                self.mesh
+                    .write_vtk(file_name, field_data(&vertex_data)?, field_data(&elem_data)?)
+                    .map_err(to_pyerr)
+            }
+
+            /// Write the mesh and vertex / element fields in a .vtu file
+            #[pyo3(signature = (file_name, vertex_data=None, elem_data=None))]
+            pub fn write_vtu(
+                &self,
+                file_name: &str,
+                vertex_data: Option<HashMap<String, PyReadonlyArrayDyn<f64>>>,
+                elem_data: Option<HashMap<String, PyReadonlyArrayDyn<f64>>>,
+            ) -> PyResult<()> {
+                self.mesh
+                    .write_vtu(file_name, field_data(&vertex_data)?, field_data(&elem_data)?)
+                    .map_err(to_pyerr)
+            }
+
+            /// Get the # of vertices
+            #[must_use]
+            pub fn n_verts(&self) -> Idx {
+                self.mesh.n_verts()
+            }
+
+            /// Get the # of elements
+            #[must_use]
+            pub fn n_elems(&self) -> Idx {
+                self.mesh.n_elems()
+            }
+
+            /// Get the # of faces
+            #[must_use]
+            pub fn n_faces(&self) -> Idx {
+                self.mesh.n_faces()
+            }
+
+            /// Get the vertex coordinates, as a (n_verts, dim) array
+            pub fn get_coords<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyArray2<f64>>> {
+                let coords = self.mesh.verts().flat_map(<[f64; $dim]>::from).collect();
+                to_numpy_2d(py, coords, $dim)
+            }
+
+            /// Get the element connectivity
+            pub fn get_elems<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyArray2<Idx>>> {
+                let elems = self.mesh.elems().flatten().collect();
+                to_numpy_2d(py, elems, <$etype as Elem>::N_VERTS as usize)
+            }
+
+            /// Get the element tags
+            #[must_use]
+            pub fn get_etags<'py>(&self, py: Python<'py>) -> Bound<'py, PyArray1<Tag>> {
+                self.mesh.etags().collect::<Vec<_>>().into_pyarray(py)
+            }
+
+            /// Get the face connectivity
+            pub fn get_faces<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyArray2<Idx>>> {
+                let faces = self.mesh.faces().flatten().collect();
+                to_numpy_2d(py, faces, <<$etype as Elem>::Face as Elem>::N_VERTS as usize)
+            }
+
+            /// Get the face tags
+            #[must_use]
+            pub fn get_ftags<'py>(&self, py: Python<'py>) -> Bound<'py, PyArray1<Tag>> {
+                self.mesh.ftags().collect::<Vec<_>>().into_pyarray(py)
+            }
+
+            /// Add the missing boundary faces and the internal faces between elements with
+            /// different tags. Return the tags of the boundary and internal faces added
+            pub fn add_boundary_faces(&mut self) -> (HashMap<Tag, Tag>, HashMap<Tag, Vec<Tag>>) {
+                self.mesh.add_boundary_faces()
+            }
+
+            /// Compute the mesh topology
+            pub fn compute_topology(&mut self) {
+                self.mesh.compute_topology();
+            }
+
+            /// Split all the elements and faces uniformly
+            #[must_use]
+            pub fn split(&self) -> Self {
+                Self {
+                    mesh: self.mesh.split(),
+                }
+            }
+
+            /// Get the total volume of the mesh
+            #[must_use]
+            pub fn vol(&self) -> f64 {
+                self.mesh.vol()
+            }
+
+            /// Partition the mesh into `n_parts` using `method` ("hilbert", "rcb", "scotch",
+            /// "metis_recursive" or "metis_kway")
+            pub fn partition(&mut self, method: &str, n_parts: Idx) -> PyResult<()> {
+                self.mesh
+                    .partition(partition_type(method, n_parts)?)
+                    .map_err(to_pyerr)
+            }
+
+            /// Get the partition id of each element
+            pub fn get_partition<'py>(
+                &self,
+                py: Python<'py>,
+            ) -> PyResult<Bound<'py, PyArray1<Tag>>> {
+                self.mesh
+                    .get_partition()
+                    .map(|p| p.to_vec().into_pyarray(py))
+                    .map_err(to_pyerr)
+            }
+
+            $($methods)*
+        }
+    };
+}
+
+/// Add the methods for meshes of dimension $dim with boundary `$bdy` and anisotropic metric
+/// `$metric`
+macro_rules! create_volume_mesh {
+    ($name: ident, $dim: expr, $etype: ident, $bdy: ident, $metric: ident) => {
+        create_mesh!($name, $dim, $etype, {
+            /// Get the boundary faces as a mesh, and the indices of the boundary vertices in
+            /// the mesh
+            pub fn boundary<'py>(&self, py: Python<'py>) -> ($bdy, Bound<'py, PyArray1<Idx>>) {
+                let (mesh, ids) = self.mesh.boundary();
+                ($bdy { mesh }, ids.into_pyarray(py))
+            }
+
+            /// Compute the element-implied metric, as a (n_verts, N) array
+            pub fn implied_metric<'py>(
+                &self,
+                py: Python<'py>,
+            ) -> PyResult<Bound<'py, PyArray2<f64>>> {
+                let m = self.mesh.implied_metric().map_err(to_pyerr)?;
+                metric_to_numpy(py, &m)
+            }
+
+            /// Compute the optimal metric to control the interpolation error of a vertex field
+            /// `f` in L^p norm with a target complexity `n_elems`
+            pub fn metric_from_field<'py>(
+                &mut self,
+                py: Python<'py>,
+                f: PyReadonlyArray1<f64>,
+                p: f64,
+                n_elems: Idx,
+            ) -> PyResult<Bound<'py, PyArray2<f64>>> {
+                if f.shape()[0] != self.mesh.n_verts() as usize {
+                    return Err(PyValueError::new_err("Invalid dimension 0 for f"));
+                }
+                self.mesh.compute_vertex_to_vertices();
+                self.mesh.compute_volumes();
+                let m = self
+                    .mesh
+                    .metric_from_field::<$metric>(as_slice(&f)?, p, n_elems)
+                    .map_err(to_pyerr)?;
+                metric_to_numpy(py, &m)
+            }
+        });
+    };
+}
+
+create_volume_mesh!(Mesh33, 3, Tetrahedron, Mesh32, AnisoMetric3d);
+create_volume_mesh!(Mesh22, 2, Triangle, Mesh21, AnisoMetric2d);
+create_mesh!(Mesh32, 3, Triangle, {});
+create_mesh!(Mesh21, 2, Edge, {});
@@ -0,0 +1,224 @@
+use crate::{
+    geometry::{LinearGeometry2d, LinearGeometry3d},
+    mesh::{Mesh22, Mesh33},
+    metric_from_numpy, metric_to_numpy, to_pyerr,
+};
+use numpy::{IntoPyArray, PyArray1, PyArray2, PyReadonlyArray2};
+use pyo3::{exceptions::PyValueError, prelude::*, types::PyDict};
+use tucanos::{
+    mesh::{Tetrahedron, Triangle},
+    metric::{AnisoMetric2d, AnisoMetric3d, IsoMetric},
+    remesher::{Remesher, RemesherParams, RemeshingMode, SmoothingType},
+    Idx,
+};
+
+/// Get the remeshing mode from its name
+fn remeshing_mode(mode: &str) -> PyResult<RemeshingMode> {
+    match mode {
+        "full" => Ok(RemeshingMode::Full),
+        "coarsen_only" => Ok(RemeshingMode::CoarsenOnly),
+        "refine_only" => Ok(RemeshingMode::RefineOnly),
+        _ => Err(PyValueError::new_err(format!(
+            "Invalid remeshing mode {mode}"
+        ))),
+    }
+}
+
+/// Get the smoothing method from its name
+fn smoothing_type(method: &str) -> PyResult<SmoothingType> {
+    match method {
+        "laplacian" => Ok(SmoothingType::Laplacian),
+        "laplacian2" => Ok(SmoothingType::Laplacian2),
+        "avro" => Ok(SmoothingType::Avro),
+        "optimization" => Ok(SmoothingType::Optimization),
+        _ => Err(PyValueError::new_err(format!(
+            "Invalid smoothing method {method}"
+        ))),
+    }
+}
+
+/// Get the remesher parameters from keyword arguments with the same names as the fields of
+/// `RemesherParams`; the default values are used for the missing arguments. `mode` ("full",
+/// "coarsen_only" or "refine_only") and `smooth_type` ("laplacian", "laplacian2", "avro" or
+/// "optimization") are given by name
+fn remesher_params(kwargs: Option<&Bound<'_, PyDict>>) -> PyResult<RemesherParams> {
+    let mut params = RemesherParams::default();
+    let Some(kwargs) = kwargs else {
+        return Ok(params);
+    };
+    for (key, value) in kwargs {
+        let key = key.extract::<String>()?;
+        macro_rules! set_fields {
+            ($($field: ident),*) => {
+                match key.as_str() {
+                    "mode" => params.mode = remeshing_mode(&value.extract::<String>()?)?,
+                    "smooth_type" => {
+                        params.smooth_type = smoothing_type(&value.extract::<String>()?)?;
+                    }
+                    $(stringify!($field) => params.$field = value.extract()?,)*
+                    _ => return Err(PyValueError::new_err(format!("Invalid parameter {key}"))),
+                }
+            };
+        }
+        set_fields!(
+            num_iter,
+            two_steps,
+            split_max_iter,
+            split_min_l_rel,
+            split_min_l_abs,
+            split_min_q_rel,
+            split_min_q_abs,
+            collapse_max_iter,
+            collapse_max_l_rel,
+            collapse_max_l_abs,
+            collapse_min_q_rel,
+            collapse_min_q_abs,
+            swap_max_iter,
+            swap_max_l_rel,
+            swap_max_l_abs,
+            swap_min_l_rel,
+            swap_min_l_abs,
+            smooth_iter,
+            smooth_relax,
+            smooth_keep_local_minima,
+            max_angle,
+            chordal_error,
+            frozen_verts,
+            frozen_face_tags,
+            frozen_elem_tags,
+            deterministic,
+            debug
+        );
+    }
+    Ok(params)
+}
+
+/// Create a python class for a `Remesher<$dim, $etype, $metric>`
+macro_rules! create_remesher {
+    ($name: ident, $dim: expr, $etype: ident, $metric: ty, $mesh: ident, $geom: ident) => {
+        #[doc = concat!("Remesher for a ", stringify!($mesh), " using a ", stringify!($metric))]
+        #[pyclass]
+        pub struct $name {
+            pub remesher: Remesher<$dim, $etype, $metric>,
+        }
+
+        #[pymethods]
+        impl $name {
+            /// Create a remesher from a mesh, a metric given as a (n_verts, N) array and a
+            /// geometry
+            #[new]
+            pub fn new(
+                mesh: &$mesh,
+                metric: PyReadonlyArray2<f64>,
+                geometry: &$geom,
+            ) -> PyResult<Self> {
+                let m: Vec<$metric> = metric_from_numpy(&metric, mesh.mesh.n_verts() as usize)?;
+                Remesher::new(&mesh.mesh, &m, &geometry.geom)
+                    .map(|remesher| Self { remesher })
+                    .map_err(to_pyerr)
+            }
+
+            /// Remesh to match the metric. The remeshing parameters may be given as keyword
+            /// arguments with the same names as the fields of `RemesherParams`
+            #[pyo3(signature = (geometry, **params))]
+            pub fn remesh(
+                &mut self,
+                geometry: &$geom,
+                params: Option<&Bound<'_, PyDict>>,
+            ) -> PyResult<()> {
+                let params = remesher_params(params)?;
+                self.remesher
+                    .remesh(params, &geometry.geom)
+                    .map_err(to_pyerr)
+            }
+
+            /// Check that the remesher holds a valid mesh
+            pub fn check(&self) -> PyResult<()> {
+                self.remesher.check().map_err(to_pyerr)
+            }
+
+            /// Get the # of vertices
+            #[must_use]
+            pub fn n_verts(&self) -> Idx {
+                self.remesher.n_verts()
+            }
+
+            /// Get the # of elements
+            #[must_use]
+            pub fn n_elems(&self) -> Idx {
+                self.remesher.n_elems()
+            }
+
+            /// Get the # of edges
+            #[must_use]
+            pub fn n_edges(&self) -> Idx {
+                self.remesher.n_edges()
+            }
+
+            /// Get the mesh; only the boundary faces are kept if `only_bdy_faces`
+            #[pyo3(signature = (only_bdy_faces=false))]
+            #[must_use]
+            pub fn to_mesh(&self, only_bdy_faces: bool) -> $mesh {
+                $mesh {
+                    mesh: self.remesher.to_mesh(only_bdy_faces),
+                }
+            }
+
+            /// Get the metric at the vertices, as a (n_verts, N) array
+            pub fn get_metric<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyArray2<f64>>> {
+                metric_to_numpy(py, &self.remesher.metrics())
+            }
+
+            /// Get the edge lengths in metric space
+            #[must_use]
+            pub fn lengths<'py>(&self, py: Python<'py>) -> Bound<'py, PyArray1<f64>> {
+                self.remesher.lengths().into_pyarray(py)
+            }
+
+            /// Get the element qualities
+            #[must_use]
+            pub fn qualities<'py>(&self, py: Python<'py>) -> Bound<'py, PyArray1<f64>> {
+                self.remesher.qualities().into_pyarray(py)
+            }
+
+            /// Get the remeshing statistics as a json string
+            #[must_use]
+            pub fn stats_json(&self) -> String {
+                self.remesher.stats_json()
+            }
+        }
+    };
+}
+
+create_remesher!(
+    Remesher2dIso,
+    2,
+    Triangle,
+    IsoMetric<2>,
+    Mesh22,
+    LinearGeometry2d
+);
+create_remesher!(
+    Remesher2dAniso,
+    2,
+    Triangle,
+    AnisoMetric2d,
+    Mesh22,
+    LinearGeometry2d
+);
+create_remesher!(
+    Remesher3dIso,
+    3,
+    Tetrahedron,
+    IsoMetric<3>,
+    Mesh33,
+    LinearGeometry3d
+);
+create_remesher!(
+    Remesher3dAniso,
+    3,
+    Tetrahedron,
+    AnisoMetric3d,
+    Mesh33,
+    LinearGeometry3d
+);
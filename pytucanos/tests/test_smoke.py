@@ -0,0 +1,93 @@
+import itertools
+
+import numpy as np
+import pytest
+
+import pytucanos
+
+
+def unit_square():
+    coords = np.array([[0.0, 0.0], [1.0, 0.0], [1.0, 1.0], [0.0, 1.0]])
+    elems = np.array([[0, 1, 2], [0, 2, 3]], dtype=np.uint32)
+    etags = np.array([1, 2], dtype=np.int16)
+    faces = np.zeros((0, 2), dtype=np.uint32)
+    ftags = np.zeros(0, dtype=np.int16)
+    return pytucanos.Mesh22(coords, elems, etags, faces, ftags)
+
+
+def unit_cube():
+    # vertex i is at (i & 1, (i >> 1) & 1, (i >> 2) & 1), and the cube is split into 6 tetrahedra
+    # along the diagonal (0, 7)
+    coords = np.array([[i & 1, (i >> 1) & 1, (i >> 2) & 1] for i in range(8)], dtype=np.float64)
+    elems = []
+    for a, b, _ in itertools.permutations([1, 2, 4]):
+        e = [0, a, a + b, 7]
+        x = coords[e[1:]] - coords[e[0]]
+        if np.linalg.det(x) < 0:
+            e[2], e[3] = e[3], e[2]
+        elems.append(e)
+    elems = np.array(elems, dtype=np.uint32)
+    etags = np.ones(6, dtype=np.int16)
+    faces = np.zeros((0, 3), dtype=np.uint32)
+    ftags = np.zeros(0, dtype=np.int16)
+    return pytucanos.Mesh33(coords, elems, etags, faces, ftags)
+
+
+@pytest.mark.parametrize(
+    "mesh, geom, remesher, h",
+    [
+        (unit_square, pytucanos.LinearGeometry2d, pytucanos.Remesher2dIso, 0.1),
+        (unit_cube, pytucanos.LinearGeometry3d, pytucanos.Remesher3dIso, 0.25),
+    ],
+)
+def test_remesh(tmp_path, mesh, geom, remesher, h):
+    msh = mesh()
+    msh.add_boundary_faces()
+    msh.compute_topology()
+    assert msh.vol() == pytest.approx(1.0)
+
+    geometry = geom(msh)
+    m = np.full((msh.n_verts(), 1), h)
+    rmsh = remesher(msh, m, geometry)
+    rmsh.remesh(geometry, mode="full", smooth_type="laplacian", num_iter=2)
+    rmsh.check()
+
+    msh = rmsh.to_mesh()
+    assert msh.n_verts() > 4
+    assert msh.vol() == pytest.approx(1.0)
+
+    fname = tmp_path / "mesh.vtu"
+    msh.write_vtu(str(fname), vertex_data={"m": rmsh.get_metric()})
+    assert fname.exists()
+
+
+def test_invalid_indices():
+    coords = np.array([[0.0, 0.0], [1.0, 0.0], [1.0, 1.0], [0.0, 1.0]])
+    etags = np.array([1, 2], dtype=np.int16)
+    faces = np.zeros((0, 2), dtype=np.uint32)
+    ftags = np.zeros(0, dtype=np.int16)
+
+    elems = np.array([[0, 1, 2], [0, 2, 4]], dtype=np.uint32)
+    with pytest.raises(ValueError):
+        pytucanos.Mesh22(coords, elems, etags, faces, ftags)
+
+    elems = np.array([[0, 1, 2], [0, 2, 3]], dtype=np.uint32)
+    faces = np.array([[0, 1], [1, 5]], dtype=np.uint32)
+    ftags = np.array([1, 2], dtype=np.int16)
+    with pytest.raises(ValueError):
+        pytucanos.Mesh22(coords, elems, etags, faces, ftags)
+
+
+def test_invalid_params():
+    msh = unit_square()
+    msh.add_boundary_faces()
+    msh.compute_topology()
+    geometry = pytucanos.LinearGeometry2d(msh)
+    rmsh = pytucanos.Remesher2dIso(msh, np.full((msh.n_verts(), 1), 0.1), geometry)
+
+    with pytest.raises(ValueError):
+        rmsh.remesh(geometry, mode="coarsen")
+    with pytest.raises(ValueError):
+        rmsh.remesh(geometry, smooth_type="unknown")
+    with pytest.raises(ValueError):
+        rmsh.remesh(geometry, unknown=1)
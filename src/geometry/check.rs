@@ -0,0 +1,249 @@
+use crate::{
+    geometry::Geometry,
+    mesh::{Elem, GElem, SimplexMesh},
+    Dim, Error, Idx, Result, TopoTag,
+};
+use log::debug;
+use rustc_hash::FxHashMap;
+use std::fmt;
+
+/// Statistics of the distances between the mesh entities with a given tag and the geometry
+#[derive(Clone, Debug)]
+pub struct GeometryDistances {
+    /// The topological tag
+    pub tag: TopoTag,
+    /// The # of entities
+    pub count: Idx,
+    /// The maximum distance
+    pub max: f64,
+    /// The mean distance
+    pub mean: f64,
+    /// The 90th percentile of the distances
+    pub q90: f64,
+}
+
+impl GeometryDistances {
+    fn new(tag: TopoTag, mut dist: Vec<f64>) -> Self {
+        dist.sort_by(f64::total_cmp);
+        let n = dist.len();
+        Self {
+            tag,
+            count: n as Idx,
+            max: dist[n - 1],
+            mean: dist.iter().sum::<f64>() / n as f64,
+            q90: dist[(0.9 * (n - 1) as f64).round() as usize],
+        }
+    }
+}
+
+impl fmt::Display for GeometryDistances {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "tag {:?}: {} entities, d_max = {:.2e}, d_mean = {:.2e}, d_90 = {:.2e}",
+            self.tag, self.count, self.max, self.mean, self.q90
+        )
+    }
+}
+
+/// Distances between the boundary of a mesh and a geometry, computed by `check_geometry`
+#[derive(Clone, Debug)]
+pub struct GeometryReport {
+    /// The distances of the boundary vertices, per vertex tag (sorted)
+    pub verts: Vec<GeometryDistances>,
+    /// The distances of the face centers, per face tag (sorted)
+    pub faces: Vec<GeometryDistances>,
+    /// The # of boundary vertices that are not projected by the geometry (see
+    /// `Geometry::is_projected`), per vertex tag (sorted). They are not included in `verts`
+    pub skipped_verts: Vec<(TopoTag, Idx)>,
+    /// The # of face centers that are not projected by the geometry, per face tag (sorted).
+    /// They are not included in `faces`
+    pub skipped_faces: Vec<(TopoTag, Idx)>,
+}
+
+impl GeometryReport {
+    /// The maximum distance between the boundary vertices or face centers and the geometry
+    #[must_use]
+    pub fn max_distance(&self) -> f64 {
+        self.verts
+            .iter()
+            .chain(self.faces.iter())
+            .map(|d| d.max)
+            .fold(0.0, f64::max)
+    }
+
+    /// Check that the distances between the boundary and the geometry are lower than `tol` (the
+    /// skipped entities are not checked)
+    pub fn check(&self, tol: f64) -> Result<()> {
+        let invalid = self
+            .verts
+            .iter()
+            .chain(self.faces.iter())
+            .filter(|d| d.max > tol)
+            .map(|d| d.tag)
+            .collect::<Vec<_>>();
+        if invalid.is_empty() {
+            Ok(())
+        } else {
+            Err(Error::from(&format!(
+                "distance to the geometry larger than {tol} for tags {invalid:?}"
+            )))
+        }
+    }
+}
+
+impl fmt::Display for GeometryReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "vertices:")?;
+        for d in &self.verts {
+            writeln!(f, "  {d}")?;
+        }
+        writeln!(f, "faces:")?;
+        for d in &self.faces {
+            writeln!(f, "  {d}")?;
+        }
+        if !(self.skipped_verts.is_empty() && self.skipped_faces.is_empty()) {
+            writeln!(f, "not projected:")?;
+            for (tag, n) in &self.skipped_verts {
+                writeln!(f, "  vertex tag {tag:?}: {n} entities")?;
+            }
+            for (tag, n) in &self.skipped_faces {
+                writeln!(f, "  face tag {tag:?}: {n} entities")?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Compute the distances between the boundary vertices (with a topological dimension < D)
+/// and the face centers of `mesh` and their projection onto `geom`, to detect mismatched
+/// mesh / geometry pairs before adaptation. The entities that `geom` does not project are
+/// counted separately. The mesh topology must be computed
+pub fn check_geometry<const D: usize, E: Elem, G: Geometry<D>>(
+    mesh: &SimplexMesh<D, E>,
+    geom: &G,
+) -> Result<GeometryReport> {
+    debug!("Check the distances between the mesh and the geometry");
+
+    let vtags = mesh.get_vertex_tags()?;
+
+    let mut verts: FxHashMap<TopoTag, Vec<f64>> = FxHashMap::default();
+    let mut skipped_verts: FxHashMap<TopoTag, Idx> = FxHashMap::default();
+    for (mut p, tag) in mesh.verts().zip(vtags.iter()) {
+        if tag.0 < D as Dim {
+            if geom.is_projected(tag) {
+                let d = geom.project(&mut p, tag);
+                verts.entry(*tag).or_default().push(d);
+            } else {
+                *skipped_verts.entry(*tag).or_default() += 1;
+            }
+        }
+    }
+
+    let mut faces: FxHashMap<TopoTag, Vec<f64>> = FxHashMap::default();
+    let mut skipped_faces: FxHashMap<TopoTag, Idx> = FxHashMap::default();
+    for (gf, tag) in mesh.gfaces().zip(mesh.ftags()) {
+        let tag = (E::Face::DIM as Dim, tag);
+        if geom.is_projected(&tag) {
+            let mut c = gf.center();
+            let d = geom.project(&mut c, &tag);
+            faces.entry(tag).or_default().push(d);
+        } else {
+            *skipped_faces.entry(tag).or_default() += 1;
+        }
+    }
+
+    let stats = |map: FxHashMap<TopoTag, Vec<f64>>| {
+        let mut res = map
+            .into_iter()
+            .map(|(tag, dist)| GeometryDistances::new(tag, dist))
+            .collect::<Vec<_>>();
+        res.sort_by_key(|d| d.tag);
+        res
+    };
+    let counts = |map: FxHashMap<TopoTag, Idx>| {
+        let mut res = map.into_iter().collect::<Vec<_>>();
+        res.sort_unstable();
+        res
+    };
+    let res = GeometryReport {
+        verts: stats(verts),
+        faces: stats(faces),
+        skipped_verts: counts(skipped_verts),
+        skipped_faces: counts(skipped_faces),
+    };
+    debug!(
+        "Max. distance between the mesh and the geometry: {:.2e}",
+        res.max_distance()
+    );
+    let n_skipped = res
+        .skipped_verts
+        .iter()
+        .chain(res.skipped_faces.iter())
+        .map(|&(_, n)| n)
+        .sum::<Idx>();
+    if n_skipped > 0 {
+        debug!("{n_skipped} entities are not projected by the geometry");
+    }
+
+    Ok(res)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::check_geometry;
+    use crate::{
+        geometry::{LinearGeometry, NoGeometry},
+        mesh::{test_meshes::test_mesh_3d, Point},
+        Idx, Result,
+    };
+
+    #[test]
+    fn test_check_geometry() -> Result<()> {
+        let mut mesh = test_mesh_3d().split().split();
+        mesh.add_boundary_faces();
+        mesh.compute_topology();
+
+        let (bdy, _) = mesh.boundary();
+        let geom = LinearGeometry::new(&mesh, bdy)?;
+
+        let report = check_geometry(&mesh, &geom)?;
+        assert_eq!(report.faces.len(), 6);
+        assert_eq!(report.faces[0].tag, (2, 1));
+        assert_eq!(report.faces[0].count, 32);
+        assert!(report.max_distance() < 1e-12);
+        assert!(report.check(1e-12).is_ok());
+        // the corners are not projected
+        assert_eq!(report.skipped_verts.len(), 8);
+        assert!(report
+            .skipped_verts
+            .iter()
+            .all(|&(tag, n)| tag.0 == 0 && n == 1));
+        assert!(report.verts.iter().all(|d| d.tag.0 == 1));
+        assert!(report.skipped_faces.is_empty());
+
+        // shift the vertices of the face z = 0
+        mesh.mut_verts()
+            .filter(|p| p[2] < 1e-12)
+            .for_each(|p| *p -= Point::<3>::new(0.0, 0.0, 0.1));
+        let report = check_geometry(&mesh, &geom)?;
+        assert!(f64::abs(report.faces[0].max - 0.1) < 1e-12);
+        assert!(f64::abs(report.faces[0].mean - 0.1) < 1e-12);
+        assert!(report.faces[1].max < 1e-12);
+        assert!(f64::abs(report.max_distance() - 0.1) < 1e-12);
+        assert!(report.check(0.01).is_err());
+        assert!(report.check(0.2).is_ok());
+
+        // nothing is projected
+        let report = check_geometry(&mesh, &NoGeometry())?;
+        assert!(report.verts.is_empty());
+        assert!(report.faces.is_empty());
+        assert_eq!(report.skipped_faces.len(), 6);
+        assert_eq!(
+            report.skipped_faces.iter().map(|&(_, n)| n).sum::<Idx>(),
+            mesh.n_faces()
+        );
+
+        Ok(())
+    }
+}
@@ -0,0 +1,215 @@
+use crate::{
+    geometry::Geometry,
+    mesh::{Point, Topology},
+    Dim, Error, Result, TopoTag,
+};
+use log::warn;
+
+type ScalarFn<const D: usize> = Box<dyn Fn(&Point<D>) -> f64 + Send + Sync>;
+type VectorFn<const D: usize> = Box<dyn Fn(&Point<D>) -> Point<D> + Send + Sync>;
+
+/// Geometry defined by an implicit function `phi`, the boundary being the surface
+/// ```math
+/// \phi(x) = 0
+/// ```
+/// with the normal given by the gradient of `phi` (i.e. outwards if `phi < 0` inside the
+/// domain). All the boundary entities (faces, but also edges and corners that lie on the
+/// intersection of the surface patches) are projected onto the same surface
+pub struct ImplicitGeometry<const D: usize> {
+    /// The implicit function
+    phi: ScalarFn<D>,
+    /// The gradient of the implicit function
+    grad_phi: VectorFn<D>,
+    /// The tolerance on `phi` for the Newton projection
+    tol: f64,
+    /// The maximum # of Newton iterations
+    max_iter: usize,
+}
+
+impl<const D: usize> ImplicitGeometry<D> {
+    /// Create an `ImplicitGeometry` from an implicit function and its gradient
+    #[must_use]
+    pub fn new<F, G>(phi: F, grad_phi: G) -> Self
+    where
+        F: Fn(&Point<D>) -> f64 + Send + Sync + 'static,
+        G: Fn(&Point<D>) -> Point<D> + Send + Sync + 'static,
+    {
+        Self {
+            phi: Box::new(phi),
+            grad_phi: Box::new(grad_phi),
+            tol: 1e-12,
+            max_iter: 50,
+        }
+    }
+
+    /// Create the `ImplicitGeometry` of a sphere (or circle in 2D), the domain being inside
+    #[must_use]
+    pub fn sphere(center: Point<D>, radius: f64) -> Self {
+        Self::new(
+            move |p| (p - center).norm() - radius,
+            move |p| {
+                // the gradient is not defined at the center
+                let d = p - center;
+                let n = d.norm();
+                if n > 0.0 {
+                    d / n
+                } else {
+                    Point::<D>::zeros()
+                }
+            },
+        )
+    }
+
+    /// Set the tolerance on `phi` and the maximum # of iterations of the Newton projection
+    pub fn set_tolerance(&mut self, tol: f64, max_iter: usize) {
+        self.tol = tol;
+        self.max_iter = max_iter;
+    }
+
+    /// Project `pt` onto the surface using Newton iterations
+    /// ```math
+    /// x_{k+1} = x_k - \frac{\phi(x_k)}{\|\nabla \phi(x_k)\|^2} \nabla \phi(x_k)
+    /// ```
+    /// which converge to the closest point if `pt` is close enough to the surface
+    pub fn newton_projection(&self, pt: &Point<D>) -> Result<Point<D>> {
+        let mut p = *pt;
+        for _ in 0..self.max_iter {
+            let phi = (self.phi)(&p);
+            if phi.abs() < self.tol {
+                return Ok(p);
+            }
+            let grad = (self.grad_phi)(&p);
+            let n = grad.norm_squared();
+            if n < f64::EPSILON {
+                return Err(Error::from("ImplicitGeometry: zero gradient"));
+            }
+            p -= (phi / n) * grad;
+        }
+        Err(Error::from(&format!(
+            "ImplicitGeometry: projection of {pt:?} did not converge"
+        )))
+    }
+}
+
+impl<const D: usize> Geometry<D> for ImplicitGeometry<D> {
+    fn check(&self, _topo: &Topology) -> Result<()> {
+        Ok(())
+    }
+
+    /// Project `pt` onto the surface (for all the boundary entities). If the projection fails,
+    /// `pt` is not modified and the first order estimate of the distance
+    /// $`|\phi(x)| / \|\nabla \phi(x)\|`$ is returned (infinite if the gradient vanishes)
+    fn project(&self, pt: &mut Point<D>, tag: &TopoTag) -> f64 {
+        assert!(tag.0 < D as Dim);
+
+        match self.newton_projection(pt) {
+            Ok(p) => {
+                let dist = (p - *pt).norm();
+                *pt = p;
+                dist
+            }
+            Err(e) => {
+                warn!("{e}, the vertex is not projected");
+                let grad = (self.grad_phi)(pt).norm();
+                if grad > 0.0 {
+                    (self.phi)(pt).abs() / grad
+                } else {
+                    f64::INFINITY
+                }
+            }
+        }
+    }
+
+    fn angle(&self, pt: &Point<D>, n: &Point<D>, tag: &TopoTag) -> f64 {
+        assert_eq!(tag.0, D as Dim - 1);
+
+        let p = self.newton_projection(pt).unwrap_or_else(|e| {
+            warn!("{e}, the normal is computed at {pt:?}");
+            *pt
+        });
+        let n_ref = (self.grad_phi)(&p).normalize();
+        let cos_a = n.dot(&n_ref).clamp(-1.0, 1.0);
+        f64::acos(cos_a).to_degrees()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ImplicitGeometry;
+    use crate::{
+        geometry::Geometry,
+        mesh::{test_meshes::test_mesh_2d, Point},
+        Result,
+    };
+
+    #[test]
+    fn test_sphere() {
+        let geom = ImplicitGeometry::sphere(Point::<3>::new(1.0, 0.0, 0.0), 1.0);
+
+        let mut p = Point::<3>::new(3.0, 0.0, 0.0);
+        let d = geom.project(&mut p, &(2, 1));
+        assert!(f64::abs(d - 1.0) < 1e-12);
+        assert!((p - Point::<3>::new(2.0, 0.0, 0.0)).norm() < 1e-12);
+
+        let mut p = Point::<3>::new(1.0, 0.3, 0.4);
+        let d = geom.project(&mut p, &(2, 1));
+        assert!(f64::abs(d - 0.5) < 1e-12);
+        assert!((p - Point::<3>::new(1.0, 0.6, 0.8)).norm() < 1e-12);
+
+        // edges and corners are projected onto the surface too
+        for dim in [0, 1] {
+            let mut p = Point::<3>::new(1.0, 0.3, 0.4);
+            let d = geom.project(&mut p, &(dim, 1));
+            assert!(f64::abs(d - 0.5) < 1e-12);
+            assert!((p - Point::<3>::new(1.0, 0.6, 0.8)).norm() < 1e-12);
+        }
+
+        // no projection from the center
+        let mut p = Point::<3>::new(1.0, 0.0, 0.0);
+        assert!(geom.project(&mut p, &(2, 1)).is_infinite());
+        assert!((p - Point::<3>::new(1.0, 0.0, 0.0)).norm() < 1e-12);
+
+        let n = Point::<3>::new(0.0, 1.0, 0.0);
+        let a = geom.angle(&Point::<3>::new(1.0, 1.1, 0.0), &n, &(2, 1));
+        assert!(a < 1e-4);
+        let a = geom.angle(&Point::<3>::new(2.0, 0.0, 0.0), &n, &(2, 1));
+        assert!(f64::abs(a - 90.0) < 1e-4);
+    }
+
+    #[test]
+    fn test_no_convergence() {
+        let mut geom = ImplicitGeometry::sphere(Point::<3>::new(1.0, 0.0, 0.0), 1.0);
+        geom.set_tolerance(1e-12, 0);
+
+        let mut p = Point::<3>::new(3.0, 0.0, 0.0);
+        assert!(geom.newton_projection(&p).is_err());
+        // estimate of the distance
+        assert!(f64::abs(geom.project(&mut p, &(2, 1)) - 1.0) < 1e-12);
+        assert!((p - Point::<3>::new(3.0, 0.0, 0.0)).norm() < 1e-12);
+
+        let n = Point::<3>::new(1.0, 0.0, 0.0);
+        assert!(geom.angle(&p, &n, &(2, 1)) < 1e-4);
+    }
+
+    #[test]
+    fn test_ellipse() -> Result<()> {
+        let mut geom = ImplicitGeometry::new(
+            |p: &Point<2>| p[0] * p[0] + 4.0 * p[1] * p[1] - 1.0,
+            |p: &Point<2>| Point::<2>::new(2.0 * p[0], 8.0 * p[1]),
+        );
+
+        let mut mesh = test_mesh_2d().split().split();
+        mesh.mut_verts().for_each(|p| *p *= 0.9);
+        for p in mesh.verts() {
+            if p.norm() > 0.0 {
+                let q = geom.newton_projection(&p)?;
+                assert!(f64::abs(q[0] * q[0] + 4.0 * q[1] * q[1] - 1.0) < 1e-12);
+            }
+        }
+
+        geom.set_tolerance(1e-12, 1);
+        assert!(geom.newton_projection(&Point::<2>::new(0.1, 0.1)).is_err());
+
+        Ok(())
+    }
+}
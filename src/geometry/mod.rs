@@ -1,4 +1,14 @@
+mod check;
 mod curvature;
+mod implicit;
+#[cfg(feature = "occ")]
+mod occ;
+
+pub use check::{check_geometry, GeometryDistances, GeometryReport};
+pub use curvature::compute_curvature_tensor;
+pub use implicit::ImplicitGeometry;
+#[cfg(feature = "occ")]
+pub use occ::OccGeometry;
 
 use crate::{
     geometry::curvature::HasCurvature,
@@ -23,6 +33,21 @@ pub trait Geometry<const D: usize>: Send + Sync {
     /// Compute the angle between a vector n and the normal at the projection of pt onto the geometry
     fn angle(&self, pt: &Point<D>, n: &Point<D>, tag: &TopoTag) -> f64;
 
+    /// Check if `project` actually projects the vertices with topological tag `tag` (the
+    /// vertices that are not projected are left unchanged and a zero distance is returned). By
+    /// default, all the boundary entities are projected
+    fn is_projected(&self, _tag: &TopoTag) -> bool {
+        true
+    }
+
+    /// Compute the principal curvature directions, scaled by the principal curvatures, at the
+    /// projection of `pt` onto the surface with topological tag `tag` (the second direction is
+    /// only defined in 3D). The curvature is only defined on the surfaces, i.e. for
+    /// `tag.0 == D - 1`. By default, the curvature is not available
+    fn curvature(&self, _pt: &Point<D>, _tag: &TopoTag) -> Result<(Point<D>, Option<Point<D>>)> {
+        Err(Error::from("curvature not available for this geometry"))
+    }
+
     /// Compute the max distance between the face centers and the geometry normals
     fn project_vertices<E: Elem>(&self, mesh: &mut SimplexMesh<D, E>) -> f64 {
         let vtags = mesh.get_vertex_tags().unwrap().to_vec();
@@ -81,6 +106,10 @@ impl<const D: usize> Geometry<D> for NoGeometry<D> {
         assert_eq!(tag.0, D as Dim - 1);
         0.
     }
+
+    fn is_projected(&self, _tag: &TopoTag) -> bool {
+        false
+    }
 }
 
 /// Geometry for a patch of faces with a constant tag
@@ -92,7 +121,7 @@ struct LinearPatchGeometry<const D: usize> {
 impl<const D: usize> LinearPatchGeometry<D> {
     /// Create a `LinearPatchGeometry` from a `SimplexMesh`
     pub fn new<E: Elem>(mesh: &SimplexMesh<D, E>) -> Self {
-        let tree = mesh.compute_elem_tree();
+        let tree = <DefaultObjectIndex<D> as ObjectIndex<D>>::new(mesh);
 
         Self { tree }
     }
@@ -121,7 +150,7 @@ where
 {
     /// Create a `LinearPatchGeometry` from a `SimplexMesh`
     pub fn new(mut mesh: SimplexMesh<D, E>) -> Self {
-        let tree = mesh.compute_elem_tree();
+        let tree = <DefaultObjectIndex<D> as ObjectIndex<D>>::new(&mesh);
         mesh.compute_face_to_elems();
         mesh.add_boundary_faces();
         mesh.clear_face_to_elems();
@@ -191,8 +220,13 @@ where
     }
 }
 
-/// Piecewise linear (stl-like) representation of a geometry
-/// doc TODO
+/// Piecewise linear (stl-like) representation of a geometry, defined by a surface mesh with the
+/// face tags of the mesh to adapt. This surface can be the mesh boundary, or a finer reference
+/// surface mesh (e.g. the initial high-resolution mesh) so that the boundary vertices created
+/// during adaptation are projected onto the original geometry.
+///
+/// The projections, normals and curvatures are computed per tag using an `ObjectIndex` on each
+/// patch
 pub struct LinearGeometry<const D: usize, E: Elem>
 where
     SimplexMesh<D, E>: HasCurvature<D>,
@@ -268,6 +302,16 @@ where
         self.patches.get(&tag).unwrap().curvature(pt)
     }
 
+    /// Get the normal of the reference surface with tag `tag` at the projection of `pt`
+    pub fn normal(&self, pt: &Point<D>, tag: Tag) -> Result<Point<D>> {
+        let patch = self
+            .patches
+            .get(&tag)
+            .ok_or_else(|| Error::from(&format!("LinearGeometry: invalid face tag {tag}")))?;
+        let idx = patch.tree.nearest_elem(pt);
+        Ok(patch.mesh.gelem(patch.mesh.elem(idx)).normal())
+    }
+
     pub fn write_curvature(&self, fname: &str) -> Result<()> {
         for (tag, patch) in &self.patches {
             patch.write_curvature(&String::from(fname).replace(".vtu", &format!("_{tag}.vtu")))?;
@@ -309,12 +353,24 @@ where
     fn angle(&self, pt: &Point<D>, n: &Point<D>, tag: &TopoTag) -> f64 {
         assert_eq!(tag.0, D as Dim - 1);
 
-        let patch = self.patches.get(&tag.1).unwrap();
-        let idx = patch.tree.nearest_elem(pt);
-        let n_ref = patch.mesh.gelem(patch.mesh.elem(idx)).normal();
+        let n_ref = self.normal(pt, tag.1).unwrap();
         let cos_a = n.dot(&n_ref).clamp(-1.0, 1.0);
         f64::acos(cos_a).to_degrees()
     }
+
+    /// The corners are not projected
+    fn is_projected(&self, tag: &TopoTag) -> bool {
+        tag.0 != 0
+    }
+
+    fn curvature(&self, pt: &Point<D>, tag: &TopoTag) -> Result<(Point<D>, Option<Point<D>>)> {
+        if tag.0 != D as Dim - 1 {
+            return Err(Error::from(&format!(
+                "LinearGeometry: curvature not defined for {tag:?}"
+            )));
+        }
+        Self::curvature(self, pt, tag.1)
+    }
 }
 
 #[cfg(test)]
@@ -439,4 +495,32 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    fn test_reference_geometry() -> Result<()> {
+        let mut mesh = test_mesh_3d().split();
+        mesh.add_boundary_faces();
+        mesh.compute_topology();
+
+        // finer reference surface
+        let mut fine = test_mesh_3d().split().split().split();
+        fine.add_boundary_faces();
+        let (bdy, _) = fine.boundary();
+        let mut geom = LinearGeometry::new(&mesh, bdy)?;
+        geom.compute_curvature();
+
+        let mut pt = Point::<3>::new(0.3, 0.6, 0.1);
+        let d = geom.project(&mut pt, &(2, 1));
+        assert!(f64::abs(d - 0.1) < 1e-12);
+        assert!((pt - Point::<3>::new(0.3, 0.6, 0.0)).norm() < 1e-12);
+
+        let n = geom.normal(&pt, 1)?;
+        assert!(f64::abs(n[2].abs() - 1.0) < 1e-12);
+        assert!(geom.normal(&pt, 10).is_err());
+        assert!(geom.curvature(&pt, 1).is_ok());
+        assert!(Geometry::curvature(&geom, &pt, &(2, 1)).is_ok());
+        assert!(Geometry::curvature(&geom, &pt, &(1, 1)).is_err());
+
+        Ok(())
+    }
 }
@@ -0,0 +1,415 @@
+use crate::{
+    geometry::Geometry,
+    mesh::{Elem, Point, SimplexMesh, Topology},
+    Error, Result, Tag, TopoTag,
+};
+use log::{debug, warn};
+use rustc_hash::FxHashMap;
+use std::{
+    ffi::CString,
+    os::raw::{c_char, c_int, c_void},
+    path::Path,
+};
+
+extern "C" {
+    fn tucanos_occ_read(file_name: *const c_char, format: c_int, shape: *mut *mut c_void) -> c_int;
+    #[cfg(test)]
+    fn tucanos_occ_make_box(p0: *const f64, p1: *const f64, shape: *mut *mut c_void) -> c_int;
+    #[cfg(test)]
+    fn tucanos_occ_write_step(shape: *const c_void, file_name: *const c_char) -> c_int;
+    fn tucanos_occ_free(shape: *mut c_void);
+    fn tucanos_occ_count(
+        shape: *const c_void,
+        n_faces: *mut c_int,
+        n_edges: *mut c_int,
+        n_verts: *mut c_int,
+    ) -> c_int;
+    fn tucanos_occ_edge_faces(
+        shape: *const c_void,
+        edge: c_int,
+        faces: *mut c_int,
+        n_max: c_int,
+        n: *mut c_int,
+    ) -> c_int;
+    fn tucanos_occ_vertex_edges(
+        shape: *const c_void,
+        vert: c_int,
+        edges: *mut c_int,
+        n_max: c_int,
+        n: *mut c_int,
+    ) -> c_int;
+    fn tucanos_occ_project_face(
+        shape: *const c_void,
+        face: c_int,
+        pt: *const f64,
+        res: *mut f64,
+        normal: *mut f64,
+        u: *mut f64,
+        v: *mut f64,
+    ) -> c_int;
+    fn tucanos_occ_project_edge(
+        shape: *const c_void,
+        edge: c_int,
+        pt: *const f64,
+        res: *mut f64,
+    ) -> c_int;
+    fn tucanos_occ_vertex(shape: *const c_void, vert: c_int, res: *mut f64) -> c_int;
+}
+
+type AdjacentFn =
+    unsafe extern "C" fn(*const c_void, c_int, *mut c_int, c_int, *mut c_int) -> c_int;
+
+/// Max # of adjacent entities read for a CAD edge or vertex
+const MAX_ADJACENT: usize = 16;
+
+fn check(ier: c_int) -> Result<()> {
+    match ier {
+        0 => Ok(()),
+        1 => Err(Error::from("occ: unable to read / write the file")),
+        2 => Err(Error::from("occ: invalid entity index")),
+        3 => Err(Error::from("occ: projection failed")),
+        _ => Err(Error::from(&format!("occ: error {ier}"))),
+    }
+}
+
+/// OpenCASCADE shape, freed when dropped
+struct Shape(*mut c_void);
+
+impl Shape {
+    /// Get the sorted ids of the entities adjacent to entity `i` using `f`
+    fn adjacent(&self, f: AdjacentFn, i: c_int) -> Result<Vec<c_int>> {
+        let mut ids = [0; MAX_ADJACENT];
+        let mut n = 0;
+        check(unsafe { f(self.0, i, ids.as_mut_ptr(), MAX_ADJACENT as c_int, &mut n) })?;
+        let mut ids = ids[..n as usize].to_vec();
+        ids.sort_unstable();
+        ids.dedup();
+        Ok(ids)
+    }
+}
+
+impl Drop for Shape {
+    fn drop(&mut self) {
+        unsafe { tucanos_occ_free(self.0) };
+    }
+}
+
+// The shape is never modified after it is read, and the OpenCASCADE queries used only read it
+unsafe impl Send for Shape {}
+unsafe impl Sync for Shape {}
+
+/// Get the point closest to `pt` among the points computed by `f` for the CAD entities `ids`
+fn closest<F: Fn(c_int, &mut Point<3>) -> c_int>(
+    pt: &Point<3>,
+    ids: &[c_int],
+    f: F,
+) -> Result<Point<3>> {
+    let mut res = Vec::with_capacity(ids.len());
+    for &i in ids {
+        let mut p = Point::<3>::zeros();
+        check(f(i, &mut p))?;
+        res.push(p);
+    }
+    res.into_iter()
+        .min_by(|p, q| (p - pt).norm().total_cmp(&(q - pt).norm()))
+        .ok_or_else(|| Error::from("occ: no CAD entity"))
+}
+
+/// Projection of a point onto a CAD face
+struct FaceProjection {
+    pt: Point<3>,
+    normal: Point<3>,
+    u: Point<3>,
+    v: Point<3>,
+}
+
+/// CAD geometry read from a STEP or IGES file with OpenCASCADE (requires the `occ` feature).
+///
+/// The face tags of the mesh are the (1-based) indices of the CAD faces, in the order given by
+/// OpenCASCADE (e.g. the order of the faces in Gmsh when the mesh is generated from the same
+/// file). The edges and corners of the mesh topology are matched to the CAD edges and vertices
+/// with the same adjacent faces and edges
+pub struct OccGeometry {
+    shape: Shape,
+    /// # of CAD faces
+    n_faces: usize,
+    /// CAD edges for each edge tag of the mesh topology
+    edges: FxHashMap<Tag, Vec<c_int>>,
+    /// CAD vertices for each vertex tag of the mesh topology
+    verts: FxHashMap<Tag, Vec<c_int>>,
+}
+
+impl OccGeometry {
+    /// Create an `OccGeometry` from a .step / .stp or .iges / .igs file, the topology of `mesh`
+    /// being used to match the mesh and CAD entities
+    pub fn new<E: Elem>(file_name: &str, mesh: &SimplexMesh<3, E>) -> Result<Self> {
+        debug!("Read {file_name}");
+        let format = match Path::new(file_name)
+            .extension()
+            .and_then(|e| e.to_str())
+            .map(str::to_lowercase)
+            .as_deref()
+        {
+            Some("step" | "stp") => 0,
+            Some("iges" | "igs") => 1,
+            _ => {
+                return Err(Error::from(&format!(
+                    "occ: invalid file extension for {file_name}"
+                )))
+            }
+        };
+        let c_name = CString::new(file_name)?;
+        let mut ptr = std::ptr::null_mut();
+        check(unsafe { tucanos_occ_read(c_name.as_ptr(), format, &mut ptr) })?;
+
+        Self::from_shape(Shape(ptr), mesh.get_topology()?)
+    }
+
+    fn from_shape(shape: Shape, topo: &Topology) -> Result<Self> {
+        let (mut n_faces, mut n_edges, mut n_verts) = (0, 0, 0);
+        check(unsafe { tucanos_occ_count(shape.0, &mut n_faces, &mut n_edges, &mut n_verts) })?;
+        debug!("CAD geometry: {n_faces} faces, {n_edges} edges, {n_verts} vertices");
+
+        // Edges: match the faces adjacent to the CAD edges with the parents in the topology
+        let mut cad_edges = FxHashMap::<_, Vec<_>>::default();
+        for i in 1..=n_edges {
+            let faces = shape
+                .adjacent(tucanos_occ_edge_faces, i)?
+                .into_iter()
+                .map(|j| j as Tag)
+                .collect::<Vec<_>>();
+            cad_edges.entry(faces).or_default().push(i);
+        }
+        let mut edges = FxHashMap::default();
+        let mut edge_tags = FxHashMap::default();
+        for tag in topo.tags(1) {
+            let node = topo.get((1, tag)).unwrap();
+            let mut parents = node.parents.iter().copied().collect::<Vec<_>>();
+            parents.sort_unstable();
+            let ids = cad_edges.get(&parents).ok_or_else(|| {
+                Error::from(&format!(
+                    "occ: no CAD edge between the faces {parents:?} for edge {tag}"
+                ))
+            })?;
+            for &i in ids {
+                edge_tags.insert(i, tag);
+            }
+            edges.insert(tag, ids.clone());
+        }
+
+        // Vertices: match the edges adjacent to the CAD vertices with the parents in the topology
+        let mut cad_verts = FxHashMap::<_, Vec<_>>::default();
+        for i in 1..=n_verts {
+            let mut tags = shape
+                .adjacent(tucanos_occ_vertex_edges, i)?
+                .iter()
+                .filter_map(|j| edge_tags.get(j).copied())
+                .collect::<Vec<_>>();
+            tags.sort_unstable();
+            tags.dedup();
+            cad_verts.entry(tags).or_default().push(i);
+        }
+        let mut verts = FxHashMap::default();
+        for tag in topo.tags(0) {
+            let node = topo.get((0, tag)).unwrap();
+            let mut parents = node.parents.iter().copied().collect::<Vec<_>>();
+            parents.sort_unstable();
+            let ids = cad_verts.get(&parents).ok_or_else(|| {
+                Error::from(&format!(
+                    "occ: no CAD vertex between the edges {parents:?} for vertex {tag}"
+                ))
+            })?;
+            verts.insert(tag, ids.clone());
+        }
+
+        let res = Self {
+            shape,
+            n_faces: n_faces as usize,
+            edges,
+            verts,
+        };
+        res.check(topo)?;
+
+        Ok(res)
+    }
+
+    /// Project `pt` onto the CAD face with tag `tag`
+    fn project_face(&self, pt: &Point<3>, tag: Tag) -> Result<FaceProjection> {
+        let mut res = FaceProjection {
+            pt: Point::<3>::zeros(),
+            normal: Point::<3>::zeros(),
+            u: Point::<3>::zeros(),
+            v: Point::<3>::zeros(),
+        };
+        check(unsafe {
+            tucanos_occ_project_face(
+                self.shape.0,
+                tag as c_int,
+                pt.as_ptr(),
+                res.pt.as_mut_ptr(),
+                res.normal.as_mut_ptr(),
+                res.u.as_mut_ptr(),
+                res.v.as_mut_ptr(),
+            )
+        })?;
+        Ok(res)
+    }
+
+    /// Project `pt` onto the closest CAD edge with tag `tag`
+    fn project_edge(&self, pt: &Point<3>, tag: Tag) -> Result<Point<3>> {
+        let ids = self
+            .edges
+            .get(&tag)
+            .ok_or_else(|| Error::from(&format!("occ: invalid edge tag {tag}")))?;
+        closest(pt, ids, |i, p| unsafe {
+            tucanos_occ_project_edge(self.shape.0, i, pt.as_ptr(), p.as_mut_ptr())
+        })
+    }
+
+    /// Get the closest CAD vertex with tag `tag`
+    fn project_vertex(&self, pt: &Point<3>, tag: Tag) -> Result<Point<3>> {
+        let ids = self
+            .verts
+            .get(&tag)
+            .ok_or_else(|| Error::from(&format!("occ: invalid vertex tag {tag}")))?;
+        closest(pt, ids, |i, p| unsafe {
+            tucanos_occ_vertex(self.shape.0, i, p.as_mut_ptr())
+        })
+    }
+}
+
+impl Geometry<3> for OccGeometry {
+    fn check(&self, topo: &Topology) -> Result<()> {
+        for tag in topo.tags(2) {
+            if tag < 1 || tag as usize > self.n_faces {
+                return Err(Error::from(&format!(
+                    "occ: no CAD face for face tag {tag} ({} faces)",
+                    self.n_faces
+                )));
+            }
+        }
+        for tag in topo.tags(1) {
+            if !self.edges.contains_key(&tag) {
+                return Err(Error::from(&format!("occ: no CAD edge for edge tag {tag}")));
+            }
+        }
+        for tag in topo.tags(0) {
+            if !self.verts.contains_key(&tag) {
+                return Err(Error::from(&format!(
+                    "occ: no CAD vertex for vertex tag {tag}"
+                )));
+            }
+        }
+        Ok(())
+    }
+
+    fn project(&self, pt: &mut Point<3>, tag: &TopoTag) -> f64 {
+        assert!(tag.0 < 3);
+
+        let p = match tag.0 {
+            2 => self.project_face(pt, tag.1).map(|p| p.pt),
+            1 => self.project_edge(pt, tag.1),
+            0 => self.project_vertex(pt, tag.1),
+            _ => unreachable!("{:?}", tag),
+        };
+        match p {
+            Ok(p) => {
+                let dist = (p - *pt).norm();
+                *pt = p;
+                dist
+            }
+            Err(e) => {
+                warn!("Unable to project {pt:?} onto {tag:?}: {e}");
+                0.0
+            }
+        }
+    }
+
+    fn angle(&self, pt: &Point<3>, n: &Point<3>, tag: &TopoTag) -> f64 {
+        assert_eq!(tag.0, 2);
+
+        match self.project_face(pt, tag.1) {
+            Ok(p) => {
+                let cos_a = n.dot(&p.normal).clamp(-1.0, 1.0);
+                f64::acos(cos_a).to_degrees()
+            }
+            Err(e) => {
+                warn!("Unable to compute the normal at {pt:?} on {tag:?}: {e}");
+                0.0
+            }
+        }
+    }
+
+    fn curvature(&self, pt: &Point<3>, tag: &TopoTag) -> Result<(Point<3>, Option<Point<3>>)> {
+        if tag.0 != 2 {
+            return Err(Error::from(&format!(
+                "OccGeometry: curvature not defined for {tag:?}"
+            )));
+        }
+        let p = self.project_face(pt, tag.1)?;
+        Ok((p.u, Some(p.v)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{tucanos_occ_make_box, tucanos_occ_write_step, OccGeometry, Shape};
+    use crate::{
+        geometry::Geometry,
+        mesh::{test_meshes::test_mesh_3d, GElem, Point},
+        Result,
+    };
+    use std::ffi::CString;
+
+    #[test]
+    fn test_box() -> Result<()> {
+        let p0 = Point::<3>::new(0.0, 0.0, 0.0);
+        let p1 = Point::<3>::new(1.0, 1.0, 1.0);
+        let mut ptr = std::ptr::null_mut();
+        super::check(unsafe { tucanos_occ_make_box(p0.as_ptr(), p1.as_ptr(), &mut ptr) })?;
+        let shape = Shape(ptr);
+        let dir = tempfile::tempdir()?;
+        let fname = dir.path().join("box.step");
+        let fname = fname.to_str().unwrap();
+        let c_name = CString::new(fname)?;
+        super::check(unsafe { tucanos_occ_write_step(shape.0, c_name.as_ptr()) })?;
+
+        let mut mesh = test_mesh_3d().split();
+        mesh.compute_topology();
+        let geom = OccGeometry::new(fname, &mesh)?;
+
+        // Renumber the mesh faces to match the CAD faces
+        let mut new_tags = vec![0; 7];
+        for (gf, tag) in mesh.gfaces().zip(mesh.ftags()) {
+            let c = gf.center();
+            let best = (1..=6)
+                .min_by(|&i, &j| {
+                    let di = geom.project(&mut c.clone(), &(2, i));
+                    let dj = geom.project(&mut c.clone(), &(2, j));
+                    di.partial_cmp(&dj).unwrap()
+                })
+                .unwrap();
+            new_tags[tag as usize] = best;
+        }
+        mesh.mut_ftags().for_each(|t| *t = new_tags[*t as usize]);
+        mesh.clear_topology();
+        mesh.compute_topology();
+        let geom = OccGeometry::new(fname, &mesh)?;
+
+        assert!(geom.project_vertices(&mut mesh) < 1e-10);
+        assert!(geom.max_distance(&mesh) < 1e-10);
+        assert!(geom.max_normal_angle(&mesh) < 1e-6);
+
+        let (gf, tag) = mesh.gfaces().zip(mesh.ftags()).next().unwrap();
+        let mut p = gf.center() + 0.3 * gf.normal();
+        let d = geom.project(&mut p, &(2, tag));
+        assert!(f64::abs(d - 0.3) < 1e-10);
+        assert!((p - gf.center()).norm() < 1e-10);
+
+        let (u, v) = geom.curvature(&p, &(2, tag))?;
+        assert!(u.norm() < 1e-10);
+        assert!(v.unwrap().norm() < 1e-10);
+
+        Ok(())
+    }
+}
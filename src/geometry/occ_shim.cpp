@@ -0,0 +1,274 @@
+// C interface to OpenCASCADE used by src/geometry/occ.rs (compiled by build.rs when the occ
+// feature is enabled). The faces, edges and vertices are numbered from 1 in the order given by
+// TopExp::MapShapes, and all the functions return a status code (TUCANOS_OCC_OK on success).
+
+#include <BRepBuilderAPI_Sewing.hxx>
+#include <BRepPrimAPI_MakeBox.hxx>
+#include <BRepTools.hxx>
+#include <BRep_Tool.hxx>
+#include <GeomAPI_ProjectPointOnCurve.hxx>
+#include <GeomAPI_ProjectPointOnSurf.hxx>
+#include <GeomLProp_SLProps.hxx>
+#include <Geom_Curve.hxx>
+#include <Geom_Surface.hxx>
+#include <IFSelect_ReturnStatus.hxx>
+#include <IGESControl_Reader.hxx>
+#include <Precision.hxx>
+#include <STEPControl_Reader.hxx>
+#include <STEPControl_Writer.hxx>
+#include <ShapeAnalysis_Surface.hxx>
+#include <Standard_Failure.hxx>
+#include <TopExp.hxx>
+#include <TopTools_IndexedDataMapOfShapeListOfShape.hxx>
+#include <TopTools_IndexedMapOfShape.hxx>
+#include <TopTools_ListOfShape.hxx>
+#include <TopoDS.hxx>
+#include <TopoDS_Shape.hxx>
+
+#include <algorithm>
+
+namespace {
+
+const int TUCANOS_OCC_OK = 0;
+const int TUCANOS_OCC_ERR_READ = 1;
+const int TUCANOS_OCC_ERR_INDEX = 2;
+const int TUCANOS_OCC_ERR_PROJECTION = 3;
+const int TUCANOS_OCC_ERR_EXCEPTION = 4;
+
+struct Shape {
+  TopoDS_Shape shape;
+  TopTools_IndexedMapOfShape faces;
+  TopTools_IndexedMapOfShape edges;
+  TopTools_IndexedMapOfShape verts;
+  TopTools_IndexedDataMapOfShapeListOfShape edge_to_faces;
+  TopTools_IndexedDataMapOfShapeListOfShape vert_to_edges;
+
+  explicit Shape(const TopoDS_Shape &s) : shape(s) {
+    TopExp::MapShapes(shape, TopAbs_FACE, faces);
+    TopExp::MapShapes(shape, TopAbs_EDGE, edges);
+    TopExp::MapShapes(shape, TopAbs_VERTEX, verts);
+    TopExp::MapShapesAndUniqueAncestors(shape, TopAbs_EDGE, TopAbs_FACE, edge_to_faces);
+    TopExp::MapShapesAndUniqueAncestors(shape, TopAbs_VERTEX, TopAbs_EDGE, vert_to_edges);
+  }
+};
+
+void to_array(const gp_XYZ &p, double *res) {
+  res[0] = p.X();
+  res[1] = p.Y();
+  res[2] = p.Z();
+}
+
+// Get the ids of the shapes in `ancestors` of the shape with id `i` (in `shapes`)
+int ancestors(const TopTools_IndexedMapOfShape &shapes,
+              const TopTools_IndexedDataMapOfShapeListOfShape &ancestors,
+              const TopTools_IndexedMapOfShape &ids, int i, int *res, int n_max, int *n) {
+  if (i < 1 || i > shapes.Extent()) {
+    return TUCANOS_OCC_ERR_INDEX;
+  }
+  *n = 0;
+  const TopTools_ListOfShape &lst = ancestors.FindFromKey(shapes(i));
+  for (TopTools_ListOfShape::Iterator it(lst); it.More() && *n < n_max; it.Next()) {
+    res[(*n)++] = ids.FindIndex(it.Value());
+  }
+  return TUCANOS_OCC_OK;
+}
+
+} // namespace
+
+extern "C" {
+
+// Read a STEP (format = 0) or IGES (format = 1) file
+int tucanos_occ_read(const char *file_name, int format, void **shape) {
+  try {
+    TopoDS_Shape s;
+    if (format == 0) {
+      STEPControl_Reader reader;
+      if (reader.ReadFile(file_name) != IFSelect_RetDone) {
+        return TUCANOS_OCC_ERR_READ;
+      }
+      reader.TransferRoots();
+      s = reader.OneShape();
+    } else {
+      IGESControl_Reader reader;
+      if (reader.ReadFile(file_name) != IFSelect_RetDone) {
+        return TUCANOS_OCC_ERR_READ;
+      }
+      reader.TransferRoots();
+      s = reader.OneShape();
+      // the IGES faces are usually not connected
+      BRepBuilderAPI_Sewing sewing;
+      sewing.Add(s);
+      sewing.Perform();
+      s = sewing.SewedShape();
+    }
+    if (s.IsNull()) {
+      return TUCANOS_OCC_ERR_READ;
+    }
+    *shape = new Shape(s);
+  } catch (Standard_Failure &) {
+    return TUCANOS_OCC_ERR_EXCEPTION;
+  }
+  return TUCANOS_OCC_OK;
+}
+
+// Create an axis aligned box
+int tucanos_occ_make_box(const double *p0, const double *p1, void **shape) {
+  try {
+    BRepPrimAPI_MakeBox box(gp_Pnt(p0[0], p0[1], p0[2]), gp_Pnt(p1[0], p1[1], p1[2]));
+    *shape = new Shape(box.Shape());
+  } catch (Standard_Failure &) {
+    return TUCANOS_OCC_ERR_EXCEPTION;
+  }
+  return TUCANOS_OCC_OK;
+}
+
+// Write a shape to a STEP file
+int tucanos_occ_write_step(const void *shape, const char *file_name) {
+  try {
+    STEPControl_Writer writer;
+    if (writer.Transfer(static_cast<const Shape *>(shape)->shape, STEPControl_AsIs) !=
+            IFSelect_RetDone ||
+        writer.Write(file_name) != IFSelect_RetDone) {
+      return TUCANOS_OCC_ERR_READ;
+    }
+  } catch (Standard_Failure &) {
+    return TUCANOS_OCC_ERR_EXCEPTION;
+  }
+  return TUCANOS_OCC_OK;
+}
+
+void tucanos_occ_free(void *shape) { delete static_cast<Shape *>(shape); }
+
+// Get the # of faces, edges and vertices
+int tucanos_occ_count(const void *shape, int *n_faces, int *n_edges, int *n_verts) {
+  const Shape *s = static_cast<const Shape *>(shape);
+  *n_faces = s->faces.Extent();
+  *n_edges = s->edges.Extent();
+  *n_verts = s->verts.Extent();
+  return TUCANOS_OCC_OK;
+}
+
+// Get the (at most n_max) faces adjacent to an edge
+int tucanos_occ_edge_faces(const void *shape, int edge, int *faces, int n_max, int *n) {
+  const Shape *s = static_cast<const Shape *>(shape);
+  try {
+    return ancestors(s->edges, s->edge_to_faces, s->faces, edge, faces, n_max, n);
+  } catch (Standard_Failure &) {
+    return TUCANOS_OCC_ERR_EXCEPTION;
+  }
+}
+
+// Get the (at most n_max) edges adjacent to a vertex
+int tucanos_occ_vertex_edges(const void *shape, int vert, int *edges, int n_max, int *n) {
+  const Shape *s = static_cast<const Shape *>(shape);
+  try {
+    return ancestors(s->verts, s->vert_to_edges, s->edges, vert, edges, n_max, n);
+  } catch (Standard_Failure &) {
+    return TUCANOS_OCC_ERR_EXCEPTION;
+  }
+}
+
+// Project pt onto a face. The outward normal and the principal curvature directions scaled by
+// the principal curvatures at the projection are also computed
+int tucanos_occ_project_face(const void *shape, int face, const double *pt, double *res,
+                             double *normal, double *u, double *v) {
+  const Shape *s = static_cast<const Shape *>(shape);
+  if (face < 1 || face > s->faces.Extent()) {
+    return TUCANOS_OCC_ERR_INDEX;
+  }
+  try {
+    const TopoDS_Face &f = TopoDS::Face(s->faces(face));
+    Handle(Geom_Surface) surf = BRep_Tool::Surface(f);
+    if (surf.IsNull()) {
+      return TUCANOS_OCC_ERR_PROJECTION;
+    }
+    double u_min, u_max, v_min, v_max;
+    BRepTools::UVBounds(f, u_min, u_max, v_min, v_max);
+
+    const gp_Pnt p(pt[0], pt[1], pt[2]);
+    double pu, pv;
+    GeomAPI_ProjectPointOnSurf proj(p, surf, u_min, u_max, v_min, v_max);
+    if (proj.NbPoints() > 0) {
+      proj.LowerDistanceParameters(pu, pv);
+    } else {
+      // the closest point is on the boundary of the face
+      Handle(ShapeAnalysis_Surface) sas = new ShapeAnalysis_Surface(surf);
+      const gp_Pnt2d uv = sas->ValueOfUV(p, Precision::Confusion());
+      pu = std::min(std::max(uv.X(), u_min), u_max);
+      pv = std::min(std::max(uv.Y(), v_min), v_max);
+    }
+
+    GeomLProp_SLProps props(surf, pu, pv, 2, Precision::Confusion());
+    to_array(props.Value().XYZ(), res);
+    if (!props.IsNormalDefined()) {
+      return TUCANOS_OCC_ERR_PROJECTION;
+    }
+    const double sign = f.Orientation() == TopAbs_REVERSED ? -1.0 : 1.0;
+    to_array(sign * props.Normal().XYZ(), normal);
+
+    if (!props.IsCurvatureDefined()) {
+      to_array(gp_XYZ(0.0, 0.0, 0.0), u);
+      to_array(gp_XYZ(0.0, 0.0, 0.0), v);
+    } else if (props.IsUmbilic()) {
+      const gp_Dir d1(props.D1U());
+      const gp_Dir d2 = props.Normal().Crossed(d1);
+      const double k = sign * props.MeanCurvature();
+      to_array(k * d1.XYZ(), u);
+      to_array(k * d2.XYZ(), v);
+    } else {
+      gp_Dir d_max, d_min;
+      props.CurvatureDirections(d_max, d_min);
+      to_array(sign * props.MaxCurvature() * d_max.XYZ(), u);
+      to_array(sign * props.MinCurvature() * d_min.XYZ(), v);
+    }
+  } catch (Standard_Failure &) {
+    return TUCANOS_OCC_ERR_EXCEPTION;
+  }
+  return TUCANOS_OCC_OK;
+}
+
+// Project pt onto an edge
+int tucanos_occ_project_edge(const void *shape, int edge, const double *pt, double *res) {
+  const Shape *s = static_cast<const Shape *>(shape);
+  if (edge < 1 || edge > s->edges.Extent()) {
+    return TUCANOS_OCC_ERR_INDEX;
+  }
+  try {
+    double first, last;
+    Handle(Geom_Curve) curve = BRep_Tool::Curve(TopoDS::Edge(s->edges(edge)), first, last);
+    if (curve.IsNull()) {
+      // degenerated edge
+      return TUCANOS_OCC_ERR_PROJECTION;
+    }
+    const gp_Pnt p(pt[0], pt[1], pt[2]);
+    // the closest point may be one of the ends of the edge
+    gp_Pnt best = curve->Value(first);
+    const gp_Pnt end = curve->Value(last);
+    if (p.Distance(end) < p.Distance(best)) {
+      best = end;
+    }
+    GeomAPI_ProjectPointOnCurve proj(p, curve, first, last);
+    if (proj.NbPoints() > 0 && proj.LowerDistance() < p.Distance(best)) {
+      best = proj.NearestPoint();
+    }
+    to_array(best.XYZ(), res);
+  } catch (Standard_Failure &) {
+    return TUCANOS_OCC_ERR_EXCEPTION;
+  }
+  return TUCANOS_OCC_OK;
+}
+
+// Get the coordinates of a vertex
+int tucanos_occ_vertex(const void *shape, int vert, double *res) {
+  const Shape *s = static_cast<const Shape *>(shape);
+  if (vert < 1 || vert > s->verts.Extent()) {
+    return TUCANOS_OCC_ERR_INDEX;
+  }
+  try {
+    to_array(BRep_Tool::Pnt(TopoDS::Vertex(s->verts(vert))).XYZ(), res);
+  } catch (Standard_Failure &) {
+    return TUCANOS_OCC_ERR_EXCEPTION;
+  }
+  return TUCANOS_OCC_OK;
+}
+}
@@ -35,7 +35,11 @@ impl Error {
     }
 }
 
-/// Vertex and element indices
+/// Vertex and element indices (64 bit with the `large-indices` feature, for meshes with more
+/// than $`2^{32}`$ vertices / elements)
+#[cfg(feature = "large-indices")]
+pub type Idx = u64;
+#[cfg(not(feature = "large-indices"))]
 pub type Idx = u32;
 
 /// Topological tags: dimension
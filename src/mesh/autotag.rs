@@ -110,7 +110,10 @@ impl<const D: usize, E: Elem> SimplexMesh<D, E> {
 
 #[cfg(test)]
 mod tests {
-    use crate::mesh::test_meshes::{test_mesh_2d, test_mesh_3d};
+    use crate::{
+        mesh::test_meshes::{test_mesh_2d, test_mesh_3d},
+        spatialindex::{DefaultObjectIndex, ObjectIndex},
+    };
     use std::collections::HashMap;
 
     #[test]
@@ -172,7 +175,7 @@ mod tests {
         assert_eq!(new_tags.len(), 1);
         assert_eq!(*new_tags.get(&1).unwrap(), vec![1, 2, 3, 4, 5, 6]);
 
-        let tree = bdy.compute_elem_tree();
+        let tree = <DefaultObjectIndex<3> as ObjectIndex<3>>::new(&bdy);
         bdy.transfer_tags(&tree, &mut mesh).unwrap();
 
         let mut res = HashMap::new();
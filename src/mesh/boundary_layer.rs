@@ -0,0 +1,280 @@
+use crate::{
+    mesh::{get_face_to_elem, Elem, GElem, Point, SimplexMesh},
+    metric::IsoMetric,
+    Error, Idx, Result, Tag,
+};
+use log::debug;
+use rustc_hash::FxHashSet;
+
+/// Split the prism between the faces `bottom` and `top` (given in the same vertex order) into
+/// simplices. The vertices must be sorted by increasing global index on the wall so that the
+/// splitting is conforming between neighboring prisms
+fn split_prism(bottom: &[Idx], top: &[Idx]) -> Vec<Vec<Idx>> {
+    let n = bottom.len();
+    (0..n)
+        .map(|j| {
+            bottom[..n - j]
+                .iter()
+                .chain(top[n - 1 - j..].iter())
+                .copied()
+                .collect()
+        })
+        .collect()
+}
+
+impl<const D: usize, E: Elem> SimplexMesh<D, E> {
+    /// Insert `n_layers` layers of elements on the faces tagged with one of `tags`. The first
+    /// layer has a thickness `h0` and the thickness of the next layers is multiplied by
+    /// `growth`. The layers are obtained by moving the vertices of the wall faces inwards along
+    /// the vertex normals by the total thickness
+    /// ```math
+    /// h_0 \sum_{k=0}^{n - 1} g^k
+    /// ```
+    /// and splitting the prisms between the wall and the moved vertices into simplices; they
+    /// use the tag of the element adjacent to the wall. The faces that contain an edge (in 3D)
+    /// or a vertex (in 2D) at the boundary of the wall are also extruded.
+    ///
+    /// An error is returned if the total thickness is too large compared to the elements
+    /// adjacent to the wall, i.e. if some elements are inverted
+    pub fn insert_boundary_layer(
+        &self,
+        tags: &[Tag],
+        n_layers: u32,
+        h0: f64,
+        growth: f64,
+    ) -> Result<Self> {
+        debug!(
+            "Insert {n_layers} boundary layers on faces tagged {tags:?} (h0 = {h0:.2e}, growth = {growth:.2})"
+        );
+
+        if E::N_VERTS as usize != D + 1 {
+            return Err(Error::from(
+                "boundary layers are only available for volume meshes",
+            ));
+        }
+        if n_layers == 0 || h0 <= 0.0 || growth <= 0.0 {
+            return Err(Error::from("invalid boundary layer parameters"));
+        }
+
+        let n_layers = n_layers as usize;
+        let n_verts = self.n_verts() as usize;
+        let f2e = get_face_to_elem(self.elems());
+
+        // Wall faces and adjacent elements
+        let mut wall = Vec::new();
+        for (f, t) in self.faces().zip(self.ftags()) {
+            if tags.contains(&t) {
+                let els = f2e
+                    .get(&f.sorted())
+                    .ok_or_else(|| Error::from("face not found"))?;
+                if els.len() != 1 {
+                    return Err(Error::from("the wall faces must be boundary faces"));
+                }
+                wall.push((f, els[0]));
+            }
+        }
+        if wall.is_empty() {
+            return Err(Error::from("no face found with the wall tags"));
+        }
+
+        // Inward vertex normals
+        let mut normals = vec![Point::<D>::zeros(); n_verts];
+        let mut is_wall = vec![false; n_verts];
+        for (f, i_elem) in &wall {
+            let gf = self.gface(*f);
+            let mut n = gf.scaled_normal();
+            let i_opp = self
+                .elem(*i_elem)
+                .iter()
+                .copied()
+                .find(|i| !f.contains_vertex(*i))
+                .unwrap();
+            if n.dot(&(self.vert(i_opp) - gf.center())) < 0.0 {
+                n = -n;
+            }
+            for &i in f.iter() {
+                normals[i as usize] += n;
+                is_wall[i as usize] = true;
+            }
+        }
+        for (n, _) in normals.iter_mut().zip(is_wall.iter()).filter(|(_, &x)| x) {
+            let nrm = n.norm();
+            if nrm < f64::EPSILON {
+                return Err(Error::from("cannot compute the wall normals"));
+            }
+            *n /= nrm;
+        }
+
+        // Layer heights
+        let heights = std::iter::once(0.0)
+            .chain((0..n_layers).scan(0.0, |h, k| {
+                *h += h0 * growth.powi(k as i32);
+                Some(*h)
+            }))
+            .collect::<Vec<_>>();
+        let thickness = heights[n_layers];
+        debug!("Total thickness: {thickness:.2e}");
+
+        // Vertices: the original wall vertices are moved to the last layer
+        let mut verts = self.verts().collect::<Vec<_>>();
+        let mut ids = vec![Vec::new(); n_verts];
+        for (i_vert, id) in ids.iter_mut().enumerate().filter(|(i, _)| is_wall[*i]) {
+            let (p, n) = (verts[i_vert], normals[i_vert]);
+            for &h in &heights[..n_layers] {
+                id.push(verts.len() as Idx);
+                verts.push(p + h * n);
+            }
+            id.push(i_vert as Idx);
+            verts[i_vert] = p + thickness * n;
+        }
+
+        // Elements
+        let mut elems = self.elems().collect::<Vec<_>>();
+        let mut etags = self.etags().collect::<Vec<_>>();
+        for (f, i_elem) in &wall {
+            let etag = etags[*i_elem as usize];
+            let f = f.sorted();
+            for k in 0..n_layers {
+                let bottom = f.iter().map(|&i| ids[i as usize][k]).collect::<Vec<_>>();
+                let top = f
+                    .iter()
+                    .map(|&i| ids[i as usize][k + 1])
+                    .collect::<Vec<_>>();
+                for s in split_prism(&bottom, &top) {
+                    let mut e = E::from_slice(&s);
+                    let ge = E::Geom::<D, IsoMetric<D>>::from_verts(
+                        e.iter()
+                            .map(|&i| (verts[i as usize], IsoMetric::<D>::from(1.0))),
+                    );
+                    if ge.vol() < 0.0 {
+                        let (i0, i1) = (e[0], e[1]);
+                        e[0] = i1;
+                        e[1] = i0;
+                    }
+                    elems.push(e);
+                    etags.push(etag);
+                }
+            }
+        }
+
+        // Faces: the wall faces use the vertices on the wall, and the faces that contain a
+        // ridge of the wall are extruded
+        let mut wall_ridges = FxHashSet::default();
+        for (f, _) in &wall {
+            for i in 0..E::Face::N_FACES {
+                wall_ridges.insert(f.face(i).sorted());
+            }
+        }
+        let mut faces = Vec::with_capacity(self.n_faces() as usize);
+        let mut ftags = Vec::with_capacity(self.n_faces() as usize);
+        let mut side = FxHashSet::default();
+        for (f, t) in self.faces().zip(self.ftags()) {
+            if tags.contains(&t) {
+                faces.push(E::Face::from_iter(f.iter().map(|&i| ids[i as usize][0])));
+            } else {
+                faces.push(f);
+                for i in 0..E::Face::N_FACES {
+                    let r = f.face(i).sorted();
+                    if wall_ridges.contains(&r) {
+                        side.insert((r, t));
+                    }
+                }
+            }
+            ftags.push(t);
+        }
+        for (r, t) in side {
+            for k in 0..n_layers {
+                let bottom = r.iter().map(|&i| ids[i as usize][k]).collect::<Vec<_>>();
+                let top = r
+                    .iter()
+                    .map(|&i| ids[i as usize][k + 1])
+                    .collect::<Vec<_>>();
+                for s in split_prism(&bottom, &top) {
+                    faces.push(E::Face::from_slice(&s));
+                    ftags.push(t);
+                }
+            }
+        }
+
+        let mut res = Self::new(verts, elems, etags, faces, ftags);
+        *res.tag_names_mut() = self.tag_names().clone();
+
+        let n_inverted = res.inverted_elems().len();
+        if n_inverted > 0 {
+            return Err(Error::from(&format!(
+                "the boundary layer is too thick: {n_inverted} inverted elements"
+            )));
+        }
+
+        // orient the new faces
+        let (bdy, _) = res.add_boundary_faces();
+        if !bdy.is_empty() {
+            return Err(Error::from("the boundary faces are not all present"));
+        }
+
+        Ok(res)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::split_prism;
+    use crate::{
+        mesh::test_meshes::{test_mesh_2d, test_mesh_3d},
+        Idx, Result,
+    };
+
+    #[test]
+    fn test_split_prism() {
+        assert_eq!(
+            split_prism(&[0, 1], &[2, 3]),
+            vec![vec![0, 1, 3], vec![0, 2, 3]]
+        );
+        assert_eq!(
+            split_prism(&[0, 1, 2], &[3, 4, 5]),
+            vec![vec![0, 1, 2, 5], vec![0, 1, 4, 5], vec![0, 3, 4, 5]]
+        );
+    }
+
+    #[test]
+    fn test_boundary_layer_2d() -> Result<()> {
+        let mesh = test_mesh_2d().split().split().split();
+        let n_wall = mesh.ftags().filter(|&t| t == 1).count() as Idx;
+
+        let mut res = mesh.insert_boundary_layer(&[1], 3, 0.005, 1.5)?;
+        assert_eq!(res.n_verts(), mesh.n_verts() + 3 * (n_wall + 1));
+        assert_eq!(res.n_elems(), mesh.n_elems() + 3 * 2 * n_wall);
+        assert_eq!(res.n_faces(), mesh.n_faces() + 2 * 3);
+        assert!((res.vol() - 1.0).abs() < 1e-12);
+
+        for h in [0.0, 0.005, 0.0125, 0.02375] {
+            assert_eq!(
+                res.verts().filter(|p| (p[1] - h).abs() < 1e-12).count() as Idx,
+                n_wall + 1
+            );
+        }
+
+        res.compute_face_to_elems();
+        res.check()?;
+
+        assert!(mesh.insert_boundary_layer(&[1], 3, 0.1, 1.5).is_err());
+        assert!(mesh.insert_boundary_layer(&[7], 3, 0.005, 1.5).is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_boundary_layer_3d() -> Result<()> {
+        let mesh = test_mesh_3d().split().split();
+        let n_wall = mesh.ftags().filter(|&t| t == 1).count() as Idx;
+
+        let mut res = mesh.insert_boundary_layer(&[1], 2, 0.01, 1.2)?;
+        assert_eq!(res.n_elems(), mesh.n_elems() + 2 * 3 * n_wall);
+        assert!((res.vol() - 1.0).abs() < 1e-12);
+
+        res.compute_face_to_elems();
+        res.check()?;
+
+        Ok(())
+    }
+}
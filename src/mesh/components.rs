@@ -0,0 +1,160 @@
+use crate::{
+    mesh::{
+        graph::{CSRGraph, ConnectedComponents},
+        Elem, SimplexMesh,
+    },
+    Idx, Result,
+};
+use log::debug;
+use rustc_hash::FxHashMap;
+
+/// Connected components of graph `g` with `n` vertices. The vertices that are not connected
+/// to any other vertex (and may not be in `g`) are single-vertex components
+pub fn graph_components(g: &CSRGraph, n: Idx) -> Result<Vec<Idx>> {
+    let mut res = ConnectedComponents::<Idx>::new(g)?.tags().to_vec();
+    res.truncate(n as usize);
+    let mut next = res.iter().copied().max().map_or(0, |c| c + 1);
+    while res.len() < n as usize {
+        res.push(next);
+        next += 1;
+    }
+    Ok(res)
+}
+
+/// Index of the component with the largest number of items (the first one in case of ties)
+fn largest_component(components: &[Idx]) -> Option<Idx> {
+    let mut counts = FxHashMap::default();
+    for &c in components {
+        *counts.entry(c).or_insert(0) += 1;
+    }
+    counts
+        .into_iter()
+        .max_by_key(|&(c, n)| (n, std::cmp::Reverse(c)))
+        .map(|(c, _)| c)
+}
+
+impl<const D: usize, E: Elem> SimplexMesh<D, E> {
+    /// Get the index of the connected component of each element, two elements being
+    /// connected if they share a face. The element-to-element connectivity must be computed
+    pub fn element_components(&self) -> Result<Vec<Idx>> {
+        debug!("Compute the connected components of the elements");
+
+        let res = graph_components(self.get_elem_to_elems()?, self.n_elems())?;
+        debug!(
+            "{} components found",
+            res.iter().copied().max().map_or(0, |c| c + 1)
+        );
+
+        Ok(res)
+    }
+
+    /// Get the index of the connected component of each face, two faces being connected if
+    /// they share a face (i.e. an edge for triangles, a vertex for edges)
+    pub fn boundary_components(&self) -> Result<Vec<Idx>> {
+        debug!("Compute the connected components of the faces");
+
+        let mut f2f: FxHashMap<_, Vec<Idx>> = FxHashMap::default();
+        for (i_face, f) in self.faces().enumerate() {
+            for i in 0..<E::Face as Elem>::N_FACES {
+                f2f.entry(f.face(i).sorted())
+                    .or_default()
+                    .push(i_face as Idx);
+            }
+        }
+        let edges = f2f
+            .values()
+            .flat_map(|faces| faces.iter().skip(1).map(|&j| [faces[0], j]))
+            .collect::<Vec<_>>();
+
+        let res = graph_components(&CSRGraph::new(&edges), self.n_faces())?;
+        debug!(
+            "{} components found",
+            res.iter().copied().max().map_or(0, |c| c + 1)
+        );
+
+        Ok(res)
+    }
+
+    /// Keep only the largest connected component of the elements (see `element_components`),
+    /// e.g. to remove debris. The faces whose vertices are not all used by the remaining
+    /// elements and the unused vertices are removed.
+    ///
+    /// Returns the number of elements and faces removed
+    pub fn keep_largest_component(&mut self) -> Result<(Idx, Idx)> {
+        debug!("Keep the largest connected component");
+
+        if self.get_elem_to_elems().is_err() {
+            self.compute_elem_to_elems();
+        }
+        let components = self.element_components()?;
+        let Some(largest) = largest_component(&components) else {
+            return Ok((0, 0));
+        };
+
+        let mut used = vec![false; self.n_verts() as usize];
+        let (elems, etags): (Vec<_>, Vec<_>) = self
+            .elems()
+            .zip(self.etags())
+            .zip(components.iter())
+            .filter(|&(_, &c)| c == largest)
+            .map(|(x, _)| x)
+            .unzip();
+        elems
+            .iter()
+            .for_each(|e| e.iter().for_each(|&i| used[i as usize] = true));
+        let (faces, ftags): (Vec<_>, Vec<_>) = self
+            .faces()
+            .zip(self.ftags())
+            .filter(|(f, _)| f.iter().all(|&i| used[i as usize]))
+            .unzip();
+
+        let n_elems = self.n_elems() - elems.len() as Idx;
+        let n_faces = self.n_faces() - faces.len() as Idx;
+        if n_elems > 0 || n_faces > 0 {
+            let verts = self.verts().collect();
+            let tag_names = self.tag_names().clone();
+            *self = Self::new(verts, elems, etags, faces, ftags);
+            *self.tag_names_mut() = tag_names;
+            self.remove_unused_vertices();
+        }
+        debug!("{n_elems} elements and {n_faces} faces removed");
+
+        Ok((n_elems, n_faces))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        mesh::{test_meshes::test_mesh_2d, Point},
+        Result,
+    };
+
+    #[test]
+    fn test_components() -> Result<()> {
+        let mut mesh = test_mesh_2d().split();
+        let mut other = test_mesh_2d();
+        other.translate(&Point::<2>::new(2.0, 0.0));
+        mesh.add(&other, |_| true, |_| true, None);
+        let n_verts = mesh.n_verts();
+
+        mesh.compute_elem_to_elems();
+        let components = mesh.element_components()?;
+        assert_eq!(components.iter().filter(|&&c| c == 0).count(), 8);
+        assert_eq!(components.iter().filter(|&&c| c == 1).count(), 2);
+
+        let components = mesh.boundary_components()?;
+        assert_eq!(components.iter().filter(|&&c| c == 0).count(), 8);
+        assert_eq!(components.iter().filter(|&&c| c == 1).count(), 4);
+
+        assert_eq!(mesh.keep_largest_component()?, (2, 4));
+        assert_eq!(mesh.n_elems(), 8);
+        assert_eq!(mesh.n_faces(), 8);
+        assert_eq!(mesh.n_verts(), n_verts - 4);
+        assert!(mesh.validate().is_empty());
+
+        assert_eq!(mesh.keep_largest_component()?, (0, 0));
+
+        Ok(())
+    }
+}
@@ -0,0 +1,454 @@
+use crate::{
+    mesh::{Elem, SimplexMesh},
+    Error, Idx, Result,
+};
+use log::{debug, warn};
+use nalgebra::{Vector2, Vector3};
+use rustc_hash::FxHashSet;
+
+/// Relative tolerance on the fraction of the element volume covered by the other mesh
+const COVERAGE_TOL: f64 = 1e-8;
+
+/// Clip a convex polygon by the half plane $`n \cdot (x - a) \ge 0`$ (Sutherland-Hodgman)
+fn clip_polygon_2d(poly: &[Vector2<f64>], a: &Vector2<f64>, n: &Vector2<f64>) -> Vec<Vector2<f64>> {
+    let mut res = Vec::with_capacity(poly.len() + 1);
+    for (i, p) in poly.iter().enumerate() {
+        let q = &poly[(i + 1) % poly.len()];
+        let sp = n.dot(&(p - a));
+        let sq = n.dot(&(q - a));
+        if sp >= 0.0 {
+            res.push(*p);
+        }
+        if (sp >= 0.0) != (sq >= 0.0) {
+            res.push(p + sp / (sp - sq) * (q - p));
+        }
+    }
+    res
+}
+
+/// Area of the intersection of two triangles
+fn triangle_intersection_area(t0: &[Vector2<f64>; 3], t1: &[Vector2<f64>; 3]) -> f64 {
+    let mut poly = t0.to_vec();
+    for i in 0..3 {
+        if poly.len() < 3 {
+            return 0.0;
+        }
+        let a = t1[(i + 1) % 3];
+        let b = t1[(i + 2) % 3];
+        let mut n = Vector2::new(a[1] - b[1], b[0] - a[0]);
+        if n.dot(&(t1[i] - a)) < 0.0 {
+            n = -n;
+        }
+        poly = clip_polygon_2d(&poly, &a, &n);
+    }
+    if poly.len() < 3 {
+        return 0.0;
+    }
+    let area = (0..poly.len()).fold(0.0, |s, i| {
+        let p = poly[i];
+        let q = poly[(i + 1) % poly.len()];
+        s + p[0] * q[1] - p[1] * q[0]
+    });
+    0.5 * area.abs()
+}
+
+/// Clip a convex polyhedron, given by its (planar, convex) faces, by the half space
+/// $`n \cdot (x - a) \ge 0`$ with $`\|n\| = 1`$. Points closer than `eps` to the plane are
+/// considered to lie on it. The new face lying on the plane is built from the
+/// intersection points, sorted by angle around their center, unless one of the faces already
+/// lies on the plane
+fn clip_polyhedron(
+    faces: &[Vec<Vector3<f64>>],
+    a: &Vector3<f64>,
+    n: &Vector3<f64>,
+    eps: f64,
+) -> Vec<Vec<Vector3<f64>>> {
+    let mut res = Vec::with_capacity(faces.len() + 1);
+    let mut cap = Vec::new();
+    let mut has_cap = false;
+    for face in faces {
+        let mut new_face = Vec::with_capacity(face.len() + 1);
+        let mut on_plane = true;
+        for (i, p) in face.iter().enumerate() {
+            let q = &face[(i + 1) % face.len()];
+            let sp = n.dot(&(p - a));
+            let sq = n.dot(&(q - a));
+            if sp.abs() > eps {
+                on_plane = false;
+            }
+            if sp >= -eps {
+                new_face.push(*p);
+                if sp <= eps {
+                    cap.push(*p);
+                }
+            }
+            if (sp > eps && sq < -eps) || (sp < -eps && sq > eps) {
+                let x = p + sp / (sp - sq) * (q - p);
+                new_face.push(x);
+                cap.push(x);
+            }
+        }
+        has_cap |= on_plane;
+        if new_face.len() >= 3 {
+            res.push(new_face);
+        }
+    }
+
+    if !has_cap && cap.len() >= 3 {
+        let c = cap.iter().sum::<Vector3<f64>>() / cap.len() as f64;
+        let u = cap
+            .iter()
+            .map(|x| x - c)
+            .max_by(|x, y| x.norm().partial_cmp(&y.norm()).unwrap())
+            .unwrap();
+        if u.norm() <= eps {
+            return res;
+        }
+        let u = u.normalize();
+        let v = n.cross(&u).normalize();
+        cap.sort_by(|x, y| {
+            let ax = f64::atan2((x - c).dot(&v), (x - c).dot(&u));
+            let ay = f64::atan2((y - c).dot(&v), (y - c).dot(&u));
+            ax.partial_cmp(&ay).unwrap()
+        });
+        res.push(cap);
+    }
+
+    res
+}
+
+/// Volume of the intersection of two tetrahedra
+fn tetrahedron_intersection_volume(t0: &[Vector3<f64>; 4], t1: &[Vector3<f64>; 4]) -> f64 {
+    let mut faces = (0..4)
+        .map(|i| (1..4).map(|j| t0[(i + j) % 4]).collect::<Vec<_>>())
+        .collect::<Vec<_>>();
+    for i in 0..4 {
+        if faces.len() < 4 {
+            return 0.0;
+        }
+        let a = t1[(i + 1) % 4];
+        let b = t1[(i + 2) % 4];
+        let c = t1[(i + 3) % 4];
+        let mut n = (b - a).cross(&(c - a)).normalize();
+        if n.dot(&(t1[i] - a)) < 0.0 {
+            n = -n;
+        }
+        let eps = 1e-12 * (b - a).norm();
+        faces = clip_polyhedron(&faces, &a, &n, eps);
+    }
+    if faces.len() < 4 {
+        return 0.0;
+    }
+
+    // the center is inside the convex polyhedron, so the volume is the sum of the
+    // (unsigned) volumes of the tetrahedra built from the center and the face triangles
+    let n_pts = faces.iter().map(Vec::len).sum::<usize>();
+    let c = faces.iter().flatten().sum::<Vector3<f64>>() / n_pts as f64;
+    let mut vol = 0.0;
+    for face in &faces {
+        for i in 1..face.len() - 1 {
+            let e0 = face[0] - c;
+            let e1 = face[i] - c;
+            let e2 = face[i + 1] - c;
+            vol += e0.cross(&e1).dot(&e2).abs() / 6.0;
+        }
+    }
+    vol
+}
+
+impl<const D: usize, E: Elem> SimplexMesh<D, E> {
+    /// Volume of the intersection of element `i_elem` of `self` and element `i_other` of `other`
+    fn elem_intersection_volume(&self, i_elem: Idx, other: &Self, i_other: Idx) -> f64 {
+        let e0 = self.elem(i_elem);
+        let e1 = other.elem(i_other);
+        if D == 2 {
+            let t0 = [0, 1, 2].map(|i| {
+                let p = self.vert(e0[i]);
+                Vector2::new(p[0], p[1])
+            });
+            let t1 = [0, 1, 2].map(|i| {
+                let p = other.vert(e1[i]);
+                Vector2::new(p[0], p[1])
+            });
+            triangle_intersection_area(&t0, &t1)
+        } else {
+            let t0 = [0, 1, 2, 3].map(|i| {
+                let p = self.vert(e0[i]);
+                Vector3::new(p[0], p[1], p[2])
+            });
+            let t1 = [0, 1, 2, 3].map(|i| {
+                let p = other.vert(e1[i]);
+                Vector3::new(p[0], p[1], p[2])
+            });
+            tetrahedron_intersection_volume(&t0, &t1)
+        }
+    }
+
+    /// Transfer fields defined at the elements of `other` (P0) to the elements of `self`
+    /// conservatively, using the exact intersections between the elements of the two meshes:
+    /// ```math
+    /// f_K = \frac{1}{|K|} \sum_{L} |K \cap L| f_L
+    /// ```
+    /// If both meshes cover the same domain, the integrals of the fields are preserved; a
+    /// warning is issued if some elements of `self` are not fully covered by `other` (e.g. due to
+    /// the boundary discretization), as the integrals are then not preserved.
+    ///
+    /// The elements $`L`$ intersecting $`K`$ are found by walking the element-to-element
+    /// connectivity of `other` (which is required) starting from the elements whose bounding
+    /// box overlaps the bounding box of $`K`$, found with an `ElementTree`. Only triangle meshes
+    /// in 2D and tetrahedron meshes in 3D are supported
+    pub fn transfer_conservative(&self, other: &Self, f: &[f64]) -> Result<Vec<f64>> {
+        debug!("Conservative transfer of element data");
+
+        if !((D == 2 && E::N_VERTS == 3) || (D == 3 && E::N_VERTS == 4)) {
+            return Err(Error::from(
+                "conservative transfer only available for triangle / tetrahedron meshes",
+            ));
+        }
+
+        let n_elems_other = other.n_elems() as usize;
+        if n_elems_other == 0 || f.len() % n_elems_other != 0 {
+            return Err(Error::from("invalid field size"));
+        }
+        let n_comp = f.len() / n_elems_other;
+
+        let e2e = other.get_elem_to_elems()?;
+        let tree = other.compute_element_tree();
+
+        let mut res = vec![0.0; n_comp * self.n_elems() as usize];
+        let mut n_uncovered = 0;
+        let mut min_coverage = 1.0_f64;
+        for (i_elem, (e, v)) in self.elems().zip(res.chunks_mut(n_comp)).enumerate() {
+            let ge = self.gelem(e);
+            let vol = f64::abs(ge.vol());
+            let tol = 1e-12 * vol;
+
+            let pts = e.iter().map(|&i| self.vert(i)).collect::<Vec<_>>();
+            let min = pts.iter().fold(pts[0], |a, p| a.inf(p));
+            let max = pts.iter().fold(pts[0], |a, p| a.sup(p));
+            let mut stack = tree.intersecting_elements(&min, &max);
+            let mut visited = stack.iter().copied().collect::<FxHashSet<_>>();
+            let mut covered = 0.0;
+            while let Some(i_other) = stack.pop() {
+                let w = self.elem_intersection_volume(i_elem as Idx, other, i_other);
+                if w > tol {
+                    covered += w;
+                    let offset = n_comp * i_other as usize;
+                    v.iter_mut()
+                        .zip(f[offset..offset + n_comp].iter())
+                        .for_each(|(x, y)| *x += w * y);
+                    for &i_next in e2e.row(i_other) {
+                        if visited.insert(i_next) {
+                            stack.push(i_next);
+                        }
+                    }
+                }
+            }
+            v.iter_mut().for_each(|x| *x /= vol);
+
+            let coverage = covered / vol;
+            if coverage < 1.0 - COVERAGE_TOL {
+                n_uncovered += 1;
+                min_coverage = min_coverage.min(coverage);
+            }
+        }
+
+        if n_uncovered > 0 {
+            warn!(
+                "Conservative transfer: {n_uncovered} elements are not fully covered by the source mesh (min. coverage: {min_coverage:.2e}), the integrals are not preserved"
+            );
+        }
+
+        Ok(res)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{tetrahedron_intersection_volume, triangle_intersection_area};
+    use crate::{
+        mesh::{
+            test_meshes::{test_mesh_2d, test_mesh_3d},
+            GElem, Point,
+        },
+        Result,
+    };
+    use nalgebra::{Vector2, Vector3};
+    use std::f64::consts::PI;
+
+    #[test]
+    fn test_triangle_intersection() {
+        let t0 = [
+            Vector2::new(0.0, 0.0),
+            Vector2::new(1.0, 0.0),
+            Vector2::new(0.0, 1.0),
+        ];
+        assert!((triangle_intersection_area(&t0, &t0) - 0.5).abs() < 1e-12);
+
+        let t1 = [
+            Vector2::new(0.0, 0.0),
+            Vector2::new(0.0, 1.0),
+            Vector2::new(-1.0, 0.0),
+        ];
+        assert!(triangle_intersection_area(&t0, &t1) < 1e-12);
+
+        // the intersection is the triangle (0,0), (0.5,0), (0,0.5)
+        let t1 = [
+            Vector2::new(0.0, 0.0),
+            Vector2::new(0.5, 0.0),
+            Vector2::new(0.0, 0.5),
+        ];
+        assert!((triangle_intersection_area(&t0, &t1) - 0.125).abs() < 1e-12);
+
+        // the intersection is a square of size 0.5
+        let t1 = [
+            Vector2::new(0.5, 0.5),
+            Vector2::new(0.5, -1.0),
+            Vector2::new(-1.0, 0.5),
+        ];
+        assert!((triangle_intersection_area(&t0, &t1) - 0.25).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_tetrahedron_intersection() {
+        let t0 = [
+            Vector3::new(0.0, 0.0, 0.0),
+            Vector3::new(1.0, 0.0, 0.0),
+            Vector3::new(0.0, 1.0, 0.0),
+            Vector3::new(0.0, 0.0, 1.0),
+        ];
+        assert!((tetrahedron_intersection_volume(&t0, &t0) - 1.0 / 6.0).abs() < 1e-12);
+
+        let t1 = t0.map(|p| 0.5 * p);
+        assert!((tetrahedron_intersection_volume(&t0, &t1) - 1.0 / 48.0).abs() < 1e-12);
+        assert!((tetrahedron_intersection_volume(&t1, &t0) - 1.0 / 48.0).abs() < 1e-12);
+
+        let t1 = t0.map(|p| -p);
+        assert!(tetrahedron_intersection_volume(&t0, &t1) < 1e-12);
+
+        // the intersection is the cube [0, 0.5]^3
+        let t1 = [
+            Vector3::new(0.5, 0.5, 0.5),
+            Vector3::new(-2.0, 0.5, 0.5),
+            Vector3::new(0.5, -2.0, 0.5),
+            Vector3::new(0.5, 0.5, -2.0),
+        ];
+        let t0 = [
+            Vector3::new(0.0, 0.0, 0.0),
+            Vector3::new(3.0, 0.0, 0.0),
+            Vector3::new(0.0, 3.0, 0.0),
+            Vector3::new(0.0, 0.0, 3.0),
+        ];
+        assert!((tetrahedron_intersection_volume(&t0, &t1) - 0.125).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_transfer_conservative_2d() -> Result<()> {
+        let mut old = test_mesh_2d().split().split().split();
+        old.compute_elem_to_elems();
+        let new = test_mesh_2d().split().split();
+
+        let fun = |p: Point<2>| [p[0] * p[0] + p[1], 1.0];
+        let f = old
+            .gelems()
+            .flat_map(|ge| fun(ge.center()))
+            .collect::<Vec<_>>();
+
+        let g = new.transfer_conservative(&old, &f)?;
+        assert_eq!(g.len(), 2 * new.n_elems() as usize);
+
+        let int_f = old
+            .gelems()
+            .zip(f.chunks(2))
+            .fold(0.0, |a, (ge, v)| a + ge.vol().abs() * v[0]);
+        let int_g = new
+            .gelems()
+            .zip(g.chunks(2))
+            .fold(0.0, |a, (ge, v)| a + ge.vol().abs() * v[0]);
+        assert!((int_f - int_g).abs() < 1e-12);
+        assert!(g.chunks(2).all(|v| (v[1] - 1.0).abs() < 1e-12));
+
+        assert!(new.transfer_conservative(&old, &f[1..]).is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_transfer_conservative_2d_non_matching() -> Result<()> {
+        let mut old = test_mesh_2d().split().split().split();
+        old.mut_verts().for_each(|x| {
+            let y = 0.05 * f64::sin(PI * x[0]) * f64::sin(PI * x[1]);
+            x[0] += y;
+            x[1] -= 0.5 * y;
+        });
+        old.compute_elem_to_elems();
+
+        // the vertices of new are not vertices of old, and the edges are not aligned
+        let mut new = test_mesh_2d().split().split();
+        new.mut_verts().for_each(|x| {
+            let y = 0.1 * x[0] * (1.0 - x[0]) * x[1] * (1.0 - x[1]);
+            x[1] += y;
+        });
+        let new = new.split();
+
+        let fun = |p: Point<2>| [f64::cos(3.0 * p[0]) + p[1] * p[1], 1.0];
+        let f = old
+            .gelems()
+            .flat_map(|ge| fun(ge.center()))
+            .collect::<Vec<_>>();
+
+        let g = new.transfer_conservative(&old, &f)?;
+
+        let int_f = old
+            .gelems()
+            .zip(f.chunks(2))
+            .fold(0.0, |a, (ge, v)| a + ge.vol().abs() * v[0]);
+        let int_g = new
+            .gelems()
+            .zip(g.chunks(2))
+            .fold(0.0, |a, (ge, v)| a + ge.vol().abs() * v[0]);
+        assert!((int_f - int_g).abs() < 1e-12);
+        assert!(g.chunks(2).all(|v| (v[1] - 1.0).abs() < 1e-12));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_transfer_conservative_3d() -> Result<()> {
+        let mut old = test_mesh_3d().split().split();
+        old.compute_elem_to_elems();
+
+        // an unrelated mesh of the same domain
+        let mut new = test_mesh_3d().split();
+        new.mut_verts().for_each(|x| {
+            let y = 0.1 * x[0] * (1.0 - x[0]) * x[1] * (1.0 - x[1]) * x[2] * (1.0 - x[2]);
+            x[0] += y;
+        });
+        let new = new.split();
+
+        let f = old
+            .gelems()
+            .map(|ge| {
+                let c = ge.center();
+                c[0] + c[1] * c[2]
+            })
+            .collect::<Vec<_>>();
+
+        let g = new.transfer_conservative(&old, &f)?;
+
+        let int_f = old
+            .gelems()
+            .zip(f.iter())
+            .fold(0.0, |a, (ge, v)| a + ge.vol().abs() * v);
+        let int_g = new
+            .gelems()
+            .zip(g.iter())
+            .fold(0.0, |a, (ge, v)| a + ge.vol().abs() * v);
+        assert!((int_f - int_g).abs() < 1e-12);
+
+        // missing connectivity
+        assert!(old.transfer_conservative(&new, &g).is_err());
+
+        Ok(())
+    }
+}
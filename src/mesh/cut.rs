@@ -0,0 +1,561 @@
+use crate::{
+    mesh::{Edge, Elem, Point, SimplexMesh, Tetrahedron, Triangle},
+    Error, Idx, Result,
+};
+use log::debug;
+use rustc_hash::FxHashMap;
+
+/// Points where the edges of a mesh are cut by the level set `phi = 0`. The point on edge
+/// `(i, j)` with `phi[i] < 0 <= phi[j]` is
+/// ```math
+/// (1 - w) p_i + w p_j, \quad w = \frac{\phi_i}{\phi_i - \phi_j}
+/// ```
+/// and vertex `j` is used directly if `phi[j] = 0`. The other cut points are numbered from
+/// `offset`
+struct CutPoints<'a> {
+    phi: &'a [f64],
+    offset: Idx,
+    ids: FxHashMap<[Idx; 2], Idx>,
+    points: Vec<(Idx, Idx, f64)>,
+}
+
+impl<'a> CutPoints<'a> {
+    fn new(phi: &'a [f64], offset: Idx) -> Self {
+        Self {
+            phi,
+            offset,
+            ids: FxHashMap::default(),
+            points: Vec::new(),
+        }
+    }
+
+    fn is_inside(&self, i: Idx) -> bool {
+        self.phi[i as usize] < 0.0
+    }
+
+    /// Index of the cut point on edge `(i, j)`, with `phi[i] < 0 <= phi[j]`
+    fn id(&mut self, i: Idx, j: Idx) -> Idx {
+        let (phi_i, phi_j) = (self.phi[i as usize], self.phi[j as usize]);
+        if phi_j <= 0.0 {
+            return j;
+        }
+        let next = self.offset + self.points.len() as Idx;
+        let key = if i < j { [i, j] } else { [j, i] };
+        *self.ids.entry(key).or_insert_with(|| {
+            self.points.push((i, j, phi_i / (phi_i - phi_j)));
+            next
+        })
+    }
+
+    /// Interpolation weights of point `i`: `(i0, i1, w)` with `p = (1 - w) p_i0 + w p_i1`
+    fn weights(&self, i: Idx) -> (Idx, Idx, f64) {
+        if i < self.offset {
+            (i, i, 0.0)
+        } else {
+            self.points[(i - self.offset) as usize]
+        }
+    }
+
+    /// Coordinates of point `i`
+    fn coords<const D: usize, E: Elem>(&self, mesh: &SimplexMesh<D, E>, i: Idx) -> Point<D> {
+        let (i0, i1, w) = self.weights(i);
+        (1.0 - w) * mesh.vert(i0) + w * mesh.vert(i1)
+    }
+
+    /// Coordinates of the mesh vertices followed by the cut points
+    fn vertices<const D: usize, E: Elem>(&self, mesh: &SimplexMesh<D, E>) -> Vec<Point<D>> {
+        let n = self.offset + self.points.len() as Idx;
+        (0..n).map(|i| self.coords(mesh, i)).collect()
+    }
+
+    /// Clip polygon `poly` (a segment if it has 2 vertices) to the region `phi < 0`, keeping
+    /// the orientation
+    fn clip(&mut self, poly: &[Idx]) -> Vec<Idx> {
+        let n = poly.len();
+        let mut res = Vec::with_capacity(n + 1);
+        for k in 0..n {
+            let (a, b) = (poly[k], poly[(k + 1) % n]);
+            if self.is_inside(a) {
+                res.push(a);
+            }
+            if self.is_inside(a) != self.is_inside(b) {
+                let c = if self.is_inside(a) {
+                    self.id(a, b)
+                } else {
+                    self.id(b, a)
+                };
+                res.push(c);
+            }
+        }
+        res.dedup();
+        if res.len() > 1 && res[0] == res[res.len() - 1] {
+            res.pop();
+        }
+        res
+    }
+
+    /// Cut points on the edges of element `e`, sorted
+    fn elem_points<E: Elem>(&mut self, e: &E) -> Vec<Idx> {
+        let mut res = Vec::with_capacity(E::N_EDGES as usize);
+        for i_edge in 0..E::N_EDGES {
+            let [i, j] = e.edge(i_edge);
+            match (self.is_inside(i), self.is_inside(j)) {
+                (true, false) => res.push(self.id(i, j)),
+                (false, true) => res.push(self.id(j, i)),
+                _ => {}
+            }
+        }
+        res.sort_unstable();
+        res.dedup();
+        res
+    }
+
+    /// Vertices, fields (with `n_comp` components) and renumbered connectivity of the mesh
+    /// made of elements `elems` built on the points
+    fn renumber<const D: usize, E: Elem, F: Elem>(
+        &self,
+        mesh: &SimplexMesh<D, E>,
+        elems: &mut [F],
+        fields: &[f64],
+        n_comp: usize,
+    ) -> (Vec<Point<D>>, Vec<f64>) {
+        let mut new_ids = FxHashMap::default();
+        let mut verts = Vec::new();
+        let mut res = Vec::new();
+        for e in elems.iter_mut() {
+            *e = F::from_iter(e.iter().map(|&i| {
+                *new_ids.entry(i).or_insert_with(|| {
+                    let (i0, i1, w) = self.weights(i);
+                    verts.push(self.coords(mesh, i));
+                    let (i0, i1) = (i0 as usize, i1 as usize);
+                    res.extend((0..n_comp).map(|k| {
+                        (1.0 - w) * fields[n_comp * i0 + k] + w * fields[n_comp * i1 + k]
+                    }));
+                    verts.len() as Idx - 1
+                })
+            }));
+        }
+        (verts, res)
+    }
+}
+
+/// Split polygon `poly` into triangles sharing its vertex with the smallest index. The
+/// triangulation only depends on the vertex indices, so that it is the same for the elements
+/// sharing the polygon
+fn fan(poly: &[Idx]) -> Vec<[Idx; 3]> {
+    let n = poly.len();
+    let k0 = (0..n).min_by_key(|&k| poly[k]).unwrap_or(0);
+    (1..n.saturating_sub(1))
+        .map(|m| [poly[k0], poly[(k0 + m) % n], poly[(k0 + m + 1) % n]])
+        .collect()
+}
+
+/// Signed distance to the plane through `origin` with normal `normal`
+fn plane_levelset<const D: usize, E: Elem>(
+    mesh: &SimplexMesh<D, E>,
+    origin: &Point<D>,
+    normal: &Point<D>,
+) -> Vec<f64> {
+    let n = normal.normalize();
+    mesh.verts().map(|p| (p - origin).dot(&n)).collect()
+}
+
+/// Level set `field - value` of a scalar vertex field
+fn isosurface_levelset(n_verts: Idx, field: &[f64], value: f64) -> Result<Vec<f64>> {
+    if field.len() != n_verts as usize {
+        return Err(Error::from("invalid field size"));
+    }
+    Ok(field.iter().map(|x| x - value).collect())
+}
+
+/// Number of components of a vertex field
+fn n_components(n_verts: Idx, fields: &[f64]) -> Result<usize> {
+    let n_verts = n_verts as usize;
+    if n_verts == 0 || fields.len() % n_verts != 0 {
+        return Err(Error::from("invalid field size"));
+    }
+    Ok(fields.len() / n_verts)
+}
+
+impl SimplexMesh<2, Triangle> {
+    /// Cut section of element `e`, oriented outwards from the region `phi < 0`
+    fn cut_edge(&self, cut: &mut CutPoints, e: &Triangle) -> Option<Edge> {
+        let pts = cut.elem_points(e);
+        if pts.len() != 2 {
+            return None;
+        }
+        let inside = e.iter().copied().find(|&i| cut.is_inside(i)).unwrap();
+        let a = cut.coords(self, pts[0]);
+        let u = cut.coords(self, pts[1]) - a;
+        let v = self.vert(inside) - a;
+        if u[0] * v[1] - u[1] * v[0] > 0.0 {
+            Some(Edge::new(pts[0], pts[1]))
+        } else {
+            Some(Edge::new(pts[1], pts[0]))
+        }
+    }
+
+    /// Keep the part of the mesh where `phi < 0`
+    fn clip(&self, phi: &[f64]) -> Self {
+        let mut cut = CutPoints::new(phi, self.n_verts());
+
+        let mut elems = Vec::with_capacity(self.n_elems() as usize);
+        let mut etags = Vec::with_capacity(self.n_elems() as usize);
+        let mut cut_faces = Vec::new();
+        for (e, t) in self.elems().zip(self.etags()) {
+            let n_inside = e.iter().filter(|&&i| cut.is_inside(i)).count();
+            if n_inside == 3 {
+                elems.push(e);
+                etags.push(t);
+            } else if n_inside > 0 {
+                for [a, b, c] in fan(&cut.clip(&[e[0], e[1], e[2]])) {
+                    elems.push(Triangle::new(a, b, c));
+                    etags.push(t);
+                }
+                cut_faces.extend(self.cut_edge(&mut cut, &e));
+            }
+        }
+
+        let mut faces = Vec::with_capacity(self.n_faces() as usize + cut_faces.len());
+        let mut ftags = Vec::with_capacity(faces.capacity());
+        for (f, t) in self.faces().zip(self.ftags()) {
+            let edg = cut.clip(&[f[0], f[1]]);
+            if edg.len() == 2 {
+                faces.push(Edge::new(edg[0], edg[1]));
+                ftags.push(t);
+            }
+        }
+        let cut_tag = self.ftags().max().unwrap_or(0) + 1;
+        ftags.extend(cut_faces.iter().map(|_| cut_tag));
+        faces.extend(cut_faces);
+
+        let mut res = Self::new(cut.vertices(self), elems, etags, faces, ftags);
+        res.remove_unused_vertices();
+        *res.tag_names_mut() = self.tag_names().clone();
+
+        res
+    }
+
+    /// Section of the mesh by the level set `phi = 0`, with the vertex fields interpolated
+    fn section(&self, phi: &[f64], fields: &[f64]) -> Result<(SimplexMesh<2, Edge>, Vec<f64>)> {
+        let n_comp = n_components(self.n_verts(), fields)?;
+        let mut cut = CutPoints::new(phi, self.n_verts());
+
+        let mut elems = Vec::new();
+        let mut etags = Vec::new();
+        for (e, t) in self.elems().zip(self.etags()) {
+            if let Some(edg) = self.cut_edge(&mut cut, &e) {
+                elems.push(edg);
+                etags.push(t);
+            }
+        }
+        let (verts, values) = cut.renumber(self, &mut elems, fields, n_comp);
+
+        Ok((
+            SimplexMesh::new(verts, elems, etags, Vec::new(), Vec::new()),
+            values,
+        ))
+    }
+
+    /// Keep the part of the mesh below the plane (line) through `origin` with normal `normal`.
+    /// The elements cut by the plane are split (see `fan`), and the faces on the plane are
+    /// tagged with the largest face tag + 1
+    #[must_use]
+    pub fn cut_by_plane(&self, origin: &Point<2>, normal: &Point<2>) -> Self {
+        debug!("Cut the mesh by a plane");
+        self.clip(&plane_levelset(self, origin, normal))
+    }
+
+    /// Section of the mesh by the plane (line) through `origin` with normal `normal`, oriented
+    /// along `normal`. The vertex fields (stored as `[v_0(p_0), ..., v_{n-1}(p_0), v_0(p_1),
+    /// ...]`) are interpolated linearly on the section
+    pub fn plane_section(
+        &self,
+        origin: &Point<2>,
+        normal: &Point<2>,
+        fields: &[f64],
+    ) -> Result<(SimplexMesh<2, Edge>, Vec<f64>)> {
+        debug!("Compute the section of the mesh by a plane");
+        self.section(&plane_levelset(self, origin, normal), fields)
+    }
+
+    /// Iso-line `field = value` of a vertex field, oriented towards the increasing values of
+    /// `field`. The vertex fields `fields` (stored as `[v_0(p_0), ..., v_{n-1}(p_0), v_0(p_1),
+    /// ...]`) are interpolated linearly on the iso-line
+    pub fn extract_isosurface(
+        &self,
+        field: &[f64],
+        value: f64,
+        fields: &[f64],
+    ) -> Result<(SimplexMesh<2, Edge>, Vec<f64>)> {
+        debug!("Extract the iso-line {value} of a vertex field");
+        self.section(&isosurface_levelset(self.n_verts(), field, value)?, fields)
+    }
+}
+
+impl SimplexMesh<3, Tetrahedron> {
+    /// Cut section of element `e`, ordered and oriented outwards from the region `phi < 0`
+    fn cut_polygon(&self, cut: &mut CutPoints, e: &Tetrahedron) -> Vec<Idx> {
+        let pts = cut.elem_points(e);
+        if pts.len() < 3 {
+            return pts;
+        }
+        let coords = pts.iter().map(|&i| cut.coords(self, i)).collect::<Vec<_>>();
+        let inside = e.iter().copied().find(|&i| cut.is_inside(i)).unwrap();
+        let mut n = (coords[1] - coords[0]).cross(&(coords[2] - coords[0]));
+        if n.dot(&(self.vert(inside) - coords[0])) > 0.0 {
+            n = -n;
+        }
+        let center = coords.iter().sum::<Point<3>>() / coords.len() as f64;
+        let u = coords[0] - center;
+        let v = n.cross(&u);
+        let angles = coords
+            .iter()
+            .map(|p| (p - center).dot(&v).atan2((p - center).dot(&u)))
+            .collect::<Vec<_>>();
+        let mut order = (0..pts.len()).collect::<Vec<_>>();
+        order.sort_by(|&i, &j| angles[i].total_cmp(&angles[j]));
+        order.iter().map(|&i| pts[i]).collect()
+    }
+
+    /// Keep the part of the mesh where `phi < 0`
+    fn clip(&self, phi: &[f64]) -> Self {
+        let mut cut = CutPoints::new(phi, self.n_verts());
+
+        let mut elems = Vec::with_capacity(self.n_elems() as usize);
+        let mut etags = Vec::with_capacity(self.n_elems() as usize);
+        let mut cut_faces = Vec::new();
+        for (e, t) in self.elems().zip(self.etags()) {
+            let n_inside = e.iter().filter(|&&i| cut.is_inside(i)).count();
+            if n_inside == 4 {
+                elems.push(e);
+                etags.push(t);
+            } else if n_inside > 0 {
+                // split the polytope into tetrahedra joining its vertex with the smallest index
+                // to the triangulated facets
+                let cut_facet = self.cut_polygon(&mut cut, &e);
+                let facets = (0..4)
+                    .map(|i| {
+                        let f = e.face(i);
+                        cut.clip(&[f[0], f[1], f[2]])
+                    })
+                    .filter(|p| p.len() > 2)
+                    .collect::<Vec<_>>();
+                let v0 = *facets.iter().flatten().min().unwrap();
+                for [a, b, c] in facets
+                    .iter()
+                    .chain(std::iter::once(&cut_facet))
+                    .filter(|p| !p.contains(&v0))
+                    .flat_map(|p| fan(p))
+                {
+                    elems.push(Tetrahedron::new(b, a, c, v0));
+                    etags.push(t);
+                }
+                cut_faces.extend(fan(&cut_facet));
+            }
+        }
+
+        let mut faces = Vec::with_capacity(self.n_faces() as usize + cut_faces.len());
+        let mut ftags = Vec::with_capacity(faces.capacity());
+        for (f, t) in self.faces().zip(self.ftags()) {
+            for [a, b, c] in fan(&cut.clip(&[f[0], f[1], f[2]])) {
+                faces.push(Triangle::new(a, b, c));
+                ftags.push(t);
+            }
+        }
+        let cut_tag = self.ftags().max().unwrap_or(0) + 1;
+        for [a, b, c] in cut_faces {
+            faces.push(Triangle::new(a, b, c));
+            ftags.push(cut_tag);
+        }
+
+        let mut res = Self::new(cut.vertices(self), elems, etags, faces, ftags);
+        res.remove_unused_vertices();
+        *res.tag_names_mut() = self.tag_names().clone();
+
+        res
+    }
+
+    /// Section of the mesh by the level set `phi = 0`, with the vertex fields interpolated
+    fn section(&self, phi: &[f64], fields: &[f64]) -> Result<(SimplexMesh<3, Triangle>, Vec<f64>)> {
+        let n_comp = n_components(self.n_verts(), fields)?;
+        let mut cut = CutPoints::new(phi, self.n_verts());
+
+        let mut elems = Vec::new();
+        let mut etags = Vec::new();
+        for (e, t) in self.elems().zip(self.etags()) {
+            for [a, b, c] in fan(&self.cut_polygon(&mut cut, &e)) {
+                elems.push(Triangle::new(a, b, c));
+                etags.push(t);
+            }
+        }
+        let (verts, values) = cut.renumber(self, &mut elems, fields, n_comp);
+
+        Ok((
+            SimplexMesh::new(verts, elems, etags, Vec::new(), Vec::new()),
+            values,
+        ))
+    }
+
+    /// Keep the part of the mesh below the plane through `origin` with normal `normal`. The
+    /// elements cut by the plane are split into tetrahedra (see `fan`), and the faces on the
+    /// plane are tagged with the largest face tag + 1
+    #[must_use]
+    pub fn cut_by_plane(&self, origin: &Point<3>, normal: &Point<3>) -> Self {
+        debug!("Cut the mesh by a plane");
+        self.clip(&plane_levelset(self, origin, normal))
+    }
+
+    /// Section of the mesh by the plane through `origin` with normal `normal`, oriented along
+    /// `normal`. The vertex fields (stored as `[v_0(p_0), ..., v_{n-1}(p_0), v_0(p_1), ...]`)
+    /// are interpolated linearly on the section
+    pub fn plane_section(
+        &self,
+        origin: &Point<3>,
+        normal: &Point<3>,
+        fields: &[f64],
+    ) -> Result<(SimplexMesh<3, Triangle>, Vec<f64>)> {
+        debug!("Compute the section of the mesh by a plane");
+        self.section(&plane_levelset(self, origin, normal), fields)
+    }
+
+    /// Iso-surface `field = value` of a vertex field, oriented towards the increasing values of
+    /// `field`. The vertex fields `fields` (stored as `[v_0(p_0), ..., v_{n-1}(p_0), v_0(p_1),
+    /// ...]`) are interpolated linearly on the iso-surface
+    pub fn extract_isosurface(
+        &self,
+        field: &[f64],
+        value: f64,
+        fields: &[f64],
+    ) -> Result<(SimplexMesh<3, Triangle>, Vec<f64>)> {
+        debug!("Extract the iso-surface {value} of a vertex field");
+        self.section(&isosurface_levelset(self.n_verts(), field, value)?, fields)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::fan;
+    use crate::{
+        mesh::{
+            test_meshes::{test_mesh_2d, test_mesh_3d},
+            GElem, Point,
+        },
+        Result,
+    };
+    use std::f64::consts::SQRT_2;
+
+    #[test]
+    fn test_fan() {
+        assert_eq!(fan(&[3, 1, 2, 5]), [[1, 2, 5], [1, 5, 3]]);
+        assert!(fan(&[1, 2]).is_empty());
+    }
+
+    #[test]
+    fn test_cut_2d() -> Result<()> {
+        let mut mesh = test_mesh_2d();
+        mesh.add_boundary_faces();
+
+        let origin = Point::<2>::new(0.5, 0.5);
+        let normal = Point::<2>::new(2.0, 0.0);
+        let cut = mesh.cut_by_plane(&origin, &normal);
+        assert!((cut.vol() - 0.5).abs() < 1e-12);
+        assert!(cut.gelems().all(|ge| ge.vol() > 0.0));
+        assert!(cut.validate().is_empty());
+        let l: f64 = cut
+            .faces()
+            .zip(cut.ftags())
+            .filter(|&(_, t)| t == 6)
+            .map(|(f, _)| cut.gface(f).vol())
+            .sum();
+        assert!((l - 1.0).abs() < 1e-12);
+
+        // plane through vertices 1 and 3
+        let cut = mesh.cut_by_plane(&origin, &Point::<2>::new(1.0, 1.0));
+        assert_eq!(cut.n_verts(), 4);
+        assert!((cut.vol() - 0.5).abs() < 1e-12);
+        assert!(cut.validate().is_empty());
+
+        let coords = mesh.verts().flat_map(|p| [p[0], p[1]]).collect::<Vec<_>>();
+        let (section, values) = mesh.plane_section(&origin, &normal, &coords)?;
+        assert_eq!(section.n_elems(), 2);
+        assert!((section.vol() - 1.0).abs() < 1e-12);
+        for (p, v) in section.verts().zip(values.chunks(2)) {
+            assert!((p[0] - 0.5).abs() < 1e-12);
+            assert!((p - Point::<2>::from_column_slice(v)).norm() < 1e-12);
+        }
+        assert!(mesh.plane_section(&origin, &normal, &coords[1..]).is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_cut_3d() -> Result<()> {
+        let mut mesh = test_mesh_3d().split();
+        mesh.add_boundary_faces();
+
+        let origin = Point::<3>::new(0.3, 0.4, 0.5);
+        let normal = Point::<3>::new(1.0, 2.0, 3.0);
+        let below = mesh.cut_by_plane(&origin, &normal);
+        let above = mesh.cut_by_plane(&origin, &-normal);
+        assert!(below.gelems().all(|ge| ge.vol() > 0.0));
+        assert!(above.gelems().all(|ge| ge.vol() > 0.0));
+        assert!((below.vol() + above.vol() - 1.0).abs() < 1e-12);
+        assert!(below.validate().is_empty());
+        assert!(above.validate().is_empty());
+
+        let coords = mesh
+            .verts()
+            .flat_map(|p| [p[0], p[1], p[2]])
+            .collect::<Vec<_>>();
+        let (section, values) = mesh.plane_section(&origin, &normal, &coords)?;
+        for (p, v) in section.verts().zip(values.chunks(3)) {
+            assert!((p - origin).dot(&normal).abs() < 1e-12);
+            assert!((p - Point::<3>::from_column_slice(v)).norm() < 1e-12);
+        }
+        let n = normal.normalize();
+        for ge in section.gelems() {
+            assert!((ge.normal() - n).norm() < 1e-10);
+        }
+        let area = section.vol();
+        let (section, _) = mesh.plane_section(&origin, &-normal, &coords)?;
+        assert!((section.vol() - area).abs() < 1e-12);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_isosurface_2d() -> Result<()> {
+        let mesh = test_mesh_2d().split().split();
+        let field = mesh.verts().map(|p| p.norm_squared()).collect::<Vec<_>>();
+
+        let (iso, values) = mesh.extract_isosurface(&field, 0.25, &field)?;
+        assert!(iso.n_elems() > 0);
+        assert!(values.iter().all(|v| (v - 0.25).abs() < 1e-12));
+        let l = iso.vol();
+        assert!(l > 0.7 && l < 0.8);
+        for ge in iso.gelems() {
+            assert!(ge.normal().dot(&ge.center()) > 0.0);
+        }
+
+        assert!(mesh.extract_isosurface(&field[1..], 0.25, &field).is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_isosurface_3d() -> Result<()> {
+        let mesh = test_mesh_3d().split().split();
+        let field = mesh.verts().map(|p| p[0] + p[1]).collect::<Vec<_>>();
+
+        let (iso, values) = mesh.extract_isosurface(&field, 1.0, &field)?;
+        assert!((iso.vol() - SQRT_2).abs() < 1e-12);
+        assert!(values.iter().all(|v| (v - 1.0).abs() < 1e-12));
+        let n = Point::<3>::new(1.0, 1.0, 0.0).normalize();
+        for ge in iso.gelems() {
+            assert!((ge.normal() - n).norm() < 1e-10);
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,47 @@
+use crate::{
+    mesh::{Elem, SimplexMesh},
+    Error, Result, Tag,
+};
+use log::debug;
+use rayon::prelude::*;
+
+impl<const D: usize, E: Elem> SimplexMesh<D, E> {
+    /// Compute the (exact) distance from each vertex to the faces with a tag in `tags`. The
+    /// faces are stored in an `ElementTree` and the vertices are projected in parallel
+    pub fn distance_to_tagged_faces(&self, tags: &[Tag]) -> Result<Vec<f64>> {
+        debug!("Compute the distance to the faces with tags {tags:?}");
+
+        let (bdy, _) = self.boundary();
+        let faces = bdy.extract(|t| tags.contains(&t)).mesh;
+        if faces.n_elems() == 0 {
+            return Err(Error::from("no face found with the given tags"));
+        }
+        let tree = faces.compute_element_tree();
+
+        Ok(self.par_verts().map(|p| tree.project(&p).0).collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{mesh::test_meshes::test_mesh_3d, Result};
+
+    #[test]
+    fn test_distance_to_tagged_faces() -> Result<()> {
+        let mesh = test_mesh_3d().split().split();
+
+        let dist = mesh.distance_to_tagged_faces(&[1])?;
+        for (p, d) in mesh.verts().zip(dist) {
+            assert!((d - p[2]).abs() < 1e-12);
+        }
+
+        let dist = mesh.distance_to_tagged_faces(&[1, 3])?;
+        for (p, d) in mesh.verts().zip(dist) {
+            assert!((d - p[1].min(p[2])).abs() < 1e-12);
+        }
+
+        assert!(mesh.distance_to_tagged_faces(&[10]).is_err());
+
+        Ok(())
+    }
+}
@@ -0,0 +1,153 @@
+use crate::{
+    mesh::{Elem, SimplexMesh},
+    Error, Idx, Result,
+};
+use log::debug;
+use rayon::{
+    iter::{IndexedParallelIterator, ParallelIterator},
+    slice::ParallelSliceMut,
+};
+
+impl<const D: usize, E: Elem> SimplexMesh<D, E> {
+    /// Get the index of the edge between vertices `i` and `j`, if any
+    /// The edges are required
+    pub fn edge_index(&self, i: Idx, j: Idx) -> Result<Option<Idx>> {
+        let edg = if i < j { [i, j] } else { [j, i] };
+        Ok(self
+            .get_edges()?
+            .binary_search(&edg)
+            .ok()
+            .map(|i_edge| i_edge as Idx))
+    }
+
+    /// Compute the edge lengths
+    /// The edges are required
+    pub fn edge_lengths(&self) -> Result<Vec<f64>> {
+        Ok(self
+            .get_edges()?
+            .iter()
+            .map(|e| (self.vert(e[1]) - self.vert(e[0])).norm())
+            .collect())
+    }
+
+    /// Convert a field defined at the vertices to a field defined at the edge centers (i.e.
+    /// the average of the values at the two vertices). Fields with several components are
+    /// stored as in `elem_data_to_vertex_data`
+    /// The edges are required
+    pub fn vertex_data_to_edge_data(&self, v: &[f64]) -> Result<Vec<f64>> {
+        debug!("Convert vertex data to edge data");
+        let edges = self.get_edges()?;
+        let n_verts = self.n_verts() as usize;
+        if n_verts == 0 || v.len() % n_verts != 0 {
+            return Err(Error::from("invalid vertex data size"));
+        }
+
+        let n_comp = v.len() / n_verts;
+
+        let mut res = vec![0.; n_comp * edges.len()];
+        res.par_chunks_mut(n_comp).zip(edges).for_each(|(vals, e)| {
+            let (i0, i1) = (n_comp * e[0] as usize, n_comp * e[1] as usize);
+            for (i_comp, val) in vals.iter_mut().enumerate() {
+                *val = 0.5 * (v[i0 + i_comp] + v[i1 + i_comp]);
+            }
+        });
+
+        Ok(res)
+    }
+
+    /// Convert a field defined on the edges to a field defined at the vertices, using the
+    /// average of the values on the edges connected to each vertex. Fields with several
+    /// components are stored as in `elem_data_to_vertex_data`
+    /// The vertex-to-edge connectivity is required
+    pub fn edge_data_to_vertex_data(&self, v: &[f64]) -> Result<Vec<f64>> {
+        debug!("Convert edge data to vertex data");
+        let v2e = self.get_vertex_to_edges()?;
+        let n_edges = v2e.m() as usize;
+        if n_edges == 0 || v.len() % n_edges != 0 {
+            return Err(Error::from("invalid edge data size"));
+        }
+
+        let n_comp = v.len() / n_edges;
+
+        let mut res = vec![0.; n_comp * self.n_verts() as usize];
+        res.par_chunks_mut(n_comp)
+            .enumerate()
+            .for_each(|(i_vert, vals)| {
+                let edges = v2e.row(i_vert as Idx);
+                if edges.is_empty() {
+                    return;
+                }
+                for &i_edge in edges {
+                    let i = n_comp * i_edge as usize;
+                    vals.iter_mut()
+                        .zip(&v[i..i + n_comp])
+                        .for_each(|(x, y)| *x += y);
+                }
+                let w = 1.0 / edges.len() as f64;
+                vals.iter_mut().for_each(|x| *x *= w);
+            });
+
+        Ok(res)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        mesh::{test_meshes::test_mesh_3d, Point},
+        Idx, Result,
+    };
+
+    #[test]
+    fn test_edges_3d() -> Result<()> {
+        let mut mesh = test_mesh_3d().split();
+        assert!(mesh.edge_lengths().is_err());
+
+        let edges = mesh.compute_edges().clone();
+        assert!(edges.windows(2).all(|w| w[0] < w[1]));
+        assert!(edges.iter().all(|e| e[0] < e[1]));
+
+        for (i_edge, e) in edges.iter().enumerate() {
+            assert_eq!(mesh.edge_index(e[1], e[0])?, Some(i_edge as Idx));
+        }
+        assert_eq!(mesh.edge_index(0, 6)?, None);
+
+        let lengths = mesh.edge_lengths()?;
+        assert_eq!(lengths.len(), edges.len());
+        assert!(lengths.iter().all(|&l| l > 0.4 && l < 1.0 + 1e-12));
+
+        mesh.compute_vertex_to_edges();
+        let v2e = mesh.get_vertex_to_edges()?;
+        assert_eq!(v2e.n(), mesh.n_verts());
+        assert_eq!(v2e.n_edges() as usize, 2 * edges.len());
+        for i_vert in 0..mesh.n_verts() {
+            let row = v2e.row(i_vert);
+            assert!(row
+                .iter()
+                .all(|&i_edge| edges[i_edge as usize].contains(&i_vert)));
+        }
+
+        // linear fields are exact at the edge centers
+        let f = |p: Point<3>| [p[0] + 2.0 * p[1], p[2]];
+        let v = mesh.verts().flat_map(f).collect::<Vec<_>>();
+        let v_edges = mesh.vertex_data_to_edge_data(&v)?;
+        for (e, vals) in edges.iter().zip(v_edges.chunks(2)) {
+            let p = 0.5 * (mesh.vert(e[0]) + mesh.vert(e[1]));
+            let [a, b] = f(p);
+            assert!(f64::abs(vals[0] - a) < 1e-12);
+            assert!(f64::abs(vals[1] - b) < 1e-12);
+        }
+
+        // constant fields
+        let v = vec![2.0; 3 * edges.len()];
+        let v_verts = mesh.edge_data_to_vertex_data(&v)?;
+        assert_eq!(v_verts.len(), 3 * mesh.n_verts() as usize);
+        assert!(v_verts.iter().all(|&x| f64::abs(x - 2.0) < 1e-12));
+        assert!(mesh.edge_data_to_vertex_data(&[1.0]).is_err());
+
+        mesh.clear_edges();
+        assert!(mesh.get_vertex_to_edges().is_err());
+
+        Ok(())
+    }
+}
@@ -0,0 +1,471 @@
+use crate::{
+    mesh::{AsSliceF64, Elem, GElem, Point, SimplexMesh},
+    metric::IsoMetric,
+    Idx,
+};
+use nalgebra::{DMatrix, DVector};
+use rayon::iter::{IntoParallelRefIterator, ParallelIterator};
+use std::marker::PhantomData;
+
+/// Maximum number of elements in the leaves of the tree
+const LEAF_SIZE: usize = 8;
+
+/// Tolerance on the barycentric coordinates used to decide if a point is inside an element
+const BCOORDS_TOL: f64 = 1e-12;
+
+/// Node of the bounding volume hierarchy: a bounding box and either two children or a range
+/// of elements
+#[derive(Debug)]
+struct Node<const D: usize> {
+    min: Point<D>,
+    max: Point<D>,
+    start: usize,
+    end: usize,
+    children: Option<[usize; 2]>,
+}
+
+impl<const D: usize> Node<D> {
+    /// Squared distance between `pt` and the bounding box
+    fn dist2(&self, pt: &Point<D>) -> f64 {
+        (0..D)
+            .map(|i| {
+                let d = (self.min[i] - pt[i]).max(pt[i] - self.max[i]).max(0.0);
+                d * d
+            })
+            .sum()
+    }
+}
+
+/// Project `pt` onto the simplex with vertices `pts`: `pt` is first projected onto the affine
+/// hull of the simplex, and if the projection is outside of the simplex (or if the simplex is
+/// degenerate) the closest projection onto its faces is used
+fn project_simplex<const D: usize>(pts: &[Point<D>], pt: &Point<D>) -> Point<D> {
+    if pts.len() == 1 {
+        return pts[0];
+    }
+
+    let n = pts.len() - 1;
+    let a = DMatrix::from_fn(D, n, |i, j| pts[j + 1][i] - pts[0][i]);
+    let b = DVector::from_column_slice((pt - pts[0]).as_slice());
+    if let Some(chol) = (a.transpose() * &a).cholesky() {
+        let x = chol.solve(&(a.transpose() * b));
+        if x.iter().all(|&c| c >= 0.0) && x.sum() <= 1.0 {
+            return x
+                .iter()
+                .enumerate()
+                .fold(pts[0], |p, (j, &c)| p + c * (pts[j + 1] - pts[0]));
+        }
+    }
+
+    (0..pts.len())
+        .map(|i| {
+            let face = pts
+                .iter()
+                .enumerate()
+                .filter(|&(j, _)| j != i)
+                .map(|(_, p)| *p)
+                .collect::<Vec<_>>();
+            project_simplex(&face, pt)
+        })
+        .min_by(|p, q| (p - pt).norm_squared().total_cmp(&(q - pt).norm_squared()))
+        .unwrap()
+}
+
+/// Barycentric coordinates in the elements of type `E` (fixed-size vectors)
+pub type BCoords<const D: usize, E> =
+    <<E as Elem>::Geom<D, IsoMetric<D>> as GElem<D, IsoMetric<D>>>::BCoords;
+
+/// Bounding volume hierarchy over the element bounding boxes of a `SimplexMesh` (see
+/// `SimplexMesh::compute_element_tree`), used to locate points in the mesh and to find the
+/// nearest element or the projection of a point. The element coordinates are copied so that
+/// the tree can be used independently of the mesh
+pub struct ElementTree<const D: usize, E: Elem> {
+    verts: Vec<Point<D>>,
+    nodes: Vec<Node<D>>,
+    elems: Vec<Idx>,
+    eps: f64,
+    elem: PhantomData<E>,
+}
+
+impl<const D: usize, E: Elem> ElementTree<D, E> {
+    /// Build the tree for the elements of `mesh`
+    #[must_use]
+    pub fn new(mesh: &SimplexMesh<D, E>) -> Self {
+        let verts = mesh
+            .elems()
+            .flat_map(|e| e.iter().map(|&i| mesh.vert(i)).collect::<Vec<_>>())
+            .collect::<Vec<_>>();
+        let mut res = Self {
+            verts,
+            nodes: Vec::new(),
+            elems: (0..mesh.n_elems()).collect(),
+            eps: 0.0,
+            elem: PhantomData,
+        };
+
+        let n = E::N_VERTS as usize;
+        let boxes = res
+            .verts
+            .chunks(n)
+            .map(|pts| {
+                let min = pts.iter().fold(pts[0], |a, p| a.inf(p));
+                let max = pts.iter().fold(pts[0], |a, p| a.sup(p));
+                (min, max)
+            })
+            .collect::<Vec<_>>();
+
+        if !boxes.is_empty() {
+            let mut elems = std::mem::take(&mut res.elems);
+            res.build(&boxes, &mut elems, 0);
+            res.elems = elems;
+            res.eps = 1e-10 * (res.nodes[0].max - res.nodes[0].min).norm();
+        }
+
+        res
+    }
+
+    /// Recursively build the tree for `elems`, whose first item is at position `start` in the
+    /// element list, and return the index of the node
+    fn build(&mut self, boxes: &[(Point<D>, Point<D>)], elems: &mut [Idx], start: usize) -> usize {
+        let (mut min, mut max) = boxes[elems[0] as usize];
+        let mut cmin = 0.5 * (min + max);
+        let mut cmax = cmin;
+        for &i in &*elems {
+            let (bmin, bmax) = &boxes[i as usize];
+            min = min.inf(bmin);
+            max = max.sup(bmax);
+            let c = 0.5 * (bmin + bmax);
+            cmin = cmin.inf(&c);
+            cmax = cmax.sup(&c);
+        }
+
+        let i_node = self.nodes.len();
+        self.nodes.push(Node {
+            min,
+            max,
+            start,
+            end: start + elems.len(),
+            children: None,
+        });
+
+        if elems.len() > LEAF_SIZE {
+            // split along the largest dimension of the box containing the centers
+            let axis = (cmax - cmin).imax();
+            let mid = elems.len() / 2;
+            let center = |i: &Idx| {
+                let (bmin, bmax) = &boxes[*i as usize];
+                bmin[axis] + bmax[axis]
+            };
+            elems.select_nth_unstable_by(mid, |i, j| center(i).total_cmp(&center(j)));
+            let (left, right) = elems.split_at_mut(mid);
+            let i_left = self.build(boxes, left, start);
+            let i_right = self.build(boxes, right, start + mid);
+            self.nodes[i_node].children = Some([i_left, i_right]);
+        }
+
+        i_node
+    }
+
+    /// Coordinates of the vertices of element `i_elem`
+    fn elem_verts(&self, i_elem: Idx) -> &[Point<D>] {
+        let n = E::N_VERTS as usize;
+        let start = n * i_elem as usize;
+        &self.verts[start..start + n]
+    }
+
+    /// Barycentric coordinates of `pt` in element `i_elem`
+    fn bcoords(&self, i_elem: Idx, pt: &Point<D>) -> BCoords<D, E> {
+        let ge = E::Geom::<D, IsoMetric<D>>::from_verts(
+            self.elem_verts(i_elem)
+                .iter()
+                .map(|p| (*p, IsoMetric::<D>::from(1.0))),
+        );
+        ge.bcoords(pt)
+    }
+
+    /// Find an element containing `pt`, and return its index together with the barycentric
+    /// coordinates of `pt` in this element, or `None` if `pt` is outside of the mesh
+    #[must_use]
+    pub fn locate(&self, pt: &Point<D>) -> Option<(Idx, BCoords<D, E>)> {
+        if self.nodes.is_empty() {
+            return None;
+        }
+
+        let mut stack = vec![0];
+        while let Some(i_node) = stack.pop() {
+            let node = &self.nodes[i_node];
+            if (0..D).any(|i| pt[i] < node.min[i] - self.eps || pt[i] > node.max[i] + self.eps) {
+                continue;
+            }
+            if let Some(children) = node.children {
+                stack.extend(children);
+            } else {
+                for &i_elem in &self.elems[node.start..node.end] {
+                    let x = self.bcoords(i_elem, pt);
+                    if x.as_slice_f64()
+                        .iter()
+                        .all(|c| (-BCOORDS_TOL..1.0 + BCOORDS_TOL).contains(c))
+                    {
+                        return Some((i_elem, x));
+                    }
+                }
+            }
+        }
+
+        None
+    }
+
+    /// Get the elements whose bounding box intersects the box [`min`, `max`]
+    #[must_use]
+    pub fn intersecting_elements(&self, min: &Point<D>, max: &Point<D>) -> Vec<Idx> {
+        let mut res = Vec::new();
+        if self.nodes.is_empty() {
+            return res;
+        }
+
+        let overlaps = |bmin: &Point<D>, bmax: &Point<D>| {
+            (0..D).all(|i| bmin[i] <= max[i] + self.eps && bmax[i] >= min[i] - self.eps)
+        };
+        let n = E::N_VERTS as usize;
+        let mut stack = vec![0];
+        while let Some(i_node) = stack.pop() {
+            let node = &self.nodes[i_node];
+            if !overlaps(&node.min, &node.max) {
+                continue;
+            }
+            if let Some(children) = node.children {
+                stack.extend(children);
+            } else {
+                for &i_elem in &self.elems[node.start..node.end] {
+                    let pts = &self.verts[n * i_elem as usize..n * (i_elem as usize + 1)];
+                    let bmin = pts.iter().fold(pts[0], |a, p| a.inf(p));
+                    let bmax = pts.iter().fold(pts[0], |a, p| a.sup(p));
+                    if overlaps(&bmin, &bmax) {
+                        res.push(i_elem);
+                    }
+                }
+            }
+        }
+
+        res
+    }
+
+    /// Locate several points in parallel (see `locate`)
+    #[must_use]
+    pub fn locate_all(&self, pts: &[Point<D>]) -> Vec<Option<(Idx, BCoords<D, E>)>> {
+        pts.par_iter().map(|pt| self.locate(pt)).collect()
+    }
+
+    /// Find the element nearest to `pt`, and return its index together with the projection of
+    /// `pt` onto this element and the squared distance. The nodes are visited depth first,
+    /// nearest child first, and are skipped if their bounding box is further than the current
+    /// nearest element
+    fn nearest(&self, pt: &Point<D>) -> (Idx, Point<D>, f64) {
+        assert!(!self.nodes.is_empty(), "empty element tree");
+
+        let mut res = (0, *pt, f64::INFINITY);
+        let mut stack = vec![0];
+        while let Some(i_node) = stack.pop() {
+            let node = &self.nodes[i_node];
+            if node.dist2(pt) >= res.2 {
+                continue;
+            }
+            if let Some([i_left, i_right]) = node.children {
+                if self.nodes[i_left].dist2(pt) < self.nodes[i_right].dist2(pt) {
+                    stack.extend([i_right, i_left]);
+                } else {
+                    stack.extend([i_left, i_right]);
+                }
+            } else {
+                for &i_elem in &self.elems[node.start..node.end] {
+                    let p = project_simplex(self.elem_verts(i_elem), pt);
+                    let d = (p - pt).norm_squared();
+                    if d < res.2 {
+                        res = (i_elem, p, d);
+                    }
+                }
+            }
+        }
+
+        res
+    }
+
+    /// Find the element nearest to `pt`
+    #[must_use]
+    pub fn nearest_elem(&self, pt: &Point<D>) -> Idx {
+        self.nearest(pt).0
+    }
+
+    /// Project `pt` onto the mesh, and return the distance and the projection
+    #[must_use]
+    pub fn project(&self, pt: &Point<D>) -> (f64, Point<D>) {
+        let (_, p, d) = self.nearest(pt);
+        (d.sqrt(), p)
+    }
+
+    /// Find the element nearest to `pt`, and return its index together with the barycentric
+    /// coordinates of `pt` in this element
+    #[must_use]
+    pub fn nearest_elem_bcoords(&self, pt: &Point<D>) -> (Idx, BCoords<D, E>) {
+        let i_elem = self.nearest_elem(pt);
+        (i_elem, self.bcoords(i_elem, pt))
+    }
+
+    /// Locate `pt` in the mesh (see `locate`); if it is outside of the mesh, use the nearest
+    /// element with the barycentric coordinates clipped to $`[0, 1]`$ and renormalized
+    #[must_use]
+    pub fn locate_or_nearest(&self, pt: &Point<D>) -> (Idx, BCoords<D, E>) {
+        self.locate(pt).unwrap_or_else(|| {
+            let (i_elem, mut x) = self.nearest_elem_bcoords(pt);
+            let w = x.as_mut_slice_f64();
+            w.iter_mut().for_each(|c| *c = c.max(0.0));
+            let sum = w.iter().sum::<f64>();
+            w.iter_mut().for_each(|c| *c /= sum);
+            (i_elem, x)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        mesh::{
+            test_meshes::{test_mesh_2d, test_mesh_3d},
+            GElem, Point,
+        },
+        spatialindex::{DefaultObjectIndex, ObjectIndex},
+    };
+    use rand::{rngs::StdRng, Rng, SeedableRng};
+
+    #[test]
+    fn test_locate_2d() {
+        let mesh = test_mesh_2d().split().split().split();
+        let tree = mesh.compute_element_tree();
+
+        let mut rng = StdRng::seed_from_u64(0);
+        let pts = (0..100)
+            .map(|_| Point::<2>::new(rng.random(), rng.random()))
+            .collect::<Vec<_>>();
+
+        for (pt, res) in pts.iter().zip(tree.locate_all(&pts)) {
+            let (i_elem, x) = res.unwrap();
+            let e = mesh.elem(i_elem);
+            let p = e
+                .iter()
+                .zip(x.iter())
+                .fold(Point::<2>::zeros(), |a, (&i, &w)| a + w * mesh.vert(i));
+            assert!((p - pt).norm() < 1e-12);
+        }
+
+        assert!(tree.locate(&Point::<2>::new(1.1, 0.5)).is_none());
+        assert!(tree.locate(&Point::<2>::new(0.5, -0.1)).is_none());
+    }
+
+    #[test]
+    fn test_locate_3d() {
+        let mesh = test_mesh_3d().split().split();
+        let tree = mesh.compute_element_tree();
+
+        for pt in mesh.verts() {
+            let (i_elem, x) = tree.locate(&pt).unwrap();
+            assert!(x.iter().all(|&c| c > -1e-12));
+            let e = mesh.elem(i_elem);
+            let p = e
+                .iter()
+                .zip(x.iter())
+                .fold(Point::<3>::zeros(), |a, (&i, &w)| a + w * mesh.vert(i));
+            assert!((p - pt).norm() < 1e-12);
+        }
+
+        assert!(tree.locate(&Point::<3>::new(0.5, 0.5, 1.01)).is_none());
+    }
+
+    #[test]
+    fn test_intersecting_elements() {
+        let mesh = test_mesh_2d().split().split();
+        let tree = mesh.compute_element_tree();
+
+        let min = Point::<2>::new(0.3, 0.3);
+        let max = Point::<2>::new(0.6, 0.4);
+        let mut res = tree.intersecting_elements(&min, &max);
+        res.sort_unstable();
+
+        let expected = (0..mesh.n_elems())
+            .filter(|&i| {
+                let ge = mesh.gelem(mesh.elem(i));
+                let pts = (0..3).map(|j| ge.vert(j)).collect::<Vec<_>>();
+                (0..2).all(|k| {
+                    pts.iter().any(|p| p[k] >= min[k]) && pts.iter().any(|p| p[k] <= max[k])
+                })
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(res, expected);
+
+        let res =
+            tree.intersecting_elements(&Point::<2>::new(2.0, 2.0), &Point::<2>::new(3.0, 3.0));
+        assert!(res.is_empty());
+    }
+
+    #[test]
+    fn test_nearest_elem_2d() {
+        let mesh = test_mesh_2d().split().split();
+        let tree = mesh.compute_element_tree();
+        let index = <DefaultObjectIndex<2> as ObjectIndex<2>>::new(&mesh);
+
+        let mut rng = StdRng::seed_from_u64(0);
+        for _ in 0..100 {
+            let pt = Point::<2>::new(
+                3.0 * rng.random::<f64>() - 1.0,
+                3.0 * rng.random::<f64>() - 1.0,
+            );
+            let (d, p) = tree.project(&pt);
+            let (d_ref, p_ref) = index.project(&pt);
+            assert!(f64::abs(d - d_ref) < 1e-12);
+            assert!((p - p_ref).norm() < 1e-12);
+        }
+    }
+
+    #[test]
+    fn test_nearest_elem_3d() {
+        let mesh = test_mesh_3d().split().split();
+        let tree = mesh.compute_element_tree();
+
+        let mut rng = StdRng::seed_from_u64(0);
+        for _ in 0..100 {
+            let pt = Point::<3>::new(
+                3.0 * rng.random::<f64>() - 1.0,
+                3.0 * rng.random::<f64>() - 1.0,
+                3.0 * rng.random::<f64>() - 1.0,
+            );
+            // projection onto the unit cube
+            let p_ref = pt.map(|x| x.clamp(0.0, 1.0));
+            let (d, p) = tree.project(&pt);
+            assert!(f64::abs(d - (pt - p_ref).norm()) < 1e-12);
+            assert!((p - p_ref).norm() < 1e-12);
+
+            let i_elem = tree.nearest_elem(&pt);
+            let x = mesh.gelem(mesh.elem(i_elem)).bcoords(&p);
+            assert!(x.iter().all(|&c| c > -1e-10));
+        }
+    }
+
+    #[test]
+    fn test_project_surface() {
+        let mut mesh = test_mesh_3d().split();
+        mesh.add_boundary_faces();
+        let (bdy, _) = mesh.boundary();
+        let tree = bdy.compute_element_tree();
+
+        let (d, p) = tree.project(&Point::<3>::new(0.3, 0.4, 1.5));
+        assert!(f64::abs(d - 0.5) < 1e-12);
+        assert!((p - Point::<3>::new(0.3, 0.4, 1.0)).norm() < 1e-12);
+
+        let (d, p) = tree.project(&Point::<3>::new(2.0, 2.0, 0.5));
+        assert!(f64::abs(d - f64::sqrt(2.0)) < 1e-12);
+        assert!((p - Point::<3>::new(1.0, 1.0, 0.5)).norm() < 1e-12);
+
+        let (d, p) = tree.project(&Point::<3>::new(0.5, 0.4, 0.3));
+        assert!(f64::abs(d - 0.3) < 1e-12);
+        assert!((p - Point::<3>::new(0.5, 0.4, 0.0)).norm() < 1e-12);
+    }
+}
@@ -0,0 +1,132 @@
+use crate::{
+    mesh::{Elem, GradientRecovery, Point, SimplexMesh},
+    Error, Result,
+};
+use log::debug;
+use nalgebra::SMatrix;
+
+impl<const D: usize, E: Elem> SimplexMesh<D, E> {
+    /// Gradient of the P1 field `f` in each element
+    fn elem_gradients(&self, f: &[f64]) -> Result<Vec<Point<D>>> {
+        self.elems()
+            .map(|e| {
+                let p0 = self.vert(e[0]);
+                let mat = SMatrix::<f64, D, D>::from_fn(|i, j| self.vert(e[i + 1])[j] - p0[j]);
+                let rhs = Point::<D>::from_fn(|i, _| f[e[i + 1] as usize] - f[e[0] as usize]);
+                mat.lu()
+                    .solve(&rhs)
+                    .ok_or_else(|| Error::from("degenerate element"))
+            })
+            .collect()
+    }
+
+    /// Zienkiewicz-Zhu error estimator for a scalar field `f` defined at the mesh vertices.
+    ///
+    /// The gradient $`G`$ is recovered at the vertices using `method`, and the error in element
+    /// $`K`$ is estimated as the difference with the (constant) gradient of the P1 field
+    /// ```math
+    /// \eta_K^2 = \int_K \|G - \nabla f_h\|^2 \approx \frac{|K|}{D+1} \sum_{i \in K} \|G_i - \nabla f_h|_K\|^2
+    /// ```
+    /// The vertex indicators are the volume weighted averages of the element indicators and the
+    /// global estimate is $`\sqrt{\sum_K \eta_K^2}`$.
+    ///
+    /// Returns the element and vertex indicators. The connectivities required by `method`, the
+    /// vertex-to-element connectivity and the volumes are required
+    pub fn estimate_error_zz(
+        &self,
+        f: &[f64],
+        method: GradientRecovery,
+    ) -> Result<(Vec<f64>, Vec<f64>)> {
+        debug!("Compute the Zienkiewicz-Zhu error estimate");
+
+        if E::N_VERTS as usize != D + 1 {
+            return Err(Error::from(
+                "the ZZ estimator is only available for volume meshes",
+            ));
+        }
+        if f.len() != self.n_verts() as usize {
+            return Err(Error::from("invalid field size"));
+        }
+
+        let grad = self.recover_gradient(f, method)?;
+        let elem_grad = self.elem_gradients(f)?;
+        let vols = self.get_elem_volumes()?;
+
+        let eta_elem = self
+            .elems()
+            .zip(elem_grad.iter())
+            .zip(vols.iter())
+            .map(|((e, g), &vol)| {
+                let err = e
+                    .iter()
+                    .map(|&i| {
+                        let start = D * i as usize;
+                        let gi = Point::<D>::from_column_slice(&grad[start..start + D]);
+                        (gi - g).norm_squared()
+                    })
+                    .sum::<f64>();
+                (vol * err / E::N_VERTS as f64).sqrt()
+            })
+            .collect::<Vec<_>>();
+
+        let eta_vert = self.elem_data_to_vertex_data(&eta_elem)?;
+
+        Ok((eta_elem, eta_vert))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        mesh::{
+            test_meshes::{test_mesh_2d, test_mesh_3d},
+            GradientRecovery, Point,
+        },
+        Result,
+    };
+
+    #[test]
+    fn test_zz_linear() -> Result<()> {
+        let mut mesh = test_mesh_3d().split().split();
+        mesh.compute_vertex_to_vertices();
+        mesh.compute_vertex_to_elems();
+        mesh.compute_volumes();
+
+        let f = mesh
+            .verts()
+            .map(|p| 1.0 + p[0] - 2.0 * p[1] + 3.0 * p[2])
+            .collect::<Vec<_>>();
+
+        for method in [GradientRecovery::default(), GradientRecovery::L2Projection] {
+            let (eta_elem, eta_vert) = mesh.estimate_error_zz(&f, method)?;
+            assert_eq!(eta_elem.len(), mesh.n_elems() as usize);
+            assert_eq!(eta_vert.len(), mesh.n_verts() as usize);
+            assert!(eta_elem.iter().all(|&x| x < 1e-8));
+            assert!(eta_vert.iter().all(|&x| x < 1e-8));
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_zz_convergence() -> Result<()> {
+        let fun = |p: Point<2>| f64::sin(3.0 * p[0]) * f64::cos(2.0 * p[1]);
+
+        let mut prev = f64::MAX;
+        let mut mesh = test_mesh_2d().split().split();
+        for _ in 0..3 {
+            mesh = mesh.split();
+            mesh.compute_vertex_to_vertices();
+            mesh.compute_vertex_to_elems();
+            mesh.compute_volumes();
+
+            let f = mesh.verts().map(fun).collect::<Vec<_>>();
+            let (eta_elem, _) = mesh.estimate_error_zz(&f, GradientRecovery::L2Projection)?;
+            let eta = eta_elem.iter().map(|x| x * x).sum::<f64>().sqrt();
+            assert!(eta < 0.75 * prev);
+            prev = eta;
+        }
+
+        Ok(())
+    }
+}
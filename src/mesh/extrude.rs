@@ -0,0 +1,258 @@
+use crate::{
+    mesh::{Elem, Point, SimplexMesh, Tetrahedron, Triangle},
+    min_max_iter, Error, Idx, Result,
+};
+use log::debug;
+use std::f64::consts::{PI, TAU};
+
+/// Split the prism between triangle `tri` and its copy in the next layer into 3 tetrahedra,
+/// `lower(i)` and `upper(i)` being the indices of vertex `i` in the two layers. The vertices are
+/// sorted so that the quadrilateral face built on edge `(i, j)`, `i < j`, is split along the
+/// diagonal from `i` in the lower layer to `j` in the upper layer, which makes the splitting of
+/// neighboring prisms conforming
+fn split_prism<F1, F2>(tri: &Triangle, lower: F1, upper: F2) -> [Tetrahedron; 3]
+where
+    F1: Fn(Idx) -> Idx,
+    F2: Fn(Idx) -> Idx,
+{
+    let mut v = [tri[0], tri[1], tri[2]];
+    v.sort_unstable();
+    let [a, b, c] = v.map(&lower);
+    let [a1, b1, c1] = v.map(&upper);
+    [
+        Tetrahedron::new(a, b, c, c1),
+        Tetrahedron::new(a, b, b1, c1),
+        Tetrahedron::new(a, a1, b1, c1),
+    ]
+}
+
+impl SimplexMesh<2, Triangle> {
+    /// Build the tetrahedral mesh swept by the mesh through `n_layers` layers, `id(i, layer)`
+    /// being the index of vertex `i` in layer `layer`. The elements and faces that are
+    /// degenerate, i.e. that contain vertices shared by several layers, are removed. If `caps`
+    /// is true, the faces of the first and last layers are added and tagged with the largest
+    /// face tag + 1 and + 2
+    fn sweep<F: Fn(Idx, Idx) -> Idx>(
+        &self,
+        verts: Vec<Point<3>>,
+        n_layers: Idx,
+        id: F,
+        caps: bool,
+    ) -> SimplexMesh<3, Tetrahedron> {
+        let mut elems = Vec::with_capacity(3 * (n_layers * self.n_elems()) as usize);
+        let mut etags = Vec::with_capacity(elems.capacity());
+        for layer in 0..n_layers {
+            let lower = |i| id(i, layer);
+            let upper = |i| id(i, layer + 1);
+            for (e, t) in self.elems().zip(self.etags()) {
+                for mut tet in split_prism(&e, lower, upper) {
+                    let [p0, p1, p2, p3] = [0, 1, 2, 3].map(|i| verts[tet[i] as usize]);
+                    if (p1 - p0).cross(&(p2 - p0)).dot(&(p3 - p0)) < 0.0 {
+                        tet = Tetrahedron::new(tet[1], tet[0], tet[2], tet[3]);
+                    }
+                    elems.push(tet);
+                    etags.push(t);
+                }
+            }
+        }
+
+        let mut faces = Vec::new();
+        let mut ftags = Vec::new();
+        for layer in 0..n_layers {
+            for (f, t) in self.faces().zip(self.ftags()) {
+                let (i, j) = (f[0].min(f[1]), f[0].max(f[1]));
+                let (i0, j0, i1, j1) = (
+                    id(i, layer),
+                    id(j, layer),
+                    id(i, layer + 1),
+                    id(j, layer + 1),
+                );
+                faces.push(Triangle::new(i0, j0, j1));
+                faces.push(Triangle::new(i0, j1, i1));
+                ftags.extend([t, t]);
+            }
+        }
+        if caps {
+            let bottom_tag = self.ftags().max().unwrap_or(0) + 1;
+            let top_tag = bottom_tag + 1;
+            for e in self.elems() {
+                let [i, j, k] = [e[0], e[1], e[2]];
+                let [i1, j1, k1] = [i, j, k].map(|i| id(i, n_layers));
+                faces.push(Triangle::new(id(i, 0), id(k, 0), id(j, 0)));
+                faces.push(Triangle::new(i1, j1, k1));
+                ftags.extend([bottom_tag, top_tag]);
+            }
+        }
+
+        let mut res = SimplexMesh::new(verts, elems, etags, faces, ftags);
+        res.remove_degenerate_elements();
+        res.fix_face_orientation();
+        *res.tag_names_mut() = self.tag_names().clone();
+
+        res
+    }
+
+    /// Extrude the mesh along z into layers of tetrahedra, `heights` being the z coordinates of
+    /// the layers (at least 2). The prisms are split into 3 tetrahedra (see `split_prism`) that
+    /// have the tag of the triangle they are built from. The lateral faces have the tag of the
+    /// boundary edge they are built from, and the bottom and top faces are tagged with the
+    /// largest face tag + 1 and + 2
+    pub fn extrude(&self, heights: &[f64]) -> Result<SimplexMesh<3, Tetrahedron>> {
+        if heights.len() < 2 {
+            return Err(Error::from("at least two layers are required"));
+        }
+        debug!("Extrude the mesh with {} layers", heights.len() - 1);
+
+        let n_verts = self.n_verts();
+        let verts = heights
+            .iter()
+            .flat_map(|&z| self.verts().map(move |p| Point::<3>::new(p[0], p[1], z)))
+            .collect::<Vec<_>>();
+
+        Ok(self.sweep(
+            verts,
+            heights.len() as Idx - 1,
+            |i, layer| i + layer * n_verts,
+            true,
+        ))
+    }
+
+    /// Revolve the mesh by `angle` (in radians) about coordinate axis `axis` (0 for x, 1 for y)
+    /// using `n_slices` slices of tetrahedra. Point `(x, y)` is mapped to
+    /// `(x, y cos(t), y sin(t))` for `axis = 0` and to `(x cos(t), y, x sin(t))` for `axis = 1`.
+    /// The vertices on the axis are not duplicated and the degenerate elements are removed.
+    /// The tags are set as in `extrude`, the first and last slices being tagged as the bottom
+    /// and top faces unless the revolution is complete (`angle = 2 pi`)
+    pub fn revolve(
+        &self,
+        axis: usize,
+        angle: f64,
+        n_slices: Idx,
+    ) -> Result<SimplexMesh<3, Tetrahedron>> {
+        debug!("Revolve the mesh by {angle} about axis {axis} with {n_slices} slices");
+
+        if axis > 1 {
+            return Err(Error::from("invalid axis"));
+        }
+        let radii = self.verts().map(|p| p[1 - axis]).collect::<Vec<_>>();
+        let (r_min, r_max) = min_max_iter(radii.iter().copied());
+        let tol = 1e-12 * r_max.abs().max(r_min.abs());
+        if r_min < -tol {
+            return Err(Error::from("the mesh must lie on one side of the axis"));
+        }
+        let full = (angle.abs() - TAU).abs() < 1e-12;
+        let n_min = if full { 3 } else { 1 };
+        if n_slices < n_min || angle.abs() / n_slices as f64 >= PI {
+            return Err(Error::from("not enough slices"));
+        }
+
+        // vertices on the axis first, then the other vertices slice by slice
+        let on_axis = radii.iter().map(|&r| r <= tol).collect::<Vec<_>>();
+        let n_axis = on_axis.iter().filter(|&&x| x).count() as Idx;
+        let n_off = self.n_verts() - n_axis;
+        let mut counts = [0, 0];
+        let local = on_axis
+            .iter()
+            .map(|&x| {
+                let k = usize::from(x);
+                counts[k] += 1;
+                counts[k] - 1
+            })
+            .collect::<Vec<Idx>>();
+        let n_layers = if full { n_slices } else { n_slices + 1 };
+        let id = |i: Idx, slice: Idx| {
+            if on_axis[i as usize] {
+                local[i as usize]
+            } else {
+                n_axis + (slice % n_layers) * n_off + local[i as usize]
+            }
+        };
+
+        let rotate = |p: Point<2>, t: f64| {
+            let (s, c) = t.sin_cos();
+            let r = p[1 - axis].max(0.0);
+            if axis == 0 {
+                Point::<3>::new(p[0], r * c, r * s)
+            } else {
+                Point::<3>::new(r * c, p[1], r * s)
+            }
+        };
+        let mut verts = self
+            .verts()
+            .zip(on_axis.iter())
+            .filter(|(_, &x)| x)
+            .map(|(p, _)| rotate(p, 0.0))
+            .collect::<Vec<_>>();
+        for slice in 0..n_layers {
+            let t = angle * slice as f64 / n_slices as f64;
+            verts.extend(
+                self.verts()
+                    .zip(on_axis.iter())
+                    .filter(|(_, &x)| !x)
+                    .map(|(p, _)| rotate(p, t)),
+            );
+        }
+
+        Ok(self.sweep(verts, n_slices, id, !full))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        mesh::{test_meshes::test_mesh_2d, GElem},
+        Result,
+    };
+    use std::f64::consts::{FRAC_PI_2, PI, TAU};
+
+    #[test]
+    fn test_extrude() -> Result<()> {
+        let mut mesh = test_mesh_2d();
+        mesh.add_boundary_faces();
+        let n_faces = mesh.n_faces();
+
+        let ext = mesh.extrude(&[0.0, 0.5, 1.0])?;
+        assert_eq!(ext.n_verts(), 12);
+        assert_eq!(ext.n_elems(), 12);
+        assert_eq!(ext.n_faces(), 4 * n_faces + 4);
+        assert!((ext.vol() - 1.0).abs() < 1e-12);
+        assert!(ext.gelems().all(|ge| ge.vol() > 0.0));
+        assert!(ext.validate().is_empty());
+
+        let n_bottom = ext.ftags().filter(|&t| t == 6).count();
+        let n_top = ext.ftags().filter(|&t| t == 7).count();
+        assert_eq!((n_bottom, n_top), (2, 2));
+
+        assert!(mesh.extrude(&[0.0]).is_err());
+        assert!(mesh.extrude(&[]).is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_revolve() -> Result<()> {
+        let mut mesh = test_mesh_2d();
+        mesh.add_boundary_faces();
+
+        let rev = mesh.revolve(0, TAU, 16)?;
+        assert_eq!(rev.n_verts(), 2 + 16 * 2);
+        assert!((rev.vol() - 8.0 * (PI / 8.0).sin()).abs() < 1e-10);
+        assert!(rev.gelems().all(|ge| ge.vol() > 0.0));
+        assert!(rev.validate().is_empty());
+        assert!(!rev.ftags().any(|t| t > 5));
+
+        let rev = mesh.revolve(1, FRAC_PI_2, 4)?;
+        assert_eq!(rev.n_verts(), 2 + 5 * 2);
+        assert!((rev.vol() - 2.0 * (PI / 8.0).sin()).abs() < 1e-10);
+        assert!(rev.validate().is_empty());
+        assert_eq!(rev.ftags().filter(|&t| t == 6).count(), 2);
+        assert_eq!(rev.ftags().filter(|&t| t == 7).count(), 2);
+
+        assert!(mesh.revolve(2, TAU, 16).is_err());
+        assert!(mesh.revolve(0, TAU, 2).is_err());
+        mesh.mut_verts().for_each(|p| p[1] -= 0.5);
+        assert!(mesh.revolve(0, TAU, 16).is_err());
+
+        Ok(())
+    }
+}
@@ -0,0 +1,92 @@
+use crate::{
+    mesh::{Edge, SimplexMesh, Triangle},
+    Idx, Result,
+};
+use log::debug;
+use rustc_hash::FxHashSet;
+
+impl SimplexMesh<3, Triangle> {
+    /// Detect the feature edges and corners of the surface: the triangles are tagged with
+    /// `autotag(angle_deg)`, i.e. the surface is split into patches of triangles with the same
+    /// tag connected through edges where the angle between the normals is lower than
+    /// `angle_deg`, and the topology is computed. The feature edges are the edges of `self`, the
+    /// boundary edges and the edges between patches, and the corners are the vertices of the
+    /// topology.
+    ///
+    /// These are the edges and corners that the remesher and `LinearGeometry` preserve when the
+    /// surface is tagged with `autotag` (or the boundary of a volume mesh with `autotag_bdy`).
+    ///
+    /// Returns the feature edges, as an edge mesh that shares the vertices of `self` and
+    /// where the edges between each pair of surface patches have their own tag, and the
+    /// (sorted) corner vertices
+    pub fn detect_features(&self, angle_deg: f64) -> Result<(SimplexMesh<3, Edge>, Vec<Idx>)> {
+        debug!("Detect the feature edges and corners with angle {angle_deg} deg");
+
+        let mut mesh = SimplexMesh::new(
+            self.verts().collect(),
+            self.elems().collect(),
+            self.etags().collect(),
+            self.faces().collect(),
+            self.ftags().collect(),
+        );
+        mesh.compute_face_to_elems();
+        mesh.autotag(angle_deg)?;
+        mesh.add_boundary_faces();
+        mesh.compute_topology();
+
+        let corners = mesh
+            .get_vertex_tags()?
+            .iter()
+            .enumerate()
+            .filter(|(_, t)| t.0 == 0)
+            .map(|(i, _)| i as Idx)
+            .collect::<Vec<_>>();
+
+        let res = SimplexMesh::new(
+            mesh.verts().collect(),
+            mesh.faces().collect(),
+            mesh.ftags().collect(),
+            Vec::new(),
+            Vec::new(),
+        );
+        debug!(
+            "{} feature edges with {} tags and {} corners found",
+            res.n_elems(),
+            res.etags().collect::<FxHashSet<_>>().len(),
+            corners.len()
+        );
+
+        Ok((res, corners))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{mesh::test_meshes::test_mesh_3d, Result};
+
+    #[test]
+    fn test_cube_features() -> Result<()> {
+        let (mut bdy, _) = test_mesh_3d().split().boundary();
+
+        let (edges, corners) = bdy.detect_features(30.0)?;
+        assert_eq!(edges.n_elems(), 24);
+        assert_eq!(edges.etags().max(), Some(12));
+        assert_eq!(corners.len(), 8);
+        for i in corners {
+            let p = bdy.vert(i);
+            assert!((0..3).all(|j| p[j].abs() < 1e-12 || (p[j] - 1.0).abs() < 1e-12));
+        }
+
+        // the cube edges are found from the angles only
+        bdy.mut_etags().for_each(|t| *t = 1);
+        let (edges, corners) = bdy.detect_features(30.0)?;
+        assert_eq!(edges.n_elems(), 24);
+        assert_eq!(corners.len(), 8);
+
+        let (edges, corners) = bdy.detect_features(100.0)?;
+        assert_eq!(edges.n_elems(), 0);
+        assert!(corners.is_empty());
+
+        Ok(())
+    }
+}
@@ -0,0 +1,647 @@
+use crate::{
+    mesh::{Elem, GElem, SimplexMesh},
+    spatialindex::PointIndex,
+    Error, Idx, Result,
+};
+use log::debug;
+use rustc_hash::FxHashMap;
+use std::{any::Any, collections::BTreeMap, fmt::Debug};
+
+/// Scalar types that can be stored in `Fields`
+pub trait FieldValue: Copy + Default + Debug + Send + Sync + 'static {
+    /// Whether the values can be interpolated (i.e. the default `FieldInterpolation` is
+    /// `Linear` / `Conservative` rather than `Nearest`)
+    const INTERPOLATE: bool;
+    /// Value at the center of an edge, used for the vertices created by `split`
+    fn midpoint(a: Self, b: Self) -> Self;
+    /// Conversion to f64 (for I/O)
+    fn to_f64(self) -> f64;
+    /// Conversion from f64 (after interpolation)
+    fn from_f64(x: f64) -> Self;
+}
+
+impl FieldValue for f64 {
+    const INTERPOLATE: bool = true;
+    fn midpoint(a: Self, b: Self) -> Self {
+        0.5 * (a + b)
+    }
+    fn to_f64(self) -> f64 {
+        self
+    }
+    fn from_f64(x: f64) -> Self {
+        x
+    }
+}
+
+impl FieldValue for f32 {
+    const INTERPOLATE: bool = true;
+    fn midpoint(a: Self, b: Self) -> Self {
+        0.5 * (a + b)
+    }
+    fn to_f64(self) -> f64 {
+        f64::from(self)
+    }
+    fn from_f64(x: f64) -> Self {
+        x as Self
+    }
+}
+
+/// Integer values are not interpolated: the smallest value is used at the edge centers
+impl FieldValue for i32 {
+    const INTERPOLATE: bool = false;
+    fn midpoint(a: Self, b: Self) -> Self {
+        a.min(b)
+    }
+    fn to_f64(self) -> f64 {
+        f64::from(self)
+    }
+    fn from_f64(x: f64) -> Self {
+        x.round() as Self
+    }
+}
+
+/// Integer values are not interpolated: the smallest value is used at the edge centers
+impl FieldValue for i64 {
+    const INTERPOLATE: bool = false;
+    fn midpoint(a: Self, b: Self) -> Self {
+        a.min(b)
+    }
+    fn to_f64(self) -> f64 {
+        self as f64
+    }
+    fn from_f64(x: f64) -> Self {
+        x.round() as Self
+    }
+}
+
+/// How a field is transferred to a new mesh by `SimplexMesh::remap_fields_from` (e.g. after
+/// remeshing)
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum FieldInterpolation {
+    /// Linear interpolation in the elements of the source mesh (vertex fields only)
+    Linear,
+    /// Value at the nearest vertex / at the element nearest to the element center
+    Nearest,
+    /// Conservative transfer using the element intersections (element fields only, see
+    /// `SimplexMesh::transfer_conservative`)
+    Conservative,
+}
+
+/// Type-erased field
+trait AnyField: Debug + Send + Sync {
+    fn as_any(&self) -> &dyn Any;
+    fn as_any_mut(&mut self) -> &mut dyn Any;
+    fn box_clone(&self) -> Box<dyn AnyField>;
+    fn n_comp(&self) -> usize;
+    fn len(&self) -> usize;
+    fn select(&self, ids: &[Idx]) -> Box<dyn AnyField>;
+    fn append(&self, other: Option<&dyn AnyField>, ids: &[Idx]) -> Box<dyn AnyField>;
+    fn split(&self, edges: &FxHashMap<[Idx; 2], Idx>) -> Box<dyn AnyField>;
+    fn to_f64(&self) -> Vec<f64>;
+    fn with_f64(&self, data: &[f64]) -> Box<dyn AnyField>;
+    fn interpolation(&self, is_elem: bool) -> FieldInterpolation;
+    fn set_interpolation(&mut self, interpolation: FieldInterpolation);
+    fn memory_usage(&self) -> usize;
+}
+
+/// Field with `n_comp` values of type `T` per vertex / element. If `interpolation` is None,
+/// the default policy for `T` is used
+#[derive(Clone, Debug)]
+struct TypedField<T: FieldValue> {
+    n_comp: usize,
+    data: Vec<T>,
+    interpolation: Option<FieldInterpolation>,
+}
+
+impl<T: FieldValue> AnyField for TypedField<T> {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+
+    fn box_clone(&self) -> Box<dyn AnyField> {
+        Box::new(self.clone())
+    }
+
+    fn n_comp(&self) -> usize {
+        self.n_comp
+    }
+
+    fn len(&self) -> usize {
+        self.data.len() / self.n_comp
+    }
+
+    fn select(&self, ids: &[Idx]) -> Box<dyn AnyField> {
+        let n = self.n_comp;
+        let data = ids
+            .iter()
+            .flat_map(|&i| {
+                self.data[n * i as usize..n * (i as usize + 1)]
+                    .iter()
+                    .copied()
+            })
+            .collect();
+        Box::new(Self { data, ..*self })
+    }
+
+    fn append(&self, other: Option<&dyn AnyField>, ids: &[Idx]) -> Box<dyn AnyField> {
+        let n = self.n_comp;
+        let mut data = self.data.clone();
+        match other
+            .and_then(|f| f.as_any().downcast_ref::<Self>())
+            .filter(|f| f.n_comp == n)
+        {
+            Some(other) => data.extend(ids.iter().flat_map(|&i| {
+                other.data[n * i as usize..n * (i as usize + 1)]
+                    .iter()
+                    .copied()
+            })),
+            None => data.resize(self.data.len() + n * ids.len(), T::default()),
+        }
+        Box::new(Self { data, ..*self })
+    }
+
+    fn split(&self, edges: &FxHashMap<[Idx; 2], Idx>) -> Box<dyn AnyField> {
+        let n = self.n_comp;
+        let mut data = self.data.clone();
+        data.resize(n * (self.len() + edges.len()), T::default());
+        for (edg, &i) in edges {
+            let (i0, i1, i) = (n * edg[0] as usize, n * edg[1] as usize, n * i as usize);
+            for j in 0..n {
+                data[i + j] = T::midpoint(data[i0 + j], data[i1 + j]);
+            }
+        }
+        Box::new(Self { data, ..*self })
+    }
+
+    fn to_f64(&self) -> Vec<f64> {
+        self.data.iter().map(|x| x.to_f64()).collect()
+    }
+
+    fn with_f64(&self, data: &[f64]) -> Box<dyn AnyField> {
+        let data = data.iter().map(|&x| T::from_f64(x)).collect();
+        Box::new(Self { data, ..*self })
+    }
+
+    fn interpolation(&self, is_elem: bool) -> FieldInterpolation {
+        self.interpolation
+            .unwrap_or(match (T::INTERPOLATE, is_elem) {
+                (false, _) => FieldInterpolation::Nearest,
+                (true, false) => FieldInterpolation::Linear,
+                (true, true) => FieldInterpolation::Conservative,
+            })
+    }
+
+    fn set_interpolation(&mut self, interpolation: FieldInterpolation) {
+        self.interpolation = Some(interpolation);
+    }
+
+    fn memory_usage(&self) -> usize {
+        self.data.capacity() * std::mem::size_of::<T>()
+    }
+}
+
+/// Named fields attached to the vertices or elements of a mesh, with `n_comp` values per
+/// vertex / element stored as `[v_0(e_0), ..., v_{n_comp-1}(e_0), v_0(e_1), ...]`
+#[derive(Debug, Default)]
+pub struct Fields {
+    fields: BTreeMap<String, Box<dyn AnyField>>,
+}
+
+impl Clone for Fields {
+    fn clone(&self) -> Self {
+        Self {
+            fields: self
+                .fields
+                .iter()
+                .map(|(k, v)| (k.clone(), v.box_clone()))
+                .collect(),
+        }
+    }
+}
+
+impl Fields {
+    /// Add (or replace) a field with `N` components
+    pub fn insert<T: FieldValue, const N: usize>(
+        &mut self,
+        name: &str,
+        data: Vec<T>,
+    ) -> Result<()> {
+        self.insert_with_comp(name, N, data)
+    }
+
+    /// Add (or replace) a field with `n_comp` components (e.g. read from a file)
+    pub(crate) fn insert_with_comp<T: FieldValue>(
+        &mut self,
+        name: &str,
+        n_comp: usize,
+        data: Vec<T>,
+    ) -> Result<()> {
+        if n_comp == 0 || data.len() % n_comp != 0 {
+            return Err(Error::from(&format!("Invalid size for field {name}")));
+        }
+        self.fields.insert(
+            name.to_string(),
+            Box::new(TypedField {
+                n_comp,
+                data,
+                interpolation: None,
+            }),
+        );
+        Ok(())
+    }
+
+    fn typed<T: FieldValue>(&self, name: &str, n_comp: usize) -> Result<&TypedField<T>> {
+        let f = self
+            .fields
+            .get(name)
+            .ok_or_else(|| Error::from(&format!("Field {name} not found")))?;
+        let f = f
+            .as_any()
+            .downcast_ref::<TypedField<T>>()
+            .ok_or_else(|| Error::from(&format!("Invalid type for field {name}")))?;
+        if f.n_comp != n_comp {
+            return Err(Error::from(&format!(
+                "Field {name} has {} components",
+                f.n_comp
+            )));
+        }
+        Ok(f)
+    }
+
+    /// Get a field with `N` components
+    pub fn get<T: FieldValue, const N: usize>(&self, name: &str) -> Result<&[T]> {
+        Ok(&self.typed::<T>(name, N)?.data)
+    }
+
+    /// Get a field with `N` components (mutable)
+    pub fn get_mut<T: FieldValue, const N: usize>(&mut self, name: &str) -> Result<&mut [T]> {
+        self.typed::<T>(name, N)?;
+        let f = self.fields.get_mut(name).unwrap();
+        Ok(&mut f.as_any_mut().downcast_mut::<TypedField<T>>().unwrap().data)
+    }
+
+    /// Set the policy used to remap a field onto a new mesh. By default, floating point vertex
+    /// (resp. element) fields use `Linear` (resp. `Conservative`) and integer fields use
+    /// `Nearest`
+    pub fn set_interpolation(
+        &mut self,
+        name: &str,
+        interpolation: FieldInterpolation,
+    ) -> Result<()> {
+        self.fields
+            .get_mut(name)
+            .ok_or_else(|| Error::from(&format!("Field {name} not found")))?
+            .set_interpolation(interpolation);
+        Ok(())
+    }
+
+    /// Remove a field, returning true if it existed
+    pub fn remove(&mut self, name: &str) -> bool {
+        self.fields.remove(name).is_some()
+    }
+
+    /// Remove all the fields
+    pub fn clear(&mut self) {
+        self.fields.clear();
+    }
+
+    /// Get the memory used by the field values, in bytes
+    #[must_use]
+    pub fn memory_usage(&self) -> usize {
+        self.fields.values().map(|f| f.memory_usage()).sum()
+    }
+
+    /// Get the # of fields
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.fields.len()
+    }
+
+    /// Check if there are no fields
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.fields.is_empty()
+    }
+
+    /// Get the field names (sorted) and their number of components
+    pub fn names(&self) -> impl Iterator<Item = (&str, usize)> + '_ {
+        self.fields.iter().map(|(k, v)| (k.as_str(), v.n_comp()))
+    }
+
+    /// Restriction of the fields to a subset of vertices / elements: the i-th value of the new
+    /// fields is the `ids[i]`-th value of `self`
+    #[must_use]
+    pub fn select(&self, ids: &[Idx]) -> Self {
+        Self {
+            fields: self
+                .fields
+                .iter()
+                .map(|(k, v)| (k.clone(), v.select(ids)))
+                .collect(),
+        }
+    }
+
+    /// Append the values of `other` at `ids` to each field (e.g. for the vertices / elements
+    /// added from another mesh). Default values are used if `other` has no field with the same
+    /// name, type and number of components, and the fields of `other` that are not in `self`
+    /// are ignored
+    #[must_use]
+    pub fn append(&self, other: &Self, ids: &[Idx]) -> Self {
+        Self {
+            fields: self
+                .fields
+                .iter()
+                .map(|(k, v)| {
+                    (
+                        k.clone(),
+                        v.append(other.fields.get(k).map(AsRef::as_ref), ids),
+                    )
+                })
+                .collect(),
+        }
+    }
+
+    /// Add the values at the vertices created at the edge centers (see
+    /// `SimplexMesh::split_vertex_indices`) to vertex fields
+    #[must_use]
+    pub fn split(&self, edges: &FxHashMap<[Idx; 2], Idx>) -> Self {
+        Self {
+            fields: self
+                .fields
+                .iter()
+                .map(|(k, v)| (k.clone(), v.split(edges)))
+                .collect(),
+        }
+    }
+
+    /// Get all the fields converted to f64
+    #[must_use]
+    pub fn to_f64(&self) -> Vec<(String, Vec<f64>)> {
+        self.fields
+            .iter()
+            .map(|(k, v)| (k.clone(), v.to_f64()))
+            .collect()
+    }
+
+    /// Get all the fields converted to f64, with their number of components
+    pub(crate) fn to_f64_with_comp(&self) -> Vec<(String, usize, Vec<f64>)> {
+        self.fields
+            .iter()
+            .map(|(k, v)| (k.clone(), v.n_comp(), v.to_f64()))
+            .collect()
+    }
+}
+
+impl<const D: usize, E: Elem> SimplexMesh<D, E> {
+    /// Attach a field with `N` values of type `T` per vertex to the mesh. The vertex fields
+    /// are carried through `split`, `extract`, the reorderings, remapped by the remesher (see
+    /// `remap_fields_from`), and written by `write_vtu`, `write_meshb` and `write_gmsh`
+    pub fn add_vertex_field<T: FieldValue, const N: usize>(
+        &mut self,
+        name: &str,
+        data: Vec<T>,
+    ) -> Result<()> {
+        if data.len() != N * self.n_verts() as usize {
+            return Err(Error::from(&format!("Invalid size for field {name}")));
+        }
+        self.vertex_fields_mut().insert::<T, N>(name, data)
+    }
+
+    /// Get a vertex field with `N` values of type `T` per vertex
+    pub fn get_vertex_field<T: FieldValue, const N: usize>(&self, name: &str) -> Result<&[T]> {
+        let data = self.vertex_fields().get::<T, N>(name)?;
+        if data.len() != N * self.n_verts() as usize {
+            return Err(Error::from(&format!(
+                "Field {name} does not match the mesh vertices"
+            )));
+        }
+        Ok(data)
+    }
+
+    /// Attach a field with `N` values of type `T` per element to the mesh. The element fields
+    /// are carried through `split`, `extract`, the reorderings, remapped by the remesher (see
+    /// `remap_fields_from`), and written by `write_vtu`, `write_meshb` and `write_gmsh`
+    pub fn add_elem_field<T: FieldValue, const N: usize>(
+        &mut self,
+        name: &str,
+        data: Vec<T>,
+    ) -> Result<()> {
+        if data.len() != N * self.n_elems() as usize {
+            return Err(Error::from(&format!("Invalid size for field {name}")));
+        }
+        self.elem_fields_mut().insert::<T, N>(name, data)
+    }
+
+    /// Get an element field with `N` values of type `T` per element
+    pub fn get_elem_field<T: FieldValue, const N: usize>(&self, name: &str) -> Result<&[T]> {
+        let data = self.elem_fields().get::<T, N>(name)?;
+        if data.len() != N * self.n_elems() as usize {
+            return Err(Error::from(&format!(
+                "Field {name} does not match the mesh elements"
+            )));
+        }
+        Ok(data)
+    }
+
+    /// Replace the fields of `self` by the fields of `other` (e.g. the mesh before remeshing)
+    /// remapped onto `self`, using the `FieldInterpolation` of each field
+    pub fn remap_fields_from(&mut self, other: &Self) -> Result<()> {
+        debug!("Remap the fields from another mesh");
+
+        let mut vert_fields = Fields::default();
+        let mut vert_tree = None;
+        for (name, f) in &other.vertex_fields().fields {
+            if f.len() != other.n_verts() as usize {
+                return Err(Error::from(&format!(
+                    "Field {name} does not match the mesh vertices"
+                )));
+            }
+            let data = f.to_f64();
+            let data = match f.interpolation(false) {
+                FieldInterpolation::Linear => self.interpolate_from(other, &data)?,
+                FieldInterpolation::Nearest => {
+                    let tree = vert_tree.get_or_insert_with(|| other.compute_vert_tree());
+                    let n = f.n_comp();
+                    self.verts()
+                        .flat_map(|p| {
+                            let (i, _) = tree.nearest_vert(&p);
+                            data[n * i as usize..n * (i as usize + 1)].to_vec()
+                        })
+                        .collect()
+                }
+                FieldInterpolation::Conservative => {
+                    return Err(Error::from(&format!(
+                        "Conservative interpolation not available for vertex field {name}"
+                    )));
+                }
+            };
+            vert_fields.fields.insert(name.clone(), f.with_f64(&data));
+        }
+
+        let mut elem_fields = Fields::default();
+        let mut elem_tree = None;
+        let mut src = None;
+        for (name, f) in &other.elem_fields().fields {
+            if f.len() != other.n_elems() as usize {
+                return Err(Error::from(&format!(
+                    "Field {name} does not match the mesh elements"
+                )));
+            }
+            let data = f.to_f64();
+            let data = match f.interpolation(true) {
+                FieldInterpolation::Conservative => {
+                    let src = src.get_or_insert_with(|| {
+                        let mut src = SimplexMesh::new(
+                            other.verts().collect(),
+                            other.elems().collect(),
+                            other.etags().collect(),
+                            Vec::new(),
+                            Vec::new(),
+                        );
+                        src.compute_elem_to_elems();
+                        src
+                    });
+                    self.transfer_conservative(src, &data)?
+                }
+                FieldInterpolation::Nearest => {
+                    let tree = elem_tree.get_or_insert_with(|| other.compute_element_tree());
+                    let n = f.n_comp();
+                    self.gelems()
+                        .flat_map(|ge| {
+                            let i = tree.nearest_elem(&ge.center()) as usize;
+                            data[n * i..n * (i + 1)].to_vec()
+                        })
+                        .collect()
+                }
+                FieldInterpolation::Linear => {
+                    return Err(Error::from(&format!(
+                        "Linear interpolation not available for element field {name}"
+                    )));
+                }
+            };
+            elem_fields.fields.insert(name.clone(), f.with_f64(&data));
+        }
+
+        *self.vertex_fields_mut() = vert_fields;
+        *self.elem_fields_mut() = elem_fields;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        mesh::{test_meshes::test_mesh_2d, FieldInterpolation, Point},
+        Idx, Result,
+    };
+
+    #[test]
+    fn test_fields() -> Result<()> {
+        let mut mesh = test_mesh_2d();
+
+        let f = |p: Point<2>| [p[0] + 2.0 * p[1], p[0]];
+        let v = mesh.verts().flat_map(f).collect::<Vec<_>>();
+        mesh.add_vertex_field::<f64, 2>("linear", v.clone())?;
+        mesh.add_vertex_field::<i32, 1>("flag", vec![1, 2, 3, 4])?;
+        mesh.add_elem_field::<f64, 1>("tag", vec![1.0, 2.0])?;
+        assert!(mesh.add_elem_field::<f64, 1>("tag", vec![1.0]).is_err());
+
+        assert_eq!(mesh.get_vertex_field::<f64, 2>("linear")?, &v[..]);
+        assert!(mesh.get_vertex_field::<f64, 1>("linear").is_err());
+        assert!(mesh.get_vertex_field::<i32, 2>("linear").is_err());
+        assert!(mesh.get_vertex_field::<f64, 2>("other").is_err());
+        let names = mesh.vertex_fields().names().collect::<Vec<_>>();
+        assert_eq!(names, vec![("flag", 1), ("linear", 2)]);
+
+        // split
+        let split = mesh.split();
+        let v = split.get_vertex_field::<f64, 2>("linear")?;
+        for (p, x) in split.verts().zip(v.chunks(2)) {
+            let [a, b] = f(p);
+            assert!(f64::abs(x[0] - a) < 1e-12);
+            assert!(f64::abs(x[1] - b) < 1e-12);
+        }
+        let flag = split.get_vertex_field::<i32, 1>("flag")?;
+        assert_eq!(flag.len(), split.n_verts() as usize);
+        assert!(flag.iter().all(|&x| (1..=4).contains(&x)));
+        let tag = split.get_elem_field::<f64, 1>("tag")?;
+        for (t, &x) in split.etags().zip(tag) {
+            assert!(f64::abs(t as f64 - x) < 1e-12);
+        }
+
+        // extract
+        let sub = split.extract_tag(2);
+        let tag = sub.mesh.get_elem_field::<f64, 1>("tag")?;
+        assert_eq!(tag.len(), sub.mesh.n_elems() as usize);
+        assert!(tag.iter().all(|&x| f64::abs(x - 2.0) < 1e-12));
+        let flag = sub.mesh.get_vertex_field::<i32, 1>("flag")?;
+        for (&i, &x) in sub.parent_vert_ids.iter().zip(flag) {
+            assert_eq!(split.get_vertex_field::<i32, 1>("flag")?[i as usize], x);
+        }
+
+        // the sizes are checked
+        let mut other = mesh.clone();
+        other.add_verts([[0.0, 0.0]].iter().map(|x| &x[..]));
+        assert!(other.get_vertex_field::<f64, 2>("linear").is_err());
+
+        mesh.vertex_fields_mut()
+            .get_mut::<i32, 1>("flag")?
+            .copy_from_slice(&[0, 0, 0, 0]);
+        assert_eq!(mesh.get_vertex_field::<i32, 1>("flag")?, &[0, 0, 0, 0]);
+        assert!(mesh.vertex_fields_mut().remove("flag"));
+        assert!(mesh.get_vertex_field::<i32, 1>("flag").is_err());
+
+        Ok(())
+    }
+    #[test]
+    fn test_remap_fields() -> Result<()> {
+        let mut mesh = test_mesh_2d().split().split();
+        let f = |p: Point<2>| p[0] + 2.0 * p[1];
+        mesh.add_vertex_field::<f64, 1>("linear", mesh.verts().map(f).collect())?;
+        let flag = (0..mesh.n_verts() as i32).collect();
+        mesh.add_vertex_field::<i32, 1>("flag", flag)?;
+        let tags = mesh.etags().map(|t| t as f64).collect();
+        mesh.add_elem_field::<f64, 1>("tag", tags)?;
+        let tags = mesh.etags().map(|t| t as i64).collect();
+        mesh.add_elem_field::<i64, 1>("itag", tags)?;
+
+        let mut other = test_mesh_2d().split().split().split();
+        other.remap_fields_from(&mesh)?;
+
+        let v = other.get_vertex_field::<f64, 1>("linear")?;
+        for (p, &x) in other.verts().zip(v) {
+            assert!(f64::abs(x - f(p)) < 1e-12);
+        }
+        let flag = other.get_vertex_field::<i32, 1>("flag")?;
+        for (p, &i) in other.verts().zip(flag) {
+            assert!((mesh.vert(i as Idx) - p).norm() < 0.25);
+        }
+        let tag = other.get_elem_field::<f64, 1>("tag")?;
+        let itag = other.get_elem_field::<i64, 1>("itag")?;
+        for ((t, &x), &i) in other.etags().zip(tag).zip(itag) {
+            assert!(f64::abs(x - t as f64) < 1e-12);
+            assert_eq!(i, t as i64);
+        }
+
+        // invalid policies
+        mesh.elem_fields_mut()
+            .set_interpolation("tag", FieldInterpolation::Linear)?;
+        assert!(other.remap_fields_from(&mesh).is_err());
+        mesh.elem_fields_mut()
+            .set_interpolation("tag", FieldInterpolation::Nearest)?;
+        mesh.vertex_fields_mut()
+            .set_interpolation("linear", FieldInterpolation::Conservative)?;
+        assert!(other.remap_fields_from(&mesh).is_err());
+        assert!(mesh
+            .vertex_fields_mut()
+            .set_interpolation("other", FieldInterpolation::Linear)
+            .is_err());
+
+        Ok(())
+    }
+}
@@ -8,30 +8,47 @@ use std::fmt::Debug;
 
 pub trait AsSliceF64 {
     fn as_slice_f64(&self) -> &[f64];
+    fn as_mut_slice_f64(&mut self) -> &mut [f64];
 }
 
 impl AsSliceF64 for Vector4<f64> {
     fn as_slice_f64(&self) -> &[f64] {
         self.as_slice()
     }
+
+    fn as_mut_slice_f64(&mut self) -> &mut [f64] {
+        self.as_mut_slice()
+    }
 }
 
 impl AsSliceF64 for Vector3<f64> {
     fn as_slice_f64(&self) -> &[f64] {
         self.as_slice()
     }
+
+    fn as_mut_slice_f64(&mut self) -> &mut [f64] {
+        self.as_mut_slice()
+    }
 }
 
 impl AsSliceF64 for Vector2<f64> {
     fn as_slice_f64(&self) -> &[f64] {
         self.as_slice()
     }
+
+    fn as_mut_slice_f64(&mut self) -> &mut [f64] {
+        self.as_mut_slice()
+    }
 }
 
 impl AsSliceF64 for Vector1<f64> {
     fn as_slice_f64(&self) -> &[f64] {
         self.as_slice()
     }
+
+    fn as_mut_slice_f64(&mut self) -> &mut [f64] {
+        self.as_mut_slice()
+    }
 }
 
 const SQRT_3: f64 = 1.732_050_807_568_877_2;
@@ -40,13 +57,14 @@ const SQRT_6: f64 = std::f64::consts::SQRT_2 * SQRT_3;
 /// Geometric element defined by the coordinate of its vertices as well as metric information
 pub trait GElem<const D: usize, M: Metric<D>>: Clone + Copy + Debug + Send {
     type Face: GElem<D, M>;
-    type BCoords: AsSliceF64 + Debug;
+    type BCoords: AsSliceF64 + Debug + Send;
     const IDEAL_VOL: f64;
 
     /// Create a `GElem` from its vertices and the metric at each vertex
     fn from_verts<I: Iterator<Item = (Point<D>, M)>>(points_n_metrics: I) -> Self;
 
-    /// Create a `GElem` from a vertex and its opposite face
+    /// Create a `GElem` from a vertex and its opposite face. For second order elements, the new
+    /// edges are straight, i.e. their mid-edge nodes are the edge centers
     fn from_vert_and_face(point: &Point<D>, metric: &M, face: &Self::Face) -> Self;
 
     /// Get the element's volume
@@ -352,66 +370,78 @@ impl<const D: usize, M: Metric<D>> GElem<D, M> for GTetrahedron<D, M> {
     }
 }
 
-/// GTetrahedron quadratique 
+/// Evaluate the second order Lagrange interpolation of `points` at barycentric coordinates `b`,
+/// the first `b.len()` points being the vertices and `mids` giving the index of the mid-edge
+/// nodes and of the corresponding edge vertices
+/// Center of the edge (`i`, `j`) and metric interpolated at the center, used for the straight
+/// edges of second order elements
+fn mid_node<const D: usize, M: Metric<D>>(
+    points: &[Point<D>],
+    metrics: &[M],
+    i: usize,
+    j: usize,
+) -> (Point<D>, M) {
+    (
+        0.5 * (points[i] + points[j]),
+        M::interpolate([(0.5, &metrics[i]), (0.5, &metrics[j])].into_iter()),
+    )
+}
+
+fn lagrange_p2<const D: usize>(
+    points: &[Point<D>],
+    b: &[f64],
+    mids: &[(usize, usize, usize)],
+) -> Point<D> {
+    let mut res = Point::<D>::zeros();
+    for (i, &bi) in b.iter().enumerate() {
+        res += bi * (2.0 * bi - 1.0) * points[i];
+    }
+    for &(k, i, j) in mids {
+        res += 4.0 * b[i] * b[j] * points[k];
+    }
+    res
+}
 
+/// Second order tetrahedron, with the mid-edge nodes ordered as in `TetrahedronQuadratique`.
+/// The volume, quality, barycentric coordinates and gamma are those of the straight-sided
+/// tetrahedron defined by the 4 vertices
 #[derive(Clone, Copy, Debug)]
 pub struct GTetrahedronQuadratique<const D: usize, M: Metric<D>> {
     points: [Point<D>; 10],
     metrics: [M; 10],
 }
 
-impl<const D: usize, M: Metric<D>> GTetrahedron<D, M> {
-    const DIM: f64 = 3.0;
-    // Jacobian of the reference to equilateral transformation
-    const J_EQ: Matrix3<f64> = Matrix3::new(
-        1.0,
-        -1. / SQRT_3,
-        -1. / SQRT_6,
-        0.,
-        2. / SQRT_3,
-        -1.0 / SQRT_6,
-        0.,
-        0.,
-        3. / SQRT_6,
-    );
-
-    /// Get the jacobian of the transformation from the reference to the current element
-    pub fn jacobian(&self) -> Matrix3<f64> {
-        Matrix3::<f64>::new(
-            self.points[1][0] - self.points[0][0],
-            self.points[2][0] - self.points[0][0],
-            self.points[3][0] - self.points[0][0],
-            self.points[1][1] - self.points[0][1],
-            self.points[2][1] - self.points[0][1],
-            self.points[3][1] - self.points[0][1],
-            self.points[1][2] - self.points[0][2],
-            self.points[2][2] - self.points[0][2],
-            self.points[3][2] - self.points[0][2],
-        )
-    }
-
-    /// Compute the implied metric
-    /// It can be computed using the Jacobian $`J`$ of the transformation from the
-    /// reference unit-length element to the physical element as $`(J J^T)^{-1}`$ .
-    /// $`J`$ can be decomposed as the product of
-    ///  - the Jacobian $`J_0`$ of the transformation from the reference unit-length
-    ///    element to the orthogonal element, stored as `Self::J_EQ`
-    ///  - the Jacobian $`J_1`$ of the transformation from the orthogonal element to
-    ///    the physical element
-    ///
-    /// (reference: Ph.D. P. Caplan, p. 35)
-    pub fn implied_metric(&self) -> AnisoMetric3d {
-        let j = self.jacobian() * Self::J_EQ;
-        let m = j * j.transpose();
-        let m = m.try_inverse().unwrap();
-        AnisoMetric3d::from_mat(m)
+impl<const D: usize, M: Metric<D>> GTetrahedronQuadratique<D, M> {
+    // (mid-edge node, vertex, vertex)
+    const MIDS: [(usize, usize, usize); 6] = [
+        (4, 2, 3),
+        (5, 1, 3),
+        (6, 1, 2),
+        (7, 0, 2),
+        (8, 0, 3),
+        (9, 0, 1),
+    ];
+    const FACES: [[usize; 6]; 4] = [
+        [1, 2, 3, 4, 5, 6],
+        [2, 0, 3, 8, 4, 7],
+        [0, 1, 3, 5, 8, 9],
+        [0, 2, 1, 6, 9, 7],
+    ];
+
+    /// Get the straight-sided tetrahedron defined by the vertices
+    #[must_use]
+    pub fn linear(&self) -> GTetrahedron<D, M> {
+        GTetrahedron {
+            points: std::array::from_fn(|i| self.points[i]),
+            metrics: std::array::from_fn(|i| self.metrics[i]),
+        }
     }
 }
 
 impl<const D: usize, M: Metric<D>> GElem<D, M> for GTetrahedronQuadratique<D, M> {
     type Face = GTriangleQuadratique<D, M>;
     type BCoords = Vector4<f64>;
-    const IDEAL_VOL: f64 = 1.0 / (6.0 * std::f64::consts::SQRT_2);
+    const IDEAL_VOL: f64 = GTetrahedron::<D, M>::IDEAL_VOL;
 
     fn vert(&self, i: Idx) -> Point<D> {
         self.points[i as usize]
@@ -419,7 +449,7 @@ impl<const D: usize, M: Metric<D>> GElem<D, M> for GTetrahedronQuadratique<D, M>
 
     fn from_verts<I: Iterator<Item = (Point<D>, M)>>(mut points_n_metrics: I) -> Self {
         assert_eq!(D, 3);
-        let p: [_; 4] = std::array::from_fn(|_| points_n_metrics.next().unwrap());
+        let p: [_; 10] = std::array::from_fn(|_| points_n_metrics.next().unwrap());
         assert!(points_n_metrics.next().is_none());
         Self {
             points: p.map(|x| x.0),
@@ -428,103 +458,46 @@ impl<const D: usize, M: Metric<D>> GElem<D, M> for GTetrahedronQuadratique<D, M>
     }
 
     fn from_vert_and_face(point: &Point<D>, metric: &M, face: &Self::Face) -> Self {
-        Self {
-            points: [*point, face.points[0], face.points[1], face.points[2]],
-            metrics: [*metric, face.metrics[0], face.metrics[1], face.metrics[2]],
+        let mut points = [*point; 10];
+        let mut metrics = [*metric; 10];
+        // vertices and mid-edge nodes of the face
+        for (i, j) in [(1, 0), (2, 1), (3, 2), (4, 3), (5, 4), (6, 5)] {
+            points[i] = face.points[j];
+            metrics[i] = face.metrics[j];
+        }
+        for (k, i, j) in Self::MIDS {
+            if i == 0 {
+                (points[k], metrics[k]) = mid_node(&points, &metrics, i, j);
+            }
         }
+        Self { points, metrics }
     }
 
     fn vol(&self) -> f64 {
-        let e1 = self.points[1] - self.points[0];
-        let e2 = self.points[2] - self.points[0];
-        let e3 = self.points[3] - self.points[0];
-        let n = e1.cross(&e2);
-        (1. / 6.) * n.dot(&e3)
+        self.linear().vol()
     }
 
     fn center(&self) -> Point<D> {
-        (self.points[0] + self.points[1] + self.points[2] + self.points[3] + self.points[4] 
-            + self.points[5] + self.points[6] + self.points[7] + self.points[8] + self.points[9]) / 10.0
+        self.point(&[0.25, 0.25, 0.25, 0.25])
     }
 
     fn quality(&self) -> f64 {
-        let m = M::min_metric(self.metrics.iter());
-
-        let mut e1 = self.points[1] - self.points[0];
-        let e2 = self.points[2] - self.points[0];
-        let e3 = self.points[3] - self.points[0];
-        let n = e1.cross(&e2);
-        let vol = (1. / 6.) * n.dot(&e3);
-        if vol < 0.0 {
-            return -1.0;
-        }
-
-        let mut l = f64::powi(m.length(&e1), 2);
-        l += f64::powi(m.length(&e2), 2);
-        l += f64::powi(m.length(&e3), 2);
-        e1 = self.points[1] - self.points[2];
-        l += f64::powi(m.length(&e1), 2);
-        e1 = self.points[2] - self.points[3];
-        l += f64::powi(m.length(&e1), 2);
-        e1 = self.points[3] - self.points[1];
-        l += f64::powi(m.length(&e1), 2);
-
-        let l = l / 6.0;
-        let vol = vol / m.vol() / Self::IDEAL_VOL;
-
-        f64::powf(vol, 2. / Self::DIM) / l
+        self.linear().quality()
     }
 
     fn point(&self, x: &[f64]) -> Point<D> {
-        if x.len() == 4 {
-            x[0]**2 * self.points[0]
-                + x[1]**2 * self.points[1]
-                + x[2]**2 * self.points[2]
-                + x[3]**2 * self.points[3]
-                + 2 * x[0] * x[1] * self.points[4]
-                + 2 * x[0] * x[2] * self.points[6]
-                + 2 * x[0] * x[3] * self.points[7]
-                + 2 * x[1] * x[2] * self.points[5]
-                + 2 * x[1] * x[3] * self.points[8]
-                + 2 * x[2] * x[3] * self.points[9]
+        let b = if x.len() == 4 {
+            [x[0], x[1], x[2], x[3]]
         } else if x.len() == 3 {
-            (1. - x[0] - x[1] - x[2])**2 * self.points[0]
-                + x[1]**2 * self.points[1]
-                + x[2]**2 * self.points[2]
-                + x[3]**2 * self.points[3]
-                + 2 * (1. - x[0] - x[1] - x[2]) * x[1] * self.points[4]
-                + 2 * (1. - x[0] - x[1] - x[2]) * x[2] * self.points[6]
-                + 2 * (1. - x[0] - x[1] - x[2]) * x[3] * self.points[7]
-                + 2 * x[1] * x[2] * self.points[5]
-                + 2 * x[1] * x[3] * self.points[8]
-                + 2 * x[2] * x[3] * self.points[9]
+            [1. - x[0] - x[1] - x[2], x[0], x[1], x[2]]
         } else {
             unreachable!();
-        }
+        };
+        lagrange_p2(&self.points, &b, &Self::MIDS)
     }
 
     fn bcoords(&self, p: &Point<D>) -> Self::BCoords {
-        let a = Matrix4::new(
-            1.0,
-            1.0,
-            1.0,
-            1.0,
-            self.points[0][0],
-            self.points[1][0],
-            self.points[2][0],
-            self.points[3][0],
-            self.points[0][1],
-            self.points[1][1],
-            self.points[2][1],
-            self.points[3][1],
-            self.points[0][2],
-            self.points[1][2],
-            self.points[2][2],
-            self.points[3][2],
-        );
-        let b = Vector4::new(1., p[0], p[1], p[2]);
-        let decomp = a.lu();
-        decomp.solve(&b).unwrap()
+        self.linear().bcoords(p)
     }
 
     fn scaled_normal(&self) -> Point<D> {
@@ -532,76 +505,18 @@ impl<const D: usize, M: Metric<D>> GElem<D, M> for GTetrahedronQuadratique<D, M>
     }
 
     fn gface(&self, i: Idx) -> Self::Face {
-        match i {
-            0 => GTriangle {
-                points: [self.points[1], self.points[2], self.points[3]],
-                metrics: [self.metrics[1], self.metrics[2], self.metrics[3]],
-            },
-            1 => GTriangle {
-                points: [self.points[2], self.points[0], self.points[3]],
-                metrics: [self.metrics[2], self.metrics[0], self.metrics[3]],
-            },
-            2 => GTriangle {
-                points: [self.points[0], self.points[1], self.points[3]],
-                metrics: [self.metrics[0], self.metrics[1], self.metrics[3]],
-            },
-            3 => GTriangle {
-                points: [self.points[0], self.points[2], self.points[1]],
-                metrics: [self.metrics[0], self.metrics[2], self.metrics[1]],
-            },
-            _ => unreachable!(),
+        let f = Self::FACES[i as usize];
+        GTriangleQuadratique {
+            points: f.map(|j| self.points[j]),
+            metrics: f.map(|j| self.metrics[j]),
         }
     }
 
     fn gamma(&self) -> f64 {
-        let vol = self.vol();
-        if vol < f64::EPSILON {
-            return 0.0;
-        }
-
-        let a = self.points[1] - self.points[0];
-        let b = self.points[2] - self.points[0];
-        let c = self.points[3] - self.points[0];
-
-        let aa = self.points[3] - self.points[2];
-        let bb = self.points[3] - self.points[1];
-        let cc = self.points[2] - self.points[1];
-
-        let la = a.norm_squared();
-        let lb = b.norm_squared();
-        let lc = c.norm_squared();
-        let laa = aa.norm_squared();
-        let lbb = bb.norm_squared();
-        let lcc = cc.norm_squared();
-
-        let lalaa = (la * laa).sqrt();
-        let lblbb = (lb * lbb).sqrt();
-        let lclcc = (lc * lcc).sqrt();
-
-        let tmp = (lalaa + lblbb + lclcc)
-            * (lalaa + lblbb - lclcc)
-            * (lalaa - lblbb + lclcc)
-            * (-lalaa + lblbb + lclcc);
-
-        // This happens when the 4 points are (nearly) co-planar
-        // => R is actually undetermined but the quality is (close to) zero
-        if tmp < f64::EPSILON {
-            return 0.0;
-        }
-
-        let r = tmp.sqrt() / 24.0 / vol;
-
-        let s1 = self.gface(0).vol();
-        let s2 = self.gface(1).vol();
-        let s3 = self.gface(2).vol();
-        let s4 = self.gface(3).vol();
-        let rho = 9.0 * vol / (s1 + s2 + s3 + s4);
-
-        rho / r
+        self.linear().gamma()
     }
 }
 
-
 #[derive(Clone, Copy, Debug)]
 pub struct GTriangle<const D: usize, M: Metric<D>> {
     points: [Point<D>; 3],
@@ -805,8 +720,9 @@ impl<const D: usize, M: Metric<D>> GElem<D, M> for GTriangle<D, M> {
     }
 }
 
-/// GTriangleQuadratique
-
+/// Second order triangle, with the mid-edge nodes ordered as in `TriangleQuadratique`.
+/// The volume, quality, barycentric coordinates, normal and gamma are those of the
+/// straight-sided triangle defined by the 3 vertices
 #[derive(Clone, Copy, Debug)]
 pub struct GTriangleQuadratique<const D: usize, M: Metric<D>> {
     points: [Point<D>; 6],
@@ -814,64 +730,16 @@ pub struct GTriangleQuadratique<const D: usize, M: Metric<D>> {
 }
 
 impl<const D: usize, M: Metric<D>> GTriangleQuadratique<D, M> {
-    const DIM: f64 = 3.0;
-
-    /// Get the jacobian of the transformation from the reference to the current element
-    pub fn jacobian(&self, x: &[f64]) -> Matrix3<f64> {
-        if x.len() == 2{
-            Matrix3::<f64>::new(
-                2.0 * (1 - x[0] - x[1]) * self.points[0][0] + 2 * x[0] * self.points[3][0] + 2 * x[1] * self.points[5][0],
-                2 * x[0] * self.points[1][0] + 2 * (1 - x[0] - x[1]) * self.points[3][0] + 2 * x[1] * self.points[4][0],
-                2 * x[1] * self.points[1][0] + 2 * (1 - x[0] - x[1]) *  self.points[5][0] + 2 * x[0] *  self.points[4][0],
-                2 * (1 - x[0] - x[1]) * self.points[0][1] + 2 * x[0] * self.points[3][1] + 2 * x[1] * self.points[5][1],
-                2 * x[0] * self.points[1][1] + 2 * (1 - x[0] - x[1]) * self.points[3][1] + 2 * x[1] * self.points[4][1],
-                2 * x[1] * self.points[1][1] + 2 * (1 - x[0] - x[1]) *  self.points[5][1] + 2 * x[0] *  self.points[4][1],
-                2 * (1 - x[0] - x[1]) * self.points[0][2] + 2 * x[0] * self.points[3][2] + 2 * x[1] * self.points[5][2],
-                2 * x[0] * self.points[1][2] + 2 * (1 - x[0] - x[1]) * self.points[3][2] + 2 * x[1] * self.points[4][2],
-                2 * x[1] * self.points[1][2] + 2 * (1 - x[0] - x[1]) *  self.points[5][2] + 2 * x[0] *  self.points[4][2],
-            )
-
-        } else if x.len() == 3 {
-            Matrix3::<f64>::new(
-                2.0 * x[0] * self.points[0][0] + 2 * x[1] * self.points[3][0] + 2 * x[2] * self.points[5][0],
-                2 * x[1] * self.points[1][0] + 2 * x[0] * self.points[3][0] + 2 * x[2] * self.points[4][0],
-                2 * x[2] * self.points[1][0] + 2 * x[0] *  self.points[5][0] + 2 * x[1] *  self.points[4][0],
-                2 * x[0] * self.points[0][1] + 2 * x[1] * self.points[3][1] + 2 * x[2] * self.points[5][1],
-                2 * x[1] * self.points[1][1] + 2 * x[0] * self.points[3][1] + 2 * x[2] * self.points[4][1],
-                2 * x[2] * self.points[1][1] + 2 * x[0] *  self.points[5][1] + 2 * x[1] *  self.points[4][1],
-                2 * x[0] * self.points[0][2] + 2 * x[1] * self.points[3][2] + 2 * x[2] * self.points[5][2],
-                2 * x[1] * self.points[1][2] + 2 * x[0] * self.points[3][2] + 2 * x[2] * self.points[4][2],
-                2 * x[2] * self.points[1][2] + 2 * x[0] *  self.points[5][2] + 2 * x[1] *  self.points[4][2],
-            )
-        } else {
-            unreachable!();
-        }
-    }
-
-    /// Compute the implied metric
-    pub fn implied_metric(&self, x: &[f64]) -> AnisoMetric2d {
-        let j = self.jacobian(x);
-        let m = j * j.transpose();
-        let m = m.try_inverse().unwrap();
-        AnisoMetric2d::from_mat(m)
-    }
-
-    // Get the edges
-    pub fn edge(&self, i: Idx) -> Point<D> {
-        match i {
-            0 => self.points[1] - self.points[0],
-            1 => self.points[2] - self.points[0],
-            2 => self.points[2] - self.points[1],
-            _ => unreachable!(),
-        }
-    }
-
-    fn cross_norm(e1: &Point<D>, e2: &Point<D>) -> f64 {
-        if D == 2 {
-            (e1[0] * e2[1] - e1[1] * e2[0]).abs()
-        } else {
-            let n = e1.cross(e2);
-            n.norm()
+    // (mid-edge node, vertex, vertex)
+    const MIDS: [(usize, usize, usize); 3] = [(3, 1, 2), (4, 2, 0), (5, 0, 1)];
+    const FACES: [[usize; 3]; 3] = [[1, 2, 3], [2, 0, 4], [0, 1, 5]];
+
+    /// Get the straight-sided triangle defined by the vertices
+    #[must_use]
+    pub fn linear(&self) -> GTriangle<D, M> {
+        GTriangle {
+            points: std::array::from_fn(|i| self.points[i]),
+            metrics: std::array::from_fn(|i| self.metrics[i]),
         }
     }
 }
@@ -879,14 +747,14 @@ impl<const D: usize, M: Metric<D>> GTriangleQuadratique<D, M> {
 impl<const D: usize, M: Metric<D>> GElem<D, M> for GTriangleQuadratique<D, M> {
     type Face = GEdgeQuadratique<D, M>;
     type BCoords = Vector3<f64>;
-    const IDEAL_VOL: f64 = SQRT_3 / 4.;
+    const IDEAL_VOL: f64 = GTriangle::<D, M>::IDEAL_VOL;
 
     fn vert(&self, i: Idx) -> Point<D> {
         self.points[i as usize]
     }
 
     fn from_verts<I: Iterator<Item = (Point<D>, M)>>(mut points_n_metrics: I) -> Self {
-        let p: [_; 3] = std::array::from_fn(|_| points_n_metrics.next().unwrap());
+        let p: [_; 6] = std::array::from_fn(|_| points_n_metrics.next().unwrap());
         assert!(points_n_metrics.next().is_none());
         Self {
             points: p.map(|x| x.0),
@@ -895,136 +763,65 @@ impl<const D: usize, M: Metric<D>> GElem<D, M> for GTriangleQuadratique<D, M> {
     }
 
     fn from_vert_and_face(point: &Point<D>, metric: &M, face: &Self::Face) -> Self {
-        Self {
-            points: [*point, face.points[0], face.points[1]],
-            metrics: [*metric, face.metrics[0], face.metrics[1]],
+        let mut points = [*point; 6];
+        let mut metrics = [*metric; 6];
+        // vertices and mid-edge node of the face
+        for (i, j) in [(1, 0), (2, 1), (3, 2)] {
+            points[i] = face.points[j];
+            metrics[i] = face.metrics[j];
+        }
+        for (k, i, j) in Self::MIDS {
+            if i == 0 || j == 0 {
+                (points[k], metrics[k]) = mid_node(&points, &metrics, i, j);
+            }
         }
+        Self { points, metrics }
     }
 
     fn vol(&self) -> f64 {
-        let e1 = self.points[1] - self.points[0];
-        let e2 = self.points[2] - self.points[0];
-        if D == 2 {
-            // <0 if not properly ordered
-            0.5 * (e1[0] * e2[1] - e1[1] * e2[0])
-        } else {
-            0.5 * Self::cross_norm(&e1, &e2)
-        }
+        self.linear().vol()
     }
 
     fn center(&self) -> Point<D> {
-        (self.points[0] + self.points[1] + self.points[2] + self.points[3] + self.points[4] + self.points[5]) / 6.0
+        self.point(&[1. / 3., 1. / 3., 1. / 3.])
     }
 
     fn quality(&self) -> f64 {
-        let m = M::min_metric(self.metrics.iter());
-
-        let mut e1 = self.points[1] - self.points[0];
-        let e2 = self.points[2] - self.points[0];
-        let vol = if D == 2 {
-            0.5 * (e1[0] * e2[1] - e1[1] * e2[0])
-        } else {
-            let n = e1.cross(&e2);
-            0.5 * n.norm()
-        };
-
-        let mut l = f64::powi(m.length(&e1), 2);
-        l += f64::powi(m.length(&e2), 2);
-        e1 = self.points[1] - self.points[2];
-        l += f64::powi(m.length(&e1), 2);
-
-        let l = l / 3.0;
-        let vol = vol / m.vol() / Self::IDEAL_VOL;
-
-        f64::powf(vol, 2. / Self::DIM) / l
+        self.linear().quality()
     }
 
     fn point(&self, x: &[f64]) -> Point<D> {
-        if x.len() == 3 {
-            x[0]**2 self.points[0] + x[1]**2 * self.points[1] + x[2]**2 * self.points[2]
-            + 2 * x[0] * x[1] * self.points[3] + 2 * x[0] * x[2] * self.points[5] + 2 * x[1] * x[2] * self.points[4]
+        let b = if x.len() == 3 {
+            [x[0], x[1], x[2]]
         } else if x.len() == 2 {
-            (1. - x[0] - x[1])**2 self.points[0] + x[0]**2 * self.points[1] + x[1]**2 * self.points[2]
-            + 2 * (1. - x[0] - x[1]) * x[0] * self.points[3] + 2 * (1. - x[0] - x[1]) * x[1] * self.points[5] + 2 * x[1] * x[2] * self.points[4]
+            [1. - x[0] - x[1], x[0], x[1]]
         } else {
             unreachable!();
-        }
+        };
+        lagrange_p2(&self.points, &b, &Self::MIDS)
     }
 
-    fn bcoords(&self, point: &Point<D>) -> Self::BCoords {
-        let p0 = &self.points[0];
-        let p1 = &self.points[1];
-        let p2 = &self.points[2];
-
-        if D == 2 {
-            let a = Matrix3::new(1.0, 1.0, 1.0, p0[0], p1[0], p2[0], p0[1], p1[1], p2[1]);
-            let b = Vector3::new(1., point[0], point[1]);
-            let decomp = a.lu();
-            decomp.solve(&b).unwrap()
-        } else {
-            let u = p1 - p0;
-            let v = p2 - p0;
-            let n = u.cross(&v);
-            let w = point - p0;
-            let nrm = n.norm_squared();
-            let gamma = u.cross(&w).dot(&n) / nrm;
-            let beta = w.cross(&v).dot(&n) / nrm;
-            Vector3::new(1.0 - beta - gamma, beta, gamma)
-        }
+    fn bcoords(&self, p: &Point<D>) -> Self::BCoords {
+        self.linear().bcoords(p)
     }
 
     fn scaled_normal(&self) -> Point<D> {
-        if D == 3 {
-            let e0 = self.points[1] - self.points[0];
-            let e1 = self.points[2] - self.points[0];
-            0.5 * e0.cross(&e1)
-        } else {
-            unreachable!();
-        }
+        self.linear().scaled_normal()
     }
 
     fn gface(&self, i: Idx) -> Self::Face {
-        match i {
-            0 => GEdgeQuadratique {
-                points: [self.points[1], self.points[2], self.points[3]],
-                metrics: [self.metrics[1], self.metrics[2], self.metrics[3]],
-            },
-            1 => GEdgeQuadratique {
-                points: [self.points[2], self.points[0], self.points[4]],
-                metrics: [self.metrics[2], self.metrics[0], self.metrics[4]],
-            },
-            2 => GEdgeQuadratique {
-                points: [self.points[0], self.points[1], self.points[5]],
-                metrics: [self.metrics[0], self.metrics[1], self.metrics[5]],
-            },
-            _ => unreachable!(),
+        let f = Self::FACES[i as usize];
+        GEdgeQuadratique {
+            points: f.map(|j| self.points[j]),
+            metrics: f.map(|j| self.metrics[j]),
         }
     }
 
     fn gamma(&self) -> f64 {
-        let mut a = self.points[2] - self.points[1];
-        let mut b = self.points[0] - self.points[2];
-        let mut c = self.points[1] - self.points[0];
-
-        a.normalize_mut();
-        b.normalize_mut();
-        c.normalize_mut();
-
-        let sina = Self::cross_norm(&b, &c);
-        let sinb = Self::cross_norm(&a, &c);
-        let sinc = Self::cross_norm(&a, &b);
-
-        let tmp = sina + sinb + sinc;
-        if tmp < 1e-12 {
-            0.0
-        } else {
-            4.0 * sina * sinb * sinc / tmp
-        }
+        self.linear().gamma()
     }
 }
 
-
-
 #[derive(Clone, Copy, Debug)]
 pub struct GEdge<const D: usize, M: Metric<D>> {
     points: [Point<D>; 2],
@@ -1084,8 +881,12 @@ impl<const D: usize, M: Metric<D>> GElem<D, M> for GEdge<D, M> {
         }
     }
 
-    fn bcoords(&self, _: &Point<D>) -> Self::BCoords {
-        unreachable!();
+    /// The barycentric coordinates are those of the projection of the vertex onto the line of
+    /// the edge
+    fn bcoords(&self, p: &Point<D>) -> Self::BCoords {
+        let e = self.points[1] - self.points[0];
+        let t = (p - self.points[0]).dot(&e) / e.norm_squared();
+        Vector2::new(1.0 - t, t)
     }
 
     fn scaled_normal(&self) -> Point<D> {
@@ -1100,8 +901,95 @@ impl<const D: usize, M: Metric<D>> GElem<D, M> for GEdge<D, M> {
         }
     }
 
-    fn gface(&self, _i: Idx) -> Self::Face {
-        unreachable!();
+    fn gface(&self, i: Idx) -> Self::Face {
+        GVertex {
+            points: [self.points[i as usize]],
+            metrics: [self.metrics[i as usize]],
+        }
+    }
+
+    fn gamma(&self) -> f64 {
+        1.0
+    }
+}
+
+/// Second order edge, the 3rd node being the mid-edge node. The volume, barycentric coordinates
+/// and normal are those of the straight edge defined by the 2 vertices
+#[derive(Clone, Copy, Debug)]
+pub struct GEdgeQuadratique<const D: usize, M: Metric<D>> {
+    points: [Point<D>; 3],
+    metrics: [M; 3],
+}
+
+impl<const D: usize, M: Metric<D>> GEdgeQuadratique<D, M> {
+    /// Get the straight edge defined by the vertices
+    #[must_use]
+    pub const fn linear(&self) -> GEdge<D, M> {
+        GEdge {
+            points: [self.points[0], self.points[1]],
+            metrics: [self.metrics[0], self.metrics[1]],
+        }
+    }
+}
+
+impl<const D: usize, M: Metric<D>> GElem<D, M> for GEdgeQuadratique<D, M> {
+    type Face = GVertex<D, M>;
+    type BCoords = Vector2<f64>;
+    const IDEAL_VOL: f64 = 1.0;
+
+    fn vert(&self, i: Idx) -> Point<D> {
+        self.points[i as usize]
+    }
+
+    fn from_verts<I: Iterator<Item = (Point<D>, M)>>(mut points_n_metrics: I) -> Self {
+        let p: [_; 3] = std::array::from_fn(|_| points_n_metrics.next().unwrap());
+        assert!(points_n_metrics.next().is_none());
+        Self {
+            points: p.map(|x| x.0),
+            metrics: p.map(|x| x.1),
+        }
+    }
+
+    fn from_vert_and_face(point: &Point<D>, metric: &M, face: &Self::Face) -> Self {
+        let mut points = [*point, face.points[0], *point];
+        let mut metrics = [*metric, face.metrics[0], *metric];
+        (points[2], metrics[2]) = mid_node(&points, &metrics, 0, 1);
+        Self { points, metrics }
+    }
+
+    fn vol(&self) -> f64 {
+        self.linear().vol()
+    }
+
+    fn center(&self) -> Point<D> {
+        self.points[2]
+    }
+
+    fn quality(&self) -> f64 {
+        self.linear().quality()
+    }
+
+    fn point(&self, x: &[f64]) -> Point<D> {
+        let b = if x.len() == 2 {
+            [x[0], x[1]]
+        } else if x.len() == 1 {
+            [1. - x[0], x[0]]
+        } else {
+            unreachable!();
+        };
+        lagrange_p2(&self.points, &b, &[(2, 0, 1)])
+    }
+
+    fn bcoords(&self, p: &Point<D>) -> Self::BCoords {
+        self.linear().bcoords(p)
+    }
+
+    fn scaled_normal(&self) -> Point<D> {
+        self.linear().scaled_normal()
+    }
+
+    fn gface(&self, i: Idx) -> Self::Face {
+        self.linear().gface(i)
     }
 
     fn gamma(&self) -> f64 {
@@ -1137,8 +1025,11 @@ impl<const D: usize, M: Metric<D>> GElem<D, M> for GVertex<D, M> {
         }
     }
 
-    fn from_vert_and_face(_point: &Point<D>, _metric: &M, _face: &Self::Face) -> Self {
-        unreachable!();
+    fn from_vert_and_face(point: &Point<D>, metric: &M, _face: &Self::Face) -> Self {
+        Self {
+            points: [*point],
+            metrics: [*metric],
+        }
     }
 
     fn vol(&self) -> f64 {
@@ -1180,7 +1071,10 @@ mod tests {
     use nalgebra::{Matrix2, Matrix3};
 
     use crate::{
-        mesh::{GElem, GTetrahedron, GTriangle, Point},
+        mesh::{
+            GEdge, GEdgeQuadratique, GElem, GTetrahedron, GTetrahedronQuadratique, GTriangle,
+            GTriangleQuadratique, Point,
+        },
         metric::{AnisoMetric2d, AnisoMetric3d, IsoMetric},
     };
 
@@ -1330,4 +1224,65 @@ mod tests {
     }
 
     // TODO: test qualities & barycentric coordinates
+
+    #[test]
+    fn test_edge_bcoords_and_faces() {
+        let m = IsoMetric::<2>::from(1.0);
+        let points = [Point::<2>::new(1., 0.), Point::<2>::new(3., 0.)];
+        let edge = GEdge::from_verts(points.iter().map(|x| (*x, m)));
+        let x = edge.bcoords(&Point::<2>::new(1.5, 2.0));
+        assert!((x - nalgebra::Vector2::new(0.75, 0.25)).norm() < 1e-12);
+        assert!((edge.gface(1).center() - points[1]).norm() < 1e-12);
+
+        let points = [points[0], points[1], Point::<2>::new(2., 0.5)];
+        let edge = GEdgeQuadratique::from_verts(points.iter().map(|x| (*x, m)));
+        let x = edge.bcoords(&Point::<2>::new(1.5, 2.0));
+        assert!((x - nalgebra::Vector2::new(0.75, 0.25)).norm() < 1e-12);
+        assert!((edge.gface(0).center() - points[0]).norm() < 1e-12);
+    }
+
+    #[test]
+    fn test_quadratic_from_vert_and_face() {
+        let m = IsoMetric::<3>::from(1.0);
+        let verts = [
+            Point::<3>::new(0., 0., 0.),
+            Point::<3>::new(1., 0., 0.),
+            Point::<3>::new(0., 1., 0.),
+            Point::<3>::new(0., 0., 1.),
+        ];
+
+        // straight-sided elements
+        let mid = |i: usize, j: usize| 0.5 * (verts[i] + verts[j]);
+        let points = [
+            verts[0],
+            verts[1],
+            verts[2],
+            verts[3],
+            mid(2, 3),
+            mid(1, 3),
+            mid(1, 2),
+            mid(0, 2),
+            mid(0, 3),
+            mid(0, 1),
+        ];
+        let tet = GTetrahedronQuadratique::from_verts(points.iter().map(|x| (*x, m)));
+        let tet2 = GTetrahedronQuadratique::from_vert_and_face(&verts[0], &m, &tet.gface(0));
+        assert!((0..10).all(|i| (tet.vert(i) - tet2.vert(i)).norm() < 1e-12));
+
+        let points = [
+            verts[0],
+            verts[1],
+            verts[2],
+            mid(1, 2),
+            mid(2, 0),
+            mid(0, 1),
+        ];
+        let tri = GTriangleQuadratique::from_verts(points.iter().map(|x| (*x, m)));
+        let tri2 = GTriangleQuadratique::from_vert_and_face(&verts[0], &m, &tri.gface(0));
+        assert!((0..6).all(|i| (tri.vert(i) - tri2.vert(i)).norm() < 1e-12));
+
+        let edge = tri.gface(2);
+        let edge2 = GEdgeQuadratique::from_vert_and_face(&verts[0], &m, &edge.gface(1));
+        assert!((0..3).all(|i| (edge.vert(i) - edge2.vert(i)).norm() < 1e-12));
+    }
 }
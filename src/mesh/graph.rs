@@ -3,6 +3,10 @@ use crate::{
     Error, Idx, Result,
 };
 use num::PrimInt;
+use rayon::{
+    prelude::{IntoParallelIterator, IntoParallelRefIterator, ParallelIterator},
+    slice::ParallelSliceMut,
+};
 use rustc_hash::FxHashMap;
 use std::{collections::hash_map::Entry, fmt::Display, ops::AddAssign};
 
@@ -142,6 +146,46 @@ impl CSRGraph {
         res
     }
 
+    /// Build a graph with `n` rows from (row, column) pairs, sorted in parallel
+    fn from_pairs(mut pairs: Vec<[Idx; 2]>, n: usize, m: Idx) -> Self {
+        pairs.par_sort_unstable();
+
+        let mut ptr = vec![0; n + 1];
+        for p in &pairs {
+            ptr[p[0] as usize + 1] += 1;
+        }
+        for i in 0..n {
+            ptr[i + 1] += ptr[i];
+        }
+        let indices = pairs.par_iter().map(|p| p[1]).collect();
+
+        Self { ptr, indices, m }
+    }
+
+    /// Parallel version of `new`, with identical results
+    #[must_use]
+    pub fn par_new(edgs: &[[Idx; 2]]) -> Self {
+        let n = edgs.par_iter().map(|e| e[0].max(e[1])).max().unwrap_or(0) as usize + 1;
+        let pairs = edgs
+            .par_iter()
+            .flat_map_iter(|e| [[e[0], e[1]], [e[1], e[0]]])
+            .collect();
+        Self::from_pairs(pairs, n, n as Idx)
+    }
+
+    /// Parallel version of `transpose`, with identical results
+    #[must_use]
+    pub fn par_transpose<E: Elem>(elems: &vector::Vector<E>, nv: Option<usize>) -> Self {
+        let n_elems = elems.len() as Idx;
+        let pairs: Vec<_> = (0..n_elems)
+            .into_par_iter()
+            .flat_map_iter(|i| elems.index(i).into_iter().map(move |j| [j, i]))
+            .collect();
+        let nv =
+            nv.unwrap_or_else(|| pairs.par_iter().map(|p| p[0]).max().unwrap_or(0) as usize + 1);
+        Self::from_pairs(pairs, nv, n_elems)
+    }
+
     #[must_use]
     pub fn n(&self) -> Idx {
         self.ptr.len() as Idx - 1
@@ -157,6 +201,12 @@ impl CSRGraph {
         self.indices.len() as Idx
     }
 
+    /// Get the memory used by the graph, in bytes
+    #[must_use]
+    pub fn memory_usage(&self) -> usize {
+        (self.ptr.capacity() + self.indices.capacity()) * std::mem::size_of::<Idx>()
+    }
+
     #[must_use]
     pub fn row(&self, i: Idx) -> &[Idx] {
         let start = self.ptr[i as usize] as usize;
@@ -226,6 +276,8 @@ mod tests {
     use crate::{
         mesh::{
             graph::{reindex, CSRGraph, ConnectedComponents},
+            test_meshes::test_mesh_3d,
+            vector::Vector,
             Edge, Elem, Triangle,
         },
         Tag,
@@ -284,6 +336,33 @@ mod tests {
         assert_eq!(*edgs, [1]);
     }
 
+    #[test]
+    fn test_csr_par() {
+        let mesh = test_mesh_3d().split().split();
+        let elems = Vector::from(mesh.elems().collect::<Vec<_>>());
+
+        let g = CSRGraph::transpose(&elems, None);
+        let g_par = CSRGraph::par_transpose(&elems, None);
+        assert_eq!(g.ptr, g_par.ptr);
+        assert_eq!(g.indices, g_par.indices);
+        assert_eq!(g.m(), g_par.m());
+
+        let g = CSRGraph::transpose(&elems, Some(mesh.n_verts() as usize + 2));
+        let g_par = CSRGraph::par_transpose(&elems, Some(mesh.n_verts() as usize + 2));
+        assert_eq!(g.ptr, g_par.ptr);
+        assert_eq!(g.indices, g_par.indices);
+
+        let edgs = mesh
+            .elems()
+            .flat_map(|e| [[e[0], e[1]], [e[2], e[1]], [e[3], e[0]]])
+            .collect::<Vec<_>>();
+        let g = CSRGraph::new(&edgs);
+        let g_par = CSRGraph::par_new(&edgs);
+        assert_eq!(g.ptr, g_par.ptr);
+        assert_eq!(g.indices, g_par.indices);
+        assert_eq!(g.m(), g_par.m());
+    }
+
     #[test]
     fn test_cc() {
         let g = vec![[0, 1], [1, 2], [2, 0], [3, 4]];
@@ -0,0 +1,265 @@
+use crate::{
+    mesh::{Point, SimplexMesh, TagNames, Tetrahedron},
+    Idx, Tag,
+};
+use log::debug;
+
+/// Mixed-element 3D mesh, e.g. a boundary layer mesh with prisms and pyramids, that can be
+/// converted to a `SimplexMesh` by splitting its elements into tetrahedra.
+/// The elements are numbered as the tetrahedra, then pyramids, prisms and hexahedra and the
+/// faces as the triangles, then quadrangles. The node ordering is the one of Gmsh / CGNS
+/// (base 0-3 and apex 4 for the pyramids, bottom 0-2 and top 3-5 for the prisms, bottom 0-3
+/// and top 4-7 for the hexahedra)
+#[derive(Clone, Debug, Default)]
+pub struct HybridMesh {
+    /// The vertices
+    pub verts: Vec<Point<3>>,
+    /// The tetrahedra
+    pub tets: Vec<[Idx; 4]>,
+    /// The tetrahedron tags
+    pub tet_tags: Vec<Tag>,
+    /// The pyramids
+    pub pyrs: Vec<[Idx; 5]>,
+    /// The pyramid tags
+    pub pyr_tags: Vec<Tag>,
+    /// The prisms
+    pub pris: Vec<[Idx; 6]>,
+    /// The prism tags
+    pub pri_tags: Vec<Tag>,
+    /// The hexahedra
+    pub hexs: Vec<[Idx; 8]>,
+    /// The hexahedron tags
+    pub hex_tags: Vec<Tag>,
+    /// The triangular faces
+    pub tris: Vec<[Idx; 3]>,
+    /// The triangle tags
+    pub tri_tags: Vec<Tag>,
+    /// The quadrangular faces
+    pub quas: Vec<[Idx; 4]>,
+    /// The quadrangle tags
+    pub qua_tags: Vec<Tag>,
+    /// The tag names
+    pub tag_names: TagNames,
+}
+
+/// Correspondence between the simplices of a split `HybridMesh` and its elements / faces
+#[derive(Clone, Debug)]
+pub struct HybridMapping {
+    /// # of elements of the `HybridMesh`
+    pub n_elems: Idx,
+    /// # of faces of the `HybridMesh`
+    pub n_faces: Idx,
+    /// The element of the `HybridMesh` each tetrahedron comes from
+    pub elems: Vec<Idx>,
+    /// The face of the `HybridMesh` each triangle comes from
+    pub faces: Vec<Idx>,
+}
+
+impl HybridMapping {
+    /// Average element data (with `m` values per tetrahedron) onto the elements of the
+    /// `HybridMesh`
+    #[must_use]
+    pub fn elem_data_to_hybrid(&self, data: &[f64], m: usize) -> Vec<f64> {
+        assert_eq!(data.len(), m * self.elems.len());
+
+        let mut res = vec![0.0; m * self.n_elems as usize];
+        let mut count = vec![0; self.n_elems as usize];
+        for (&i_elem, v) in self.elems.iter().zip(data.chunks(m)) {
+            let i_elem = i_elem as usize;
+            let r = &mut res[m * i_elem..m * (i_elem + 1)];
+            r.iter_mut().zip(v).for_each(|(x, y)| *x += y);
+            count[i_elem] += 1;
+        }
+        for (r, &c) in res.chunks_mut(m).zip(&count) {
+            if c > 0 {
+                r.iter_mut().for_each(|x| *x /= f64::from(c));
+            }
+        }
+
+        res
+    }
+
+    /// Copy element data (with `m` values per element of the `HybridMesh`) onto the
+    /// tetrahedra
+    #[must_use]
+    pub fn elem_data_from_hybrid(&self, data: &[f64], m: usize) -> Vec<f64> {
+        assert_eq!(data.len(), m * self.n_elems as usize);
+
+        self.elems
+            .iter()
+            .flat_map(|&i| data[m * i as usize..m * (i as usize + 1)].iter().copied())
+            .collect()
+    }
+}
+
+impl HybridMesh {
+    /// Get the # of vertices
+    #[must_use]
+    pub fn n_verts(&self) -> Idx {
+        self.verts.len() as Idx
+    }
+
+    /// Get the # of elements
+    #[must_use]
+    pub fn n_elems(&self) -> Idx {
+        (self.tets.len() + self.pyrs.len() + self.pris.len() + self.hexs.len()) as Idx
+    }
+
+    /// Get the # of faces
+    #[must_use]
+    pub fn n_faces(&self) -> Idx {
+        (self.tris.len() + self.quas.len()) as Idx
+    }
+
+    /// Split the elements into tetrahedra and the faces into triangles. The splitting only
+    /// depends on the vertex indices, so the resulting mesh is conforming; the vertices are
+    /// not modified. Returns the mesh and the mapping from the simplices to the elements and
+    /// faces of `self`
+    #[must_use]
+    pub fn to_simplices(&self) -> (SimplexMesh<3, Tetrahedron>, HybridMapping) {
+        debug!("Split the hybrid mesh into tetrahedra");
+
+        let mut res = SimplexMesh::new(
+            self.verts.clone(),
+            Vec::new(),
+            Vec::new(),
+            Vec::new(),
+            Vec::new(),
+        );
+        *res.tag_names_mut() = self.tag_names.clone();
+
+        let mut elems = Vec::new();
+        let mut offset = 0;
+        let mut append = |(_, indices): ([Idx; 2], Vec<Idx>), n: usize| {
+            elems.extend(indices.iter().map(|&i| offset + i));
+            offset += n as Idx;
+        };
+        let tets = self.tets.iter().map(|e| &e[..]);
+        append(
+            res.add_tets(tets, self.tet_tags.iter().copied()),
+            self.tets.len(),
+        );
+        let pyrs = self.pyrs.iter().map(|e| &e[..]);
+        append(
+            res.add_pyrs(pyrs, self.pyr_tags.iter().copied()),
+            self.pyrs.len(),
+        );
+        let pris = self.pris.iter().map(|e| &e[..]);
+        append(
+            res.add_pris(pris, self.pri_tags.iter().copied()),
+            self.pris.len(),
+        );
+        let hexs = self.hexs.iter().map(|e| &e[..]);
+        append(
+            res.add_hexs(hexs, self.hex_tags.iter().copied()),
+            self.hexs.len(),
+        );
+
+        let (_, mut faces) = res.add_tris(
+            self.tris.iter().map(|f| &f[..]),
+            self.tri_tags.iter().copied(),
+        );
+        let quas = self.quas.iter().map(|f| &f[..]);
+        let (_, indices) = res.add_quas(quas, self.qua_tags.iter().copied());
+        faces.extend(indices.iter().map(|&i| self.tris.len() as Idx + i));
+
+        debug!(
+            "{} elements and {} faces split into {} tetrahedra and {} triangles",
+            self.n_elems(),
+            self.n_faces(),
+            res.n_elems(),
+            res.n_faces()
+        );
+
+        let mapping = HybridMapping {
+            n_elems: self.n_elems(),
+            n_faces: self.n_faces(),
+            elems,
+            faces,
+        };
+        (res, mapping)
+    }
+
+    /// Create a `HybridMesh` that contains only the tetrahedra and triangles of `mesh`, e.g.
+    /// to export an adapted mesh
+    #[must_use]
+    pub fn from_simplices(mesh: &SimplexMesh<3, Tetrahedron>) -> Self {
+        Self {
+            verts: mesh.verts().collect(),
+            tets: mesh.elems().map(|e| [e[0], e[1], e[2], e[3]]).collect(),
+            tet_tags: mesh.etags().collect(),
+            tris: mesh.faces().map(|f| [f[0], f[1], f[2]]).collect(),
+            tri_tags: mesh.ftags().collect(),
+            tag_names: mesh.tag_names().clone(),
+            ..Self::default()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::HybridMesh;
+    use crate::mesh::{
+        get_face_to_elem,
+        test_meshes::{test_hybrid_mesh, test_mesh_3d},
+        GElem,
+    };
+
+    #[test]
+    fn test_to_simplices() {
+        let hybrid = test_hybrid_mesh();
+        assert_eq!(hybrid.n_elems(), 3);
+        assert_eq!(hybrid.n_faces(), 12);
+
+        let (mesh, mapping) = hybrid.to_simplices();
+        assert_eq!(mesh.n_verts(), 11);
+        assert_eq!(mesh.n_elems(), 2 + 3 + 6);
+        assert_eq!(mapping.elems.len(), 11);
+        assert_eq!(mesh.n_faces(), 18);
+        assert_eq!(mapping.faces.len(), 18);
+        assert_eq!(mesh.tag_names().elem_name(2), Some("prism"));
+
+        for e in mesh.elems() {
+            assert!(mesh.gelem(e).vol() > 0.0);
+        }
+        assert!(f64::abs(mesh.vol() - 5.0 / 3.0) < 1e-12);
+
+        // conforming split: the internal faces belong to 2 tetrahedra
+        let f2e = get_face_to_elem(mesh.elems());
+        let mut bdy = f2e
+            .iter()
+            .filter(|(_, elems)| elems.len() == 1)
+            .map(|(f, _)| [f[0], f[1], f[2]])
+            .collect::<Vec<_>>();
+        bdy.sort_unstable();
+        let mut faces = mesh
+            .faces()
+            .map(|f| {
+                let mut f = [f[0], f[1], f[2]];
+                f.sort_unstable();
+                f
+            })
+            .collect::<Vec<_>>();
+        faces.sort_unstable();
+        assert_eq!(bdy, faces);
+
+        // element data
+        let data = mapping.elems.iter().map(|&i| i as f64).collect::<Vec<_>>();
+        let avg = mapping.elem_data_to_hybrid(&data, 1);
+        assert_eq!(avg, vec![0.0, 1.0, 2.0]);
+        assert_eq!(mapping.elem_data_from_hybrid(&avg, 1), data);
+    }
+
+    #[test]
+    fn test_from_simplices() {
+        let mesh = test_mesh_3d();
+        let hybrid = HybridMesh::from_simplices(&mesh);
+        assert_eq!(hybrid.n_elems(), mesh.n_elems());
+        assert_eq!(hybrid.n_faces(), mesh.n_faces());
+
+        let (other, _) = hybrid.to_simplices();
+        assert!(other.elems().eq(mesh.elems()));
+        assert!(other.faces().eq(mesh.faces()));
+        assert!(f64::abs(other.vol() - mesh.vol()) < 1e-12);
+    }
+}
@@ -1,8 +1,10 @@
 use crate::{
-    mesh::{AsSliceF64, Elem, GElem, SimplexMesh},
-    spatialindex::{DefaultObjectIndex, DefaultPointIndex, ObjectIndex, PointIndex},
-    Result,
+    mesh::{AsSliceF64, Elem, ElementTree, SimplexMesh},
+    metric::Metric,
+    spatialindex::{DefaultPointIndex, PointIndex},
+    Error, Result,
 };
+use log::debug;
 
 impl<const D: usize, E: Elem> SimplexMesh<D, E> {
     pub fn interpolate_nearest(
@@ -30,7 +32,7 @@ impl<const D: usize, E: Elem> SimplexMesh<D, E> {
 
     pub fn interpolate_linear(
         &self,
-        tree: &DefaultObjectIndex<D>,
+        tree: &ElementTree<D, E>,
         other: &Self,
         f: &[f64],
         tol: Option<f64>,
@@ -44,10 +46,10 @@ impl<const D: usize, E: Elem> SimplexMesh<D, E> {
 
         let mut res = Vec::with_capacity(n_verts_other * n_comp);
         for vert in other.verts() {
-            let i_elem = tree.nearest_elem(&vert);
+            let (i_elem, x) = tree
+                .locate(&vert)
+                .unwrap_or_else(|| tree.nearest_elem_bcoords(&vert));
             let e = self.elem(i_elem);
-            let ge = self.gelem(e);
-            let x = ge.bcoords(&vert);
             assert!(
                 x.as_slice_f64()
                     .iter()
@@ -61,6 +63,75 @@ impl<const D: usize, E: Elem> SimplexMesh<D, E> {
         }
         Ok(res)
     }
+
+    /// Transfer fields defined at the vertices of `other` (e.g. the mesh before remeshing) to the
+    /// vertices of `self` using linear interpolation. Each vertex of `self` is located in
+    /// `other` using an element tree; for vertices lying outside of `other` (e.g. due to
+    /// the boundary discretization) the barycentric coordinates in the nearest element are
+    /// clipped to $`[0, 1]`$ and renormalized, i.e. the value is extrapolated from
+    /// the nearest element
+    pub fn interpolate_from(&self, other: &Self, f: &[f64]) -> Result<Vec<f64>> {
+        debug!("Interpolate fields from another mesh");
+
+        let n_verts_other = other.n_verts() as usize;
+        if n_verts_other == 0 || f.len() % n_verts_other != 0 {
+            return Err(Error::from("invalid field size"));
+        }
+        let n_comp = f.len() / n_verts_other;
+
+        let tree = other.compute_element_tree();
+
+        let mut res = Vec::with_capacity(self.n_verts() as usize * n_comp);
+        for vert in self.verts() {
+            let (i_elem, x) = tree.locate_or_nearest(&vert);
+            let e = other.elem(i_elem);
+            for j in 0..n_comp {
+                let iter = e.iter().copied().zip(x.as_slice_f64().iter().copied());
+                res.push(iter.fold(0.0, |a, (i, w)| a + f[n_comp * i as usize + j] * w));
+            }
+        }
+
+        Ok(res)
+    }
+
+    /// Transfer a metric field defined at the vertices of `self` to the vertices of `other`,
+    /// using the log-Euclidean interpolation (see `Metric::interpolate_log`) in the elements of
+    /// `self`
+    pub fn interpolate_metric<M: Metric<D>>(
+        &self,
+        tree: &ElementTree<D, E>,
+        other: &Self,
+        m: &[M],
+        tol: Option<f64>,
+    ) -> Result<Vec<M>> {
+        let tol = tol.unwrap_or(1e-12);
+
+        if m.len() != self.n_verts() as usize {
+            return Err(Error::from("invalid metric field size"));
+        }
+
+        other
+            .verts()
+            .map(|vert| {
+                let (i_elem, x) = tree
+                    .locate(&vert)
+                    .unwrap_or_else(|| tree.nearest_elem_bcoords(&vert));
+                let e = self.elem(i_elem);
+                let x = x.as_slice_f64();
+                if !x.iter().all(|c| (-tol..1.0 + tol).contains(c)) {
+                    return Err(Error::from(&format!(
+                        "vertex {vert:?} not found in the mesh"
+                    )));
+                }
+                let wm = x
+                    .iter()
+                    .zip(e.iter())
+                    .map(|(&w, &i)| (w, m[i as usize]))
+                    .collect::<Vec<_>>();
+                Ok(M::interpolate_log(&wm))
+            })
+            .collect::<Result<Vec<_>>>()
+    }
 }
 
 #[cfg(test)]
@@ -70,6 +141,7 @@ mod tests {
             test_meshes::{test_mesh_2d, test_mesh_3d},
             Point,
         },
+        metric::{AnisoMetric2d, IsoMetric, Metric},
         Result,
     };
     use nalgebra::{Rotation2, Rotation3};
@@ -78,7 +150,7 @@ mod tests {
     #[test]
     fn test_interpolate_2d() -> Result<()> {
         let mesh = test_mesh_2d().split().split().split();
-        let tree = mesh.compute_elem_tree();
+        let tree = mesh.compute_element_tree();
 
         let fun = |p: Point<2>| 1.0 * p[0] + 2.0 * p[1];
 
@@ -134,7 +206,7 @@ mod tests {
     #[test]
     fn test_interpolate_3d() -> Result<()> {
         let mesh = test_mesh_3d().split().split().split();
-        let tree = mesh.compute_elem_tree();
+        let tree = mesh.compute_element_tree();
 
         let fun = |p: Point<3>| 1.0 * p[0] + 2.0 * p[1] + 3.0 * p[2];
 
@@ -186,4 +258,80 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    fn test_interpolate_from_2d() -> Result<()> {
+        let old = test_mesh_2d().split().split().split();
+
+        let fun = |p: Point<2>| [1.0 + p[0] + 2.0 * p[1], 3.0 * p[0] - p[1]];
+        let f = old.verts().flat_map(fun).collect::<Vec<_>>();
+
+        // some of the vertices are slightly outside of the old mesh
+        let mut new = test_mesh_2d().split().split();
+        new.mut_verts().for_each(|x| {
+            let p = Point::<2>::new(0.5, 0.5);
+            *x = p + 1.001 * (*x - p);
+        });
+
+        let f_new = new.interpolate_from(&old, &f)?;
+        assert_eq!(f_new.len(), 2 * new.n_verts() as usize);
+        for (p, v) in new.verts().zip(f_new.chunks(2)) {
+            let inside = p.iter().all(|x| (0.0..=1.0).contains(x));
+            let tol = if inside { 1e-10 } else { 1e-2 };
+            let p = Point::<2>::new(p[0].clamp(0.0, 1.0), p[1].clamp(0.0, 1.0));
+            let v_ref = fun(p);
+            assert!((v[0] - v_ref[0]).abs() < tol);
+            assert!((v[1] - v_ref[1]).abs() < tol);
+        }
+
+        assert!(new.interpolate_from(&old, &f[1..]).is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_interpolate_metric_2d() -> Result<()> {
+        let mesh = test_mesh_2d().split().split().split();
+        let tree = mesh.compute_element_tree();
+
+        let mut other = test_mesh_2d().split().split();
+        other.mut_verts().for_each(|x| {
+            let p = Point::<2>::new(0.5, 0.5);
+            *x = p + 0.9 * (*x - p);
+        });
+
+        // sizes varying geometrically
+        let fun = |p: Point<2>| f64::powf(10.0, -1.0 - p[0] - p[1]);
+        let m = mesh
+            .verts()
+            .map(|p| IsoMetric::<2>::from(fun(p)))
+            .collect::<Vec<_>>();
+        let m_other = mesh.interpolate_metric(&tree, &other, &m, None)?;
+        for (p, m) in other.verts().zip(m_other.iter()) {
+            assert!((m.sizes()[0] / fun(p) - 1.0).abs() < 1e-10);
+        }
+
+        // anisotropic metrics remain valid
+        let m = mesh
+            .verts()
+            .map(|p| {
+                let v0 = Point::<2>::new(fun(p), 0.0);
+                let v1 = Point::<2>::new(0.0, 0.1);
+                AnisoMetric2d::from_sizes(&v0, &v1)
+            })
+            .collect::<Vec<_>>();
+        let m_other = mesh.interpolate_metric(&tree, &other, &m, None)?;
+        for (p, m) in other.verts().zip(m_other.iter()) {
+            m.check()?;
+            let s = m.sizes();
+            assert!((s[0] / fun(p) - 1.0).abs() < 1e-6);
+            assert!((s[1] - 0.1).abs() < 1e-6);
+        }
+
+        assert!(mesh
+            .interpolate_metric(&tree, &other, &m[1..], None)
+            .is_err());
+
+        Ok(())
+    }
 }
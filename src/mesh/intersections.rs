@@ -0,0 +1,274 @@
+use crate::{
+    mesh::{
+        predicates::{orient2d_projected, orient3d},
+        Elem, Point, SimplexMesh, Triangle,
+    },
+    Idx,
+};
+use log::debug;
+use rayon::prelude::*;
+
+/// Coordinate to ignore to project a triangle on a coordinate plane, i.e. the largest component
+/// of its normal
+fn projection_axis(t: &[Point<3>; 3]) -> usize {
+    (t[1] - t[0]).cross(&(t[2] - t[0])).abs().imax()
+}
+
+/// Check if the segments `[a, b]` and `[c, d]` of a plane intersect (projected along `axis`)
+fn segments_intersect_2d(
+    a: &Point<3>,
+    b: &Point<3>,
+    c: &Point<3>,
+    d: &Point<3>,
+    axis: usize,
+) -> bool {
+    let o = [
+        orient2d_projected(a, b, c, axis),
+        orient2d_projected(a, b, d, axis),
+        orient2d_projected(c, d, a, axis),
+        orient2d_projected(c, d, b, axis),
+    ];
+    if o[0] * o[1] < 0 && o[2] * o[3] < 0 {
+        return true;
+    }
+    // collinear cases
+    let on_segment = |p: &Point<3>, q: &Point<3>, x: &Point<3>| {
+        (0..3).all(|i| x[i] >= p[i].min(q[i]) && x[i] <= p[i].max(q[i]))
+    };
+    (o[0] == 0 && on_segment(a, b, c))
+        || (o[1] == 0 && on_segment(a, b, d))
+        || (o[2] == 0 && on_segment(c, d, a))
+        || (o[3] == 0 && on_segment(c, d, b))
+}
+
+/// Check if point `p` lies in triangle `t` of the same plane (projected along `axis`)
+fn point_in_triangle_2d(p: &Point<3>, t: &[Point<3>; 3], axis: usize) -> bool {
+    let o = [
+        orient2d_projected(&t[0], &t[1], p, axis),
+        orient2d_projected(&t[1], &t[2], p, axis),
+        orient2d_projected(&t[2], &t[0], p, axis),
+    ];
+    !(o.iter().any(|&x| x > 0) && o.iter().any(|&x| x < 0))
+}
+
+/// Check if segment `[a, b]` intersects triangle `t` of the same plane
+fn segment_triangle_2d(a: &Point<3>, b: &Point<3>, t: &[Point<3>; 3]) -> bool {
+    let axis = projection_axis(t);
+    point_in_triangle_2d(a, t, axis)
+        || point_in_triangle_2d(b, t, axis)
+        || (0..3).any(|i| segments_intersect_2d(a, b, &t[i], &t[(i + 1) % 3], axis))
+}
+
+/// Check if segment `[a, b]` intersects triangle `t`
+fn segment_triangle(a: &Point<3>, b: &Point<3>, t: &[Point<3>; 3]) -> bool {
+    let oa = orient3d(&t[0], &t[1], &t[2], a);
+    let ob = orient3d(&t[0], &t[1], &t[2], b);
+    if oa * ob > 0 {
+        return false;
+    }
+    if oa == 0 && ob == 0 {
+        return segment_triangle_2d(a, b, t);
+    }
+    let o = [
+        orient3d(a, b, &t[0], &t[1]),
+        orient3d(a, b, &t[1], &t[2]),
+        orient3d(a, b, &t[2], &t[0]),
+    ];
+    !(o.iter().any(|&x| x > 0) && o.iter().any(|&x| x < 0))
+}
+
+/// Check if two triangles of the same plane intersect
+fn coplanar_triangles_intersect(t0: &[Point<3>; 3], t1: &[Point<3>; 3]) -> bool {
+    let axis = projection_axis(t1);
+    let edges_intersect = |i: usize, j: usize| {
+        segments_intersect_2d(&t0[i], &t0[(i + 1) % 3], &t1[j], &t1[(j + 1) % 3], axis)
+    };
+    t0.iter().any(|p| point_in_triangle_2d(p, t1, axis))
+        || t1.iter().any(|p| point_in_triangle_2d(p, t0, axis))
+        || (0..3).any(|i| (0..3).any(|j| edges_intersect(i, j)))
+}
+
+/// Check if two triangles without common vertices intersect
+fn triangles_intersect(t0: &[Point<3>; 3], t1: &[Point<3>; 3]) -> bool {
+    let o = t0.map(|p| orient3d(&t1[0], &t1[1], &t1[2], &p));
+    if o.iter().all(|&x| x > 0) || o.iter().all(|&x| x < 0) {
+        return false;
+    }
+    if o.iter().all(|&x| x == 0) {
+        return coplanar_triangles_intersect(t0, t1);
+    }
+    (0..3).any(|i| segment_triangle(&t0[i], &t0[(i + 1) % 3], t1))
+        || (0..3).any(|i| segment_triangle(&t1[i], &t1[(i + 1) % 3], t0))
+}
+
+/// Check if triangles `e0` and `e1`, with coordinates `t0` and `t1`, intersect, depending on
+/// the number of common vertices (see `find_self_intersections`)
+fn elems_intersect(e0: &Triangle, t0: &[Point<3>; 3], e1: &Triangle, t1: &[Point<3>; 3]) -> bool {
+    let shared = e0.iter().filter(|&&k| e1.contains_vertex(k)).count();
+    match shared {
+        0 => triangles_intersect(t0, t1),
+        1 => {
+            let k0 = (0..3).find(|&k| e1.contains_vertex(e0[k])).unwrap();
+            let k1 = (0..3).find(|&k| e0.contains_vertex(e1[k])).unwrap();
+            segment_triangle(&t0[(k0 + 1) % 3], &t0[(k0 + 2) % 3], t1)
+                || segment_triangle(&t1[(k1 + 1) % 3], &t1[(k1 + 2) % 3], t0)
+        }
+        2 => {
+            let k0 = (0..3).find(|&k| !e1.contains_vertex(e0[k])).unwrap();
+            let k1 = (0..3).find(|&k| !e0.contains_vertex(e1[k])).unwrap();
+            let (a, b) = (&t0[(k0 + 1) % 3], &t0[(k0 + 2) % 3]);
+            let (c, d) = (&t0[k0], &t1[k1]);
+            if orient3d(a, b, c, d) != 0 {
+                return false;
+            }
+            let axis = projection_axis(t0);
+            orient2d_projected(a, b, c, axis) * orient2d_projected(a, b, d, axis) > 0
+        }
+        _ => true,
+    }
+}
+
+impl SimplexMesh<3, Triangle> {
+    /// Find the pairs of intersecting triangles, sorted by increasing indices. The candidate
+    /// pairs are found using an `ElementTree`, and exact predicates are used to check the
+    /// intersections. The neighboring triangles are considered as intersecting if
+    ///  - they share 3 vertices (duplicated triangles),
+    ///  - they share an edge and overlap, i.e. they are coplanar and on the same side of the
+    ///    edge,
+    ///  - they share a vertex and the edge opposite to this vertex in one of the triangles
+    ///    intersects the other triangle.
+    #[must_use]
+    pub fn find_self_intersections(&self) -> Vec<(Idx, Idx)> {
+        debug!("Find the self-intersections of the surface");
+
+        let tree = self.compute_element_tree();
+        let coords = |e: Triangle| [self.vert(e[0]), self.vert(e[1]), self.vert(e[2])];
+
+        let mut res = (0..self.n_elems())
+            .into_par_iter()
+            .flat_map_iter(|i| {
+                let e0 = self.elem(i);
+                let t0 = coords(e0);
+                let min = t0[1].inf(&t0[0]).inf(&t0[2]);
+                let max = t0[1].sup(&t0[0]).sup(&t0[2]);
+                tree.intersecting_elements(&min, &max)
+                    .into_iter()
+                    .filter(move |&j| j > i)
+                    .filter(move |&j| {
+                        let e1 = self.elem(j);
+                        elems_intersect(&e0, &t0, &e1, &coords(e1))
+                    })
+                    .map(move |j| (i, j))
+            })
+            .collect::<Vec<_>>();
+        res.sort_unstable();
+        debug!("{} intersecting pairs found", res.len());
+
+        res
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        mesh::{test_meshes::test_mesh_3d, Point, SimplexMesh, Triangle},
+        Idx,
+    };
+
+    fn surface(verts: &[[f64; 3]], tris: &[[Idx; 3]]) -> SimplexMesh<3, Triangle> {
+        SimplexMesh::new(
+            verts
+                .iter()
+                .map(|p| Point::<3>::new(p[0], p[1], p[2]))
+                .collect(),
+            tris.iter()
+                .map(|t| Triangle::new(t[0], t[1], t[2]))
+                .collect(),
+            vec![1; tris.len()],
+            Vec::new(),
+            Vec::new(),
+        )
+    }
+
+    #[test]
+    fn test_closed_surface() {
+        let mesh = test_mesh_3d().split().split();
+        let (bdy, _) = mesh.boundary();
+        assert!(bdy.find_self_intersections().is_empty());
+    }
+
+    #[test]
+    fn test_crossing_triangles() {
+        let verts = [
+            [0.0, 0.0, 0.0],
+            [1.0, 0.0, 0.0],
+            [0.0, 1.0, 0.0],
+            [0.25, 0.25, -1.0],
+            [0.25, 0.25, 1.0],
+            [2.0, 2.0, 0.0],
+            [5.0, 5.0, 5.0],
+            [6.0, 5.0, 5.0],
+            [5.0, 6.0, 5.0],
+        ];
+        let mesh = surface(&verts, &[[0, 1, 2], [3, 4, 5], [6, 7, 8]]);
+        assert_eq!(mesh.find_self_intersections(), vec![(0, 1)]);
+
+        // coplanar overlapping triangles
+        let verts = [
+            [0.0, 0.0, 0.0],
+            [1.0, 0.0, 0.0],
+            [0.0, 1.0, 0.0],
+            [0.1, 0.1, 0.0],
+            [0.5, 0.1, 0.0],
+            [0.1, 0.5, 0.0],
+            [1.0, 1.0, 0.0],
+            [2.0, 1.0, 0.0],
+            [1.0, 2.0, 0.0],
+        ];
+        let mesh = surface(&verts, &[[0, 1, 2], [3, 4, 5], [6, 7, 8]]);
+        assert_eq!(mesh.find_self_intersections(), vec![(0, 1)]);
+    }
+
+    #[test]
+    fn test_neighbors() {
+        // common edge
+        let mut verts = vec![
+            [0.0, 0.0, 0.0],
+            [1.0, 0.0, 0.0],
+            [0.0, 1.0, 0.0],
+            [0.3, 0.3, 0.0],
+        ];
+        let tris = [[0, 1, 2], [0, 1, 3]];
+        assert_eq!(
+            surface(&verts, &tris).find_self_intersections(),
+            vec![(0, 1)]
+        );
+        verts[3] = [0.3, -0.3, 0.0];
+        assert!(surface(&verts, &tris).find_self_intersections().is_empty());
+        verts[3] = [0.3, 0.3, 0.1];
+        assert!(surface(&verts, &tris).find_self_intersections().is_empty());
+
+        // common vertex
+        let mut verts = vec![
+            [0.0, 0.0, 0.0],
+            [1.0, 0.0, 0.0],
+            [0.0, 1.0, 0.0],
+            [0.3, 0.3, -1.0],
+            [0.3, 0.3, 1.0],
+        ];
+        let tris = [[0, 1, 2], [0, 3, 4]];
+        assert_eq!(
+            surface(&verts, &tris).find_self_intersections(),
+            vec![(0, 1)]
+        );
+        verts[3] = [0.6, 0.2, 1.0];
+        assert!(surface(&verts, &tris).find_self_intersections().is_empty());
+
+        // duplicated triangles
+        let tris = [[0, 1, 2], [1, 2, 0]];
+        assert_eq!(
+            surface(&verts, &tris).find_self_intersections(),
+            vec![(0, 1)]
+        );
+    }
+}
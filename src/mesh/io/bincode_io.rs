@@ -0,0 +1,195 @@
+use crate::{
+    mesh::{Elem, SimplexMesh},
+    Error, Result,
+};
+
+#[cfg(feature = "serialize")]
+use crate::{
+    mesh::{Point, TagNames},
+    Idx, Tag,
+};
+#[cfg(feature = "serialize")]
+use log::debug;
+#[cfg(feature = "serialize")]
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+#[cfg(feature = "serialize")]
+use std::{
+    fs::File,
+    io::{BufReader, BufWriter, Write},
+};
+
+/// Serialized representation of a `SimplexMesh`: the vertices, elements, faces, tags, tag names
+/// and partition. The other connectivities are not stored and must be recomputed if needed
+#[cfg(feature = "serialize")]
+#[derive(Serialize, Deserialize)]
+struct SerializedMesh {
+    dim: usize,
+    elem_n_verts: Idx,
+    verts: Vec<f64>,
+    elems: Vec<Idx>,
+    etags: Vec<Tag>,
+    faces: Vec<Idx>,
+    ftags: Vec<Tag>,
+    tag_names: TagNames,
+    partition: Option<Vec<Tag>>,
+}
+
+#[cfg(feature = "serialize")]
+impl<const D: usize, E: Elem> Serialize for SimplexMesh<D, E> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error> {
+        let mut verts = Vec::with_capacity(D * self.n_verts() as usize);
+        self.verts().for_each(|p| verts.extend(p.iter()));
+        SerializedMesh {
+            dim: D,
+            elem_n_verts: E::N_VERTS,
+            verts,
+            elems: self.elems().flatten().collect(),
+            etags: self.etags().collect(),
+            faces: self.faces().flatten().collect(),
+            ftags: self.ftags().collect(),
+            tag_names: self.tag_names().clone(),
+            partition: self.get_partition().ok().map(<[Tag]>::to_vec),
+        }
+        .serialize(serializer)
+    }
+}
+
+#[cfg(feature = "serialize")]
+impl<'de, const D: usize, E: Elem> Deserialize<'de> for SimplexMesh<D, E> {
+    fn deserialize<De: Deserializer<'de>>(
+        deserializer: De,
+    ) -> std::result::Result<Self, De::Error> {
+        use serde::de::Error as _;
+
+        let m = SerializedMesh::deserialize(deserializer)?;
+        if m.dim != D || m.elem_n_verts != E::N_VERTS {
+            return Err(De::Error::custom("invalid mesh dimension or element type"));
+        }
+        let n = E::N_VERTS as usize;
+        let n_faces = E::Face::N_VERTS as usize;
+        if m.verts.len() % D != 0
+            || m.elems.len() != n * m.etags.len()
+            || m.faces.len() != n_faces * m.ftags.len()
+        {
+            return Err(De::Error::custom("invalid mesh sizes"));
+        }
+
+        let mut res = Self::new(
+            m.verts
+                .chunks(D)
+                .map(Point::<D>::from_column_slice)
+                .collect(),
+            m.elems.chunks(n).map(E::from_slice).collect(),
+            m.etags,
+            m.faces.chunks(n_faces).map(E::Face::from_slice).collect(),
+            m.ftags,
+        );
+        *res.tag_names_mut() = m.tag_names;
+        if let Some(partition) = m.partition {
+            res.set_partition(partition).map_err(De::Error::custom)?;
+        }
+        Ok(res)
+    }
+}
+
+impl<const D: usize, E: Elem> SimplexMesh<D, E> {
+    #[cfg(not(feature = "serialize"))]
+    pub fn write_bincode(&self, _file_name: &str) -> Result<()> {
+        Err(Error::from("the serialize feature is not enabled"))
+    }
+
+    /// Write the mesh in a binary file using `bincode` (see `Serialize` for what is stored), e.g.
+    /// to checkpoint an adaptation loop
+    #[cfg(feature = "serialize")]
+    pub fn write_bincode(&self, file_name: &str) -> Result<()> {
+        debug!("Write {file_name}");
+
+        let mut file = BufWriter::new(File::create(file_name)?);
+        bincode::serialize_into(&mut file, self)?;
+        file.flush()?;
+
+        Ok(())
+    }
+
+    #[cfg(not(feature = "serialize"))]
+    pub fn read_bincode(_file_name: &str) -> Result<Self> {
+        Err(Error::from("the serialize feature is not enabled"))
+    }
+
+    /// Read a mesh written by `write_bincode`
+    #[cfg(feature = "serialize")]
+    pub fn read_bincode(file_name: &str) -> Result<Self> {
+        debug!("Read {file_name}");
+
+        let file = BufReader::new(File::open(file_name)?);
+        let res = bincode::deserialize_from(file)?;
+
+        Ok(res)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::mesh::{test_meshes::test_mesh_3d, SimplexMesh, Tetrahedron};
+
+    #[cfg(not(feature = "serialize"))]
+    #[test]
+    fn test_bincode_disabled() {
+        let mesh = test_mesh_3d();
+        assert!(mesh.write_bincode("mesh.bin").is_err());
+        assert!(SimplexMesh::<3, Tetrahedron>::read_bincode("mesh.bin").is_err());
+    }
+
+    #[cfg(feature = "serialize")]
+    #[test]
+    fn test_bincode() -> crate::Result<()> {
+        use crate::{
+            mesh::{test_meshes::test_mesh_2d, PartitionType, Triangle},
+            metric::{AnisoMetric3d, Metric},
+            remesher::RemesherParams,
+        };
+        use tempfile::NamedTempFile;
+
+        let mut mesh = test_mesh_3d().split().split();
+        mesh.tag_names_mut().set_face_name(1, "wall");
+        mesh.partition(PartitionType::Hilbert(3))?;
+
+        let file = NamedTempFile::new().unwrap();
+        let fname = file.path().to_str().unwrap().to_owned() + ".bin";
+        mesh.write_bincode(&fname)?;
+        let mesh2 = SimplexMesh::<3, Tetrahedron>::read_bincode(&fname)?;
+
+        assert_eq!(mesh.n_verts(), mesh2.n_verts());
+        for (p0, p1) in mesh.verts().zip(mesh2.verts()) {
+            assert!((p0 - p1).norm() < 1e-12);
+        }
+        assert!(mesh.elems().zip(mesh2.elems()).all(|(e0, e1)| e0 == e1));
+        assert!(mesh.etags().zip(mesh2.etags()).all(|(t0, t1)| t0 == t1));
+        assert!(mesh.faces().zip(mesh2.faces()).all(|(f0, f1)| f0 == f1));
+        assert!(mesh.ftags().zip(mesh2.ftags()).all(|(t0, t1)| t0 == t1));
+        assert_eq!(mesh2.tag_names().face_name(1), Some("wall"));
+        assert_eq!(mesh.get_partition()?, mesh2.get_partition()?);
+
+        // dimension mismatch
+        assert!(SimplexMesh::<2, Triangle>::read_bincode(&fname).is_err());
+
+        // metrics and parameters
+        let m = AnisoMetric3d::from_slice(&[1.0, 2.0, 3.0, 0.1, 0.2, 0.3]);
+        let m2: AnisoMetric3d = bincode::deserialize(&bincode::serialize(&m)?)?;
+        assert!(m.into_iter().zip(m2).all(|(a, b)| (a - b).abs() < 1e-12));
+
+        let params = RemesherParams {
+            num_iter: 7,
+            ..RemesherParams::default()
+        };
+        let params2: RemesherParams = bincode::deserialize(&bincode::serialize(&params)?)?;
+        assert_eq!(params2.num_iter, 7);
+        assert_eq!(params2.smooth_relax, params.smooth_relax);
+
+        let mesh = test_mesh_2d();
+        let mesh2: SimplexMesh<2, Triangle> = bincode::deserialize(&bincode::serialize(&mesh)?)?;
+        assert_eq!(mesh2.n_elems(), 2);
+
+        Ok(())
+    }
+}
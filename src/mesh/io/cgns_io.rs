@@ -0,0 +1,811 @@
+use crate::{
+    mesh::{Elem, SimplexMesh},
+    Error, Result,
+};
+
+#[cfg(feature = "cgns")]
+use crate::{mesh::Point, Idx, Tag};
+#[cfg(feature = "cgns")]
+use log::{debug, warn};
+#[cfg(feature = "cgns")]
+use std::{
+    ffi::{CStr, CString},
+    os::raw::{c_char, c_int},
+};
+
+impl<const D: usize, E: Elem> SimplexMesh<D, E> {
+    #[cfg(not(feature = "cgns"))]
+    pub fn write_cgns(&self, _file_name: &str) -> Result<()> {
+        Err(Error::from("the cgns feature is not enabled"))
+    }
+
+    /// Write the mesh in a CGNS file, as a single unstructured zone. The elements are stored in
+    /// one section per element tag, and the faces in one section per face tag together with a
+    /// boundary condition. The face tag names (see `tag_names`) are used as family names, if
+    /// defined
+    #[cfg(feature = "cgns")]
+    pub fn write_cgns(&self, file_name: &str) -> Result<()> {
+        debug!("Write {file_name}");
+
+        let elem_type = cgns::elem_type(E::N_VERTS)?;
+        let face_type = cgns::elem_type(E::Face::N_VERTS)?;
+        let bc_location = if E::DIM == 3 {
+            cgns::FACE_CENTER
+        } else {
+            cgns::EDGE_CENTER
+        };
+
+        let file = cgns::File::open(file_name, cgns::MODE_WRITE)?;
+        let f = file.0;
+
+        let mut b = 0;
+        let name = CString::new("Base")?;
+        cgns::check(unsafe {
+            cgns::cg_base_write(f, name.as_ptr(), E::DIM as c_int, D as c_int, &mut b)
+        })?;
+
+        let mut z = 0;
+        let size = [cgns::size(self.n_verts())?, cgns::size(self.n_elems())?, 0];
+        let name = CString::new("Zone")?;
+        cgns::check(unsafe {
+            cgns::cg_zone_write(
+                f,
+                b,
+                name.as_ptr(),
+                size.as_ptr(),
+                cgns::UNSTRUCTURED,
+                &mut z,
+            )
+        })?;
+
+        for (i, coord) in ["CoordinateX", "CoordinateY", "CoordinateZ"]
+            .iter()
+            .enumerate()
+            .take(D)
+        {
+            let x = self.verts().map(|p| p[i]).collect::<Vec<_>>();
+            let name = CString::new(*coord)?;
+            let mut c = 0;
+            cgns::check(unsafe {
+                cgns::cg_coord_write(
+                    f,
+                    b,
+                    z,
+                    cgns::REAL_DOUBLE,
+                    name.as_ptr(),
+                    x.as_ptr().cast(),
+                    &mut c,
+                )
+            })?;
+        }
+
+        let mut etags = self.etags().collect::<Vec<_>>();
+        etags.sort_unstable();
+        etags.dedup();
+        let mut ftags = self.ftags().collect::<Vec<_>>();
+        ftags.sort_unstable();
+        ftags.dedup();
+
+        let mut start: cgns::CgSize = 1;
+        for &tag in &etags {
+            let conn = self
+                .elems()
+                .zip(self.etags())
+                .filter(|&(_, t)| t == tag)
+                .flat_map(|(e, _)| e)
+                .map(|i| Ok(cgns::size(i)? + 1))
+                .collect::<Result<Vec<_>>>()?;
+            let n = cgns::size(conn.len() / E::N_VERTS as usize)?;
+            let name = CString::new(format!("Elements_{tag}"))?;
+            let mut s = 0;
+            cgns::check(unsafe {
+                cgns::cg_section_write(
+                    f,
+                    b,
+                    z,
+                    name.as_ptr(),
+                    elem_type,
+                    start,
+                    start + n - 1,
+                    0,
+                    conn.as_ptr(),
+                    &mut s,
+                )
+            })?;
+            start += n;
+        }
+
+        for &tag in &ftags {
+            let conn = self
+                .faces()
+                .zip(self.ftags())
+                .filter(|&(_, t)| t == tag)
+                .flat_map(|(e, _)| e)
+                .map(|i| Ok(cgns::size(i)? + 1))
+                .collect::<Result<Vec<_>>>()?;
+            let n = cgns::size(conn.len() / E::Face::N_VERTS as usize)?;
+            let name = self
+                .tag_names()
+                .face_name(tag)
+                .map_or_else(|| format!("Boundary_{tag}"), str::to_string);
+            let c_name = CString::new(name.as_str())?;
+            let mut s = 0;
+            cgns::check(unsafe {
+                cgns::cg_section_write(
+                    f,
+                    b,
+                    z,
+                    c_name.as_ptr(),
+                    face_type,
+                    start,
+                    start + n - 1,
+                    0,
+                    conn.as_ptr(),
+                    &mut s,
+                )
+            })?;
+
+            let range = [start, start + n - 1];
+            let mut bc = 0;
+            cgns::check(unsafe {
+                cgns::cg_boco_write(
+                    f,
+                    b,
+                    z,
+                    c_name.as_ptr(),
+                    cgns::BC_TYPE_USER_DEFINED,
+                    cgns::POINT_RANGE,
+                    2,
+                    range.as_ptr(),
+                    &mut bc,
+                )
+            })?;
+            cgns::check(unsafe { cgns::cg_boco_gridlocation_write(f, b, z, bc, bc_location) })?;
+
+            // the tag is stored as a descriptor since the name may be a tag name
+            cgns::goto_boco(f, b, z, bc)?;
+            let descr_name = CString::new(cgns::TAG_DESCRIPTOR)?;
+            let descr_text = CString::new(tag.to_string())?;
+            cgns::check(unsafe {
+                cgns::cg_descriptor_write(descr_name.as_ptr(), descr_text.as_ptr())
+            })?;
+
+            if self.tag_names().face_name(tag).is_some() {
+                let mut fam = 0;
+                cgns::check(unsafe { cgns::cg_family_write(f, b, c_name.as_ptr(), &mut fam) })?;
+                cgns::goto_boco(f, b, z, bc)?;
+                cgns::check(unsafe { cgns::cg_famname_write(c_name.as_ptr()) })?;
+            }
+            start += n;
+        }
+
+        Ok(())
+    }
+
+    #[cfg(not(feature = "cgns"))]
+    pub fn read_cgns(_file_name: &str) -> Result<Self> {
+        Err(Error::from("the cgns feature is not enabled"))
+    }
+
+    /// Read a mesh from the first unstructured zone of a CGNS file. The elements of all the
+    /// sections with the right type (e.g. TETRA_4 in 3D) are read, and tagged by section (the
+    /// tag is `i` for the sections named `Elements_i`, the section index otherwise). The faces are
+    /// read from the boundary conditions (faces not referenced by a boundary condition are ignored),
+    /// and tagged by the `Tag` descriptor of the boundary condition written by `write_cgns` (or `i`
+    /// for the boundary conditions named `Boundary_i`, the boundary condition index otherwise).
+    /// The family names of the boundary conditions (or
+    /// their names) are stored as face tag names (see `tag_names`)
+    #[cfg(feature = "cgns")]
+    #[allow(clippy::too_many_lines)]
+    pub fn read_cgns(file_name: &str) -> Result<Self> {
+        debug!("Read {file_name}");
+
+        let elem_type = cgns::elem_type(E::N_VERTS)?;
+        let face_type = cgns::elem_type(E::Face::N_VERTS)?;
+
+        let file = cgns::File::open(file_name, cgns::MODE_READ)?;
+        let f = file.0;
+        let b = 1;
+
+        let mut name = [0 as c_char; 33];
+        let (mut cell_dim, mut phys_dim) = (0, 0);
+        cgns::check(unsafe {
+            cgns::cg_base_read(f, b, name.as_mut_ptr(), &mut cell_dim, &mut phys_dim)
+        })?;
+        if cell_dim != E::DIM as c_int {
+            return Err(Error::from("cgns: invalid cell dimension"));
+        }
+
+        let mut n_zones = 0;
+        cgns::check(unsafe { cgns::cg_nzones(f, b, &mut n_zones) })?;
+        if n_zones > 1 {
+            warn!("cgns: only the first zone is read");
+        }
+        let z = 1;
+        let mut zone_type = 0;
+        cgns::check(unsafe { cgns::cg_zone_type(f, b, z, &mut zone_type) })?;
+        if zone_type != cgns::UNSTRUCTURED {
+            return Err(Error::from("cgns: only unstructured zones are supported"));
+        }
+        let mut size: [cgns::CgSize; 3] = [0; 3];
+        cgns::check(unsafe { cgns::cg_zone_read(f, b, z, name.as_mut_ptr(), size.as_mut_ptr()) })?;
+        let n_verts = size[0] as usize;
+
+        // Vertices
+        let mut verts = vec![Point::<D>::zeros(); n_verts];
+        for (i, coord) in ["CoordinateX", "CoordinateY", "CoordinateZ"]
+            .iter()
+            .enumerate()
+            .take(phys_dim as usize)
+        {
+            let mut x = vec![0.0; n_verts];
+            let c_name = CString::new(*coord)?;
+            let (r_min, r_max): (cgns::CgSize, cgns::CgSize) = (1, size[0]);
+            cgns::check(unsafe {
+                cgns::cg_coord_read(
+                    f,
+                    b,
+                    z,
+                    c_name.as_ptr(),
+                    cgns::REAL_DOUBLE,
+                    &r_min,
+                    &r_max,
+                    x.as_mut_ptr().cast(),
+                )
+            })?;
+            if i < D {
+                verts.iter_mut().zip(x).for_each(|(p, x)| p[i] = x);
+            }
+        }
+
+        // Sections
+        let mut elems = Vec::new();
+        let mut etags = Vec::new();
+        let mut faces = Vec::new();
+        // global element index -> face index
+        let mut face_ids = Vec::new();
+
+        let mut n_sections = 0;
+        cgns::check(unsafe { cgns::cg_nsections(f, b, z, &mut n_sections) })?;
+        let mut i_elem_section: Tag = 0;
+        for s in 1..=n_sections {
+            let mut etype = 0;
+            let (mut start, mut end) = (0, 0);
+            let (mut n_bdy, mut parent_flag) = (0, 0);
+            cgns::check(unsafe {
+                cgns::cg_section_read(
+                    f,
+                    b,
+                    z,
+                    s,
+                    name.as_mut_ptr(),
+                    &mut etype,
+                    &mut start,
+                    &mut end,
+                    &mut n_bdy,
+                    &mut parent_flag,
+                )
+            })?;
+            let section_name = unsafe { CStr::from_ptr(name.as_ptr()) }.to_string_lossy();
+            let n = (end - start + 1) as usize;
+            if etype != elem_type && etype != face_type {
+                warn!("cgns: skip section {section_name} (element type {etype})");
+                continue;
+            }
+            let mut data_size = 0;
+            cgns::check(unsafe { cgns::cg_ElementDataSize(f, b, z, s, &mut data_size) })?;
+            let mut conn = vec![0; data_size as usize];
+            cgns::check(unsafe {
+                cgns::cg_elements_read(f, b, z, s, conn.as_mut_ptr(), std::ptr::null_mut())
+            })?;
+            let conn = conn.iter().map(|&i| (i - 1) as Idx).collect::<Vec<_>>();
+            if etype == elem_type {
+                i_elem_section += 1;
+                let tag = section_name
+                    .strip_prefix("Elements_")
+                    .and_then(|t| t.parse().ok())
+                    .unwrap_or(i_elem_section);
+                elems.extend(conn.chunks(E::N_VERTS as usize).map(E::from_slice));
+                etags.extend(std::iter::repeat_n(tag, n));
+            } else {
+                let end = end as usize;
+                if face_ids.len() < end {
+                    face_ids.resize(end, None);
+                }
+                for (i, f) in conn.chunks(E::Face::N_VERTS as usize).enumerate() {
+                    face_ids[start as usize - 1 + i] = Some(faces.len());
+                    faces.push(E::Face::from_slice(f));
+                }
+            }
+        }
+
+        // Boundary conditions
+        let mut ftags = vec![None; faces.len()];
+        let mut tag_names = Vec::new();
+        let mut n_bcs = 0;
+        cgns::check(unsafe { cgns::cg_nbocos(f, b, z, &mut n_bcs) })?;
+        for bc in 1..=n_bcs {
+            let mut bc_type = 0;
+            let mut ptset_type = 0;
+            let mut n_pts = 0;
+            let mut normal_index = [0; 3];
+            let mut normal_size = 0;
+            let mut normal_type = 0;
+            let mut n_datasets = 0;
+            cgns::check(unsafe {
+                cgns::cg_boco_info(
+                    f,
+                    b,
+                    z,
+                    bc,
+                    name.as_mut_ptr(),
+                    &mut bc_type,
+                    &mut ptset_type,
+                    &mut n_pts,
+                    normal_index.as_mut_ptr(),
+                    &mut normal_size,
+                    &mut normal_type,
+                    &mut n_datasets,
+                )
+            })?;
+            let bc_name = unsafe { CStr::from_ptr(name.as_ptr()) }
+                .to_string_lossy()
+                .to_string();
+            let tag = cgns::boco_tag(f, b, z, bc)?
+                .or_else(|| {
+                    bc_name
+                        .strip_prefix("Boundary_")
+                        .and_then(|t| t.parse().ok())
+                })
+                .unwrap_or(bc as Tag);
+            let mut location = 0;
+            cgns::check(unsafe { cgns::cg_boco_gridlocation_read(f, b, z, bc, &mut location) })?;
+
+            let is_elem_set = matches!(ptset_type, cgns::ELEMENT_RANGE | cgns::ELEMENT_LIST);
+            if !is_elem_set && location == cgns::VERTEX {
+                warn!("cgns: skip boundary condition {bc_name} (defined at the vertices)");
+                continue;
+            }
+            let mut pts = vec![0; n_pts as usize];
+            cgns::check(unsafe {
+                cgns::cg_boco_read(f, b, z, bc, pts.as_mut_ptr(), std::ptr::null_mut())
+            })?;
+            let ids: Vec<cgns::CgSize> = match ptset_type {
+                cgns::POINT_RANGE | cgns::ELEMENT_RANGE => (pts[0]..=pts[1]).collect(),
+                cgns::POINT_LIST | cgns::ELEMENT_LIST => pts,
+                _ => {
+                    warn!("cgns: skip boundary condition {bc_name} (point set type {ptset_type})");
+                    continue;
+                }
+            };
+            for i in ids {
+                let face = face_ids.get(i as usize - 1).copied().flatten();
+                let face = face.ok_or_else(|| {
+                    Error::from(&format!(
+                        "cgns: invalid face {i} in boundary condition {bc_name}"
+                    ))
+                })?;
+                ftags[face] = Some(tag);
+            }
+
+            let fam_name = cgns::goto_boco(f, b, z, bc).ok().and_then(|()| {
+                let mut fam = [0 as c_char; 33];
+                (unsafe { cgns::cg_famname_read(fam.as_mut_ptr()) } == 0).then(|| {
+                    unsafe { CStr::from_ptr(fam.as_ptr()) }
+                        .to_string_lossy()
+                        .to_string()
+                })
+            });
+            tag_names.push((tag, fam_name.unwrap_or(bc_name)));
+        }
+
+        let n_ignored = ftags.iter().filter(|t| t.is_none()).count();
+        if n_ignored > 0 {
+            warn!("cgns: {n_ignored} faces are not in a boundary condition and are ignored");
+        }
+        let (faces, ftags): (Vec<_>, Vec<_>) = faces
+            .into_iter()
+            .zip(ftags)
+            .filter_map(|(f, t)| t.map(|t| (f, t)))
+            .unzip();
+
+        let mut res = Self::new(verts, elems, etags, faces, ftags);
+        for (tag, name) in tag_names {
+            res.tag_names_mut().set_face_name(tag, &name);
+        }
+
+        Ok(res)
+    }
+}
+
+#[cfg(feature = "cgns")]
+mod cgns {
+    use crate::{Error, Idx, Result, Tag};
+    use std::{
+        ffi::{CStr, CString},
+        os::raw::{c_char, c_int, c_void},
+    };
+
+    /// `cgsize_t`: 64-bit integers unless the CGNS library was built without 64-bit support
+    /// (detected by build.rs from `cgnsconfig.h`)
+    #[cfg(not(cgns_32bit))]
+    pub type CgSize = i64;
+    #[cfg(cgns_32bit)]
+    pub type CgSize = i32;
+
+    /// Name of the descriptor used to store the face tag of a boundary condition
+    pub const TAG_DESCRIPTOR: &str = "Tag";
+
+    pub const MODE_READ: c_int = 0;
+    pub const MODE_WRITE: c_int = 1;
+    pub const UNSTRUCTURED: c_int = 3;
+    pub const REAL_DOUBLE: c_int = 4;
+    pub const BAR_2: c_int = 3;
+    pub const TRI_3: c_int = 5;
+    pub const TETRA_4: c_int = 10;
+    pub const BC_TYPE_USER_DEFINED: c_int = 1;
+    pub const POINT_LIST: c_int = 2;
+    pub const POINT_RANGE: c_int = 4;
+    pub const ELEMENT_RANGE: c_int = 6;
+    pub const ELEMENT_LIST: c_int = 7;
+    pub const VERTEX: c_int = 2;
+    pub const FACE_CENTER: c_int = 4;
+    pub const EDGE_CENTER: c_int = 8;
+
+    #[link(name = "cgns")]
+    extern "C" {
+        pub fn cg_open(filename: *const c_char, mode: c_int, fn_: *mut c_int) -> c_int;
+        pub fn cg_close(fn_: c_int) -> c_int;
+        pub fn cg_get_error() -> *const c_char;
+        pub fn cg_base_write(
+            fn_: c_int,
+            basename: *const c_char,
+            cell_dim: c_int,
+            phys_dim: c_int,
+            b: *mut c_int,
+        ) -> c_int;
+        pub fn cg_base_read(
+            fn_: c_int,
+            b: c_int,
+            basename: *mut c_char,
+            cell_dim: *mut c_int,
+            phys_dim: *mut c_int,
+        ) -> c_int;
+        pub fn cg_nzones(fn_: c_int, b: c_int, nzones: *mut c_int) -> c_int;
+        pub fn cg_zone_type(fn_: c_int, b: c_int, z: c_int, zonetype: *mut c_int) -> c_int;
+        pub fn cg_zone_write(
+            fn_: c_int,
+            b: c_int,
+            zonename: *const c_char,
+            size: *const CgSize,
+            zonetype: c_int,
+            z: *mut c_int,
+        ) -> c_int;
+        pub fn cg_zone_read(
+            fn_: c_int,
+            b: c_int,
+            z: c_int,
+            zonename: *mut c_char,
+            size: *mut CgSize,
+        ) -> c_int;
+        pub fn cg_coord_write(
+            fn_: c_int,
+            b: c_int,
+            z: c_int,
+            datatype: c_int,
+            coordname: *const c_char,
+            coord: *const c_void,
+            c: *mut c_int,
+        ) -> c_int;
+        #[allow(clippy::too_many_arguments)]
+        pub fn cg_coord_read(
+            fn_: c_int,
+            b: c_int,
+            z: c_int,
+            coordname: *const c_char,
+            datatype: c_int,
+            rmin: *const CgSize,
+            rmax: *const CgSize,
+            coord: *mut c_void,
+        ) -> c_int;
+        #[allow(clippy::too_many_arguments)]
+        pub fn cg_section_write(
+            fn_: c_int,
+            b: c_int,
+            z: c_int,
+            sectionname: *const c_char,
+            etype: c_int,
+            start: CgSize,
+            end: CgSize,
+            nbndry: c_int,
+            elements: *const CgSize,
+            s: *mut c_int,
+        ) -> c_int;
+        pub fn cg_nsections(fn_: c_int, b: c_int, z: c_int, nsections: *mut c_int) -> c_int;
+        #[allow(clippy::too_many_arguments)]
+        pub fn cg_section_read(
+            fn_: c_int,
+            b: c_int,
+            z: c_int,
+            s: c_int,
+            sectionname: *mut c_char,
+            etype: *mut c_int,
+            start: *mut CgSize,
+            end: *mut CgSize,
+            nbndry: *mut c_int,
+            parent_flag: *mut c_int,
+        ) -> c_int;
+        #[allow(non_snake_case)]
+        pub fn cg_ElementDataSize(
+            fn_: c_int,
+            b: c_int,
+            z: c_int,
+            s: c_int,
+            size: *mut CgSize,
+        ) -> c_int;
+        pub fn cg_elements_read(
+            fn_: c_int,
+            b: c_int,
+            z: c_int,
+            s: c_int,
+            elements: *mut CgSize,
+            parent_data: *mut CgSize,
+        ) -> c_int;
+        #[allow(clippy::too_many_arguments)]
+        pub fn cg_boco_write(
+            fn_: c_int,
+            b: c_int,
+            z: c_int,
+            boconame: *const c_char,
+            bocotype: c_int,
+            ptset_type: c_int,
+            npnts: CgSize,
+            pnts: *const CgSize,
+            bc: *mut c_int,
+        ) -> c_int;
+        pub fn cg_boco_gridlocation_write(
+            fn_: c_int,
+            b: c_int,
+            z: c_int,
+            bc: c_int,
+            location: c_int,
+        ) -> c_int;
+        pub fn cg_nbocos(fn_: c_int, b: c_int, z: c_int, nbocos: *mut c_int) -> c_int;
+        #[allow(clippy::too_many_arguments)]
+        pub fn cg_boco_info(
+            fn_: c_int,
+            b: c_int,
+            z: c_int,
+            bc: c_int,
+            boconame: *mut c_char,
+            bocotype: *mut c_int,
+            ptset_type: *mut c_int,
+            npnts: *mut CgSize,
+            normal_index: *mut c_int,
+            normal_list_size: *mut CgSize,
+            normal_data_type: *mut c_int,
+            ndataset: *mut c_int,
+        ) -> c_int;
+        pub fn cg_boco_gridlocation_read(
+            fn_: c_int,
+            b: c_int,
+            z: c_int,
+            bc: c_int,
+            location: *mut c_int,
+        ) -> c_int;
+        pub fn cg_boco_read(
+            fn_: c_int,
+            b: c_int,
+            z: c_int,
+            bc: c_int,
+            pnts: *mut CgSize,
+            normal_list: *mut c_void,
+        ) -> c_int;
+        pub fn cg_family_write(
+            fn_: c_int,
+            b: c_int,
+            family_name: *const c_char,
+            fam: *mut c_int,
+        ) -> c_int;
+        pub fn cg_famname_write(family_name: *const c_char) -> c_int;
+        pub fn cg_famname_read(family_name: *mut c_char) -> c_int;
+        pub fn cg_goto(fn_: c_int, b: c_int, ...) -> c_int;
+        pub fn cg_descriptor_write(descr_name: *const c_char, descr_text: *const c_char) -> c_int;
+        pub fn cg_ndescriptors(ndescriptors: *mut c_int) -> c_int;
+        pub fn cg_descriptor_read(
+            descr_no: c_int,
+            descr_name: *mut c_char,
+            descr_text: *mut *mut c_char,
+        ) -> c_int;
+        pub fn cg_free(data: *mut c_void) -> c_int;
+    }
+
+    /// Convert an index or a size to `CgSize`
+    pub fn size<I: TryInto<CgSize>>(i: I) -> Result<CgSize> {
+        i.try_into()
+            .map_err(|_| Error::from("cgns: index too large for cgsize_t"))
+    }
+
+    /// Get the face tag stored in the `Tag` descriptor of boundary condition `bc`, if any
+    pub fn boco_tag(f: c_int, b: c_int, z: c_int, bc: c_int) -> Result<Option<Tag>> {
+        goto_boco(f, b, z, bc)?;
+        let mut n = 0;
+        check(unsafe { cg_ndescriptors(&mut n) })?;
+        for i in 1..=n {
+            let mut name = [0 as c_char; 33];
+            let mut text = std::ptr::null_mut();
+            check(unsafe { cg_descriptor_read(i, name.as_mut_ptr(), &mut text) })?;
+            let value = unsafe { CStr::from_ptr(text) }
+                .to_string_lossy()
+                .to_string();
+            unsafe { cg_free(text.cast()) };
+            if unsafe { CStr::from_ptr(name.as_ptr()) }.to_bytes() == TAG_DESCRIPTOR.as_bytes() {
+                return value
+                    .trim()
+                    .parse()
+                    .map(Some)
+                    .map_err(|_| Error::from(&format!("cgns: invalid tag {value}")));
+            }
+        }
+        Ok(None)
+    }
+
+    /// Check the return value of a CGNS function
+    pub fn check(ier: c_int) -> Result<()> {
+        if ier == 0 {
+            Ok(())
+        } else {
+            let msg = unsafe { CStr::from_ptr(cg_get_error()) }.to_string_lossy();
+            Err(Error::from(&format!("cgns: {msg}")))
+        }
+    }
+
+    /// CGNS element type for simplices with `n` vertices
+    pub fn elem_type(n: Idx) -> Result<c_int> {
+        match n {
+            2 => Ok(BAR_2),
+            3 => Ok(TRI_3),
+            4 => Ok(TETRA_4),
+            _ => Err(Error::from("cgns: unsupported element type")),
+        }
+    }
+
+    /// Go to boundary condition `bc`
+    pub fn goto_boco(f: c_int, b: c_int, z: c_int, bc: c_int) -> Result<()> {
+        let zone = CString::new("Zone_t")?;
+        let zone_bc = CString::new("ZoneBC_t")?;
+        let bc_t = CString::new("BC_t")?;
+        let end = CString::new("end")?;
+        check(unsafe {
+            cg_goto(
+                f,
+                b,
+                zone.as_ptr(),
+                z,
+                zone_bc.as_ptr(),
+                1_i32,
+                bc_t.as_ptr(),
+                bc,
+                end.as_ptr(),
+            )
+        })
+    }
+
+    /// CGNS file, closed when dropped
+    pub struct File(pub c_int);
+
+    impl File {
+        pub fn open(file_name: &str, mode: c_int) -> Result<Self> {
+            let name = CString::new(file_name)?;
+            let mut f = 0;
+            check(unsafe { cg_open(name.as_ptr(), mode, &mut f) })?;
+            Ok(Self(f))
+        }
+    }
+
+    impl Drop for File {
+        fn drop(&mut self) {
+            unsafe {
+                cg_close(self.0);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::mesh::{test_meshes::test_mesh_2d, SimplexMesh};
+
+    #[cfg(not(feature = "cgns"))]
+    #[test]
+    fn test_cgns_disabled() {
+        use crate::mesh::Tetrahedron;
+
+        let mesh = test_mesh_2d();
+        assert!(mesh.write_cgns("mesh.cgns").is_err());
+        assert!(SimplexMesh::<3, Tetrahedron>::read_cgns("mesh.cgns").is_err());
+    }
+
+    #[cfg(feature = "cgns")]
+    #[test]
+    fn test_cgns_2d() -> crate::Result<()> {
+        use crate::mesh::Triangle;
+        use tempfile::NamedTempFile;
+
+        let mut mesh = test_mesh_2d().split();
+        mesh.tag_names_mut().set_face_name(1, "wall");
+
+        let file = NamedTempFile::new().unwrap();
+        let fname = file.path().to_str().unwrap().to_owned() + ".cgns";
+        mesh.write_cgns(&fname)?;
+        let mesh2 = SimplexMesh::<2, Triangle>::read_cgns(&fname)?;
+
+        assert_eq!(mesh.n_verts(), mesh2.n_verts());
+        assert_eq!(mesh.n_elems(), mesh2.n_elems());
+        assert_eq!(mesh.n_faces(), mesh2.n_faces());
+        assert!((mesh.vol() - mesh2.vol()).abs() < 1e-12);
+        let mut etags = mesh.etags().collect::<Vec<_>>();
+        etags.sort_unstable();
+        assert_eq!(etags, mesh2.etags().collect::<Vec<_>>());
+        let mut ftags = mesh.ftags().collect::<Vec<_>>();
+        ftags.sort_unstable();
+        assert_eq!(ftags, mesh2.ftags().collect::<Vec<_>>());
+        assert_eq!(mesh2.tag_names().face_name(1), Some("wall"));
+        assert_eq!(mesh2.tag_names().face_name(2), Some("Boundary_2"));
+
+        Ok(())
+    }
+
+    #[cfg(feature = "cgns")]
+    #[test]
+    fn test_cgns_3d() -> crate::Result<()> {
+        use crate::mesh::{test_meshes::test_mesh_3d, Tetrahedron};
+        use tempfile::NamedTempFile;
+
+        let mesh = test_mesh_3d().split();
+
+        let file = NamedTempFile::new().unwrap();
+        let fname = file.path().to_str().unwrap().to_owned() + ".cgns";
+        mesh.write_cgns(&fname)?;
+        let mesh2 = SimplexMesh::<3, Tetrahedron>::read_cgns(&fname)?;
+
+        assert_eq!(mesh.n_verts(), mesh2.n_verts());
+        assert_eq!(mesh.n_elems(), mesh2.n_elems());
+        assert_eq!(mesh.n_faces(), mesh2.n_faces());
+        assert!((mesh.vol() - mesh2.vol()).abs() < 1e-12);
+        for (p0, p1) in mesh.verts().zip(mesh2.verts()) {
+            assert!((p0 - p1).norm() < 1e-12);
+        }
+
+        Ok(())
+    }
+
+    #[cfg(feature = "cgns")]
+    #[test]
+    fn test_cgns_tags() -> crate::Result<()> {
+        use crate::mesh::{Edge, Triangle};
+        use tempfile::NamedTempFile;
+
+        // non contiguous face tags, in a BC order different from the sorted tags
+        let mut mesh = test_mesh_2d();
+        let ftags = [3, -2, 7, 3];
+        mesh.mut_ftags().zip(ftags).for_each(|(t, new)| *t = new);
+        mesh.tag_names_mut().set_face_name(7, "wall");
+
+        let file = NamedTempFile::new().unwrap();
+        let fname = file.path().to_str().unwrap().to_owned() + ".cgns";
+        mesh.write_cgns(&fname)?;
+        let mesh2 = SimplexMesh::<2, Triangle>::read_cgns(&fname)?;
+
+        assert_eq!(mesh.n_faces(), mesh2.n_faces());
+        let mut faces = mesh.faces().zip(mesh.ftags()).collect::<Vec<(Edge, _)>>();
+        faces.sort_by_key(|&(_, t)| t);
+        let mut faces2 = mesh2.faces().zip(mesh2.ftags()).collect::<Vec<(Edge, _)>>();
+        faces2.sort_by_key(|&(_, t)| t);
+        assert_eq!(faces, faces2);
+        assert_eq!(mesh2.tag_names().face_name(7), Some("wall"));
+        assert_eq!(mesh2.tag_names().face_name(-2), Some("Boundary_-2"));
+
+        Ok(())
+    }
+}
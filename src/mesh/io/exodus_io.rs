@@ -0,0 +1,699 @@
+use crate::{
+    mesh::{Elem, SimplexMesh},
+    Error, Result,
+};
+
+#[cfg(feature = "exodus")]
+use crate::{mesh::Point, Idx};
+#[cfg(feature = "exodus")]
+use log::{debug, warn};
+#[cfg(feature = "exodus")]
+use rustc_hash::FxHashMap;
+#[cfg(feature = "exodus")]
+use std::{
+    ffi::{CStr, CString},
+    os::raw::{c_char, c_int},
+};
+
+impl<const D: usize, E: Elem> SimplexMesh<D, E> {
+    #[cfg(not(feature = "exodus"))]
+    pub fn write_exodus(&self, _file_name: &str) -> Result<()> {
+        Err(Error::from("the exodus feature is not enabled"))
+    }
+
+    /// Write the mesh in an Exodus II file (volume meshes only). The elements are stored in one
+    /// element block per element tag, and the faces in one side set per face tag, the block and
+    /// side set ids being the tags (which must be > 0). The tag names (see `tag_names`) are used
+    /// as block and side set names
+    #[cfg(feature = "exodus")]
+    #[allow(clippy::too_many_lines)]
+    pub fn write_exodus(&self, file_name: &str) -> Result<()> {
+        debug!("Write {file_name}");
+
+        if E::DIM as usize != D {
+            return Err(Error::from("exodus: only volume meshes are supported"));
+        }
+        let elem_type = CString::new(exo::elem_type(E::N_VERTS)?)?;
+        let sides = exo::sides(E::N_VERTS)?;
+
+        let mut etags = self.etags().collect::<Vec<_>>();
+        etags.sort_unstable();
+        etags.dedup();
+        let mut ftags = self.ftags().collect::<Vec<_>>();
+        ftags.sort_unstable();
+        ftags.dedup();
+        if etags.iter().chain(ftags.iter()).any(|&t| t <= 0) {
+            return Err(Error::from("exodus: the tags must be > 0"));
+        }
+
+        // The elements are numbered block by block: sorted face -> (element, side), both 1-based
+        let mut elem_sides = FxHashMap::default();
+        let mut i_elem = 0;
+        for &tag in &etags {
+            for (e, _) in self.elems().zip(self.etags()).filter(|&(_, t)| t == tag) {
+                i_elem += 1;
+                for (i_side, side) in sides.iter().enumerate() {
+                    let f = E::Face::from_iter(side.iter().map(|&i| e[i]));
+                    elem_sides
+                        .entry(f.sorted())
+                        .or_insert((i_elem, i_side as c_int + 1));
+                }
+            }
+        }
+
+        let file = exo::File::create(file_name)?;
+        let f = file.0;
+
+        let title = CString::new("tucanos")?;
+        exo::check(unsafe {
+            exo::ex_put_init(
+                f,
+                title.as_ptr(),
+                D as i64,
+                self.n_verts() as i64,
+                self.n_elems() as i64,
+                etags.len() as i64,
+                0,
+                ftags.len() as i64,
+            )
+        })?;
+
+        let coords = (0..D)
+            .map(|i| self.verts().map(|p| p[i]).collect::<Vec<_>>())
+            .collect::<Vec<_>>();
+        let z = if D == 3 {
+            coords[2].as_ptr()
+        } else {
+            std::ptr::null()
+        };
+        exo::check(unsafe {
+            exo::ex_put_coord(
+                f,
+                coords[0].as_ptr().cast(),
+                coords[1].as_ptr().cast(),
+                z.cast(),
+            )
+        })?;
+
+        for &tag in &etags {
+            let conn = self
+                .elems()
+                .zip(self.etags())
+                .filter(|&(_, t)| t == tag)
+                .flat_map(|(e, _)| e)
+                .map(|i| i as c_int + 1)
+                .collect::<Vec<_>>();
+            let n = conn.len() / E::N_VERTS as usize;
+            let id = i64::from(tag);
+            exo::check(unsafe {
+                exo::ex_put_block(
+                    f,
+                    exo::EX_ELEM_BLOCK,
+                    id,
+                    elem_type.as_ptr(),
+                    n as i64,
+                    E::N_VERTS as i64,
+                    0,
+                    0,
+                    0,
+                )
+            })?;
+            exo::check(unsafe {
+                exo::ex_put_conn(
+                    f,
+                    exo::EX_ELEM_BLOCK,
+                    id,
+                    conn.as_ptr().cast(),
+                    std::ptr::null(),
+                    std::ptr::null(),
+                )
+            })?;
+        }
+
+        for &tag in &ftags {
+            let mut set_elems = Vec::new();
+            let mut set_sides = Vec::new();
+            for (face, _) in self.faces().zip(self.ftags()).filter(|&(_, t)| t == tag) {
+                let &(i_elem, i_side) = elem_sides
+                    .get(&face.sorted())
+                    .ok_or_else(|| Error::from("exodus: face not found in the elements"))?;
+                set_elems.push(i_elem);
+                set_sides.push(i_side);
+            }
+            let id = i64::from(tag);
+            exo::check(unsafe {
+                exo::ex_put_set_param(f, exo::EX_SIDE_SET, id, set_elems.len() as i64, 0)
+            })?;
+            exo::check(unsafe {
+                exo::ex_put_set(
+                    f,
+                    exo::EX_SIDE_SET,
+                    id,
+                    set_elems.as_ptr().cast(),
+                    set_sides.as_ptr().cast(),
+                )
+            })?;
+        }
+
+        for (obj_type, tags, names) in [
+            (
+                exo::EX_ELEM_BLOCK,
+                &etags,
+                etags
+                    .iter()
+                    .map(|&t| self.tag_names().elem_name(t))
+                    .collect::<Vec<_>>(),
+            ),
+            (
+                exo::EX_SIDE_SET,
+                &ftags,
+                ftags
+                    .iter()
+                    .map(|&t| self.tag_names().face_name(t))
+                    .collect::<Vec<_>>(),
+            ),
+        ] {
+            if tags.is_empty() || names.iter().all(Option::is_none) {
+                continue;
+            }
+            let names = names
+                .iter()
+                .map(|n| CString::new(n.unwrap_or("")))
+                .collect::<std::result::Result<Vec<_>, _>>()?;
+            let ptrs = names.iter().map(|n| n.as_ptr()).collect::<Vec<_>>();
+            exo::check(unsafe { exo::ex_put_names(f, obj_type, ptrs.as_ptr()) })?;
+        }
+
+        Ok(())
+    }
+
+    #[cfg(not(feature = "exodus"))]
+    pub fn read_exodus(_file_name: &str) -> Result<Self> {
+        Err(Error::from("the exodus feature is not enabled"))
+    }
+
+    /// Read a mesh from an Exodus II file. The elements are read from the element blocks with the
+    /// right type (e.g. TETRA4 in 3D) and tagged by block id, and the faces from the side sets and
+    /// tagged by side set id. The block and side set names are stored as tag names (see
+    /// `tag_names`)
+    #[cfg(feature = "exodus")]
+    #[allow(clippy::too_many_lines)]
+    pub fn read_exodus(file_name: &str) -> Result<Self> {
+        debug!("Read {file_name}");
+
+        let sides = exo::sides(E::N_VERTS)?;
+
+        let file = exo::File::open(file_name)?;
+        let f = file.0;
+
+        let mut title = [0 as c_char; exo::MAX_LINE_LENGTH + 1];
+        let (mut dim, mut n_verts, mut n_elems) = (0, 0, 0);
+        let (mut n_blocks, mut n_node_sets, mut n_side_sets) = (0, 0, 0);
+        exo::check(unsafe {
+            exo::ex_get_init(
+                f,
+                title.as_mut_ptr(),
+                &mut dim,
+                &mut n_verts,
+                &mut n_elems,
+                &mut n_blocks,
+                &mut n_node_sets,
+                &mut n_side_sets,
+            )
+        })?;
+        if dim as usize != D || E::DIM as usize != D {
+            return Err(Error::from("exodus: invalid dimension"));
+        }
+
+        let n_verts = n_verts as usize;
+        let mut coords = vec![vec![0.0_f64; n_verts]; 3];
+        let z = if D == 3 {
+            coords[2].as_mut_ptr()
+        } else {
+            std::ptr::null_mut()
+        };
+        exo::check(unsafe {
+            exo::ex_get_coord(
+                f,
+                coords[0].as_mut_ptr().cast(),
+                coords[1].as_mut_ptr().cast(),
+                z.cast(),
+            )
+        })?;
+        let verts = (0..n_verts)
+            .map(|i| Point::<D>::from_fn(|j, _| coords[j][i]))
+            .collect::<Vec<_>>();
+
+        // Element blocks
+        let mut elems = Vec::with_capacity(n_elems as usize);
+        let mut etags = Vec::with_capacity(n_elems as usize);
+        // 1-based element index -> index in elems (None if the block is skipped)
+        let mut elem_ids = Vec::with_capacity(n_elems as usize);
+        let block_ids = exo::get_ids(f, exo::EX_ELEM_BLOCK, n_blocks as usize)?;
+        for &id in &block_ids {
+            let mut name = [0 as c_char; exo::MAX_STR_LENGTH + 1];
+            let (mut n, mut m, mut n_edges, mut n_faces, mut n_attrs) = (0, 0, 0, 0, 0);
+            exo::check(unsafe {
+                exo::ex_get_block(
+                    f,
+                    exo::EX_ELEM_BLOCK,
+                    i64::from(id),
+                    name.as_mut_ptr(),
+                    &mut n,
+                    &mut m,
+                    &mut n_edges,
+                    &mut n_faces,
+                    &mut n_attrs,
+                )
+            })?;
+            let block_type = unsafe { CStr::from_ptr(name.as_ptr()) }.to_string_lossy();
+            if m as Idx != E::N_VERTS || !exo::is_simplex(&block_type) {
+                warn!("exodus: skip block {id} (element type {block_type})");
+                elem_ids.extend(std::iter::repeat_n(None, n as usize));
+                continue;
+            }
+            let mut conn = vec![0 as c_int; (n * m) as usize];
+            exo::check(unsafe {
+                exo::ex_get_conn(
+                    f,
+                    exo::EX_ELEM_BLOCK,
+                    i64::from(id),
+                    conn.as_mut_ptr().cast(),
+                    std::ptr::null_mut(),
+                    std::ptr::null_mut(),
+                )
+            })?;
+            let tag = exo::tag(id)?;
+            for e in conn.chunks(m as usize) {
+                elem_ids.push(Some(elems.len()));
+                elems.push(E::from_iter(e.iter().map(|&i| (i - 1) as Idx)));
+                etags.push(tag);
+            }
+        }
+
+        // Side sets
+        let mut faces = Vec::new();
+        let mut ftags = Vec::new();
+        let set_ids = exo::get_ids(f, exo::EX_SIDE_SET, n_side_sets as usize)?;
+        for &id in &set_ids {
+            let (mut n, mut n_df) = (0, 0);
+            exo::check(unsafe {
+                exo::ex_get_set_param(f, exo::EX_SIDE_SET, i64::from(id), &mut n, &mut n_df)
+            })?;
+            let mut set_elems = vec![0 as c_int; n as usize];
+            let mut set_sides = vec![0 as c_int; n as usize];
+            exo::check(unsafe {
+                exo::ex_get_set(
+                    f,
+                    exo::EX_SIDE_SET,
+                    i64::from(id),
+                    set_elems.as_mut_ptr().cast(),
+                    set_sides.as_mut_ptr().cast(),
+                )
+            })?;
+            let tag = exo::tag(id)?;
+            for (&i_elem, &i_side) in set_elems.iter().zip(set_sides.iter()) {
+                let e = elem_ids
+                    .get(i_elem as usize - 1)
+                    .copied()
+                    .flatten()
+                    .ok_or_else(|| {
+                        Error::from(&format!("exodus: invalid element in side set {id}"))
+                    })?;
+                let side = sides.get(i_side as usize - 1).ok_or_else(|| {
+                    Error::from(&format!("exodus: invalid side in side set {id}"))
+                })?;
+                let e = elems[e];
+                faces.push(E::Face::from_iter(side.iter().map(|&i| e[i])));
+                ftags.push(tag);
+            }
+        }
+
+        let mut res = Self::new(verts, elems, etags, faces, ftags);
+        for (name, tag) in exo::get_names(f, exo::EX_ELEM_BLOCK, block_ids.len())?
+            .into_iter()
+            .zip(block_ids)
+        {
+            if !name.is_empty() {
+                res.tag_names_mut().set_elem_name(exo::tag(tag)?, &name);
+            }
+        }
+        for (name, tag) in exo::get_names(f, exo::EX_SIDE_SET, set_ids.len())?
+            .into_iter()
+            .zip(set_ids)
+        {
+            if !name.is_empty() {
+                res.tag_names_mut().set_face_name(exo::tag(tag)?, &name);
+            }
+        }
+
+        Ok(res)
+    }
+}
+
+#[cfg(feature = "exodus")]
+mod exo {
+    use crate::{Error, Idx, Result, Tag};
+    use std::{
+        ffi::{CStr, CString},
+        os::raw::{c_char, c_float, c_int, c_void},
+    };
+
+    pub type ExEntityId = i64;
+
+    pub const EX_READ: c_int = 0x0002;
+    pub const EX_CLOBBER: c_int = 0x0008;
+    /// Only used by the library to warn about version mismatches
+    pub const EX_API_VERS_NODOT: c_int = 800;
+    pub const EX_ELEM_BLOCK: c_int = 1;
+    pub const EX_SIDE_SET: c_int = 3;
+    pub const MAX_STR_LENGTH: usize = 32;
+    pub const MAX_LINE_LENGTH: usize = 80;
+
+    #[link(name = "exodus")]
+    extern "C" {
+        pub fn ex_create_int(
+            path: *const c_char,
+            cmode: c_int,
+            comp_ws: *mut c_int,
+            io_ws: *mut c_int,
+            run_version: c_int,
+        ) -> c_int;
+        pub fn ex_open_int(
+            path: *const c_char,
+            mode: c_int,
+            comp_ws: *mut c_int,
+            io_ws: *mut c_int,
+            version: *mut c_float,
+            run_version: c_int,
+        ) -> c_int;
+        pub fn ex_close(exoid: c_int) -> c_int;
+        pub fn ex_get_err(msg: *mut *const c_char, func: *mut *const c_char, err_num: *mut c_int);
+        #[allow(clippy::too_many_arguments)]
+        pub fn ex_put_init(
+            exoid: c_int,
+            title: *const c_char,
+            num_dim: i64,
+            num_nodes: i64,
+            num_elem: i64,
+            num_elem_blk: i64,
+            num_node_sets: i64,
+            num_side_sets: i64,
+        ) -> c_int;
+        #[allow(clippy::too_many_arguments)]
+        pub fn ex_get_init(
+            exoid: c_int,
+            title: *mut c_char,
+            num_dim: *mut c_int,
+            num_nodes: *mut c_int,
+            num_elem: *mut c_int,
+            num_elem_blk: *mut c_int,
+            num_node_sets: *mut c_int,
+            num_side_sets: *mut c_int,
+        ) -> c_int;
+        pub fn ex_put_coord(
+            exoid: c_int,
+            x_coor: *const c_void,
+            y_coor: *const c_void,
+            z_coor: *const c_void,
+        ) -> c_int;
+        pub fn ex_get_coord(
+            exoid: c_int,
+            x_coor: *mut c_void,
+            y_coor: *mut c_void,
+            z_coor: *mut c_void,
+        ) -> c_int;
+        #[allow(clippy::too_many_arguments)]
+        pub fn ex_put_block(
+            exoid: c_int,
+            blk_type: c_int,
+            blk_id: ExEntityId,
+            entry_descrip: *const c_char,
+            num_entries_this_blk: i64,
+            num_nodes_per_entry: i64,
+            num_edges_per_entry: i64,
+            num_faces_per_entry: i64,
+            num_attr_per_entry: i64,
+        ) -> c_int;
+        #[allow(clippy::too_many_arguments)]
+        pub fn ex_get_block(
+            exoid: c_int,
+            blk_type: c_int,
+            blk_id: ExEntityId,
+            elem_type: *mut c_char,
+            num_entries_this_blk: *mut c_int,
+            num_nodes_per_entry: *mut c_int,
+            num_edges_per_entry: *mut c_int,
+            num_faces_per_entry: *mut c_int,
+            num_attr_per_entry: *mut c_int,
+        ) -> c_int;
+        pub fn ex_put_conn(
+            exoid: c_int,
+            blk_type: c_int,
+            blk_id: ExEntityId,
+            node_conn: *const c_void,
+            elem_edge_conn: *const c_void,
+            elem_face_conn: *const c_void,
+        ) -> c_int;
+        pub fn ex_get_conn(
+            exoid: c_int,
+            blk_type: c_int,
+            blk_id: ExEntityId,
+            node_conn: *mut c_void,
+            elem_edge_conn: *mut c_void,
+            elem_face_conn: *mut c_void,
+        ) -> c_int;
+        pub fn ex_put_set_param(
+            exoid: c_int,
+            set_type: c_int,
+            set_id: ExEntityId,
+            num_entries_in_set: i64,
+            num_dist_fact_in_set: i64,
+        ) -> c_int;
+        pub fn ex_get_set_param(
+            exoid: c_int,
+            set_type: c_int,
+            set_id: ExEntityId,
+            num_entry_in_set: *mut c_int,
+            num_dist_fact_in_set: *mut c_int,
+        ) -> c_int;
+        pub fn ex_put_set(
+            exoid: c_int,
+            set_type: c_int,
+            set_id: ExEntityId,
+            set_entry_list: *const c_void,
+            set_extra_list: *const c_void,
+        ) -> c_int;
+        pub fn ex_get_set(
+            exoid: c_int,
+            set_type: c_int,
+            set_id: ExEntityId,
+            set_entry_list: *mut c_void,
+            set_extra_list: *mut c_void,
+        ) -> c_int;
+        pub fn ex_get_ids(exoid: c_int, obj_type: c_int, ids: *mut c_void) -> c_int;
+        pub fn ex_put_names(exoid: c_int, obj_type: c_int, names: *const *const c_char) -> c_int;
+        pub fn ex_get_names(exoid: c_int, obj_type: c_int, names: *mut *mut c_char) -> c_int;
+    }
+
+    /// Check the return value of an Exodus function (> 0 for warnings)
+    pub fn check(ier: c_int) -> Result<()> {
+        if ier >= 0 {
+            Ok(())
+        } else {
+            let mut msg = std::ptr::null();
+            let mut func = std::ptr::null();
+            let mut err_num = 0;
+            unsafe { ex_get_err(&mut msg, &mut func, &mut err_num) };
+            let msg = if msg.is_null() {
+                format!("error {err_num}")
+            } else {
+                unsafe { CStr::from_ptr(msg) }.to_string_lossy().to_string()
+            };
+            Err(Error::from(&format!("exodus: {msg}")))
+        }
+    }
+
+    /// Convert a block or side set id to a tag
+    #[allow(clippy::unnecessary_fallible_conversions)]
+    pub fn tag(id: c_int) -> Result<Tag> {
+        Ok(id.try_into()?)
+    }
+
+    /// Exodus element type for simplices with `n` vertices
+    pub fn elem_type(n: Idx) -> Result<&'static str> {
+        match n {
+            3 => Ok("TRI3"),
+            4 => Ok("TETRA4"),
+            _ => Err(Error::from("exodus: unsupported element type")),
+        }
+    }
+
+    /// Check if an Exodus element type is a (linear) simplex
+    pub fn is_simplex(elem_type: &str) -> bool {
+        let t = elem_type.to_uppercase();
+        ["TRI", "TRI3", "TRIANGLE", "TETRA", "TETRA4", "TET4"].contains(&t.as_str())
+    }
+
+    /// Local vertices of the sides of an element with `n` vertices, in the Exodus order. The sides
+    /// are oriented outwards
+    pub fn sides(n: Idx) -> Result<&'static [&'static [usize]]> {
+        match n {
+            3 => Ok(&[&[0, 1], &[1, 2], &[2, 0]]),
+            4 => Ok(&[&[0, 1, 3], &[1, 2, 3], &[0, 3, 2], &[0, 2, 1]]),
+            _ => Err(Error::from("exodus: unsupported element type")),
+        }
+    }
+
+    /// Get the ids of the `n` entities of type `obj_type`
+    pub fn get_ids(f: c_int, obj_type: c_int, n: usize) -> Result<Vec<c_int>> {
+        let mut ids = vec![0; n];
+        if n > 0 {
+            check(unsafe { ex_get_ids(f, obj_type, ids.as_mut_ptr().cast()) })?;
+        }
+        Ok(ids)
+    }
+
+    /// Get the names of the `n` entities of type `obj_type`
+    pub fn get_names(f: c_int, obj_type: c_int, n: usize) -> Result<Vec<String>> {
+        if n == 0 {
+            return Ok(Vec::new());
+        }
+        let mut buffers = vec![[0 as c_char; MAX_STR_LENGTH + 1]; n];
+        let mut ptrs = buffers
+            .iter_mut()
+            .map(|b| b.as_mut_ptr())
+            .collect::<Vec<_>>();
+        check(unsafe { ex_get_names(f, obj_type, ptrs.as_mut_ptr()) })?;
+        Ok(buffers
+            .iter()
+            .map(|b| {
+                unsafe { CStr::from_ptr(b.as_ptr()) }
+                    .to_string_lossy()
+                    .trim()
+                    .to_string()
+            })
+            .collect())
+    }
+
+    /// Exodus file, closed when dropped
+    pub struct File(pub c_int);
+
+    impl File {
+        pub fn create(file_name: &str) -> Result<Self> {
+            let name = CString::new(file_name)?;
+            let (mut comp_ws, mut io_ws) = (8, 8);
+            let f = unsafe {
+                ex_create_int(
+                    name.as_ptr(),
+                    EX_CLOBBER,
+                    &mut comp_ws,
+                    &mut io_ws,
+                    EX_API_VERS_NODOT,
+                )
+            };
+            check(f)?;
+            Ok(Self(f))
+        }
+
+        pub fn open(file_name: &str) -> Result<Self> {
+            let name = CString::new(file_name)?;
+            let (mut comp_ws, mut io_ws) = (8, 0);
+            let mut version = 0.0;
+            let f = unsafe {
+                ex_open_int(
+                    name.as_ptr(),
+                    EX_READ,
+                    &mut comp_ws,
+                    &mut io_ws,
+                    &mut version,
+                    EX_API_VERS_NODOT,
+                )
+            };
+            check(f)?;
+            Ok(Self(f))
+        }
+    }
+
+    impl Drop for File {
+        fn drop(&mut self) {
+            unsafe {
+                ex_close(self.0);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::mesh::{test_meshes::test_mesh_2d, SimplexMesh};
+
+    #[cfg(not(feature = "exodus"))]
+    #[test]
+    fn test_exodus_disabled() {
+        use crate::mesh::Tetrahedron;
+
+        let mesh = test_mesh_2d();
+        assert!(mesh.write_exodus("mesh.exo").is_err());
+        assert!(SimplexMesh::<3, Tetrahedron>::read_exodus("mesh.exo").is_err());
+    }
+
+    #[cfg(feature = "exodus")]
+    #[test]
+    fn test_exodus_2d() -> crate::Result<()> {
+        use crate::mesh::Triangle;
+        use tempfile::NamedTempFile;
+
+        let mut mesh = test_mesh_2d().split();
+        mesh.tag_names_mut().set_elem_name(2, "solid");
+        mesh.tag_names_mut().set_face_name(1, "wall");
+
+        let file = NamedTempFile::new().unwrap();
+        let fname = file.path().to_str().unwrap().to_owned() + ".exo";
+        mesh.write_exodus(&fname)?;
+        let mesh2 = SimplexMesh::<2, Triangle>::read_exodus(&fname)?;
+
+        assert_eq!(mesh.n_verts(), mesh2.n_verts());
+        assert_eq!(mesh.n_elems(), mesh2.n_elems());
+        assert_eq!(mesh.n_faces(), mesh2.n_faces());
+        assert!((mesh.vol() - mesh2.vol()).abs() < 1e-12);
+        let mut etags = mesh.etags().collect::<Vec<_>>();
+        etags.sort_unstable();
+        assert_eq!(etags, mesh2.etags().collect::<Vec<_>>());
+        let mut ftags = mesh.ftags().collect::<Vec<_>>();
+        ftags.sort_unstable();
+        assert_eq!(ftags, mesh2.ftags().collect::<Vec<_>>());
+        assert_eq!(mesh2.tag_names().elem_name(2), Some("solid"));
+        assert_eq!(mesh2.tag_names().elem_name(1), None);
+        assert_eq!(mesh2.tag_names().face_name(1), Some("wall"));
+
+        Ok(())
+    }
+
+    #[cfg(feature = "exodus")]
+    #[test]
+    fn test_exodus_3d() -> crate::Result<()> {
+        use crate::mesh::{test_meshes::test_mesh_3d, Tetrahedron};
+        use tempfile::NamedTempFile;
+
+        let mesh = test_mesh_3d().split();
+
+        let file = NamedTempFile::new().unwrap();
+        let fname = file.path().to_str().unwrap().to_owned() + ".exo";
+        mesh.write_exodus(&fname)?;
+        let mut mesh2 = SimplexMesh::<3, Tetrahedron>::read_exodus(&fname)?;
+
+        assert_eq!(mesh.n_verts(), mesh2.n_verts());
+        assert_eq!(mesh.n_elems(), mesh2.n_elems());
+        assert_eq!(mesh.n_faces(), mesh2.n_faces());
+        assert!((mesh.vol() - mesh2.vol()).abs() < 1e-12);
+        for (p0, p1) in mesh.verts().zip(mesh2.verts()) {
+            assert!((p0 - p1).norm() < 1e-12);
+        }
+        // the faces read from the sides are oriented outwards
+        mesh2.compute_face_to_elems();
+        mesh2.check()?;
+
+        Ok(())
+    }
+}
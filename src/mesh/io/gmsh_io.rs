@@ -0,0 +1,1248 @@
+use crate::{
+    mesh::{Elem, HybridMesh, Point, SimplexMesh},
+    Error, Idx, Result, Tag,
+};
+use log::{debug, warn};
+use rustc_hash::FxHashMap;
+use std::{
+    fs::File,
+    io::{BufWriter, Read, Write},
+    ops::Index,
+};
+
+/// Gmsh element type for 2-node lines
+const MSH_LINE: i32 = 1;
+/// Gmsh element type for 3-node triangles
+const MSH_TRIANGLE: i32 = 2;
+/// Gmsh element type for 4-node quadrangles
+const MSH_QUADRANGLE: i32 = 3;
+/// Gmsh element type for 4-node tetrahedra
+const MSH_TETRAHEDRON: i32 = 4;
+/// Gmsh element type for 8-node hexahedra
+const MSH_HEXAHEDRON: i32 = 5;
+/// Gmsh element type for 6-node prisms
+const MSH_PRISM: i32 = 6;
+/// Gmsh element type for 5-node pyramids
+const MSH_PYRAMID: i32 = 7;
+/// Gmsh element type for 3-node second order lines
+const MSH_LINE_3: i32 = 8;
+/// Gmsh element type for 6-node second order triangles
+const MSH_TRIANGLE_6: i32 = 9;
+/// Gmsh element type for 10-node second order tetrahedra
+const MSH_TETRAHEDRON_10: i32 = 11;
+
+/// Number of nodes for the Gmsh element types
+const fn msh_n_nodes(etype: i32) -> Option<usize> {
+    match etype {
+        15 => Some(1),
+        1 => Some(2),
+        2 | 8 => Some(3),
+        3 | 4 => Some(4),
+        7 => Some(5),
+        6 | 9 => Some(6),
+        5 => Some(8),
+        10 => Some(9),
+        11 => Some(10),
+        12 => Some(27),
+        13 => Some(18),
+        14 => Some(14),
+        16 => Some(8),
+        17 => Some(20),
+        18 => Some(15),
+        19 => Some(13),
+        _ => None,
+    }
+}
+
+/// Gmsh element type for simplices of dimension `dim` with `n` nodes, and the node ordering
+/// (the k-th Gmsh node is node `perm[k]` of the element)
+fn msh_type(dim: Idx, n: Idx) -> Result<(i32, &'static [usize])> {
+    match (dim, n) {
+        (1, 2) => Ok((MSH_LINE, &[0, 1])),
+        (2, 3) => Ok((MSH_TRIANGLE, &[0, 1, 2])),
+        (3, 4) => Ok((MSH_TETRAHEDRON, &[0, 1, 2, 3])),
+        (1, 3) => Ok((MSH_LINE_3, &[0, 1, 2])),
+        (2, 6) => Ok((MSH_TRIANGLE_6, &[0, 1, 2, 5, 3, 4])),
+        (3, 10) => Ok((MSH_TETRAHEDRON_10, &[0, 1, 2, 3, 9, 6, 7, 8, 4, 5])),
+        _ => Err(Error::from("gmsh: unsupported element type")),
+    }
+}
+
+/// Convert a tag to a Gmsh entity / physical tag
+#[allow(clippy::unnecessary_fallible_conversions)]
+fn msh_tag(tag: Tag) -> Result<i32> {
+    Ok(tag.try_into()?)
+}
+
+/// Convert a Gmsh entity / physical tag to a tag
+#[allow(clippy::unnecessary_fallible_conversions)]
+fn tag_from_msh(tag: i32) -> Result<Tag> {
+    Ok(tag.try_into()?)
+}
+
+/// 3D coordinates of a vertex
+fn coords<const D: usize>(p: &Point<D>) -> impl Iterator<Item = f64> + '_ {
+    (0..3).map(move |i| if i < D { p[i] } else { 0.0 })
+}
+
+/// Low level reader for MSH 4.1 files (ascii or binary)
+struct MshReader {
+    data: Vec<u8>,
+    pos: usize,
+    binary: bool,
+}
+
+impl MshReader {
+    fn new(file_name: &str) -> Result<Self> {
+        let mut data = Vec::new();
+        File::open(file_name)?.read_to_end(&mut data)?;
+        Ok(Self {
+            data,
+            pos: 0,
+            binary: false,
+        })
+    }
+
+    const fn is_done(&self) -> bool {
+        self.pos >= self.data.len()
+    }
+
+    /// Read a line (without the trailing whitespaces)
+    fn line(&mut self) -> Result<String> {
+        if self.is_done() {
+            return Err(Error::from("gmsh: unexpected end of file"));
+        }
+        let start = self.pos;
+        while self.pos < self.data.len() && self.data[self.pos] != b'\n' {
+            self.pos += 1;
+        }
+        let res = String::from_utf8_lossy(&self.data[start..self.pos])
+            .trim_end()
+            .to_string();
+        self.pos += 1;
+        Ok(res)
+    }
+
+    /// Read the next non empty line
+    fn non_empty_line(&mut self) -> Result<String> {
+        loop {
+            let line = self.line()?;
+            if !line.is_empty() {
+                return Ok(line);
+            }
+        }
+    }
+
+    /// Read the end of a section
+    fn end_section(&mut self, name: &str) -> Result<()> {
+        let line = self.non_empty_line()?;
+        if line != format!("$End{name}") {
+            return Err(Error::from(&format!(
+                "gmsh: expected $End{name}, found {line}"
+            )));
+        }
+        Ok(())
+    }
+
+    /// Read an ascii token
+    fn token(&mut self) -> Result<&str> {
+        while self.pos < self.data.len() && self.data[self.pos].is_ascii_whitespace() {
+            self.pos += 1;
+        }
+        let start = self.pos;
+        while self.pos < self.data.len() && !self.data[self.pos].is_ascii_whitespace() {
+            self.pos += 1;
+        }
+        if start == self.pos {
+            return Err(Error::from("gmsh: unexpected end of file"));
+        }
+        Ok(std::str::from_utf8(&self.data[start..self.pos])?)
+    }
+
+    fn bytes<const N: usize>(&mut self) -> Result<[u8; N]> {
+        if self.pos + N > self.data.len() {
+            return Err(Error::from("gmsh: unexpected end of file"));
+        }
+        let res = self.data[self.pos..self.pos + N].try_into().unwrap();
+        self.pos += N;
+        Ok(res)
+    }
+
+    fn int(&mut self) -> Result<i32> {
+        if self.binary {
+            Ok(i32::from_le_bytes(self.bytes()?))
+        } else {
+            Ok(self.token()?.parse()?)
+        }
+    }
+
+    fn size(&mut self) -> Result<usize> {
+        if self.binary {
+            Ok(u64::from_le_bytes(self.bytes()?).try_into()?)
+        } else {
+            Ok(self.token()?.parse()?)
+        }
+    }
+
+    fn float(&mut self) -> Result<f64> {
+        if self.binary {
+            Ok(f64::from_le_bytes(self.bytes()?))
+        } else {
+            Ok(self.token()?.parse()?)
+        }
+    }
+}
+
+/// Low level writer for MSH 4.1 files (ascii or binary)
+struct MshWriter {
+    w: BufWriter<File>,
+    binary: bool,
+    first: bool,
+}
+
+impl MshWriter {
+    fn new(file_name: &str, binary: bool) -> Result<Self> {
+        Ok(Self {
+            w: BufWriter::new(File::create(file_name)?),
+            binary,
+            first: true,
+        })
+    }
+
+    fn sep(&mut self) -> Result<()> {
+        if !self.first {
+            self.w.write_all(b" ")?;
+        }
+        self.first = false;
+        Ok(())
+    }
+
+    fn int(&mut self, v: i32) -> Result<()> {
+        if self.binary {
+            self.w.write_all(&v.to_le_bytes())?;
+        } else {
+            self.sep()?;
+            write!(self.w, "{v}")?;
+        }
+        Ok(())
+    }
+
+    fn size(&mut self, v: usize) -> Result<()> {
+        if self.binary {
+            self.w.write_all(&(v as u64).to_le_bytes())?;
+        } else {
+            self.sep()?;
+            write!(self.w, "{v}")?;
+        }
+        Ok(())
+    }
+
+    fn float(&mut self, v: f64) -> Result<()> {
+        if self.binary {
+            self.w.write_all(&v.to_le_bytes())?;
+        } else {
+            self.sep()?;
+            write!(self.w, "{v:.16e}")?;
+        }
+        Ok(())
+    }
+
+    fn end_line(&mut self) -> Result<()> {
+        if !self.binary {
+            self.w.write_all(b"\n")?;
+        }
+        self.first = true;
+        Ok(())
+    }
+
+    fn begin_section(&mut self, name: &str) -> Result<()> {
+        writeln!(self.w, "${name}")?;
+        self.first = true;
+        Ok(())
+    }
+
+    fn end_section(&mut self, name: &str) -> Result<()> {
+        if self.binary {
+            self.w.write_all(b"\n")?;
+        }
+        writeln!(self.w, "$End{name}")?;
+        self.first = true;
+        Ok(())
+    }
+}
+
+/// A block of elements of the same type and tag, with the node indices in Gmsh order
+struct MshBlock {
+    dim: i32,
+    tag: Tag,
+    etype: i32,
+    n_nodes: usize,
+    conn: Vec<Idx>,
+    /// Gmsh tags of the elements (only when reading, the elements being numbered from 1 in the
+    /// order of the blocks when writing)
+    elem_tags: Vec<usize>,
+}
+
+/// Field stored in a $NodeData or $ElementData section, with `n_comp` values per node / element
+/// given by its Gmsh tag
+struct MshField {
+    elem: bool,
+    name: String,
+    n_comp: usize,
+    tags: Vec<usize>,
+    values: Vec<f64>,
+}
+
+/// Content of a MSH file
+struct MshData<const D: usize> {
+    verts: Vec<Point<D>>,
+    /// Index of the vertices from their Gmsh tags
+    node_ids: FxHashMap<usize, Idx>,
+    blocks: Vec<MshBlock>,
+    names: Vec<(i32, Tag, String)>,
+    fields: Vec<MshField>,
+}
+
+/// Write a MSH 4.1 file (ascii or binary). An entity, associated with a physical group with
+/// the same tag, is created for every block dimension and tag, and the nodes are all in the
+/// first entity of dimension `node_dim`. The fields are written in $NodeData / $ElementData
+/// sections
+#[allow(clippy::too_many_lines)]
+fn write_msh<const D: usize>(
+    file_name: &str,
+    binary: bool,
+    verts: &[Point<D>],
+    node_dim: i32,
+    blocks: &[MshBlock],
+    names: &[(i32, Tag, &str)],
+    fields: &[MshField],
+) -> Result<()> {
+    if blocks.iter().any(|b| b.tag <= 0) {
+        return Err(Error::from("gmsh: the tags must be > 0"));
+    }
+
+    // One entity per dimension and tag
+    let mut entities = blocks.iter().map(|b| (b.dim, b.tag)).collect::<Vec<_>>();
+    entities.sort_unstable();
+    entities.dedup();
+
+    // Bounding boxes of the entities
+    let bbox = |dim: i32, tag: Tag| {
+        let mut p_min = Point::<D>::repeat(f64::MAX);
+        let mut p_max = Point::<D>::repeat(f64::MIN);
+        for b in blocks.iter().filter(|b| b.dim == dim && b.tag == tag) {
+            for &i in &b.conn {
+                let p = verts[i as usize];
+                p_min = p_min.inf(&p);
+                p_max = p_max.sup(&p);
+            }
+        }
+        (p_min, p_max)
+    };
+
+    let mut w = MshWriter::new(file_name, binary)?;
+
+    // Header
+    w.begin_section("MeshFormat")?;
+    writeln!(w.w, "4.1 {} 8", i32::from(binary))?;
+    if binary {
+        w.w.write_all(&1_i32.to_le_bytes())?;
+    }
+    w.end_section("MeshFormat")?;
+
+    // Physical names (always in ascii)
+    if !names.is_empty() {
+        writeln!(w.w, "$PhysicalNames")?;
+        writeln!(w.w, "{}", names.len())?;
+        for &(dim, tag, name) in names {
+            writeln!(w.w, "{dim} {} \"{name}\"", msh_tag(tag)?)?;
+        }
+        writeln!(w.w, "$EndPhysicalNames")?;
+    }
+
+    // Entities
+    w.begin_section("Entities")?;
+    let mut counts = [0; 4];
+    entities
+        .iter()
+        .for_each(|&(dim, _)| counts[dim as usize] += 1);
+    for c in counts {
+        w.size(c)?;
+    }
+    w.end_line()?;
+    for &(dim, tag) in &entities {
+        let (p_min, p_max) = bbox(dim, tag);
+        w.int(msh_tag(tag)?)?;
+        if dim == 0 {
+            for x in coords(&p_min) {
+                w.float(x)?;
+            }
+        } else {
+            for x in coords(&p_min).chain(coords(&p_max)) {
+                w.float(x)?;
+            }
+        }
+        w.size(1)?;
+        w.int(msh_tag(tag)?)?;
+        if dim > 0 {
+            w.size(0)?;
+        }
+        w.end_line()?;
+    }
+    w.end_section("Entities")?;
+
+    // Nodes: all in the first entity of dimension node_dim
+    let node_tag = entities
+        .iter()
+        .find(|&&(dim, _)| dim == node_dim)
+        .map_or(1, |&(_, t)| t);
+    let n_verts = verts.len();
+    w.begin_section("Nodes")?;
+    w.size(1)?;
+    w.size(n_verts)?;
+    w.size(1)?;
+    w.size(n_verts)?;
+    w.end_line()?;
+    w.int(node_dim)?;
+    w.int(msh_tag(node_tag)?)?;
+    w.int(0)?;
+    w.size(n_verts)?;
+    w.end_line()?;
+    for i in 0..n_verts {
+        w.size(i + 1)?;
+        w.end_line()?;
+    }
+    for p in verts {
+        for x in coords(p) {
+            w.float(x)?;
+        }
+        w.end_line()?;
+    }
+    w.end_section("Nodes")?;
+
+    // Elements
+    let n_elems = blocks
+        .iter()
+        .map(|b| b.conn.len() / b.n_nodes)
+        .sum::<usize>();
+    w.begin_section("Elements")?;
+    w.size(blocks.len())?;
+    w.size(n_elems)?;
+    w.size(1)?;
+    w.size(n_elems)?;
+    w.end_line()?;
+    let mut next = 1;
+    for b in blocks {
+        w.int(b.dim)?;
+        w.int(msh_tag(b.tag)?)?;
+        w.int(b.etype)?;
+        w.size(b.conn.len() / b.n_nodes)?;
+        w.end_line()?;
+        for e in b.conn.chunks(b.n_nodes) {
+            w.size(next)?;
+            next += 1;
+            for &i in e {
+                w.size(i as usize + 1)?;
+            }
+            w.end_line()?;
+        }
+    }
+    w.end_section("Elements")?;
+
+    // Fields: the header is always in ascii
+    for f in fields {
+        let section = if f.elem { "ElementData" } else { "NodeData" };
+        w.begin_section(section)?;
+        writeln!(w.w, "1\n\"{}\"\n1\n0.0\n3\n0", f.name)?;
+        writeln!(w.w, "{}\n{}", f.n_comp, f.tags.len())?;
+        for (&tag, v) in f.tags.iter().zip(f.values.chunks(f.n_comp)) {
+            w.int(tag.try_into()?)?;
+            for &x in v {
+                w.float(x)?;
+            }
+            w.end_line()?;
+        }
+        w.end_section(section)?;
+    }
+    w.w.flush()?;
+
+    Ok(())
+}
+
+/// Read a MSH 4.1 file (ascii or binary). The block tags are the physical tags of the
+/// entities if defined, or the entity tags otherwise
+#[allow(clippy::too_many_lines)]
+fn read_msh<const D: usize>(file_name: &str) -> Result<MshData<D>> {
+    let mut reader = MshReader::new(file_name)?;
+
+    let mut entity_tags: FxHashMap<(i32, i32), Tag> = FxHashMap::default();
+    let mut res = MshData {
+        verts: Vec::new(),
+        node_ids: FxHashMap::default(),
+        blocks: Vec::new(),
+        names: Vec::new(),
+        fields: Vec::new(),
+    };
+
+    while !reader.is_done() {
+        let line = reader.line()?;
+        if line.is_empty() {
+            continue;
+        }
+        match line.as_str() {
+            "$MeshFormat" => {
+                let header = reader.line()?;
+                let mut it = header.split_whitespace();
+                let version = it.next().unwrap_or("");
+                if !version.starts_with("4.") {
+                    return Err(Error::from(&format!(
+                        "gmsh: unsupported format version {version}"
+                    )));
+                }
+                reader.binary = it.next() == Some("1");
+                if it.next() != Some("8") {
+                    return Err(Error::from("gmsh: unsupported data size"));
+                }
+                if reader.binary && reader.int()? != 1 {
+                    return Err(Error::from("gmsh: big endian files are not supported"));
+                }
+                reader.end_section("MeshFormat")?;
+            }
+            "$PhysicalNames" => {
+                let n = reader.non_empty_line()?.trim().parse::<usize>()?;
+                for _ in 0..n {
+                    let line = reader.non_empty_line()?;
+                    let mut it = line.splitn(3, ' ');
+                    let dim: i32 = it.next().unwrap_or("").parse()?;
+                    let tag: i32 = it.next().unwrap_or("").parse()?;
+                    let name = it.next().unwrap_or("").trim().trim_matches('"');
+                    res.names.push((dim, tag_from_msh(tag)?, name.to_string()));
+                }
+                reader.end_section("PhysicalNames")?;
+            }
+            "$Entities" => {
+                let mut counts = [0; 4];
+                for c in &mut counts {
+                    *c = reader.size()?;
+                }
+                for (dim, &n) in counts.iter().enumerate() {
+                    for _ in 0..n {
+                        let tag = reader.int()?;
+                        let n_coords = if dim == 0 { 3 } else { 6 };
+                        for _ in 0..n_coords {
+                            reader.float()?;
+                        }
+                        let n_phys = reader.size()?;
+                        let mut phys = Vec::with_capacity(n_phys);
+                        for _ in 0..n_phys {
+                            phys.push(reader.int()?);
+                        }
+                        if dim > 0 {
+                            let n_bdy = reader.size()?;
+                            for _ in 0..n_bdy {
+                                reader.int()?;
+                            }
+                        }
+                        if phys.len() > 1 {
+                            warn!("gmsh: only the first physical tag of entity {tag} is used");
+                        }
+                        let t = phys.first().copied().unwrap_or(tag);
+                        entity_tags.insert((dim as i32, tag), tag_from_msh(t)?);
+                    }
+                }
+                reader.end_section("Entities")?;
+            }
+            "$Nodes" => {
+                let n_blocks = reader.size()?;
+                let n_nodes = reader.size()?;
+                reader.size()?;
+                reader.size()?;
+                res.verts.reserve(n_nodes);
+                for _ in 0..n_blocks {
+                    let dim = reader.int()?;
+                    reader.int()?;
+                    let parametric = reader.int()?;
+                    let n = reader.size()?;
+                    let mut tags = Vec::with_capacity(n);
+                    for _ in 0..n {
+                        tags.push(reader.size()?);
+                    }
+                    for tag in tags {
+                        let x = [reader.float()?, reader.float()?, reader.float()?];
+                        let p = Point::<D>::from_iterator(x.into_iter());
+                        if parametric == 1 {
+                            for _ in 0..dim {
+                                reader.float()?;
+                            }
+                        }
+                        res.node_ids.insert(tag, res.verts.len() as Idx);
+                        res.verts.push(p);
+                    }
+                }
+                reader.end_section("Nodes")?;
+            }
+            "$Elements" => {
+                let n_blocks = reader.size()?;
+                reader.size()?;
+                reader.size()?;
+                reader.size()?;
+                for _ in 0..n_blocks {
+                    let dim = reader.int()?;
+                    let entity = reader.int()?;
+                    let etype = reader.int()?;
+                    let n = reader.size()?;
+                    let n_nodes = msh_n_nodes(etype).ok_or_else(|| {
+                        Error::from(&format!("gmsh: unknown element type {etype}"))
+                    })?;
+                    let tag = match entity_tags.get(&(dim, entity)) {
+                        Some(&t) => t,
+                        None => tag_from_msh(entity)?,
+                    };
+                    let mut conn = Vec::with_capacity(n * n_nodes);
+                    let mut elem_tags = Vec::with_capacity(n);
+                    for _ in 0..n {
+                        elem_tags.push(reader.size()?);
+                        for _ in 0..n_nodes {
+                            let i = reader.size()?;
+                            conn.push(
+                                *res.node_ids.get(&i).ok_or_else(|| {
+                                    Error::from(&format!("gmsh: unknown node {i}"))
+                                })?,
+                            );
+                        }
+                    }
+                    res.blocks.push(MshBlock {
+                        dim,
+                        tag,
+                        etype,
+                        n_nodes,
+                        conn,
+                        elem_tags,
+                    });
+                }
+                reader.end_section("Elements")?;
+            }
+            "$NodeData" | "$ElementData" => {
+                let section = &line[1..];
+                let mut header = |n: &mut Vec<String>| -> Result<()> {
+                    let count = reader.non_empty_line()?.trim().parse::<usize>()?;
+                    for _ in 0..count {
+                        n.push(reader.non_empty_line()?.trim().to_string());
+                    }
+                    Ok(())
+                };
+                let mut strings = Vec::new();
+                header(&mut strings)?;
+                header(&mut Vec::new())?;
+                let mut ints = Vec::new();
+                header(&mut ints)?;
+                let (Some(name), Some(n_comp), Some(count)) =
+                    (strings.first(), ints.get(1), ints.get(2))
+                else {
+                    return Err(Error::from(&format!("gmsh: invalid {section} section")));
+                };
+                let name = name.trim_matches('"').to_string();
+                let n_comp = n_comp.parse::<usize>()?;
+                let count = count.parse::<usize>()?;
+                let mut tags: Vec<usize> = Vec::with_capacity(count);
+                let mut values = Vec::with_capacity(n_comp * count);
+                for _ in 0..count {
+                    tags.push(reader.int()?.try_into()?);
+                    for _ in 0..n_comp {
+                        values.push(reader.float()?);
+                    }
+                }
+                reader.end_section(section)?;
+                res.fields.push(MshField {
+                    elem: section == "ElementData",
+                    name,
+                    n_comp,
+                    tags,
+                    values,
+                });
+            }
+            _ => {
+                if let Some(name) = line.strip_prefix('$') {
+                    debug!("gmsh: skip section {name}");
+                    let end = format!("$End{name}");
+                    while reader.line()? != end {}
+                }
+            }
+        }
+    }
+
+    Ok(res)
+}
+
+/// Blocks of the elements with tag `tag` (in Gmsh order)
+fn msh_block<T: Index<usize, Output = Idx>, I: Iterator<Item = (T, Tag)>>(
+    dim: i32,
+    tag: Tag,
+    etype: i32,
+    perm: &[usize],
+    elems: I,
+) -> Option<MshBlock> {
+    let conn = elems
+        .filter(|&(_, t)| t == tag)
+        .flat_map(|(e, _)| perm.iter().map(move |&k| e[k]))
+        .collect::<Vec<_>>();
+    if conn.is_empty() {
+        None
+    } else {
+        Some(MshBlock {
+            dim,
+            tag,
+            etype,
+            n_nodes: perm.len(),
+            conn,
+            elem_tags: Vec::new(),
+        })
+    }
+}
+
+/// Store the elements of a block
+fn push_block<const N: usize>(b: &MshBlock, elems: &mut Vec<[Idx; N]>, tags: &mut Vec<Tag>) {
+    for ids in b.conn.chunks(N) {
+        elems.push(ids.try_into().unwrap());
+        tags.push(b.tag);
+    }
+}
+
+/// Sorted unique tags
+fn unique_tags<I: Iterator<Item = Tag>>(tags: I) -> Vec<Tag> {
+    let mut res = tags.collect::<Vec<_>>();
+    res.sort_unstable();
+    res.dedup();
+    res
+}
+
+impl<const D: usize, E: Elem> SimplexMesh<D, E> {
+    /// Write the mesh in the Gmsh MSH 4.1 format (ascii or binary).
+    /// An entity, associated with a physical group with the same tag, is created for every element
+    /// and face tag (the tags must be > 0). The tag names (see `tag_names`) are written as physical
+    /// names, and the fields attached to the mesh (see `add_vertex_field`) as $NodeData /
+    /// $ElementData (converted to f64)
+    pub fn write_gmsh(&self, file_name: &str, binary: bool) -> Result<()> {
+        debug!("Write {file_name}");
+
+        if self.etags().chain(self.ftags()).any(|t| t <= 0) {
+            return Err(Error::from("gmsh: the tags must be > 0"));
+        }
+        if E::DIM < 2 {
+            return Err(Error::from("gmsh: unsupported element type"));
+        }
+
+        let elem_dim = E::DIM as i32;
+        let (elem_type, elem_perm) = msh_type(E::DIM, E::N_VERTS)?;
+        let (face_type, face_perm) = msh_type(E::Face::DIM, E::Face::N_VERTS)?;
+
+        let etags = unique_tags(self.etags());
+        let ftags = unique_tags(self.ftags());
+
+        let blocks = ftags
+            .iter()
+            .filter_map(|&t| {
+                let faces = self.faces().zip(self.ftags());
+                msh_block(elem_dim - 1, t, face_type, face_perm, faces)
+            })
+            .chain(etags.iter().filter_map(|&t| {
+                let elems = self.elems().zip(self.etags());
+                msh_block(elem_dim, t, elem_type, elem_perm, elems)
+            }))
+            .collect::<Vec<_>>();
+
+        let names = ftags
+            .iter()
+            .filter_map(|&t| self.tag_names().face_name(t).map(|n| (elem_dim - 1, t, n)))
+            .chain(
+                etags
+                    .iter()
+                    .filter_map(|&t| self.tag_names().elem_name(t).map(|n| (elem_dim, t, n))),
+            )
+            .collect::<Vec<_>>();
+
+        // The faces are written first, then the elements sorted by tag
+        let mut fields = self
+            .vertex_fields()
+            .to_f64_with_comp()
+            .into_iter()
+            .map(|(name, n_comp, values)| MshField {
+                elem: false,
+                name,
+                n_comp,
+                tags: (1..=self.n_verts() as usize).collect(),
+                values,
+            })
+            .collect::<Vec<_>>();
+        if !self.elem_fields().is_empty() {
+            let mut order = (0..self.n_elems()).collect::<Vec<_>>();
+            order.sort_by_key(|&i| self.etag(i));
+            let mut tags = vec![0; order.len()];
+            for (k, &i) in order.iter().enumerate() {
+                tags[i as usize] = self.n_faces() as usize + k + 1;
+            }
+            fields.extend(self.elem_fields().to_f64_with_comp().into_iter().map(
+                |(name, n_comp, values)| MshField {
+                    elem: true,
+                    name,
+                    n_comp,
+                    tags: tags.clone(),
+                    values,
+                },
+            ));
+        }
+
+        let verts = self.verts().collect::<Vec<_>>();
+        write_msh(
+            file_name, binary, &verts, elem_dim, &blocks, &names, &fields,
+        )
+    }
+
+    /// Read a mesh in the Gmsh MSH 4.1 format (ascii or binary).
+    /// The element / face tags are the physical tags of the entities they belong to if defined, or
+    /// the entity tags otherwise, and the physical names are stored in `tag_names`. Elements of
+    /// other types (e.g. points or lines in 3D) are ignored. The $NodeData / $ElementData defined
+    /// on all the vertices / elements are read as f64 fields (the last one for each name)
+    pub fn read_gmsh(file_name: &str) -> Result<Self> {
+        debug!("Read {file_name}");
+
+        if E::DIM < 2 {
+            return Err(Error::from("gmsh: unsupported element type"));
+        }
+        let elem_dim = E::DIM as i32;
+        let (elem_type, elem_perm) = msh_type(E::DIM, E::N_VERTS)?;
+        let (face_type, face_perm) = msh_type(E::Face::DIM, E::Face::N_VERTS)?;
+
+        let data = read_msh::<D>(file_name)?;
+
+        let mut elems = Vec::new();
+        let mut etags = Vec::new();
+        let with_elem_fields = data.fields.iter().any(|f| f.elem);
+        let mut elem_ids = FxHashMap::default();
+        let mut faces = Vec::new();
+        let mut ftags = Vec::new();
+        for b in &data.blocks {
+            if b.dim == elem_dim && b.etype == elem_type {
+                for (ids, &t) in b.conn.chunks(b.n_nodes).zip(&b.elem_tags) {
+                    let mut e = E::default();
+                    elem_perm.iter().zip(ids).for_each(|(&k, &i)| e[k] = i);
+                    if with_elem_fields {
+                        elem_ids.insert(t, elems.len() as Idx);
+                    }
+                    elems.push(e);
+                    etags.push(b.tag);
+                }
+            } else if b.dim == elem_dim - 1 && b.etype == face_type {
+                for ids in b.conn.chunks(b.n_nodes) {
+                    let mut f = E::Face::default();
+                    face_perm.iter().zip(ids).for_each(|(&k, &i)| f[k] = i);
+                    faces.push(f);
+                    ftags.push(b.tag);
+                }
+            }
+        }
+
+        let mut res = Self::new(data.verts, elems, etags, faces, ftags);
+        for (dim, tag, name) in data.names {
+            if dim == elem_dim {
+                res.tag_names_mut().set_elem_name(tag, &name);
+            } else if dim == elem_dim - 1 {
+                res.tag_names_mut().set_face_name(tag, &name);
+            }
+        }
+
+        for f in data.fields {
+            let (ids, n) = if f.elem {
+                (&elem_ids, res.n_elems() as usize)
+            } else {
+                (&data.node_ids, res.n_verts() as usize)
+            };
+            let mut values = vec![0.0; f.n_comp * n];
+            let mut found = vec![false; n];
+            for (tag, v) in f.tags.iter().zip(f.values.chunks(f.n_comp)) {
+                if let Some(&i) = ids.get(tag) {
+                    let i = i as usize;
+                    values[f.n_comp * i..f.n_comp * (i + 1)].copy_from_slice(v);
+                    found[i] = true;
+                }
+            }
+            if found.iter().all(|&x| x) {
+                let fields = if f.elem {
+                    res.elem_fields_mut()
+                } else {
+                    res.vertex_fields_mut()
+                };
+                fields.insert_with_comp(&f.name, f.n_comp, values)?;
+            } else {
+                warn!("gmsh: field {} is not defined everywhere, ignored", f.name);
+            }
+        }
+
+        Ok(res)
+    }
+}
+
+impl HybridMesh {
+    /// Write the mesh in the Gmsh MSH 4.1 format (ascii or binary), with the same entities and
+    /// physical groups as `SimplexMesh::write_gmsh`
+    pub fn write_gmsh(&self, file_name: &str, binary: bool) -> Result<()> {
+        debug!("Write {file_name}");
+
+        let etags = unique_tags(
+            self.tet_tags
+                .iter()
+                .chain(&self.pyr_tags)
+                .chain(&self.pri_tags)
+                .chain(&self.hex_tags)
+                .copied(),
+        );
+        let ftags = unique_tags(self.tri_tags.iter().chain(&self.qua_tags).copied());
+
+        // The node ordering is the same as Gmsh
+        let perm = [0, 1, 2, 3, 4, 5, 6, 7];
+        let mut blocks = Vec::new();
+        for &t in &ftags {
+            let tris = self.tris.iter().copied().zip(self.tri_tags.iter().copied());
+            blocks.extend(msh_block(2, t, MSH_TRIANGLE, &perm[..3], tris));
+            let quas = self.quas.iter().copied().zip(self.qua_tags.iter().copied());
+            blocks.extend(msh_block(2, t, MSH_QUADRANGLE, &perm[..4], quas));
+        }
+        for &t in &etags {
+            let tets = self.tets.iter().copied().zip(self.tet_tags.iter().copied());
+            blocks.extend(msh_block(3, t, MSH_TETRAHEDRON, &perm[..4], tets));
+            let pyrs = self.pyrs.iter().copied().zip(self.pyr_tags.iter().copied());
+            blocks.extend(msh_block(3, t, MSH_PYRAMID, &perm[..5], pyrs));
+            let pris = self.pris.iter().copied().zip(self.pri_tags.iter().copied());
+            blocks.extend(msh_block(3, t, MSH_PRISM, &perm[..6], pris));
+            let hexs = self.hexs.iter().copied().zip(self.hex_tags.iter().copied());
+            blocks.extend(msh_block(3, t, MSH_HEXAHEDRON, &perm, hexs));
+        }
+
+        let names = ftags
+            .iter()
+            .filter_map(|&t| self.tag_names.face_name(t).map(|n| (2, t, n)))
+            .chain(
+                etags
+                    .iter()
+                    .filter_map(|&t| self.tag_names.elem_name(t).map(|n| (3, t, n))),
+            )
+            .collect::<Vec<_>>();
+
+        write_msh(file_name, binary, &self.verts, 3, &blocks, &names, &[])
+    }
+
+    /// Read a mesh in the Gmsh MSH 4.1 format (ascii or binary), as `SimplexMesh::read_gmsh`.
+    /// The tetrahedra, pyramids, prisms, hexahedra, triangles and quadrangles are read and the
+    /// other elements are ignored
+    pub fn read_gmsh(file_name: &str) -> Result<Self> {
+        debug!("Read {file_name}");
+
+        let data = read_msh::<3>(file_name)?;
+
+        let mut res = Self {
+            verts: data.verts,
+            ..Self::default()
+        };
+        for b in &data.blocks {
+            match (b.dim, b.etype) {
+                (3, MSH_TETRAHEDRON) => push_block(b, &mut res.tets, &mut res.tet_tags),
+                (3, MSH_PYRAMID) => push_block(b, &mut res.pyrs, &mut res.pyr_tags),
+                (3, MSH_PRISM) => push_block(b, &mut res.pris, &mut res.pri_tags),
+                (3, MSH_HEXAHEDRON) => push_block(b, &mut res.hexs, &mut res.hex_tags),
+                (2, MSH_TRIANGLE) => push_block(b, &mut res.tris, &mut res.tri_tags),
+                (2, MSH_QUADRANGLE) => push_block(b, &mut res.quas, &mut res.qua_tags),
+                _ => {}
+            }
+        }
+        for (dim, tag, name) in data.names {
+            if dim == 3 {
+                res.tag_names.set_elem_name(tag, &name);
+            } else if dim == 2 {
+                res.tag_names.set_face_name(tag, &name);
+            }
+        }
+
+        Ok(res)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        geometry::NoGeometry,
+        mesh::{
+            test_meshes::{test_hybrid_mesh, test_mesh_2d, test_mesh_3d},
+            Elem, GElem, HybridMesh, SimplexMesh, Tetrahedron, TetrahedronQuadratique, Triangle,
+            TriangleQuadratique,
+        },
+        Result,
+    };
+    use std::io::Write;
+    use tempfile::NamedTempFile;
+
+    fn check_same<const D: usize, E: Elem>(m0: &SimplexMesh<D, E>, m1: &SimplexMesh<D, E>) {
+        assert_eq!(m0.n_verts(), m1.n_verts());
+        assert_eq!(m0.n_elems(), m1.n_elems());
+        assert_eq!(m0.n_faces(), m1.n_faces());
+        for (p0, p1) in m0.verts().zip(m1.verts()) {
+            assert!((p0 - p1).norm() < 1e-12);
+        }
+        // the elements / faces are sorted by tag
+        let mut e0 = m0.elems().zip(m0.etags()).collect::<Vec<_>>();
+        e0.sort_by_key(|(_, t)| *t);
+        for ((e0, t0), (e1, t1)) in e0.iter().zip(m1.elems().zip(m1.etags())) {
+            assert_eq!(*t0, t1);
+            assert!(e0.iter().zip(e1.iter()).all(|(i, j)| i == j));
+        }
+        let mut f0 = m0.faces().zip(m0.ftags()).collect::<Vec<_>>();
+        f0.sort_by_key(|(_, t)| *t);
+        for ((f0, t0), (f1, t1)) in f0.iter().zip(m1.faces().zip(m1.ftags())) {
+            assert_eq!(*t0, t1);
+            assert!(f0.iter().zip(f1.iter()).all(|(i, j)| i == j));
+        }
+    }
+
+    #[test]
+    fn test_gmsh_2d() -> Result<()> {
+        let mesh = test_mesh_2d().split();
+
+        for binary in [false, true] {
+            let file = NamedTempFile::new().unwrap();
+            let fname = file.path().to_str().unwrap().to_owned() + ".msh";
+            mesh.write_gmsh(&fname, binary)?;
+            let mesh2 = SimplexMesh::<2, Triangle>::read_gmsh(&fname)?;
+            check_same(&mesh, &mesh2);
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_gmsh_fields() -> Result<()> {
+        let mut mesh = test_mesh_3d().split();
+        let v = mesh.verts().flat_map(|p| [p[0], p[1]]).collect::<Vec<_>>();
+        mesh.add_vertex_field::<f64, 2>("x", v.clone())?;
+        let c = mesh.gelems().map(|ge| ge.center()[2]).collect::<Vec<_>>();
+        mesh.add_elem_field::<f64, 1>("z", c)?;
+
+        for binary in [false, true] {
+            let file = NamedTempFile::new().unwrap();
+            let fname = file.path().to_str().unwrap().to_owned() + ".msh";
+            mesh.write_gmsh(&fname, binary)?;
+            let mesh2 = SimplexMesh::<3, Tetrahedron>::read_gmsh(&fname)?;
+            check_same(&mesh, &mesh2);
+            assert_eq!(mesh2.get_vertex_field::<f64, 2>("x")?, &v[..]);
+            let c = mesh2.get_elem_field::<f64, 1>("z")?;
+            for (ge, &z) in mesh2.gelems().zip(c) {
+                assert!((ge.center()[2] - z).abs() < 1e-12);
+            }
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_gmsh_3d() -> Result<()> {
+        let mesh = test_mesh_3d().split();
+
+        for binary in [false, true] {
+            let file = NamedTempFile::new().unwrap();
+            let fname = file.path().to_str().unwrap().to_owned() + ".msh";
+            mesh.write_gmsh(&fname, binary)?;
+            let mesh2 = SimplexMesh::<3, Tetrahedron>::read_gmsh(&fname)?;
+            check_same(&mesh, &mesh2);
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_gmsh_physical_groups() -> Result<()> {
+        // A square with 2 triangles: surface 1 in physical group 10, curves 1/2 in group 20 and
+        // curve 3 without physical group
+        let msh = r#"$MeshFormat
+4.1 0 8
+$EndMeshFormat
+$PhysicalNames
+2
+1 20 "wall"
+2 10 "fluid"
+$EndPhysicalNames
+$Entities
+0 3 1 0
+1 0 0 0 1 0 0 1 20 0
+2 1 0 0 1 1 0 1 20 0
+3 0 0 0 1 1 0 0 0
+1 0 0 0 1 1 0 1 10 0
+$EndEntities
+$Nodes
+1 4 1 4
+2 1 0 4
+1
+2
+3
+4
+0 0 0
+1 0 0
+1 1 0
+0 1 0
+$EndNodes
+$Elements
+4 5 1 5
+1 1 1 1
+1 1 2
+1 2 1 1
+2 2 3
+1 3 1 1
+3 3 4
+2 1 2 2
+4 1 2 3
+5 1 3 4
+$EndElements
+"#;
+        let mut file = NamedTempFile::new().unwrap();
+        file.write_all(msh.as_bytes()).unwrap();
+        let mesh = SimplexMesh::<2, Triangle>::read_gmsh(file.path().to_str().unwrap())?;
+
+        assert_eq!(mesh.n_verts(), 4);
+        assert_eq!(mesh.n_elems(), 2);
+        assert_eq!(mesh.n_faces(), 3);
+        assert!(mesh.etags().all(|t| t == 10));
+        assert_eq!(mesh.ftags().collect::<Vec<_>>(), [20, 20, 3]);
+        assert!((mesh.vol() - 1.0).abs() < 1e-12);
+        assert_eq!(mesh.tag_names().elem_tag_by_name("fluid"), Some(10));
+        assert_eq!(mesh.tag_names().face_tag_by_name("wall"), Some(20));
+        assert_eq!(mesh.tag_names().face_name(3), None);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_gmsh_physical_names() -> Result<()> {
+        let mut mesh = test_mesh_3d().split();
+        mesh.tag_names_mut().set_elem_name(1, "fluid");
+        mesh.tag_names_mut().set_face_name(2, "inlet");
+        mesh.tag_names_mut().set_face_name(3, "outlet wall");
+
+        for binary in [false, true] {
+            let file = NamedTempFile::new().unwrap();
+            let fname = file.path().to_str().unwrap().to_owned() + ".msh";
+            mesh.write_gmsh(&fname, binary)?;
+            let mesh2 = SimplexMesh::<3, Tetrahedron>::read_gmsh(&fname)?;
+
+            check_same(&mesh, &mesh2);
+            assert_eq!(mesh2.tag_names().elem_tag_by_name("fluid"), Some(1));
+            assert_eq!(mesh2.tag_names().face_tag_by_name("inlet"), Some(2));
+            assert_eq!(mesh2.tag_names().face_name(3), Some("outlet wall"));
+            assert_eq!(mesh2.tag_names().face_name(1), None);
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_gmsh_quadratic() -> Result<()> {
+        let mesh = test_mesh_2d().split().to_quadratic(&NoGeometry())?;
+        let file = NamedTempFile::new().unwrap();
+        let fname = file.path().to_str().unwrap().to_owned() + ".msh";
+        mesh.write_gmsh(&fname, false)?;
+        let mesh2 = SimplexMesh::<2, TriangleQuadratique>::read_gmsh(&fname)?;
+        check_same(&mesh, &mesh2);
+
+        let mesh = test_mesh_3d().split().to_quadratic(&NoGeometry())?;
+        let file = NamedTempFile::new().unwrap();
+        let fname = file.path().to_str().unwrap().to_owned() + ".msh";
+        mesh.write_gmsh(&fname, true)?;
+        let mesh2 = SimplexMesh::<3, TetrahedronQuadratique>::read_gmsh(&fname)?;
+        check_same(&mesh, &mesh2);
+
+        // Gmsh node ordering
+        let msh = r#"$MeshFormat
+4.1 0 8
+$EndMeshFormat
+$Entities
+0 0 0 1
+1 0 0 0 1 1 1 0 0
+$EndEntities
+$Nodes
+1 10 1 10
+3 1 0 10
+1
+2
+3
+4
+5
+6
+7
+8
+9
+10
+0 0 0
+1 0 0
+0 1 0
+0 0 1
+0.5 0 0
+0.5 0.5 0
+0 0.5 0
+0 0 0.5
+0 0.5 0.5
+0.5 0 0.5
+$EndNodes
+$Elements
+1 1 1 1
+3 1 11 1
+1 1 2 3 4 5 6 7 8 9 10
+$EndElements
+"#;
+        let mut file = NamedTempFile::new().unwrap();
+        file.write_all(msh.as_bytes()).unwrap();
+        let mesh =
+            SimplexMesh::<3, TetrahedronQuadratique>::read_gmsh(file.path().to_str().unwrap())?;
+        assert_eq!(mesh.n_elems(), 1);
+        let e = mesh.elem(0);
+        for i_edge in 0..TetrahedronQuadratique::N_EDGES {
+            let [i0, i1] = e.edge(i_edge);
+            let p = 0.5 * (mesh.vert(i0) + mesh.vert(i1));
+            assert!((mesh.vert(e.mid_node(i_edge)) - p).norm() < 1e-12);
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_gmsh_hybrid() -> Result<()> {
+        let mesh = test_hybrid_mesh();
+
+        for binary in [false, true] {
+            let file = NamedTempFile::new().unwrap();
+            let fname = file.path().to_str().unwrap().to_owned() + ".msh";
+            mesh.write_gmsh(&fname, binary)?;
+            let other = HybridMesh::read_gmsh(&fname)?;
+
+            assert_eq!(other.n_verts(), mesh.n_verts());
+            for (p0, p1) in mesh.verts.iter().zip(other.verts.iter()) {
+                assert!((p0 - p1).norm() < 1e-12);
+            }
+            assert!(other.tets.is_empty());
+            assert_eq!(other.pyrs, mesh.pyrs);
+            assert_eq!(other.pyr_tags, mesh.pyr_tags);
+            assert_eq!(other.pris, mesh.pris);
+            assert_eq!(other.pri_tags, mesh.pri_tags);
+            assert_eq!(other.hexs, mesh.hexs);
+            assert_eq!(other.hex_tags, mesh.hex_tags);
+            assert_eq!(other.tris, mesh.tris);
+            assert_eq!(other.tri_tags, mesh.tri_tags);
+            assert_eq!(other.quas, mesh.quas);
+            assert_eq!(other.qua_tags, mesh.qua_tags);
+            assert_eq!(other.tag_names.elem_name(2), Some("prism"));
+            assert_eq!(other.tag_names.face_name(2), Some("side"));
+
+            // read the faces and tetrahedra only
+            let tets = SimplexMesh::<3, Tetrahedron>::read_gmsh(&fname)?;
+            assert_eq!(tets.n_elems(), 0);
+            assert_eq!(tets.n_faces(), 6);
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,640 @@
+use crate::{
+    mesh::{Elem, Point, SimplexMesh},
+    Error, Idx, Result, Tag,
+};
+use log::debug;
+use rustc_hash::FxHashMap;
+use std::{
+    collections::VecDeque,
+    fs::File,
+    io::{BufRead, BufReader, Read, Seek, SeekFrom},
+};
+
+/// Streaming access to the vertices, elements and faces of a mesh file, in order to process
+/// (e.g. filter or partition) large meshes by chunks without loading the whole mesh
+pub trait MeshReader<const D: usize, E: Elem> {
+    /// Total number of vertices
+    fn n_verts(&self) -> usize;
+
+    /// Total number of elements
+    fn n_elems(&self) -> usize;
+
+    /// Total number of faces
+    fn n_faces(&self) -> usize;
+
+    /// Read the next `n` vertices (or less at the end). An empty chunk is returned once all the
+    /// vertices have been read
+    fn next_vertices_chunk(&mut self, n: usize) -> Result<Vec<Point<D>>>;
+
+    /// Read the next `n` elements and their tags (or less at the end). An empty chunk is returned
+    /// once all the elements have been read
+    fn next_elems_chunk(&mut self, n: usize) -> Result<(Vec<E>, Vec<Tag>)>;
+
+    /// Read the next `n` faces and their tags (or less at the end). An empty chunk is returned
+    /// once all the faces have been read
+    fn next_faces_chunk(&mut self, n: usize) -> Result<(Vec<E::Face>, Vec<Tag>)>;
+
+    /// Read the remaining vertices, elements and faces by chunks of `chunk_size`, and build a
+    /// mesh
+    fn read_mesh(&mut self, chunk_size: usize) -> Result<SimplexMesh<D, E>> {
+        let mut verts = Vec::with_capacity(self.n_verts());
+        loop {
+            let chunk = self.next_vertices_chunk(chunk_size)?;
+            if chunk.is_empty() {
+                break;
+            }
+            verts.extend(chunk);
+        }
+        let mut elems = Vec::with_capacity(self.n_elems());
+        let mut etags = Vec::with_capacity(self.n_elems());
+        loop {
+            let (chunk, tags) = self.next_elems_chunk(chunk_size)?;
+            if chunk.is_empty() {
+                break;
+            }
+            elems.extend(chunk);
+            etags.extend(tags);
+        }
+        let mut faces = Vec::with_capacity(self.n_faces());
+        let mut ftags = Vec::with_capacity(self.n_faces());
+        loop {
+            let (chunk, tags) = self.next_faces_chunk(chunk_size)?;
+            if chunk.is_empty() {
+                break;
+            }
+            faces.extend(chunk);
+            ftags.extend(tags);
+        }
+        Ok(SimplexMesh::new(verts, elems, etags, faces, ftags))
+    }
+}
+
+pub(super) const GMF_DIMENSION: i64 = 3;
+pub(super) const GMF_VERTICES: i64 = 4;
+const GMF_EDGES: i64 = 5;
+const GMF_TRIANGLES: i64 = 6;
+const GMF_TETRAHEDRA: i64 = 8;
+const GMF_TRIANGLES_P2: i64 = 24;
+const GMF_EDGES_P2: i64 = 25;
+const GMF_TETRAHEDRA_P2: i64 = 30;
+pub(super) const GMF_END: i64 = 54;
+pub(super) const GMF_SOL_AT_VERTICES: i64 = 62;
+pub(super) const GMF_SOL_AT_TRIANGLES: i64 = 64;
+pub(super) const GMF_SOL_AT_TETRAHEDRA: i64 = 66;
+
+/// Keyword codes used for the elements and faces of simplices with `n` nodes
+pub(super) fn gmf_keywords(n: Idx) -> Result<(i64, i64)> {
+    match n {
+        3 => Ok((GMF_TRIANGLES, GMF_EDGES)),
+        4 => Ok((GMF_TETRAHEDRA, GMF_TRIANGLES)),
+        6 => Ok((GMF_TRIANGLES_P2, GMF_EDGES_P2)),
+        10 => Ok((GMF_TETRAHEDRA_P2, GMF_TRIANGLES_P2)),
+        _ => Err(Error::from("meshb: unsupported element type")),
+    }
+}
+
+/// Keyword code from its name in .mesh files
+fn gmf_keyword(name: &str) -> Option<i64> {
+    match name {
+        "Dimension" => Some(GMF_DIMENSION),
+        "Vertices" => Some(GMF_VERTICES),
+        "Edges" => Some(GMF_EDGES),
+        "Triangles" => Some(GMF_TRIANGLES),
+        "Tetrahedra" => Some(GMF_TETRAHEDRA),
+        "TrianglesP2" => Some(GMF_TRIANGLES_P2),
+        "EdgesP2" => Some(GMF_EDGES_P2),
+        "TetrahedraP2" => Some(GMF_TETRAHEDRA_P2),
+        "End" => Some(GMF_END),
+        "SolAtVertices" => Some(GMF_SOL_AT_VERTICES),
+        "SolAtTriangles" => Some(GMF_SOL_AT_TRIANGLES),
+        "SolAtTetrahedra" => Some(GMF_SOL_AT_TETRAHEDRA),
+        _ => None,
+    }
+}
+
+/// Name of a keyword in .mesh files
+pub(super) const fn gmf_name(kwd: i64) -> &'static str {
+    match kwd {
+        GMF_DIMENSION => "Dimension",
+        GMF_VERTICES => "Vertices",
+        GMF_EDGES => "Edges",
+        GMF_TRIANGLES => "Triangles",
+        GMF_TETRAHEDRA => "Tetrahedra",
+        GMF_TRIANGLES_P2 => "TrianglesP2",
+        GMF_EDGES_P2 => "EdgesP2",
+        GMF_TETRAHEDRA_P2 => "TetrahedraP2",
+        GMF_SOL_AT_VERTICES => "SolAtVertices",
+        GMF_SOL_AT_TRIANGLES => "SolAtTriangles",
+        GMF_SOL_AT_TETRAHEDRA => "SolAtTetrahedra",
+        _ => "End",
+    }
+}
+
+/// Node ordering of the elements of a keyword: the k-th node in the file is node `order[k]` of
+/// the element. The mid-edge nodes of the second order elements are given after the vertices, for
+/// the edges (0, 1), (1, 2), (2, 0) (triangles) and (0, 3), (1, 3), (2, 3) (tetrahedra)
+pub(super) const fn gmf_node_order(kwd: i64) -> &'static [usize] {
+    match kwd {
+        GMF_EDGES => &[0, 1],
+        GMF_TRIANGLES | GMF_EDGES_P2 => &[0, 1, 2],
+        GMF_TETRAHEDRA => &[0, 1, 2, 3],
+        GMF_TRIANGLES_P2 => &[0, 1, 2, 5, 3, 4],
+        GMF_TETRAHEDRA_P2 => &[0, 1, 2, 3, 9, 6, 7, 8, 5, 4],
+        _ => &[],
+    }
+}
+
+/// Read a native endian integer with `size` bytes
+fn read_binary_int<R: Read>(reader: &mut R, size: usize) -> Result<i64> {
+    if size == 4 {
+        let mut buf = [0_u8; 4];
+        reader.read_exact(&mut buf)?;
+        Ok(i64::from(i32::from_ne_bytes(buf)))
+    } else {
+        let mut buf = [0_u8; 8];
+        reader.read_exact(&mut buf)?;
+        Ok(i64::from_ne_bytes(buf))
+    }
+}
+
+#[derive(Clone, Copy)]
+struct MeshbFormat {
+    binary: bool,
+    version: i64,
+}
+
+impl MeshbFormat {
+    /// Size of the integers (indices, tags, number of entities)
+    const fn int_size(self) -> usize {
+        if self.version >= 4 {
+            8
+        } else {
+            4
+        }
+    }
+
+    /// Size of the positions in the file
+    const fn pos_size(self) -> usize {
+        if self.version >= 3 {
+            8
+        } else {
+            4
+        }
+    }
+
+    /// Size of the reals
+    const fn real_size(self) -> usize {
+        if self.version == 1 {
+            4
+        } else {
+            8
+        }
+    }
+}
+
+/// Cursor on the data of a keyword (vertices, elements or faces)
+struct MeshbSection {
+    reader: BufReader<File>,
+    format: MeshbFormat,
+    /// Node ordering of the elements
+    order: &'static [usize],
+    count: usize,
+    remaining: usize,
+    tokens: VecDeque<String>,
+}
+
+impl MeshbSection {
+    fn new(
+        file_name: &str,
+        format: MeshbFormat,
+        order: &'static [usize],
+        offset: u64,
+        count: usize,
+    ) -> Result<Self> {
+        let mut reader = BufReader::new(File::open(file_name)?);
+        reader.seek(SeekFrom::Start(offset))?;
+        Ok(Self {
+            reader,
+            format,
+            order,
+            count,
+            remaining: count,
+            tokens: VecDeque::new(),
+        })
+    }
+
+    fn token(&mut self) -> Result<String> {
+        while self.tokens.is_empty() {
+            let mut line = String::new();
+            if self.reader.read_line(&mut line)? == 0 {
+                return Err(Error::from("meshb: unexpected end of file"));
+            }
+            self.tokens
+                .extend(line.split_whitespace().map(str::to_string));
+        }
+        Ok(self.tokens.pop_front().unwrap())
+    }
+
+    fn int(&mut self) -> Result<i64> {
+        if self.format.binary {
+            read_binary_int(&mut self.reader, self.format.int_size())
+        } else {
+            Ok(self.token()?.parse()?)
+        }
+    }
+
+    /// Read a 4 bytes integer (e.g. the solution types) whatever the file version
+    fn word(&mut self) -> Result<i64> {
+        if self.format.binary {
+            read_binary_int(&mut self.reader, 4)
+        } else {
+            Ok(self.token()?.parse()?)
+        }
+    }
+
+    fn real(&mut self) -> Result<f64> {
+        if self.format.binary {
+            if self.format.real_size() == 4 {
+                let mut buf = [0_u8; 4];
+                self.reader.read_exact(&mut buf)?;
+                Ok(f64::from(f32::from_ne_bytes(buf)))
+            } else {
+                let mut buf = [0_u8; 8];
+                self.reader.read_exact(&mut buf)?;
+                Ok(f64::from_ne_bytes(buf))
+            }
+        } else {
+            Ok(self.token()?.parse()?)
+        }
+    }
+
+    /// Read the next `n` (or less) elements and their tags
+    fn next_elems<E: Elem>(&mut self, n: usize) -> Result<(Vec<E>, Vec<Tag>)> {
+        if self.order.len() != E::N_VERTS as usize {
+            return Err(Error::from("meshb: invalid element type"));
+        }
+        let n = n.min(self.remaining);
+        let mut elems = Vec::with_capacity(n);
+        let mut tags = Vec::with_capacity(n);
+        for _ in 0..n {
+            let mut e = E::default();
+            for &k in self.order {
+                e[k] = (self.int()? - 1) as Idx;
+            }
+            elems.push(e);
+            tags.push(self.int()? as Tag);
+        }
+        self.remaining -= n;
+        Ok((elems, tags))
+    }
+}
+
+/// Chunked reader for .mesh (ascii) and .meshb (binary, native endianness) files. The file is
+/// opened with minimeshb to check the dimension and get the number of entities, then scanned once
+/// to locate the vertices, elements and faces, which can then be read independently
+pub struct MeshbChunkReader<const D: usize, E: Elem> {
+    verts: Option<MeshbSection>,
+    elems: Option<MeshbSection>,
+    faces: Option<MeshbSection>,
+    _elem: std::marker::PhantomData<E>,
+}
+
+impl<const D: usize, E: Elem> MeshbChunkReader<D, E> {
+    pub fn new(file_name: &str) -> Result<Self> {
+        debug!("Open {file_name}");
+
+        let (elem_kwd, face_kwd) = gmf_keywords(E::N_VERTS)?;
+        let (format, offsets) = if file_name.ends_with(".meshb") {
+            scan_binary(file_name)?
+        } else {
+            scan_ascii(file_name)?
+        };
+
+        // the dimension is stored as the count of the Dimension keyword
+        if offsets.get(&GMF_DIMENSION).map(|&(_, dim)| dim) != Some(D) {
+            return Err(Error::from("invalid dimension"));
+        }
+
+        let section = |kwd| -> Result<Option<MeshbSection>> {
+            offsets
+                .get(&kwd)
+                .map(|&(offset, count)| {
+                    MeshbSection::new(file_name, format, gmf_node_order(kwd), offset, count)
+                })
+                .transpose()
+        };
+
+        Ok(Self {
+            verts: section(GMF_VERTICES)?,
+            elems: section(elem_kwd)?,
+            faces: section(face_kwd)?,
+            _elem: std::marker::PhantomData,
+        })
+    }
+}
+
+/// Locate the data of the keywords in a .meshb file, following the positions of the next
+/// keywords, and get the number of entities (or the dimension for the Dimension keyword)
+fn scan_binary(file_name: &str) -> Result<(MeshbFormat, FxHashMap<i64, (u64, usize)>)> {
+    let mut reader = BufReader::new(File::open(file_name)?);
+    if read_binary_int(&mut reader, 4)? != 1 {
+        return Err(Error::from("meshb: unsupported endianness"));
+    }
+    let version = read_binary_int(&mut reader, 4)?;
+    if !(1..=4).contains(&version) {
+        return Err(Error::from(&format!("meshb: invalid version {version}")));
+    }
+    let format = MeshbFormat {
+        binary: true,
+        version,
+    };
+
+    let mut offsets = FxHashMap::default();
+    let mut pos = 8;
+    loop {
+        reader.seek(SeekFrom::Start(pos))?;
+        let kwd = read_binary_int(&mut reader, 4)?;
+        if kwd == GMF_END {
+            break;
+        }
+        let next = read_binary_int(&mut reader, format.pos_size())? as u64;
+        if kwd == GMF_DIMENSION {
+            // always stored as a 4 bytes integer
+            let dim = read_binary_int(&mut reader, 4)? as usize;
+            offsets.insert(kwd, (pos + 4 + format.pos_size() as u64 + 4, dim));
+        } else if matches!(
+            kwd,
+            GMF_VERTICES
+                | GMF_EDGES
+                | GMF_TRIANGLES
+                | GMF_TETRAHEDRA
+                | GMF_TRIANGLES_P2
+                | GMF_EDGES_P2
+                | GMF_TETRAHEDRA_P2
+                | GMF_SOL_AT_VERTICES
+                | GMF_SOL_AT_TRIANGLES
+                | GMF_SOL_AT_TETRAHEDRA
+        ) {
+            let count = read_binary_int(&mut reader, format.int_size())? as usize;
+            let offset = pos + (4 + format.pos_size() + format.int_size()) as u64;
+            offsets.insert(kwd, (offset, count));
+        }
+        if next == 0 {
+            break;
+        }
+        pos = next;
+    }
+
+    Ok((format, offsets))
+}
+
+/// Locate the data of the keywords in a .mesh file and get the number of entities (or the
+/// dimension for the Dimension keyword), that is expected to be on the keyword line or the next one, the data being on the following lines
+fn scan_ascii(file_name: &str) -> Result<(MeshbFormat, FxHashMap<i64, (u64, usize)>)> {
+    let mut reader = BufReader::new(File::open(file_name)?);
+    let format = MeshbFormat {
+        binary: false,
+        version: 2,
+    };
+
+    let mut offsets = FxHashMap::default();
+    let mut offset = 0;
+    let mut current = None;
+    let mut line = String::new();
+    loop {
+        line.clear();
+        let n = reader.read_line(&mut line)?;
+        if n == 0 {
+            break;
+        }
+        offset += n as u64;
+        let mut words = line.split_whitespace().peekable();
+        if words
+            .peek()
+            .is_some_and(|w| w.starts_with(char::is_alphabetic))
+        {
+            let name = words.next().unwrap();
+            current = gmf_keyword(name);
+            if current == Some(GMF_END) {
+                break;
+            }
+        }
+        if let (Some(kwd), Some(count)) = (current, words.next()) {
+            offsets.insert(kwd, (offset, count.parse()?));
+            current = None;
+        }
+    }
+
+    Ok((format, offsets))
+}
+
+/// Read the solution with keyword `kwd` (e.g. SolAtVertices) from a .sol (ascii) or .solb
+/// (binary) file. Returns the number of values per entity and the values, or None if the file
+/// does not contain `kwd`
+pub(super) fn read_gmf_sol(
+    file_name: &str,
+    dim: usize,
+    kwd: i64,
+) -> Result<Option<(usize, Vec<f64>)>> {
+    debug!("Read {file_name}");
+
+    let (format, offsets) = if file_name.ends_with(".solb") {
+        scan_binary(file_name)?
+    } else {
+        scan_ascii(file_name)?
+    };
+    let Some(&(offset, count)) = offsets.get(&kwd) else {
+        return Ok(None);
+    };
+
+    let mut s = MeshbSection::new(file_name, format, &[], offset, count)?;
+    let mut n_comp = 0;
+    for _ in 0..s.word()? {
+        n_comp += match s.word()? {
+            1 => 1,
+            2 => dim,
+            3 => dim * (dim + 1) / 2,
+            4 => dim * dim,
+            t => return Err(Error::from(&format!("meshb: invalid solution type {t}"))),
+        };
+    }
+    let mut values = Vec::with_capacity(n_comp * count);
+    for _ in 0..n_comp * count {
+        values.push(s.real()?);
+    }
+
+    Ok(Some((n_comp, values)))
+}
+
+impl<const D: usize, E: Elem> MeshReader<D, E> for MeshbChunkReader<D, E> {
+    fn n_verts(&self) -> usize {
+        self.verts.as_ref().map_or(0, |s| s.count)
+    }
+
+    fn n_elems(&self) -> usize {
+        self.elems.as_ref().map_or(0, |s| s.count)
+    }
+
+    fn n_faces(&self) -> usize {
+        self.faces.as_ref().map_or(0, |s| s.count)
+    }
+
+    fn next_vertices_chunk(&mut self, n: usize) -> Result<Vec<Point<D>>> {
+        let Some(s) = self.verts.as_mut() else {
+            return Ok(Vec::new());
+        };
+        let n = n.min(s.remaining);
+        let mut verts = Vec::with_capacity(n);
+        for _ in 0..n {
+            let mut p = Point::<D>::zeros();
+            for x in p.iter_mut() {
+                *x = s.real()?;
+            }
+            // vertex tag
+            s.int()?;
+            verts.push(p);
+        }
+        s.remaining -= n;
+        Ok(verts)
+    }
+
+    fn next_elems_chunk(&mut self, n: usize) -> Result<(Vec<E>, Vec<Tag>)> {
+        self.elems
+            .as_mut()
+            .map_or_else(|| Ok((Vec::new(), Vec::new())), |s| s.next_elems(n))
+    }
+
+    fn next_faces_chunk(&mut self, n: usize) -> Result<(Vec<E::Face>, Vec<Tag>)> {
+        self.faces
+            .as_mut()
+            .map_or_else(|| Ok((Vec::new(), Vec::new())), |s| s.next_elems(n))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{MeshReader, MeshbChunkReader};
+    use crate::{
+        mesh::{
+            test_meshes::{test_mesh_2d, test_mesh_3d},
+            Elem, SimplexMesh, Tetrahedron, Triangle,
+        },
+        Result,
+    };
+    use tempfile::NamedTempFile;
+
+    fn check_same<const D: usize, E: Elem>(m0: &SimplexMesh<D, E>, m1: &SimplexMesh<D, E>) {
+        assert_eq!(m0.n_verts(), m1.n_verts());
+        assert_eq!(m0.n_elems(), m1.n_elems());
+        assert_eq!(m0.n_faces(), m1.n_faces());
+        for (p0, p1) in m0.verts().zip(m1.verts()) {
+            assert!((p0 - p1).norm() < 1e-12);
+        }
+        assert!(m0.elems().zip(m1.elems()).all(|(e0, e1)| e0 == e1));
+        assert!(m0.etags().zip(m1.etags()).all(|(t0, t1)| t0 == t1));
+        assert!(m0.faces().zip(m1.faces()).all(|(f0, f1)| f0 == f1));
+        assert!(m0.ftags().zip(m1.ftags()).all(|(t0, t1)| t0 == t1));
+    }
+
+    #[test]
+    fn test_chunks_2d() -> Result<()> {
+        let mesh = test_mesh_2d().split().split();
+
+        for ext in [".mesh", ".meshb"] {
+            let file = NamedTempFile::new().unwrap();
+            let fname = file.path().to_str().unwrap().to_owned() + ext;
+            mesh.write_meshb(&fname)?;
+
+            let mut reader = MeshbChunkReader::<2, Triangle>::new(&fname)?;
+            assert_eq!(reader.n_verts(), mesh.n_verts() as usize);
+            assert_eq!(reader.n_elems(), mesh.n_elems() as usize);
+            assert_eq!(reader.n_faces(), mesh.n_faces() as usize);
+            let mesh2 = reader.read_mesh(7)?;
+            check_same(&mesh, &mesh2);
+            assert!(reader.next_vertices_chunk(7)?.is_empty());
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_chunks_3d() -> Result<()> {
+        let mesh = test_mesh_3d().split();
+
+        for ext in [".mesh", ".meshb"] {
+            let file = NamedTempFile::new().unwrap();
+            let fname = file.path().to_str().unwrap().to_owned() + ext;
+            mesh.write_meshb(&fname)?;
+
+            // filter the elements on the fly, before reading the vertices
+            let mut reader = MeshbChunkReader::<3, Tetrahedron>::new(&fname)?;
+            let mut n_elems = 0;
+            loop {
+                let (elems, tags) = reader.next_elems_chunk(5)?;
+                if elems.is_empty() {
+                    break;
+                }
+                assert!(elems.len() <= 5);
+                n_elems += tags.iter().filter(|&&t| t == 1).count();
+            }
+            assert_eq!(n_elems, mesh.etags().filter(|&t| t == 1).count());
+
+            let mut n_verts = 0;
+            loop {
+                let verts = reader.next_vertices_chunk(10)?;
+                if verts.is_empty() {
+                    break;
+                }
+                for (p0, p1) in verts.iter().zip(mesh.verts().skip(n_verts)) {
+                    assert!((p0 - p1).norm() < 1e-12);
+                }
+                n_verts += verts.len();
+            }
+            assert_eq!(n_verts, mesh.n_verts() as usize);
+
+            let mesh2 = MeshbChunkReader::<3, Tetrahedron>::new(&fname)?.read_mesh(1000)?;
+            check_same(&mesh, &mesh2);
+        }
+
+        assert!(MeshbChunkReader::<2, Triangle>::new("missing.meshb").is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_lazy() -> Result<()> {
+        let mesh = test_mesh_2d().split();
+
+        let file = NamedTempFile::new().unwrap();
+        let fname = file.path().to_str().unwrap().to_owned() + ".mesh";
+        mesh.write_meshb(&fname)?;
+        assert!(MeshbChunkReader::<3, Tetrahedron>::new(&fname).is_err());
+
+        // corrupt the last vertex: only the keywords are read when the reader is created
+        let content = std::fs::read_to_string(&fname)?;
+        let mut lines = content.lines().collect::<Vec<_>>();
+        let start = lines
+            .iter()
+            .position(|l| l.starts_with("Vertices"))
+            .unwrap();
+        let offset = if lines[start].split_whitespace().count() == 1 {
+            2
+        } else {
+            1
+        };
+        lines[start + offset + mesh.n_verts() as usize - 1] = "a b c";
+        std::fs::write(&fname, lines.join("\n"))?;
+
+        let mut reader = MeshbChunkReader::<2, Triangle>::new(&fname)?;
+        assert_eq!(reader.n_verts(), mesh.n_verts() as usize);
+        assert_eq!(reader.n_elems(), mesh.n_elems() as usize);
+        assert_eq!(reader.n_faces(), mesh.n_faces() as usize);
+        let (elems, _) = reader.next_elems_chunk(usize::MAX)?;
+        assert_eq!(elems.len(), mesh.n_elems() as usize);
+        let n = mesh.n_verts() as usize - 1;
+        assert_eq!(reader.next_vertices_chunk(n)?.len(), n);
+        assert!(reader.next_vertices_chunk(1).is_err());
+
+        Ok(())
+    }
+}
@@ -1,5 +1,5 @@
 use crate::{
-    mesh::{Elem, SimplexMesh},
+    mesh::{io::vtu_io::vtk_cell_type, Elem, SimplexMesh},
     Result,
 };
 use log::debug;
@@ -20,14 +20,21 @@ impl<const D: usize, E: Elem> SimplexMesh<D, E> {
         elem_data: Option<HashMap<String, &[f64]>>,
     ) -> Result<()> {
         debug!("Write {file_name}");
-        let connectivity = self.elems().flatten().map(u64::from).collect();
+        let (_, perm) = vtk_cell_type(E::DIM, E::N_VERTS);
+        let connectivity = self
+            .elems()
+            .flat_map(|e| perm.iter().map(move |&k| u64::from(e[k])))
+            .collect();
         let offsets = (0..self.n_elems())
             .map(|i| u64::from(E::N_VERTS * (i + 1)))
             .collect();
-        let cell_type = match E::N_VERTS {
-            4 => CellType::Tetra,
-            3 => CellType::Triangle,
-            2 => CellType::PolyLine,
+        let cell_type = match (E::DIM, E::N_VERTS) {
+            (3, 4) => CellType::Tetra,
+            (2, 3) => CellType::Triangle,
+            (1, 2) => CellType::PolyLine,
+            (3, 10) => CellType::QuadraticTetra,
+            (2, 6) => CellType::QuadraticTriangle,
+            (1, 3) => CellType::QuadraticEdge,
             _ => unreachable!(),
         };
         let mut point_data = Vec::new();
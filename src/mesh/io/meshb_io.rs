@@ -1,6 +1,18 @@
+use super::mesh_reader::{
+    gmf_keywords, gmf_name, gmf_node_order, read_gmf_sol, MeshReader, MeshbChunkReader,
+    GMF_DIMENSION, GMF_END, GMF_SOL_AT_TETRAHEDRA, GMF_SOL_AT_TRIANGLES, GMF_SOL_AT_VERTICES,
+    GMF_VERTICES,
+};
 use crate::{
     mesh::{Elem, Point, SimplexMesh},
-    Idx, Result, Tag,
+    Error, Idx, Result, Tag,
+};
+use log::debug;
+use rustc_hash::FxHashSet;
+use std::{
+    fs::File,
+    io::{BufWriter, Write},
+    path::Path,
 };
 #[cfg(not(feature = "libmeshb"))]
 type Reader = minimeshb::reader::MeshbReader;
@@ -29,8 +41,248 @@ type Writer = minimeshb::libmeshb::GmfWriter;
 //     }
 // }
 
+/// Name of the file used to store the vertex (`kind == "vert"`) or element (`kind == "elem"`)
+/// field `name` of the mesh written in `file_name`: `<prefix>.<kind>.<name>.solb` for .meshb
+/// files and `<prefix>.<kind>.<name>.sol` for .mesh files
+fn field_file_name(file_name: &str, kind: &str, name: &str) -> String {
+    let (prefix, ext) = split_meshb_file_name(file_name);
+    format!("{prefix}.{kind}.{name}.{ext}")
+}
+
+/// Split a .mesh / .meshb file name into its prefix and the extension of the solution files
+fn split_meshb_file_name(file_name: &str) -> (&str, &str) {
+    file_name.strip_suffix(".meshb").map_or_else(
+        || (file_name.strip_suffix(".mesh").unwrap_or(file_name), "sol"),
+        |prefix| (prefix, "solb"),
+    )
+}
+
+/// Name of the index file listing the fields written alongside the mesh file `file_name`:
+/// `<prefix>.fields`, with one `<kind> <name>` line per field
+fn field_index_file_name(file_name: &str) -> String {
+    format!("{}.fields", split_meshb_file_name(file_name).0)
+}
+
+/// Get the (kind, name) of the fields listed in the index file of `file_name` (see
+/// `field_index_file_name`)
+fn read_field_index(file_name: &str) -> Result<Vec<(String, String)>> {
+    let fname = field_index_file_name(file_name);
+    let content = std::fs::read_to_string(&fname)
+        .map_err(|e| Error::from(&format!("unable to read {fname}: {e}")))?;
+    content
+        .lines()
+        .filter(|l| !l.trim().is_empty())
+        .map(|l| {
+            l.split_once(' ')
+                .filter(|(kind, name)| (*kind == "vert" || *kind == "elem") && !name.is_empty())
+                .map(|(kind, name)| (kind.to_string(), name.to_string()))
+                .ok_or_else(|| Error::from(&format!("{fname}: invalid line {l}")))
+        })
+        .collect()
+}
+
+/// Required entities of a .mesh / .meshb file (RequiredVertices, RequiredEdges and
+/// RequiredTriangles), that must not be modified by the remesher
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct RequiredEntities {
+    /// Indices of the required vertices
+    pub verts: Vec<Idx>,
+    /// Indices of the required faces (edges in 2D, triangles in 3D)
+    pub faces: Vec<Idx>,
+    /// Required edges in 3D, given by their vertices
+    pub edges: Vec<[Idx; 2]>,
+}
+
+impl RequiredEntities {
+    /// Check if there are no required entities
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.verts.is_empty() && self.faces.is_empty() && self.edges.is_empty()
+    }
+}
+
+/// Writer for the keywords that are not supported by minimeshb (second order elements, and
+/// solutions with any number of values at the vertices or elements): .mesh / .sol files, or
+/// .meshb / .solb files (version 2, native endianness)
+struct NativeMeshbWriter {
+    writer: BufWriter<File>,
+    binary: bool,
+    /// Position of the next keyword in binary files
+    pos: usize,
+}
+
+impl NativeMeshbWriter {
+    fn new(file_name: &str, dim: usize) -> Result<Self> {
+        let mut res = Self {
+            writer: BufWriter::new(File::create(file_name)?),
+            binary: file_name.ends_with(".meshb") || file_name.ends_with(".solb"),
+            pos: 8,
+        };
+        if res.binary {
+            res.int(1)?;
+            res.int(2)?;
+            res.keyword(GMF_DIMENSION, None, 4)?;
+            res.int(dim)?;
+        } else {
+            writeln!(res.writer, "MeshVersionFormatted 2")?;
+            res.keyword(GMF_DIMENSION, None, 0)?;
+            writeln!(res.writer, "{dim}")?;
+        }
+        Ok(res)
+    }
+
+    fn int<I: TryInto<i32>>(&mut self, i: I) -> Result<()> {
+        let i: i32 = i
+            .try_into()
+            .map_err(|_| Error::from("meshb: integer too large for the file format"))?;
+        if self.binary {
+            self.writer.write_all(&i.to_ne_bytes())?;
+        } else {
+            write!(self.writer, " {i}")?;
+        }
+        Ok(())
+    }
+
+    /// Start a keyword with `count` entities (if any), the data being `size` bytes long in binary
+    fn keyword(&mut self, kwd: i64, count: Option<usize>, size: usize) -> Result<()> {
+        if self.binary {
+            let header = if count.is_some() { 12 } else { 8 };
+            self.pos += header + size;
+            self.int(kwd)?;
+            self.int(self.pos)?;
+            if let Some(count) = count {
+                self.int(count)?;
+            }
+        } else {
+            writeln!(self.writer, "\n{}", gmf_name(kwd))?;
+            if let Some(count) = count {
+                writeln!(self.writer, "{count}")?;
+            }
+        }
+        Ok(())
+    }
+
+    fn write_vertices<const D: usize, I: ExactSizeIterator<Item = Point<D>>>(
+        &mut self,
+        verts: I,
+    ) -> Result<()> {
+        self.keyword(GMF_VERTICES, Some(verts.len()), verts.len() * (8 * D + 4))?;
+        for p in verts {
+            if self.binary {
+                for x in &p {
+                    self.writer.write_all(&x.to_ne_bytes())?;
+                }
+            } else {
+                for x in &p {
+                    write!(self.writer, "{x} ")?;
+                }
+            }
+            self.int(1)?;
+            if !self.binary {
+                writeln!(self.writer)?;
+            }
+        }
+        Ok(())
+    }
+
+    fn write_elems<E: Elem, I: ExactSizeIterator<Item = (E, Tag)>>(
+        &mut self,
+        kwd: i64,
+        elems: I,
+    ) -> Result<()> {
+        let order = gmf_node_order(kwd);
+        self.keyword(kwd, Some(elems.len()), elems.len() * 4 * (order.len() + 1))?;
+        for (e, t) in elems {
+            for &k in order {
+                self.int(e[k] + 1)?;
+            }
+            self.int(t)?;
+            if !self.binary {
+                writeln!(self.writer)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Write a solution with `n_comp` scalar values per vertex / element
+    fn write_sol(&mut self, kwd: i64, n_comp: usize, values: &[f64]) -> Result<()> {
+        let count = values.len() / n_comp;
+        self.keyword(kwd, Some(count), 4 * (n_comp + 1) + 8 * values.len())?;
+        self.int(n_comp)?;
+        for _ in 0..n_comp {
+            self.int(1)?;
+        }
+        if !self.binary {
+            writeln!(self.writer)?;
+        }
+        for v in values.chunks(n_comp) {
+            for x in v {
+                if self.binary {
+                    self.writer.write_all(&x.to_ne_bytes())?;
+                } else {
+                    write!(self.writer, "{x} ")?;
+                }
+            }
+            if !self.binary {
+                writeln!(self.writer)?;
+            }
+        }
+        Ok(())
+    }
+
+    fn close(mut self) -> Result<()> {
+        if self.binary {
+            self.int(GMF_END)?;
+            self.int(0)?;
+        } else {
+            writeln!(self.writer, "\n{}", gmf_name(GMF_END))?;
+        }
+        self.writer.flush()?;
+        Ok(())
+    }
+}
+
 impl<const D: usize, E: Elem> SimplexMesh<D, E> {
+    /// Write the mesh (vertices, elements and faces with their tags) in a .mesh (ascii) or .meshb
+    /// (binary) file. The second order elements are written as TrianglesP2 / TetrahedraP2, the
+    /// mid-edge nodes being ordered as in `gmf_node_order`. The fields attached to the mesh (see
+    /// `add_vertex_field`) are written as f64 in `<prefix>.vert.<name>.sol(b)` and
+    /// `<prefix>.elem.<name>.sol(b)` files, listed in `<prefix>.fields` (see
+    /// `read_meshb_with_fields`)
     pub fn write_meshb(&self, file_name: &str) -> Result<()> {
+        self.write_meshb_with_required(file_name, &RequiredEntities::default())
+    }
+
+    /// Write the mesh in a .mesh (ascii) or .meshb (binary) file as in `write_meshb`, together
+    /// with the required entities
+    pub fn write_meshb_with_required(
+        &self,
+        file_name: &str,
+        required: &RequiredEntities,
+    ) -> Result<()> {
+        if required.verts.iter().any(|&i| i >= self.n_verts())
+            || required.faces.iter().any(|&i| i >= self.n_faces())
+            || required
+                .edges
+                .iter()
+                .flatten()
+                .any(|&i| i >= self.n_verts())
+        {
+            return Err(Error::from("meshb: invalid required entities"));
+        }
+        if D == 2 && !required.edges.is_empty() {
+            return Err(Error::from("meshb: required edges are faces in 2D"));
+        }
+        if E::N_VERTS > E::DIM + 1 {
+            if !(required.verts.is_empty() && required.faces.is_empty()) {
+                return Err(Error::from(
+                    "meshb: required entities are not supported for second order elements",
+                ));
+            }
+            self.write_meshb_p2(file_name)?;
+            return self.write_meshb_fields(file_name);
+        }
+
         let mut writer = Writer::new(file_name, 2, D as u8)?;
 
         writer.write_vertices(self.verts().map(Into::into), (0..self.n_verts()).map(|_| 1))?;
@@ -79,11 +331,109 @@ impl<const D: usize, E: Elem> SimplexMesh<D, E> {
                     #[allow(clippy::unnecessary_fallible_conversions)]
                     self.ftags().map(|t| t.try_into().unwrap()),
                 )?;
+                if !required.edges.is_empty() {
+                    writer.write_edges(
+                        required
+                            .edges
+                            .iter()
+                            .map(|e| [e[0].try_into().unwrap(), e[1].try_into().unwrap()]),
+                        required.edges.iter().map(|_| 1),
+                    )?;
+                    writer.write_required_edges(
+                        (0..required.edges.len()).map(|i| i.try_into().unwrap()),
+                    )?;
+                }
+            }
+            _ => return Err(Error::from("meshb: unsupported element type")),
+        }
+        if !required.verts.is_empty() {
+            writer
+                .write_required_vertices(required.verts.iter().map(|&i| i.try_into().unwrap()))?;
+        }
+        if !required.faces.is_empty() {
+            let faces = required.faces.iter().map(|&i| i.try_into().unwrap());
+            match E::N_VERTS {
+                3 => writer.write_required_edges(faces)?,
+                _ => writer.write_required_triangles(faces)?,
             }
-            _ => unreachable!(),
         }
         writer.close();
 
+        self.write_meshb_fields(file_name)
+    }
+
+    /// Write a mesh with second order elements
+    fn write_meshb_p2(&self, file_name: &str) -> Result<()> {
+        let (elem_kwd, face_kwd) = gmf_keywords(E::N_VERTS)?;
+        let mut writer = NativeMeshbWriter::new(file_name, D)?;
+        writer.write_vertices(self.verts())?;
+        writer.write_elems(elem_kwd, self.elems().zip(self.etags()))?;
+        writer.write_elems(face_kwd, self.faces().zip(self.ftags()))?;
+        writer.close()
+    }
+
+    /// Write the fields attached to the mesh in solution files alongside the mesh file (see
+    /// `field_file_name`), and their list in the index file (see `field_index_file_name`). The
+    /// index is not written if the mesh has no fields, unless an index of a previous mesh with the
+    /// same name exists (it is then overwritten, but the previous field files are kept)
+    fn write_meshb_fields(&self, file_name: &str) -> Result<()> {
+        let elem_kwd = if E::DIM == 2 {
+            GMF_SOL_AT_TRIANGLES
+        } else {
+            GMF_SOL_AT_TETRAHEDRA
+        };
+        let mut index = String::new();
+        for (kind, fields, kwd) in [
+            ("vert", self.vertex_fields(), GMF_SOL_AT_VERTICES),
+            ("elem", self.elem_fields(), elem_kwd),
+        ] {
+            for (name, n_comp, values) in fields.to_f64_with_comp() {
+                if name.contains(['\n', '/', '\\']) {
+                    return Err(Error::from(&format!("meshb: invalid field name {name}")));
+                }
+                let fname = field_file_name(file_name, kind, &name);
+                debug!("Write {fname}");
+                let mut writer = NativeMeshbWriter::new(&fname, D)?;
+                writer.write_sol(kwd, n_comp, &values)?;
+                writer.close()?;
+                index += &format!("{kind} {name}\n");
+            }
+        }
+        let fname = field_index_file_name(file_name);
+        if !index.is_empty() || Path::new(&fname).exists() {
+            debug!("Write {fname}");
+            std::fs::write(&fname, index)?;
+        }
+        Ok(())
+    }
+
+    /// Read the fields listed in the index file written alongside the mesh file (see
+    /// `write_meshb_fields`), as f64 fields
+    fn read_meshb_fields(&mut self, file_name: &str) -> Result<()> {
+        let elem_kwd = if E::DIM == 2 {
+            GMF_SOL_AT_TRIANGLES
+        } else {
+            GMF_SOL_AT_TETRAHEDRA
+        };
+        for (kind, name) in read_field_index(file_name)? {
+            let (kwd, n) = if kind == "vert" {
+                (GMF_SOL_AT_VERTICES, self.n_verts())
+            } else {
+                (elem_kwd, self.n_elems())
+            };
+            let fname = field_file_name(file_name, &kind, &name);
+            match read_gmf_sol(&fname, D, kwd)? {
+                Some((n_comp, values)) if values.len() == n_comp * n as usize => {
+                    let fields = if kind == "vert" {
+                        self.vertex_fields_mut()
+                    } else {
+                        self.elem_fields_mut()
+                    };
+                    fields.insert_with_comp(&name, n_comp, values)?;
+                }
+                _ => return Err(Error::from(&format!("{fname} does not match the mesh"))),
+            }
+        }
         Ok(())
     }
 
@@ -93,7 +443,9 @@ impl<const D: usize, E: Elem> SimplexMesh<D, E> {
         file_name: &str,
         f: F,
     ) -> Result<()> {
-        assert_eq!(arr.len(), N * self.n_verts() as usize);
+        if arr.len() != N * self.n_verts() as usize {
+            return Err(Error::from("invalid solution size"));
+        }
 
         let mut writer = Writer::new(file_name, 2, D as u8)?;
         writer.write_solution(arr.chunks(N).map(f))?;
@@ -102,6 +454,9 @@ impl<const D: usize, E: Elem> SimplexMesh<D, E> {
         Ok(())
     }
 
+    /// Write a field defined at the vertices (SolAtVertices) in a .sol (ascii) or .solb (binary)
+    /// file. The number of components is deduced from the size of `arr`: 1 for scalars, D for
+    /// vectors, and 3 (2D) or 6 (3D) for metrics, that are reordered to follow the meshb conventions
     pub fn write_solb(&self, arr: &[f64], file_name: &str) -> Result<()> {
         let n_comp = arr.len() / self.n_verts() as usize;
         match D {
@@ -109,7 +464,7 @@ impl<const D: usize, E: Elem> SimplexMesh<D, E> {
                 1 => self.write_solb_it::<1, _>(arr, file_name, |x| [x[0]])?,
                 2 => self.write_solb_it::<2, _>(arr, file_name, |x| [x[0], x[1]])?,
                 3 => self.write_solb_it::<3, _>(arr, file_name, |x| [x[0], x[2], x[1]])?,
-                _ => unreachable!(),
+                _ => return Err(Error::from("invalid number of components")),
             },
             3 => match n_comp {
                 1 => self.write_solb_it::<1, _>(arr, file_name, |x| [x[0]])?,
@@ -117,7 +472,7 @@ impl<const D: usize, E: Elem> SimplexMesh<D, E> {
                 6 => self.write_solb_it::<6, _>(arr, file_name, |x| {
                     [x[0], x[3], x[1], x[5], x[4], x[2]]
                 })?, // [0, 2, 5, 1, 4, 3]
-                _ => unreachable!(),
+                _ => return Err(Error::from("invalid number of components")),
             },
             _ => unreachable!(),
         }
@@ -125,9 +480,33 @@ impl<const D: usize, E: Elem> SimplexMesh<D, E> {
         Ok(())
     }
 
+    /// Read a mesh from a .mesh (ascii) or .meshb (binary) file
     pub fn read_meshb(file_name: &str) -> Result<Self> {
+        Ok(Self::read_meshb_with_required(file_name)?.0)
+    }
+
+    /// Read a mesh from a .mesh (ascii) or .meshb (binary) file together with the fields
+    /// written alongside it by `write_meshb` (as f64 fields). The fields are those listed in
+    /// the `<prefix>.fields` index file, which must exist
+    pub fn read_meshb_with_fields(file_name: &str) -> Result<Self> {
+        let mut mesh = Self::read_meshb(file_name)?;
+        mesh.read_meshb_fields(file_name)?;
+        Ok(mesh)
+    }
+
+    /// Read a mesh from a .mesh (ascii) or .meshb (binary) file as in `read_meshb`, together with
+    /// its required entities
+    pub fn read_meshb_with_required(file_name: &str) -> Result<(Self, RequiredEntities)> {
+        if E::N_VERTS > E::DIM + 1 {
+            // second order elements, not supported by minimeshb
+            let mesh = MeshbChunkReader::<D, E>::new(file_name)?.read_mesh(usize::MAX)?;
+            return Ok((mesh, RequiredEntities::default()));
+        }
+
         let mut reader = Reader::new(file_name)?;
-        assert_eq!(reader.dimension(), D as u8);
+        if reader.dimension() != D as u8 {
+            return Err(Error::from("invalid dimension"));
+        }
 
         let it = reader.read_vertices::<D>()?;
         let mut verts = Vec::with_capacity(it.len());
@@ -172,10 +551,71 @@ impl<const D: usize, E: Elem> SimplexMesh<D, E> {
                     ftags.push(t as Tag);
                 }
             }
-            _ => unreachable!(),
+            _ => return Err(Error::from("meshb: unsupported element type")),
         }
 
-        Ok(Self::new(verts, elems, etags, faces, ftags))
+        let mut required = RequiredEntities {
+            verts: reader.read_required_vertices()?.map(|i| i as Idx).collect(),
+            ..Default::default()
+        };
+        if E::N_VERTS == 3 {
+            required.faces = reader.read_required_edges()?.map(|i| i as Idx).collect();
+        } else {
+            required.faces = reader
+                .read_required_triangles()?
+                .map(|i| i as Idx)
+                .collect();
+            let ids = reader.read_required_edges()?.collect::<Vec<_>>();
+            if !ids.is_empty() {
+                let edges = reader
+                    .read_edges()?
+                    .map(|(e, _)| [e[0] as Idx, e[1] as Idx])
+                    .collect::<Vec<_>>();
+                required.edges = ids
+                    .iter()
+                    .map(|&i| edges.get(i as usize).copied())
+                    .collect::<Option<_>>()
+                    .ok_or_else(|| Error::from("meshb: invalid required edges"))?;
+            }
+        }
+        if required.verts.iter().any(|&i| i as usize >= verts.len())
+            || required.faces.iter().any(|&i| i as usize >= faces.len())
+        {
+            return Err(Error::from("meshb: invalid required entities"));
+        }
+
+        let mesh = Self::new(verts, elems, etags, faces, ftags);
+
+        Ok((mesh, required))
+    }
+
+    /// Get the vertices and face tags to freeze during remeshing (`RemesherParams::frozen_verts`
+    /// and `RemesherParams::frozen_face_tags`) from the required entities of a .mesh(b) file:
+    /// the face tags are frozen if all their faces are required, and the required vertices and
+    /// the vertices of the other required edges / faces are frozen
+    #[must_use]
+    pub fn frozen_from_required(&self, required: &RequiredEntities) -> (Vec<Idx>, Vec<Tag>) {
+        let required_faces = required.faces.iter().copied().collect::<FxHashSet<_>>();
+        let mut all_tags = self.ftags().collect::<FxHashSet<_>>();
+        for (i, t) in self.ftags().enumerate() {
+            if !required_faces.contains(&(i as Idx)) {
+                all_tags.remove(&t);
+            }
+        }
+
+        let mut frozen_verts = required.verts.iter().copied().collect::<FxHashSet<_>>();
+        frozen_verts.extend(required.edges.iter().flatten().copied());
+        for &i in &required.faces {
+            if !all_tags.contains(&self.ftag(i)) {
+                frozen_verts.extend(self.face(i));
+            }
+        }
+
+        let mut frozen_verts = frozen_verts.into_iter().collect::<Vec<_>>();
+        frozen_verts.sort_unstable();
+        let mut frozen_face_tags = all_tags.into_iter().collect::<Vec<_>>();
+        frozen_face_tags.sort_unstable();
+        (frozen_verts, frozen_face_tags)
     }
 
     fn read_solb_it<const N: usize, F: FnMut([f64; N]) -> [f64; N]>(
@@ -187,10 +627,14 @@ impl<const D: usize, E: Elem> SimplexMesh<D, E> {
         Ok(sol.flat_map(f).collect())
     }
 
+    /// Read a field defined at the vertices from a .sol (ascii) or .solb (binary) file. Returns the
+    /// values and the number of components (the metrics are reordered as in `write_solb`)
     pub fn read_solb(file_name: &str) -> Result<(Vec<f64>, usize)> {
         let mut reader = Reader::new(file_name)?;
         let d = reader.dimension();
-        assert_eq!(d, D as u8);
+        if d != D as u8 {
+            return Err(Error::from("invalid dimension"));
+        }
         let m = reader.get_solution_size()?;
 
         let res = match d {
@@ -198,13 +642,13 @@ impl<const D: usize, E: Elem> SimplexMesh<D, E> {
                 1 => Self::read_solb_it::<1, _>(reader, |x| [x[0]])?,
                 2 => Self::read_solb_it::<2, _>(reader, |x| [x[0], x[1]])?,
                 3 => Self::read_solb_it::<3, _>(reader, |x| [x[0], x[2], x[1]])?,
-                _ => unreachable!(),
+                _ => return Err(Error::from("invalid number of components")),
             },
             3 => match m {
                 1 => Self::read_solb_it::<1, _>(reader, |x| [x[0]])?,
                 3 => Self::read_solb_it::<3, _>(reader, |x| [x[0], x[1], x[2]])?,
                 6 => Self::read_solb_it::<6, _>(reader, |x| [x[0], x[2], x[5], x[1], x[4], x[3]])?,
-                _ => unreachable!(),
+                _ => return Err(Error::from("invalid number of components")),
             },
             _ => unreachable!(),
         };
@@ -216,16 +660,33 @@ impl<const D: usize, E: Elem> SimplexMesh<D, E> {
 #[cfg(test)]
 mod tests {
 
+    use super::{field_file_name, field_index_file_name, RequiredEntities};
     use crate::{
+        geometry::NoGeometry,
         mesh::{
             test_meshes::{test_mesh_2d, test_mesh_3d},
-            Point, SimplexMesh, Tetrahedron, Triangle,
+            Elem, Point, SimplexMesh, Tetrahedron, TetrahedronQuadratique, Triangle,
+            TriangleQuadratique,
         },
         metric::{AnisoMetric2d, AnisoMetric3d, Metric},
-        Result,
+        Idx, Result,
     };
+    use std::path::Path;
     use tempfile::NamedTempFile;
 
+    fn check_same<const D: usize, E: Elem>(m0: &SimplexMesh<D, E>, m1: &SimplexMesh<D, E>) {
+        assert_eq!(m0.n_verts(), m1.n_verts());
+        assert_eq!(m0.n_elems(), m1.n_elems());
+        assert_eq!(m0.n_faces(), m1.n_faces());
+        for (p0, p1) in m0.verts().zip(m1.verts()) {
+            assert!((p0 - p1).norm() < 1e-12);
+        }
+        assert!(m0.elems().zip(m1.elems()).all(|(e0, e1)| e0 == e1));
+        assert!(m0.etags().zip(m1.etags()).all(|(t0, t1)| t0 == t1));
+        assert!(m0.faces().zip(m1.faces()).all(|(f0, f1)| f0 == f1));
+        assert!(m0.ftags().zip(m1.ftags()).all(|(t0, t1)| t0 == t1));
+    }
+
     #[test]
     fn test_2d_ascii() -> Result<()> {
         let mesh = test_mesh_2d().split();
@@ -428,6 +889,195 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn test_required() -> Result<()> {
+        let mesh = test_mesh_2d().split();
+        let mut required = RequiredEntities {
+            verts: vec![0],
+            faces: mesh
+                .ftags()
+                .enumerate()
+                .filter(|&(_, t)| t == 1)
+                .map(|(i, _)| i as Idx)
+                .collect(),
+            edges: Vec::new(),
+        };
+        required
+            .faces
+            .push(mesh.ftags().position(|t| t == 2).unwrap() as Idx);
+
+        for ext in [".mesh", ".meshb"] {
+            let file = NamedTempFile::new().unwrap();
+            let fname = file.path().to_str().unwrap().to_owned() + ext;
+            mesh.write_meshb_with_required(&fname, &required)?;
+            let (mesh2, required2) = SimplexMesh::<2, Triangle>::read_meshb_with_required(&fname)?;
+            assert_eq!(required2, required);
+
+            let (frozen_verts, frozen_face_tags) = mesh2.frozen_from_required(&required2);
+            assert_eq!(frozen_face_tags, [1]);
+            let face = mesh.face(*required.faces.last().unwrap());
+            let mut expected = vec![0, face[0], face[1]];
+            expected.sort_unstable();
+            assert_eq!(frozen_verts, expected);
+        }
+
+        let mesh = test_mesh_3d().split();
+        let required = RequiredEntities {
+            verts: vec![0, 1],
+            faces: vec![3],
+            edges: vec![[0, 1], [2, 3]],
+        };
+        let file = NamedTempFile::new().unwrap();
+        let fname = file.path().to_str().unwrap().to_owned() + ".meshb";
+        mesh.write_meshb_with_required(&fname, &required)?;
+        let (mesh2, required2) = SimplexMesh::<3, Tetrahedron>::read_meshb_with_required(&fname)?;
+        assert_eq!(mesh2.n_faces(), mesh.n_faces());
+        assert_eq!(required2, required);
+
+        let required = RequiredEntities {
+            verts: vec![mesh.n_verts()],
+            ..Default::default()
+        };
+        assert!(mesh.write_meshb_with_required(&fname, &required).is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_invalid() -> Result<()> {
+        let mesh = test_mesh_3d();
+
+        let file = NamedTempFile::new().unwrap();
+        let fname = file.path().to_str().unwrap().to_owned() + ".meshb";
+        mesh.write_meshb(&fname)?;
+        assert!(SimplexMesh::<2, Triangle>::read_meshb(&fname).is_err());
+
+        let fname = file.path().to_str().unwrap().to_owned() + ".solb";
+        let arr = vec![1.0; 4 * mesh.n_verts() as usize];
+        assert!(mesh.write_solb(&arr, &fname).is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_fields() -> Result<()> {
+        let mut mesh = test_mesh_2d().split();
+        let v = mesh.verts().flat_map(|p| [p[0], p[1]]).collect::<Vec<_>>();
+        mesh.add_vertex_field::<f64, 2>("x", v.clone())?;
+        let t = (0..mesh.n_elems() as i32).collect::<Vec<_>>();
+        mesh.add_elem_field::<i32, 1>("x", t.clone())?;
+        let t = t.iter().map(|&i| f64::from(i)).collect::<Vec<_>>();
+
+        for ext in [".mesh", ".meshb"] {
+            let file = NamedTempFile::new().unwrap();
+            let fname = file.path().to_str().unwrap().to_owned() + ext;
+            mesh.write_meshb(&fname)?;
+
+            // the fields are read only if requested
+            let mesh2 = SimplexMesh::<2, Triangle>::read_meshb(&fname)?;
+            check_same(&mesh, &mesh2);
+            assert!(mesh2.vertex_fields().is_empty());
+            assert!(mesh2.elem_fields().is_empty());
+
+            let mesh2 = SimplexMesh::<2, Triangle>::read_meshb_with_fields(&fname)?;
+            check_same(&mesh, &mesh2);
+            assert_eq!(mesh2.get_vertex_field::<f64, 2>("x")?, &v[..]);
+            assert_eq!(mesh2.get_elem_field::<f64, 1>("x")?, &t[..]);
+
+            // files that are not listed in the index are ignored
+            let stray = field_file_name(&fname, "vert", "stray");
+            std::fs::copy(field_file_name(&fname, "vert", "x"), &stray)?;
+            let mesh2 = SimplexMesh::<2, Triangle>::read_meshb_with_fields(&fname)?;
+            assert_eq!(mesh2.vertex_fields().names().count(), 1);
+
+            // no files are removed when a mesh without fields is written, but the index is
+            // updated
+            let mut mesh3 = mesh.clone();
+            mesh3.vertex_fields_mut().clear();
+            mesh3.elem_fields_mut().clear();
+            mesh3.write_meshb(&fname)?;
+            assert!(Path::new(&stray).exists());
+            assert!(Path::new(&field_file_name(&fname, "vert", "x")).exists());
+            let mesh3 = SimplexMesh::<2, Triangle>::read_meshb_with_fields(&fname)?;
+            assert!(mesh3.vertex_fields().is_empty());
+            assert!(mesh3.elem_fields().is_empty());
+
+            for name in ["stray", "x"] {
+                std::fs::remove_file(field_file_name(&fname, "vert", name))?;
+            }
+            std::fs::remove_file(field_file_name(&fname, "elem", "x"))?;
+            std::fs::remove_file(field_index_file_name(&fname))?;
+            assert!(SimplexMesh::<2, Triangle>::read_meshb_with_fields(&fname).is_err());
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_quadratic() -> Result<()> {
+        let mesh2d = test_mesh_2d().split().to_quadratic(&NoGeometry())?;
+        let mesh3d = test_mesh_3d().split().to_quadratic(&NoGeometry())?;
+        for ext in [".mesh", ".meshb"] {
+            let file = NamedTempFile::new().unwrap();
+            let fname = file.path().to_str().unwrap().to_owned() + ext;
+            mesh2d.write_meshb(&fname)?;
+            let mesh = SimplexMesh::<2, TriangleQuadratique>::read_meshb(&fname)?;
+            check_same(&mesh2d, &mesh);
+
+            let file = NamedTempFile::new().unwrap();
+            let fname = file.path().to_str().unwrap().to_owned() + ext;
+            mesh3d.write_meshb(&fname)?;
+            let mesh = SimplexMesh::<3, TetrahedronQuadratique>::read_meshb(&fname)?;
+            check_same(&mesh3d, &mesh);
+
+            let required = RequiredEntities {
+                verts: vec![0],
+                ..Default::default()
+            };
+            assert!(mesh3d.write_meshb_with_required(&fname, &required).is_err());
+        }
+
+        // meshb node ordering
+        let mesh = "MeshVersionFormatted 2
+
+Dimension
+3
+
+Vertices
+10
+0 0 0 1
+1 0 0 1
+0 1 0 1
+0 0 1 1
+0.5 0 0 1
+0.5 0.5 0 1
+0 0.5 0 1
+0 0 0.5 1
+0.5 0 0.5 1
+0 0.5 0.5 1
+
+TetrahedraP2
+1
+1 2 3 4 5 6 7 8 9 10 1
+
+End
+";
+        let file = NamedTempFile::new().unwrap();
+        let fname = file.path().to_str().unwrap().to_owned() + ".mesh";
+        std::fs::write(&fname, mesh)?;
+        let mesh = SimplexMesh::<3, TetrahedronQuadratique>::read_meshb(&fname)?;
+        std::fs::remove_file(&fname)?;
+        assert_eq!(mesh.n_elems(), 1);
+        let e = mesh.elem(0);
+        for i_edge in 0..TetrahedronQuadratique::N_EDGES {
+            let [i0, i1] = e.edge(i_edge);
+            let p = 0.5 * (mesh.vert(i0) + mesh.vert(i1));
+            assert!((mesh.vert(e.mid_node(i_edge)) - p).norm() < 1e-12);
+        }
+
+        Ok(())
+    }
+
     #[test]
     #[ignore]
     fn test_3d_simple3d() -> Result<()> {
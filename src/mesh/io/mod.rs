@@ -1,5 +1,20 @@
+mod bincode_io;
+mod cgns_io;
+mod exodus_io;
+mod gmsh_io;
+mod mesh_reader;
 mod mesh_vtk;
 mod meshb_io;
+mod obj_io;
+mod openfoam_io;
+mod ply_io;
 mod stl_io;
+mod su2_io;
+mod tecplot_io;
+mod vtu_io;
+mod xdmf_io;
 
+pub use mesh_reader::{MeshReader, MeshbChunkReader};
+pub use meshb_io::RequiredEntities;
 pub use stl_io::{orient_stl, read_stl};
+pub use vtu_io::metric_to_vtk_tensor;
@@ -0,0 +1,221 @@
+use crate::{
+    mesh::{Point, SimplexMesh, Triangle},
+    Error, Idx, Result, Tag,
+};
+use log::debug;
+use std::{
+    fs::File,
+    io::{BufRead, BufReader, BufWriter, Write},
+};
+
+/// Group name used for the elements with tag `tag` if no name is defined
+fn obj_group_name(tag: Tag) -> String {
+    format!("tag_{tag}")
+}
+
+impl<const D: usize> SimplexMesh<D, Triangle> {
+    /// Write the mesh in a Wavefront .obj file. The elements are written in groups, one per
+    /// element tag, named after the element tag name (see `tag_names`) or `tag_{tag}`. The faces
+    /// are not written
+    pub fn write_obj(&self, file_name: &str) -> Result<()> {
+        debug!("Write {file_name}");
+
+        if !(2..=3).contains(&D) {
+            return Err(Error::from("obj: invalid dimension"));
+        }
+
+        let mut file = BufWriter::new(File::create(file_name)?);
+        writeln!(file, "# written by tucanos")?;
+        for p in self.verts() {
+            let z = if D == 3 { p[2] } else { 0.0 };
+            writeln!(file, "v {:.16e} {:.16e} {z:.16e}", p[0], p[1])?;
+        }
+        let mut current = None;
+        for (e, t) in self.elems().zip(self.etags()) {
+            if current != Some(t) {
+                let name = self
+                    .tag_names()
+                    .elem_name(t)
+                    .map_or_else(|| obj_group_name(t), str::to_string);
+                writeln!(file, "g {name}")?;
+                current = Some(t);
+            }
+            writeln!(file, "f {} {} {}", e[0] + 1, e[1] + 1, e[2] + 1)?;
+        }
+        file.flush()?;
+
+        Ok(())
+    }
+
+    /// Read a Wavefront .obj file. Polygonal faces are split into triangles, and texture
+    /// coordinates and normals are ignored. The elements in a group `tag_{tag}` are tagged `tag`,
+    /// the ones in the other groups are tagged with the next available tags and the group names
+    /// are stored as element tag names (see `tag_names`). The elements before the first group are
+    /// tagged 1
+    pub fn read_obj(file_name: &str) -> Result<Self> {
+        debug!("Read {file_name}");
+
+        let file = BufReader::new(File::open(file_name)?);
+
+        let mut verts = Vec::new();
+        let mut elems = Vec::new();
+        let mut elem_groups = Vec::new();
+        let mut groups = vec![obj_group_name(1)];
+        let mut current = 0;
+        for line in file.lines() {
+            let line = line?;
+            let mut words = line.split_whitespace();
+            match words.next() {
+                Some("v") => {
+                    let mut p = Point::<D>::zeros();
+                    for x in p.iter_mut() {
+                        *x = words
+                            .next()
+                            .ok_or_else(|| Error::from("obj: invalid vertex"))?
+                            .parse()?;
+                    }
+                    verts.push(p);
+                }
+                Some("f") => {
+                    let n_verts = verts.len() as i64;
+                    let polygon = words
+                        .map(|w| -> Result<Idx> {
+                            // v, v/vt, v//vn or v/vt/vn, with negative indices
+                            // relative to the end
+                            let i: i64 = w.split('/').next().unwrap_or("").parse()?;
+                            let i = if i < 0 { n_verts + i } else { i - 1 };
+                            if i < 0 || i >= n_verts {
+                                return Err(Error::from(&format!("obj: invalid vertex index {w}")));
+                            }
+                            Ok(i as Idx)
+                        })
+                        .collect::<Result<Vec<_>>>()?;
+                    if polygon.len() < 3 {
+                        return Err(Error::from("obj: invalid face"));
+                    }
+                    for i in 1..polygon.len() - 1 {
+                        elems.push(Triangle::new(polygon[0], polygon[i], polygon[i + 1]));
+                        elem_groups.push(current);
+                    }
+                }
+                Some("g" | "o") => {
+                    let name = words.collect::<Vec<_>>().join(" ");
+                    current = groups.iter().position(|g| *g == name).unwrap_or_else(|| {
+                        groups.push(name);
+                        groups.len() - 1
+                    });
+                }
+                _ => {}
+            }
+        }
+
+        let group_tag = |name: &str| {
+            name.strip_prefix("tag_")
+                .and_then(|t| t.parse::<Tag>().ok())
+        };
+        let mut next_tag = groups
+            .iter()
+            .filter_map(|g| group_tag(g))
+            .max()
+            .unwrap_or(0)
+            + 1;
+        let mut tag_names = Vec::new();
+        let tags = groups
+            .iter()
+            .map(|g| {
+                group_tag(g).unwrap_or_else(|| {
+                    next_tag += 1;
+                    tag_names.push((next_tag - 1, g.clone()));
+                    next_tag - 1
+                })
+            })
+            .collect::<Vec<_>>();
+        let etags = elem_groups.iter().map(|&i| tags[i]).collect();
+
+        let mut res = Self::new(verts, elems, etags, Vec::new(), Vec::new());
+        for (tag, name) in tag_names {
+            res.tag_names_mut().set_elem_name(tag, &name);
+        }
+
+        Ok(res)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        mesh::{
+            test_meshes::{test_mesh_2d, test_mesh_3d},
+            SimplexMesh, Triangle,
+        },
+        Result,
+    };
+    use std::io::Write;
+    use tempfile::NamedTempFile;
+
+    #[test]
+    fn test_obj_2d() -> Result<()> {
+        let mut mesh = test_mesh_2d().split();
+        mesh.tag_names_mut().set_elem_name(2, "right");
+
+        let file = NamedTempFile::new().unwrap();
+        let fname = file.path().to_str().unwrap().to_owned() + ".obj";
+        mesh.write_obj(&fname)?;
+        let mesh2 = SimplexMesh::<2, Triangle>::read_obj(&fname)?;
+
+        assert_eq!(mesh.n_verts(), mesh2.n_verts());
+        assert_eq!(mesh.n_elems(), mesh2.n_elems());
+        for (p0, p1) in mesh.verts().zip(mesh2.verts()) {
+            assert!((p0 - p1).norm() < 1e-12);
+        }
+        assert!(mesh.elems().zip(mesh2.elems()).all(|(e0, e1)| e0 == e1));
+        // "right" is given the next available tag, i.e. 2
+        assert!(mesh.etags().zip(mesh2.etags()).all(|(t0, t1)| t0 == t1));
+        assert_eq!(mesh2.tag_names().elem_name(2), Some("right"));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_obj_3d() -> Result<()> {
+        let mesh = test_mesh_3d().split().boundary().0;
+
+        let file = NamedTempFile::new().unwrap();
+        let fname = file.path().to_str().unwrap().to_owned() + ".obj";
+        mesh.write_obj(&fname)?;
+        let mesh2 = SimplexMesh::<3, Triangle>::read_obj(&fname)?;
+
+        assert_eq!(mesh.n_verts(), mesh2.n_verts());
+        assert!(mesh.elems().zip(mesh2.elems()).all(|(e0, e1)| e0 == e1));
+        assert!(mesh.etags().zip(mesh2.etags()).all(|(t0, t1)| t0 == t1));
+        assert!((mesh.vol() - mesh2.vol()).abs() < 1e-12);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_obj_polygons() -> Result<()> {
+        let obj = "# a square
+v 0 0 0
+v 1 0 0
+v 1 1 0
+v 0 1 0
+vt 0 0
+vn 0 0 1
+f 1/1/1 2/1/1 -2/1/1 -1/1/1
+g wall
+f 1//1 2//1 3//1
+";
+        let mut file = NamedTempFile::new().unwrap();
+        file.write_all(obj.as_bytes()).unwrap();
+        let mesh = SimplexMesh::<3, Triangle>::read_obj(file.path().to_str().unwrap())?;
+
+        assert_eq!(mesh.n_verts(), 4);
+        assert_eq!(mesh.n_elems(), 3);
+        assert_eq!(mesh.etags().collect::<Vec<_>>(), [1, 1, 2]);
+        assert_eq!(mesh.tag_names().elem_name(2), Some("wall"));
+        assert!((mesh.vol() - 1.5).abs() < 1e-12);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,241 @@
+use crate::{
+    mesh::{Elem, SimplexMesh, Tetrahedron, Triangle},
+    Error, Idx, Result, Tag,
+};
+use log::debug;
+use rustc_hash::FxHashMap;
+use std::{
+    fs::{create_dir_all, File},
+    io::{BufWriter, Write},
+    path::Path,
+};
+
+/// Create an OpenFOAM file in `dir` and write its header
+fn foam_file(dir: &Path, class: &str, object: &str) -> Result<BufWriter<File>> {
+    let mut file = BufWriter::new(File::create(dir.join(object))?);
+    writeln!(file, "FoamFile")?;
+    writeln!(file, "{{")?;
+    writeln!(file, "    version     2.0;")?;
+    writeln!(file, "    format      ascii;")?;
+    writeln!(file, "    class       {class};")?;
+    writeln!(file, "    location    \"constant/polyMesh\";")?;
+    writeln!(file, "    object      {object};")?;
+    writeln!(file, "}}")?;
+    writeln!(file)?;
+    Ok(file)
+}
+
+/// Write a `labelList` OpenFOAM file
+fn foam_label_list(dir: &Path, object: &str, labels: &[Idx]) -> Result<()> {
+    let mut file = foam_file(dir, "labelList", object)?;
+    writeln!(file, "{}", labels.len())?;
+    writeln!(file, "(")?;
+    for i in labels {
+        writeln!(file, "{i}")?;
+    }
+    writeln!(file, ")")?;
+    file.flush()?;
+    Ok(())
+}
+
+impl SimplexMesh<3, Tetrahedron> {
+    /// Write the mesh in the OpenFOAM format, in `{case_dir}/constant/polyMesh` (files `points`,
+    /// `faces`, `owner`, `neighbour` and `boundary`). The boundary faces are grouped in one patch
+    /// per face tag, named after the face tag name (see `tag_names`) or `Boundary_{tag}`. The
+    /// internal tagged faces are not written as patches. The elements must be positively oriented
+    pub fn write_openfoam(&self, case_dir: &str) -> Result<()> {
+        debug!("Write {case_dir}/constant/polyMesh");
+
+        // Build the faces: the elements are processed in order, so the owner of a face is
+        // the element with the lowest index and its faces are oriented outwards
+        let mut faces: FxHashMap<Triangle, (Triangle, Idx, Option<Idx>)> = FxHashMap::default();
+        for (i_elem, e) in self.elems().enumerate() {
+            for i_face in 0..Tetrahedron::N_FACES {
+                let f = e.face(i_face);
+                faces
+                    .entry(f.sorted())
+                    .and_modify(|v| v.2 = Some(i_elem as Idx))
+                    .or_insert((f, i_elem as Idx, None));
+            }
+        }
+
+        let tags: FxHashMap<Triangle, (Tag, usize)> = self
+            .faces()
+            .zip(self.ftags())
+            .enumerate()
+            .map(|(i, (f, t))| (f.sorted(), (t, i)))
+            .collect();
+
+        let mut internal = Vec::new();
+        let mut boundary = Vec::new();
+        for (k, (f, owner, neighbour)) in faces {
+            if let Some(neighbour) = neighbour {
+                internal.push((owner, neighbour, f));
+            } else {
+                let &(tag, i_face) = tags
+                    .get(&k)
+                    .ok_or_else(|| Error::from("openfoam: untagged boundary face"))?;
+                boundary.push((tag, owner, i_face, f));
+            }
+        }
+        // Internal faces in upper triangular order, then boundary faces grouped by patch
+        internal.sort_unstable_by_key(|&(owner, neighbour, _)| (owner, neighbour));
+        boundary.sort_unstable_by_key(|&(tag, owner, i_face, _)| (tag, owner, i_face));
+
+        let dir = Path::new(case_dir).join("constant").join("polyMesh");
+        create_dir_all(&dir)?;
+
+        let mut file = foam_file(&dir, "vectorField", "points")?;
+        writeln!(file, "{}", self.n_verts())?;
+        writeln!(file, "(")?;
+        for p in self.verts() {
+            writeln!(file, "({:.16e} {:.16e} {:.16e})", p[0], p[1], p[2])?;
+        }
+        writeln!(file, ")")?;
+        file.flush()?;
+
+        let mut file = foam_file(&dir, "faceList", "faces")?;
+        writeln!(file, "{}", internal.len() + boundary.len())?;
+        writeln!(file, "(")?;
+        for f in internal
+            .iter()
+            .map(|(_, _, f)| f)
+            .chain(boundary.iter().map(|(_, _, _, f)| f))
+        {
+            writeln!(file, "3({} {} {})", f[0], f[1], f[2])?;
+        }
+        writeln!(file, ")")?;
+        file.flush()?;
+
+        let owner = internal
+            .iter()
+            .map(|&(owner, _, _)| owner)
+            .chain(boundary.iter().map(|&(_, owner, _, _)| owner))
+            .collect::<Vec<_>>();
+        foam_label_list(&dir, "owner", &owner)?;
+        let neighbour = internal
+            .iter()
+            .map(|&(_, neighbour, _)| neighbour)
+            .collect::<Vec<_>>();
+        foam_label_list(&dir, "neighbour", &neighbour)?;
+
+        let mut patches: Vec<(Tag, usize, usize)> = Vec::new();
+        for (i, &(tag, _, _, _)) in boundary.iter().enumerate() {
+            match patches.last_mut() {
+                Some(p) if p.0 == tag => p.2 += 1,
+                _ => patches.push((tag, internal.len() + i, 1)),
+            }
+        }
+        let mut file = foam_file(&dir, "polyBoundaryMesh", "boundary")?;
+        writeln!(file, "{}", patches.len())?;
+        writeln!(file, "(")?;
+        for (tag, start, n) in patches {
+            let name = self
+                .tag_names()
+                .face_name(tag)
+                .map_or_else(|| format!("Boundary_{tag}"), str::to_string);
+            writeln!(file, "    {name}")?;
+            writeln!(file, "    {{")?;
+            writeln!(file, "        type            patch;")?;
+            writeln!(file, "        nFaces          {n};")?;
+            writeln!(file, "        startFace       {start};")?;
+            writeln!(file, "    }}")?;
+        }
+        writeln!(file, ")")?;
+        file.flush()?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        mesh::{test_meshes::test_mesh_3d, GElem, Point},
+        Idx, Result,
+    };
+    use std::{fs::read_to_string, path::Path};
+
+    /// Read the entries of an OpenFOAM list
+    fn read_list(dir: &Path, object: &str) -> Result<Vec<String>> {
+        let data = read_to_string(dir.join(object))?;
+        let mut lines = data.lines().skip_while(|l| *l != "}").skip(2);
+        let n: usize = lines.next().unwrap().parse()?;
+        assert_eq!(lines.next(), Some("("));
+        let res = lines.take(n).map(str::to_string).collect::<Vec<_>>();
+        assert_eq!(res.len(), n);
+        Ok(res)
+    }
+
+    #[test]
+    fn test_openfoam() -> Result<()> {
+        let mut mesh = test_mesh_3d().split();
+        mesh.tag_names_mut().set_face_name(1, "inlet");
+
+        let dir = tempfile::tempdir()?;
+        let case_dir = dir.path().to_str().unwrap();
+        mesh.write_openfoam(case_dir)?;
+        let dir = dir.path().join("constant").join("polyMesh");
+
+        let points = read_list(&dir, "points")?
+            .iter()
+            .map(|l| {
+                let x = l
+                    .trim_matches(|c| c == '(' || c == ')')
+                    .split_whitespace()
+                    .map(|x| x.parse().unwrap())
+                    .collect::<Vec<f64>>();
+                Point::<3>::new(x[0], x[1], x[2])
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(points.len(), mesh.n_verts() as usize);
+
+        let faces = read_list(&dir, "faces")?
+            .iter()
+            .map(|l| {
+                l.trim_start_matches("3(")
+                    .trim_end_matches(')')
+                    .split_whitespace()
+                    .map(|i| i.parse().unwrap())
+                    .collect::<Vec<Idx>>()
+            })
+            .collect::<Vec<_>>();
+        let owner = read_list(&dir, "owner")?
+            .iter()
+            .map(|l| l.parse().unwrap())
+            .collect::<Vec<Idx>>();
+        let neighbour = read_list(&dir, "neighbour")?
+            .iter()
+            .map(|l| l.parse().unwrap())
+            .collect::<Vec<Idx>>();
+
+        let n_elems = mesh.n_elems() as usize;
+        assert_eq!(owner.len(), faces.len());
+        assert_eq!(2 * neighbour.len() + mesh.n_faces() as usize, 4 * n_elems);
+        assert_eq!(faces.len() - neighbour.len(), mesh.n_faces() as usize);
+
+        // The faces are oriented from the owner to the neighbour
+        let centers = mesh.gelems().map(|ge| ge.center()).collect::<Vec<_>>();
+        for (i, f) in faces.iter().enumerate() {
+            let (p0, p1, p2) = (
+                points[f[0] as usize],
+                points[f[1] as usize],
+                points[f[2] as usize],
+            );
+            let n = (p1 - p0).cross(&(p2 - p0));
+            let c = (p0 + p1 + p2) / 3.0;
+            assert!(n.dot(&(c - centers[owner[i] as usize])) > 0.0);
+            if i < neighbour.len() {
+                assert!(owner[i] < neighbour[i]);
+                assert!(n.dot(&(c - centers[neighbour[i] as usize])) < 0.0);
+            }
+        }
+
+        let boundary = read_to_string(dir.join("boundary"))?;
+        assert!(boundary.contains("inlet"));
+        assert!(boundary.contains("Boundary_6"));
+        assert_eq!(boundary.matches("type            patch;").count(), 6);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,460 @@
+use crate::{
+    mesh::{Point, SimplexMesh, Triangle},
+    Error, Idx, Result, Tag,
+};
+use log::debug;
+use std::{
+    collections::HashMap,
+    fs::File,
+    io::{BufRead, BufReader, BufWriter, Read, Write},
+};
+
+/// Scalar types in .ply files
+#[derive(Clone, Copy, Debug)]
+enum PlyType {
+    I8,
+    U8,
+    I16,
+    U16,
+    I32,
+    U32,
+    F32,
+    F64,
+}
+
+impl PlyType {
+    fn from_name(name: &str) -> Result<Self> {
+        match name {
+            "char" | "int8" => Ok(Self::I8),
+            "uchar" | "uint8" => Ok(Self::U8),
+            "short" | "int16" => Ok(Self::I16),
+            "ushort" | "uint16" => Ok(Self::U16),
+            "int" | "int32" => Ok(Self::I32),
+            "uint" | "uint32" => Ok(Self::U32),
+            "float" | "float32" => Ok(Self::F32),
+            "double" | "float64" => Ok(Self::F64),
+            _ => Err(Error::from(&format!("ply: unknown type {name}"))),
+        }
+    }
+
+    const fn size(self) -> usize {
+        match self {
+            Self::I8 | Self::U8 => 1,
+            Self::I16 | Self::U16 => 2,
+            Self::I32 | Self::U32 | Self::F32 => 4,
+            Self::F64 => 8,
+        }
+    }
+}
+
+/// Property of an element in a .ply file. `count` is the type of the number of items for list
+/// properties
+struct PlyProperty {
+    name: String,
+    value: PlyType,
+    count: Option<PlyType>,
+}
+
+/// Element in a .ply file
+struct PlyElement {
+    name: String,
+    count: usize,
+    properties: Vec<PlyProperty>,
+}
+
+/// Reader for the body of a .ply file
+enum PlyReader<R: Read> {
+    Ascii(std::vec::IntoIter<String>),
+    Binary(R, bool),
+}
+
+impl<R: Read> PlyReader<R> {
+    fn value(&mut self, t: PlyType) -> Result<f64> {
+        match self {
+            Self::Ascii(tokens) => Ok(tokens
+                .next()
+                .ok_or_else(|| Error::from("ply: unexpected end of file"))?
+                .parse()?),
+            Self::Binary(reader, big_endian) => {
+                let mut buf = [0_u8; 8];
+                let b = &mut buf[..t.size()];
+                reader.read_exact(b)?;
+                if *big_endian {
+                    b.reverse();
+                }
+                Ok(match t {
+                    PlyType::I8 => f64::from(i8::from_le_bytes([b[0]])),
+                    PlyType::U8 => f64::from(b[0]),
+                    PlyType::I16 => f64::from(i16::from_le_bytes([b[0], b[1]])),
+                    PlyType::U16 => f64::from(u16::from_le_bytes([b[0], b[1]])),
+                    PlyType::I32 => f64::from(i32::from_le_bytes([b[0], b[1], b[2], b[3]])),
+                    PlyType::U32 => f64::from(u32::from_le_bytes([b[0], b[1], b[2], b[3]])),
+                    PlyType::F32 => f64::from(f32::from_le_bytes([b[0], b[1], b[2], b[3]])),
+                    PlyType::F64 => f64::from_le_bytes(buf),
+                })
+            }
+        }
+    }
+
+    /// Read the values of a property (a single value, or the items of a list)
+    fn property(&mut self, p: &PlyProperty) -> Result<Vec<f64>> {
+        if let Some(count) = p.count {
+            let n = self.value(count)? as usize;
+            (0..n).map(|_| self.value(p.value)).collect()
+        } else {
+            Ok(vec![self.value(p.value)?])
+        }
+    }
+}
+
+#[allow(clippy::unnecessary_fallible_conversions)]
+fn ply_tag(tag: Tag) -> Result<i32> {
+    Ok(tag.try_into()?)
+}
+
+/// Vertex indices are written as `uint`
+#[allow(clippy::unnecessary_fallible_conversions)]
+fn ply_index(i: Idx) -> Result<u32> {
+    u32::try_from(i).map_err(|_| Error::from(&format!("ply: vertex index {i} too large")))
+}
+
+/// Convert a vertex index read from a .ply file, that must be lower than `n_verts`
+fn read_index(x: f64, n_verts: usize) -> Result<Idx> {
+    if x < 0.0 || x.fract() != 0.0 || x >= n_verts as f64 {
+        return Err(Error::from(&format!("ply: invalid vertex index {x}")));
+    }
+    Ok(x as Idx)
+}
+
+impl<const D: usize> SimplexMesh<D, Triangle> {
+    /// Write the mesh in a .ply file, ascii or binary (little endian). The element tags are
+    /// written as a `tag` face property, and the scalar vertex fields in `vertex_data` as vertex
+    /// properties. The faces are not written
+    pub fn write_ply(
+        &self,
+        file_name: &str,
+        binary: bool,
+        vertex_data: Option<HashMap<String, &[f64]>>,
+    ) -> Result<()> {
+        debug!("Write {file_name}");
+
+        if !(2..=3).contains(&D) {
+            return Err(Error::from("ply: invalid dimension"));
+        }
+        let n_verts = self.n_verts() as usize;
+        let mut fields = vertex_data
+            .unwrap_or_default()
+            .into_iter()
+            .collect::<Vec<_>>();
+        fields.sort_by(|a, b| a.0.cmp(&b.0));
+        for (name, arr) in &fields {
+            if arr.len() != n_verts {
+                return Err(Error::from(&format!(
+                    "ply: field {name} is not a scalar vertex field"
+                )));
+            }
+            if name.is_empty() || name.contains(char::is_whitespace) {
+                return Err(Error::from(&format!("ply: invalid field name {name}")));
+            }
+        }
+
+        let mut file = BufWriter::new(File::create(file_name)?);
+        writeln!(file, "ply")?;
+        if binary {
+            writeln!(file, "format binary_little_endian 1.0")?;
+        } else {
+            writeln!(file, "format ascii 1.0")?;
+        }
+        writeln!(file, "comment written by tucanos")?;
+        writeln!(file, "element vertex {n_verts}")?;
+        for name in ["x", "y", "z"].iter().chain(fields.iter().map(|(n, _)| n)) {
+            writeln!(file, "property double {name}")?;
+        }
+        writeln!(file, "element face {}", self.n_elems())?;
+        writeln!(file, "property list uchar uint vertex_indices")?;
+        writeln!(file, "property int tag")?;
+        writeln!(file, "end_header")?;
+
+        for (i, p) in self.verts().enumerate() {
+            let z = if D == 3 { p[2] } else { 0.0 };
+            let values = [p[0], p[1], z]
+                .into_iter()
+                .chain(fields.iter().map(|(_, arr)| arr[i]));
+            if binary {
+                for x in values {
+                    file.write_all(&x.to_le_bytes())?;
+                }
+            } else {
+                let values = values.map(|x| format!("{x:.16e}")).collect::<Vec<_>>();
+                writeln!(file, "{}", values.join(" "))?;
+            }
+        }
+        for (e, t) in self.elems().zip(self.etags()) {
+            let t = ply_tag(t)?;
+            if binary {
+                file.write_all(&[3_u8])?;
+                for i in e {
+                    file.write_all(&ply_index(i)?.to_le_bytes())?;
+                }
+                file.write_all(&t.to_le_bytes())?;
+            } else {
+                let [i0, i1, i2] = [e[0], e[1], e[2]].map(ply_index);
+                writeln!(file, "3 {} {} {} {t}", i0?, i1?, i2?)?;
+            }
+        }
+        file.flush()?;
+
+        Ok(())
+    }
+
+    /// Read a .ply file (ascii or binary). Polygonal faces are split into triangles. The element
+    /// tags are read from a `tag` face property if present (1 otherwise), and the other scalar
+    /// vertex properties are returned as vertex fields
+    pub fn read_ply(file_name: &str) -> Result<(Self, HashMap<String, Vec<f64>>)> {
+        debug!("Read {file_name}");
+
+        let mut reader = BufReader::new(File::open(file_name)?);
+        let mut next_line = || -> Result<String> {
+            let mut line = String::new();
+            if reader.read_line(&mut line)? == 0 {
+                return Err(Error::from("ply: unexpected end of file"));
+            }
+            Ok(line.trim().to_string())
+        };
+
+        if next_line()? != "ply" {
+            return Err(Error::from("ply: invalid file"));
+        }
+        let mut format = None;
+        let mut elements: Vec<PlyElement> = Vec::new();
+        loop {
+            let line = next_line()?;
+            let words = line.split_whitespace().collect::<Vec<_>>();
+            match words.as_slice() {
+                ["end_header"] => break,
+                ["format", f, _] => format = Some((*f).to_string()),
+                ["element", name, count] => elements.push(PlyElement {
+                    name: (*name).to_string(),
+                    count: count.parse()?,
+                    properties: Vec::new(),
+                }),
+                ["property", "list", count, value, name] => elements
+                    .last_mut()
+                    .ok_or_else(|| Error::from("ply: property without element"))?
+                    .properties
+                    .push(PlyProperty {
+                        name: (*name).to_string(),
+                        value: PlyType::from_name(value)?,
+                        count: Some(PlyType::from_name(count)?),
+                    }),
+                ["property", value, name] => elements
+                    .last_mut()
+                    .ok_or_else(|| Error::from("ply: property without element"))?
+                    .properties
+                    .push(PlyProperty {
+                        name: (*name).to_string(),
+                        value: PlyType::from_name(value)?,
+                        count: None,
+                    }),
+                ["comment", ..] | ["obj_info", ..] | [] => {}
+                _ => return Err(Error::from(&format!("ply: invalid header line {line}"))),
+            }
+        }
+
+        let mut body = match format.as_deref() {
+            Some("ascii") => {
+                let mut data = String::new();
+                reader.read_to_string(&mut data)?;
+                PlyReader::Ascii(
+                    data.split_whitespace()
+                        .map(str::to_string)
+                        .collect::<Vec<_>>()
+                        .into_iter(),
+                )
+            }
+            Some("binary_little_endian") => PlyReader::Binary(reader, false),
+            Some("binary_big_endian") => PlyReader::Binary(reader, true),
+            _ => return Err(Error::from("ply: invalid format")),
+        };
+
+        let mut verts = Vec::new();
+        let mut fields: HashMap<String, Vec<f64>> = HashMap::new();
+        let mut polygons = Vec::new();
+        let mut elems = Vec::new();
+        let mut etags = Vec::new();
+        for element in &elements {
+            for _ in 0..element.count {
+                let mut p = Point::<D>::zeros();
+                let mut polygon = Vec::new();
+                let mut tag = 1;
+                for property in &element.properties {
+                    let values = body.property(property)?;
+                    match (element.name.as_str(), property.name.as_str()) {
+                        ("vertex", "x") => p[0] = values[0],
+                        ("vertex", "y") => p[1] = values[0],
+                        ("vertex", "z") => {
+                            if D == 3 {
+                                p[2] = values[0];
+                            }
+                        }
+                        ("vertex", name) if property.count.is_none() => {
+                            fields.entry(name.to_string()).or_default().push(values[0])
+                        }
+                        ("face", "vertex_indices" | "vertex_index") => polygon = values,
+                        ("face", "tag") => tag = values[0] as Tag,
+                        _ => {}
+                    }
+                }
+                match element.name.as_str() {
+                    "vertex" => verts.push(p),
+                    "face" => {
+                        if polygon.len() < 3 {
+                            return Err(Error::from("ply: invalid face"));
+                        }
+                        polygons.push((polygon, tag));
+                    }
+                    _ => {}
+                }
+            }
+        }
+
+        // the vertex and face elements may be in any order
+        for (polygon, tag) in polygons {
+            let polygon = polygon
+                .into_iter()
+                .map(|x| read_index(x, verts.len()))
+                .collect::<Result<Vec<_>>>()?;
+            for i in 1..polygon.len() - 1 {
+                elems.push(Triangle::new(polygon[0], polygon[i], polygon[i + 1]));
+                etags.push(tag);
+            }
+        }
+
+        Ok((
+            Self::new(verts, elems, etags, Vec::new(), Vec::new()),
+            fields,
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        mesh::{
+            test_meshes::{test_mesh_2d, test_mesh_3d},
+            SimplexMesh, Triangle,
+        },
+        Result,
+    };
+    use std::{collections::HashMap, io::Write};
+    use tempfile::NamedTempFile;
+
+    fn check_same<const D: usize>(m0: &SimplexMesh<D, Triangle>, m1: &SimplexMesh<D, Triangle>) {
+        assert_eq!(m0.n_verts(), m1.n_verts());
+        assert_eq!(m0.n_elems(), m1.n_elems());
+        for (p0, p1) in m0.verts().zip(m1.verts()) {
+            assert!((p0 - p1).norm() < 1e-12);
+        }
+        assert!(m0.elems().zip(m1.elems()).all(|(e0, e1)| e0 == e1));
+        assert!(m0.etags().zip(m1.etags()).all(|(t0, t1)| t0 == t1));
+    }
+
+    #[test]
+    fn test_ply_2d() -> Result<()> {
+        let mesh = test_mesh_2d().split();
+        let u = mesh.verts().map(|p| p[0] + 2.0 * p[1]).collect::<Vec<_>>();
+
+        for (binary, ext) in [(false, "_a.ply"), (true, "_b.ply")] {
+            let file = NamedTempFile::new().unwrap();
+            let fname = file.path().to_str().unwrap().to_owned() + ext;
+            let mut vertex_data = HashMap::new();
+            vertex_data.insert(String::from("u"), u.as_slice());
+            mesh.write_ply(&fname, binary, Some(vertex_data))?;
+
+            let (mesh2, fields) = SimplexMesh::<2, Triangle>::read_ply(&fname)?;
+            check_same(&mesh, &mesh2);
+            assert_eq!(fields.len(), 1);
+            assert!(fields["u"]
+                .iter()
+                .zip(u.iter())
+                .all(|(a, b)| (a - b).abs() < 1e-12));
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_ply_3d() -> Result<()> {
+        let mesh = test_mesh_3d().split().boundary().0;
+
+        let file = NamedTempFile::new().unwrap();
+        let fname = file.path().to_str().unwrap().to_owned() + ".ply";
+        mesh.write_ply(&fname, true, None)?;
+        let (mesh2, fields) = SimplexMesh::<3, Triangle>::read_ply(&fname)?;
+        check_same(&mesh, &mesh2);
+        assert!(fields.is_empty());
+
+        let mut vertex_data = HashMap::new();
+        vertex_data.insert(String::from("u"), &[1.0, 2.0][..]);
+        assert!(mesh.write_ply(&fname, true, Some(vertex_data)).is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_ply_polygons() -> Result<()> {
+        let ply = "ply
+format ascii 1.0
+element vertex 4
+property float x
+property float y
+property float z
+property uchar red
+element face 1
+property list uchar uint vertex_index
+end_header
+0 0 0 255
+1 0 0 0
+1 1 0 0
+0 1 0 0
+4 0 1 2 3
+";
+        let mut file = NamedTempFile::new().unwrap();
+        file.write_all(ply.as_bytes()).unwrap();
+        let (mesh, fields) = SimplexMesh::<3, Triangle>::read_ply(file.path().to_str().unwrap())?;
+
+        assert_eq!(mesh.n_verts(), 4);
+        assert_eq!(mesh.n_elems(), 2);
+        assert!(mesh.etags().all(|t| t == 1));
+        assert!((mesh.vol() - 1.0).abs() < 1e-12);
+        assert_eq!(fields["red"], [255.0, 0.0, 0.0, 0.0]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_ply_invalid_index() {
+        for face in ["3 0 1 3", "3 0 1 -1"] {
+            let ply = format!(
+                "ply
+format ascii 1.0
+element vertex 3
+property float x
+property float y
+property float z
+element face 1
+property list uchar int vertex_index
+end_header
+0 0 0
+1 0 0
+1 1 0
+{face}
+"
+            );
+            let mut file = NamedTempFile::new().unwrap();
+            file.write_all(ply.as_bytes()).unwrap();
+            let res = SimplexMesh::<3, Triangle>::read_ply(file.path().to_str().unwrap());
+            assert!(res.is_err());
+        }
+    }
+}
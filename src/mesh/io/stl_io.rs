@@ -2,10 +2,11 @@ use crate::{
     mesh::geom_elems::GElem,
     mesh::{Elem, Point, SimplexMesh, Triangle},
     spatialindex::{DefaultObjectIndex, ObjectIndex},
-    Idx,
+    Error, Idx, Result,
 };
 use log::{debug, warn};
-use std::{f64::consts::PI, fs::OpenOptions};
+use rustc_hash::FxHashMap;
+use std::{collections::VecDeque, f64::consts::PI, fs::OpenOptions};
 
 /// Read a .stl file (ascii or binary) and return a new SimplexMesh<3, Triangle>
 #[must_use]
@@ -37,6 +38,138 @@ pub fn read_stl(file_name: &str) -> SimplexMesh<3, Triangle> {
     SimplexMesh::<3, Triangle>::new(verts, elems, etags, faces, ftags)
 }
 
+impl SimplexMesh<3, Triangle> {
+    /// Read a .stl file (ascii or binary) and return a new SimplexMesh<3, Triangle>
+    ///   - the vertices closer than `merge_tol` are merged, and the degenerate triangles removed,
+    ///   - the triangles are oriented consistently (see `orient_surface`),
+    ///   - if `feature_angle` is given, the surface is split into patches at the edges where the
+    ///     angle between the normals is above `feature_angle` (in degrees), and the element tags
+    ///     are the patch indices (see `autotag`)
+    pub fn read_stl(file_name: &str, merge_tol: f64, feature_angle: Option<f64>) -> Result<Self> {
+        debug!("Read {file_name}");
+
+        let mut file = OpenOptions::new().read(true).open(file_name)?;
+        let stl = stl_io::read_stl(&mut file)?;
+
+        let verts = stl
+            .vertices
+            .iter()
+            .map(|v| Point::<3>::new(f64::from(v[0]), f64::from(v[1]), f64::from(v[2])))
+            .collect::<Vec<_>>();
+        let elems = stl
+            .faces
+            .iter()
+            .map(|f| Triangle::from_iter(f.vertices.iter().map(|&i| i as Idx)))
+            .collect::<Vec<_>>();
+        let etags = vec![1; elems.len()];
+
+        let mut res = Self::new(verts, elems, etags, Vec::new(), Vec::new());
+        let n_merged = res.merge_coincident_vertices(merge_tol);
+        if n_merged > 0 {
+            debug!("{n_merged} vertices merged");
+        }
+        let (n_degenerate, _) = res.remove_degenerate_elements();
+        if n_degenerate > 0 {
+            warn!("{n_degenerate} degenerate triangles removed");
+        }
+        res.orient_surface();
+
+        if let Some(angle) = feature_angle {
+            res.compute_face_to_elems();
+            res.autotag(angle)?;
+            res.clear_face_to_elems();
+        }
+
+        Ok(res)
+    }
+
+    /// Orient the triangles consistently: two triangles sharing a (manifold) edge go through it
+    /// in opposite directions. Each connected component is oriented such that its signed volume is
+    /// positive, i.e. outwards for closed surfaces. Returns the number of triangles reoriented
+    pub fn orient_surface(&mut self) -> Idx {
+        debug!("Orient the surface triangles");
+
+        // sorted edge -> (element, true if the edge is traversed from the smallest vertex)
+        let mut edges: FxHashMap<[Idx; 2], Vec<(Idx, bool)>> = FxHashMap::default();
+        for (i_elem, e) in self.elems().enumerate() {
+            for i in 0..3 {
+                let (i0, i1) = (e[i], e[(i + 1) % 3]);
+                edges
+                    .entry([i0.min(i1), i0.max(i1)])
+                    .or_default()
+                    .push((i_elem as Idx, i0 < i1));
+            }
+        }
+
+        let n_elems = self.n_elems() as usize;
+        let mut flip = vec![false; n_elems];
+        let mut component = vec![usize::MAX; n_elems];
+        let mut n_components = 0;
+        let mut n_conflicts = 0;
+        let mut queue = VecDeque::new();
+        for start in 0..n_elems {
+            if component[start] != usize::MAX {
+                continue;
+            }
+            component[start] = n_components;
+            queue.push_back(start);
+            while let Some(i_elem) = queue.pop_front() {
+                let e = self.elem(i_elem as Idx);
+                for i in 0..3 {
+                    let (i0, i1) = (e[i], e[(i + 1) % 3]);
+                    let neighbors = &edges[&[i0.min(i1), i0.max(i1)]];
+                    if neighbors.len() != 2 {
+                        continue;
+                    }
+                    let fwd = i0 < i1;
+                    let &(j_elem, j_fwd) = neighbors
+                        .iter()
+                        .find(|&&(j, _)| j as usize != i_elem)
+                        .unwrap();
+                    let j_elem = j_elem as usize;
+                    let j_flip = flip[i_elem] ^ (fwd == j_fwd);
+                    if component[j_elem] == usize::MAX {
+                        component[j_elem] = n_components;
+                        flip[j_elem] = j_flip;
+                        queue.push_back(j_elem);
+                    } else if flip[j_elem] != j_flip {
+                        n_conflicts += 1;
+                    }
+                }
+            }
+            n_components += 1;
+        }
+        if n_conflicts > 0 {
+            warn!("The surface is not orientable");
+        }
+
+        // Signed volume of the components
+        let mut vol = vec![0.0; n_components];
+        for (i_elem, e) in self.elems().enumerate() {
+            let p0 = self.vert(e[0]);
+            let p1 = self.vert(e[1]);
+            let p2 = self.vert(e[2]);
+            let v = p0.dot(&p1.cross(&p2)) / 6.0;
+            vol[component[i_elem]] += if flip[i_elem] { -v } else { v };
+        }
+
+        let mut n_flipped = 0;
+        self.mut_elems()
+            .zip(flip.iter().zip(component.iter()))
+            .for_each(|(e, (&f, &c))| {
+                if f != (vol[c] < 0.0) {
+                    *e = Triangle::new(e[1], e[0], e[2]);
+                    n_flipped += 1;
+                }
+            });
+
+        if n_flipped > 0 {
+            warn!("{} triangles reoriented", n_flipped);
+        }
+        n_flipped
+    }
+}
+
 /// Reorder a surface mesh that provides a representation of the geometry of the boundary of a
 /// volume mesh such that boundary faces are oriented outwards.
 /// TODO: find a better name!
@@ -90,9 +223,12 @@ mod tests {
     use crate::{
         mesh::io::read_stl,
         mesh::test_meshes::{test_mesh_3d, write_stl_file},
-        mesh::Triangle,
+        mesh::{SimplexMesh, Triangle},
         Result,
     };
+    use rustc_hash::FxHashSet;
+    use std::io::Write;
+    use tempfile::NamedTempFile;
 
     #[test]
     fn test_stl() -> Result<()> {
@@ -133,4 +269,84 @@ mod tests {
         assert!(f64::abs(v - 6.0) < 1e-10);
         Ok(())
     }
+
+    #[test]
+    fn test_read_stl_features() -> Result<()> {
+        let file = NamedTempFile::new().unwrap();
+        let fname = file.path().to_str().unwrap();
+        write_stl_file(fname)?;
+        let geom = SimplexMesh::<3, Triangle>::read_stl(fname, 1e-6, None)?;
+        let geom_tagged = SimplexMesh::<3, Triangle>::read_stl(fname, 1e-6, Some(30.0))?;
+
+        assert_eq!(geom.n_verts(), 8);
+        assert!(geom.etags().all(|t| t == 1));
+        assert!(f64::abs(geom.vol() - 6.0) < 1e-10);
+
+        let tags = geom_tagged.etags().collect::<FxHashSet<_>>();
+        assert_eq!(tags.len(), 6);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_read_stl_merge_orient() -> Result<()> {
+        // Tetrahedron surface, with duplicated (and slightly moved) vertices and the last
+        // triangle inverted
+        let stl = "solid tet
+facet normal 0 0 0
+  outer loop
+    vertex 0 0 0
+    vertex 0 1 0
+    vertex 1 0 0
+  endloop
+endfacet
+facet normal 0 0 0
+  outer loop
+    vertex 0 0 0.00001
+    vertex 1 0 0
+    vertex 0 0 1
+  endloop
+endfacet
+facet normal 0 0 0
+  outer loop
+    vertex 0 0 0
+    vertex 0 0 1
+    vertex 0 1 0
+  endloop
+endfacet
+facet normal 0 0 0
+  outer loop
+    vertex 1 0 0
+    vertex 0 0 1
+    vertex 0 1 0
+  endloop
+endfacet
+endsolid tet
+";
+        let mut file = NamedTempFile::new().unwrap();
+        file.write_all(stl.as_bytes()).unwrap();
+        let fname = file.path().to_str().unwrap();
+
+        let geom = SimplexMesh::<3, Triangle>::read_stl(fname, 1e-3, None)?;
+        assert_eq!(geom.n_verts(), 4);
+        assert_eq!(geom.n_elems(), 4);
+
+        // each directed edge appears once, and the surface is oriented outwards
+        let mut edges = FxHashSet::default();
+        let mut vol = 0.0;
+        for e in geom.elems() {
+            for i in 0..3 {
+                assert!(edges.insert([e[i], e[(i + 1) % 3]]));
+            }
+            let (p0, p1, p2) = (geom.vert(e[0]), geom.vert(e[1]), geom.vert(e[2]));
+            vol += p0.dot(&p1.cross(&p2)) / 6.0;
+        }
+        assert!((vol - 1.0 / 6.0).abs() < 1e-4);
+
+        // no merge
+        let geom = SimplexMesh::<3, Triangle>::read_stl(fname, 1e-8, None)?;
+        assert_eq!(geom.n_verts(), 5);
+
+        Ok(())
+    }
 }
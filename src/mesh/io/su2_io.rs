@@ -0,0 +1,336 @@
+use crate::{
+    mesh::{Elem, Point, SimplexMesh},
+    Error, Idx, Result, Tag,
+};
+use log::debug;
+use std::{
+    fs::File,
+    io::{BufRead, BufReader, BufWriter, Write},
+};
+
+/// VTK element type (as used in .su2 files) for simplices with `n` vertices
+fn su2_elem_type(n: Idx) -> Result<usize> {
+    match n {
+        2 => Ok(3),
+        3 => Ok(5),
+        4 => Ok(10),
+        _ => Err(Error::from("su2: unsupported element type")),
+    }
+}
+
+/// Get the value of `keyword` in a line `keyword= value`
+fn su2_value<'a>(line: &'a str, keyword: &str) -> Result<&'a str> {
+    line.strip_prefix(keyword)
+        .and_then(|l| l.trim_start().strip_prefix('='))
+        .map(str::trim)
+        .ok_or_else(|| Error::from(&format!("su2: expected {keyword}, found {line}")))
+}
+
+/// Read the connectivity of an element of type `etype` from a line of a .su2 file
+fn su2_elem<E: Elem>(line: &str, etype: usize) -> Result<E> {
+    let mut it = line.split_whitespace();
+    let t: usize = it.next().unwrap_or("").parse()?;
+    if t != etype {
+        return Err(Error::from(&format!("su2: unsupported element type {t}")));
+    }
+    let mut ids = Vec::with_capacity(E::N_VERTS as usize);
+    for _ in 0..E::N_VERTS {
+        ids.push(
+            it.next()
+                .ok_or_else(|| Error::from("su2: invalid element"))?
+                .parse()?,
+        );
+    }
+    Ok(E::from_slice(&ids))
+}
+
+impl<const D: usize, E: Elem> SimplexMesh<D, E> {
+    /// Write the mesh in the SU2 native format. The faces are written as markers, one per face
+    /// tag, named after the face tag name (see `tag_names`) or the tag itself. The element tags
+    /// are not written
+    pub fn write_su2(&self, file_name: &str) -> Result<()> {
+        debug!("Write {file_name}");
+
+        if E::DIM as usize != D {
+            return Err(Error::from("su2: only volume meshes are supported"));
+        }
+        let elem_type = su2_elem_type(E::N_VERTS)?;
+        let face_type = su2_elem_type(E::Face::N_VERTS)?;
+
+        let mut ftags = self.ftags().collect::<Vec<_>>();
+        ftags.sort_unstable();
+        ftags.dedup();
+        let mut names = Vec::with_capacity(ftags.len());
+        for &tag in &ftags {
+            let name = self
+                .tag_names()
+                .face_name(tag)
+                .map_or_else(|| tag.to_string(), str::to_string);
+            if name.is_empty() || name.contains(char::is_whitespace) {
+                return Err(Error::from(&format!("su2: invalid marker name {name}")));
+            }
+            names.push(name);
+        }
+
+        let mut file = BufWriter::new(File::create(file_name)?);
+
+        writeln!(file, "NDIME= {D}")?;
+        writeln!(file, "NELEM= {}", self.n_elems())?;
+        for (i, e) in self.elems().enumerate() {
+            write!(file, "{elem_type}")?;
+            for j in e {
+                write!(file, " {j}")?;
+            }
+            writeln!(file, " {i}")?;
+        }
+
+        writeln!(file, "NPOIN= {}", self.n_verts())?;
+        for (i, p) in self.verts().enumerate() {
+            for x in p.iter() {
+                write!(file, "{x:.16e} ")?;
+            }
+            writeln!(file, "{i}")?;
+        }
+
+        writeln!(file, "NMARK= {}", ftags.len())?;
+        for (tag, name) in ftags.into_iter().zip(names) {
+            writeln!(file, "MARKER_TAG= {name}")?;
+            writeln!(
+                file,
+                "MARKER_ELEMS= {}",
+                self.ftags().filter(|&t| t == tag).count()
+            )?;
+            for (f, _) in self.faces().zip(self.ftags()).filter(|&(_, t)| t == tag) {
+                write!(file, "{face_type}")?;
+                for j in f {
+                    write!(file, " {j}")?;
+                }
+                writeln!(file)?;
+            }
+        }
+        file.flush()?;
+
+        Ok(())
+    }
+
+    /// Read a mesh in the SU2 native format (single zone). All the elements are tagged 1. The faces
+    /// of the markers with an integer name are tagged with this integer, the ones of the other
+    /// markers with the next available tags, and the marker names are stored as face tag names
+    /// (see `tag_names`)
+    pub fn read_su2(file_name: &str) -> Result<Self> {
+        debug!("Read {file_name}");
+
+        let elem_type = su2_elem_type(E::N_VERTS)?;
+        let face_type = su2_elem_type(E::Face::N_VERTS)?;
+
+        let file = BufReader::new(File::open(file_name)?);
+        let mut lines = file
+            .lines()
+            .map_while(std::result::Result::ok)
+            .map(|l| l.trim().to_string())
+            .filter(|l| !l.is_empty() && !l.starts_with('%'));
+        let mut next_line = || {
+            lines
+                .next()
+                .ok_or_else(|| Error::from("su2: unexpected end of file"))
+        };
+
+        let mut verts = Vec::new();
+        let mut elems = Vec::new();
+        let mut markers = Vec::new();
+
+        let mut n_markers = None;
+        while n_markers.is_none_or(|n| markers.len() < n) {
+            let line = match next_line() {
+                Ok(l) => l,
+                Err(e) => {
+                    if n_markers.is_none() {
+                        break;
+                    }
+                    return Err(e);
+                }
+            };
+            if line.starts_with("NDIME") {
+                let dim: usize = su2_value(&line, "NDIME")?.parse()?;
+                if dim != D || E::DIM as usize != D {
+                    return Err(Error::from("su2: invalid dimension"));
+                }
+            } else if line.starts_with("NELEM") {
+                let n: usize = su2_value(&line, "NELEM")?.parse()?;
+                elems.reserve(n);
+                for _ in 0..n {
+                    elems.push(su2_elem::<E>(&next_line()?, elem_type)?);
+                }
+            } else if line.starts_with("NPOIN") {
+                // NPOIN= n [n_domain]
+                let n: usize = su2_value(&line, "NPOIN")?
+                    .split_whitespace()
+                    .next()
+                    .unwrap_or("")
+                    .parse()?;
+                verts.reserve(n);
+                for _ in 0..n {
+                    let line = next_line()?;
+                    let mut p = Point::<D>::zeros();
+                    let mut it = line.split_whitespace();
+                    for x in p.iter_mut() {
+                        *x = it
+                            .next()
+                            .ok_or_else(|| Error::from("su2: invalid vertex"))?
+                            .parse()?;
+                    }
+                    verts.push(p);
+                }
+            } else if line.starts_with("NMARK") {
+                n_markers = Some(su2_value(&line, "NMARK")?.parse()?);
+            } else if line.starts_with("MARKER_TAG") {
+                let name = su2_value(&line, "MARKER_TAG")?.to_string();
+                let line = next_line()?;
+                let n: usize = su2_value(&line, "MARKER_ELEMS")?.parse()?;
+                let mut faces = Vec::with_capacity(n);
+                for _ in 0..n {
+                    faces.push(su2_elem::<E::Face>(&next_line()?, face_type)?);
+                }
+                markers.push((name, faces));
+            } else if line.starts_with("NZONE") {
+                return Err(Error::from("su2: multi-zone files are not supported"));
+            } else {
+                debug!("su2: skip {line}");
+            }
+        }
+
+        let mut next_tag = markers
+            .iter()
+            .filter_map(|(name, _)| name.parse::<Tag>().ok())
+            .max()
+            .unwrap_or(0)
+            + 1;
+        let mut faces = Vec::new();
+        let mut ftags = Vec::new();
+        let mut tag_names = Vec::new();
+        for (name, marker_faces) in markers {
+            let tag = name.parse::<Tag>().unwrap_or_else(|_| {
+                next_tag += 1;
+                next_tag - 1
+            });
+            ftags.extend(std::iter::repeat_n(tag, marker_faces.len()));
+            faces.extend(marker_faces);
+            tag_names.push((tag, name));
+        }
+
+        let etags = vec![1; elems.len()];
+        let mut res = Self::new(verts, elems, etags, faces, ftags);
+        for (tag, name) in tag_names {
+            res.tag_names_mut().set_face_name(tag, &name);
+        }
+
+        Ok(res)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        mesh::{
+            test_meshes::{test_mesh_2d, test_mesh_3d},
+            Elem, SimplexMesh, Tetrahedron, Triangle,
+        },
+        Result,
+    };
+    use std::io::Write;
+    use tempfile::NamedTempFile;
+
+    fn check_same<const D: usize, E: Elem>(m0: &SimplexMesh<D, E>, m1: &SimplexMesh<D, E>) {
+        assert_eq!(m0.n_verts(), m1.n_verts());
+        assert_eq!(m0.n_elems(), m1.n_elems());
+        assert_eq!(m0.n_faces(), m1.n_faces());
+        for (p0, p1) in m0.verts().zip(m1.verts()) {
+            assert!((p0 - p1).norm() < 1e-12);
+        }
+        for (e0, e1) in m0.elems().zip(m1.elems()) {
+            assert_eq!(e0, e1);
+        }
+        // the faces are sorted by tag
+        let mut f0 = m0.faces().zip(m0.ftags()).collect::<Vec<_>>();
+        f0.sort_by_key(|(_, t)| *t);
+        for ((f0, t0), (f1, t1)) in f0.iter().zip(m1.faces().zip(m1.ftags())) {
+            assert_eq!(*t0, t1);
+            assert_eq!(*f0, f1);
+        }
+    }
+
+    #[test]
+    fn test_su2_2d() -> Result<()> {
+        let mut mesh = test_mesh_2d().split();
+        mesh.mut_etags().for_each(|t| *t = 1);
+        mesh.tag_names_mut().set_face_name(2, "wall");
+
+        let file = NamedTempFile::new().unwrap();
+        let fname = file.path().to_str().unwrap().to_owned() + ".su2";
+        mesh.write_su2(&fname)?;
+        let mesh2 = SimplexMesh::<2, Triangle>::read_su2(&fname)?;
+
+        check_same(&mesh, &mesh2);
+        assert_eq!(mesh2.tag_names().face_name(1), Some("1"));
+        assert_eq!(mesh2.tag_names().face_name(2), Some("wall"));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_su2_3d() -> Result<()> {
+        let mut mesh = test_mesh_3d().split();
+        mesh.mut_etags().for_each(|t| *t = 1);
+
+        let file = NamedTempFile::new().unwrap();
+        let fname = file.path().to_str().unwrap().to_owned() + ".su2";
+        mesh.write_su2(&fname)?;
+        let mesh2 = SimplexMesh::<3, Tetrahedron>::read_su2(&fname)?;
+
+        check_same(&mesh, &mesh2);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_su2_markers() -> Result<()> {
+        let su2 = "% a square
+NDIME= 2
+NELEM= 2
+5 0 1 2 0
+5 0 2 3 1
+NPOIN= 4
+0.0 0.0 0
+1.0 0.0 1
+1.0 1.0 2
+0.0 1.0 3
+NMARK= 3
+MARKER_TAG= inlet
+MARKER_ELEMS= 1
+3 3 0
+MARKER_TAG= 2
+MARKER_ELEMS= 1
+3 1 2
+MARKER_TAG= wall
+MARKER_ELEMS= 2
+3 0 1
+3 2 3
+";
+        let mut file = NamedTempFile::new().unwrap();
+        file.write_all(su2.as_bytes()).unwrap();
+        let mesh = SimplexMesh::<2, Triangle>::read_su2(file.path().to_str().unwrap())?;
+
+        assert_eq!(mesh.n_verts(), 4);
+        assert_eq!(mesh.n_elems(), 2);
+        assert_eq!(mesh.ftags().collect::<Vec<_>>(), [3, 2, 4, 4]);
+        assert_eq!(mesh.tag_names().face_name(3), Some("inlet"));
+        assert_eq!(mesh.tag_names().face_name(4), Some("wall"));
+        assert!((mesh.vol() - 1.0).abs() < 1e-12);
+
+        let mut mesh2 = mesh.clone();
+        mesh2.tag_names_mut().set_face_name(2, "bad name");
+        assert!(mesh2.write_su2("mesh.su2").is_err());
+
+        Ok(())
+    }
+}
@@ -0,0 +1,568 @@
+use crate::{
+    mesh::{Elem, SimplexMesh},
+    Error, Idx, Result, Tag,
+};
+use log::debug;
+use std::{
+    collections::HashMap,
+    fs::File,
+    io::{BufWriter, Write},
+};
+#[cfg(feature = "tecio")]
+use std::{ffi::CString, os::raw::c_void, ptr::null};
+
+/// Zone marker in .plt files
+const PLT_ZONE_MARKER: f32 = 299.0;
+/// End of header marker in .plt files
+const PLT_EOH_MARKER: f32 = 357.0;
+
+/// Tecplot zone type for simplices with `n` vertices
+fn tecplot_zone_type(n: Idx) -> Result<(&'static str, i32)> {
+    match n {
+        2 => Ok(("FELINESEG", 1)),
+        3 => Ok(("FETRIANGLE", 2)),
+        4 => Ok(("FETETRAHEDRON", 4)),
+        _ => Err(Error::from("tecplot: unsupported element type")),
+    }
+}
+
+/// Zone (i.e. the elements with a given tag) in a Tecplot file
+struct TecplotZone {
+    tag: Tag,
+    /// Vertices of the zone
+    verts: Vec<Idx>,
+    /// Connectivity, using the local vertex numbering (0 based)
+    elems: Vec<Idx>,
+}
+
+/// Write a string in a .plt file
+fn plt_string<W: Write>(w: &mut W, s: &str) -> Result<()> {
+    for c in s.chars() {
+        w.write_all(&(c as i32).to_le_bytes())?;
+    }
+    w.write_all(&0_i32.to_le_bytes())?;
+    Ok(())
+}
+
+fn plt_i32<W: Write>(w: &mut W, v: i32) -> Result<()> {
+    w.write_all(&v.to_le_bytes())?;
+    Ok(())
+}
+
+impl<const D: usize, E: Elem> SimplexMesh<D, E> {
+    /// Split the elements into zones, one per element tag
+    fn tecplot_zones(&self) -> Vec<TecplotZone> {
+        let mut tags = self.etags().collect::<Vec<_>>();
+        tags.sort_unstable();
+        tags.dedup();
+
+        tags.iter()
+            .map(|&tag| {
+                let mut new_ids = vec![Idx::MAX; self.n_verts() as usize];
+                let mut verts = Vec::new();
+                let mut elems = Vec::new();
+                for (e, _) in self.elems().zip(self.etags()).filter(|&(_, t)| t == tag) {
+                    for i in e {
+                        if new_ids[i as usize] == Idx::MAX {
+                            new_ids[i as usize] = verts.len() as Idx;
+                            verts.push(i);
+                        }
+                        elems.push(new_ids[i as usize]);
+                    }
+                }
+                TecplotZone { tag, verts, elems }
+            })
+            .collect()
+    }
+
+    /// Write the mesh and vertex fields in a Tecplot file, with one FE zone (FETRIANGLE,
+    /// FETETRAHEDRON, ...) per element tag. The format is deduced from the extension: binary
+    /// for .plt, SZL for .szplt (requires the `tecio` feature), ascii otherwise (e.g. .dat).
+    /// Fields with several components are written as one variable per component (`{name}_0`,
+    /// `{name}_1`, ...)
+    pub fn write_tecplot(
+        &self,
+        file_name: &str,
+        vertex_fields: Option<HashMap<String, &[f64]>>,
+    ) -> Result<()> {
+        debug!("Write {file_name}");
+
+        let (zone_type_name, zone_type) = tecplot_zone_type(E::N_VERTS)?;
+
+        // Variables: coordinates, then fields
+        let n_verts = self.n_verts() as usize;
+        let mut names = ["X", "Y", "Z"]
+            .iter()
+            .take(D)
+            .map(|s| (*s).to_string())
+            .collect::<Vec<_>>();
+        let mut values: Vec<Vec<f64>> = (0..D)
+            .map(|i| self.verts().map(|p| p[i]).collect())
+            .collect();
+        let mut fields = vertex_fields
+            .unwrap_or_default()
+            .into_iter()
+            .collect::<Vec<_>>();
+        fields.sort_by(|a, b| a.0.cmp(&b.0));
+        for (name, arr) in fields {
+            if n_verts == 0 || arr.len() % n_verts != 0 {
+                return Err(Error::from(&format!("Invalid size for field {name}")));
+            }
+            let n_comp = arr.len() / n_verts;
+            for j in 0..n_comp {
+                names.push(if n_comp == 1 {
+                    name.clone()
+                } else {
+                    format!("{name}_{j}")
+                });
+                values.push(arr.iter().skip(j).step_by(n_comp).copied().collect());
+            }
+        }
+
+        let zones = self.tecplot_zones();
+        if file_name.ends_with(".szplt") {
+            return Self::write_szplt(file_name, &names, &values, &zones, zone_type);
+        }
+        let mut file = BufWriter::new(File::create(file_name)?);
+
+        if file_name.ends_with(".plt") {
+            // Header
+            file.write_all(b"#!TDV112")?;
+            plt_i32(&mut file, 1)?;
+            plt_i32(&mut file, 0)?;
+            plt_string(&mut file, "tucanos")?;
+            plt_i32(&mut file, names.len() as i32)?;
+            for name in &names {
+                plt_string(&mut file, name)?;
+            }
+            for zone in &zones {
+                file.write_all(&PLT_ZONE_MARKER.to_le_bytes())?;
+                plt_string(&mut file, &format!("tag_{}", zone.tag))?;
+                plt_i32(&mut file, -1)?; // parent zone
+                plt_i32(&mut file, -1)?; // strand id
+                file.write_all(&0.0_f64.to_le_bytes())?; // solution time
+                plt_i32(&mut file, -1)?; // not used
+                plt_i32(&mut file, zone_type)?;
+                plt_i32(&mut file, 0)?; // all the variables at the vertices
+                plt_i32(&mut file, 0)?; // no raw face neighbors
+                plt_i32(&mut file, 0)?; // no user-defined face neighbor connections
+                plt_i32(&mut file, zone.verts.len() as i32)?;
+                plt_i32(&mut file, (zone.elems.len() / E::N_VERTS as usize) as i32)?;
+                for _ in 0..3 {
+                    plt_i32(&mut file, 0)?;
+                }
+                plt_i32(&mut file, 0)?; // no auxiliary data
+            }
+            file.write_all(&PLT_EOH_MARKER.to_le_bytes())?;
+
+            // Data
+            for zone in &zones {
+                file.write_all(&PLT_ZONE_MARKER.to_le_bytes())?;
+                for _ in &names {
+                    plt_i32(&mut file, 2)?; // double
+                }
+                plt_i32(&mut file, 0)?; // no passive variables
+                plt_i32(&mut file, 0)?; // no variable sharing
+                plt_i32(&mut file, -1)?; // no connectivity sharing
+                for v in &values {
+                    let (v_min, v_max) = zone
+                        .verts
+                        .iter()
+                        .map(|&i| v[i as usize])
+                        .fold((f64::MAX, f64::MIN), |(a, b), x| (a.min(x), b.max(x)));
+                    file.write_all(&v_min.to_le_bytes())?;
+                    file.write_all(&v_max.to_le_bytes())?;
+                }
+                for v in &values {
+                    for &i in &zone.verts {
+                        file.write_all(&v[i as usize].to_le_bytes())?;
+                    }
+                }
+                for &i in &zone.elems {
+                    plt_i32(&mut file, i as i32)?;
+                }
+            }
+        } else {
+            writeln!(file, "TITLE = \"tucanos\"")?;
+            write!(file, "VARIABLES =")?;
+            for name in &names {
+                write!(file, " \"{name}\"")?;
+            }
+            writeln!(file)?;
+            for zone in &zones {
+                writeln!(
+                    file,
+                    "ZONE T=\"tag_{}\", N={}, E={}, DATAPACKING=BLOCK, ZONETYPE={zone_type_name}",
+                    zone.tag,
+                    zone.verts.len(),
+                    zone.elems.len() / E::N_VERTS as usize
+                )?;
+                for v in &values {
+                    for (k, &i) in zone.verts.iter().enumerate() {
+                        write!(file, "{:.16e}", v[i as usize])?;
+                        if k % 5 == 4 {
+                            writeln!(file)?;
+                        } else {
+                            write!(file, " ")?;
+                        }
+                    }
+                    writeln!(file)?;
+                }
+                for e in zone.elems.chunks(E::N_VERTS as usize) {
+                    for (k, i) in e.iter().enumerate() {
+                        if k > 0 {
+                            write!(file, " ")?;
+                        }
+                        write!(file, "{}", i + 1)?;
+                    }
+                    writeln!(file)?;
+                }
+            }
+        }
+        file.flush()?;
+
+        Ok(())
+    }
+
+    #[cfg(not(feature = "tecio"))]
+    fn write_szplt(
+        _file_name: &str,
+        _names: &[String],
+        _values: &[Vec<f64>],
+        _zones: &[TecplotZone],
+        _zone_type: i32,
+    ) -> Result<()> {
+        Err(Error::from("the tecio feature is not enabled"))
+    }
+
+    /// Write a .szplt file using TecIO
+    #[cfg(feature = "tecio")]
+    fn write_szplt(
+        file_name: &str,
+        names: &[String],
+        values: &[Vec<f64>],
+        zones: &[TecplotZone],
+        zone_type: i32,
+    ) -> Result<()> {
+        let title = CString::new("tucanos")?;
+        let variables = CString::new(names.join(" "))?;
+        let c_name = CString::new(file_name)?;
+        let scratch_dir = CString::new(".")?;
+        let file = tecio::File::open(&title, &variables, &c_name, &scratch_dir)?;
+
+        let zero = 0;
+        let one = 1;
+        let solution_time = 0.0_f64;
+        for zone in zones {
+            let zone_title = CString::new(format!("tag_{}", zone.tag))?;
+            let n_verts = zone.verts.len() as i32;
+            let n_elems = (zone.elems.len() / E::N_VERTS as usize) as i32;
+            let strand_id = -1;
+            tecio::check(unsafe {
+                tecio::TECZNE142(
+                    zone_title.as_ptr(),
+                    &zone_type,
+                    &n_verts,
+                    &n_elems,
+                    &zero,
+                    &zero,
+                    &zero,
+                    &zero,
+                    &solution_time,
+                    &strand_id,
+                    &zero,
+                    &one,
+                    &zero,
+                    &zero,
+                    &zero,
+                    &zero,
+                    &zero,
+                    null(),
+                    null(),
+                    null(),
+                    &zero,
+                )
+            })?;
+            for v in values {
+                let data = zone
+                    .verts
+                    .iter()
+                    .map(|&i| v[i as usize])
+                    .collect::<Vec<_>>();
+                tecio::check(unsafe {
+                    tecio::TECDAT142(&n_verts, data.as_ptr().cast::<c_void>(), &one)
+                })?;
+            }
+            let conn = zone.elems.iter().map(|&i| i as i32 + 1).collect::<Vec<_>>();
+            tecio::check(unsafe { tecio::TECNOD142(conn.as_ptr()) })?;
+        }
+        file.close()
+    }
+}
+
+#[cfg(feature = "tecio")]
+mod tecio {
+    use crate::{Error, Result};
+    use std::{
+        ffi::CStr,
+        os::raw::{c_char, c_void},
+    };
+
+    pub const FILE_FORMAT_SZPLT: i32 = 1;
+    pub const FILE_TYPE_FULL: i32 = 0;
+
+    #[link(name = "tecio")]
+    extern "C" {
+        pub fn TECINI142(
+            title: *const c_char,
+            variables: *const c_char,
+            file_name: *const c_char,
+            scratch_dir: *const c_char,
+            file_format: *const i32,
+            file_type: *const i32,
+            debug: *const i32,
+            v_is_double: *const i32,
+        ) -> i32;
+        pub fn TECZNE142(
+            zone_title: *const c_char,
+            zone_type: *const i32,
+            i_max_or_num_pts: *const i32,
+            j_max_or_num_elements: *const i32,
+            k_max_or_num_faces: *const i32,
+            i_cell_max: *const i32,
+            j_cell_max: *const i32,
+            k_cell_max: *const i32,
+            solution_time: *const f64,
+            strand_id: *const i32,
+            parent_zone: *const i32,
+            is_block: *const i32,
+            num_face_connections: *const i32,
+            face_neighbor_mode: *const i32,
+            total_num_face_nodes: *const i32,
+            num_connected_boundary_faces: *const i32,
+            total_num_boundary_connections: *const i32,
+            passive_var_list: *const i32,
+            value_location: *const i32,
+            share_var_from_zone: *const i32,
+            share_connectivity_from_zone: *const i32,
+        ) -> i32;
+        pub fn TECDAT142(n: *const i32, field_data: *const c_void, is_double: *const i32) -> i32;
+        pub fn TECNOD142(n_data: *const i32) -> i32;
+        pub fn TECEND142() -> i32;
+    }
+
+    pub fn check(ier: i32) -> Result<()> {
+        if ier == 0 {
+            Ok(())
+        } else {
+            Err(Error::from(&format!("tecio: error {ier}")))
+        }
+    }
+
+    /// File opened with `TECINI142`, closed when dropped if `close` was not called
+    pub struct File(bool);
+
+    impl File {
+        pub fn open(
+            title: &CStr,
+            variables: &CStr,
+            file_name: &CStr,
+            scratch_dir: &CStr,
+        ) -> Result<Self> {
+            let debug = 0;
+            let is_double = 1;
+            check(unsafe {
+                TECINI142(
+                    title.as_ptr(),
+                    variables.as_ptr(),
+                    file_name.as_ptr(),
+                    scratch_dir.as_ptr(),
+                    &FILE_FORMAT_SZPLT,
+                    &FILE_TYPE_FULL,
+                    &debug,
+                    &is_double,
+                )
+            })?;
+            Ok(Self(true))
+        }
+
+        pub fn close(mut self) -> Result<()> {
+            self.0 = false;
+            check(unsafe { TECEND142() })
+        }
+    }
+
+    impl Drop for File {
+        fn drop(&mut self) {
+            if self.0 {
+                unsafe {
+                    TECEND142();
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{PLT_EOH_MARKER, PLT_ZONE_MARKER};
+    use crate::{
+        mesh::test_meshes::{test_mesh_2d, test_mesh_3d},
+        Result,
+    };
+    use std::collections::HashMap;
+
+    #[test]
+    fn test_tecplot_ascii() -> Result<()> {
+        let mesh = test_mesh_2d().split();
+        let u = mesh.verts().flat_map(|p| [p[0], p[1]]).collect::<Vec<_>>();
+
+        let dir = tempfile::tempdir()?;
+        let fname = dir.path().join("mesh.dat");
+        let fname = fname.to_str().unwrap();
+        let mut fields = HashMap::new();
+        fields.insert(String::from("u"), u.as_slice());
+        mesh.write_tecplot(fname, Some(fields))?;
+
+        let data = std::fs::read_to_string(fname)?;
+        let mut lines = data.lines();
+        assert_eq!(lines.next(), Some("TITLE = \"tucanos\""));
+        assert_eq!(
+            lines.next(),
+            Some("VARIABLES = \"X\" \"Y\" \"u_0\" \"u_1\"")
+        );
+        // 2 tags with 4 elements each
+        let zones = data
+            .lines()
+            .filter(|l| l.starts_with("ZONE"))
+            .collect::<Vec<_>>();
+        assert_eq!(zones.len(), 2);
+        for zone in zones {
+            assert!(zone.contains("N=6, E=4"));
+            assert!(zone.contains("ZONETYPE=FETRIANGLE"));
+        }
+
+        Ok(())
+    }
+
+    /// Reader for the header of a .plt file
+    struct PltHeader<'a>(&'a [u8]);
+
+    impl PltHeader<'_> {
+        fn i32(&mut self) -> i32 {
+            let (v, rest) = self.0.split_at(4);
+            self.0 = rest;
+            i32::from_le_bytes(v.try_into().unwrap())
+        }
+
+        fn f32(&mut self) -> f32 {
+            f32::from_bits(self.i32() as u32)
+        }
+
+        fn f64(&mut self) -> f64 {
+            let (v, rest) = self.0.split_at(8);
+            self.0 = rest;
+            f64::from_le_bytes(v.try_into().unwrap())
+        }
+
+        fn string(&mut self) -> String {
+            let mut res = String::new();
+            loop {
+                let c = self.i32();
+                if c == 0 {
+                    return res;
+                }
+                res.push(char::from_u32(c as u32).unwrap());
+            }
+        }
+    }
+
+    #[test]
+    fn test_tecplot_binary() -> Result<()> {
+        let mesh = test_mesh_3d().split();
+        let u = mesh.verts().map(|p| p[2]).collect::<Vec<_>>();
+
+        let dir = tempfile::tempdir()?;
+        let fname = dir.path().join("mesh.plt");
+        let fname = fname.to_str().unwrap();
+        let mut fields = HashMap::new();
+        fields.insert(String::from("u"), u.as_slice());
+        mesh.write_tecplot(fname, Some(fields))?;
+
+        let data = std::fs::read(fname)?;
+        assert_eq!(&data[..8], b"#!TDV112");
+        let mut header = PltHeader(&data[8..]);
+        assert_eq!(header.i32(), 1);
+        assert_eq!(header.i32(), 0);
+        assert_eq!(header.string(), "tucanos");
+        assert_eq!(header.i32(), 4);
+        for name in ["X", "Y", "Z", "u"] {
+            assert_eq!(header.string(), name);
+        }
+
+        let mut tags = mesh.etags().collect::<Vec<_>>();
+        tags.sort_unstable();
+        tags.dedup();
+        for &tag in &tags {
+            let n_elems = mesh.etags().filter(|&t| t == tag).count();
+            let n_verts = {
+                let mut verts = mesh
+                    .elems()
+                    .zip(mesh.etags())
+                    .filter(|&(_, t)| t == tag)
+                    .flat_map(|(e, _)| e)
+                    .collect::<Vec<_>>();
+                verts.sort_unstable();
+                verts.dedup();
+                verts.len()
+            };
+            assert!((header.f32() - PLT_ZONE_MARKER).abs() < 1e-12);
+            assert_eq!(header.string(), format!("tag_{tag}"));
+            assert_eq!(header.i32(), -1);
+            assert_eq!(header.i32(), -1);
+            assert!(header.f64().abs() < 1e-12);
+            assert_eq!(header.i32(), -1);
+            assert_eq!(header.i32(), 4);
+            assert_eq!(header.i32(), 0);
+            assert_eq!(header.i32(), 0);
+            assert_eq!(header.i32(), 0);
+            assert_eq!(header.i32(), n_verts as i32);
+            assert_eq!(header.i32(), n_elems as i32);
+            for _ in 0..3 {
+                assert_eq!(header.i32(), 0);
+            }
+            assert_eq!(header.i32(), 0);
+        }
+        assert!((header.f32() - PLT_EOH_MARKER).abs() < 1e-12);
+
+        // first zone
+        assert!((header.f32() - PLT_ZONE_MARKER).abs() < 1e-12);
+        for _ in 0..4 {
+            assert_eq!(header.i32(), 2);
+        }
+        assert_eq!(header.i32(), 0);
+        assert_eq!(header.i32(), 0);
+        assert_eq!(header.i32(), -1);
+
+        #[cfg(not(feature = "tecio"))]
+        assert!(mesh.write_tecplot("mesh.szplt", None).is_err());
+
+        Ok(())
+    }
+
+    #[cfg(feature = "tecio")]
+    #[test]
+    fn test_tecplot_szplt() -> Result<()> {
+        let mesh = test_mesh_3d().split();
+
+        let dir = tempfile::tempdir()?;
+        let fname = dir.path().join("mesh.szplt");
+        let fname = fname.to_str().unwrap();
+        mesh.write_tecplot(fname, None)?;
+
+        let data = std::fs::read(fname)?;
+        assert!(!data.is_empty());
+
+        Ok(())
+    }
+}
@@ -0,0 +1,628 @@
+use crate::{
+    mesh::{Elem, SimplexMesh},
+    metric::Metric,
+    Error, Idx, Result,
+};
+use log::debug;
+use std::{
+    collections::HashMap,
+    fs::File,
+    io::{BufWriter, Write},
+    path::Path,
+};
+
+#[cfg(feature = "64bit-tags")]
+const TAG_TYPE: &str = "Int64";
+#[cfg(feature = "32bit-tags")]
+const TAG_TYPE: &str = "Int32";
+#[cfg(not(any(feature = "32bit-tags", feature = "64bit-tags")))]
+const TAG_TYPE: &str = "Int16";
+
+/// VTK cell type for simplices of dimension `dim` with `n` nodes, and the node ordering (the
+/// k-th VTK node is node `perm[k]` of the element)
+#[must_use]
+pub const fn vtk_cell_type(dim: Idx, n: Idx) -> (u8, &'static [usize]) {
+    match (dim, n) {
+        (3, 4) => (10, &[0, 1, 2, 3]),
+        (2, 3) => (5, &[0, 1, 2]),
+        (1, 2) => (3, &[0, 1]),
+        (3, 10) => (24, &[0, 1, 2, 3, 9, 6, 7, 8, 5, 4]),
+        (2, 6) => (22, &[0, 1, 2, 5, 3, 4]),
+        (1, 3) => (21, &[0, 1, 2]),
+        _ => unreachable!(),
+    }
+}
+
+/// Convert a metric field to 6-component symmetric tensors (XX, YY, ZZ, XY, YZ, XZ), as
+/// expected by ParaView
+#[must_use]
+pub fn metric_to_vtk_tensor<const D: usize, M: Metric<D>>(m: &[M]) -> Vec<f64> {
+    let mut res = Vec::with_capacity(6 * m.len());
+    for x in m {
+        let v = x.into_iter().collect::<Vec<_>>();
+        match (D, v.len()) {
+            (_, 1) => {
+                let s = 1.0 / (v[0] * v[0]);
+                let zz = if D == 3 { s } else { 0.0 };
+                res.extend_from_slice(&[s, s, zz, 0.0, 0.0, 0.0]);
+            }
+            (2, 3) => res.extend_from_slice(&[v[0], v[1], 0.0, v[2], 0.0, 0.0]),
+            (3, 6) => res.extend_from_slice(&v),
+            _ => unreachable!(),
+        }
+    }
+    res
+}
+
+/// Description of a data array in a .vtu file
+#[derive(Clone, Debug)]
+struct VtuArray {
+    name: String,
+    type_name: &'static str,
+    n_comp: usize,
+}
+
+impl VtuArray {
+    /// Xml description (`tag` = "DataArray" or "PDataArray")
+    fn xml(&self, tag: &str) -> String {
+        format!(
+            "<{tag} type=\"{}\" Name=\"{}\" NumberOfComponents=\"{}\"",
+            self.type_name, self.name, self.n_comp
+        )
+    }
+}
+
+/// Data arrays stored in the `AppendedData` section
+struct AppendedData {
+    data: Vec<u8>,
+    xml: String,
+}
+
+impl AppendedData {
+    const fn new() -> Self {
+        Self {
+            data: Vec::new(),
+            xml: String::new(),
+        }
+    }
+
+    /// Add an array: the size (in bytes) is written as a UInt64 header
+    fn add<const N: usize, I: ExactSizeIterator<Item = [u8; N]>>(
+        &mut self,
+        arr: &VtuArray,
+        values: I,
+    ) {
+        let offset = self.data.len();
+        self.data
+            .extend_from_slice(&((N * values.len()) as u64).to_le_bytes());
+        values.for_each(|x| self.data.extend_from_slice(&x));
+        self.xml += &format!(
+            "        {} format=\"appended\" offset=\"{offset}\"/>\n",
+            arr.xml("DataArray")
+        );
+    }
+}
+
+/// Check the fields and get the number of components
+fn vtu_arrays(
+    data: Option<&HashMap<String, &[f64]>>,
+    n: usize,
+) -> Result<Vec<(VtuArray, Vec<f64>)>> {
+    let mut res = Vec::new();
+    if let Some(data) = data {
+        for (name, arr) in data {
+            if n == 0 || arr.len() % n != 0 {
+                return Err(Error::from(&format!("Invalid size for field {name}")));
+            }
+            res.push((
+                VtuArray {
+                    name: name.clone(),
+                    type_name: "Float64",
+                    n_comp: arr.len() / n,
+                },
+                arr.to_vec(),
+            ));
+        }
+    }
+    res.sort_by(|a, b| a.0.name.cmp(&b.0.name));
+    Ok(res)
+}
+
+/// Add the fields attached to the mesh (converted to f64) to `data`, the fields in `data`
+/// having priority
+fn with_mesh_fields<'a>(
+    data: Option<HashMap<String, &'a [f64]>>,
+    fields: &'a [(String, Vec<f64>)],
+) -> Option<HashMap<String, &'a [f64]>> {
+    if fields.is_empty() {
+        return data;
+    }
+    let mut res = data.unwrap_or_default();
+    for (name, v) in fields {
+        res.entry(name.clone()).or_insert(v.as_slice());
+    }
+    Some(res)
+}
+
+/// Restriction of fields to a subset of vertices / elements
+fn sub_arrays(arrays: &[(VtuArray, Vec<f64>)], ids: &[Idx]) -> Vec<(String, Vec<f64>)> {
+    arrays
+        .iter()
+        .map(|(a, v)| {
+            let n = a.n_comp;
+            let sub = ids
+                .iter()
+                .flat_map(|&i| v[n * i as usize..n * (i as usize + 1)].iter().copied())
+                .collect();
+            (a.name.clone(), sub)
+        })
+        .collect()
+}
+
+/// Attributes used by ParaView to pick the active scalars / vectors / tensors
+fn active_attributes(arrays: &[VtuArray]) -> String {
+    let mut res = String::new();
+    for (attr, n_comp) in [("Scalars", 1), ("Vectors", 3), ("Tensors", 6)] {
+        if let Some(arr) = arrays.iter().find(|a| a.n_comp == n_comp) {
+            res += &format!(" {attr}=\"{}\"", arr.name);
+        }
+    }
+    res
+}
+
+impl<const D: usize, E: Elem> SimplexMesh<D, E> {
+    /// Write the mesh and named vertex / element fields in an appended binary .vtu file
+    /// (VTK XML unstructured grid). The number of components of each field is deduced from its
+    /// size, and 6-component fields are written as symmetric tensors (see `metric_to_vtk_tensor`).
+    /// The element tags (and the partition if any) are written as the "tag" (and "partition")
+    /// element fields. The fields attached to the mesh (see `add_vertex_field`) are also
+    /// written, converted to Float64.
+    pub fn write_vtu(
+        &self,
+        file_name: &str,
+        point_data: Option<HashMap<String, &[f64]>>,
+        cell_data: Option<HashMap<String, &[f64]>>,
+    ) -> Result<()> {
+        debug!("Write {file_name}");
+        let vert_fields = self.vertex_fields().to_f64();
+        let point_data = with_mesh_fields(point_data, &vert_fields);
+        let elem_fields = self.elem_fields().to_f64();
+        let cell_data = with_mesh_fields(cell_data, &elem_fields);
+        self.write_vtu_arrays(file_name, point_data.as_ref(), cell_data.as_ref())?;
+        Ok(())
+    }
+
+    /// Write a .vtu file, returning the description of the vertex and element fields
+    fn write_vtu_arrays(
+        &self,
+        file_name: &str,
+        point_data: Option<&HashMap<String, &[f64]>>,
+        cell_data: Option<&HashMap<String, &[f64]>>,
+    ) -> Result<(Vec<VtuArray>, Vec<VtuArray>)> {
+        let n_verts = self.n_verts() as usize;
+        let n_elems = self.n_elems() as usize;
+
+        let point_data = vtu_arrays(point_data, n_verts)?;
+        let mut cell_data = vtu_arrays(cell_data, n_elems)?;
+        cell_data.retain(|(a, _)| a.name != "tag" && a.name != "partition");
+
+        let mut appended = AppendedData::new();
+        let mut xml = String::new();
+
+        // Vertex data
+        let point_arrays = point_data
+            .iter()
+            .map(|(a, _)| a.clone())
+            .collect::<Vec<_>>();
+        for (arr, values) in &point_data {
+            debug!("Write vertex data {}", arr.name);
+            appended.add(arr, values.iter().map(|x| x.to_le_bytes()));
+        }
+        xml += &format!(
+            "      <PointData{}>\n{}      </PointData>\n",
+            active_attributes(&point_arrays),
+            std::mem::take(&mut appended.xml)
+        );
+
+        // Element data
+        let tag = VtuArray {
+            name: String::from("tag"),
+            type_name: TAG_TYPE,
+            n_comp: 1,
+        };
+        appended.add(&tag, self.etags().map(|t| t.to_le_bytes()));
+        let mut cell_arrays = vec![tag];
+        if let Ok(partition) = self.get_partition() {
+            let arr = VtuArray {
+                name: String::from("partition"),
+                type_name: TAG_TYPE,
+                n_comp: 1,
+            };
+            appended.add(&arr, partition.iter().map(|t| t.to_le_bytes()));
+            cell_arrays.push(arr);
+        }
+        for (arr, values) in &cell_data {
+            debug!("Write element data {}", arr.name);
+            appended.add(arr, values.iter().map(|x| x.to_le_bytes()));
+            cell_arrays.push(arr.clone());
+        }
+        xml += &format!(
+            "      <CellData{}>\n{}      </CellData>\n",
+            active_attributes(&cell_arrays),
+            std::mem::take(&mut appended.xml)
+        );
+
+        // Vertices
+        let coords = VtuArray {
+            name: String::from("Points"),
+            type_name: "Float64",
+            n_comp: 3,
+        };
+        let mut values = Vec::with_capacity(3 * n_verts);
+        for p in self.verts() {
+            values.extend((0..3).map(|i| if i < D { p[i] } else { 0.0 }));
+        }
+        appended.add(&coords, values.iter().map(|x| x.to_le_bytes()));
+        xml += &format!(
+            "      <Points>\n{}      </Points>\n",
+            std::mem::take(&mut appended.xml)
+        );
+
+        // Elements
+        let (cell_type, perm) = vtk_cell_type(E::DIM, E::N_VERTS);
+        let connectivity = self
+            .elems()
+            .flat_map(|e| perm.iter().map(move |&k| u64::from(e[k]).to_le_bytes()))
+            .collect::<Vec<_>>();
+        appended.add(
+            &VtuArray {
+                name: String::from("connectivity"),
+                type_name: "UInt64",
+                n_comp: 1,
+            },
+            connectivity.into_iter(),
+        );
+        appended.add(
+            &VtuArray {
+                name: String::from("offsets"),
+                type_name: "UInt64",
+                n_comp: 1,
+            },
+            (0..n_elems).map(|i| (u64::from(E::N_VERTS) * (i as u64 + 1)).to_le_bytes()),
+        );
+        appended.add(
+            &VtuArray {
+                name: String::from("types"),
+                type_name: "UInt8",
+                n_comp: 1,
+            },
+            (0..n_elems).map(|_| [cell_type]),
+        );
+        xml += &format!(
+            "      <Cells>\n{}      </Cells>\n",
+            std::mem::take(&mut appended.xml)
+        );
+
+        let mut file = BufWriter::new(File::create(file_name)?);
+        writeln!(file, "<?xml version=\"1.0\"?>")?;
+        writeln!(file, "<VTKFile type=\"UnstructuredGrid\" version=\"1.0\" byte_order=\"LittleEndian\" header_type=\"UInt64\">")?;
+        writeln!(file, "  <UnstructuredGrid>")?;
+        writeln!(
+            file,
+            "    <Piece NumberOfPoints=\"{n_verts}\" NumberOfCells=\"{n_elems}\">"
+        )?;
+        write!(file, "{xml}")?;
+        writeln!(file, "    </Piece>")?;
+        writeln!(file, "  </UnstructuredGrid>")?;
+        writeln!(file, "  <AppendedData encoding=\"raw\">")?;
+        write!(file, "   _")?;
+        file.write_all(&appended.data)?;
+        writeln!(file)?;
+        writeln!(file, "  </AppendedData>")?;
+        writeln!(file, "</VTKFile>")?;
+        file.flush()?;
+
+        Ok((point_arrays, cell_arrays))
+    }
+
+    /// Write a partitioned mesh (see `partition`) as one .vtu file `{prefix}_{i}.vtu` per
+    /// partition and a `{prefix}.pvtu` master file. The fields are written as in `write_vtu`
+    pub fn write_pvtu(
+        &self,
+        prefix: &str,
+        point_data: Option<HashMap<String, &[f64]>>,
+        cell_data: Option<HashMap<String, &[f64]>>,
+    ) -> Result<()> {
+        debug!("Write {prefix}.pvtu");
+
+        self.get_partition()?;
+        let vert_fields = self.vertex_fields().to_f64();
+        let point_data = with_mesh_fields(point_data, &vert_fields);
+        let elem_fields = self.elem_fields().to_f64();
+        let cell_data = with_mesh_fields(cell_data, &elem_fields);
+        let point_data = vtu_arrays(point_data.as_ref(), self.n_verts() as usize)?;
+        let cell_data = vtu_arrays(cell_data.as_ref(), self.n_elems() as usize)?;
+
+        let base = Path::new(prefix)
+            .file_name()
+            .and_then(|s| s.to_str())
+            .ok_or_else(|| Error::from(&format!("Invalid prefix {prefix}")))?;
+
+        let mut pieces = String::new();
+        let mut arrays = (Vec::new(), Vec::new());
+        for (i, part) in self.extract_partitions(0).iter().enumerate() {
+            let point_data = sub_arrays(&point_data, &part.parent_vert_ids);
+            let point_data = point_data
+                .iter()
+                .map(|(n, v)| (n.clone(), v.as_slice()))
+                .collect::<HashMap<_, _>>();
+            let cell_data = sub_arrays(&cell_data, &part.parent_elem_ids);
+            let cell_data = cell_data
+                .iter()
+                .map(|(n, v)| (n.clone(), v.as_slice()))
+                .collect::<HashMap<_, _>>();
+            arrays = part.mesh.write_vtu_arrays(
+                &format!("{prefix}_{i}.vtu"),
+                Some(&point_data),
+                Some(&cell_data),
+            )?;
+            pieces += &format!("    <Piece Source=\"{base}_{i}.vtu\"/>\n");
+        }
+
+        let pxml = |arrays: &[VtuArray]| {
+            arrays
+                .iter()
+                .map(|a| format!("      {}/>\n", a.xml("PDataArray")))
+                .collect::<String>()
+        };
+
+        let mut file = BufWriter::new(File::create(format!("{prefix}.pvtu"))?);
+        writeln!(file, "<?xml version=\"1.0\"?>")?;
+        writeln!(file, "<VTKFile type=\"PUnstructuredGrid\" version=\"1.0\" byte_order=\"LittleEndian\" header_type=\"UInt64\">")?;
+        writeln!(file, "  <PUnstructuredGrid GhostLevel=\"0\">")?;
+        writeln!(file, "    <PPointData{}>", active_attributes(&arrays.0))?;
+        write!(file, "{}", pxml(&arrays.0))?;
+        writeln!(file, "    </PPointData>")?;
+        writeln!(file, "    <PCellData{}>", active_attributes(&arrays.1))?;
+        write!(file, "{}", pxml(&arrays.1))?;
+        writeln!(file, "    </PCellData>")?;
+        writeln!(file, "    <PPoints>")?;
+        writeln!(
+            file,
+            "      <PDataArray type=\"Float64\" Name=\"Points\" NumberOfComponents=\"3\"/>"
+        )?;
+        writeln!(file, "    </PPoints>")?;
+        write!(file, "{pieces}")?;
+        writeln!(file, "  </PUnstructuredGrid>")?;
+        writeln!(file, "</VTKFile>")?;
+        file.flush()?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::metric_to_vtk_tensor;
+    use crate::{
+        geometry::NoGeometry,
+        mesh::{
+            test_meshes::{test_mesh_2d, test_mesh_3d},
+            Elem, PartitionType,
+        },
+        metric::{AnisoMetric2d, AnisoMetric3d, IsoMetric, Metric},
+        Idx, Result, Tag,
+    };
+    use std::{collections::HashMap, mem::size_of};
+    use tempfile::NamedTempFile;
+
+    /// Read the arrays in the appended section of a .vtu file
+    fn read_appended(fname: &str) -> Vec<Vec<u8>> {
+        let data = std::fs::read(fname).unwrap();
+        let start = data.windows(5).position(|w| w == b"raw\">").unwrap();
+        let mut pos = start + data[start..].iter().position(|&c| c == b'_').unwrap() + 1;
+        let end = data.len() - "\n  </AppendedData>\n</VTKFile>\n".len();
+        let mut res = Vec::new();
+        while pos < end {
+            let n = u64::from_le_bytes(data[pos..pos + 8].try_into().unwrap()) as usize;
+            pos += 8;
+            res.push(data[pos..pos + n].to_vec());
+            pos += n;
+        }
+        assert_eq!(pos, end);
+        res
+    }
+
+    fn to_f64(data: &[u8]) -> Vec<f64> {
+        data.chunks(8)
+            .map(|x| f64::from_le_bytes(x.try_into().unwrap()))
+            .collect()
+    }
+
+    #[test]
+    fn test_vtu_2d() -> Result<()> {
+        let mesh = test_mesh_2d().split();
+        let n_verts = mesh.n_verts() as usize;
+        let n_elems = mesh.n_elems() as usize;
+
+        let u = mesh.verts().map(|p| p[0]).collect::<Vec<_>>();
+        let v = mesh
+            .verts()
+            .flat_map(|p| [p[0], p[1], 0.0])
+            .collect::<Vec<_>>();
+        let m = (0..n_verts)
+            .map(|_| AnisoMetric2d::from_slice(&[1.0, 2.0, 0.5]))
+            .collect::<Vec<_>>();
+        let [xx, yy, xy]: [f64; 3] = m[0].into_iter().collect::<Vec<_>>().try_into().unwrap();
+        let m = metric_to_vtk_tensor(&m);
+        let c = vec![1.0; n_elems];
+
+        let file = NamedTempFile::new().unwrap();
+        let fname = file.path().to_str().unwrap().to_owned() + ".vtu";
+        let mut point_data = HashMap::new();
+        point_data.insert(String::from("u"), u.as_slice());
+        point_data.insert(String::from("v"), v.as_slice());
+        point_data.insert(String::from("m"), m.as_slice());
+        let mut cell_data = HashMap::new();
+        cell_data.insert(String::from("c"), c.as_slice());
+        mesh.write_vtu(&fname, Some(point_data), Some(cell_data))?;
+
+        let xml = std::fs::read(&fname)?;
+        let xml = String::from_utf8_lossy(&xml);
+        assert!(xml.contains("<PointData Scalars=\"u\" Vectors=\"v\" Tensors=\"m\">"));
+        assert!(xml.contains("Name=\"m\" NumberOfComponents=\"6\""));
+
+        // m, u, v, tag, c, points, connectivity, offsets, types
+        let arrays = read_appended(&fname);
+        assert_eq!(arrays.len(), 9);
+        assert_eq!(to_f64(&arrays[0])[..6], [xx, yy, 0.0, xy, 0.0, 0.0]);
+        assert_eq!(to_f64(&arrays[1]), u);
+        assert_eq!(to_f64(&arrays[4]), c);
+        let coords = to_f64(&arrays[5]);
+        let v = mesh
+            .verts()
+            .flat_map(|p| [p[0], p[1], 0.0])
+            .collect::<Vec<_>>();
+        assert_eq!(coords, v);
+        assert_eq!(arrays[6].len(), 8 * 3 * n_elems);
+        assert_eq!(arrays[8], vec![5; n_elems]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_vtu_3d() -> Result<()> {
+        let mesh = test_mesh_3d().split();
+        let n_verts = mesh.n_verts() as usize;
+
+        let m = (0..n_verts)
+            .map(|_| AnisoMetric3d::from_slice(&[1.0, 2.0, 3.0, 0.1, 0.2, 0.3]))
+            .collect::<Vec<_>>();
+        let m0 = m[0].into_iter().collect::<Vec<_>>();
+        let m = metric_to_vtk_tensor(&m);
+        assert_eq!(m[..6], m0);
+        let h = metric_to_vtk_tensor(&[IsoMetric::<3>::from(0.5)]);
+        assert_eq!(h, [4.0, 4.0, 4.0, 0.0, 0.0, 0.0]);
+
+        let file = NamedTempFile::new().unwrap();
+        let fname = file.path().to_str().unwrap().to_owned() + ".vtu";
+        let mut point_data = HashMap::new();
+        point_data.insert(String::from("m"), m.as_slice());
+        mesh.write_vtu(&fname, Some(point_data), None)?;
+
+        let arrays = read_appended(&fname);
+        assert_eq!(arrays.len(), 6);
+        assert_eq!(arrays[5], vec![10; mesh.n_elems() as usize]);
+
+        // invalid size
+        let mut point_data = HashMap::new();
+        point_data.insert(String::from("m"), &m[1..]);
+        assert!(mesh.write_vtu(&fname, Some(point_data), None).is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_vtu_quadratic() -> Result<()> {
+        let mesh = test_mesh_3d().to_quadratic(&NoGeometry())?;
+        let n_elems = mesh.n_elems() as usize;
+
+        let file = NamedTempFile::new().unwrap();
+        let fname = file.path().to_str().unwrap().to_owned() + ".vtu";
+        mesh.write_vtu(&fname, None, None)?;
+
+        // tag, points, connectivity, offsets, types
+        let arrays = read_appended(&fname);
+        assert_eq!(arrays.len(), 5);
+        assert_eq!(arrays[4], vec![24; n_elems]);
+        let connectivity = arrays[2]
+            .chunks(8)
+            .map(|x| u64::from_le_bytes(x.try_into().unwrap()) as Idx)
+            .collect::<Vec<_>>();
+        assert_eq!(connectivity.len(), 10 * n_elems);
+        let e = mesh.elem(0);
+        for (i_edge, (j, k)) in [(0, 1), (1, 2), (0, 2), (0, 3), (1, 3), (2, 3)]
+            .into_iter()
+            .enumerate()
+        {
+            let mid = connectivity[4 + i_edge];
+            let p = 0.5 * (mesh.vert(connectivity[j]) + mesh.vert(connectivity[k]));
+            assert!((mesh.vert(mid) - p).norm() < 1e-12);
+            assert!(e.contains_vertex(mid));
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_pvtu() -> Result<()> {
+        let mut mesh = test_mesh_3d().split().split();
+        let u = mesh.verts().map(|p| p[0]).collect::<Vec<_>>();
+
+        let dir = tempfile::tempdir()?;
+        let prefix = dir.path().join("mesh");
+        let prefix = prefix.to_str().unwrap();
+
+        let mut point_data = HashMap::new();
+        point_data.insert(String::from("u"), u.as_slice());
+        assert!(mesh
+            .write_pvtu(prefix, Some(point_data.clone()), None)
+            .is_err());
+
+        mesh.partition(PartitionType::Hilbert(3))?;
+        mesh.write_pvtu(prefix, Some(point_data), None)?;
+
+        let pvtu = std::fs::read_to_string(format!("{prefix}.pvtu"))?;
+        assert!(pvtu.contains("<PDataArray type=\"Float64\" Name=\"u\" NumberOfComponents=\"1\"/>"));
+        assert!(pvtu.contains("Name=\"partition\""));
+        let mut n_elems = 0;
+        for i in 0..3_usize {
+            assert!(pvtu.contains(&format!("<Piece Source=\"mesh_{i}.vtu\"/>")));
+            let arrays = read_appended(&format!("{prefix}_{i}.vtu"));
+            // u, tag, partition, points, connectivity, offsets, types
+            assert_eq!(arrays.len(), 7);
+            let partition = arrays[2]
+                .chunks(size_of::<Tag>())
+                .map(|x| Tag::from_le_bytes(x.try_into().unwrap()))
+                .collect::<Vec<_>>();
+            assert!(partition.iter().all(|&t| t == i as Tag + 1));
+            n_elems += partition.len();
+            let u = to_f64(&arrays[0]);
+            let coords = to_f64(&arrays[3]);
+            assert!(u
+                .iter()
+                .zip(coords.chunks(3))
+                .all(|(&x, p)| (x - p[0]).abs() < 1e-12));
+        }
+        assert_eq!(n_elems, mesh.n_elems() as usize);
+        assert!(!dir.path().join("mesh_3.vtu").exists());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_vtu_mesh_fields() -> Result<()> {
+        let mut mesh = test_mesh_2d();
+        mesh.add_vertex_field::<i32, 1>("flag", vec![1, 2, 3, 4])?;
+        mesh.add_elem_field::<f64, 1>("w", vec![0.5, 1.5])?;
+
+        let file = NamedTempFile::new().unwrap();
+        let fname = file.path().to_str().unwrap().to_owned() + ".vtu";
+        let u = vec![1.0; 4];
+        let mut point_data = HashMap::new();
+        point_data.insert(String::from("flag"), u.as_slice());
+        mesh.write_vtu(&fname, Some(point_data), None)?;
+        let arrays = read_appended(&fname);
+        // flag, tag, w, points, connectivity, offsets, types
+        assert_eq!(arrays.len(), 7);
+        assert_eq!(to_f64(&arrays[0]), u);
+        assert_eq!(to_f64(&arrays[2]), vec![0.5, 1.5]);
+
+        mesh.write_vtu(&fname, None, None)?;
+        let arrays = read_appended(&fname);
+        assert_eq!(arrays.len(), 7);
+        assert_eq!(to_f64(&arrays[0]), vec![1.0, 2.0, 3.0, 4.0]);
+
+        Ok(())
+    }
+}
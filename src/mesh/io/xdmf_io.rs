@@ -0,0 +1,301 @@
+use crate::{
+    mesh::{Elem, SimplexMesh},
+    Error, Result,
+};
+use std::collections::HashMap;
+
+#[cfg(feature = "hdf5")]
+use crate::{mesh::Point, Idx, Tag};
+#[cfg(feature = "hdf5")]
+use log::debug;
+#[cfg(feature = "hdf5")]
+use std::{fmt::Write as _, mem::size_of, path::Path};
+
+/// XDMF attribute type for fields with `n_comp` components
+#[cfg(feature = "hdf5")]
+fn xdmf_attribute_type(n_comp: usize) -> &'static str {
+    match n_comp {
+        1 => "Scalar",
+        3 => "Vector",
+        6 => "Tensor6",
+        9 => "Tensor",
+        _ => "Matrix",
+    }
+}
+
+/// XDMF `DataItem` referring to dataset `name` in `h5_file`
+#[cfg(feature = "hdf5")]
+fn xdmf_data_item(
+    h5_file: &str,
+    name: &str,
+    dims: &[usize],
+    number_type: &str,
+    precision: usize,
+) -> String {
+    let dims = dims
+        .iter()
+        .map(ToString::to_string)
+        .collect::<Vec<_>>()
+        .join(" ");
+    format!(
+        "<DataItem Dimensions=\"{dims}\" NumberType=\"{number_type}\" Precision=\"{precision}\" Format=\"HDF\">{h5_file}:/{name}</DataItem>"
+    )
+}
+
+impl<const D: usize, E: Elem> SimplexMesh<D, E> {
+    #[cfg(not(feature = "hdf5"))]
+    pub fn write_xdmf(
+        &self,
+        _file_name: &str,
+        _vertex_data: Option<HashMap<String, &[f64]>>,
+    ) -> Result<()> {
+        Err(Error::from("the hdf5 feature is not enabled"))
+    }
+
+    /// Write the mesh and vertex fields in a HDF5 file `{file_name}.h5` (datasets `coords`,
+    /// `elems`, `etags`, `faces`, `ftags` and `fields/{name}`), together with a XDMF descriptor
+    /// `{file_name}.xdmf` with two grids (elements and faces) that can be read by ParaView
+    #[cfg(feature = "hdf5")]
+    pub fn write_xdmf(
+        &self,
+        file_name: &str,
+        vertex_data: Option<HashMap<String, &[f64]>>,
+    ) -> Result<()> {
+        debug!("Write {file_name}.h5 and {file_name}.xdmf");
+
+        let n_verts = self.n_verts() as usize;
+        let n_elems = self.n_elems() as usize;
+        let n_faces = self.n_faces() as usize;
+
+        let mut fields = Vec::new();
+        if let Some(vertex_data) = vertex_data {
+            for (name, arr) in vertex_data {
+                if n_verts == 0 || arr.len() % n_verts != 0 {
+                    return Err(Error::from(&format!("Invalid size for field {name}")));
+                }
+                fields.push((name, arr.len() / n_verts, arr));
+            }
+        }
+        fields.sort_by(|a, b| a.0.cmp(&b.0));
+
+        let h5_name = format!("{file_name}.h5");
+        let file = hdf5::File::create(&h5_name)?;
+
+        let mut coords = Vec::with_capacity(D * n_verts);
+        self.verts().for_each(|p| coords.extend(p.iter()));
+        file.new_dataset::<f64>()
+            .shape([n_verts, D])
+            .create("coords")?
+            .write_raw(&coords)?;
+        file.new_dataset::<Idx>()
+            .shape([n_elems, E::N_VERTS as usize])
+            .create("elems")?
+            .write_raw(&self.elems().flatten().collect::<Vec<_>>())?;
+        file.new_dataset::<Tag>()
+            .shape([n_elems])
+            .create("etags")?
+            .write_raw(&self.etags().collect::<Vec<_>>())?;
+        file.new_dataset::<Idx>()
+            .shape([n_faces, E::Face::N_VERTS as usize])
+            .create("faces")?
+            .write_raw(&self.faces().flatten().collect::<Vec<_>>())?;
+        file.new_dataset::<Tag>()
+            .shape([n_faces])
+            .create("ftags")?
+            .write_raw(&self.ftags().collect::<Vec<_>>())?;
+        let group = file.create_group("fields")?;
+        for (name, n_comp, arr) in &fields {
+            group
+                .new_dataset::<f64>()
+                .shape([n_verts, *n_comp])
+                .create(name.as_str())?
+                .write_raw(*arr)?;
+        }
+
+        // The .h5 file is referred to relatively to the .xdmf file
+        let h5_ref = Path::new(&h5_name)
+            .file_name()
+            .and_then(|s| s.to_str())
+            .ok_or_else(|| Error::from(&format!("Invalid file name {file_name}")))?
+            .to_string();
+        let geometry_type = if D == 3 { "XYZ" } else { "XY" };
+        let tag_size = size_of::<Tag>();
+
+        let mut xml = String::new();
+        writeln!(xml, "<?xml version=\"1.0\" ?>")?;
+        writeln!(xml, "<Xdmf Version=\"3.0\">")?;
+        writeln!(xml, "  <Domain>")?;
+        for (grid, elem_name, n, m, conn, tags) in [
+            ("Elements", E::NAME, n_elems, E::N_VERTS, "elems", "etags"),
+            (
+                "Faces",
+                E::Face::NAME,
+                n_faces,
+                E::Face::N_VERTS,
+                "faces",
+                "ftags",
+            ),
+        ] {
+            writeln!(xml, "    <Grid Name=\"{grid}\" GridType=\"Uniform\">")?;
+            writeln!(
+                xml,
+                "      <Topology TopologyType=\"{elem_name}\" NumberOfElements=\"{n}\">"
+            )?;
+            writeln!(
+                xml,
+                "        {}",
+                xdmf_data_item(&h5_ref, conn, &[n, m as usize], "UInt", size_of::<Idx>())
+            )?;
+            writeln!(xml, "      </Topology>")?;
+            writeln!(xml, "      <Geometry GeometryType=\"{geometry_type}\">")?;
+            writeln!(
+                xml,
+                "        {}",
+                xdmf_data_item(&h5_ref, "coords", &[n_verts, D], "Float", 8)
+            )?;
+            writeln!(xml, "      </Geometry>")?;
+            writeln!(
+                xml,
+                "      <Attribute Name=\"tag\" AttributeType=\"Scalar\" Center=\"Cell\">"
+            )?;
+            writeln!(
+                xml,
+                "        {}",
+                xdmf_data_item(&h5_ref, tags, &[n], "Int", tag_size)
+            )?;
+            writeln!(xml, "      </Attribute>")?;
+            for (name, n_comp, _) in &fields {
+                writeln!(
+                    xml,
+                    "      <Attribute Name=\"{name}\" AttributeType=\"{}\" Center=\"Node\">",
+                    xdmf_attribute_type(*n_comp)
+                )?;
+                writeln!(
+                    xml,
+                    "        {}",
+                    xdmf_data_item(
+                        &h5_ref,
+                        &format!("fields/{name}"),
+                        &[n_verts, *n_comp],
+                        "Float",
+                        8
+                    )
+                )?;
+                writeln!(xml, "      </Attribute>")?;
+            }
+            writeln!(xml, "    </Grid>")?;
+        }
+        writeln!(xml, "  </Domain>")?;
+        writeln!(xml, "</Xdmf>")?;
+        std::fs::write(format!("{file_name}.xdmf"), xml)?;
+
+        Ok(())
+    }
+
+    #[cfg(not(feature = "hdf5"))]
+    pub fn read_hdf5(_file_name: &str) -> Result<(Self, HashMap<String, Vec<f64>>)> {
+        Err(Error::from("the hdf5 feature is not enabled"))
+    }
+
+    /// Read a mesh and its vertex fields from a HDF5 file written by `write_xdmf` (e.g. to
+    /// restart a computation)
+    #[cfg(feature = "hdf5")]
+    pub fn read_hdf5(file_name: &str) -> Result<(Self, HashMap<String, Vec<f64>>)> {
+        debug!("Read {file_name}");
+
+        let file = hdf5::File::open(file_name)?;
+
+        let coords = file.dataset("coords")?;
+        if coords.shape().get(1) != Some(&D) {
+            return Err(Error::from("invalid dimension"));
+        }
+        let verts = coords
+            .read_raw::<f64>()?
+            .chunks(D)
+            .map(Point::<D>::from_column_slice)
+            .collect::<Vec<_>>();
+        let elems = file
+            .dataset("elems")?
+            .read_raw::<Idx>()?
+            .chunks(E::N_VERTS as usize)
+            .map(E::from_slice)
+            .collect::<Vec<_>>();
+        let etags = file.dataset("etags")?.read_raw::<Tag>()?;
+        let faces = file
+            .dataset("faces")?
+            .read_raw::<Idx>()?
+            .chunks(E::Face::N_VERTS as usize)
+            .map(E::Face::from_slice)
+            .collect::<Vec<_>>();
+        let ftags = file.dataset("ftags")?.read_raw::<Tag>()?;
+        if etags.len() != elems.len() || ftags.len() != faces.len() {
+            return Err(Error::from("invalid tag size"));
+        }
+
+        let mut fields = HashMap::new();
+        if let Ok(group) = file.group("fields") {
+            for name in group.member_names()? {
+                let arr = group.dataset(&name)?.read_raw::<f64>()?;
+                fields.insert(name, arr);
+            }
+        }
+
+        Ok((Self::new(verts, elems, etags, faces, ftags), fields))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::mesh::{test_meshes::test_mesh_3d, SimplexMesh, Tetrahedron};
+
+    #[cfg(not(feature = "hdf5"))]
+    #[test]
+    fn test_hdf5_disabled() {
+        let mesh = test_mesh_3d();
+        assert!(mesh.write_xdmf("mesh", None).is_err());
+        assert!(SimplexMesh::<3, Tetrahedron>::read_hdf5("mesh.h5").is_err());
+    }
+
+    #[cfg(feature = "hdf5")]
+    #[test]
+    fn test_xdmf() -> crate::Result<()> {
+        use std::collections::HashMap;
+
+        let mesh = test_mesh_3d().split();
+        let u = mesh.verts().map(|p| p[0]).collect::<Vec<_>>();
+        let v = mesh
+            .verts()
+            .flat_map(|p| [p[1], p[2], p[0]])
+            .collect::<Vec<_>>();
+
+        let dir = tempfile::tempdir()?;
+        let fname = dir.path().join("mesh");
+        let fname = fname.to_str().unwrap();
+        let mut vertex_data = HashMap::new();
+        vertex_data.insert(String::from("u"), u.as_slice());
+        vertex_data.insert(String::from("v"), v.as_slice());
+        mesh.write_xdmf(fname, Some(vertex_data))?;
+
+        let xdmf = std::fs::read_to_string(format!("{fname}.xdmf"))?;
+        assert!(xdmf.contains("TopologyType=\"Tetrahedron\""));
+        assert!(xdmf.contains("TopologyType=\"Triangle\""));
+        assert!(xdmf.contains("mesh.h5:/fields/v"));
+        assert!(xdmf.contains("AttributeType=\"Vector\""));
+
+        let (mesh2, fields) = SimplexMesh::<3, Tetrahedron>::read_hdf5(&format!("{fname}.h5"))?;
+        assert_eq!(mesh.n_verts(), mesh2.n_verts());
+        assert_eq!(mesh.n_elems(), mesh2.n_elems());
+        assert_eq!(mesh.n_faces(), mesh2.n_faces());
+        for (p0, p1) in mesh.verts().zip(mesh2.verts()) {
+            assert!((p0 - p1).norm() < 1e-12);
+        }
+        assert!(mesh.elems().zip(mesh2.elems()).all(|(e0, e1)| e0 == e1));
+        assert!(mesh.etags().zip(mesh2.etags()).all(|(t0, t1)| t0 == t1));
+        assert!(mesh.ftags().zip(mesh2.ftags()).all(|(t0, t1)| t0 == t1));
+        assert_eq!(fields.len(), 2);
+        assert_eq!(fields["u"], u);
+        assert_eq!(fields["v"], v);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,177 @@
+use super::{graph::CSRGraph, twovec};
+use crate::{
+    mesh::{Elem, Point, SimplexMesh},
+    Idx, Tag, TopoTag,
+};
+use serde::Serialize;
+use std::{fmt, mem::size_of};
+
+/// Approximate memory used by a hash map with `n` entries, in bytes (one control byte per
+/// entry is added to the key and value sizes)
+pub(crate) const fn hash_map_bytes<K, V>(n: usize) -> usize {
+    n * (size_of::<K>() + size_of::<V>() + 1)
+}
+
+/// Memory used by the arrays, cached connectivities and fields of a `SimplexMesh`, in bytes.
+/// The connectivities that are not computed use no memory
+#[derive(Clone, Debug, Default, Serialize)]
+pub struct MeshMemoryUsage {
+    /// Vertex coordinates
+    pub verts: usize,
+    /// Element connectivity and tags
+    pub elems: usize,
+    /// Face connectivity and tags
+    pub faces: usize,
+    /// Face-to-element connectivity
+    pub face_to_elems: usize,
+    /// Vertex-to-element connectivity
+    pub vertex_to_elems: usize,
+    /// Element-to-element connectivity
+    pub elem_to_elems: usize,
+    /// Edges
+    pub edges: usize,
+    /// Vertex-to-edge connectivity
+    pub vertex_to_edges: usize,
+    /// Vertex-to-vertex connectivity
+    pub vertex_to_vertices: usize,
+    /// Element and vertex volumes
+    pub volumes: usize,
+    /// Vertex tags
+    pub vertex_tags: usize,
+    /// Element and vertex partitions
+    pub partition: usize,
+    /// Vertex and element fields
+    pub fields: usize,
+}
+
+impl MeshMemoryUsage {
+    /// Get the total memory used, in bytes
+    #[must_use]
+    pub const fn total(&self) -> usize {
+        self.verts
+            + self.elems
+            + self.faces
+            + self.face_to_elems
+            + self.vertex_to_elems
+            + self.elem_to_elems
+            + self.edges
+            + self.vertex_to_edges
+            + self.vertex_to_vertices
+            + self.volumes
+            + self.vertex_tags
+            + self.partition
+            + self.fields
+    }
+}
+
+impl fmt::Display for MeshMemoryUsage {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "total: {:.1} MB", self.total() as f64 / 1e6)?;
+        for (name, bytes) in [
+            ("vertices", self.verts),
+            ("elements", self.elems),
+            ("faces", self.faces),
+            ("face_to_elems", self.face_to_elems),
+            ("vertex_to_elems", self.vertex_to_elems),
+            ("elem_to_elems", self.elem_to_elems),
+            ("edges", self.edges),
+            ("vertex_to_edges", self.vertex_to_edges),
+            ("vertex_to_vertices", self.vertex_to_vertices),
+            ("volumes", self.volumes),
+            ("vertex_tags", self.vertex_tags),
+            ("partition", self.partition),
+            ("fields", self.fields),
+        ] {
+            if bytes > 0 {
+                writeln!(f, "  {name}: {:.1} MB", bytes as f64 / 1e6)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl<const D: usize, E: Elem> SimplexMesh<D, E> {
+    /// Get the memory used by the mesh, per array / cached connectivity, e.g. to decide which
+    /// connectivities to clear (`clear_elem_to_elems()`, ...) when adapting large meshes.
+    /// The memory used by the hash maps is approximate
+    #[must_use]
+    pub fn memory_usage(&self) -> MeshMemoryUsage {
+        let n_verts = self.n_verts() as usize;
+        let n_elems = self.n_elems() as usize;
+        let n_faces = self.n_faces() as usize;
+
+        MeshMemoryUsage {
+            verts: n_verts * size_of::<Point<D>>(),
+            elems: n_elems * (size_of::<E>() + size_of::<Tag>()),
+            faces: n_faces * (size_of::<E::Face>() + size_of::<Tag>()),
+            face_to_elems: self.get_face_to_elems().map_or(0, |f2e| {
+                hash_map_bytes::<E::Face, twovec::Vec<Idx>>(f2e.capacity())
+            }),
+            vertex_to_elems: self.get_vertex_to_elems().map_or(0, CSRGraph::memory_usage),
+            elem_to_elems: self.get_elem_to_elems().map_or(0, CSRGraph::memory_usage),
+            edges: self
+                .get_edges()
+                .map_or(0, |e| e.len() * size_of::<[Idx; 2]>()),
+            vertex_to_edges: self.get_vertex_to_edges().map_or(0, CSRGraph::memory_usage),
+            vertex_to_vertices: self
+                .get_vertex_to_vertices()
+                .map_or(0, CSRGraph::memory_usage),
+            volumes: self
+                .get_elem_volumes()
+                .map_or(0, |v| v.len() * size_of::<f64>())
+                + self
+                    .get_vertex_volumes()
+                    .map_or(0, |v| v.len() * size_of::<f64>()),
+            vertex_tags: self
+                .get_vertex_tags()
+                .map_or(0, |t| t.len() * size_of::<TopoTag>()),
+            partition: self
+                .get_partition()
+                .map_or(0, |p| p.len() * size_of::<Tag>())
+                + self
+                    .get_vertex_partition()
+                    .map_or(0, |p| p.len() * size_of::<Tag>()),
+            fields: self.vertex_fields().memory_usage() + self.elem_fields().memory_usage(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        mesh::{test_meshes::test_mesh_3d, Point, Tetrahedron},
+        Tag,
+    };
+    use std::mem::size_of;
+
+    #[test]
+    fn test_memory_usage() {
+        let mut mesh = test_mesh_3d().split();
+        let usage = mesh.memory_usage();
+        assert_eq!(usage.verts, mesh.n_verts() as usize * size_of::<Point<3>>());
+        assert_eq!(
+            usage.elems,
+            mesh.n_elems() as usize * (size_of::<Tetrahedron>() + size_of::<Tag>())
+        );
+        assert_eq!(usage.face_to_elems, 0);
+        assert_eq!(usage.vertex_to_elems, 0);
+        assert_eq!(usage.fields, 0);
+        let total = usage.total();
+
+        mesh.compute_face_to_elems();
+        mesh.compute_vertex_to_elems();
+        mesh.compute_volumes();
+        let usage = mesh.memory_usage();
+        assert!(usage.face_to_elems > 0);
+        assert!(usage.vertex_to_elems > 4 * mesh.n_elems() as usize);
+        assert_eq!(
+            usage.volumes,
+            (mesh.n_elems() + mesh.n_verts()) as usize * size_of::<f64>()
+        );
+        assert!(usage.total() > total);
+        assert!(format!("{usage}").contains("vertex_to_elems"));
+
+        mesh.clear_vertex_to_elems();
+        assert_eq!(mesh.memory_usage().vertex_to_elems, 0);
+    }
+}
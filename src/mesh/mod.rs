@@ -1,26 +1,66 @@
 mod autotag;
+mod boundary_layer;
+mod components;
+mod conservative;
+mod cut;
+mod distance;
+mod edges;
+mod element_tree;
+mod error_estimate;
+mod extrude;
+mod features;
+mod fields;
 mod geom_elems;
 mod gradient_l2proj;
 mod gradient_ls;
 mod graph;
+mod hybrid;
 mod interpolate;
+mod intersections;
+mod memory;
 mod ordering;
 mod partition;
+mod predicates;
+mod quadratic;
+mod quadrature;
+mod quality;
+mod recovery;
 #[allow(dead_code)]
 #[allow(clippy::enum_variant_names)]
 mod simplex_mesh;
 mod split;
+mod tag_names;
 mod to_simplices;
 mod topo_elems;
 mod topology;
+mod topology_report;
+mod transform;
 mod twovec;
+mod untangle;
+mod validity;
 mod vector;
 
 pub mod io;
 pub mod test_meshes;
 
-pub use geom_elems::{AsSliceF64, GEdge, GElem, GTetrahedron, GTriangle};
-pub use partition::PartitionType;
+pub use element_tree::{BCoords, ElementTree};
+pub use fields::{FieldInterpolation, FieldValue, Fields};
+pub use geom_elems::{
+    AsSliceF64, GEdge, GEdgeQuadratique, GElem, GTetrahedron, GTetrahedronQuadratique, GTriangle,
+    GTriangleQuadratique,
+};
+pub use hybrid::{HybridMapping, HybridMesh};
+pub(crate) use memory::hash_map_bytes;
+pub use memory::MeshMemoryUsage;
+pub use partition::{PartitionStats, PartitionType};
+pub use quality::{ElemQuality, QualityHistogram, QualityMetric};
+pub use recovery::{GradientRecovery, HessianRecovery};
 pub use simplex_mesh::{Point, SimplexMesh, SubSimplexMesh};
-pub use topo_elems::{get_face_to_elem, Edge, Elem, Tetrahedron, Triangle};
+pub use tag_names::TagNames;
+pub use topo_elems::{
+    get_face_to_elem, par_get_face_to_elem, Edge, EdgeQuadratique, Elem, Tetrahedron,
+    TetrahedronQuadratique, Triangle, TriangleQuadratique,
+};
 pub use topology::Topology;
+pub use topology_report::TopologyReport;
+pub use validity::MeshViolation;
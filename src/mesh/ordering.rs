@@ -1,5 +1,5 @@
 use crate::{
-    mesh::{Elem, Point, SimplexMesh},
+    mesh::{graph::CSRGraph, Elem, Point, SimplexMesh},
     Idx,
 };
 use lindel::Lineariseable;
@@ -40,11 +40,76 @@ pub fn hilbert_indices<const D: usize, I: ExactSizeIterator<Item = Point<D>>>(
     get_indices(n, |i| hilbert_ids[i])
 }
 
+/// Breadth-first search of graph `g` from vertex `start`, visiting the neighbors by increasing
+/// degree. The vertices found are marked in `visited`, and the vertices sorted by visit order
+/// and the index of the first vertex of the last level are returned
+fn bfs(g: &CSRGraph, start: usize, visited: &mut [bool]) -> (Vec<usize>, usize) {
+    let n = g.n() as usize;
+    let neighbors = |i: usize| if i < n { g.row(i as Idx) } else { &[] };
+
+    let mut order = vec![start];
+    visited[start] = true;
+    let (mut head, mut level_end, mut last_level) = (0, 1, 0);
+    while head < order.len() {
+        if head == level_end {
+            last_level = head;
+            level_end = order.len();
+        }
+        let i = order[head];
+        head += 1;
+        let mut next = neighbors(i)
+            .iter()
+            .map(|&j| j as usize)
+            .filter(|&j| !visited[j])
+            .collect::<Vec<_>>();
+        next.sort_by_key(|&j| (neighbors(j).len(), j));
+        for j in next {
+            visited[j] = true;
+            order.push(j);
+        }
+    }
+
+    (order, last_level)
+}
+
+/// Reverse Cuthill-McKee ordering of the `n` vertices of graph `g`: each connected component is
+/// numbered by a breadth-first search starting from a pseudo-peripheral vertex (a vertex with
+/// minimum degree in the last level of a first search), and the ordering is reversed
+fn rcm_indices(g: &CSRGraph, n: usize) -> Vec<Idx> {
+    let degree = |i: usize| {
+        if i < g.n() as usize {
+            g.row(i as Idx).len()
+        } else {
+            0
+        }
+    };
+
+    let mut order = Vec::with_capacity(n);
+    let mut visited = vec![false; n];
+    for i in 0..n {
+        if visited[i] {
+            continue;
+        }
+        let (component, last_level) = bfs(g, i, &mut visited);
+        let start = *component[last_level..]
+            .iter()
+            .min_by_key(|&&j| (degree(j), j))
+            .unwrap();
+        component.iter().for_each(|&j| visited[j] = false);
+        order.extend(bfs(g, start, &mut visited).0);
+    }
+
+    let mut new_indices = vec![0; n];
+    for (k, &i) in order.iter().rev().enumerate() {
+        new_indices[i] = k as Idx;
+    }
+    new_indices
+}
+
 impl<const D: usize, E: Elem> SimplexMesh<D, E> {
     /// Reorder the mesh vertices
-    /// Vertex data is updated accordingly, but edges, vertex-to-vertex connections
+    /// Vertex data (including the vertex fields) is updated accordingly, but edges, vertex-to-vertex connections
     /// and vertex volumes are reset to None
-    /// TODO: update the fields instead of setting them to None and avoid recomputing
     pub fn reorder_vertices(&mut self, new_indices: &[Idx]) {
         debug!("Reordering the vertices");
 
@@ -63,6 +128,20 @@ impl<const D: usize, E: Elem> SimplexMesh<D, E> {
             .for_each(|e| *e = E::from_iter(e.iter().map(|&i| new_indices[i as usize])));
         self.mut_faces()
             .for_each(|f| *f = E::Face::from_iter(f.iter().map(|&i| new_indices[i as usize])));
+        if let Ok(partition) = self.get_vertex_partition() {
+            let mut new_partition = vec![0; n];
+            for (i_old, &i_new) in new_indices.iter().enumerate() {
+                new_partition[i_new as usize] = partition[i_old];
+            }
+            self.set_vertex_partition(new_partition).unwrap();
+        }
+        if !self.vertex_fields().is_empty() {
+            let mut old_indices = vec![0; n];
+            for (i_old, &i_new) in new_indices.iter().enumerate() {
+                old_indices[i_new as usize] = i_old as Idx;
+            }
+            *self.vertex_fields_mut() = self.vertex_fields().select(&old_indices);
+        }
 
         self.clear_vertex_to_elems();
         self.clear_vertex_to_vertices();
@@ -71,9 +150,8 @@ impl<const D: usize, E: Elem> SimplexMesh<D, E> {
     }
 
     /// Reorder the mesh elements
-    /// Element data is updated accordingly, face-to-element and element-to-element connections
+    /// Element data (including the element fields) is updated accordingly, face-to-element and element-to-element connections
     /// and element volumes are reset to None
-    /// TODO: update the fields instead of setting them to None and avoid recomputing
     pub fn reorder_elems(&mut self, new_indices: &[Idx]) {
         debug!("Reordering the elements");
         let n = self.n_elems() as usize;
@@ -91,6 +169,20 @@ impl<const D: usize, E: Elem> SimplexMesh<D, E> {
         self.mut_etags()
             .zip(etags.iter())
             .for_each(|(t0, t1)| *t0 = *t1);
+        if let Ok(partition) = self.get_partition() {
+            let mut new_partition = vec![0; n];
+            for (i_old, i_new) in new_indices.iter().copied().enumerate() {
+                new_partition[i_new as usize] = partition[i_old];
+            }
+            self.set_partition(new_partition).unwrap();
+        }
+        if !self.elem_fields().is_empty() {
+            let mut old_indices = vec![0; n];
+            for (i_old, i_new) in new_indices.iter().copied().enumerate() {
+                old_indices[i_new as usize] = i_old as Idx;
+            }
+            *self.elem_fields_mut() = self.elem_fields().select(&old_indices);
+        }
 
         self.clear_face_to_elems();
         self.clear_elem_to_elems();
@@ -147,6 +239,28 @@ impl<const D: usize, E: Elem> SimplexMesh<D, E> {
         (new_vert_indices, new_elem_indices, new_face_indices)
     }
 
+    /// Reorder the vertices using the reverse Cuthill-McKee algorithm to reduce the bandwidth
+    /// of the vertex-to-vertex connectivity, and the elements and faces using their minimum
+    /// vertex Id. The vertex and element fields are reordered accordingly (see
+    /// `reorder_vertices` and `reorder_elems`). The new vertex, element and face indices are
+    /// returned
+    pub fn reorder_vertices_rcm(&mut self) -> (Vec<Idx>, Vec<Idx>, Vec<Idx>) {
+        debug!("Reordering the vertices / elements / faces (RCM)");
+
+        if self.get_vertex_to_vertices().is_err() {
+            self.compute_vertex_to_vertices();
+        }
+        let new_vert_indices = rcm_indices(
+            self.get_vertex_to_vertices().unwrap(),
+            self.n_verts() as usize,
+        );
+        self.reorder_vertices(&new_vert_indices);
+
+        let (new_elem_indices, new_face_indices) = self.reorder_elems_and_faces();
+
+        (new_vert_indices, new_elem_indices, new_face_indices)
+    }
+
     /// Reorder faces and elements to have increasing minimum vertex indices
     pub fn reorder_elems_and_faces(&mut self) -> (Vec<Idx>, Vec<Idx>) {
         debug!("Reordering the elements / faces based on their minimum vertex Id");
@@ -171,7 +285,7 @@ mod tests {
             test_meshes::{test_mesh_2d, test_mesh_3d},
             Elem, GElem, SimplexMesh,
         },
-        Idx,
+        Idx, Result,
     };
     use rand::{prelude::SliceRandom, rngs::StdRng, SeedableRng};
 
@@ -284,6 +398,36 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_reorder_fields_2d() -> Result<()> {
+        let mut mesh = test_mesh_2d().split().split().split();
+
+        let f = mesh.verts().map(|p| p[0]).collect::<Vec<_>>();
+        mesh.add_vertex_field::<f64, 1>("x", f)?;
+        let f = mesh.gelems().map(|ge| ge.center()[0]).collect::<Vec<_>>();
+        mesh.add_elem_field::<f64, 1>("x", f)?;
+
+        // Random reordering
+        let mut rng = StdRng::seed_from_u64(123);
+        let mut new_vert_indices: Vec<Idx> = (0..mesh.n_verts()).collect();
+        new_vert_indices.shuffle(&mut rng);
+        mesh.reorder_vertices(&new_vert_indices);
+        let mut new_elem_indices: Vec<Idx> = (0..mesh.n_elems()).collect();
+        new_elem_indices.shuffle(&mut rng);
+        mesh.reorder_elems(&new_elem_indices);
+
+        let f = mesh.get_vertex_field::<f64, 1>("x")?;
+        for (p, &x) in mesh.verts().zip(f) {
+            assert!(f64::abs(p[0] - x) < 1e-10);
+        }
+        let f = mesh.get_elem_field::<f64, 1>("x")?;
+        for (ge, &x) in mesh.gelems().zip(f) {
+            assert!(f64::abs(ge.center()[0] - x) < 1e-10);
+        }
+
+        Ok(())
+    }
+
     #[test]
     fn test_reorder_faces_2d() {
         let mesh = test_mesh_2d();
@@ -341,7 +485,7 @@ mod tests {
             let e = mesh.elem(i_elem);
             mean += e.iter().max().unwrap() - e.iter().min().unwrap();
         }
-        f64::from(mean) / f64::from(mesh.n_elems())
+        mean as f64 / mesh.n_elems() as f64
     }
 
     fn mean_bandwidth_e2e<const D: usize, E: Elem>(mesh: &SimplexMesh<D, E>) -> f64 {
@@ -355,7 +499,7 @@ mod tests {
                 .max()
                 .unwrap();
         }
-        f64::from(mean) / f64::from(mesh.n_elems())
+        mean as f64 / mesh.n_elems() as f64
     }
 
     #[test]
@@ -393,4 +537,32 @@ mod tests {
         assert!(mean_e2v_after < 0.5 * mean_e2v_before);
         assert!(mean_e2e_after < 1.1 * mean_e2e_before);
     }
+
+    fn bandwidth<const D: usize, E: Elem>(mesh: &SimplexMesh<D, E>) -> Idx {
+        mesh.elems()
+            .map(|e| e.iter().max().unwrap() - e.iter().min().unwrap())
+            .max()
+            .unwrap()
+    }
+
+    #[test]
+    fn test_reorder_rcm() {
+        let mut mesh = test_mesh_3d().split().split().split();
+        mesh.add_boundary_faces();
+
+        let mut new_vert_indices: Vec<Idx> = (0..mesh.n_verts()).collect();
+        let mut rng = StdRng::seed_from_u64(123);
+        new_vert_indices.shuffle(&mut rng);
+        mesh.reorder_vertices(&new_vert_indices);
+        let bw = bandwidth(&mesh);
+
+        let f: Vec<f64> = mesh.verts().map(|p| p[0]).collect();
+        let (new_vert_indices, _, _) = mesh.reorder_vertices_rcm();
+        assert!(2 * bandwidth(&mesh) < bw);
+        assert!(mesh.validate().is_empty());
+
+        for (i_old, &i_new) in new_vert_indices.iter().enumerate() {
+            assert!((mesh.vert(i_new)[0] - f[i_old]).abs() < 1e-10);
+        }
+    }
 }
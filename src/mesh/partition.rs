@@ -1,26 +1,103 @@
 use crate::{
-    mesh::{ordering::hilbert_indices, Elem, GElem, SimplexMesh},
-    Idx, Result, Tag,
+    mesh::{
+        get_face_to_elem, graph::CSRGraph, ordering::hilbert_indices, twovec, Elem, GElem, Point,
+        SimplexMesh, SubSimplexMesh,
+    },
+    spatialindex::PointIndex,
+    Error, Idx, Result, Tag,
 };
 use log::{debug, warn};
+use rayon::prelude::*;
+use rustc_hash::{FxHashMap, FxHashSet};
+use serde::Serialize;
+use std::fmt;
 
 #[allow(dead_code)]
 #[derive(Clone, Copy, Debug)]
 pub enum PartitionType {
     Hilbert(Idx),
+    Rcb(Idx),
     Scotch(Idx),
     MetisRecursive(Idx),
     MetisKWay(Idx),
     None,
 }
 
+/// Statistics on a mesh partition
+#[derive(Clone, Debug, Serialize)]
+pub struct PartitionStats {
+    /// The partition ids
+    pub parts: Vec<Tag>,
+    /// The # of faces between elements in different partitions
+    pub edge_cut: Idx,
+    /// The # of elements in each partition
+    pub n_elems: Vec<Idx>,
+    /// The ratio of the max. # of elements in a partition to the average
+    pub imbalance: f64,
+    /// The # of connected components of each partition
+    pub n_components: Vec<Idx>,
+    /// The # of vertices of each partition that belong to a partition interface
+    pub n_interface_verts: Vec<Idx>,
+    /// The total # of vertices that belong to a partition interface
+    pub n_interface_verts_total: Idx,
+}
+
+impl fmt::Display for PartitionStats {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "{} partitions, edge cut = {}, imbalance = {:.3}, {} interface vertices",
+            self.parts.len(),
+            self.edge_cut,
+            self.imbalance,
+            self.n_interface_verts_total
+        )?;
+        for (i, part) in self.parts.iter().enumerate() {
+            writeln!(
+                f,
+                "  partition {part}: {} elements, {} component(s), {} interface vertices",
+                self.n_elems[i], self.n_components[i], self.n_interface_verts[i]
+            )?;
+        }
+        Ok(())
+    }
+}
+
 impl<const D: usize, E: Elem> SimplexMesh<D, E> {
+    /// Store the partition id of each element, and copy it to the element tags if
+    /// `partition_to_etags()`
+    fn store_partition(&mut self, partition: Vec<Tag>) {
+        if self.partition_to_etags() {
+            if self.etags().any(|t| t != 1) {
+                warn!("Erase the element tags");
+            }
+            self.mut_etags()
+                .zip(partition.iter())
+                .for_each(|(t, &p)| *t = p);
+        }
+        self.set_partition(partition).unwrap();
+    }
+
+    /// Get the partition id of each element: the mesh partition if it has been set, the element tags
+    /// otherwise
+    fn partition_ids(&self) -> Vec<Tag> {
+        self.get_partition()
+            .map_or_else(|_| self.etags().collect(), <[Tag]>::to_vec)
+    }
+
+    /// Partition the mesh according to `ptype`. The partition id, defined for all the elements
+    /// is stored in the mesh partition (see `get_partition`), and in the element tags if
+    /// `partition_to_etags()`
     pub fn partition(&mut self, ptype: PartitionType) -> Result<()> {
         match ptype {
             PartitionType::Hilbert(n) => {
                 self.partition_hilbert(n);
                 Ok(())
             }
+            PartitionType::Rcb(n) => {
+                self.partition_rcb(n, false);
+                Ok(())
+            }
             PartitionType::Scotch(n) => self.partition_scotch(n),
             PartitionType::MetisRecursive(n) => self.partition_metis(n, "recursive"),
             PartitionType::MetisKWay(n) => self.partition_metis(n, "kway"),
@@ -28,168 +105,1593 @@ impl<const D: usize, E: Elem> SimplexMesh<D, E> {
         }
     }
 
-    pub fn partition_hilbert(&mut self, n_parts: Idx) {
-        debug!("Partition the mesh into {} using a Hilbert curve", n_parts);
+    /// Partition the mesh into `n_parts` by ordering the element centers along a Hilbert curve and
+    /// splitting this ordering into contiguous chunks. The partition id, defined for all the elements
+    /// is stored in the mesh partition. No external dependency is required.
+    pub fn partition_hilbert(&mut self, n_parts: Idx) {
+        debug!("Partition the mesh into {} using a Hilbert curve", n_parts);
+
+        if n_parts == 1 {
+            self.store_partition(vec![1; self.n_elems() as usize]);
+        } else {
+            let indices = hilbert_indices(self.bounding_box(), self.gelems().map(|ge| ge.center()));
+
+            let m = self.n_elems() / n_parts + 1;
+            let partition = indices.iter().map(|&i| (i / m) as Tag).collect::<Vec<_>>();
+
+            self.store_partition(partition.iter().map(|&p| p + 1).collect());
+        }
+    }
+
+    /// Partition the mesh into `n_parts` using recursive coordinate bisection of the element centers:
+    /// the elements are recursively split along the largest dimension of their bounding box.
+    /// If `volume_weighted` is true, the parts have (approximately) the same volume, otherwise they
+    /// have the same number of elements. The partition id, defined for all the elements
+    /// is stored in the mesh partition. No external dependency is required.
+    pub fn partition_rcb(&mut self, n_parts: Idx, volume_weighted: bool) {
+        debug!(
+            "Partition the mesh into {} using recursive coordinate bisection",
+            n_parts
+        );
+
+        if n_parts == 1 {
+            self.store_partition(vec![1; self.n_elems() as usize]);
+            return;
+        }
+
+        let centers = self.gelems().map(|ge| ge.center()).collect::<Vec<_>>();
+        let weights = if volume_weighted {
+            self.gelems().map(|ge| ge.vol()).collect::<Vec<_>>()
+        } else {
+            vec![1.0; self.n_elems() as usize]
+        };
+
+        let mut partition = vec![0; self.n_elems() as usize];
+        let mut ids = (0..self.n_elems()).collect::<Vec<_>>();
+        rcb(&centers, &weights, &mut ids, n_parts, 0, &mut partition);
+
+        self.store_partition(partition.iter().map(|&p| p as Tag + 1).collect());
+    }
+
+    /// Partition the mesh using scotch into `n_parts`. The partition id, defined for all the elements
+    /// is stored in the mesh partition
+    pub fn partition_scotch(&mut self, n_parts: Idx) -> Result<()> {
+        self.partition_scotch_weighted(n_parts, None, None)
+    }
+
+    #[allow(clippy::needless_pass_by_ref_mut)]
+    #[cfg(not(feature = "scotch"))]
+    pub fn partition_scotch_weighted(
+        &mut self,
+        _n_parts: Idx,
+        _elem_weights: Option<&[f64]>,
+        _edge_weights: Option<&[f64]>,
+    ) -> Result<()> {
+        Err(Error::from("the scotch feature is not enabled"))
+    }
+
+    /// Partition the mesh using scotch into `n_parts`, with optional weights
+    ///   - `elem_weights`: one weight per element (e.g. the expected remeshing cost)
+    ///   - `edge_weights`: one communication weight per entry of the element-to-element graph
+    ///     (i.e. aligned with `get_elem_to_elems()?.indices`)
+    ///
+    /// The weights are converted to integers, the largest weight being mapped to `WEIGHT_SCALE`
+    #[cfg(feature = "scotch")]
+    pub fn partition_scotch_weighted(
+        &mut self,
+        n_parts: Idx,
+        elem_weights: Option<&[f64]>,
+        edge_weights: Option<&[f64]>,
+    ) -> Result<()> {
+        debug!("Partition the mesh into {} using scotch", n_parts);
+
+        if n_parts == 1 {
+            self.store_partition(vec![1; self.n_elems() as usize]);
+            return Ok(());
+        }
+
+        let mut partition = vec![0; self.n_elems() as usize];
+        let n_elems = self.n_elems() as usize;
+        let e2e = self.compute_elem_to_elems();
+        check_weights(n_elems, e2e, elem_weights, edge_weights)?;
+
+        let architecture = scotch::Architecture::complete(n_parts.try_into()?);
+
+        let xadj: Vec<scotch::Num> = to_partitioner_ints(e2e.ptr.iter().copied())?;
+        let adjncy: Vec<scotch::Num> = to_partitioner_ints(e2e.indices.iter().copied())?;
+        let velotab: Vec<scotch::Num> = elem_weights
+            .map(|w| to_partitioner_ints(integer_weights(w)))
+            .transpose()?
+            .unwrap_or_default();
+        let edlotab: Vec<scotch::Num> = edge_weights
+            .map(|w| to_partitioner_ints(integer_weights(w)))
+            .transpose()?
+            .unwrap_or_default();
+
+        let mut graph = scotch::Graph::build(&scotch::graph::Data::new(
+            0,
+            &xadj,
+            &[],
+            &velotab,
+            &[],
+            &adjncy,
+            &edlotab,
+        ))
+        .unwrap();
+        graph.check().unwrap();
+        graph
+            .mapping(&architecture, &mut partition)
+            .compute(&mut scotch::Strategy::new())?;
+
+        self.store_partition(partition.iter().map(|&p| p as Tag + 1).collect());
+
+        Ok(())
+    }
+
+    #[allow(clippy::needless_pass_by_ref_mut)]
+    #[cfg(not(feature = "kahip"))]
+    pub fn partition_kahip(&mut self, _n_parts: Idx, _imbalance: f64) -> Result<()> {
+        Err(Error::from("the kahip feature is not enabled"))
+    }
+
+    /// Partition the mesh using KaHIP (kaffpa, eco mode) into `n_parts`, with a maximum allowed
+    /// imbalance `imbalance` (e.g. 0.03 for 3%). The partition id, defined for all the elements
+    /// is stored in the mesh partition
+    #[cfg(feature = "kahip")]
+    pub fn partition_kahip(&mut self, n_parts: Idx, imbalance: f64) -> Result<()> {
+        use std::os::raw::c_int;
+
+        debug!("Partition the mesh into {} using kahip", n_parts);
+
+        if n_parts == 1 {
+            self.store_partition(vec![1; self.n_elems() as usize]);
+            return Ok(());
+        }
+
+        let mut n: c_int = self.n_elems().try_into()?;
+        let e2e = self.compute_elem_to_elems();
+        if e2e.n() != n as Idx {
+            return Err(Error::from("kahip: isolated elements are not supported"));
+        }
+
+        let mut xadj: Vec<c_int> = to_partitioner_ints(e2e.ptr.iter().copied())?;
+        let mut adjncy: Vec<c_int> = to_partitioner_ints(e2e.indices.iter().copied())?;
+        let mut nparts: c_int = n_parts.try_into()?;
+        let mut imbalance = imbalance;
+        let mut edgecut: c_int = 0;
+        let mut partition: Vec<c_int> = vec![0; n as usize];
+
+        unsafe {
+            kahip::kaffpa(
+                &mut n,
+                std::ptr::null_mut(),
+                xadj.as_mut_ptr(),
+                std::ptr::null_mut(),
+                adjncy.as_mut_ptr(),
+                &mut nparts,
+                &mut imbalance,
+                true,
+                0,
+                kahip::ECO,
+                &mut edgecut,
+                partition.as_mut_ptr(),
+            );
+        }
+        debug!("kahip: edge cut = {edgecut}");
+
+        self.store_partition(partition.iter().map(|&p| p as Tag + 1).collect());
+
+        Ok(())
+    }
+
+    /// Partition the mesh using metis into `n_parts`. The partition id, defined for all the elements
+    /// is stored in the mesh partition
+    pub fn partition_metis(&mut self, n_parts: Idx, method: &str) -> Result<()> {
+        self.partition_metis_weighted(n_parts, method, None, None)
+    }
+
+    #[allow(clippy::needless_pass_by_ref_mut)]
+    #[cfg(not(feature = "metis"))]
+    pub fn partition_metis_weighted(
+        &mut self,
+        _n_parts: Idx,
+        _method: &str,
+        _elem_weights: Option<&[f64]>,
+        _edge_weights: Option<&[f64]>,
+    ) -> Result<()> {
+        Err(Error::from("the metis feature is not enabled"))
+    }
+
+    /// Partition the mesh using metis into `n_parts`, with optional weights
+    ///   - `elem_weights`: one weight per element (e.g. the expected remeshing cost)
+    ///   - `edge_weights`: one communication weight per entry of the element-to-element graph
+    ///     (i.e. aligned with `get_elem_to_elems()?.indices`)
+    ///
+    /// The weights are converted to integers, the largest weight being mapped to `WEIGHT_SCALE`
+    #[cfg(feature = "metis")]
+    pub fn partition_metis_weighted(
+        &mut self,
+        n_parts: Idx,
+        method: &str,
+        elem_weights: Option<&[f64]>,
+        edge_weights: Option<&[f64]>,
+    ) -> Result<()> {
+        debug!("Partition the mesh into {} using metis", n_parts);
+
+        if n_parts == 1 {
+            self.store_partition(vec![1; self.n_elems() as usize]);
+            return Ok(());
+        }
+
+        let mut partition = vec![0; self.n_elems() as usize];
+        let n_elems = self.n_elems() as usize;
+        let e2e = self.compute_elem_to_elems();
+        check_weights(n_elems, e2e, elem_weights, edge_weights)?;
+
+        let mut xadj: Vec<metis::Idx> = to_partitioner_ints(e2e.ptr.iter().copied())?;
+        let mut adjncy: Vec<metis::Idx> = to_partitioner_ints(e2e.indices.iter().copied())?;
+
+        let mut vwgt: Vec<metis::Idx> = elem_weights
+            .map(|w| to_partitioner_ints(integer_weights(w)))
+            .transpose()?
+            .unwrap_or_default();
+        let mut adjwgt: Vec<metis::Idx> = edge_weights
+            .map(|w| to_partitioner_ints(integer_weights(w)))
+            .transpose()?
+            .unwrap_or_default();
+
+        let mut graph = metis::Graph::new(1, n_parts.try_into()?, &mut xadj, &mut adjncy);
+        if !vwgt.is_empty() {
+            graph = graph.set_vwgt(&mut vwgt);
+        }
+        if !adjwgt.is_empty() {
+            graph = graph.set_adjwgt(&mut adjwgt);
+        }
+        match method {
+            "recursive" => graph.part_recursive(&mut partition).unwrap(),
+            "kway" => graph.part_kway(&mut partition).unwrap(),
+            _ => unreachable!("Unknown method"),
+        };
+
+        self.store_partition(partition.iter().map(|&p| p as Tag + 1).collect());
+
+        Ok(())
+    }
+
+    #[allow(clippy::needless_pass_by_ref_mut)]
+    #[cfg(not(feature = "metis"))]
+    pub fn partition_metis_multi(&mut self, _n_parts: Idx, _weights: &[Vec<f64>]) -> Result<()> {
+        Err(Error::from("the metis feature is not enabled"))
+    }
+
+    /// Partition the mesh using metis into `n_parts`, balancing several constraints simultaneously.
+    /// `weights[i]` contains the weights of all the elements for the i-th constraint (e.g. 1 for the
+    /// boundary layer elements and 0 elsewhere). The partition id, defined for all the elements
+    /// is stored in the mesh partition
+    #[cfg(feature = "metis")]
+    pub fn partition_metis_multi(&mut self, n_parts: Idx, weights: &[Vec<f64>]) -> Result<()> {
+        debug!(
+            "Partition the mesh into {} using metis with {} constraints",
+            n_parts,
+            weights.len()
+        );
+
+        if weights.is_empty() {
+            return Err(Error::from("no constraint given"));
+        }
+        let n_elems = self.n_elems() as usize;
+        for w in weights {
+            if w.len() != n_elems {
+                return Err(Error::from("invalid number of element weights"));
+            }
+            if w.iter().any(|&x| x < 0.0 || !x.is_finite()) {
+                return Err(Error::from("element weights must be >= 0"));
+            }
+        }
+
+        if n_parts == 1 {
+            self.store_partition(vec![1; self.n_elems() as usize]);
+            return Ok(());
+        }
+
+        let mut partition = vec![0; n_elems];
+        let e2e = self.compute_elem_to_elems();
+
+        let mut xadj: Vec<metis::Idx> = to_partitioner_ints(e2e.ptr.iter().copied())?;
+        let mut adjncy: Vec<metis::Idx> = to_partitioner_ints(e2e.indices.iter().copied())?;
+
+        // metis expects the weights of the ncon constraints to be contiguous for each element
+        let ncon = weights.len();
+        let mut vwgt: Vec<metis::Idx> = vec![0; ncon * n_elems];
+        for (i_con, w) in weights.iter().enumerate() {
+            let w_max = w.iter().copied().fold(0.0, f64::max);
+            if w_max > 0.0 {
+                for (i_elem, &x) in w.iter().enumerate() {
+                    vwgt[ncon * i_elem + i_con] = ((x / w_max * WEIGHT_SCALE).round() as i64)
+                        .try_into()
+                        .unwrap();
+                }
+            }
+        }
+
+        let graph = metis::Graph::new(
+            ncon as metis::Idx,
+            n_parts.try_into()?,
+            &mut xadj,
+            &mut adjncy,
+        )
+        .set_vwgt(&mut vwgt);
+        graph.part_kway(&mut partition).unwrap();
+
+        self.store_partition(partition.iter().map(|&p| p as Tag + 1).collect());
+
+        Ok(())
+    }
+
+    #[allow(clippy::needless_pass_by_ref_mut)]
+    #[cfg(not(feature = "metis"))]
+    pub fn partition_vertices_metis(&mut self, _n_parts: Idx) -> Result<()> {
+        Err(Error::from("the metis feature is not enabled"))
+    }
+
+    /// Partition the vertices of the mesh using metis into `n_parts`, based on the vertex-to-vertex
+    /// graph. The partition id, defined for all the vertices, is stored in the mesh vertex partition
+    /// (see `get_vertex_partition`).
+    /// Each element is then owned by the partition of its vertex with the lowest index, and this
+    /// partition id is stored in the mesh partition
+    #[cfg(feature = "metis")]
+    pub fn partition_vertices_metis(&mut self, n_parts: Idx) -> Result<()> {
+        debug!("Partition the mesh vertices into {} using metis", n_parts);
+
+        if n_parts == 1 {
+            self.set_vertex_partition(vec![1; self.n_verts() as usize])?;
+            self.store_partition(vec![1; self.n_elems() as usize]);
+            return Ok(());
+        }
+
+        let n_verts = self.n_verts() as usize;
+        let mut partition = vec![0; n_verts];
+        let v2v = self.compute_vertex_to_vertices();
+        if v2v.n() as usize != n_verts {
+            return Err(Error::from("metis: isolated vertices are not supported"));
+        }
+
+        let mut xadj: Vec<metis::Idx> = to_partitioner_ints(v2v.ptr.iter().copied())?;
+        let mut adjncy: Vec<metis::Idx> = to_partitioner_ints(v2v.indices.iter().copied())?;
+
+        let graph = metis::Graph::new(1, n_parts.try_into()?, &mut xadj, &mut adjncy);
+        graph.part_kway(&mut partition).unwrap();
+
+        let vert_partition = partition.iter().map(|&p| p as Tag + 1).collect::<Vec<_>>();
+        let elem_partition = self
+            .elems()
+            .map(|e| vert_partition[*e.iter().min().unwrap() as usize])
+            .collect();
+        self.set_vertex_partition(vert_partition)?;
+        self.store_partition(elem_partition);
+
+        Ok(())
+    }
+
+    /// Two-level partitioning: the mesh is first partitioned into `n_nodes` parts, and each part is
+    /// then partitioned into `n_cores_per_node` parts. `ptype` gives the partitioner to use for a
+    /// given number of parts (e.g. `PartitionType::MetisKWay`).
+    /// The first and second level partition ids (in `1..=n_nodes` and `1..=n_cores_per_node`) of
+    /// every element are returned, and the global partition id
+    /// `(node - 1) * n_cores_per_node + core` is stored in the mesh partition
+    pub fn partition_hierarchical(
+        &mut self,
+        n_nodes: Idx,
+        n_cores_per_node: Idx,
+        ptype: fn(Idx) -> PartitionType,
+    ) -> Result<(Vec<Tag>, Vec<Tag>)> {
+        debug!(
+            "Partition the mesh into {} x {} parts",
+            n_nodes, n_cores_per_node
+        );
+
+        let partition_to_etags = self.partition_to_etags();
+        self.set_partition_to_etags(false);
+        self.partition(ptype(n_nodes))?;
+        let node_partition = self.partition_ids();
+
+        let mut core_partition = vec![0; self.n_elems() as usize];
+        for node in 1..=n_nodes as Tag {
+            let flag = node_partition
+                .iter()
+                .map(|&t| t == node)
+                .collect::<Vec<_>>();
+            let mut sub = self.extract_elems(&flag);
+            if sub.mesh.n_elems() == 0 {
+                warn!("Empty partition {node}");
+                continue;
+            }
+            sub.mesh.partition(ptype(n_cores_per_node))?;
+            for (&i, &t) in sub.parent_elem_ids.iter().zip(sub.mesh.get_partition()?) {
+                core_partition[i as usize] = t;
+            }
+        }
+
+        self.set_partition_to_etags(partition_to_etags);
+        let n_cores = n_cores_per_node as Tag;
+        self.store_partition(
+            node_partition
+                .iter()
+                .zip(core_partition.iter())
+                .map(|(&node, &core)| (node - 1) * n_cores + core)
+                .collect(),
+        );
+
+        Ok((node_partition, core_partition))
+    }
+
+    /// Region-constrained partitioning: the elements of each group (given by `groups`, e.g. the
+    /// material id of every element) are partitioned independently, so that no partition contains
+    /// elements of different groups. The `n_parts` partitions are distributed among the groups
+    /// proportionally to their number of elements (at least one per group). `ptype` gives the
+    /// partitioner to use for a given number of parts (e.g. `PartitionType::MetisKWay`).
+    /// The partition ids are numbered globally and stored in the mesh partition
+    pub fn partition_by_region(
+        &mut self,
+        n_parts: Idx,
+        groups: &[Tag],
+        ptype: fn(Idx) -> PartitionType,
+    ) -> Result<()> {
+        debug!("Partition the mesh into {} by region", n_parts);
+
+        if groups.len() != self.n_elems() as usize {
+            return Err(Error::from("invalid number of element groups"));
+        }
+
+        let mut group_ids = groups.to_vec();
+        group_ids.sort_unstable();
+        group_ids.dedup();
+        if (group_ids.len() as Idx) > n_parts {
+            return Err(Error::from("less partitions than groups"));
+        }
+
+        // Number of parts per group, such that the number of elements per part is as uniform as
+        // possible
+        let n_elems = self.n_elems() as f64;
+        let ideal = group_ids
+            .iter()
+            .map(|&g| groups.iter().filter(|&&t| t == g).count() as f64 / n_elems * n_parts as f64)
+            .collect::<Vec<_>>();
+        let mut group_parts = ideal
+            .iter()
+            .map(|&x| (x.floor() as Idx).max(1))
+            .collect::<Vec<_>>();
+        while group_parts.iter().sum::<Idx>() < n_parts {
+            let (i, _) = ideal
+                .iter()
+                .zip(group_parts.iter())
+                .map(|(&x, &n)| x / n as f64)
+                .enumerate()
+                .max_by(|a, b| a.1.total_cmp(&b.1))
+                .unwrap();
+            group_parts[i] += 1;
+        }
+        while group_parts.iter().sum::<Idx>() > n_parts {
+            let (i, _) = ideal
+                .iter()
+                .zip(group_parts.iter())
+                .enumerate()
+                .filter(|&(_, (_, &n))| n > 1)
+                .map(|(i, (&x, &n))| (i, x / (n - 1) as f64))
+                .min_by(|a, b| a.1.total_cmp(&b.1))
+                .unwrap();
+            group_parts[i] -= 1;
+        }
+
+        let mut partition = vec![0; self.n_elems() as usize];
+        let mut offset = 0;
+        for (&g, &n) in group_ids.iter().zip(group_parts.iter()) {
+            debug!("Group {g}: {n} partitions");
+            let flag = groups.iter().map(|&t| t == g).collect::<Vec<_>>();
+            let mut sub = self.extract_elems(&flag);
+            sub.mesh.partition(ptype(n))?;
+            for (&i, &t) in sub.parent_elem_ids.iter().zip(sub.mesh.get_partition()?) {
+                partition[i as usize] = offset + t;
+            }
+            offset += n as Tag;
+        }
+
+        self.store_partition(partition);
+
+        Ok(())
+    }
+
+    /// Rebalance an existing partition into `n_parts` using a diffusive scheme: the elements are
+    /// moved, one layer at a time, from the overloaded partitions to their less loaded neighbors
+    /// until the imbalance (max. number of elements / average) is below 1.05. Only the excess
+    /// elements are moved, so that the number of elements that change owner is small.
+    /// `current_partition` gives the partition id (in `1..=n_parts`) of every element; all the
+    /// partitions must be non-empty. The new partition id is stored in the mesh partition, and the number
+    /// of elements that changed owner is returned
+    pub fn repartition(&mut self, n_parts: Idx, current_partition: &[Tag]) -> Result<Idx> {
+        debug!("Rebalance the partition into {} parts", n_parts);
+
+        let n_elems = self.n_elems() as usize;
+        if current_partition.len() != n_elems {
+            return Err(Error::from("invalid partition size"));
+        }
+        if current_partition
+            .iter()
+            .any(|&t| t < 1 || t as Idx > n_parts)
+        {
+            return Err(Error::from("invalid partition id"));
+        }
+
+        let n_parts = n_parts as usize;
+        let mut partition = current_partition
+            .iter()
+            .map(|&t| t as usize - 1)
+            .collect::<Vec<_>>();
+        let mut loads = vec![0_usize; n_parts];
+        partition.iter().for_each(|&p| loads[p] += 1);
+        if loads.iter().any(|&l| l == 0) {
+            return Err(Error::from("empty partitions are not supported"));
+        }
+
+        let target = n_elems as f64 / n_parts as f64;
+        let max_load = (REPARTITION_IMBALANCE * target).floor() as usize;
+        let e2e = self.compute_elem_to_elems();
+
+        for iter in 0..REPARTITION_MAX_ITER {
+            let imbalance = *loads.iter().max().unwrap() as f64 / target;
+            debug!("Iteration {iter}: imbalance = {imbalance:.3}");
+            if imbalance <= REPARTITION_IMBALANCE {
+                break;
+            }
+
+            // Move one layer of elements at the partition interfaces
+            let old_partition = partition.clone();
+            let mut n_moved = 0;
+            for i_elem in 0..n_elems {
+                let p = old_partition[i_elem];
+                if loads[p] <= max_load.min(target.ceil() as usize) {
+                    continue;
+                }
+                let q = neighbors(e2e, i_elem as Idx)
+                    .iter()
+                    .map(|&j| old_partition[j as usize])
+                    .filter(|&q| q != p && loads[q] + 1 < loads[p])
+                    .min_by_key(|&q| loads[q]);
+                if let Some(q) = q {
+                    if (loads[q] as f64) < target {
+                        partition[i_elem] = q;
+                        loads[p] -= 1;
+                        loads[q] += 1;
+                        n_moved += 1;
+                    }
+                }
+            }
+            if n_moved == 0 {
+                warn!("Unable to rebalance the partition");
+                break;
+            }
+        }
+
+        let partition = partition.iter().map(|&p| p as Tag + 1).collect::<Vec<_>>();
+        let n_migrated = partition
+            .iter()
+            .zip(current_partition.iter())
+            .filter(|(p, p_old)| p != p_old)
+            .count() as Idx;
+        self.store_partition(partition);
+
+        Ok(n_migrated)
+    }
+
+    /// Extract the partitions (i.e. the elements with the same partition id) as `SubSimplexMesh`es,
+    /// ordered by increasing partition id. If `n_ghost_layers > 0`, the elements of the neighboring
+    /// partitions that are within `n_ghost_layers` layers (via the vertices) of a partition are added.
+    /// The partition of the submeshes is set, so that the ghost elements can be identified.
+    /// NB: the faces of the original mesh are kept, but the faces at the partition boundaries are not
+    /// created (use `add_boundary_faces` if needed)
+    #[must_use]
+    pub fn extract_partitions(&self, n_ghost_layers: Idx) -> Vec<SubSimplexMesh<D, E>> {
+        debug!(
+            "Extract the partitions with {} layers of ghost elements",
+            n_ghost_layers
+        );
+
+        let partition = self.partition_ids();
+        let mut tags = partition.clone();
+        tags.sort_unstable();
+        tags.dedup();
+
+        tags.par_iter()
+            .map(|&tag| self.extract_partition_from(&partition, tag, n_ghost_layers))
+            .collect()
+    }
+
+    /// Extract the partition with id `tag` as a `SubSimplexMesh`, with `n_ghost_layers` layers
+    /// of ghost elements (see `extract_partitions`)
+    #[must_use]
+    pub fn extract_partition(&self, tag: Tag, n_ghost_layers: Idx) -> SubSimplexMesh<D, E> {
+        self.extract_partition_from(&self.partition_ids(), tag, n_ghost_layers)
+    }
+
+    fn extract_partition_from(
+        &self,
+        partition: &[Tag],
+        tag: Tag,
+        n_ghost_layers: Idx,
+    ) -> SubSimplexMesh<D, E> {
+        let mut elem_flag = partition.iter().map(|&t| t == tag).collect::<Vec<_>>();
+        let mut vert_flag = vec![false; self.n_verts() as usize];
+        for _ in 0..n_ghost_layers {
+            self.elems()
+                .zip(elem_flag.iter())
+                .filter(|(_, f)| **f)
+                .flat_map(|(e, _)| e)
+                .for_each(|i| vert_flag[i as usize] = true);
+            self.elems().zip(elem_flag.iter_mut()).for_each(|(e, f)| {
+                if e.iter().any(|&i| vert_flag[i as usize]) {
+                    *f = true;
+                }
+            });
+        }
+        let mut res = self.extract_elems(&elem_flag);
+        let sub_partition = res
+            .parent_elem_ids
+            .iter()
+            .map(|&i| partition[i as usize])
+            .collect();
+        res.mesh.set_partition(sub_partition).unwrap();
+        res
+    }
+
+    /// Extract the elements flagged in `elem_flag` (and the faces of these elements)
+    pub(crate) fn extract_elems(&self, elem_flag: &[bool]) -> SubSimplexMesh<D, E> {
+        let mut new_vert_ids = vec![Idx::MAX; self.n_verts() as usize];
+        let mut parent_vert_ids = Vec::new();
+        let mut parent_elem_ids = Vec::new();
+        let mut verts = Vec::new();
+        let mut elems = Vec::new();
+        let mut etags = Vec::new();
+        let mut elem_faces = FxHashSet::default();
+
+        for (i_elem, (e, t)) in self
+            .elems()
+            .zip(self.etags())
+            .enumerate()
+            .filter(|(i, _)| elem_flag[*i])
+        {
+            for &i in e.iter() {
+                if new_vert_ids[i as usize] == Idx::MAX {
+                    new_vert_ids[i as usize] = verts.len() as Idx;
+                    parent_vert_ids.push(i);
+                    verts.push(self.vert(i));
+                }
+            }
+            parent_elem_ids.push(i_elem as Idx);
+            let e = E::from_iter(e.iter().map(|&i| new_vert_ids[i as usize]));
+            for i_face in 0..E::N_FACES {
+                elem_faces.insert(e.face(i_face).sorted());
+            }
+            elems.push(e);
+            etags.push(t);
+        }
+
+        let mut parent_face_ids = Vec::new();
+        let mut faces = Vec::new();
+        let mut ftags = Vec::new();
+        for (i_face, (f, t)) in self.faces().zip(self.ftags()).enumerate() {
+            if f.iter().all(|&i| new_vert_ids[i as usize] != Idx::MAX) {
+                let f = E::Face::from_iter(f.iter().map(|&i| new_vert_ids[i as usize]));
+                if elem_faces.contains(&f.sorted()) {
+                    parent_face_ids.push(i_face as Idx);
+                    faces.push(f);
+                    ftags.push(t);
+                }
+            }
+        }
+
+        let mut mesh = Self::new(verts, elems, etags, faces, ftags);
+        *mesh.tag_names_mut() = self.tag_names().clone();
+
+        SubSimplexMesh {
+            mesh,
+            parent_vert_ids,
+            parent_elem_ids,
+            parent_face_ids,
+        }
+    }
+
+    /// Merge partitions (e.g. extracted with `extract_partitions` and remeshed independently) into a
+    /// single mesh. The partitions must not overlap (i.e. ghost elements must have been removed).
+    /// The duplicate vertices at the partition interfaces are identified
+    ///   - using `maps`, if given: `maps[i][j]` is the global index of vertex `j` of partition `i`
+    ///     (`Idx::MAX` if unknown, e.g. for a vertex created during remeshing)
+    ///   - otherwise, or if the global index is unknown, if their distance is below `merge_tol`
+    ///
+    /// Duplicate faces are removed, as well as the faces with a negative tag that are shared by two
+    /// elements (i.e. the frozen partition interfaces). If the partition of all the parts is set, it
+    /// is set on the merged mesh
+    pub fn merge_partitions(
+        parts: &[Self],
+        maps: Option<&[Vec<Idx>]>,
+        merge_tol: f64,
+    ) -> Result<Self> {
+        debug!("Merge {} partitions", parts.len());
+
+        if let Some(maps) = maps {
+            if maps.len() != parts.len() {
+                return Err(Error::from("invalid number of vertex maps"));
+            }
+            for (part, map) in parts.iter().zip(maps.iter()) {
+                if map.len() != part.n_verts() as usize {
+                    return Err(Error::from("invalid vertex map size"));
+                }
+            }
+        }
+
+        let mut verts = Vec::new();
+        let mut elems = Vec::new();
+        let mut etags = Vec::new();
+        let mut faces: Vec<E::Face> = Vec::new();
+        let mut ftags = Vec::new();
+        let mut global_ids = FxHashMap::default();
+        let mut partition = Vec::new();
+
+        for (i_part, part) in parts.iter().enumerate() {
+            let map = maps.map(|m| &m[i_part]);
+            let mut new_ids = vec![Idx::MAX; part.n_verts() as usize];
+
+            // Use the global ids
+            if let Some(map) = map {
+                for (new_id, &i) in new_ids.iter_mut().zip(map.iter()) {
+                    if let Some(&j) = global_ids.get(&i) {
+                        *new_id = j;
+                    }
+                }
+            }
+
+            // Use a tolerance on the distance with the vertices already merged
+            if !elems.is_empty() && new_ids.iter().any(|&i| i == Idx::MAX) {
+                let tmp = Self::new(
+                    verts.clone(),
+                    elems.clone(),
+                    etags.clone(),
+                    Vec::new(),
+                    Vec::new(),
+                );
+                let tree = tmp.compute_vert_tree();
+                for (new_id, p) in new_ids.iter_mut().zip(part.verts()) {
+                    if *new_id == Idx::MAX {
+                        let (i, _) = tree.nearest_vert(&p);
+                        if (verts[i as usize] - p).norm() < merge_tol {
+                            *new_id = i;
+                        }
+                    }
+                }
+            }
+
+            // Add the new vertices
+            for (i, (new_id, p)) in new_ids.iter_mut().zip(part.verts()).enumerate() {
+                if *new_id == Idx::MAX {
+                    *new_id = verts.len() as Idx;
+                    verts.push(p);
+                }
+                if let Some(map) = map {
+                    if map[i] != Idx::MAX {
+                        global_ids.entry(map[i]).or_insert(*new_id);
+                    }
+                }
+            }
+
+            for (e, t) in part.elems().zip(part.etags()) {
+                elems.push(E::from_iter(e.iter().map(|&i| new_ids[i as usize])));
+                etags.push(t);
+            }
+            if let Ok(part_partition) = part.get_partition() {
+                partition.extend_from_slice(part_partition);
+            }
+            for (f, t) in part.faces().zip(part.ftags()) {
+                faces.push(E::Face::from_iter(f.iter().map(|&i| new_ids[i as usize])));
+                ftags.push(t);
+            }
+        }
+
+        // Remove the duplicate faces and the interfaces
+        let f2e = get_face_to_elem(elems.iter().copied());
+        let mut added_faces = FxHashSet::default();
+        let (faces, ftags): (Vec<_>, Vec<_>) = faces
+            .iter()
+            .zip(ftags.iter())
+            .filter(|(f, t)| {
+                let f = f.sorted();
+                let n_elems = f2e.get(&f).map_or(0, twovec::Vec::len);
+                if **t < 0 && n_elems == 2 {
+                    return false;
+                }
+                added_faces.insert(f)
+            })
+            .map(|(&f, &t)| (f, t))
+            .unzip();
+
+        let mut res = Self::new(verts, elems, etags, faces, ftags);
+        if partition.len() == res.n_elems() as usize {
+            res.set_partition(partition)?;
+        }
+
+        Ok(res)
+    }
+
+    /// Get the faces between elements with different partition ids (i.e. the partition interfaces)
+    /// as a mesh.
+    /// The interface between partitions `i` and `j` (`i < j`) is tagged according to the map that is
+    /// returned together with the mesh and the indices of the mesh vertices in the parent mesh
+    #[allow(clippy::type_complexity)]
+    pub fn partition_interfaces(
+        &self,
+    ) -> Result<(
+        SimplexMesh<D, E::Face>,
+        Vec<Idx>,
+        FxHashMap<Tag, (Tag, Tag)>,
+    )> {
+        debug!("Extract the partition interfaces");
+
+        let f2e = self.get_face_to_elems()?;
+        let partition = self.partition_ids();
+
+        let mut ifc = f2e
+            .iter()
+            .filter(|(_, v)| v.len() == 2 && partition[v[0] as usize] != partition[v[1] as usize])
+            .map(|(f, v)| {
+                let (t0, t1) = (partition[v[0] as usize], partition[v[1] as usize]);
+                (*f, (t0.min(t1), t0.max(t1)))
+            })
+            .collect::<Vec<_>>();
+        ifc.sort_unstable_by_key(|(_, pair)| *pair);
+
+        let mut pairs = ifc.iter().map(|(_, pair)| *pair).collect::<Vec<_>>();
+        pairs.dedup();
+        let tags = pairs
+            .iter()
+            .enumerate()
+            .map(|(i, &pair)| (pair, i as Tag + 1))
+            .collect::<FxHashMap<_, _>>();
+
+        let mut new_vert_ids = vec![Idx::MAX; self.n_verts() as usize];
+        let mut vert_ids = Vec::new();
+        let mut verts = Vec::new();
+        let mut faces = Vec::with_capacity(ifc.len());
+        let mut ftags = Vec::with_capacity(ifc.len());
+        for (f, pair) in &ifc {
+            for &i in f.iter() {
+                if new_vert_ids[i as usize] == Idx::MAX {
+                    new_vert_ids[i as usize] = verts.len() as Idx;
+                    vert_ids.push(i);
+                    verts.push(self.vert(i));
+                }
+            }
+            faces.push(E::Face::from_iter(
+                f.iter().map(|&i| new_vert_ids[i as usize]),
+            ));
+            ftags.push(tags[pair]);
+        }
+
+        let tags = tags.iter().map(|(&pair, &t)| (t, pair)).collect();
+
+        Ok((
+            SimplexMesh::new(verts, faces, ftags, Vec::new(), Vec::new()),
+            vert_ids,
+            tags,
+        ))
+    }
+
+    /// Make sure that all the partitions are connected:
+    /// for every partition, the connected components other than the largest one are reassigned to
+    /// the neighboring partition with which they share the most faces.
+    /// The number of elements that were reassigned is returned
+    pub fn fix_partition_contiguity(&mut self) -> Idx {
+        debug!("Fix the partition contiguity");
+
+        let mut etags = self.partition_ids();
+        let e2e = self.compute_elem_to_elems();
+
+        let mut n_reassigned = 0;
+        for iter in 0..CONTIGUITY_MAX_ITER {
+            let (components, n_comps) = partition_components(e2e, &etags);
+
+            // The size & partition of each component
+            let mut comp_size = vec![0; n_comps as usize];
+            let mut comp_part = vec![0; n_comps as usize];
+            for (&c, &t) in components.iter().zip(etags.iter()) {
+                comp_size[c as usize] += 1;
+                comp_part[c as usize] = t;
+            }
+
+            // The largest component of each partition
+            let mut largest = FxHashMap::default();
+            for (c, (&size, &t)) in comp_size.iter().zip(comp_part.iter()).enumerate() {
+                let e = largest.entry(t).or_insert((c, size));
+                if size > e.1 {
+                    *e = (c, size);
+                }
+            }
+
+            // Count the faces between the islands and the neighboring partitions
+            let mut counts: FxHashMap<Idx, FxHashMap<Tag, Idx>> = FxHashMap::default();
+            for (i_elem, &c) in components.iter().enumerate() {
+                if largest[&comp_part[c as usize]].0 == c as usize {
+                    continue;
+                }
+                for &j_elem in neighbors(e2e, i_elem as Idx) {
+                    let t = etags[j_elem as usize];
+                    if t != comp_part[c as usize] {
+                        *counts.entry(c).or_default().entry(t).or_insert(0) += 1;
+                    }
+                }
+            }
+            if counts.is_empty() {
+                break;
+            }
+            if iter == CONTIGUITY_MAX_ITER - 1 {
+                warn!("Unable to make all the partitions connected");
+                break;
+            }
+
+            let new_parts = counts
+                .iter()
+                .map(|(&c, n)| {
+                    let (&t, _) = n.iter().max_by_key(|&(&t, &n)| (n, -t)).unwrap();
+                    (c, t)
+                })
+                .collect::<FxHashMap<_, _>>();
+            for (t, c) in etags.iter_mut().zip(components.iter()) {
+                if let Some(&new_t) = new_parts.get(c) {
+                    *t = new_t;
+                    n_reassigned += 1;
+                }
+            }
+        }
+
+        if n_reassigned > 0 {
+            debug!("{n_reassigned} elements reassigned");
+        }
+        self.store_partition(etags);
+
+        n_reassigned
+    }
+
+    /// Compute statistics on the partition
+    /// The element-to-element connectivity must have been computed
+    pub fn partition_stats(&self) -> Result<PartitionStats> {
+        let e2e = self.get_elem_to_elems()?;
+        let etags = self.partition_ids();
+
+        let mut parts = etags.clone();
+        parts.sort_unstable();
+        parts.dedup();
+        let part_idx = parts
+            .iter()
+            .enumerate()
+            .map(|(i, &t)| (t, i))
+            .collect::<FxHashMap<_, _>>();
+
+        let mut n_elems = vec![0; parts.len()];
+        etags.iter().for_each(|t| n_elems[part_idx[t]] += 1);
+        let avg = self.n_elems() as f64 / parts.len() as f64;
+        let imbalance = f64::from(*n_elems.iter().max().unwrap_or(&0)) / avg;
+
+        let (components, n_comps) = partition_components(e2e, &etags);
+        let mut comp_part = vec![0; n_comps as usize];
+        components
+            .iter()
+            .zip(etags.iter())
+            .for_each(|(&c, t)| comp_part[c as usize] = part_idx[t]);
+        let mut n_components = vec![0; parts.len()];
+        comp_part.iter().for_each(|&p| n_components[p] += 1);
+
+        let mut edge_cut = 0;
+        let mut ifc_flag = vec![false; self.n_verts() as usize];
+        for (i_elem, e) in self.elems().enumerate() {
+            for &j_elem in neighbors(e2e, i_elem as Idx) {
+                if etags[i_elem] != etags[j_elem as usize] {
+                    if (i_elem as Idx) < j_elem {
+                        edge_cut += 1;
+                    }
+                    let other = self.elem(j_elem);
+                    e.iter()
+                        .filter(|&&i| other.contains_vertex(i))
+                        .for_each(|&i| ifc_flag[i as usize] = true);
+                }
+            }
+        }
+        let n_interface_verts_total = ifc_flag.iter().filter(|&&f| f).count() as Idx;
+
+        let mut n_interface_verts = vec![0; parts.len()];
+        let mut part_flag = vec![false; self.n_verts() as usize];
+        for (i, &part) in parts.iter().enumerate() {
+            part_flag.iter_mut().for_each(|f| *f = false);
+            self.elems()
+                .zip(etags.iter())
+                .filter(|&(_, &t)| t == part)
+                .flat_map(|(e, _)| e)
+                .for_each(|i_vert| part_flag[i_vert as usize] = true);
+            n_interface_verts[i] = part_flag
+                .iter()
+                .zip(ifc_flag.iter())
+                .filter(|&(&a, &b)| a && b)
+                .count() as Idx;
+        }
+
+        Ok(PartitionStats {
+            parts,
+            edge_cut,
+            n_elems,
+            imbalance,
+            n_components,
+            n_interface_verts,
+            n_interface_verts_total,
+        })
+    }
+
+    /// Get the partition quality (ration of the number of interface faces to the total number of faces)
+    pub fn partition_quality(&self) -> Result<f64> {
+        let f2e = self.get_face_to_elems()?;
+        let partition = self.partition_ids();
+
+        let n = f2e
+            .iter()
+            .filter(|(_, v)| v.len() == 2 && partition[v[0] as usize] != partition[v[1] as usize])
+            .count();
+        Ok(n as f64 / f2e.len() as f64)
+    }
+}
+
+/// Get the neighbors of element `i` in the element-to-element graph
+fn neighbors(e2e: &CSRGraph, i: Idx) -> &[Idx] {
+    if i < e2e.n() {
+        e2e.row(i)
+    } else {
+        &[]
+    }
+}
+
+/// Compute the connected components of all the partitions using the element-to-element graph.
+/// Return the component of every element and the number of components
+fn partition_components(e2e: &CSRGraph, partition: &[Tag]) -> (Vec<Idx>, Idx) {
+    let mut components = vec![Idx::MAX; partition.len()];
+    let mut n_comps = 0;
+    let mut stack = Vec::new();
+    for start in 0..partition.len() {
+        if components[start] != Idx::MAX {
+            continue;
+        }
+        components[start] = n_comps;
+        stack.push(start as Idx);
+        while let Some(i) = stack.pop() {
+            for &j in neighbors(e2e, i) {
+                if components[j as usize] == Idx::MAX
+                    && partition[j as usize] == partition[i as usize]
+                {
+                    components[j as usize] = n_comps;
+                    stack.push(j);
+                }
+            }
+        }
+        n_comps += 1;
+    }
+    (components, n_comps)
+}
+
+/// Max. number of iterations for `fix_partition_contiguity`
+const CONTIGUITY_MAX_ITER: usize = 100;
+
+/// Target imbalance for `repartition`
+const REPARTITION_IMBALANCE: f64 = 1.05;
+
+/// Max. number of iterations for `repartition`
+const REPARTITION_MAX_ITER: usize = 1000;
+
+/// Recursive coordinate bisection of the points `centers[ids]` into `n_parts`. The parts are
+/// numbered from `first_part` and stored in `partition`
+fn rcb<const D: usize>(
+    centers: &[Point<D>],
+    weights: &[f64],
+    ids: &mut [Idx],
+    n_parts: Idx,
+    first_part: Idx,
+    partition: &mut [Idx],
+) {
+    if n_parts == 1 || ids.len() <= 1 {
+        ids.iter().for_each(|&i| partition[i as usize] = first_part);
+        return;
+    }
+
+    // Split along the largest dimension of the bounding box
+    let mut p_min = centers[ids[0] as usize];
+    let mut p_max = p_min;
+    for &i in ids.iter() {
+        let p = &centers[i as usize];
+        p_min = p_min.inf(p);
+        p_max = p_max.sup(p);
+    }
+    let dir = (p_max - p_min).imax();
+    ids.sort_unstable_by(|&i, &j| centers[i as usize][dir].total_cmp(&centers[j as usize][dir]));
+
+    // Split such that the weights are proportional to the number of parts on each side
+    let n_left = n_parts / 2;
+    let total = ids.iter().map(|&i| weights[i as usize]).sum::<f64>();
+    let target = total * n_left as f64 / n_parts as f64;
+    let mut acc = 0.0;
+    let mut split = ids.len() - 1;
+    for (k, &i) in ids.iter().enumerate() {
+        acc += weights[i as usize];
+        if acc >= target {
+            split = k + 1;
+            break;
+        }
+    }
+    let split = split.clamp(1, ids.len() - 1);
+
+    let (left, right) = ids.split_at_mut(split);
+    rcb(centers, weights, left, n_left, first_part, partition);
+    rcb(
+        centers,
+        weights,
+        right,
+        n_parts - n_left,
+        first_part + n_left,
+        partition,
+    );
+}
+
+/// Bindings to the KaHIP C interface (`kaHIP_interface.h`)
+#[cfg(feature = "kahip")]
+mod kahip {
+    use std::os::raw::{c_double, c_int};
+
+    /// kaffpa's eco mode
+    pub const ECO: c_int = 1;
+
+    #[link(name = "kahip")]
+    extern "C" {
+        #[allow(clippy::too_many_arguments)]
+        pub fn kaffpa(
+            n: *mut c_int,
+            vwgt: *mut c_int,
+            xadj: *mut c_int,
+            adjcwgt: *mut c_int,
+            adjncy: *mut c_int,
+            nparts: *mut c_int,
+            imbalance: *mut c_double,
+            suppress_output: bool,
+            seed: c_int,
+            mode: c_int,
+            edgecut: *mut c_int,
+            part: *mut c_int,
+        );
+    }
+}
+
+/// Scale used to convert floating point partition weights to integers
+#[cfg(any(feature = "scotch", feature = "metis"))]
+const WEIGHT_SCALE: f64 = 1000.0;
+
+/// Convert graph indices or weights to the integer type used by a partitioner, returning an
+/// error if a value does not fit (e.g. for very large meshes with the `large-indices` feature)
+#[cfg(any(feature = "scotch", feature = "metis", feature = "kahip"))]
+fn to_partitioner_ints<I, T: TryFrom<I>>(values: impl Iterator<Item = I>) -> Result<Vec<T>> {
+    values
+        .map(|x| T::try_from(x).map_err(|_| Error::from("value too large for the partitioner")))
+        .collect::<std::result::Result<_, _>>()
+        .map_err(|e| e as Box<dyn std::error::Error>)
+}
+
+/// Check the sizes and the values of the partition weights for the element-to-element graph
+/// `e2e`. The edge weights must be symmetric (w(i, j) == w(j, i)) as required by the partitioners
+#[cfg(any(feature = "scotch", feature = "metis"))]
+fn check_weights(
+    n_elems: usize,
+    e2e: &CSRGraph,
+    elem_weights: Option<&[f64]>,
+    edge_weights: Option<&[f64]>,
+) -> Result<()> {
+    let n_edges = e2e.indices.len();
+    if let Some(w) = elem_weights {
+        if w.len() != n_elems {
+            return Err(Error::from("invalid number of element weights"));
+        }
+        if w.iter().any(|&x| x <= 0.0 || !x.is_finite()) {
+            return Err(Error::from("element weights must be > 0"));
+        }
+    }
+    if let Some(w) = edge_weights {
+        if w.len() != n_edges {
+            return Err(Error::from("invalid number of edge weights"));
+        }
+        if w.iter().any(|&x| x <= 0.0 || !x.is_finite()) {
+            return Err(Error::from("edge weights must be > 0"));
+        }
+        for i in 0..e2e.n() {
+            for k in e2e.ptr[i as usize] as usize..e2e.ptr[i as usize + 1] as usize {
+                let j = e2e.indices[k];
+                let start = e2e.ptr[j as usize] as usize;
+                let symmetric = e2e.row(j).iter().position(|&l| l == i).is_some_and(|l| {
+                    let (a, b) = (w[k], w[start + l]);
+                    (a - b).abs() <= 1e-12 * a.max(b)
+                });
+                if !symmetric {
+                    return Err(Error::from(&format!(
+                        "edge weights must be symmetric (elements {i} and {j})"
+                    )));
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Convert positive floating point weights to integers in [1, `WEIGHT_SCALE`]
+#[cfg(any(feature = "scotch", feature = "metis"))]
+fn integer_weights(w: &[f64]) -> impl Iterator<Item = i64> + '_ {
+    let w_max = w.iter().copied().fold(0.0, f64::max);
+    w.iter()
+        .map(move |&x| ((x / w_max * WEIGHT_SCALE).round() as i64).max(1))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        mesh::{
+            test_meshes::{test_mesh_2d, test_mesh_3d},
+            GElem, PartitionType, SimplexMesh,
+        },
+        Idx, Result, Tag,
+    };
+
+    #[test]
+    fn test_partition_hilbert_2d() -> Result<()> {
+        let mut mesh = test_mesh_2d().split().split().split().split().split();
+        mesh.compute_elem_to_elems();
+        mesh.partition_hilbert(4);
 
-        if self.etags().any(|t| t != 1) {
-            warn!("Erase the element tags");
-        }
+        let q = mesh.partition_quality()?;
+        assert!(q < 0.025, "failed, q = {q}");
 
-        if n_parts == 1 {
-            self.mut_etags().for_each(|t| *t = 1);
-        } else {
-            let indices = hilbert_indices(self.bounding_box(), self.gelems().map(|ge| ge.center()));
+        Ok(())
+    }
 
-            let m = self.n_elems() / n_parts + 1;
-            let partition = indices.iter().map(|&i| (i / m) as Tag).collect::<Vec<_>>();
+    #[test]
+    fn test_partition_hilbert_balance() -> Result<()> {
+        let mut mesh = test_mesh_3d().split().split().split();
+        mesh.partition_hilbert(5);
 
-            self.mut_etags()
-                .enumerate()
-                .for_each(|(i, t)| *t = partition[i] as Tag + 1);
-        }
-    }
+        let mut counts = [0; 5];
+        mesh.get_partition()?
+            .iter()
+            .copied()
+            .for_each(|t| counts[t as usize - 1] += 1);
+        let n_min = *counts.iter().min().unwrap();
+        let n_max = *counts.iter().max().unwrap();
+        assert!(n_min > 0);
+        assert!(n_max - n_min <= 5, "unbalanced: {counts:?}");
 
-    #[allow(clippy::needless_pass_by_ref_mut)]
-    #[cfg(not(feature = "scotch"))]
-    pub fn partition_scotch(&mut self, _n_parts: Idx) -> Result<()> {
-        use crate::Error;
-        Err(Error::from("the scotch feature is not enabled"))
+        Ok(())
     }
 
-    /// Partition the mesh using scotch into `n_parts`. The partition id, defined for all the elements
-    /// is stored in self.etags
-    #[cfg(feature = "scotch")]
-    pub fn partition_scotch(&mut self, n_parts: Idx) -> Result<()> {
-        debug!("Partition the mesh into {} using scotch", n_parts);
+    #[test]
+    fn test_partition_keep_etags() -> Result<()> {
+        let mut mesh = test_mesh_2d().split().split();
+        let etags = mesh.etags().collect::<Vec<_>>();
 
-        if self.etags().any(|t| t != 1) {
-            warn!("Erase the element tags");
-        }
+        mesh.partition_hilbert(4);
+        assert!(mesh.etags().zip(etags.iter()).all(|(t0, &t1)| t0 == t1));
+        let partition = mesh.get_partition()?.to_vec();
 
-        if n_parts == 1 {
-            self.mut_etags().for_each(|t| *t = 1);
-            return Ok(());
-        }
+        mesh.set_partition_to_etags(true);
+        mesh.partition_hilbert(4);
+        assert!(mesh.etags().zip(partition.iter()).all(|(t0, &t1)| t0 == t1));
 
-        let mut partition = vec![0; self.n_elems() as usize];
-        let e2e = self.compute_elem_to_elems();
+        Ok(())
+    }
 
-        let architecture = scotch::Architecture::complete(n_parts as i32);
+    #[test]
+    fn test_partition_rcb_2d() -> Result<()> {
+        let mut mesh = test_mesh_2d().split().split().split().split().split();
+        mesh.compute_elem_to_elems();
+        mesh.partition_rcb(4, false);
 
-        let xadj: Vec<scotch::Num> = e2e
-            .ptr
+        let mut counts = [0; 4];
+        mesh.get_partition()?
             .iter()
             .copied()
-            .map(|x| x.try_into().unwrap())
-            .collect();
-        let adjncy: Vec<scotch::Num> = e2e
-            .indices
+            .for_each(|t| counts[t as usize - 1] += 1);
+        let n_min = *counts.iter().min().unwrap();
+        let n_max = *counts.iter().max().unwrap();
+        assert!(n_max - n_min <= 1, "unbalanced: {counts:?}");
+
+        let q = mesh.partition_quality()?;
+        assert!(q < 0.03, "failed, q = {q}");
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_partition_rcb_3d() -> Result<()> {
+        let mut mesh = test_mesh_3d().split().split().split().split();
+        mesh.compute_elem_to_elems();
+        mesh.partition_rcb(3, true);
+
+        let mut vols = [0.0; 3];
+        mesh.get_partition()?
             .iter()
             .copied()
-            .map(|x| x.try_into().unwrap())
-            .collect();
+            .zip(mesh.gelems())
+            .for_each(|(t, ge)| vols[t as usize - 1] += ge.vol());
+        let total = vols.iter().sum::<f64>();
+        for v in vols {
+            assert!(
+                (v - total / 3.0).abs() < 0.01 * total,
+                "unbalanced: {vols:?}"
+            );
+        }
 
-        let mut graph = scotch::Graph::build(&scotch::graph::Data::new(
-            0,
-            &xadj,
-            &[],
-            &[],
-            &[],
-            &adjncy,
-            &[],
-        ))
-        .unwrap();
-        graph.check().unwrap();
-        graph
-            .mapping(&architecture, &mut partition)
-            .compute(&mut scotch::Strategy::new())?;
+        let q = mesh.partition_quality()?;
+        assert!(q < 0.05, "failed, q = {q}");
 
-        self.mut_etags()
-            .enumerate()
-            .for_each(|(i, t)| *t = partition[i] as Tag + 1);
+        Ok(())
+    }
+
+    #[test]
+    fn test_extract_partitions() -> Result<()> {
+        let mut mesh = test_mesh_2d().split().split().split();
+        mesh.partition_hilbert(3);
+        let n_elems = mesh.n_elems();
+
+        let parts = mesh.extract_partitions(0);
+        assert_eq!(parts.len(), 3);
+        assert_eq!(parts.iter().map(|p| p.mesh.n_elems()).sum::<Idx>(), n_elems);
+        let n_faces = parts.iter().map(|p| p.mesh.n_faces()).sum::<Idx>();
+        assert_eq!(n_faces, mesh.n_faces());
+        for (i, part) in parts.iter().enumerate() {
+            assert!(part
+                .mesh
+                .get_partition()?
+                .iter()
+                .copied()
+                .all(|t| t == i as Tag + 1));
+            for (e, &i_parent) in part.mesh.elems().zip(part.parent_elem_ids.iter()) {
+                let e_parent = mesh.elem(i_parent);
+                for (i, i_parent) in e.iter().zip(e_parent.iter()) {
+                    assert_eq!(part.parent_vert_ids[*i as usize], *i_parent);
+                }
+            }
+        }
+
+        let ghost_parts = mesh.extract_partitions(2);
+        for (i, (part, ghost_part)) in parts.iter().zip(ghost_parts.iter()).enumerate() {
+            let n_own = ghost_part
+                .mesh
+                .get_partition()?
+                .iter()
+                .filter(|&&t| t == i as Tag + 1)
+                .count() as Idx;
+            assert_eq!(n_own, part.mesh.n_elems());
+            assert!(ghost_part.mesh.n_elems() > part.mesh.n_elems());
+            assert!((ghost_part.mesh.vol() - part.mesh.vol()) > 0.0);
+        }
 
         Ok(())
     }
 
-    #[allow(clippy::needless_pass_by_ref_mut)]
-    #[cfg(not(feature = "metis"))]
-    pub fn partition_metis(&mut self, _n_parts: Idx, _method: &str) -> Result<()> {
-        use crate::Error;
-        Err(Error::from("the metis feature is not enabled"))
+    fn merge_partitions_3d(use_maps: bool) -> Result<()> {
+        let mut mesh = test_mesh_3d().split().split();
+        mesh.add_boundary_faces();
+        mesh.partition_hilbert(3);
+
+        let mut parts = Vec::new();
+        let mut maps = Vec::new();
+        for part in mesh.extract_partitions(0) {
+            let mut local_mesh = part.mesh;
+            let (bdy_tags, _) = local_mesh.add_boundary_faces();
+            local_mesh.mut_ftags().for_each(|t| {
+                if bdy_tags.contains_key(t) {
+                    *t = -*t;
+                }
+            });
+            parts.push(local_mesh);
+            maps.push(part.parent_vert_ids);
+        }
+
+        let maps = if use_maps {
+            Some(maps.as_slice())
+        } else {
+            None
+        };
+        let mut merged = SimplexMesh::merge_partitions(&parts, maps, 1e-12)?;
+        assert_eq!(merged.n_verts(), mesh.n_verts());
+        assert_eq!(merged.n_elems(), mesh.n_elems());
+        assert_eq!(merged.n_faces(), mesh.n_faces());
+        assert!((merged.vol() - mesh.vol()).abs() < 1e-12);
+
+        merged.mut_etags().for_each(|t| *t = 1);
+        merged.compute_face_to_elems();
+        merged.check()?;
+
+        Ok(())
     }
 
-    /// Partition the mesh using metis into `n_parts`. The partition id, defined for all the elements
-    /// is stored in self.etags
-    #[cfg(feature = "metis")]
-    pub fn partition_metis(&mut self, n_parts: Idx, method: &str) -> Result<()> {
-        debug!("Partition the mesh into {} using metis", n_parts);
+    #[test]
+    fn test_merge_partitions_maps() -> Result<()> {
+        merge_partitions_3d(true)
+    }
+
+    #[test]
+    fn test_merge_partitions_tol() -> Result<()> {
+        merge_partitions_3d(false)
+    }
+
+    #[test]
+    fn test_partition_interfaces() -> Result<()> {
+        let mut mesh = test_mesh_2d().split().split().split().split();
+        mesh.compute_face_to_elems();
+        mesh.partition_rcb(4, false);
 
-        if self.etags().any(|t| t != 1) {
-            warn!("Erase the element tags");
+        let (ifc, vert_ids, tags) = mesh.partition_interfaces()?;
+        assert_eq!(tags.len(), 4);
+        assert_eq!(vert_ids.len(), ifc.n_verts() as usize);
+        for (&t, &(t0, t1)) in &tags {
+            assert!(t0 < t1);
+            let len = ifc.extract_tag(t).mesh.vol();
+            assert!((len - 0.5).abs() < 1e-12, "{t0}-{t1}: {len}");
         }
 
-        if n_parts == 1 {
-            self.mut_etags().for_each(|t| *t = 1);
-            return Ok(());
+        let q = mesh.partition_quality()?;
+        let n_faces = mesh.get_face_to_elems()?.len();
+        assert_eq!(
+            ifc.n_elems() as usize,
+            (q * n_faces as f64).round() as usize
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_repartition() -> Result<()> {
+        let mut mesh = test_mesh_2d().split().split().split().split().split();
+        mesh.partition_rcb(4, false);
+        mesh.compute_face_to_elems();
+
+        // Unbalance: move the elements with x < 0.25 and y > 0.5 from part 2 to part 1
+        let mut current = mesh.get_partition()?.to_vec();
+        for (t, ge) in current.iter_mut().zip(mesh.gelems()) {
+            let c = ge.center();
+            if c[0] < 0.25 && c[1] > 0.5 {
+                *t = 1;
+            }
         }
 
-        let mut partition = vec![0; self.n_elems() as usize];
-        let e2e = self.compute_elem_to_elems();
+        let n_migrated = mesh.repartition(4, &current)?;
 
-        let mut xadj: Vec<metis::Idx> = e2e
-            .ptr
-            .iter()
-            .copied()
-            .map(|x| x.try_into().unwrap())
-            .collect();
-        let mut adjncy: Vec<metis::Idx> = e2e
-            .indices
+        let mut counts = [0; 4];
+        mesh.get_partition()?
             .iter()
             .copied()
-            .map(|x| x.try_into().unwrap())
-            .collect();
+            .for_each(|t| counts[t as usize - 1] += 1);
+        let n_max = *counts.iter().max().unwrap();
+        let target = mesh.n_elems() as f64 / 4.0;
+        assert!(n_max as f64 <= 1.05 * target, "unbalanced: {counts:?}");
 
-        let graph = metis::Graph::new(1, n_parts as metis::Idx, &mut xadj, &mut adjncy);
-        match method {
-            "recursive" => graph.part_recursive(&mut partition).unwrap(),
-            "kway" => graph.part_kway(&mut partition).unwrap(),
-            _ => unreachable!("Unknown method"),
-        };
+        // about 1/4 of the elements of part 1 need to be moved
+        assert!(n_migrated as f64 <= 0.3 * target, "{n_migrated} migrated");
 
-        self.mut_etags()
-            .enumerate()
-            .for_each(|(i, t)| *t = partition[i] as Tag + 1);
+        let q = mesh.partition_quality()?;
+        assert!(q < 0.05, "failed, q = {q}");
 
         Ok(())
     }
 
-    /// Get the partition quality (ration of the number of interface faces to the total number of faces)
-    pub fn partition_quality(&self) -> Result<f64> {
-        let f2e = self.get_face_to_elems()?;
+    #[test]
+    fn test_partition_stats() -> Result<()> {
+        let mut mesh = test_mesh_2d().split().split().split().split();
+        mesh.compute_elem_to_elems();
+        mesh.partition_rcb(4, false);
 
-        let n = f2e
-            .iter()
-            .filter(|(_, v)| v.len() == 2 && self.etag(v[0]) != self.etag(v[1]))
-            .count();
-        Ok(n as f64 / f2e.len() as f64)
-    }
-}
+        let stats = mesh.partition_stats()?;
+        assert_eq!(stats.parts, [1, 2, 3, 4]);
+        assert_eq!(stats.n_elems, [128, 128, 128, 128]);
+        assert!((stats.imbalance - 1.0).abs() < 1e-12);
+        assert_eq!(stats.n_components, [1, 1, 1, 1]);
+        // 4 interfaces of length 0.5 with 8 edges each
+        assert_eq!(stats.edge_cut, 4 * 8);
+        assert_eq!(stats.n_interface_verts_total, 4 * 8 + 1);
+        assert_eq!(stats.n_interface_verts, [17, 17, 17, 17]);
+        let lines = stats.to_string();
+        let mut lines = lines.lines();
+        assert_eq!(
+            lines.next(),
+            Some("4 partitions, edge cut = 32, imbalance = 1.000, 33 interface vertices")
+        );
+        assert_eq!(
+            lines.next(),
+            Some("  partition 1: 128 elements, 1 component(s), 17 interface vertices")
+        );
+        assert_eq!(lines.count(), 3);
 
-#[cfg(test)]
-mod tests {
-    use crate::{
-        mesh::test_meshes::{test_mesh_2d, test_mesh_3d},
-        Result,
-    };
+        Ok(())
+    }
 
     #[test]
-    fn test_partition_hilbert_2d() -> Result<()> {
-        let mut mesh = test_mesh_2d().split().split().split().split().split();
+    fn test_fix_partition_contiguity() -> Result<()> {
+        let mut mesh = test_mesh_2d().split().split().split().split();
+        mesh.partition_rcb(4, false);
+
+        // create an island of partition 4 in partition 1
+        let mut n_island = 0;
+        let mut etags = mesh.get_partition()?.to_vec();
+        for (t, ge) in etags.iter_mut().zip(mesh.gelems()) {
+            let c = ge.center();
+            if (c[0] - 0.2).abs() < 0.1 && (c[1] - 0.2).abs() < 0.1 {
+                *t = 4;
+                n_island += 1;
+            }
+        }
+        mesh.set_partition(etags)?;
+
         mesh.compute_elem_to_elems();
-        mesh.partition_hilbert(4);
+        let stats = mesh.partition_stats()?;
+        assert_eq!(stats.n_components, [1, 1, 1, 2]);
 
-        let q = mesh.partition_quality()?;
-        assert!(q < 0.025, "failed, q = {q}");
+        let n = mesh.fix_partition_contiguity();
+        assert_eq!(n, n_island);
+
+        let stats = mesh.partition_stats()?;
+        assert_eq!(stats.n_components, [1, 1, 1, 1]);
+        assert_eq!(stats.n_elems, [128, 128, 128, 128]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_partition_hierarchical() -> Result<()> {
+        let mut mesh = test_mesh_3d().split().split().split();
+        let (nodes, cores) = mesh.partition_hierarchical(2, 3, PartitionType::Rcb)?;
+
+        let mut counts = [0; 6];
+        for ((&node, &core), &t) in nodes.iter().zip(cores.iter()).zip(mesh.get_partition()?) {
+            assert!((1..=2).contains(&node));
+            assert!((1..=3).contains(&core));
+            assert_eq!(t, 3 * (node - 1) + core);
+            counts[t as usize - 1] += 1;
+        }
+        let n_min = *counts.iter().min().unwrap();
+        let n_max = *counts.iter().max().unwrap();
+        assert!(n_max - n_min <= 2, "unbalanced: {counts:?}");
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_partition_by_region() -> Result<()> {
+        let mut mesh = test_mesh_2d().split().split().split().split();
+        // 2 regions, with 1/4 and 3/4 of the elements
+        let groups = mesh
+            .gelems()
+            .map(|ge| if ge.center()[0] < 0.25 { 1 } else { 2 })
+            .collect::<Vec<_>>();
+        mesh.partition_by_region(4, &groups, PartitionType::Hilbert)?;
+
+        let partition = mesh.get_partition()?;
+        for (&g, &t) in groups.iter().zip(partition.iter()) {
+            if g == 1 {
+                assert_eq!(t, 1);
+            } else {
+                assert!((2..=4).contains(&t));
+            }
+        }
+
+        let mut counts = [0; 4];
+        partition.iter().for_each(|&t| counts[t as usize - 1] += 1);
+        let n_min = *counts.iter().min().unwrap();
+        let n_max = *counts.iter().max().unwrap();
+        assert!(n_max - n_min <= 3, "unbalanced: {counts:?}");
 
         Ok(())
     }
@@ -232,6 +1734,32 @@ mod tests {
         Ok(())
     }
 
+    #[cfg(feature = "kahip")]
+    #[test]
+    fn test_partition_kahip_2d() -> Result<()> {
+        let mut mesh = test_mesh_2d().split().split().split().split().split();
+        mesh.compute_elem_to_elems();
+        mesh.partition_kahip(4, 0.03)?;
+
+        let q = mesh.partition_quality()?;
+        assert!(q < 0.03, "failed, q = {q}");
+
+        Ok(())
+    }
+
+    #[cfg(feature = "kahip")]
+    #[test]
+    fn test_partition_kahip_3d() -> Result<()> {
+        let mut mesh = test_mesh_3d().split().split().split().split();
+        mesh.compute_elem_to_elems();
+        mesh.partition_kahip(4, 0.03)?;
+
+        let q = mesh.partition_quality()?;
+        assert!(q < 0.025, "failed, q = {q}");
+
+        Ok(())
+    }
+
     #[cfg(feature = "metis")]
     #[test]
     fn test_partition_metis_2d() -> Result<()> {
@@ -283,4 +1811,152 @@ mod tests {
 
         Ok(())
     }
+
+    #[cfg(feature = "metis")]
+    #[test]
+    fn test_partition_metis_weighted_2d() -> Result<()> {
+        let mut mesh = test_mesh_2d().split().split().split().split().split();
+        mesh.compute_elem_to_elems();
+
+        // elements with x < 0.5 are 4 times more expensive
+        let weights = mesh
+            .gelems()
+            .map(|ge| if ge.center()[0] < 0.5 { 4.0 } else { 1.0 })
+            .collect::<Vec<_>>();
+        mesh.partition_metis_weighted(4, "kway", Some(&weights), None)?;
+
+        let mut loads = [0.0; 4];
+        for (t, w) in mesh.get_partition()?.iter().copied().zip(weights.iter()) {
+            loads[t as usize - 1] += w;
+        }
+        let total = loads.iter().sum::<f64>();
+        for l in loads {
+            assert!(
+                (l - 0.25 * total).abs() < 0.05 * total,
+                "unbalanced: {loads:?}"
+            );
+        }
+
+        let q = mesh.partition_quality()?;
+        assert!(q < 0.05, "failed, q = {q}");
+
+        Ok(())
+    }
+
+    #[cfg(feature = "scotch")]
+    #[test]
+    fn test_partition_scotch_weighted_2d() -> Result<()> {
+        let mut mesh = test_mesh_2d().split().split().split().split().split();
+        mesh.compute_elem_to_elems();
+
+        let weights = mesh
+            .gelems()
+            .map(|ge| if ge.center()[0] < 0.5 { 4.0 } else { 1.0 })
+            .collect::<Vec<_>>();
+        let n_edges = mesh.get_elem_to_elems()?.indices.len();
+        let edge_weights = vec![1.0; n_edges];
+        mesh.partition_scotch_weighted(4, Some(&weights), Some(&edge_weights))?;
+
+        let mut loads = [0.0; 4];
+        for (t, w) in mesh.get_partition()?.iter().copied().zip(weights.iter()) {
+            loads[t as usize - 1] += w;
+        }
+        let total = loads.iter().sum::<f64>();
+        for l in loads {
+            assert!(
+                (l - 0.25 * total).abs() < 0.05 * total,
+                "unbalanced: {loads:?}"
+            );
+        }
+
+        Ok(())
+    }
+
+    #[cfg(feature = "metis")]
+    #[test]
+    fn test_partition_metis_invalid_weights() {
+        let mut mesh = test_mesh_2d().split();
+        let weights = vec![1.0; 3];
+        assert!(mesh
+            .partition_metis_weighted(2, "kway", Some(&weights), None)
+            .is_err());
+    }
+
+    #[cfg(feature = "metis")]
+    #[test]
+    fn test_partition_metis_asymmetric_weights() -> Result<()> {
+        let mut mesh = test_mesh_2d().split().split();
+        let e2e = mesh.compute_elem_to_elems();
+        let n_edges = e2e.indices.len();
+        let mut edge_weights = vec![1.0; n_edges];
+        mesh.partition_metis_weighted(2, "kway", None, Some(&edge_weights))?;
+
+        // w(i, j) != w(j, i)
+        edge_weights[0] = 2.0;
+        assert!(mesh
+            .partition_metis_weighted(2, "kway", None, Some(&edge_weights))
+            .is_err());
+
+        Ok(())
+    }
+
+    #[cfg(feature = "metis")]
+    #[test]
+    fn test_partition_metis_multi() -> Result<()> {
+        let mut mesh = test_mesh_2d().split().split().split().split().split();
+
+        // first constraint: all the elements, second constraint: elements with x < 0.25
+        let w0 = vec![1.0; mesh.n_elems() as usize];
+        let w1 = mesh
+            .gelems()
+            .map(|ge| if ge.center()[0] < 0.25 { 1.0 } else { 0.0 })
+            .collect::<Vec<_>>();
+        mesh.partition_metis_multi(4, &[w0.clone(), w1.clone()])?;
+
+        for w in [w0, w1] {
+            let mut loads = [0.0; 4];
+            for (t, x) in mesh.get_partition()?.iter().copied().zip(w.iter()) {
+                loads[t as usize - 1] += x;
+            }
+            let total = loads.iter().sum::<f64>();
+            for l in loads {
+                assert!(
+                    (l - 0.25 * total).abs() < 0.1 * total,
+                    "unbalanced: {loads:?}"
+                );
+            }
+        }
+
+        Ok(())
+    }
+
+    #[cfg(feature = "metis")]
+    #[test]
+    fn test_partition_vertices_metis() -> Result<()> {
+        use crate::mesh::Elem;
+
+        let mut mesh = test_mesh_3d().split().split().split();
+        mesh.partition_vertices_metis(4)?;
+
+        let vert_partition = mesh.get_vertex_partition()?;
+        let mut counts = [0; 4];
+        vert_partition
+            .iter()
+            .for_each(|&t| counts[t as usize - 1] += 1);
+        let avg = mesh.n_verts() as f64 / 4.0;
+        for c in counts {
+            assert!(
+                (f64::from(c) - avg).abs() < 0.05 * avg,
+                "unbalanced: {counts:?}"
+            );
+        }
+
+        // element ownership is consistent with the vertex ownership
+        for (e, &t) in mesh.elems().zip(mesh.get_partition()?.iter()) {
+            let i = *e.iter().min().unwrap();
+            assert_eq!(vert_partition[i as usize], t);
+        }
+
+        Ok(())
+    }
 }
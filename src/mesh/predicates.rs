@@ -0,0 +1,200 @@
+use crate::mesh::Point;
+
+/// Half of the machine epsilon
+const EPS: f64 = f64::EPSILON / 2.0;
+
+/// Error bound for the floating point evaluation of `orient2d`
+const ORIENT2D_ERR: f64 = (3.0 + 16.0 * EPS) * EPS;
+
+/// Error bound for the floating point evaluation of `orient3d`
+const ORIENT3D_ERR: f64 = (7.0 + 56.0 * EPS) * EPS;
+
+/// Exact sum: `x + y = a + b` with `x = fl(a + b)`
+fn two_sum(a: f64, b: f64) -> (f64, f64) {
+    let x = a + b;
+    let bv = x - a;
+    let av = x - bv;
+    (x, (a - av) + (b - bv))
+}
+
+/// Exact product: `x + y = a * b` with `x = fl(a * b)`
+fn two_product(a: f64, b: f64) -> (f64, f64) {
+    let x = a * b;
+    (x, a.mul_add(b, -x))
+}
+
+/// Exact difference `a - b` as an expansion
+fn diff(a: f64, b: f64) -> Vec<f64> {
+    let (x, y) = two_sum(a, -b);
+    vec![y, x]
+}
+
+/// Add `b` to expansion `e`
+fn grow_expansion(e: &[f64], b: f64) -> Vec<f64> {
+    let mut q = b;
+    let mut res = Vec::with_capacity(e.len() + 1);
+    for &x in e {
+        let (s, err) = two_sum(q, x);
+        res.push(err);
+        q = s;
+    }
+    res.push(q);
+    res
+}
+
+/// Sum of two expansions
+fn sum(e: &[f64], f: &[f64]) -> Vec<f64> {
+    f.iter().fold(e.to_vec(), |res, &b| grow_expansion(&res, b))
+}
+
+/// Product of two expansions
+fn product(e: &[f64], f: &[f64]) -> Vec<f64> {
+    let mut res = Vec::new();
+    for &a in e {
+        for &b in f {
+            let (x, y) = two_product(a, b);
+            res = sum(&res, &[y, x]);
+        }
+    }
+    res
+}
+
+/// Opposite of an expansion
+fn neg(e: &[f64]) -> Vec<f64> {
+    e.iter().map(|x| -x).collect()
+}
+
+/// Sign of an expansion, given by its largest non-zero component
+fn sign(e: &[f64]) -> i32 {
+    e.iter()
+        .rev()
+        .find(|x| x.abs() > 0.0)
+        .map_or(0, |&x| if x > 0.0 { 1 } else { -1 })
+}
+
+/// Sign of a floating point value
+fn fsign(x: f64) -> i32 {
+    if x > 0.0 {
+        1
+    } else {
+        -1
+    }
+}
+
+/// Orientation of the 2D points `p[0], p[1], p[2]` given by their coordinates `(x[i], y[i])`:
+/// 1 if counterclockwise, -1 if clockwise and 0 if they are collinear.
+///
+/// As for `orient3d`, the result is exact: the orientation is first computed in floating point,
+/// and recomputed with exact expansion arithmetic (Shewchuk, "Adaptive Precision Floating-Point
+/// Arithmetic and Fast Robust Geometric Predicates", 1997) if the result cannot be trusted
+fn orient2d_xy(x: [f64; 3], y: [f64; 3]) -> i32 {
+    let (acx, bcx) = (x[0] - x[2], x[1] - x[2]);
+    let (acy, bcy) = (y[0] - y[2], y[1] - y[2]);
+    let l = acx * bcy;
+    let r = acy * bcx;
+    let det = l - r;
+    if det.abs() > ORIENT2D_ERR * (l.abs() + r.abs()) {
+        return fsign(det);
+    }
+
+    let (acx, bcx) = (diff(x[0], x[2]), diff(x[1], x[2]));
+    let (acy, bcy) = (diff(y[0], y[2]), diff(y[1], y[2]));
+    sign(&sum(&product(&acx, &bcy), &neg(&product(&acy, &bcx))))
+}
+
+/// Orientation of the 3D points `a`, `b`, `c` projected on a coordinate plane, i.e. with
+/// coordinate `axis` ignored
+#[must_use]
+pub fn orient2d_projected(a: &Point<3>, b: &Point<3>, c: &Point<3>, axis: usize) -> i32 {
+    let (i, j) = ((axis + 1) % 3, (axis + 2) % 3);
+    orient2d_xy([a[i], b[i], c[i]], [a[j], b[j], c[j]])
+}
+
+/// Orientation of the 3D points `a`, `b`, `c`, `d`: the sign of the determinant
+/// ```math
+/// \det(a - d, b - d, c - d)
+/// ```
+/// i.e. 1 if `d` is below the plane of `a`, `b`, `c` (oriented counterclockwise)
+#[must_use]
+pub fn orient3d(a: &Point<3>, b: &Point<3>, c: &Point<3>, d: &Point<3>) -> i32 {
+    let (ad, bd, cd) = (a - d, b - d, c - d);
+    let m0 = bd[1] * cd[2] - bd[2] * cd[1];
+    let m1 = cd[1] * ad[2] - cd[2] * ad[1];
+    let m2 = ad[1] * bd[2] - ad[2] * bd[1];
+    let det = ad[0] * m0 + bd[0] * m1 + cd[0] * m2;
+    let permanent = ad[0].abs() * ((bd[1] * cd[2]).abs() + (bd[2] * cd[1]).abs())
+        + bd[0].abs() * ((cd[1] * ad[2]).abs() + (cd[2] * ad[1]).abs())
+        + cd[0].abs() * ((ad[1] * bd[2]).abs() + (ad[2] * bd[1]).abs());
+    if det.abs() > ORIENT3D_ERR * permanent {
+        return fsign(det);
+    }
+
+    let ad = (0..3).map(|i| diff(a[i], d[i])).collect::<Vec<_>>();
+    let bd = (0..3).map(|i| diff(b[i], d[i])).collect::<Vec<_>>();
+    let cd = (0..3).map(|i| diff(c[i], d[i])).collect::<Vec<_>>();
+    let minor =
+        |u: &[Vec<f64>], v: &[Vec<f64>]| sum(&product(&u[1], &v[2]), &neg(&product(&u[2], &v[1])));
+    let det = sum(
+        &sum(
+            &product(&ad[0], &minor(&bd, &cd)),
+            &product(&bd[0], &minor(&cd, &ad)),
+        ),
+        &product(&cd[0], &minor(&ad, &bd)),
+    );
+    sign(&det)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{orient2d_projected, orient3d};
+    use crate::mesh::Point;
+
+    #[test]
+    fn test_orient2d() {
+        let orient2d = |a: [f64; 2], b: [f64; 2], c: [f64; 2]| {
+            orient2d_projected(
+                &Point::<3>::new(a[0], a[1], 1.0),
+                &Point::<3>::new(b[0], b[1], 2.0),
+                &Point::<3>::new(c[0], c[1], 3.0),
+                2,
+            )
+        };
+        let a = [12.0, 12.0];
+        let b = [24.0, 24.0];
+        assert_eq!(orient2d(a, b, [0.0, 1.0]), 1);
+        assert_eq!(orient2d(a, b, [1.0, 0.0]), -1);
+
+        // points very close to the line, where the floating point evaluation fails
+        let delta = f64::EPSILON;
+        assert_eq!(orient2d([0.5, 0.5], a, b), 0);
+        assert_eq!(orient2d([0.5 + delta, 0.5], a, b), -1);
+        assert_eq!(orient2d([0.5, 0.5 + delta], a, b), 1);
+    }
+
+    #[test]
+    fn test_orient3d() {
+        let a = Point::<3>::new(0.0, 0.0, 0.0);
+        let b = Point::<3>::new(1.0, 0.0, 0.0);
+        let c = Point::<3>::new(0.0, 1.0, 0.0);
+        assert_eq!(orient3d(&a, &b, &c, &Point::<3>::new(0.2, 0.2, -1.0)), 1);
+        assert_eq!(orient3d(&a, &b, &c, &Point::<3>::new(0.2, 0.2, 1.0)), -1);
+
+        // points very close to a tilted plane
+        let a = Point::<3>::new(12.0, 12.0, 0.0);
+        let b = Point::<3>::new(24.0, 24.0, 0.0);
+        let c = Point::<3>::new(12.0, 12.0, 1.0);
+        let delta = f64::EPSILON;
+        assert_eq!(orient3d(&a, &b, &c, &Point::<3>::new(0.5, 0.5, 0.3)), 0);
+        let s = orient3d(&a, &b, &c, &Point::<3>::new(0.5 + delta, 0.5, 0.3));
+        assert_ne!(s, 0);
+        assert_eq!(
+            orient3d(&a, &b, &c, &Point::<3>::new(0.5, 0.5 + delta, 0.3)),
+            -s
+        );
+
+        assert_eq!(
+            orient2d_projected(&a, &b, &Point::<3>::new(0.5 + delta, 0.5, 0.3), 2),
+            -1
+        );
+    }
+}
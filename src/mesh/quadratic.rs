@@ -0,0 +1,218 @@
+use crate::{
+    geometry::Geometry,
+    mesh::{
+        Edge, EdgeQuadratique, Elem, SimplexMesh, Tetrahedron, TetrahedronQuadratique, Triangle,
+        TriangleQuadratique,
+    },
+    Result,
+};
+use log::debug;
+
+impl<const D: usize> SimplexMesh<D, Triangle> {
+    /// Create a second order mesh by adding a node at the center of each edge. The mid-edge
+    /// nodes on the boundary are projected onto `geom` as in `split_projected`, so the
+    /// boundary faces are curved. The topology is computed if not available
+    pub fn to_quadratic<G: Geometry<D>>(
+        &self,
+        geom: &G,
+    ) -> Result<SimplexMesh<D, TriangleQuadratique>> {
+        debug!("Create a second order mesh");
+
+        let verts = self.split_projected(geom)?.verts().collect();
+        let edges = self.split_vertex_indices();
+        let mid = |e: &Triangle, i| edges[&e.sorted_edge(i)];
+        let tri = |e: Triangle| {
+            TriangleQuadratique::new(e[0], e[1], e[2], mid(&e, 1), mid(&e, 2), mid(&e, 0))
+        };
+
+        let elems = self.elems().map(tri).collect();
+        let faces = self
+            .faces()
+            .map(|f| EdgeQuadratique::new(f[0], f[1], edges[&f.sorted_edge(0)]))
+            .collect();
+
+        let mut res = SimplexMesh::new(
+            verts,
+            elems,
+            self.etags().collect(),
+            faces,
+            self.ftags().collect(),
+        );
+        *res.tag_names_mut() = self.tag_names().clone();
+
+        Ok(res)
+    }
+}
+
+impl SimplexMesh<3, Tetrahedron> {
+    /// Create a second order mesh by adding a node at the center of each edge. The mid-edge
+    /// nodes on the boundary are projected onto `geom` as in `split_projected`, so the
+    /// boundary faces are curved. The topology is computed if not available
+    pub fn to_quadratic<G: Geometry<3>>(
+        &self,
+        geom: &G,
+    ) -> Result<SimplexMesh<3, TetrahedronQuadratique>> {
+        debug!("Create a second order mesh");
+
+        let verts = self.split_projected(geom)?.verts().collect();
+        let edges = self.split_vertex_indices();
+        let mid = |e: &Tetrahedron, i| edges[&e.sorted_edge(i)];
+        let mid_f = |f: &Triangle, i| edges[&f.sorted_edge(i)];
+        let tri = |f: Triangle| {
+            TriangleQuadratique::new(f[0], f[1], f[2], mid_f(&f, 1), mid_f(&f, 2), mid_f(&f, 0))
+        };
+
+        let elems = self
+            .elems()
+            .map(|e| {
+                TetrahedronQuadratique::new([
+                    e[0],
+                    e[1],
+                    e[2],
+                    e[3],
+                    mid(&e, 5),
+                    mid(&e, 4),
+                    mid(&e, 1),
+                    mid(&e, 2),
+                    mid(&e, 3),
+                    mid(&e, 0),
+                ])
+            })
+            .collect();
+        let faces = self.faces().map(tri).collect();
+
+        let mut res = SimplexMesh::new(
+            verts,
+            elems,
+            self.etags().collect(),
+            faces,
+            self.ftags().collect(),
+        );
+        *res.tag_names_mut() = self.tag_names().clone();
+
+        Ok(res)
+    }
+}
+
+impl<const D: usize> SimplexMesh<D, TriangleQuadratique> {
+    /// Create a first order mesh by removing the mid-edge nodes
+    #[must_use]
+    pub fn to_linear(&self) -> SimplexMesh<D, Triangle> {
+        debug!("Create a first order mesh");
+
+        let mut res = SimplexMesh::new(
+            self.verts().collect(),
+            self.elems()
+                .map(|e| Triangle::new(e[0], e[1], e[2]))
+                .collect(),
+            self.etags().collect(),
+            self.faces().map(|f| Edge::new(f[0], f[1])).collect(),
+            self.ftags().collect(),
+        );
+        *res.tag_names_mut() = self.tag_names().clone();
+        res.remove_unused_vertices();
+
+        res
+    }
+}
+
+impl SimplexMesh<3, TetrahedronQuadratique> {
+    /// Create a first order mesh by removing the mid-edge nodes
+    #[must_use]
+    pub fn to_linear(&self) -> SimplexMesh<3, Tetrahedron> {
+        debug!("Create a first order mesh");
+
+        let mut res = SimplexMesh::new(
+            self.verts().collect(),
+            self.elems()
+                .map(|e| Tetrahedron::new(e[0], e[1], e[2], e[3]))
+                .collect(),
+            self.etags().collect(),
+            self.faces()
+                .map(|f| Triangle::new(f[0], f[1], f[2]))
+                .collect(),
+            self.ftags().collect(),
+        );
+        *res.tag_names_mut() = self.tag_names().clone();
+        res.remove_unused_vertices();
+
+        res
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        geometry::NoGeometry,
+        mesh::{
+            test_meshes::{sphere_mesh, test_mesh_2d, SphereGeometry},
+            Elem, GElem,
+        },
+        metric::IsoMetric,
+        remesher::Remesher,
+        Idx, Result,
+    };
+
+    #[test]
+    fn test_quadratic_2d() -> Result<()> {
+        let mesh = test_mesh_2d().split();
+        let n_edges = mesh.split_vertex_indices().len() as Idx;
+
+        let quad = mesh.to_quadratic(&NoGeometry())?;
+        assert_eq!(quad.n_verts(), mesh.n_verts() + n_edges);
+        assert_eq!(quad.n_elems(), mesh.n_elems());
+        assert_eq!(quad.n_faces(), mesh.n_faces());
+
+        for e in quad.elems() {
+            for (i, j, k) in [(3, 1, 2), (4, 2, 0), (5, 0, 1)] {
+                let p = 0.5 * (quad.vert(e[j]) + quad.vert(e[k]));
+                assert!((quad.vert(e[i]) - p).norm() < 1e-12);
+            }
+        }
+        assert!((quad.vol() - mesh.vol()).abs() < 1e-12);
+
+        let lin = quad.to_linear();
+        assert_eq!(lin.n_verts(), mesh.n_verts());
+        assert_eq!(lin.n_elems(), mesh.n_elems());
+        assert_eq!(lin.n_faces(), mesh.n_faces());
+        assert!((lin.vol() - mesh.vol()).abs() < 1e-12);
+
+        // only first order elements can be remeshed
+        let h = vec![IsoMetric::<2>::from(1.0); quad.n_verts() as usize];
+        assert!(Remesher::new(&quad, &h, &NoGeometry()).is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_quadratic_sphere() -> Result<()> {
+        let mesh = sphere_mesh(1);
+        let quad = mesh.to_quadratic(&SphereGeometry)?;
+
+        // all the boundary nodes are on the sphere
+        for f in quad.faces() {
+            for i in f {
+                assert!((quad.vert(i).norm() - 1.0).abs() < 1e-12);
+            }
+        }
+
+        // the mid-edge nodes are interpolated
+        for e in quad.elems() {
+            let ge = quad.gelem(e);
+            for i_edge in 0..6 {
+                let edg = e.edge(i_edge);
+                let mut x = [0.0; 4];
+                x[e.vertex_index(edg[0]) as usize] = 0.5;
+                x[e.vertex_index(edg[1]) as usize] = 0.5;
+                let p = ge.point(&x);
+                assert!((p - quad.vert(e.mid_node(i_edge))).norm() < 1e-12);
+            }
+        }
+
+        let lin = quad.to_linear();
+        assert_eq!(lin.n_verts(), mesh.n_verts());
+        assert_eq!(lin.n_elems(), mesh.n_elems());
+
+        Ok(())
+    }
+}
@@ -0,0 +1,260 @@
+use crate::{
+    mesh::{Elem, GElem, Point, SimplexMesh},
+    Error, Idx, Result, Tag,
+};
+use log::debug;
+use rayon::iter::ParallelIterator;
+
+/// Quadrature rule on a simplex: barycentric coordinates and weights (that sum to 1)
+type QuadratureRule = Vec<(Vec<f64>, f64)>;
+
+/// All the distinct permutations of `x`
+fn permutations(x: &[f64]) -> Vec<Vec<f64>> {
+    if x.len() <= 1 {
+        return vec![x.to_vec()];
+    }
+    let mut res: Vec<Vec<f64>> = Vec::new();
+    for i in 0..x.len() {
+        let mut rest = x.to_vec();
+        let xi = rest.remove(i);
+        for mut p in permutations(&rest) {
+            p.insert(0, xi);
+            if !res
+                .iter()
+                .any(|q| q.iter().zip(p.iter()).all(|(a, b)| (a - b).abs() < 1e-15))
+            {
+                res.push(p);
+            }
+        }
+    }
+    res
+}
+
+/// Add the points obtained by permuting the barycentric coordinates `x`, with weight `w`
+fn add_orbit(rule: &mut QuadratureRule, x: &[f64], w: f64) {
+    rule.extend(permutations(x).into_iter().map(|p| (p, w)));
+}
+
+/// Quadrature rule exact for polynomials of degree `order` (1 to 4) on simplices of
+/// dimension `dim` (1 to 3)
+fn quadrature_rule(dim: Idx, order: usize) -> Result<QuadratureRule> {
+    let mut rule = Vec::new();
+    match (dim, order) {
+        (1, 1) => add_orbit(&mut rule, &[0.5, 0.5], 1.0),
+        (1, 2 | 3) => {
+            let a = 0.5 * (1.0 + 1.0 / f64::sqrt(3.0));
+            add_orbit(&mut rule, &[a, 1.0 - a], 0.5);
+        }
+        (1, 4) => {
+            let a = 0.5 * (1.0 + f64::sqrt(0.6));
+            add_orbit(&mut rule, &[0.5, 0.5], 4.0 / 9.0);
+            add_orbit(&mut rule, &[a, 1.0 - a], 5.0 / 18.0);
+        }
+        (2, 1) => add_orbit(&mut rule, &[1.0 / 3.0; 3], 1.0),
+        (2, 2) => add_orbit(&mut rule, &[2.0 / 3.0, 1.0 / 6.0, 1.0 / 6.0], 1.0 / 3.0),
+        (2, 3 | 4) => {
+            // Dunavant, degree 4
+            let a = 0.445_948_490_915_965;
+            add_orbit(&mut rule, &[a, a, 1.0 - 2.0 * a], 0.223_381_589_678_011);
+            let a = 0.091_576_213_509_771;
+            add_orbit(&mut rule, &[a, a, 1.0 - 2.0 * a], 0.109_951_743_655_322);
+        }
+        (3, 1) => add_orbit(&mut rule, &[0.25; 4], 1.0),
+        (3, 2) => {
+            let a = 0.138_196_601_125_010_5;
+            add_orbit(&mut rule, &[1.0 - 3.0 * a, a, a, a], 0.25);
+        }
+        (3, 3) => {
+            add_orbit(&mut rule, &[0.25; 4], -0.8);
+            add_orbit(&mut rule, &[0.5, 1.0 / 6.0, 1.0 / 6.0, 1.0 / 6.0], 0.45);
+        }
+        (3, 4) => {
+            // Keast, degree 4
+            add_orbit(&mut rule, &[0.25; 4], -0.078_933_333_333_333_33);
+            let a = 1.0 / 14.0;
+            add_orbit(
+                &mut rule,
+                &[1.0 - 3.0 * a, a, a, a],
+                0.045_733_333_333_333_33,
+            );
+            let a = 0.399_403_576_166_799;
+            let b = 0.5 - a;
+            add_orbit(&mut rule, &[a, a, b, b], 0.149_333_333_333_333_3);
+        }
+        _ => {
+            return Err(Error::from(&format!(
+                "no quadrature rule of order {order} for simplices of dimension {dim}"
+            )))
+        }
+    }
+    Ok(rule)
+}
+
+/// Integrate `f` on a simplex given by its vertices and its volume
+fn integrate_simplex<const D: usize, F: Fn(&Point<D>) -> f64>(
+    pts: &[Point<D>],
+    vol: f64,
+    rule: &QuadratureRule,
+    f: &F,
+) -> f64 {
+    vol * rule
+        .iter()
+        .map(|(x, w)| {
+            let p = pts
+                .iter()
+                .zip(x.iter())
+                .fold(Point::<D>::zeros(), |a, (p, c)| a + *c * p);
+            w * f(&p)
+        })
+        .sum::<f64>()
+}
+
+impl<const D: usize, E: Elem> SimplexMesh<D, E> {
+    /// Integrate a function over the mesh elements, using a quadrature rule exact for
+    /// polynomials of degree `order` ($`1 \le order \le 4`$)
+    pub fn integrate<F: Fn(&Point<D>) -> f64 + Sync>(&self, f: F, order: usize) -> Result<f64> {
+        debug!("Integrate a function over the mesh (order = {order})");
+
+        let rule = quadrature_rule(E::DIM, order)?;
+        let res = self
+            .par_elems()
+            .map(|e| {
+                let pts = e.iter().map(|&i| self.vert(i)).collect::<Vec<_>>();
+                integrate_simplex(&pts, self.gelem(e).vol(), &rule, &f)
+            })
+            .sum();
+
+        Ok(res)
+    }
+
+    /// Integrate a (P1) field defined at the mesh vertices over the mesh elements
+    pub fn integrate_field(&self, v: &[f64]) -> Result<f64> {
+        debug!("Integrate a vertex field over the mesh");
+
+        if v.len() != self.n_verts() as usize {
+            return Err(Error::from("invalid field size"));
+        }
+
+        let res = self
+            .par_elems()
+            .map(|e| {
+                let mean = e.iter().map(|&i| v[i as usize]).sum::<f64>() / E::N_VERTS as f64;
+                self.gelem(e).vol() * mean
+            })
+            .sum();
+
+        Ok(res)
+    }
+
+    /// Integrate a function over the faces tagged with `tag`, using a quadrature rule exact
+    /// for polynomials of degree `order` ($`1 \le order \le 4`$)
+    pub fn integrate_on_tag<F: Fn(&Point<D>) -> f64>(
+        &self,
+        tag: Tag,
+        f: F,
+        order: usize,
+    ) -> Result<f64> {
+        debug!("Integrate a function over the faces tagged {tag} (order = {order})");
+
+        if E::Face::DIM == 0 {
+            return Err(Error::from("cannot integrate on vertices"));
+        }
+        let rule = quadrature_rule(E::Face::DIM, order)?;
+        let res = self
+            .faces()
+            .zip(self.ftags())
+            .filter(|(_, t)| *t == tag)
+            .map(|(face, _)| {
+                let pts = face.iter().map(|&i| self.vert(i)).collect::<Vec<_>>();
+                integrate_simplex(&pts, self.gface(face).vol(), &rule, &f)
+            })
+            .sum();
+
+        Ok(res)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::quadrature_rule;
+    use crate::{
+        mesh::{
+            test_meshes::{test_mesh_2d, test_mesh_3d},
+            Point,
+        },
+        Result,
+    };
+
+    #[test]
+    fn test_rules() -> Result<()> {
+        for dim in 1..4 {
+            for order in 1..5 {
+                let rule = quadrature_rule(dim, order)?;
+                let w = rule.iter().map(|(_, w)| w).sum::<f64>();
+                assert!((w - 1.0).abs() < 1e-12);
+                assert!(rule.iter().all(|(x, _)| x.len() == dim as usize + 1
+                    && (x.iter().sum::<f64>() - 1.0).abs() < 1e-12));
+            }
+        }
+        assert!(quadrature_rule(2, 5).is_err());
+        assert!(quadrature_rule(2, 0).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_integrate_2d() -> Result<()> {
+        let mesh = test_mesh_2d().split();
+
+        // x^a y^b over the unit square
+        let exact = |a: i32, b: i32| 1.0 / f64::from((a + 1) * (b + 1));
+        for order in 1..5 {
+            for a in 0..=order {
+                let b = order - a;
+                let res = mesh.integrate(|p| p[0].powi(a as i32) * p[1].powi(b as i32), order)?;
+                assert!((res - exact(a as i32, b as i32)).abs() < 1e-12);
+            }
+        }
+
+        let v = mesh
+            .verts()
+            .map(|p| 1.0 + p[0] + 2.0 * p[1])
+            .collect::<Vec<_>>();
+        assert!((mesh.integrate_field(&v)? - 2.5).abs() < 1e-12);
+        assert!(mesh.integrate_field(&v[1..]).is_err());
+
+        // boundary y = 0
+        let res = mesh.integrate_on_tag(1, |p| p[0].powi(3), 4)?;
+        assert!((res - 0.25).abs() < 1e-12);
+        let res = mesh.integrate_on_tag(1, |p| p[0].powi(4), 4)?;
+        assert!((res - 0.2).abs() < 1e-12);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_integrate_3d() -> Result<()> {
+        let mesh = test_mesh_3d().split();
+
+        let exact = |a: i32, b: i32, c: i32| 1.0 / f64::from((a + 1) * (b + 1) * (c + 1));
+        for order in 1..5 {
+            for a in 0..=order {
+                for b in 0..=order - a {
+                    let c = order - a - b;
+                    let (a, b, c) = (a as i32, b as i32, c as i32);
+                    let res = mesh.integrate(
+                        |p: &Point<3>| p[0].powi(a) * p[1].powi(b) * p[2].powi(c),
+                        order,
+                    )?;
+                    assert!((res - exact(a, b, c)).abs() < 1e-12);
+                }
+            }
+        }
+
+        let area = (1..7)
+            .map(|tag| mesh.integrate_on_tag(tag, |_| 1.0, 1))
+            .sum::<Result<f64>>()?;
+        assert!((area - 6.0).abs() < 1e-12);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,470 @@
+use crate::{
+    mesh::{Elem, GElem, Point, SimplexMesh},
+    metric::{IsoMetric, Metric},
+    Error, Idx, Result,
+};
+use log::debug;
+use nalgebra::SMatrix;
+use rayon::prelude::*;
+use serde::Serialize;
+use std::fmt;
+
+/// Inverse of the Jacobian of the transformation from the orthogonal element to the
+/// unit-length equilateral element (the upper-left 2x2 block is used in 2D)
+const J_EQ: [[f64; 3]; 3] = [
+    [1.0, -0.577_350_269_189_625_8, -0.408_248_290_463_863],
+    [0.0, 1.154_700_538_379_251_5, -0.408_248_290_463_863],
+    [0.0, 0.0, 1.224_744_871_391_589],
+];
+
+/// Element quality measures
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum QualityMetric {
+    /// Mean ratio, in [0, 1], as used by the remesher (see `GElem::quality`)
+    MeanRatio,
+    /// Ratio of the inscribed radius to the circumradius, normalized to be in [0, 1]
+    RadiusRatio,
+    /// Minimum dihedral angle (in degrees)
+    MinDihedralAngle,
+    /// Maximum dihedral angle (in degrees)
+    MaxDihedralAngle,
+    /// Condition number of the Jacobian $`J`$ of the transformation from the unit-length
+    /// equilateral element, in $`[1, \infty]`$
+    /// ```math
+    /// \kappa = \frac{||J||_F ||J^{-1}||_F}{d}
+    /// ```
+    ConditionNumber,
+    /// Equiangular skewness, in [0, 1]
+    /// ```math
+    /// \max\left(\frac{\theta_{max} - \theta_e}{180 - \theta_e}, \frac{\theta_e - \theta_{min}}{\theta_e}\right)
+    /// ```
+    /// where $`\theta_e`$ is the dihedral angle of the equilateral element
+    Skewness,
+}
+
+impl QualityMetric {
+    /// Value of the measure for a degenerate element
+    const fn degenerate(self) -> f64 {
+        match self {
+            Self::MeanRatio | Self::RadiusRatio | Self::MinDihedralAngle => 0.0,
+            Self::MaxDihedralAngle => 180.0,
+            Self::ConditionNumber => f64::INFINITY,
+            Self::Skewness => 1.0,
+        }
+    }
+}
+
+/// Histogram of the element qualities, with uniform bins in [0, 1]
+#[derive(Clone, Debug, Serialize)]
+pub struct QualityHistogram {
+    /// The bounds of the bins (length n + 1)
+    pub bins: Vec<f64>,
+    /// The # of elements in each bin (length n); the elements with a negative or undefined
+    /// quality (e.g. inverted elements) are counted in the first bin
+    pub counts: Vec<Idx>,
+    /// The minimum quality
+    pub min: f64,
+    /// The mean quality
+    pub mean: f64,
+}
+
+impl fmt::Display for QualityHistogram {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "q_min = {:.2}, q_mean = {:.2}", self.min, self.mean)?;
+        for (i, count) in self.counts.iter().enumerate().filter(|(_, &c)| c > 0) {
+            write!(
+                f,
+                ", [{:.2}, {:.2}]: {count}",
+                self.bins[i],
+                self.bins[i + 1]
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// Quality information on an element
+#[derive(Clone, Copy, Debug, Serialize)]
+pub struct ElemQuality {
+    /// The element index
+    pub elem: Idx,
+    /// The quality in the Euclidean space
+    pub quality: f64,
+    /// The quality in the metric space
+    pub metric_quality: f64,
+    /// The minimum dihedral angle (in degrees), i.e. the minimum angle between two faces
+    pub min_angle: f64,
+    /// The maximum dihedral angle (in degrees)
+    pub max_angle: f64,
+}
+
+impl fmt::Display for ElemQuality {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "element {}: q = {:.3}, q_m = {:.3}, angles in [{:.1}, {:.1}]",
+            self.elem, self.quality, self.metric_quality, self.min_angle, self.max_angle
+        )
+    }
+}
+
+/// Minimum and maximum dihedral angles (in degrees) of an element, computed from the outward
+/// normals of its faces
+fn dihedral_angles<const D: usize, E: Elem>(ge: &E::Geom<D, IsoMetric<D>>) -> (f64, f64) {
+    let c = ge.center();
+    let normals = (0..E::N_FACES)
+        .map(|i| {
+            let gf = ge.gface(i);
+            let n = gf.normal();
+            if n.dot(&(gf.center() - c)) < 0.0 {
+                -n
+            } else {
+                n
+            }
+        })
+        .collect::<Vec<_>>();
+
+    let mut min_angle = f64::MAX;
+    let mut max_angle = 0.0_f64;
+    for (i, n0) in normals.iter().enumerate() {
+        for n1 in &normals[i + 1..] {
+            let a = 180.0 - n0.dot(n1).clamp(-1.0, 1.0).acos().to_degrees();
+            min_angle = min_angle.min(a);
+            max_angle = max_angle.max(a);
+        }
+    }
+    (min_angle, max_angle)
+}
+
+/// Compute the condition number of the Jacobian of the transformation from the unit-length
+/// equilateral element to an element with Gram matrix `g`
+fn condition_number<const D: usize>(g: &SMatrix<f64, D, D>) -> f64 {
+    let w = SMatrix::<f64, D, D>::from_fn(|i, j| J_EQ[i][j]);
+    let Some(chol) = (w.transpose() * g * w).cholesky() else {
+        return QualityMetric::ConditionNumber.degenerate();
+    };
+    let tr = chol.l().norm_squared();
+    let tr_inv = chol.inverse().trace();
+    (tr * tr_inv).sqrt() / D as f64
+}
+
+/// Compute a quality measure for an element with vertices `p` in the space of a constant metric
+/// `m`. The element is first transformed into an element in the Euclidean space with the same
+/// metric-space edge lengths, from the Cholesky factorization of the metric-space Gram matrix
+/// of its edges
+/// ```math
+/// G_{ij} = e_i^T \mathcal M e_j = \frac{1}{2}\left(l_\mathcal M(e_i)^2 + l_\mathcal M(e_j)^2 - l_\mathcal M(e_i - e_j)^2\right)
+/// ```
+fn elem_measure<const D: usize, E: Elem, M: Metric<D>>(
+    measure: QualityMetric,
+    p: &[Point<D>],
+    m: &M,
+) -> f64 {
+    let l2 = |e: Point<D>| m.length(&e).powi(2);
+    let g = SMatrix::<f64, D, D>::from_fn(|i, j| {
+        let (ei, ej) = (p[i + 1] - p[0], p[j + 1] - p[0]);
+        0.5 * (l2(ei) + l2(ej) - l2(ei - ej))
+    });
+    let Some(chol) = g.cholesky() else {
+        return measure.degenerate();
+    };
+
+    // keep the orientation of the element
+    let ge =
+        E::Geom::<D, IsoMetric<D>>::from_verts(p.iter().map(|&x| (x, IsoMetric::<D>::from(1.0))));
+    let sgn = if ge.vol() < 0.0 { -1.0 } else { 1.0 };
+    let l = chol.l();
+    let ge = E::Geom::<D, IsoMetric<D>>::from_verts(
+        std::iter::once(Point::<D>::zeros())
+            .chain((0..D).map(|i| {
+                let mut x = l.row(i).transpose();
+                x[0] *= sgn;
+                x
+            }))
+            .map(|x| (x, IsoMetric::<D>::from(1.0))),
+    );
+
+    match measure {
+        QualityMetric::MeanRatio => ge.quality(),
+        QualityMetric::RadiusRatio => ge.gamma(),
+        QualityMetric::MinDihedralAngle => dihedral_angles::<D, E>(&ge).0,
+        QualityMetric::MaxDihedralAngle => dihedral_angles::<D, E>(&ge).1,
+        QualityMetric::ConditionNumber => condition_number(&g),
+        QualityMetric::Skewness => {
+            let theta_e = if D == 2 {
+                60.0
+            } else {
+                (1.0_f64 / 3.0).acos().to_degrees()
+            };
+            let (min_angle, max_angle) = dihedral_angles::<D, E>(&ge);
+            f64::max(
+                (max_angle - theta_e) / (180.0 - theta_e),
+                (theta_e - min_angle) / theta_e,
+            )
+        }
+    }
+}
+
+impl<const D: usize, E: Elem> SimplexMesh<D, E> {
+    /// Compute a quality measure for all the mesh elements in the Euclidean space. Only
+    /// available for volume meshes (triangles in 2D and tetrahedra in 3D)
+    pub fn quality_measures(&self, measure: QualityMetric) -> Result<Vec<f64>> {
+        let m = vec![IsoMetric::<D>::from(1.0); self.n_verts() as usize];
+        self.metric_quality_measures(measure, &m)
+    }
+
+    /// Compute a quality measure for all the mesh elements in the space of metric field `m`
+    /// (the metric with the smallest volume among the element vertices is used, as in
+    /// `qualities`). Only available for volume meshes (triangles in 2D and tetrahedra in 3D)
+    pub fn metric_quality_measures<M: Metric<D>>(
+        &self,
+        measure: QualityMetric,
+        m: &[M],
+    ) -> Result<Vec<f64>> {
+        debug!("Compute the {measure:?} of the elements");
+
+        if E::N_VERTS as usize != D + 1 || D < 2 {
+            return Err(Error::from(
+                "quality measures are only available for volume meshes",
+            ));
+        }
+        if m.len() != self.n_verts() as usize {
+            return Err(Error::from("invalid metric field size"));
+        }
+
+        Ok(self
+            .par_elems()
+            .map(|e| {
+                let p = e.iter().map(|&i| self.vert(i)).collect::<Vec<_>>();
+                let mi = M::min_metric(e.iter().map(|&i| &m[i as usize]));
+                elem_measure::<D, E, M>(measure, &p, mi)
+            })
+            .collect())
+    }
+
+    /// Compute the histogram of the element qualities using a metric field, with `n_bins`
+    /// uniform bins in [0, 1]
+    pub fn qualities_histogram<M: Metric<D>>(
+        &self,
+        m: &[M],
+        n_bins: usize,
+    ) -> Result<QualityHistogram> {
+        if m.len() != self.n_verts() as usize {
+            return Err(Error::from("invalid metric field size"));
+        }
+        if n_bins == 0 {
+            return Err(Error::from("invalid number of bins"));
+        }
+
+        let q = self.qualities(m);
+        let mut counts = vec![0; n_bins];
+        for &x in &q {
+            let i = ((x.max(0.0) * n_bins as f64) as usize).min(n_bins - 1);
+            counts[i] += 1;
+        }
+
+        Ok(QualityHistogram {
+            bins: (0..=n_bins).map(|i| i as f64 / n_bins as f64).collect(),
+            counts,
+            min: q.iter().copied().fold(f64::INFINITY, f64::min),
+            mean: q.iter().sum::<f64>() / q.len().max(1) as f64,
+        })
+    }
+
+    /// Get the quality information of the `n` elements with the lowest quality in the metric
+    /// space, sorted by increasing quality. Only available for volume meshes (triangles in 2D
+    /// and tetrahedra in 3D)
+    pub fn worst_elements<M: Metric<D>>(&self, m: &[M], n: usize) -> Result<Vec<ElemQuality>> {
+        debug!("Get the {n} worst elements");
+
+        if E::N_VERTS as usize != D + 1 || D < 2 {
+            return Err(Error::from(
+                "dihedral angles are only available for volume meshes",
+            ));
+        }
+        if m.len() != self.n_verts() as usize {
+            return Err(Error::from("invalid metric field size"));
+        }
+
+        let q = self.qualities(m);
+        let mut ids = (0..self.n_elems()).collect::<Vec<_>>();
+        // undefined qualities (inverted elements) come first
+        ids.sort_by(|&i, &j| q[i as usize].max(-1.0).total_cmp(&q[j as usize].max(-1.0)));
+        ids.truncate(n);
+
+        Ok(ids
+            .iter()
+            .map(|&i| {
+                let ge = self.gelem(self.elem(i));
+                let (min_angle, max_angle) = dihedral_angles::<D, E>(&ge);
+                ElemQuality {
+                    elem: i,
+                    quality: ge.quality(),
+                    metric_quality: q[i as usize],
+                    min_angle,
+                    max_angle,
+                }
+            })
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        mesh::{
+            test_meshes::{test_mesh_2d, test_mesh_3d},
+            Point, SimplexMesh, Triangle,
+        },
+        metric::{AnisoMetric2d, IsoMetric},
+        Result,
+    };
+
+    use super::QualityMetric;
+
+    const MEASURES: [QualityMetric; 6] = [
+        QualityMetric::MeanRatio,
+        QualityMetric::RadiusRatio,
+        QualityMetric::MinDihedralAngle,
+        QualityMetric::MaxDihedralAngle,
+        QualityMetric::ConditionNumber,
+        QualityMetric::Skewness,
+    ];
+
+    #[test]
+    fn test_histogram_2d() -> Result<()> {
+        let mesh = test_mesh_2d();
+        let m = vec![IsoMetric::<2>::from(1.0); mesh.n_verts() as usize];
+
+        let h = mesh.qualities_histogram(&m, 10)?;
+        assert_eq!(h.bins.len(), 11);
+        assert_eq!(h.counts, vec![0, 0, 0, 0, 0, 0, 0, 0, 2, 0]);
+        assert!((h.min - 0.75_f64.sqrt()).abs() < 1e-12);
+        assert!((h.mean - h.min).abs() < 1e-12);
+        assert_eq!(
+            format!("{h}"),
+            "q_min = 0.87, q_mean = 0.87, [0.80, 0.90]: 2"
+        );
+
+        assert!(mesh.qualities_histogram(&m, 0).is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_worst_elements_2d() -> Result<()> {
+        let mesh = test_mesh_2d().split();
+        let m = AnisoMetric2d::from_sizes(&Point::<2>::new(1.0, 0.0), &Point::<2>::new(0.0, 0.1));
+        let m = vec![m; mesh.n_verts() as usize];
+
+        let worst = mesh.worst_elements(&m, 3)?;
+        assert_eq!(worst.len(), 3);
+        for w in &worst {
+            assert!((w.quality - 0.75_f64.sqrt()).abs() < 1e-12);
+            assert!(w.metric_quality < w.quality);
+            assert!((w.min_angle - 45.0).abs() < 1e-12);
+            assert!((w.max_angle - 90.0).abs() < 1e-12);
+        }
+        assert!(worst
+            .windows(2)
+            .all(|w| w[0].metric_quality <= w[1].metric_quality));
+
+        let all = mesh.worst_elements(&m, 100)?;
+        assert_eq!(all.len(), mesh.n_elems() as usize);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_worst_elements_3d() -> Result<()> {
+        let mesh = test_mesh_3d().split();
+        let m = vec![IsoMetric::<3>::from(1.0); mesh.n_verts() as usize];
+
+        let worst = mesh.worst_elements(&m, 1)?;
+        assert_eq!(worst.len(), 1);
+        let w = worst[0];
+        assert!((w.quality - w.metric_quality).abs() < 1e-12);
+        assert!(w.min_angle > 0.0 && w.min_angle <= w.max_angle && w.max_angle < 180.0);
+        assert!(mesh
+            .qualities(&m)
+            .iter()
+            .all(|&q| q >= w.metric_quality - 1e-12));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_measures_2d() -> Result<()> {
+        let mesh = test_mesh_2d();
+        let expected = [
+            0.75_f64.sqrt(),
+            2.0 * 2.0_f64.sqrt() - 2.0,
+            45.0,
+            90.0,
+            0.0,
+            0.25,
+        ];
+        for (measure, x) in MEASURES.iter().zip(expected) {
+            let q = mesh.quality_measures(*measure)?;
+            assert_eq!(q.len(), 2);
+            if *measure == QualityMetric::ConditionNumber {
+                assert!(q.iter().all(|&c| c > 1.0));
+            } else {
+                assert!(q.iter().all(|&y| (y - x).abs() < 1e-12), "{measure:?}");
+            }
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_measures_metric_2d() -> Result<()> {
+        // an equilateral triangle in the metric space
+        let h = 0.1;
+        let verts = vec![
+            Point::<2>::new(0.0, 0.0),
+            Point::<2>::new(1.0, 0.0),
+            Point::<2>::new(0.5, 0.5 * 3.0_f64.sqrt() * h),
+        ];
+        let mesh = SimplexMesh::new(verts, vec![Triangle::new(0, 1, 2)], vec![1], vec![], vec![]);
+        let m = AnisoMetric2d::from_sizes(&Point::<2>::new(1.0, 0.0), &Point::<2>::new(0.0, h));
+        let m = vec![m; 3];
+
+        let expected = [1.0, 1.0, 60.0, 60.0, 1.0, 0.0];
+        for (measure, x) in MEASURES.iter().zip(expected) {
+            let q = mesh.metric_quality_measures(*measure, &m)?;
+            assert!((q[0] - x).abs() < 1e-10, "{measure:?}");
+            let q = mesh.quality_measures(*measure)?;
+            assert!((q[0] - x).abs() > 1e-2, "{measure:?}");
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_measures_3d() -> Result<()> {
+        let mesh = test_mesh_3d().split();
+        let m = vec![IsoMetric::<3>::from(0.5); mesh.n_verts() as usize];
+
+        let q = mesh.quality_measures(QualityMetric::MeanRatio)?;
+        for (x, y) in q.iter().zip(mesh.qualities(&m)) {
+            assert!((x - y).abs() < 1e-12);
+        }
+        for measure in MEASURES {
+            let q0 = mesh.quality_measures(measure)?;
+            let q1 = mesh.metric_quality_measures(measure, &m)?;
+            for (x, y) in q0.iter().zip(q1.iter()) {
+                assert!((x - y).abs() < 1e-10 * x.abs().max(1.0), "{measure:?}");
+            }
+        }
+        let q = mesh.quality_measures(QualityMetric::Skewness)?;
+        assert!(q.iter().all(|&x| (0.0..=1.0).contains(&x)));
+        let q = mesh.quality_measures(QualityMetric::ConditionNumber)?;
+        assert!(q.iter().all(|&x| x >= 1.0 - 1e-12));
+
+        let bdy = mesh.boundary().0;
+        assert!(bdy.quality_measures(QualityMetric::MeanRatio).is_err());
+
+        Ok(())
+    }
+}
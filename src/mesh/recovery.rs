@@ -0,0 +1,140 @@
+use crate::{
+    mesh::{Elem, SimplexMesh},
+    Result,
+};
+use log::debug;
+
+/// Method used to recover the gradient of a field defined at the mesh vertices
+#[derive(Clone, Copy, Debug)]
+pub enum GradientRecovery {
+    /// 1st order weighted least squares on the vertex stencil (see `SimplexMesh::gradient`)
+    LeastSquares { weight_exp: i32 },
+    /// L2 projection of the element gradients (see `SimplexMesh::gradient_l2proj`)
+    L2Projection,
+}
+
+impl Default for GradientRecovery {
+    fn default() -> Self {
+        Self::LeastSquares { weight_exp: 2 }
+    }
+}
+
+/// Method used to recover the hessian of a field defined at the mesh vertices
+#[derive(Clone, Copy, Debug)]
+pub enum HessianRecovery {
+    /// 2nd order weighted least squares on the vertex stencil (see `SimplexMesh::hessian`)
+    LeastSquares {
+        weight_exp: Option<i32>,
+        use_second_order_neighbors: bool,
+    },
+    /// Double L2 projection (see `SimplexMesh::hessian_l2proj`)
+    L2Projection,
+}
+
+impl Default for HessianRecovery {
+    fn default() -> Self {
+        Self::LeastSquares {
+            weight_exp: None,
+            use_second_order_neighbors: true,
+        }
+    }
+}
+
+impl<const D: usize, E: Elem> SimplexMesh<D, E> {
+    /// Compute the gradient of a scalar field defined at the mesh vertices using `method`.
+    /// The result has $`D`$ components per vertex.
+    ///
+    /// The least squares method requires the vertex-to-vertex connectivity, the L2
+    /// projection requires the vertex-to-element connectivity and the volumes
+    pub fn recover_gradient(&self, f: &[f64], method: GradientRecovery) -> Result<Vec<f64>> {
+        debug!("Recover the gradient using {method:?}");
+        match method {
+            GradientRecovery::LeastSquares { weight_exp } => self.gradient(f, weight_exp),
+            GradientRecovery::L2Projection => self.gradient_l2proj(f),
+        }
+    }
+
+    /// Compute the hessian of a scalar field defined at the mesh vertices using `method`.
+    /// The result has $`D(D+1)/2`$ components per vertex, ordered as xx, yy, xy in 2D and
+    /// xx, yy, zz, xy, yz, xz in 3D (i.e. as expected by `AnisoMetric::from_slice`).
+    ///
+    /// The least squares method requires the vertex-to-vertex connectivity, the L2
+    /// projection requires the vertex-to-element connectivity and the volumes
+    pub fn recover_hessian(&self, f: &[f64], method: HessianRecovery) -> Result<Vec<f64>> {
+        debug!("Recover the hessian using {method:?}");
+        match method {
+            HessianRecovery::LeastSquares {
+                weight_exp,
+                use_second_order_neighbors,
+            } => self.hessian(f, weight_exp, use_second_order_neighbors),
+            HessianRecovery::L2Projection => {
+                let grad = self.gradient_l2proj(f)?;
+                self.hessian_l2proj(&grad)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{GradientRecovery, HessianRecovery};
+    use crate::{
+        mesh::test_meshes::{test_mesh_2d, test_mesh_3d},
+        Result,
+    };
+
+    #[test]
+    fn test_recovery_2d_linear() -> Result<()> {
+        let mut mesh = test_mesh_2d().split().split().split();
+        mesh.compute_vertex_to_vertices();
+        mesh.compute_vertex_to_elems();
+        mesh.compute_volumes();
+
+        let f: Vec<_> = mesh.verts().map(|p| p[0] + 2.0 * p[1]).collect();
+
+        for method in [GradientRecovery::default(), GradientRecovery::L2Projection] {
+            let grad = mesh.recover_gradient(&f, method)?;
+            assert_eq!(grad.len(), 2 * mesh.n_verts() as usize);
+            for g in grad.chunks(2) {
+                assert!((g[0] - 1.0).abs() < 1e-8);
+                assert!((g[1] - 2.0).abs() < 1e-8);
+            }
+        }
+
+        for method in [HessianRecovery::default(), HessianRecovery::L2Projection] {
+            let hess = mesh.recover_hessian(&f, method)?;
+            assert_eq!(hess.len(), 3 * mesh.n_verts() as usize);
+            assert!(hess.iter().all(|x| x.abs() < 1e-8));
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_recovery_3d_quadratic() -> Result<()> {
+        let mut mesh = test_mesh_3d().split().split();
+        mesh.compute_vertex_to_vertices();
+
+        let f: Vec<_> = mesh
+            .verts()
+            .map(|p| p[0] * p[0] + 2.0 * p[1] * p[1] + 3.0 * p[2] * p[2] + p[0] * p[1])
+            .collect();
+
+        let hess = mesh.recover_hessian(&f, HessianRecovery::default())?;
+        for h in hess.chunks(6) {
+            assert!((h[0] - 2.0).abs() < 1e-8);
+            assert!((h[1] - 4.0).abs() < 1e-8);
+            assert!((h[2] - 6.0).abs() < 1e-8);
+            assert!((h[3] - 1.0).abs() < 1e-8);
+            assert!(h[4].abs() < 1e-8);
+            assert!(h[5].abs() < 1e-8);
+        }
+
+        // missing connectivities
+        assert!(mesh
+            .recover_hessian(&f, HessianRecovery::L2Projection)
+            .is_err());
+
+        Ok(())
+    }
+}
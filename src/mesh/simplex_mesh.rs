@@ -1,10 +1,14 @@
 use super::{
+    element_tree::ElementTree,
+    fields::Fields,
     geom_elems::GElem,
     graph::{reindex, CSRGraph},
+    tag_names::TagNames,
     to_simplices::{hex2tets, pri2tets, pyr2tets, qua2tris},
-    topo_elems::{get_face_to_elem, Edge, Elem, Tetrahedron, Triangle},
+    topo_elems::{get_face_to_elem, par_get_face_to_elem, Edge, Elem, Tetrahedron, Triangle},
     topology::Topology,
     twovec,
+    validity::same_orientation,
     vector::Vector,
 };
 use crate::{
@@ -14,15 +18,25 @@ use crate::{
     Dim, Error, Idx, Result, Tag, TopoTag,
 };
 use log::{debug, warn};
-use nalgebra::SVector;
+use nalgebra::{Const, DMatrixView, Dyn, MatrixView, SVector};
+#[cfg(feature = "ndarray")]
+use ndarray::{ArrayView1, ArrayView2};
 use rayon::{
-    prelude::{IndexedParallelIterator, IntoParallelIterator, ParallelIterator},
+    prelude::{
+        IndexedParallelIterator, IntoParallelIterator, IntoParallelRefIterator, ParallelIterator,
+    },
     slice::ParallelSliceMut,
 };
 use rustc_hash::{FxHashMap, FxHashSet};
 use std::collections::HashMap;
 use std::marker::PhantomData;
 
+/// Check if an element has repeated vertices
+fn has_repeated_vertices<E: Elem>(e: &E) -> bool {
+    let e = e.sorted();
+    e.iter().zip(e.iter().skip(1)).any(|(i, j)| i == j)
+}
+
 /// A mesh containing a single type of elements in D-dimensions
 #[derive(Debug, Default)]
 pub struct SimplexMesh<const D: usize, E: Elem> {
@@ -40,13 +54,15 @@ pub struct SimplexMesh<const D: usize, E: Elem> {
     elem: PhantomData<E>,
     /// Face to element connectitivity stored as a HashMap taking the face vertices (sorted) and returning
     /// a vector of element Ids
-    faces_to_elems: Option<FxHashMap<E::Face, twovec::Vec<u32>>>,
+    faces_to_elems: Option<FxHashMap<E::Face, twovec::Vec<Idx>>>,
     /// Vertex-to-element connectivity stored in CSR format
     vertex_to_elems: Option<CSRGraph>,
     /// Element-to-element connectivity stored in CSR format
     elem_to_elems: Option<CSRGraph>,
     /// Edges (length = # of edges)
     edges: Option<Vec<[Idx; 2]>>,
+    /// Vertex-to-edge connectivity stored in CSR format
+    vertex_to_edges: Option<CSRGraph>,
     /// Vertex-to-vertex (~edges) connectivity stored in CSR format
     vertex_to_vertices: Option<CSRGraph>,
     /// Element volumes (length = # of elements)
@@ -60,6 +76,18 @@ pub struct SimplexMesh<const D: usize, E: Elem> {
     topo: Option<Topology>,
     /// Vertex tags
     vtags: Option<Vec<TopoTag>>,
+    /// Partition id of each element (length = # of elements)
+    partition: Option<Vec<Tag>>,
+    /// Partition id of each vertex (length = # of vertices)
+    vert_partition: Option<Vec<Tag>>,
+    /// If true, the partitioners also store the partition id in the element tags
+    partition_to_etags: bool,
+    /// Names of the element and face tags
+    tag_names: TagNames,
+    /// Fields attached to the vertices
+    vert_fields: Fields,
+    /// Fields attached to the elements
+    elem_fields: Fields,
 }
 
 impl<const D: usize, E: Elem> Clone for SimplexMesh<D, E> {
@@ -77,6 +105,16 @@ impl<const D: usize, E: Elem> Clone for SimplexMesh<D, E> {
         if let Some(vtags) = self.vtags.as_ref() {
             res.vtags = Some(vtags.clone());
         }
+        if let Some(partition) = self.partition.as_ref() {
+            res.partition = Some(partition.clone());
+        }
+        if let Some(vert_partition) = self.vert_partition.as_ref() {
+            res.vert_partition = Some(vert_partition.clone());
+        }
+        res.partition_to_etags = self.partition_to_etags;
+        res.tag_names = self.tag_names.clone();
+        res.vert_fields = self.vert_fields.clone();
+        res.elem_fields = self.elem_fields.clone();
         res
     }
 }
@@ -121,11 +159,18 @@ impl<const D: usize, E: Elem> SimplexMesh<D, E> {
             vertex_to_elems: None,
             elem_to_elems: None,
             edges: None,
+            vertex_to_edges: None,
             vertex_to_vertices: None,
             elem_vol: None,
             vert_vol: None,
             topo: None,
             vtags: None,
+            partition: None,
+            vert_partition: None,
+            partition_to_etags: false,
+            tag_names: TagNames::default(),
+            vert_fields: Fields::default(),
+            elem_fields: Fields::default(),
         }
     }
 
@@ -146,6 +191,88 @@ impl<const D: usize, E: Elem> SimplexMesh<D, E> {
         )
     }
 
+    /// Create a new `SimplexMesh` that uses the memory of the views, without copy. Each column
+    /// of `verts`, `elems` and `faces` contains a vertex, an element and a face, respectively.
+    /// The mesh cannot be modified in place: `clone()` returns a copy that owns its data
+    ///
+    /// # Safety
+    /// The data must outlive the mesh and must not be modified while the mesh is used
+    pub unsafe fn from_views(
+        verts: MatrixView<'_, f64, Const<D>, Dyn>,
+        elems: DMatrixView<'_, Idx>,
+        etags: &[Tag],
+        faces: DMatrixView<'_, Idx>,
+        ftags: &[Tag],
+    ) -> Result<Self> {
+        // the columns must be stored contiguously, one after the other
+        let is_contiguous = |(n_rows, n_cols): (usize, usize), (r_stride, c_stride)| {
+            (n_rows < 2 || r_stride == 1) && (n_cols < 2 || c_stride == n_rows)
+        };
+        if !is_contiguous(verts.shape(), verts.strides()) {
+            return Err(Error::from("invalid vertex view"));
+        }
+        if elems.nrows() != E::N_VERTS as usize || !is_contiguous(elems.shape(), elems.strides()) {
+            return Err(Error::from("invalid element view"));
+        }
+        if faces.nrows() != E::Face::N_VERTS as usize
+            || !is_contiguous(faces.shape(), faces.strides())
+        {
+            return Err(Error::from("invalid face view"));
+        }
+        if etags.len() != elems.ncols() || ftags.len() != faces.ncols() {
+            return Err(Error::from("invalid tag sizes"));
+        }
+
+        Ok(Self::new_with_vector(
+            (verts.as_ptr(), verts.ncols()).into(),
+            (elems.as_ptr(), elems.ncols()).into(),
+            (etags.as_ptr(), etags.len()).into(),
+            (faces.as_ptr(), faces.ncols()).into(),
+            (ftags.as_ptr(), ftags.len()).into(),
+        ))
+    }
+
+    /// Create a new `SimplexMesh` that uses the memory of ndarray views (in standard layout),
+    /// without copy. Each row of `verts`, `elems` and `faces` contains a vertex, an element and
+    /// a face, respectively (see `from_views`)
+    ///
+    /// # Safety
+    /// The data must outlive the mesh and must not be modified while the mesh is used
+    #[cfg(feature = "ndarray")]
+    pub unsafe fn from_arrays(
+        verts: ArrayView2<'_, f64>,
+        elems: ArrayView2<'_, Idx>,
+        etags: ArrayView1<'_, Tag>,
+        faces: ArrayView2<'_, Idx>,
+        ftags: ArrayView1<'_, Tag>,
+    ) -> Result<Self> {
+        let (Some(v), Some(e), Some(et), Some(f), Some(ft)) = (
+            verts.as_slice(),
+            elems.as_slice(),
+            etags.as_slice(),
+            faces.as_slice(),
+            ftags.as_slice(),
+        ) else {
+            return Err(Error::from("the arrays must be in standard layout"));
+        };
+        if verts.ncols() != D {
+            return Err(Error::from("invalid vertex array"));
+        }
+        if elems.ncols() != E::N_VERTS as usize {
+            return Err(Error::from("invalid element array"));
+        }
+        if faces.ncols() != E::Face::N_VERTS as usize {
+            return Err(Error::from("invalid face array"));
+        }
+        Self::from_views(
+            MatrixView::from_slice_generic(v, Const::<D>, Dyn(verts.nrows())),
+            DMatrixView::from_slice(e, elems.ncols(), elems.nrows()),
+            et,
+            DMatrixView::from_slice(f, faces.ncols(), faces.nrows()),
+            ft,
+        )
+    }
+
     #[must_use]
     pub fn empty() -> Self {
         Self {
@@ -160,11 +287,18 @@ impl<const D: usize, E: Elem> SimplexMesh<D, E> {
             vertex_to_elems: None,
             elem_to_elems: None,
             edges: None,
+            vertex_to_edges: None,
             vertex_to_vertices: None,
             elem_vol: None,
             vert_vol: None,
             topo: None,
             vtags: None,
+            partition: None,
+            vert_partition: None,
+            partition_to_etags: false,
+            tag_names: TagNames::default(),
+            vert_fields: Fields::default(),
+            elem_fields: Fields::default(),
         }
     }
 
@@ -334,6 +468,75 @@ impl<const D: usize, E: Elem> SimplexMesh<D, E> {
         self.ftags.as_std_mut().iter_mut()
     }
 
+    /// Get the vertex coordinates as a D x (# of vertices) matrix, without copy
+    #[must_use]
+    pub fn verts_view(&self) -> MatrixView<'_, f64, Const<D>, Dyn> {
+        MatrixView::from_slice_generic(
+            self.verts.as_flat_slice(),
+            Const::<D>,
+            Dyn(self.n_verts() as usize),
+        )
+    }
+
+    /// Get the vertex coordinates as a (# of vertices) x D ndarray view, without copy
+    #[cfg(feature = "ndarray")]
+    #[must_use]
+    pub fn verts_array(&self) -> ArrayView2<'_, f64> {
+        ArrayView2::from_shape((self.n_verts() as usize, D), self.verts.as_flat_slice()).unwrap()
+    }
+
+    /// Get the element connectivity as a (# of elements) x (# of vertices per element) ndarray
+    /// view, without copy
+    #[cfg(feature = "ndarray")]
+    #[must_use]
+    pub fn elems_array(&self) -> ArrayView2<'_, Idx> {
+        let shape = (self.n_elems() as usize, E::N_VERTS as usize);
+        ArrayView2::from_shape(shape, self.elems.as_flat_slice()).unwrap()
+    }
+
+    /// Get the face connectivity as a (# of faces) x (# of vertices per face) ndarray view,
+    /// without copy
+    #[cfg(feature = "ndarray")]
+    #[must_use]
+    pub fn faces_array(&self) -> ArrayView2<'_, Idx> {
+        let shape = (self.n_faces() as usize, E::Face::N_VERTS as usize);
+        ArrayView2::from_shape(shape, self.faces.as_flat_slice()).unwrap()
+    }
+
+    /// Get the element connectivity as a (# of vertices per element) x (# of elements) matrix,
+    /// without copy
+    #[must_use]
+    pub fn elems_view(&self) -> DMatrixView<'_, Idx> {
+        DMatrixView::from_slice(
+            self.elems.as_flat_slice(),
+            E::N_VERTS as usize,
+            self.n_elems() as usize,
+        )
+    }
+
+    /// Get the element tags, without copy
+    #[must_use]
+    pub fn etags_view(&self) -> &[Tag] {
+        self.etags.as_flat_slice()
+    }
+
+    /// Get the face connectivity as a (# of vertices per face) x (# of faces) matrix, without
+    /// copy
+    #[must_use]
+    pub fn faces_view(&self) -> DMatrixView<'_, Idx> {
+        DMatrixView::from_slice(
+            self.faces.as_flat_slice(),
+            E::Face::N_VERTS as usize,
+            self.n_faces() as usize,
+        )
+    }
+
+    /// Get the face tags, without copy
+    #[must_use]
+    pub fn ftags_view(&self) -> &[Tag] {
+        self.ftags.as_flat_slice()
+    }
+
     pub fn gface(&self, f: E::Face) -> <E::Face as Elem>::Geom<D, IsoMetric<D>> {
         <E::Face as Elem>::Geom::from_verts(
             f.iter()
@@ -361,10 +564,15 @@ impl<const D: usize, E: Elem> SimplexMesh<D, E> {
     }
 
     /// Compute the face-to-element connectivity
-    pub fn compute_face_to_elems(&mut self) -> &FxHashMap<E::Face, twovec::Vec<u32>> {
+    /// (in parallel if the `parallel-connectivity` feature is enabled)
+    pub fn compute_face_to_elems(&mut self) -> &FxHashMap<E::Face, twovec::Vec<Idx>> {
         debug!("Compute the face to element connectivity");
         if self.faces_to_elems.is_none() {
-            self.faces_to_elems = Some(get_face_to_elem(self.elems()));
+            self.faces_to_elems = Some(if cfg!(feature = "parallel-connectivity") {
+                par_get_face_to_elem(self.par_elems())
+            } else {
+                get_face_to_elem(self.elems())
+            });
         } else {
             warn!("Face to element connectivity already computed");
         }
@@ -377,8 +585,37 @@ impl<const D: usize, E: Elem> SimplexMesh<D, E> {
         self.faces_to_elems = None;
     }
 
+    /// Set the face-to-element connectivity from the elements (sorted by increasing index)
+    /// around each sorted face, e.g. if it has been maintained while modifying the mesh. In
+    /// debug builds, it is checked against a full rebuild
+    pub fn set_face_to_elems<I: IntoIterator<Item = Idx>>(
+        &mut self,
+        f2e: impl Iterator<Item = (E::Face, I)>,
+    ) {
+        debug!("Set the face to element connectivity");
+        let mut res = FxHashMap::default();
+        for (f, els) in f2e {
+            let mut els = els.into_iter();
+            let mut v = twovec::Vec::with_single(els.next().unwrap());
+            els.for_each(|i| v.push(i));
+            res.insert(f, v);
+        }
+
+        if cfg!(debug_assertions) {
+            let f2e = get_face_to_elem(self.elems());
+            assert_eq!(f2e.len(), res.len());
+            for (f, els) in &f2e {
+                assert!(
+                    res[f].iter().eq(els.iter()),
+                    "invalid face to element connectivity"
+                );
+            }
+        }
+        self.faces_to_elems = Some(res);
+    }
+
     /// Get the face-to-element connectivity
-    pub fn get_face_to_elems(&self) -> Result<&FxHashMap<<E as Elem>::Face, twovec::Vec<u32>>> {
+    pub fn get_face_to_elems(&self) -> Result<&FxHashMap<<E as Elem>::Face, twovec::Vec<Idx>>> {
         if self.faces_to_elems.is_none() {
             Err(Error::from("Face to element connectivity not computed"))
         } else {
@@ -387,11 +624,16 @@ impl<const D: usize, E: Elem> SimplexMesh<D, E> {
     }
 
     /// Compute the vertex-to-element connectivity
+    /// (in parallel if the `parallel-connectivity` feature is enabled)
     pub fn compute_vertex_to_elems(&mut self) -> &CSRGraph {
         debug!("Compute the vertex to element connectivity");
 
         if self.vertex_to_elems.is_none() {
-            self.vertex_to_elems = Some(CSRGraph::transpose(&self.elems, None));
+            self.vertex_to_elems = Some(if cfg!(feature = "parallel-connectivity") {
+                CSRGraph::par_transpose(&self.elems, None)
+            } else {
+                CSRGraph::transpose(&self.elems, None)
+            });
         } else {
             warn!("Vertex to element connectivity already computed");
         }
@@ -404,6 +646,39 @@ impl<const D: usize, E: Elem> SimplexMesh<D, E> {
         self.vertex_to_elems = None;
     }
 
+    /// Set the vertex-to-element connectivity from the elements around each vertex, e.g. if
+    /// it has been maintained while modifying the mesh. In debug builds, it is checked against
+    /// a full rebuild
+    pub fn set_vertex_to_elems<I: IntoIterator<Item = Idx>>(
+        &mut self,
+        v2e: impl ExactSizeIterator<Item = I>,
+    ) {
+        debug!("Set the vertex to element connectivity");
+        let mut ptr = Vec::with_capacity(v2e.len() + 1);
+        ptr.push(0);
+        let mut indices = Vec::new();
+        for els in v2e {
+            indices.extend(els);
+            ptr.push(indices.len() as Idx);
+        }
+        let mut res = CSRGraph {
+            ptr,
+            indices,
+            m: self.n_elems(),
+        };
+        res.sort();
+        assert_eq!(res.n(), self.n_verts());
+
+        if cfg!(debug_assertions) {
+            let v2e = CSRGraph::transpose(&self.elems, Some(self.n_verts() as usize));
+            assert!(
+                v2e.ptr == res.ptr && v2e.indices == res.indices,
+                "invalid vertex to element connectivity"
+            );
+        }
+        self.vertex_to_elems = Some(res);
+    }
+
     /// Get the vertex-to-element connectivity
     pub fn get_vertex_to_elems(&self) -> Result<&CSRGraph> {
         if self.vertex_to_elems.is_none() {
@@ -415,6 +690,7 @@ impl<const D: usize, E: Elem> SimplexMesh<D, E> {
 
     /// Compute the element-to-element connectivity
     /// face-to-element connectivity is computed if not available
+    /// (in parallel if the `parallel-connectivity` feature is enabled)
     pub fn compute_elem_to_elems(&mut self) -> &CSRGraph {
         debug!("Compute the element to element connectivity");
         if self.elem_to_elems.is_none() {
@@ -423,15 +699,25 @@ impl<const D: usize, E: Elem> SimplexMesh<D, E> {
             }
             let f2e = self.faces_to_elems.as_ref().unwrap();
 
-            let mut g = Vec::new();
-            for val in f2e.values() {
+            let pairs = |val: &twovec::Vec<Idx>| {
+                let mut g = Vec::new();
                 for (i, i_elem) in val.iter().copied().enumerate() {
                     for j_elem in val.iter().skip(i + 1).copied() {
                         g.push([i_elem, j_elem]);
                     }
                 }
-            }
-            self.elem_to_elems = Some(CSRGraph::new(&g));
+                g
+            };
+            self.elem_to_elems = Some(if cfg!(feature = "parallel-connectivity") {
+                let g: Vec<_> = f2e
+                    .par_iter()
+                    .flat_map_iter(|(_, val)| pairs(val))
+                    .collect();
+                CSRGraph::par_new(&g)
+            } else {
+                let g: Vec<_> = f2e.values().flat_map(pairs).collect();
+                CSRGraph::new(&g)
+            });
         } else {
             warn!("Element to element connectivity already computed");
         }
@@ -453,19 +739,39 @@ impl<const D: usize, E: Elem> SimplexMesh<D, E> {
         }
     }
 
-    /// Compute the edges
+    /// Compute the edges, i.e. the edge-to-vertex connectivity. The vertices of each edge are
+    /// sorted and the edges are sorted lexicographically
+    /// (in parallel if the `parallel-connectivity` feature is enabled)
     pub fn compute_edges(&mut self) -> &Vec<[Idx; 2]> {
         debug!("Compute the edges");
         if self.edges.is_none() {
-            let mut edgs = FxHashSet::default();
-            for e in self.elems() {
-                for i_edg in 0..E::N_EDGES {
-                    let mut edg = e.edge(i_edg);
-                    edg.sort_unstable();
-                    edgs.insert(edg);
+            let edgs = if cfg!(feature = "parallel-connectivity") {
+                let mut edgs: Vec<_> = self
+                    .par_elems()
+                    .flat_map_iter(|e| {
+                        (0..E::N_EDGES).map(move |i_edg| {
+                            let mut edg = e.edge(i_edg);
+                            edg.sort_unstable();
+                            edg
+                        })
+                    })
+                    .collect();
+                edgs.par_sort_unstable();
+                edgs.dedup();
+                edgs
+            } else {
+                let mut edgs = FxHashSet::default();
+                for e in self.elems() {
+                    for i_edg in 0..E::N_EDGES {
+                        let mut edg = e.edge(i_edg);
+                        edg.sort_unstable();
+                        edgs.insert(edg);
+                    }
                 }
-            }
-            let edgs: Vec<_> = edgs.iter().copied().collect();
+                let mut edgs: Vec<_> = edgs.iter().copied().collect();
+                edgs.sort_unstable();
+                edgs
+            };
             self.edges = Some(edgs);
         } else {
             warn!("Edges already computed");
@@ -473,10 +779,11 @@ impl<const D: usize, E: Elem> SimplexMesh<D, E> {
         self.edges.as_ref().unwrap()
     }
 
-    /// Clear the edges
+    /// Clear the edges (and the vertex-to-edge connectivity)
     pub fn clear_edges(&mut self) {
         debug!("Delete the edges");
         self.edges = None;
+        self.vertex_to_edges = None;
     }
 
     /// Get the the edges
@@ -488,6 +795,50 @@ impl<const D: usize, E: Elem> SimplexMesh<D, E> {
         }
     }
 
+    /// Compute the vertex-to-edge connectivity
+    /// Edges are computed if not available
+    /// (in parallel if the `parallel-connectivity` feature is enabled)
+    pub fn compute_vertex_to_edges(&mut self) -> &CSRGraph {
+        debug!("Compute the vertex to edge connectivity");
+        if self.vertex_to_edges.is_none() {
+            if self.edges.is_none() {
+                self.compute_edges();
+            }
+            let edges = self
+                .edges
+                .as_ref()
+                .unwrap()
+                .iter()
+                .map(|e| Edge::new(e[0], e[1]))
+                .collect::<Vec<_>>();
+            let edges: Vector<Edge> = edges.into();
+            let nv = Some(self.n_verts() as usize);
+            self.vertex_to_edges = Some(if cfg!(feature = "parallel-connectivity") {
+                CSRGraph::par_transpose(&edges, nv)
+            } else {
+                CSRGraph::transpose(&edges, nv)
+            });
+        } else {
+            warn!("Vertex to edge connectivity already computed");
+        }
+        self.vertex_to_edges.as_ref().unwrap()
+    }
+
+    /// Clear the vertex-to-edge connectivity
+    pub fn clear_vertex_to_edges(&mut self) {
+        debug!("Delete the vertex to edge connectivity");
+        self.vertex_to_edges = None;
+    }
+
+    /// Get the vertex-to-edge connectivity
+    pub fn get_vertex_to_edges(&self) -> Result<&CSRGraph> {
+        if self.vertex_to_edges.is_none() {
+            Err(Error::from("Vertex to edge connectivity not computed"))
+        } else {
+            Ok(self.vertex_to_edges.as_ref().unwrap())
+        }
+    }
+
     /// Compute the vertex-to-vertex connectivity
     /// Edges are computed if not available
     pub fn compute_vertex_to_vertices(&mut self) -> &CSRGraph {
@@ -524,7 +875,7 @@ impl<const D: usize, E: Elem> SimplexMesh<D, E> {
         if self.elem_vol.is_none() {
             let mut elem_vol = vec![0.0; self.n_elems() as usize];
             let mut node_vol = vec![0.0; self.n_verts() as usize];
-            let fac = 1.0 / f64::from(E::N_VERTS);
+            let fac = 1.0 / E::N_VERTS as f64;
             for (i_elem, e) in self.elems().enumerate() {
                 let v = self.gelem(e).vol();
                 elem_vol[i_elem] = v;
@@ -576,14 +927,24 @@ impl<const D: usize, E: Elem> SimplexMesh<D, E> {
     }
 
     /// Compute an octree to locate elements
+    #[deprecated(note = "use `compute_element_tree`, which can also locate points in the mesh")]
     #[must_use]
     pub fn compute_elem_tree(&self) -> DefaultObjectIndex<D> {
         debug!("Compute the element octree");
         <DefaultObjectIndex<D> as ObjectIndex<D>>::new(self)
     }
 
+    /// Compute an `ElementTree` to locate points in the mesh and find the nearest elements
+    #[must_use]
+    pub fn compute_element_tree(&self) -> ElementTree<D, E> {
+        debug!("Compute the element tree");
+        ElementTree::new(self)
+    }
+
     /// Convert a field defined at the element centers (P0) to a field defined at the vertices (P1)
-    /// using a weighted average. For metric fields, use `elem_data_to_vertex_data_metric`
+    /// using a volume weighted average. For metric fields, use `elem_data_to_vertex_data_metric`.
+    /// Fields with several components (vectors, tensors) are stored as
+    /// `[v_0(e_0), ..., v_{n-1}(e_0), v_0(e_1), ...]`
     /// vertex-to-element connectivity and volumes are required
     pub fn elem_data_to_vertex_data(&self, v: &[f64]) -> Result<Vec<f64>> {
         debug!("Convert element data to vertex data");
@@ -599,7 +960,9 @@ impl<const D: usize, E: Elem> SimplexMesh<D, E> {
 
         let n_elems = self.n_elems() as usize;
         let n_verts = self.n_verts() as usize;
-        assert_eq!(v.len() % n_elems, 0);
+        if n_elems == 0 || v.len() % n_elems != 0 {
+            return Err(Error::from("invalid element data size"));
+        }
 
         let n_comp = v.len() / n_elems;
 
@@ -613,7 +976,7 @@ impl<const D: usize, E: Elem> SimplexMesh<D, E> {
             .enumerate()
             .for_each(|(i_vert, vals)| {
                 for i_elem in v2e.row(i_vert as Idx).iter().copied() {
-                    let w = elem_vol[i_elem as usize] / f64::from(E::N_VERTS);
+                    let w = elem_vol[i_elem as usize] / E::N_VERTS as f64;
                     for i_comp in 0..n_comp {
                         vals[i_comp] += w * v[n_comp * i_elem as usize + i_comp];
                     }
@@ -626,18 +989,21 @@ impl<const D: usize, E: Elem> SimplexMesh<D, E> {
     }
 
     /// Convert a field defined at the vertices (P1) to a field defined at the element centers (P0)
-    /// For metric fields, use `elem_data_to_vertex_data_metric`
+    /// For metric fields, use `vertex_data_to_elem_data_metric`. Fields with several components
+    /// are stored as in `elem_data_to_vertex_data`
     pub fn vertex_data_to_elem_data(&self, v: &[f64]) -> Result<Vec<f64>> {
         debug!("Convert vertex data to element data");
         let n_elems = self.n_elems() as usize;
         let n_verts = self.n_verts() as usize;
-        assert_eq!(v.len() % n_verts, 0);
+        if n_verts == 0 || v.len() % n_verts != 0 {
+            return Err(Error::from("invalid vertex data size"));
+        }
 
         let n_comp = v.len() / n_verts;
 
         let mut res = vec![0.; n_comp * n_elems];
 
-        let f = 1. / f64::from(E::N_VERTS);
+        let f = 1. / E::N_VERTS as f64;
         res.par_chunks_mut(n_comp)
             .zip(self.par_elems())
             .for_each(|(vals, e)| {
@@ -828,16 +1194,58 @@ impl<const D: usize, E: Elem> SimplexMesh<D, E> {
             vert_ids[new as usize] = old;
         }
 
-        (
-            SimplexMesh::<D, E::Face>::new_with_vector(
-                verts.into(),
-                new_faces.into(),
-                self.ftags.clone(),
-                Vec::new().into(),
-                Vec::new().into(),
-            ),
-            vert_ids,
-        )
+        let mut bdy = SimplexMesh::<D, E::Face>::new_with_vector(
+            verts.into(),
+            new_faces.into(),
+            self.ftags.clone(),
+            Vec::new().into(),
+            Vec::new().into(),
+        );
+        *bdy.tag_names_mut() = self.tag_names.boundary();
+
+        (bdy, vert_ids)
+    }
+
+    /// Return the mesh of the boundary (see `boundary`) together with, for each boundary
+    /// element, the parent element and the index of the boundary element in its faces. For
+    /// internal faces, the parent element is the one for which the face is oriented outwards,
+    /// if any
+    #[allow(clippy::type_complexity)]
+    pub fn boundary_with_parents(
+        &self,
+    ) -> Result<(SimplexMesh<D, E::Face>, Vec<Idx>, Vec<(Idx, Idx)>)> {
+        debug!("Extract the mesh boundary with the parent elements");
+
+        let f2e = get_face_to_elem(self.elems());
+        let parents = self
+            .faces()
+            .enumerate()
+            .map(|(i_face, f)| {
+                let sorted = f.sorted();
+                let elems = f2e.get(&sorted).ok_or_else(|| {
+                    Error::from(&format!("face {i_face} does not belong to any element"))
+                })?;
+                let candidates = elems
+                    .iter()
+                    .map(|&i_elem| {
+                        let e = self.elem(i_elem);
+                        let i = (0..E::N_FACES)
+                            .find(|&i| e.face(i).sorted() == sorted)
+                            .unwrap();
+                        (i_elem, i)
+                    })
+                    .collect::<Vec<_>>();
+                Ok(candidates
+                    .iter()
+                    .copied()
+                    .find(|&(i_elem, i)| same_orientation(&f, &self.elem(i_elem).face(i)))
+                    .unwrap_or(candidates[0]))
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        let (bdy, vert_ids) = self.boundary();
+
+        Ok((bdy, vert_ids, parents))
     }
 
     /// Return a bool vector that indicates wether a vertex in on a face
@@ -863,6 +1271,9 @@ impl<const D: usize, E: Elem> SimplexMesh<D, E> {
         let mut res = Self::empty();
         let (parent_vert_ids, parent_elem_ids, parent_face_ids) =
             res.add(self, elem_filter, |_| true, None);
+        res.tag_names = self.tag_names.clone();
+        res.vert_fields = self.vert_fields.select(&parent_vert_ids);
+        res.elem_fields = self.elem_fields.select(&parent_elem_ids);
 
         SubSimplexMesh {
             mesh: res,
@@ -872,6 +1283,67 @@ impl<const D: usize, E: Elem> SimplexMesh<D, E> {
         }
     }
 
+    /// Extract the elements with a tag in `tags` as a standalone mesh. The faces of the
+    /// selected elements are kept, and the untagged faces between selected and non-selected
+    /// elements (cut interfaces) are added, oriented outwards, with new tags: one per pair
+    /// (selected element tag, non-selected element tag), numbered in increasing order of the
+    /// pairs from the largest face tag + 1
+    ///
+    /// Returns the new mesh and the indices of its vertices and elements in `self`
+    #[must_use]
+    pub fn extract_tagged(&self, tags: &[Tag]) -> (Self, Vec<Idx>, Vec<Idx>) {
+        debug!("Extract the elements with tags {tags:?}");
+
+        let selected = self.etags().map(|t| tags.contains(&t)).collect::<Vec<_>>();
+        let tagged_faces = self.faces().map(|f| f.sorted()).collect::<FxHashSet<_>>();
+        let f2e = get_face_to_elem(self.elems());
+
+        let mut cut_faces = Vec::new();
+        for (i_elem, e) in self.elems().enumerate().filter(|&(i, _)| selected[i]) {
+            for i_face in 0..E::N_FACES {
+                let f = e.face(i_face);
+                let sorted = f.sorted();
+                if tagged_faces.contains(&sorted) {
+                    continue;
+                }
+                if let Some(&other) = f2e[&sorted].iter().find(|&&j| !selected[j as usize]) {
+                    cut_faces.push((f, [self.etag(i_elem as Idx), self.etag(other)]));
+                }
+            }
+        }
+        let mut pairs = cut_faces.iter().map(|(_, p)| *p).collect::<Vec<_>>();
+        pairs.sort_unstable();
+        pairs.dedup();
+        let next_tag = self.ftags().max().unwrap_or(0) + 1;
+
+        let SubSimplexMesh {
+            mut mesh,
+            parent_vert_ids,
+            parent_elem_ids,
+            ..
+        } = self.extract(|t| tags.contains(&t));
+        let new_vert_ids = parent_vert_ids
+            .iter()
+            .enumerate()
+            .map(|(i, &j)| (j, i as Idx))
+            .collect::<FxHashMap<_, _>>();
+        for (f, pair) in &cut_faces {
+            let tag = next_tag + pairs.binary_search(pair).unwrap() as Tag;
+            mesh.faces
+                .push(E::Face::from_iter(f.iter().map(|i| new_vert_ids[i])));
+            mesh.ftags.push(tag);
+        }
+        debug!(
+            "{} cut interface faces added with {} new tags",
+            cut_faces.len(),
+            pairs.len()
+        );
+
+        (mesh, parent_vert_ids, parent_elem_ids)
+    }
+
+    /// Check the element volumes and the boundary faces. The face to element connectivity is
+    /// required. See `validate` for a more comprehensive validation
     pub fn check(&self) -> Result<()> {
         self.check_volumes()?;
         self.check_boundary_faces()?;
@@ -1001,22 +1473,121 @@ impl<const D: usize, E: Elem> SimplexMesh<D, E> {
         }
     }
 
+    /// Set the partition id of each element
+    pub fn set_partition(&mut self, partition: Vec<Tag>) -> Result<()> {
+        if partition.len() != self.n_elems() as usize {
+            return Err(Error::from("invalid partition size"));
+        }
+        self.partition = Some(partition);
+        Ok(())
+    }
+
+    /// Get the partition id of each element
+    pub fn get_partition(&self) -> Result<&[Tag]> {
+        if self.partition.is_none() {
+            Err(Error::from("Partition not computed"))
+        } else {
+            Ok(self.partition.as_ref().unwrap())
+        }
+    }
+
+    /// Clear the partition
+    pub fn clear_partition(&mut self) {
+        debug!("Delete the partition");
+        self.partition = None;
+    }
+
+    /// Set the partition id of each vertex
+    pub fn set_vertex_partition(&mut self, partition: Vec<Tag>) -> Result<()> {
+        if partition.len() != self.n_verts() as usize {
+            return Err(Error::from("invalid vertex partition size"));
+        }
+        self.vert_partition = Some(partition);
+        Ok(())
+    }
+
+    /// Get the partition id of each vertex
+    pub fn get_vertex_partition(&self) -> Result<&[Tag]> {
+        if self.vert_partition.is_none() {
+            Err(Error::from("Vertex partition not computed"))
+        } else {
+            Ok(self.vert_partition.as_ref().unwrap())
+        }
+    }
+
+    /// Clear the vertex partition
+    pub fn clear_vertex_partition(&mut self) {
+        debug!("Delete the vertex partition");
+        self.vert_partition = None;
+    }
+
+    /// If `flag` is true, the partitioners also store the partition id in the element tags (that
+    /// are then erased)
+    pub const fn set_partition_to_etags(&mut self, flag: bool) {
+        self.partition_to_etags = flag;
+    }
+
+    /// Check if the partitioners store the partition id in the element tags
+    #[must_use]
+    pub const fn partition_to_etags(&self) -> bool {
+        self.partition_to_etags
+    }
+
+    /// Get the names of the element and face tags
+    #[must_use]
+    pub const fn tag_names(&self) -> &TagNames {
+        &self.tag_names
+    }
+
+    /// Get the names of the element and face tags (mutable)
+    pub const fn tag_names_mut(&mut self) -> &mut TagNames {
+        &mut self.tag_names
+    }
+
+    /// Get the fields attached to the vertices
+    #[must_use]
+    pub const fn vertex_fields(&self) -> &Fields {
+        &self.vert_fields
+    }
+
+    /// Get the fields attached to the vertices (mutable)
+    pub const fn vertex_fields_mut(&mut self) -> &mut Fields {
+        &mut self.vert_fields
+    }
+
+    /// Get the fields attached to the elements
+    #[must_use]
+    pub const fn elem_fields(&self) -> &Fields {
+        &self.elem_fields
+    }
+
+    /// Get the fields attached to the elements (mutable)
+    pub const fn elem_fields_mut(&mut self) -> &mut Fields {
+        &mut self.elem_fields
+    }
+
     pub fn clear_all(&mut self) {
         self.faces_to_elems = None;
         self.vertex_to_elems = None;
         self.elem_to_elems = None;
         self.edges = None;
+        self.vertex_to_edges = None;
         self.vertex_to_vertices = None;
         self.elem_vol = None;
         self.vert_vol = None;
         self.topo = None;
         self.vtags = None;
+        self.partition = None;
+        self.vert_partition = None;
     }
 
     /// Add vertices, elements and faces from another mesh according to their tag
     ///   - only the elements with a tag t such that `element_filter(t)` is true are inserted
     ///   - among the faces belonging to these elements, only those with a tag such that `face_filter` is true are inserted
     ///   - if `merger_tol` is not None, vertices on the boundaries of `self` and `other` are merged if closer than the tolerance.
+    ///   - the vertex and element fields of `self` are extended with the values of the fields of
+    ///     `other` with the same name, type and number of components (or default values, see
+    ///     `Fields::append`)
     pub fn add<F1, F2>(
         &mut self,
         other: &Self,
@@ -1111,9 +1682,69 @@ impl<const D: usize, E: Elem> SimplexMesh<D, E> {
             self.ftags.as_std_mut().push(t);
         }
 
+        self.vert_fields = self.vert_fields.append(&other.vert_fields, &added_verts);
+        self.elem_fields = self.elem_fields.append(&other.elem_fields, &added_elems);
+
         (added_verts, added_elems, added_faces)
     }
 
+    /// Add all the vertices, elements and faces of `other` and glue the coincident boundary
+    /// interfaces: the boundary vertices of `self` and `other` closer than `tol` are merged (see
+    /// `add`), and the faces shared by an element of `self` and an element of `other` are
+    /// removed, or only one copy is kept if the two elements have different tags
+    ///
+    /// Returns the indices of the unmatched interface faces, i.e. of the boundary faces whose
+    /// vertices are all shared by the two meshes
+    pub fn merge(&mut self, other: &Self, tol: f64) -> Vec<Idx> {
+        debug!("Merge a mesh with {} elements", other.n_elems());
+
+        let n_verts = self.n_verts() as usize;
+        let n_elems = self.n_elems();
+        self.add(other, |_| true, |_| true, Some(tol));
+
+        let mut shared = vec![[false; 2]; n_verts];
+        for (i_elem, e) in self.elems().enumerate() {
+            let k = usize::from(i_elem as Idx >= n_elems);
+            e.iter()
+                .filter(|&&i| (i as usize) < n_verts)
+                .for_each(|&i| shared[i as usize][k] = true);
+        }
+        let is_shared = |i: Idx| (i as usize) < n_verts && shared[i as usize] == [true; 2];
+
+        let f2e = get_face_to_elem(self.elems());
+        let mut kept = FxHashSet::default();
+        let mut faces = Vec::with_capacity(self.n_faces() as usize);
+        let mut ftags = Vec::with_capacity(self.n_faces() as usize);
+        let mut unmatched = Vec::new();
+        for (f, t) in self.faces().zip(self.ftags()) {
+            let sorted = f.sorted();
+            match f2e.get(&sorted) {
+                Some(elems) if elems.len() == 2 && (elems[0] < n_elems) != (elems[1] < n_elems) => {
+                    let same_tags = self.etag(elems[0]) == self.etag(elems[1]);
+                    if same_tags || !kept.insert(sorted) {
+                        continue;
+                    }
+                }
+                Some(elems) if elems.len() == 1 && f.iter().all(|&i| is_shared(i)) => {
+                    unmatched.push(faces.len() as Idx);
+                }
+                _ => {}
+            }
+            faces.push(f);
+            ftags.push(t);
+        }
+        let n_removed = self.n_faces() as usize - faces.len();
+        self.faces = faces.into();
+        self.ftags = ftags.into();
+
+        debug!("{n_removed} interface faces removed");
+        if !unmatched.is_empty() {
+            warn!("{} unmatched interface faces", unmatched.len());
+        }
+
+        unmatched
+    }
+
     /// Remove faces based on their tag
     pub fn remove_faces<F: FnMut(Tag) -> bool>(&mut self, mut face_filter: F) {
         let mut new_faces = Vec::new();
@@ -1131,6 +1762,166 @@ impl<const D: usize, E: Elem> SimplexMesh<D, E> {
         self.ftags = new_ftags.into();
     }
 
+    /// Renumber the vertices: vertex `i` becomes vertex `new_ids[i]`, or is removed if
+    /// `new_ids[i] == Idx::MAX`. If several vertices are renumbered to the same index, the
+    /// coordinates and field values of the first one are used
+    fn renumber_verts(&mut self, new_ids: &[Idx]) {
+        let n_verts = new_ids
+            .iter()
+            .filter(|&&i| i != Idx::MAX)
+            .max()
+            .map_or(0, |&i| i + 1);
+        let mut verts = vec![Point::<D>::zeros(); n_verts as usize];
+        let mut parents = vec![0; n_verts as usize];
+        for (i, &j) in new_ids.iter().enumerate().rev() {
+            if j != Idx::MAX {
+                verts[j as usize] = self.verts.index(i as Idx);
+                parents[j as usize] = i as Idx;
+            }
+        }
+        self.verts = verts.into();
+        self.vert_fields = self.vert_fields.select(&parents);
+
+        self.elems
+            .as_std_mut()
+            .iter_mut()
+            .for_each(|e| *e = E::from_iter(e.iter().map(|&i| new_ids[i as usize])));
+        self.faces
+            .as_std_mut()
+            .iter_mut()
+            .for_each(|f| *f = E::Face::from_iter(f.iter().map(|&i| new_ids[i as usize])));
+
+        self.clear_all();
+    }
+
+    /// Merge the vertices closer than `tol`: each vertex is merged with the first vertex (in
+    /// the mesh ordering) that is closer than `tol` and has not been merged itself. The
+    /// elements and faces are renumbered, and may become degenerate (see
+    /// `remove_degenerate_elements`). Nothing is done if `tol <= 0`
+    ///
+    /// Returns the # of vertices removed
+    pub fn merge_coincident_vertices(&mut self, tol: f64) -> Idx {
+        if tol <= 0.0 {
+            return 0;
+        }
+        debug!("Merge the vertices closer than {tol:.2e}");
+
+        let cell = |p: &Point<D>| p.map(|x| (x / tol).floor() as i64);
+        let mut grid = FxHashMap::<SVector<i64, D>, Vec<Idx>>::default();
+        let mut new_ids = vec![Idx::MAX; self.n_verts() as usize];
+        let mut n_verts = 0;
+        for (i, p) in self.verts().enumerate() {
+            let c = cell(&p);
+            let other = (0..3_usize.pow(D as u32)).find_map(|mut k| {
+                let mut neighbor = c;
+                for x in neighbor.iter_mut() {
+                    *x += (k % 3) as i64 - 1;
+                    k /= 3;
+                }
+                grid.get(&neighbor)?
+                    .iter()
+                    .copied()
+                    .find(|&j| (self.vert(j) - p).norm() < tol)
+            });
+            if let Some(j) = other {
+                new_ids[i] = new_ids[j as usize];
+            } else {
+                new_ids[i] = n_verts;
+                n_verts += 1;
+                grid.entry(c).or_default().push(i as Idx);
+            }
+        }
+
+        let n_removed = self.n_verts() - n_verts;
+        if n_removed > 0 {
+            self.renumber_verts(&new_ids);
+        }
+        debug!("{n_removed} vertices removed");
+
+        n_removed
+    }
+
+    /// Remove the vertices that do not belong to any element or face
+    ///
+    /// Returns the # of vertices removed
+    pub fn remove_unused_vertices(&mut self) -> Idx {
+        debug!("Remove the unused vertices");
+
+        let mut used = vec![false; self.n_verts() as usize];
+        self.elems()
+            .for_each(|e| e.iter().for_each(|&i| used[i as usize] = true));
+        self.faces()
+            .for_each(|f| f.iter().for_each(|&i| used[i as usize] = true));
+
+        let mut n_verts = 0;
+        let new_ids = used
+            .iter()
+            .map(|&u| {
+                if u {
+                    n_verts += 1;
+                    n_verts - 1
+                } else {
+                    Idx::MAX
+                }
+            })
+            .collect::<Vec<_>>();
+
+        let n_removed = self.n_verts() - n_verts;
+        if n_removed > 0 {
+            self.renumber_verts(&new_ids);
+        }
+        debug!("{n_removed} vertices removed");
+
+        n_removed
+    }
+
+    /// Remove the elements and faces with repeated vertices, e.g. after
+    /// `merge_coincident_vertices`
+    ///
+    /// Returns the # of elements and faces removed
+    pub fn remove_degenerate_elements(&mut self) -> (Idx, Idx) {
+        debug!("Remove the degenerate elements and faces");
+
+        let parents = self
+            .elems()
+            .enumerate()
+            .filter(|(_, e)| !has_repeated_vertices(e))
+            .map(|(i, _)| i as Idx)
+            .collect::<Vec<_>>();
+        let elems = parents.iter().map(|&i| self.elem(i)).collect::<Vec<_>>();
+        let etags = parents.iter().map(|&i| self.etag(i)).collect::<Vec<_>>();
+        let (faces, ftags): (Vec<_>, Vec<_>) = self
+            .faces()
+            .zip(self.ftags())
+            .filter(|(f, _)| !has_repeated_vertices(f))
+            .unzip();
+
+        let n_elems = self.n_elems() - elems.len() as Idx;
+        let n_faces = self.n_faces() - faces.len() as Idx;
+        if n_elems > 0 || n_faces > 0 {
+            self.elems = elems.into();
+            self.etags = etags.into();
+            self.elem_fields = self.elem_fields.select(&parents);
+            self.faces = faces.into();
+            self.ftags = ftags.into();
+            self.clear_all();
+        }
+        debug!("{n_elems} elements and {n_faces} faces removed");
+
+        (n_elems, n_faces)
+    }
+
+    /// Replace the elements and element tags; `parents` gives the index of the original element
+    /// for each new element and is used to copy the element fields
+    pub(crate) fn set_elems(&mut self, elems: Vec<E>, etags: Vec<Tag>, parents: &[Idx]) {
+        assert_eq!(elems.len(), etags.len());
+        assert_eq!(elems.len(), parents.len());
+        self.elems = elems.into();
+        self.etags = etags.into();
+        self.elem_fields = self.elem_fields.select(parents);
+        self.clear_all();
+    }
+
     /// Modify the face tags
     pub fn update_face_tags<F: FnMut(Tag) -> Tag>(&mut self, mut new_ftags: F) {
         self.ftags
@@ -1503,11 +2294,14 @@ mod tests {
 
     use crate::{
         mesh::{
+            get_face_to_elem,
+            graph::CSRGraph,
             test_meshes::{test_mesh_2d, test_mesh_3d},
-            Edge, Elem, GElem, SimplexMesh, Tetrahedron, Triangle,
+            Edge, Elem, GElem, Point, SimplexMesh, Tetrahedron, Triangle,
         },
-        min_max_iter, Result,
+        min_max_iter, Idx, Result,
     };
+    use nalgebra::DMatrix;
 
     #[test]
     fn test_2d() {
@@ -1524,6 +2318,43 @@ mod tests {
         assert!((v - 1.0).abs() < 1e-12);
     }
 
+    #[test]
+    fn test_connectivity_3d() {
+        let mut mesh = test_mesh_3d().split().split();
+
+        // compare to the serial version
+        let f2e = get_face_to_elem(mesh.elems());
+        let res = mesh.compute_face_to_elems();
+        assert_eq!(res.len(), f2e.len());
+        for (f, elems) in &f2e {
+            assert!(res.get(f).unwrap().iter().eq(elems.iter()));
+        }
+
+        let v2e = CSRGraph::transpose(&mesh.elems().collect::<Vec<_>>().into(), None);
+        let res = mesh.compute_vertex_to_elems();
+        assert_eq!(res.ptr, v2e.ptr);
+        assert_eq!(res.indices, v2e.indices);
+
+        let mut g = Vec::new();
+        for elems in f2e.values() {
+            if elems.len() == 2 {
+                g.push([elems[0], elems[1]]);
+            }
+        }
+        let e2e = CSRGraph::new(&g);
+        let res = mesh.compute_elem_to_elems();
+        assert_eq!(res.ptr, e2e.ptr);
+        assert_eq!(res.indices, e2e.indices);
+
+        let mut edges = mesh
+            .elems()
+            .flat_map(|e| (0..6).map(move |i| e.sorted_edge(i)))
+            .collect::<Vec<_>>();
+        edges.sort_unstable();
+        edges.dedup();
+        assert_eq!(mesh.compute_edges(), &edges);
+    }
+
     #[test]
     fn test_3d() {
         let mesh = test_mesh_3d();
@@ -1536,6 +2367,114 @@ mod tests {
         assert!(f64::abs(v - 1.0) < 1e-12);
     }
 
+    #[test]
+    fn test_views() -> Result<()> {
+        let mesh = test_mesh_3d().split();
+
+        let verts = mesh.verts_view();
+        assert_eq!(verts.ncols(), mesh.n_verts() as usize);
+        assert!(mesh.verts().zip(verts.column_iter()).all(|(p, c)| p == c));
+        let elems = mesh.elems_view();
+        assert_eq!(elems.shape(), (4, mesh.n_elems() as usize));
+        assert!(mesh
+            .elems()
+            .zip(elems.column_iter())
+            .all(|(e, c)| e.iter().eq(c.iter())));
+        assert!(mesh.etags().eq(mesh.etags_view().iter().copied()));
+        assert_eq!(mesh.faces_view().shape(), (3, mesh.n_faces() as usize));
+        assert!(mesh.ftags().eq(mesh.ftags_view().iter().copied()));
+
+        // share the buffers of an other mesh
+        let verts = verts.clone_owned();
+        let (elems, faces) = (elems.clone_owned(), mesh.faces_view().clone_owned());
+        let (etags, ftags) = (mesh.etags_view().to_vec(), mesh.ftags_view().to_vec());
+        let other = unsafe {
+            SimplexMesh::<3, Tetrahedron>::from_views(
+                verts.as_view(),
+                elems.as_view(),
+                &etags,
+                faces.as_view(),
+                &ftags,
+            )?
+        };
+        assert!(other.verts().eq(mesh.verts()));
+        assert!(other.elems().eq(mesh.elems()));
+        assert!(other.faces().eq(mesh.faces()));
+        assert_eq!(other.verts_view().as_ptr(), verts.as_ptr());
+        assert_eq!(other.elems_view().as_ptr(), elems.as_ptr());
+        assert!((other.vol() - mesh.vol()).abs() < 1e-12);
+
+        let mut copy = other.clone();
+        copy.mut_verts().for_each(|p| *p *= 2.0);
+        assert!((copy.vol() - 8.0 * mesh.vol()).abs() < 1e-12);
+
+        let res = unsafe {
+            SimplexMesh::<3, Tetrahedron>::from_views(
+                verts.as_view(),
+                faces.as_view(),
+                &etags,
+                faces.as_view(),
+                &ftags,
+            )
+        };
+        assert!(res.is_err());
+
+        // strided view
+        let mut padded = DMatrix::<Idx>::zeros(5, elems.ncols());
+        padded.rows_mut(0, 4).copy_from(&elems);
+        let res = unsafe {
+            SimplexMesh::<3, Tetrahedron>::from_views(
+                verts.as_view(),
+                padded.rows(0, 4),
+                &etags,
+                faces.as_view(),
+                &ftags,
+            )
+        };
+        assert!(res.is_err());
+
+        Ok(())
+    }
+
+    #[cfg(feature = "ndarray")]
+    #[test]
+    fn test_arrays() -> Result<()> {
+        let mesh = test_mesh_3d().split();
+
+        let verts = mesh.verts_array();
+        assert_eq!(verts.shape(), [mesh.n_verts() as usize, 3]);
+        assert!(mesh
+            .verts()
+            .zip(verts.rows())
+            .all(|(p, r)| p.iter().eq(r.iter())));
+        let elems = mesh.elems_array();
+        assert_eq!(elems.shape(), [mesh.n_elems() as usize, 4]);
+        assert!(mesh
+            .elems()
+            .zip(elems.rows())
+            .all(|(e, r)| e.iter().eq(r.iter())));
+        let faces = mesh.faces_array();
+        assert_eq!(faces.shape(), [mesh.n_faces() as usize, 3]);
+
+        let etags = ndarray::ArrayView1::from(mesh.etags_view());
+        let ftags = ndarray::ArrayView1::from(mesh.ftags_view());
+        let other = unsafe {
+            SimplexMesh::<3, Tetrahedron>::from_arrays(verts, elems, etags, faces, ftags)?
+        };
+        assert!(other.verts().eq(mesh.verts()));
+        assert!(other.elems().eq(mesh.elems()));
+        assert!(other.faces().eq(mesh.faces()));
+        assert_eq!(other.verts_view().as_ptr(), mesh.verts_view().as_ptr());
+
+        // not in standard layout
+        let res = unsafe {
+            SimplexMesh::<3, Tetrahedron>::from_arrays(verts, elems.t(), etags, faces, ftags)
+        };
+        assert!(res.is_err());
+
+        Ok(())
+    }
+
     #[test]
     fn test_cell_to_node() -> Result<()> {
         let mesh = test_mesh_3d();
@@ -1570,6 +2509,31 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn test_data_conversion_sizes() -> Result<()> {
+        let mut mesh = test_mesh_2d().split();
+        mesh.compute_vertex_to_elems();
+        mesh.compute_volumes();
+
+        // constant vector field
+        let n_elems = mesh.n_elems() as usize;
+        let v_e = [1.0, 2.0].repeat(n_elems);
+        let v_v = mesh.elem_data_to_vertex_data(&v_e)?;
+        assert!(v_v
+            .chunks(2)
+            .all(|v| (v[0] - 1.0).abs() < 1e-12 && (v[1] - 2.0).abs() < 1e-12));
+        let v_e2 = mesh.vertex_data_to_elem_data(&v_v)?;
+        assert!(v_e
+            .iter()
+            .zip(v_e2.iter())
+            .all(|(a, b)| (a - b).abs() < 1e-12));
+
+        assert!(mesh.elem_data_to_vertex_data(&v_e[1..]).is_err());
+        assert!(mesh.vertex_data_to_elem_data(&v_v[1..]).is_err());
+
+        Ok(())
+    }
+
     #[test]
     fn test_vertex_to_elem() {
         let mesh = test_mesh_3d();
@@ -1613,6 +2577,24 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_tag_names() {
+        let mut mesh = test_mesh_3d();
+        mesh.tag_names_mut().set_elem_name(1, "fluid");
+        mesh.tag_names_mut().set_face_name(2, "inlet");
+
+        let mesh = mesh.split();
+        assert_eq!(mesh.tag_names().elem_tag_by_name("fluid"), Some(1));
+        assert_eq!(mesh.tag_names().tag_by_name("inlet"), Some(2));
+
+        let sub_mesh = mesh.extract_tag(1).mesh;
+        assert_eq!(sub_mesh.tag_names().face_tag_by_name("inlet"), Some(2));
+
+        let (bdy, _) = mesh.boundary();
+        assert_eq!(bdy.tag_names().elem_tag_by_name("inlet"), Some(2));
+        assert_eq!(bdy.tag_names().elem_tag_by_name("fluid"), None);
+    }
+
     #[test]
     fn test_quas_2d() {
         let verts = [0., 0., 1., 0., 1., 1., 0., 1., 1.5, 0.5];
@@ -1739,4 +2721,172 @@ mod tests {
         assert!((gamma_min - 0.623).abs() < 1e-3);
         assert!((gamma_max - 1.0).abs() < 1e-3);
     }
+
+    #[test]
+    fn test_merge_coincident_vertices() {
+        let mut mesh = test_mesh_2d();
+        let other = test_mesh_2d();
+        mesh.add(&other, |_| true, |_| true, None);
+        assert_eq!(mesh.n_verts(), 8);
+
+        assert_eq!(mesh.merge_coincident_vertices(1e-8), 4);
+        assert_eq!(mesh.n_verts(), 4);
+        assert_eq!(mesh.n_elems(), 4);
+        assert_eq!(mesh.elem(2), mesh.elem(0));
+        assert_eq!(mesh.elem(3), mesh.elem(1));
+        assert_eq!(mesh.remove_degenerate_elements(), (0, 0));
+        assert_eq!(mesh.remove_unused_vertices(), 0);
+
+        assert_eq!(mesh.merge_coincident_vertices(0.0), 0);
+        assert_eq!(mesh.merge_coincident_vertices(-1.0), 0);
+        assert_eq!(mesh.n_verts(), 4);
+    }
+
+    #[test]
+    fn test_remove_degenerate_elements() {
+        let mut mesh = test_mesh_2d();
+        *mesh.mut_verts().nth(3).unwrap() = Point::<2>::new(1e-10, 0.0);
+
+        mesh.add_vertex_field::<i32, 1>("id", vec![0, 1, 2, 3])
+            .unwrap();
+        mesh.add_elem_field::<i32, 1>("id", vec![0, 1]).unwrap();
+
+        assert_eq!(mesh.merge_coincident_vertices(1e-8), 1);
+        assert_eq!(mesh.n_verts(), 3);
+        assert_eq!(mesh.get_vertex_field::<i32, 1>("id").unwrap(), [0, 1, 2]);
+        assert_eq!(mesh.elem(1), Triangle::new(0, 2, 0));
+        assert_eq!(mesh.remove_degenerate_elements(), (1, 1));
+        assert_eq!(mesh.n_elems(), 1);
+        assert_eq!(mesh.get_elem_field::<i32, 1>("id").unwrap(), [0]);
+        assert_eq!(mesh.n_faces(), 3);
+        assert_eq!(mesh.face(2), Edge::new(2, 0));
+        assert_eq!(mesh.remove_unused_vertices(), 0);
+    }
+
+    #[test]
+    fn test_remove_unused_vertices() {
+        let mut mesh = test_mesh_2d();
+        mesh.add_verts([[2.0, 2.0], [0.5, 0.5]].iter().map(<[f64; 2]>::as_slice));
+        assert_eq!(mesh.n_verts(), 6);
+        mesh.add_vertex_field::<i32, 1>("id", (0..6).collect())
+            .unwrap();
+        assert_eq!(mesh.remove_unused_vertices(), 2);
+        assert_eq!(mesh.n_verts(), 4);
+        assert_eq!(mesh.get_vertex_field::<i32, 1>("id").unwrap(), [0, 1, 2, 3]);
+        let v: f64 = mesh.gelems().map(|ge| ge.vol()).sum();
+        assert!((v - 1.0).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_extract_tagged() {
+        let mesh = test_mesh_2d();
+
+        let (sub, vert_ids, elem_ids) = mesh.extract_tagged(&[1]);
+        assert_eq!(vert_ids, [0, 1, 2]);
+        assert_eq!(elem_ids, [0]);
+        assert_eq!(sub.n_elems(), 1);
+        assert_eq!(sub.n_faces(), 3);
+        assert_eq!(sub.face(2), Edge::new(2, 0));
+        assert_eq!(sub.ftag(2), 5);
+        assert!(sub.validate().is_empty());
+
+        let (sub, vert_ids, _) = mesh.extract_tagged(&[2]);
+        assert_eq!(vert_ids, [0, 2, 3]);
+        assert_eq!(sub.n_faces(), 3);
+        assert_eq!(sub.face(2), Edge::new(0, 1));
+        assert_eq!(sub.ftag(2), 5);
+
+        let (sub, _, _) = mesh.extract_tagged(&[1, 2]);
+        assert_eq!(sub.n_faces(), 4);
+    }
+
+    #[test]
+    fn test_boundary_with_parents() -> Result<()> {
+        let mesh = test_mesh_2d();
+        let (bdy, vert_ids, parents) = mesh.boundary_with_parents()?;
+        assert_eq!(bdy.n_elems(), 4);
+        assert_eq!(vert_ids, [0, 1, 2, 3]);
+        assert_eq!(parents, [(0, 0), (0, 1), (1, 1), (1, 2)]);
+
+        let mut mesh = test_mesh_2d();
+        mesh.add_boundary_faces();
+        let (_, _, parents) = mesh.boundary_with_parents()?;
+        for ((f, (i_elem, i)), tag) in mesh.faces().zip(parents).zip(mesh.ftags()) {
+            let e = mesh.elem(i_elem);
+            assert_eq!(e.face(i).sorted(), f.sorted());
+            if f.sorted() == Edge::new(0, 2) {
+                assert_eq!((i_elem, i), (1, 0));
+            } else {
+                assert_eq!(mesh.etag(i_elem), if tag < 3 { 1 } else { 2 });
+            }
+        }
+
+        let mesh = SimplexMesh::new(
+            mesh.verts().collect(),
+            mesh.elems().collect(),
+            mesh.etags().collect(),
+            vec![Edge::new(1, 3)],
+            vec![1],
+        );
+        assert!(mesh.boundary_with_parents().is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_merge() {
+        let mut mesh = test_mesh_2d().extract_tagged(&[1]).0;
+        let other = test_mesh_2d().extract_tagged(&[2]).0;
+        assert!(mesh.merge(&other, 1e-8).is_empty());
+        assert_eq!(mesh.n_verts(), 4);
+        assert_eq!(mesh.n_elems(), 2);
+        assert_eq!(mesh.n_faces(), 5);
+        assert!(mesh.validate().is_empty());
+
+        let mut mesh = test_mesh_2d();
+        let mut other = test_mesh_2d();
+        other.mut_verts().for_each(|p| p[0] += 1.0);
+        assert!(mesh.merge(&other, 1e-8).is_empty());
+        assert_eq!(mesh.n_verts(), 6);
+        assert_eq!(mesh.n_elems(), 4);
+        assert_eq!(mesh.n_faces(), 7);
+        let v: f64 = mesh.gelems().map(|ge| ge.vol()).sum();
+        assert!((v - 2.0).abs() < 1e-12);
+
+        let mut mesh = test_mesh_2d();
+        let mut other = test_mesh_2d().split();
+        other.mut_verts().for_each(|p| p[0] += 1.0);
+        let unmatched = mesh.merge(&other, 1e-8);
+        assert_eq!(unmatched, [1]);
+        assert_eq!(mesh.face(1), Edge::new(1, 2));
+    }
+
+    #[test]
+    fn test_merge_fields() {
+        let mut mesh = test_mesh_2d();
+        mesh.add_vertex_field::<f64, 1>("x", mesh.verts().map(|p| p[0]).collect())
+            .unwrap();
+        mesh.add_vertex_field::<i32, 1>("id", vec![1; 4]).unwrap();
+        mesh.add_elem_field::<i32, 1>("id", vec![1, 2]).unwrap();
+
+        let mut other = test_mesh_2d();
+        other.mut_verts().for_each(|p| p[0] += 1.0);
+        other
+            .add_vertex_field::<f64, 1>("x", other.verts().map(|p| p[0]).collect())
+            .unwrap();
+        other.add_elem_field::<i32, 1>("id", vec![3, 4]).unwrap();
+        other.add_elem_field::<i32, 1>("other", vec![5, 6]).unwrap();
+
+        mesh.merge(&other, 1e-8);
+        assert_eq!(mesh.n_verts(), 6);
+        assert_eq!(mesh.n_elems(), 4);
+        let x = mesh.get_vertex_field::<f64, 1>("x").unwrap();
+        assert!(mesh.verts().zip(x).all(|(p, &x)| (p[0] - x).abs() < 1e-12));
+        assert_eq!(
+            mesh.get_vertex_field::<i32, 1>("id").unwrap(),
+            [1, 1, 1, 1, 0, 0]
+        );
+        assert_eq!(mesh.get_elem_field::<i32, 1>("id").unwrap(), [1, 2, 3, 4]);
+        assert!(mesh.get_elem_field::<i32, 1>("other").is_err());
+    }
 }
@@ -1,6 +1,7 @@
 use crate::{
+    geometry::Geometry,
     mesh::{Elem, Point, SimplexMesh},
-    Idx, Tag,
+    Dim, Idx, Result, Tag,
 };
 use log::debug;
 use rustc_hash::FxHashMap;
@@ -136,12 +137,9 @@ impl<const D: usize, E: Elem> SimplexMesh<D, E> {
         (elems, etags)
     }
 
-    /// Create a new mesh by splitting all the mesh elements and faces
-    /// TODO: transfer the vertex and cell data
+    /// Get the index of the vertex created at the center of each edge (sorted) by `split`
     #[must_use]
-    pub fn split(&self) -> Self {
-        debug!("Split all the elements uniformly");
-
+    pub fn split_vertex_indices(&self) -> FxHashMap<[Idx; 2], Idx> {
         let mut edges: FxHashMap<[Idx; 2], Idx> = FxHashMap::default();
         let mut i_edg = self.n_verts() as Idx;
         for e in self.elems() {
@@ -155,6 +153,17 @@ impl<const D: usize, E: Elem> SimplexMesh<D, E> {
                 }
             }
         }
+        edges
+    }
+
+    /// Create a new mesh by splitting all the mesh elements and faces. The vertex and element
+    /// fields (see `add_vertex_field`) are transferred, the values at the new vertices being
+    /// interpolated at the edge centers
+    #[must_use]
+    pub fn split(&self) -> Self {
+        debug!("Split all the elements uniformly");
+
+        let edges = self.split_vertex_indices();
 
         let new_n_verts = self.n_verts() + edges.len() as Idx;
         let mut verts = Vec::with_capacity(new_n_verts as usize);
@@ -167,7 +176,7 @@ impl<const D: usize, E: Elem> SimplexMesh<D, E> {
             verts[i as usize] = 0.5 * (p0 + p1);
         }
 
-        if E::N_VERTS == 3 {
+        let mut res = if E::N_VERTS == 3 {
             let (elems, etags) =
                 Self::split_tris(self.n_elems(), self.elems().zip(self.etags()), &edges);
             let (faces, ftags) =
@@ -179,7 +188,41 @@ impl<const D: usize, E: Elem> SimplexMesh<D, E> {
             let (faces, ftags) =
                 Self::split_tris(self.n_faces(), self.faces().zip(self.ftags()), &edges);
             Self::new(verts, elems, etags, faces, ftags)
+        };
+        *res.tag_names_mut() = self.tag_names().clone();
+
+        let n_children = if E::N_VERTS == 3 { 4 } else { 8 };
+        let parents = (0..self.n_elems())
+            .flat_map(|i| std::iter::repeat(i).take(n_children))
+            .collect::<Vec<_>>();
+        *res.vertex_fields_mut() = self.vertex_fields().split(&edges);
+        *res.elem_fields_mut() = self.elem_fields().select(&parents);
+
+        res
+    }
+
+    /// Split all the elements uniformly (see `split`) and project the newly created boundary
+    /// vertices onto `geom`, so that repeated refinement converges to the actual geometry.
+    /// The topology of the split mesh is computed (from the topology of `self` if available)
+    pub fn split_projected<G: Geometry<D>>(&self, geom: &G) -> Result<Self> {
+        debug!("Split all the elements uniformly and project onto the geometry");
+
+        let mut res = self.split();
+        if let Ok(topo) = self.get_topology() {
+            res.compute_topology_from(topo.clone());
+        } else {
+            res.compute_topology();
         }
+
+        let n_verts = self.n_verts() as usize;
+        let vtags = res.get_vertex_tags()?.to_vec();
+        for (p, tag) in res.mut_verts().zip(vtags.iter()).skip(n_verts) {
+            if tag.0 < E::DIM as Dim {
+                geom.project(p, tag);
+            }
+        }
+
+        Ok(res)
     }
 }
 
@@ -189,11 +232,32 @@ mod tests {
     use crate::{
         mesh::{
             get_face_to_elem,
-            test_meshes::{test_mesh_2d, test_mesh_3d},
+            test_meshes::{sphere_mesh, test_mesh_2d, test_mesh_3d, SphereGeometry},
             Elem, GElem,
         },
-        Idx,
+        Idx, Result,
     };
+    use std::f64::consts::PI;
+
+    #[test]
+    fn test_split_projected() -> Result<()> {
+        let mesh = sphere_mesh(1);
+        let geom = SphereGeometry;
+
+        let flg = mesh.split().boundary_flag();
+        let mesh = mesh.split_projected(&geom)?;
+        for (p, &is_bdy) in mesh.verts().zip(flg.iter()) {
+            if is_bdy {
+                assert!((p.norm() - 1.0).abs() < 1e-12);
+            }
+        }
+        assert!(mesh.get_topology().is_ok());
+
+        let mesh = mesh.split_projected(&geom)?;
+        assert!((mesh.vol() - 4.0 * PI / 3.0).abs() < 0.2);
+
+        Ok(())
+    }
 
     #[test]
     fn test_split_2d() {
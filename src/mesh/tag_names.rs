@@ -0,0 +1,123 @@
+use crate::Tag;
+use rustc_hash::FxHashMap;
+
+/// Names associated with the element and face tags
+#[derive(Clone, Debug, Default)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
+pub struct TagNames {
+    elems: FxHashMap<Tag, String>,
+    faces: FxHashMap<Tag, String>,
+}
+
+impl TagNames {
+    /// Set the name of element tag `tag`
+    pub fn set_elem_name(&mut self, tag: Tag, name: &str) {
+        self.elems.insert(tag, name.to_string());
+    }
+
+    /// Set the name of face tag `tag`
+    pub fn set_face_name(&mut self, tag: Tag, name: &str) {
+        self.faces.insert(tag, name.to_string());
+    }
+
+    /// Get the name of element tag `tag`
+    #[must_use]
+    pub fn elem_name(&self, tag: Tag) -> Option<&str> {
+        self.elems.get(&tag).map(String::as_str)
+    }
+
+    /// Get the name of face tag `tag`
+    #[must_use]
+    pub fn face_name(&self, tag: Tag) -> Option<&str> {
+        self.faces.get(&tag).map(String::as_str)
+    }
+
+    /// Get the element tag named `name`, if any
+    #[must_use]
+    pub fn elem_tag_by_name(&self, name: &str) -> Option<Tag> {
+        self.elems
+            .iter()
+            .find(|(_, n)| n.as_str() == name)
+            .map(|(&t, _)| t)
+    }
+
+    /// Get the face tag named `name`, if any
+    #[must_use]
+    pub fn face_tag_by_name(&self, name: &str) -> Option<Tag> {
+        self.faces
+            .iter()
+            .find(|(_, n)| n.as_str() == name)
+            .map(|(&t, _)| t)
+    }
+
+    /// Get the element or face tag named `name`, looking first at the element names
+    #[must_use]
+    pub fn tag_by_name(&self, name: &str) -> Option<Tag> {
+        self.elem_tag_by_name(name)
+            .or_else(|| self.face_tag_by_name(name))
+    }
+
+    /// Iterate over the (tag, name) pairs of the elements
+    pub fn elem_names(&self) -> impl Iterator<Item = (Tag, &str)> + '_ {
+        self.elems.iter().map(|(&t, n)| (t, n.as_str()))
+    }
+
+    /// Iterate over the (tag, name) pairs of the faces
+    pub fn face_names(&self) -> impl Iterator<Item = (Tag, &str)> + '_ {
+        self.faces.iter().map(|(&t, n)| (t, n.as_str()))
+    }
+
+    /// Names to be used for the boundary mesh: the face names become element names
+    #[must_use]
+    pub fn boundary(&self) -> Self {
+        Self {
+            elems: self.faces.clone(),
+            faces: FxHashMap::default(),
+        }
+    }
+
+    /// Check if no name is defined
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.elems.is_empty() && self.faces.is_empty()
+    }
+
+    /// Remove all the names
+    pub fn clear(&mut self) {
+        self.elems.clear();
+        self.faces.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::TagNames;
+
+    #[test]
+    fn test_tag_names() {
+        let mut names = TagNames::default();
+        assert!(names.is_empty());
+
+        names.set_elem_name(1, "fluid");
+        names.set_face_name(1, "wall");
+        names.set_face_name(2, "inlet");
+        assert!(!names.is_empty());
+
+        assert_eq!(names.elem_name(1), Some("fluid"));
+        assert_eq!(names.face_name(2), Some("inlet"));
+        assert_eq!(names.elem_name(2), None);
+
+        assert_eq!(names.elem_tag_by_name("fluid"), Some(1));
+        assert_eq!(names.face_tag_by_name("inlet"), Some(2));
+        assert_eq!(names.elem_tag_by_name("inlet"), None);
+        assert_eq!(names.tag_by_name("inlet"), Some(2));
+        assert_eq!(names.tag_by_name("outlet"), None);
+
+        let bdy = names.boundary();
+        assert_eq!(bdy.elem_tag_by_name("inlet"), Some(2));
+        assert_eq!(bdy.face_names().count(), 0);
+
+        names.clear();
+        assert!(names.is_empty());
+    }
+}
@@ -1,5 +1,5 @@
 use crate::geometry::Geometry;
-use crate::mesh::{Edge, Point, SimplexMesh, Tetrahedron, Triangle};
+use crate::mesh::{Edge, HybridMesh, Point, SimplexMesh, Tetrahedron, Triangle};
 use crate::{Dim, Error, Result, TopoTag};
 use std::fs::File;
 use std::io::Write;
@@ -237,6 +237,55 @@ pub fn test_mesh_3d() -> SimplexMesh<3, Tetrahedron> {
     SimplexMesh::new(coords, elems, etags, faces, ftags)
 }
 
+/// Build a hybrid 3d mesh of a cube (1 hexahedron) with a pyramid on top and a prism on the
+/// side
+#[must_use]
+pub fn test_hybrid_mesh() -> HybridMesh {
+    let mut mesh = HybridMesh {
+        verts: vec![
+            Point::<3>::new(0., 0., 0.),
+            Point::<3>::new(1., 0., 0.),
+            Point::<3>::new(1., 1., 0.),
+            Point::<3>::new(0., 1., 0.),
+            Point::<3>::new(0., 0., 1.),
+            Point::<3>::new(1., 0., 1.),
+            Point::<3>::new(1., 1., 1.),
+            Point::<3>::new(0., 1., 1.),
+            Point::<3>::new(0.5, 0.5, 1.5),
+            Point::<3>::new(2., 0., 0.5),
+            Point::<3>::new(2., 1., 0.5),
+        ],
+        pyrs: vec![[4, 5, 6, 7, 8]],
+        pyr_tags: vec![1],
+        pris: vec![[1, 5, 9, 2, 6, 10]],
+        pri_tags: vec![2],
+        hexs: vec![[0, 1, 2, 3, 4, 5, 6, 7]],
+        hex_tags: vec![1],
+        tris: vec![
+            [4, 5, 8],
+            [5, 6, 8],
+            [6, 7, 8],
+            [7, 4, 8],
+            [1, 9, 5],
+            [2, 6, 10],
+        ],
+        tri_tags: vec![1, 1, 1, 1, 2, 2],
+        quas: vec![
+            [0, 3, 2, 1],
+            [0, 1, 5, 4],
+            [3, 0, 4, 7],
+            [2, 3, 7, 6],
+            [5, 9, 10, 6],
+            [9, 1, 2, 10],
+        ],
+        qua_tags: vec![1, 1, 1, 1, 2, 2],
+        ..HybridMesh::default()
+    };
+    mesh.tag_names.set_elem_name(2, "prism");
+    mesh.tag_names.set_face_name(2, "side");
+    mesh
+}
+
 /// Gaussian size field in 3d
 #[must_use]
 pub fn h_3d(p: &Point<3>) -> f64 {
@@ -363,6 +412,17 @@ impl Geometry<3> for SphereGeometry {
         let cos_a = n.dot(&n_ref).clamp(-1.0, 1.0);
         f64::acos(cos_a).to_degrees()
     }
+
+    fn curvature(&self, pt: &Point<3>, _tag: &TopoTag) -> Result<(Point<3>, Option<Point<3>>)> {
+        let n = pt.normalize();
+        let a = if n[0].abs() < 0.9 {
+            Point::<3>::new(1.0, 0.0, 0.0)
+        } else {
+            Point::<3>::new(0.0, 1.0, 0.0)
+        };
+        let u = (a - a.dot(&n) * n).normalize();
+        Ok((u, Some(n.cross(&u))))
+    }
 }
 
 #[must_use]
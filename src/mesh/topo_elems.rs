@@ -1,11 +1,18 @@
 use super::{
-    geom_elems::{GEdge, GEdgeQuadratique, GElem, GTetrahedron, GTetrahedronQuadratique, GTriangle, GTriangleQuadratique, GVertex},
+    geom_elems::{
+        GEdge, GEdgeQuadratique, GElem, GTetrahedron, GTetrahedronQuadratique, GTriangle,
+        GTriangleQuadratique, GVertex,
+    },
     twovec,
 };
 use crate::metric::Metric;
 use crate::Idx;
 use core::hash::Hash;
 use core::slice::Iter;
+use rayon::{
+    iter::{IndexedParallelIterator, IntoParallelIterator, ParallelIterator},
+    slice::{ParallelSlice, ParallelSliceMut},
+};
 use rustc_hash::FxHashMap;
 use std::fmt::Debug;
 use std::ops::Index;
@@ -13,6 +20,8 @@ use std::{array::IntoIter, ops::IndexMut};
 
 /// Topological elements (i.e. element-to-vertex connectivity)
 /// Only usable for simplices
+/// The memory layout must be the one of `[Idx; N_VERTS]` (e.g. `#[repr(transparent)]`), so
+/// that the connectivity can be viewed without copy
 pub trait Elem:
     Clone
     + Copy
@@ -97,6 +106,7 @@ pub trait Elem:
 
 /// Tetrahedron
 #[derive(Clone, Copy, Hash, Debug, Eq, PartialEq, Default)]
+#[repr(transparent)]
 pub struct Tetrahedron([Idx; 4]);
 
 impl Tetrahedron {
@@ -187,14 +197,30 @@ impl IndexMut<usize> for Tetrahedron {
     }
 }
 
-/// Tetrahedron Quadratique
+/// Second order tetrahedron: the 4 vertices followed by the mid-edge nodes of edges
+/// (2, 3), (1, 3), (1, 2), (0, 2), (0, 3) and (0, 1)
 #[derive(Clone, Copy, Hash, Debug, Eq, PartialEq, Default)]
+#[repr(transparent)]
 pub struct TetrahedronQuadratique([Idx; 10]);
 
 impl TetrahedronQuadratique {
     #[must_use]
-    pub const fn new(i0: Idx, i1: Idx, i2: Idx, i3: Idx, i4: Idx, i5: Idx, i6: Idx, i7: Idx, i8: Idx, i9: Idx) -> Self {
-        Self([i0, i1, i2, i3, i4, i5, i6, i7, i8, i9])
+    pub const fn new(nodes: [Idx; 10]) -> Self {
+        Self(nodes)
+    }
+
+    /// Get the mid-edge node of the i-th edge
+    #[must_use]
+    pub const fn mid_node(&self, i: Idx) -> Idx {
+        match i {
+            0 => self.0[9],
+            1 => self.0[6],
+            2 => self.0[7],
+            3 => self.0[8],
+            4 => self.0[5],
+            5 => self.0[4],
+            _ => unreachable!(),
+        }
     }
 }
 
@@ -203,7 +229,7 @@ impl Elem for TetrahedronQuadratique {
     const N_FACES: Idx = 4;
     const N_EDGES: Idx = 6;
     const DIM: Idx = 3;
-    const NAME: &'static str = "TetrahedronQuadratique";
+    const NAME: &'static str = "Tetrahedron_10";
 
     type Face = TriangleQuadratique;
     type Geom<const D: usize, M: Metric<D>> = GTetrahedronQuadratique<D, M>;
@@ -234,29 +260,36 @@ impl Elem for TetrahedronQuadratique {
     fn face(&self, i: Idx) -> Self::Face {
         debug_assert!(i < Self::N_FACES);
         match i {
-            0 => TriangleQuadratique([self.0[1], self.0[2], self.0[3], self.0[4], self.0[5], self.0[6]]),
-            1 => TriangleQuadratique([self.0[2], self.0[0], self.0[3], self.0[4], self.0[7], self.0[8]]),
-            2 => TriangleQuadratique([self.0[0], self.0[1], self.0[3], self.0[5], self.0[8], self.0[9]]),
-            3 => TriangleQuadratique([self.0[0], self.0[2], self.0[1], self.0[6], self.0[7], self.0[9]]),
+            0 => TriangleQuadratique([
+                self.0[1], self.0[2], self.0[3], self.0[4], self.0[5], self.0[6],
+            ]),
+            1 => TriangleQuadratique([
+                self.0[2], self.0[0], self.0[3], self.0[8], self.0[4], self.0[7],
+            ]),
+            2 => TriangleQuadratique([
+                self.0[0], self.0[1], self.0[3], self.0[5], self.0[8], self.0[9],
+            ]),
+            3 => TriangleQuadratique([
+                self.0[0], self.0[2], self.0[1], self.0[6], self.0[9], self.0[7],
+            ]),
             _ => TriangleQuadratique([0, 0, 0, 0, 0, 0]),
         }
     }
 
-    fn edge(&self, i: Idx) -> [Idx; 3] {
+    fn edge(&self, i: Idx) -> [Idx; 2] {
         debug_assert!(i < Self::N_EDGES);
         match i {
-            0 => EdgeQuadratique([self.0[0], self.0[1], self.0[9]]),
-            1 => EdgeQuadratique([self.0[1], self.0[2], self.0[6]]),
-            2 => EdgeQuadratique([self.0[2], self.0[0], self.0[7]]),
-            3 => EdgeQuadratique([self.0[0], self.0[3], self.0[8]]),
-            4 => EdgeQuadratique([self.0[1], self.0[3], self.0[5]]),
-            5 => EdgeQuadratique([self.0[2], self.0[3], self.0[4]]),
-            _ => EdgeQuadratique([0, 0, 0]),
+            0 => [self.0[0], self.0[1]],
+            1 => [self.0[1], self.0[2]],
+            2 => [self.0[2], self.0[0]],
+            3 => [self.0[0], self.0[3]],
+            4 => [self.0[1], self.0[3]],
+            5 => [self.0[2], self.0[3]],
+            _ => [0, 0],
         }
     }
 }
 
-
 impl IntoIterator for TetrahedronQuadratique {
     type Item = Idx;
     type IntoIter = IntoIter<Idx, 10>;
@@ -280,9 +313,9 @@ impl IndexMut<usize> for TetrahedronQuadratique {
     }
 }
 
-
 /// Triangle
 #[derive(Clone, Copy, Hash, Debug, Eq, PartialEq, Default)]
+#[repr(transparent)]
 pub struct Triangle([Idx; 3]);
 
 impl Triangle {
@@ -366,8 +399,10 @@ impl IndexMut<usize> for Triangle {
     }
 }
 
-/// Triangle Quadratique
+/// Second order triangle: the 3 vertices followed by the mid-edge nodes of edges (1, 2), (2, 0)
+/// and (0, 1)
 #[derive(Clone, Copy, Hash, Debug, Eq, PartialEq, Default)]
+#[repr(transparent)]
 pub struct TriangleQuadratique([Idx; 6]);
 
 impl TriangleQuadratique {
@@ -375,14 +410,25 @@ impl TriangleQuadratique {
     pub const fn new(i0: Idx, i1: Idx, i2: Idx, i3: Idx, i4: Idx, i5: Idx) -> Self {
         Self([i0, i1, i2, i3, i4, i5])
     }
+
+    /// Get the mid-edge node of the i-th edge
+    #[must_use]
+    pub const fn mid_node(&self, i: Idx) -> Idx {
+        match i {
+            0 => self.0[5],
+            1 => self.0[3],
+            2 => self.0[4],
+            _ => unreachable!(),
+        }
+    }
 }
 
 impl Elem for TriangleQuadratique {
     const N_VERTS: Idx = 6;
     const N_FACES: Idx = 3;
     const N_EDGES: Idx = 3;
-    const DIM: Idx = 3;
-    const NAME: &'static str = "TriangleQuadratique";
+    const DIM: Idx = 2;
+    const NAME: &'static str = "Triangle_6";
     type Face = EdgeQuadratique;
     type Geom<const D: usize, M: Metric<D>> = GTriangleQuadratique<D, M>;
 
@@ -419,13 +465,13 @@ impl Elem for TriangleQuadratique {
         }
     }
 
-    fn edge(&self, i: Idx) -> [Idx; 3] {
+    fn edge(&self, i: Idx) -> [Idx; 2] {
         assert!(i < Self::N_EDGES);
         match i {
-            0 => [self.0[0], self.0[1], self.0[5]],
-            1 => [self.0[1], self.0[2], self.0[3]],
-            2 => [self.0[2], self.0[0], self.0[4]],
-            _ => [0, 0, 0],
+            0 => [self.0[0], self.0[1]],
+            1 => [self.0[1], self.0[2]],
+            2 => [self.0[2], self.0[0]],
+            _ => [0, 0],
         }
     }
 }
@@ -451,9 +497,9 @@ impl IndexMut<usize> for TriangleQuadratique {
     }
 }
 
-
 /// Edge
 #[derive(Clone, Copy, Hash, Debug, Eq, PartialEq, Default)]
+#[repr(transparent)]
 pub struct Edge([Idx; 2]);
 
 impl Edge {
@@ -534,8 +580,9 @@ impl IndexMut<usize> for Edge {
     }
 }
 
-/// Edge Quadratique
+/// Second order edge: the 2 vertices followed by the mid-edge node
 #[derive(Clone, Copy, Hash, Debug, Eq, PartialEq, Default)]
+#[repr(transparent)]
 pub struct EdgeQuadratique([Idx; 3]);
 
 impl EdgeQuadratique {
@@ -547,10 +594,10 @@ impl EdgeQuadratique {
 
 impl Elem for EdgeQuadratique {
     const N_VERTS: Idx = 3;
-    const N_FACES: Idx = 3;
+    const N_FACES: Idx = 2;
     const N_EDGES: Idx = 1;
-    const DIM: Idx = 2;
-    const NAME: &'static str = "Polyline_Quad";
+    const DIM: Idx = 1;
+    const NAME: &'static str = "Edge_3";
     type Face = Vertex;
     type Geom<const D: usize, M: Metric<D>> = GEdgeQuadratique<D, M>;
 
@@ -582,16 +629,15 @@ impl Elem for EdgeQuadratique {
         match i {
             0 => Vertex([self.0[0]]),
             1 => Vertex([self.0[1]]),
-            2 => Vertex([self.0[2]]),
             _ => Vertex([0]),
         }
     }
 
-    fn edge(&self, i: Idx) -> [Idx; 3] {
+    fn edge(&self, i: Idx) -> [Idx; 2] {
         assert!(i < Self::N_EDGES);
         match i {
-            0 => self.0,
-            _ => [0, 0, 0],
+            0 => [self.0[0], self.0[1]],
+            _ => [0, 0],
         }
     }
 }
@@ -618,10 +664,10 @@ impl IndexMut<usize> for EdgeQuadratique {
     }
 }
 
-
 /// Vertex
 /// The Vertex edges and afaces cannot be computed
 #[derive(Clone, Copy, Hash, Debug, Eq, PartialEq, Default)]
+#[repr(transparent)]
 pub struct Vertex([Idx; 1]);
 
 impl Elem for Vertex {
@@ -709,9 +755,48 @@ pub fn get_face_to_elem<E: Elem, I: Iterator<Item = E>>(
     map
 }
 
+/// Parallel version of `get_face_to_elem`: the (face, element) pairs are sorted in parallel,
+/// and the element indices are sorted for each face as in the serial version
+#[must_use]
+pub fn par_get_face_to_elem<E: Elem, I: IndexedParallelIterator<Item = E>>(
+    elems: I,
+) -> FxHashMap<E::Face, twovec::Vec<Idx>> {
+    let mut faces: Vec<_> = elems
+        .enumerate()
+        .flat_map_iter(|(i_elem, elem)| {
+            (0..E::N_FACES).map(move |i_face| {
+                let mut f = elem.face(i_face);
+                f.sort();
+                (f, i_elem as Idx)
+            })
+        })
+        .collect();
+    faces.par_sort_unstable_by(|(f0, i0), (f1, i1)| f0.iter().cmp(f1.iter()).then(i0.cmp(i1)));
+
+    let mut starts: Vec<_> = (0..faces.len())
+        .into_par_iter()
+        .filter(|&i| i == 0 || faces[i].0 != faces[i - 1].0)
+        .collect();
+    starts.push(faces.len());
+
+    starts
+        .par_windows(2)
+        .map(|w| {
+            let (f, i_elem) = faces[w[0]];
+            let mut elems = twovec::Vec::with_single(i_elem);
+            faces[w[0] + 1..w[1]]
+                .iter()
+                .for_each(|&(_, i_elem)| elems.push(i_elem));
+            (f, elems)
+        })
+        .collect()
+}
+
 #[cfg(test)]
 mod tests {
-    use super::{get_face_to_elem, Edge, Idx, Triangle};
+    use super::{get_face_to_elem, par_get_face_to_elem, Edge, Idx, Triangle};
+    use crate::mesh::test_meshes::test_mesh_3d;
+    use rayon::iter::IntoParallelIterator;
 
     #[test]
     fn test_2d() {
@@ -735,4 +820,17 @@ mod tests {
         assert_eq!(f2e.len(), 1);
         assert_eq!(f2e[0], 1);
     }
+
+    #[test]
+    fn test_par() {
+        let mesh = test_mesh_3d().split().split();
+        let elems = mesh.elems().collect::<Vec<_>>();
+        let faces = get_face_to_elem(elems.iter().copied());
+        let par_faces = par_get_face_to_elem(elems.into_par_iter());
+        assert_eq!(faces.len(), par_faces.len());
+        for (f, f2e) in &faces {
+            let par_f2e = par_faces.get(f).unwrap();
+            assert!(f2e.iter().eq(par_f2e.iter()));
+        }
+    }
 }
@@ -0,0 +1,210 @@
+use crate::{
+    mesh::{
+        components::graph_components, graph::CSRGraph, Elem, SimplexMesh, Tetrahedron, Triangle,
+    },
+    Idx, Result,
+};
+use log::debug;
+use rustc_hash::FxHashMap;
+use std::fmt;
+
+/// Topological invariants of a surface mesh computed by `SimplexMesh::topology_report`. The
+/// edges are given by their (sorted) vertex indices
+#[derive(Clone, Debug)]
+pub struct TopologyReport {
+    /// The # of vertices used by the triangles
+    pub n_verts: Idx,
+    /// The # of edges
+    pub n_edges: Idx,
+    /// The # of triangles
+    pub n_elems: Idx,
+    /// The Euler characteristic
+    /// ```math
+    /// \chi = V - E + F
+    /// ```
+    pub euler_characteristic: i64,
+    /// The # of connected components (shells), two triangles being connected if they share
+    /// an edge
+    pub n_shells: Idx,
+    /// The edges that belong to a single triangle
+    pub boundary_edges: Vec<[Idx; 2]>,
+    /// The edges that belong to more than 2 triangles
+    pub non_manifold_edges: Vec<[Idx; 2]>,
+    /// The edges shared by 2 triangles that traverse them in the same direction
+    pub inconsistent_edges: Vec<[Idx; 2]>,
+}
+
+impl TopologyReport {
+    /// Check if the surface is closed, manifold and consistently oriented
+    #[must_use]
+    pub fn is_watertight(&self) -> bool {
+        self.boundary_edges.is_empty()
+            && self.non_manifold_edges.is_empty()
+            && self.inconsistent_edges.is_empty()
+    }
+
+    /// Total genus of the shells for a watertight surface, the Euler characteristic of a
+    /// closed orientable surface with genus g being 2 - 2g
+    #[must_use]
+    pub fn genus(&self) -> Option<i64> {
+        if self.is_watertight() {
+            Some(self.n_shells as i64 - self.euler_characteristic / 2)
+        } else {
+            None
+        }
+    }
+}
+
+impl fmt::Display for TopologyReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} vertices, {} edges, {} triangles, chi = {}, {} shell(s)",
+            self.n_verts, self.n_edges, self.n_elems, self.euler_characteristic, self.n_shells
+        )?;
+        if let Some(genus) = self.genus() {
+            write!(f, ", watertight, genus = {genus}")
+        } else {
+            write!(
+                f,
+                ", not watertight: {} boundary, {} non-manifold and {} inconsistent edges",
+                self.boundary_edges.len(),
+                self.non_manifold_edges.len(),
+                self.inconsistent_edges.len()
+            )
+        }
+    }
+}
+
+impl SimplexMesh<3, Triangle> {
+    /// Compute the topological invariants of the surface (see `TopologyReport`) to check
+    /// that it is closed and manifold before adaptation
+    pub fn topology_report(&self) -> Result<TopologyReport> {
+        debug!("Compute the topology report of the surface");
+
+        let mut edges: FxHashMap<[Idx; 2], Vec<(Idx, bool)>> = FxHashMap::default();
+        let mut used = vec![false; self.n_verts() as usize];
+        for (i_elem, e) in self.elems().enumerate() {
+            e.iter().for_each(|&i| used[i as usize] = true);
+            for i_edge in 0..Triangle::N_EDGES {
+                let edg = e.edge(i_edge);
+                let forward = edg[0] < edg[1];
+                let sorted = if forward { edg } else { [edg[1], edg[0]] };
+                edges
+                    .entry(sorted)
+                    .or_default()
+                    .push((i_elem as Idx, forward));
+            }
+        }
+
+        let mut boundary_edges = Vec::new();
+        let mut non_manifold_edges = Vec::new();
+        let mut inconsistent_edges = Vec::new();
+        let mut e2e = Vec::new();
+        for (edg, elems) in &edges {
+            match elems.len() {
+                1 => boundary_edges.push(*edg),
+                2 => {
+                    if elems[0].1 == elems[1].1 {
+                        inconsistent_edges.push(*edg);
+                    }
+                }
+                _ => non_manifold_edges.push(*edg),
+            }
+            e2e.extend(elems.iter().skip(1).map(|&(j, _)| [elems[0].0, j]));
+        }
+        boundary_edges.sort_unstable();
+        non_manifold_edges.sort_unstable();
+        inconsistent_edges.sort_unstable();
+
+        let components = graph_components(&CSRGraph::new(&e2e), self.n_elems())?;
+        let n_shells = components.iter().copied().max().map_or(0, |c| c + 1);
+
+        let n_verts = used.iter().filter(|&&u| u).count() as Idx;
+        let n_edges = edges.len() as Idx;
+        let res = TopologyReport {
+            n_verts,
+            n_edges,
+            n_elems: self.n_elems(),
+            euler_characteristic: n_verts as i64 - n_edges as i64 + self.n_elems() as i64,
+            n_shells,
+            boundary_edges,
+            non_manifold_edges,
+            inconsistent_edges,
+        };
+        debug!("{res}");
+
+        Ok(res)
+    }
+}
+
+impl SimplexMesh<3, Tetrahedron> {
+    /// Compute the topological invariants of the mesh boundary (see `TopologyReport`); the
+    /// shells are the connected components of the boundary
+    pub fn topology_report(&self) -> Result<TopologyReport> {
+        self.boundary().0.topology_report()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        mesh::{test_meshes::test_mesh_3d, Point, SimplexMesh, Triangle},
+        Result,
+    };
+
+    #[test]
+    fn test_closed_surface() -> Result<()> {
+        let mesh = test_mesh_3d().split();
+        let report = mesh.topology_report()?;
+        assert_eq!(report.euler_characteristic, 2);
+        assert_eq!(report.n_shells, 1);
+        assert!(report.is_watertight());
+        assert_eq!(report.genus(), Some(0));
+
+        // two shells
+        let (mut bdy, _) = mesh.boundary();
+        let mut other = bdy.clone();
+        other.translate(&Point::<3>::new(2.0, 0.0, 0.0));
+        bdy.add(&other, |_| true, |_| true, None);
+        let report = bdy.topology_report()?;
+        assert_eq!(report.euler_characteristic, 4);
+        assert_eq!(report.n_shells, 2);
+        assert_eq!(report.genus(), Some(0));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_open_surface() -> Result<()> {
+        let verts = vec![
+            Point::<3>::new(0.0, 0.0, 0.0),
+            Point::<3>::new(1.0, 0.0, 0.0),
+            Point::<3>::new(0.0, 1.0, 0.0),
+            Point::<3>::new(0.0, -1.0, 0.0),
+            Point::<3>::new(0.0, 0.0, 1.0),
+        ];
+        let elems = vec![
+            Triangle::new(0, 1, 2),
+            Triangle::new(1, 0, 3),
+            Triangle::new(0, 1, 4),
+        ];
+        let mut mesh = SimplexMesh::new(verts, elems, vec![1; 3], Vec::new(), Vec::new());
+
+        let report = mesh.topology_report()?;
+        assert_eq!(report.euler_characteristic, 5 - 7 + 3);
+        assert_eq!(report.n_shells, 1);
+        assert_eq!(report.non_manifold_edges, vec![[0, 1]]);
+        assert_eq!(report.boundary_edges.len(), 6);
+        assert!(report.inconsistent_edges.is_empty());
+        assert!(!report.is_watertight());
+        assert!(report.genus().is_none());
+
+        *mesh.mut_elems().nth(2).unwrap() = Triangle::new(1, 2, 4);
+        let report = mesh.topology_report()?;
+        assert!(report.non_manifold_edges.is_empty());
+        assert_eq!(report.inconsistent_edges, vec![[1, 2]]);
+
+        Ok(())
+    }
+}
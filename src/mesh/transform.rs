@@ -0,0 +1,137 @@
+use crate::mesh::{Elem, Point, SimplexMesh};
+use log::debug;
+use nalgebra::{Const, Rotation2, Rotation3, SMatrix, Unit};
+
+/// Swap the first two vertices of an element to reverse its orientation
+fn flip<E: Elem>(e: &mut E) {
+    let (i0, i1) = (e[0], e[1]);
+    e[0] = i1;
+    e[1] = i0;
+}
+
+impl<const D: usize, E: Elem> SimplexMesh<D, E> {
+    /// Apply the affine transformation `x -> mat * x + offset` to the vertices. If the
+    /// determinant of `mat` is negative, the elements and faces are flipped to keep their
+    /// orientation. The volumes are cleared
+    pub fn transform(&mut self, mat: &SMatrix<f64, D, D>, offset: &Point<D>)
+    where
+        Const<D>: nalgebra::DimMin<Const<D>, Output = Const<D>>,
+    {
+        debug!("Apply an affine transformation to the mesh");
+
+        self.mut_verts().for_each(|p| *p = mat * *p + offset);
+
+        if mat.determinant() < 0.0 {
+            debug!("Flip the elements and faces");
+            self.mut_elems().for_each(flip);
+            if E::Face::N_VERTS > 1 {
+                self.mut_faces().for_each(flip);
+            }
+        }
+
+        self.clear_volumes();
+    }
+
+    /// Translate the mesh by `offset`
+    pub fn translate(&mut self, offset: &Point<D>)
+    where
+        Const<D>: nalgebra::DimMin<Const<D>, Output = Const<D>>,
+    {
+        self.transform(&SMatrix::identity(), offset);
+    }
+
+    /// Scale the mesh by `factor` about `center`
+    pub fn scale(&mut self, center: &Point<D>, factor: f64)
+    where
+        Const<D>: nalgebra::DimMin<Const<D>, Output = Const<D>>,
+    {
+        self.transform(
+            &SMatrix::from_diagonal_element(factor),
+            &(center * (1.0 - factor)),
+        );
+    }
+
+    /// Mirror the mesh with respect to the plane (line in 2D) through `origin` with normal
+    /// `normal`
+    pub fn mirror(&mut self, origin: &Point<D>, normal: &Point<D>)
+    where
+        Const<D>: nalgebra::DimMin<Const<D>, Output = Const<D>>,
+    {
+        let n = normal.normalize();
+        let mat = SMatrix::<f64, D, D>::identity() - 2.0 * n * n.transpose();
+        self.transform(&mat, &(origin - mat * origin));
+    }
+}
+
+impl<E: Elem> SimplexMesh<2, E> {
+    /// Rotate the mesh by `angle` (in radians, counterclockwise) about `center`
+    pub fn rotate(&mut self, center: &Point<2>, angle: f64) {
+        let mat = Rotation2::new(angle).into_inner();
+        self.transform(&mat, &(center - mat * center));
+    }
+}
+
+impl<E: Elem> SimplexMesh<3, E> {
+    /// Rotate the mesh by `angle` (in radians) about the axis through `center` with direction
+    /// `axis`
+    pub fn rotate(&mut self, center: &Point<3>, axis: &Point<3>, angle: f64) {
+        let mat = Rotation3::from_axis_angle(&Unit::new_normalize(*axis), angle).into_inner();
+        self.transform(&mat, &(center - mat * center));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::mesh::{
+        test_meshes::{test_mesh_2d, test_mesh_3d},
+        Point,
+    };
+    use std::f64::consts::FRAC_PI_2;
+
+    #[test]
+    fn test_transform_2d() {
+        let mut mesh = test_mesh_2d();
+        mesh.add_boundary_faces();
+
+        mesh.translate(&Point::<2>::new(1.0, 2.0));
+        assert!((mesh.vert(2) - Point::<2>::new(2.0, 3.0)).norm() < 1e-12);
+
+        mesh.scale(&Point::<2>::new(1.0, 2.0), 2.0);
+        assert!((mesh.vert(2) - Point::<2>::new(3.0, 4.0)).norm() < 1e-12);
+        assert!((mesh.vol() - 4.0).abs() < 1e-12);
+
+        mesh.rotate(&Point::<2>::new(1.0, 2.0), FRAC_PI_2);
+        assert!((mesh.vert(2) - Point::<2>::new(-1.0, 4.0)).norm() < 1e-12);
+
+        mesh.mirror(&Point::<2>::new(0.0, 0.0), &Point::<2>::new(1.0, 0.0));
+        assert!((mesh.vert(2) - Point::<2>::new(1.0, 4.0)).norm() < 1e-12);
+        assert!((mesh.vol() - 4.0).abs() < 1e-12);
+        assert!(mesh.validate().is_empty());
+    }
+
+    #[test]
+    fn test_transform_3d() {
+        let mut mesh = test_mesh_3d().split();
+        mesh.add_boundary_faces();
+        let vol = mesh.vol();
+
+        mesh.rotate(
+            &Point::<3>::new(0.0, 0.0, 0.0),
+            &Point::<3>::new(0.0, 0.0, 2.0),
+            FRAC_PI_2,
+        );
+        assert!((mesh.vert(1) - Point::<3>::new(0.0, 1.0, 0.0)).norm() < 1e-12);
+        assert!((mesh.vol() - vol).abs() < 1e-12);
+
+        mesh.mirror(
+            &Point::<3>::new(0.0, 0.0, 0.5),
+            &Point::<3>::new(0.0, 0.0, 1.0),
+        );
+        assert!((mesh.vol() - vol).abs() < 1e-12);
+        assert!(mesh.validate().is_empty());
+
+        mesh.scale(&Point::<3>::new(0.0, 0.0, 0.0), -1.0);
+        assert!((mesh.vol() - vol).abs() < 1e-12);
+        assert!(mesh.validate().is_empty());
+    }
+}
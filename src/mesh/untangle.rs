@@ -0,0 +1,413 @@
+use crate::{
+    mesh::{Elem, GElem, Point, SimplexMesh},
+    metric::IsoMetric,
+    min_iter, Error, Idx, Result, Tag,
+};
+use log::debug;
+use rustc_hash::{FxHashMap, FxHashSet};
+
+/// Signed volume of element `e` with vertex coordinates `verts`
+fn signed_vol<const D: usize, E: Elem>(verts: &[Point<D>], e: &E) -> f64 {
+    E::Geom::<D, IsoMetric<D>>::from_verts(
+        e.iter()
+            .map(|&i| (verts[i as usize], IsoMetric::<D>::from(1.0))),
+    )
+    .vol()
+}
+
+/// Elements containing each vertex
+fn vertex_to_elems<E: Elem>(n_verts: usize, elems: &[E]) -> Vec<Vec<Idx>> {
+    let mut res = vec![Vec::new(); n_verts];
+    for (i_elem, e) in elems.iter().enumerate() {
+        for &i in e.iter() {
+            res[i as usize].push(i_elem as Idx);
+        }
+    }
+    res
+}
+
+/// Sorted face opposite to the k-th vertex of `e`
+fn opposite_face<E: Elem>(e: &E, k: usize) -> E::Face {
+    E::Face::from_iter(
+        e.iter()
+            .enumerate()
+            .filter(|&(j, _)| j != k)
+            .map(|(_, &i)| i),
+    )
+    .sorted()
+}
+
+/// Try to fix the `inverted` elements by swapping the faces shared with their neighbors (edge
+/// flips in 2D, 2-3 swaps in 3D): the 2 elements sharing a face are replaced by the D elements
+/// around the edge joining their opposite vertices if these all have a positive volume. The
+/// faces in `fixed_faces` and the faces between elements with different tags are not swapped.
+/// `parents` holds the index of the original element of each element. Returns the # of swaps
+fn swap_inverted<const D: usize, E: Elem>(
+    verts: &[Point<D>],
+    elems: &mut Vec<E>,
+    etags: &mut Vec<Tag>,
+    parents: &mut Vec<Idx>,
+    inverted: &[Idx],
+    fixed_faces: &FxHashSet<E::Face>,
+) -> Idx {
+    let n = E::N_VERTS as usize;
+    let mut f2e = FxHashMap::<E::Face, Vec<Idx>>::default();
+    for (i_elem, e) in elems.iter().enumerate() {
+        for k in 0..n {
+            f2e.entry(opposite_face(e, k))
+                .or_default()
+                .push(i_elem as Idx);
+        }
+    }
+
+    let mut modified = FxHashSet::default();
+    let mut n_swaps = 0;
+    for &i_elem in inverted {
+        if modified.contains(&i_elem) {
+            continue;
+        }
+        let e = elems[i_elem as usize];
+        for k in 0..n {
+            let f = opposite_face(&e, k);
+            if fixed_faces.contains(&f) {
+                continue;
+            }
+            let Some(&[i0, i1]) = f2e.get(&f).map(Vec::as_slice) else {
+                continue;
+            };
+            let i_other = if i0 == i_elem { i1 } else { i0 };
+            if modified.contains(&i_other) || etags[i_other as usize] != etags[i_elem as usize] {
+                continue;
+            }
+            let other = elems[i_other as usize];
+            let q = other
+                .iter()
+                .copied()
+                .find(|i| !f.contains_vertex(*i))
+                .unwrap();
+
+            let new_elems = (0..n)
+                .filter(|&m| m != k)
+                .map(|m| {
+                    let mut new_e = e;
+                    new_e[m] = q;
+                    new_e
+                })
+                .collect::<Vec<_>>();
+            if new_elems.iter().all(|e| signed_vol(verts, e) > 0.0) {
+                let tag = etags[i_elem as usize];
+                let parent = parents[i_elem as usize];
+                elems[i_elem as usize] = new_elems[0];
+                elems[i_other as usize] = new_elems[1];
+                for &new_e in &new_elems[2..] {
+                    elems.push(new_e);
+                    etags.push(tag);
+                    parents.push(parent);
+                }
+                modified.insert(i_elem);
+                modified.insert(i_other);
+                n_swaps += 1;
+                break;
+            }
+        }
+    }
+
+    n_swaps
+}
+
+impl<const D: usize, E: Elem> SimplexMesh<D, E> {
+    /// Get the indices of the inverted elements, i.e. with a negative or zero volume
+    #[must_use]
+    pub fn inverted_elems(&self) -> Vec<Idx> {
+        self.gelems()
+            .enumerate()
+            .filter(|(_, ge)| ge.vol() <= 0.0)
+            .map(|(i, _)| i as Idx)
+            .collect()
+    }
+
+    /// Try to fix the inverted elements by
+    ///  - relocating their vertices: the boundary vertices are kept fixed and the interior
+    ///    vertices are moved to maximize the minimum volume of the elements in their ball, using
+    ///    a compass search starting from the current location and the center of the neighbors,
+    ///  - local reconnection: the faces shared with their neighbors are swapped (edge flips in
+    ///    2D, 2-3 swaps in 3D) if this gives valid elements; the boundary faces, the tagged
+    ///    faces and the faces between elements with different tags are kept.
+    ///
+    /// At most `n_iter` passes over the inverted elements are performed. The elements that could
+    /// not be fixed are returned (their indices refer to the modified mesh, as the 2-3 swaps add
+    /// elements). The element fields are copied from the original elements, and the
+    /// connectivities and volumes are cleared
+    pub fn untangle(&mut self, n_iter: u32) -> Result<Vec<Idx>> {
+        debug!("Untangle the mesh");
+
+        if E::N_VERTS as usize != D + 1 {
+            return Err(Error::from(
+                "untangling is only available for volume meshes",
+            ));
+        }
+
+        let n_verts = self.n_verts() as usize;
+        let mut elems = self.elems().collect::<Vec<_>>();
+        let mut etags = self.etags().collect::<Vec<_>>();
+        let mut parents = (0..self.n_elems()).collect::<Vec<_>>();
+        let mut v2e = vertex_to_elems(n_verts, &elems);
+        let bdy = self.boundary_flag();
+        let fixed_faces = self.faces().map(|f| f.sorted()).collect::<FxHashSet<_>>();
+        let mut verts = self.verts().collect::<Vec<_>>();
+        let mut n_swaps = 0;
+
+        let inverted = |verts: &[Point<D>], elems: &[E]| {
+            elems
+                .iter()
+                .enumerate()
+                .filter(|(_, e)| signed_vol(verts, *e) <= 0.0)
+                .map(|(i, _)| i as Idx)
+                .collect::<Vec<_>>()
+        };
+
+        let mut res = inverted(&verts, &elems);
+        for iter in 0..n_iter {
+            debug!("Iteration {}: {} inverted elements", iter + 1, res.len());
+            if res.is_empty() {
+                break;
+            }
+
+            let mut to_move = res
+                .iter()
+                .flat_map(|&i_elem| elems[i_elem as usize].iter().copied().collect::<Vec<_>>())
+                .filter(|&i| !bdy[i as usize])
+                .collect::<Vec<_>>();
+            to_move.sort_unstable();
+            to_move.dedup();
+
+            for i_vert in to_move {
+                let ball = &v2e[i_vert as usize];
+                let mut neighbors = ball
+                    .iter()
+                    .flat_map(|&i_elem| elems[i_elem as usize].iter().copied().collect::<Vec<_>>())
+                    .filter(|&i| i != i_vert)
+                    .collect::<Vec<_>>();
+                neighbors.sort_unstable();
+                neighbors.dedup();
+
+                let p0 = verts[i_vert as usize];
+                let center = neighbors
+                    .iter()
+                    .fold(Point::<D>::zeros(), |a, &i| a + verts[i as usize])
+                    / neighbors.len() as f64;
+                let mut step = 0.5
+                    * neighbors
+                        .iter()
+                        .map(|&i| (verts[i as usize] - center).norm())
+                        .sum::<f64>()
+                    / neighbors.len() as f64;
+
+                // the vertex is moved in place, and set to the best location at the end
+                let mut v_min = |p: Point<D>| {
+                    verts[i_vert as usize] = p;
+                    min_iter(
+                        ball.iter()
+                            .map(|&i_elem| signed_vol(&verts, &elems[i_elem as usize])),
+                    )
+                };
+
+                let (mut p_best, mut v_best) = (p0, v_min(p0));
+                let v_center = v_min(center);
+                if v_center > v_best {
+                    p_best = center;
+                    v_best = v_center;
+                }
+
+                for _ in 0..50 {
+                    let mut improved = false;
+                    for dir in 0..D {
+                        for sgn in [-1.0, 1.0] {
+                            let mut p = p_best;
+                            p[dir] += sgn * step;
+                            let v = v_min(p);
+                            if v > v_best {
+                                p_best = p;
+                                v_best = v;
+                                improved = true;
+                            }
+                        }
+                    }
+                    if !improved {
+                        step *= 0.5;
+                    }
+                }
+                verts[i_vert as usize] = p_best;
+            }
+
+            res = inverted(&verts, &elems);
+            let n = swap_inverted(
+                &verts,
+                &mut elems,
+                &mut etags,
+                &mut parents,
+                &res,
+                &fixed_faces,
+            );
+            if n > 0 {
+                n_swaps += n;
+                v2e = vertex_to_elems(n_verts, &elems);
+                res = inverted(&verts, &elems);
+            } else if res
+                .iter()
+                .all(|&i_elem| elems[i_elem as usize].iter().all(|&i| bdy[i as usize]))
+            {
+                break;
+            }
+        }
+
+        self.mut_verts()
+            .zip(verts.iter())
+            .for_each(|(x, y)| *x = *y);
+        if n_swaps > 0 {
+            self.set_elems(elems, etags, &parents);
+        }
+        self.clear_volumes();
+
+        debug!(
+            "{n_swaps} face swaps, {} inverted elements could not be fixed",
+            res.len()
+        );
+
+        Ok(res)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        mesh::{
+            test_meshes::{test_mesh_2d, test_mesh_3d},
+            Edge, Elem, Point, SimplexMesh, Tetrahedron, Triangle,
+        },
+        Idx, Result,
+    };
+
+    #[test]
+    fn test_untangle_2d() -> Result<()> {
+        let mut mesh = test_mesh_2d().split().split();
+        assert!(mesh.inverted_elems().is_empty());
+
+        // move an interior vertex outside of its ball
+        let flg = mesh.boundary_flag();
+        let i_vert = flg.iter().position(|&x| !x).unwrap();
+        let p = mesh.vert(i_vert as Idx);
+        if let Some(x) = mesh.mut_verts().nth(i_vert) {
+            *x = p + Point::<2>::new(0.4, 0.3);
+        }
+        assert!(!mesh.inverted_elems().is_empty());
+
+        let res = mesh.untangle(5)?;
+        assert!(res.is_empty());
+        assert!(mesh.inverted_elems().is_empty());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_untangle_3d() -> Result<()> {
+        let mut mesh = test_mesh_3d().split().split();
+
+        let flg = mesh.boundary_flag();
+        let i_vert = flg.iter().position(|&x| !x).unwrap();
+        let p = mesh.vert(i_vert as Idx);
+        if let Some(x) = mesh.mut_verts().nth(i_vert) {
+            *x = p + Point::<3>::new(0.2, -0.15, 0.1);
+        }
+        assert!(!mesh.inverted_elems().is_empty());
+
+        let res = mesh.untangle(5)?;
+        assert!(res.is_empty());
+        assert!(mesh.inverted_elems().is_empty());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_untangle_boundary() -> Result<()> {
+        let mesh = test_mesh_2d();
+        let mut elems = mesh.elems().collect::<Vec<_>>();
+        elems[1] = Triangle::new(elems[1][0], elems[1][2], elems[1][1]);
+        let mut mesh = SimplexMesh::new(
+            mesh.verts().collect(),
+            elems,
+            mesh.etags().collect(),
+            mesh.faces().collect(),
+            mesh.ftags().collect(),
+        );
+
+        let res = mesh.untangle(5)?;
+        assert_eq!(res, vec![1]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_untangle_flip_2d() -> Result<()> {
+        let mut mesh = SimplexMesh::new(
+            vec![
+                Point::<2>::new(0.75, 0.75),
+                Point::<2>::new(1., 0.),
+                Point::<2>::new(1., 1.),
+                Point::<2>::new(0., 1.),
+            ],
+            vec![Triangle::new(0, 1, 3), Triangle::new(1, 2, 3)],
+            vec![1, 1],
+            vec![
+                Edge::new(0, 1),
+                Edge::new(1, 2),
+                Edge::new(2, 3),
+                Edge::new(3, 0),
+            ],
+            vec![1, 2, 3, 4],
+        );
+        assert_eq!(mesh.inverted_elems(), vec![0]);
+
+        let res = mesh.untangle(5)?;
+        assert!(res.is_empty());
+        assert_eq!(mesh.n_elems(), 2);
+        assert!(mesh.inverted_elems().is_empty());
+        assert!(mesh.elems().all(|e| e.contains_edge([0, 2])));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_untangle_swap_3d() -> Result<()> {
+        let mut mesh = SimplexMesh::new(
+            vec![
+                Point::<3>::new(1., 0., 0.),
+                Point::<3>::new(-0.5, 0.8, 0.),
+                Point::<3>::new(-0.5, -0.8, 0.),
+                Point::<3>::new(0., 0., -0.5),
+                Point::<3>::new(0., 0., -1.),
+            ],
+            vec![Tetrahedron::new(3, 0, 2, 1), Tetrahedron::new(4, 0, 1, 2)],
+            vec![1, 1],
+            vec![
+                Triangle::new(3, 0, 1),
+                Triangle::new(3, 1, 2),
+                Triangle::new(3, 2, 0),
+                Triangle::new(4, 0, 1),
+                Triangle::new(4, 1, 2),
+                Triangle::new(4, 2, 0),
+            ],
+            vec![1; 6],
+        );
+        assert_eq!(mesh.inverted_elems(), vec![0]);
+
+        let res = mesh.untangle(5)?;
+        assert!(res.is_empty());
+        assert_eq!(mesh.n_elems(), 3);
+        assert!(mesh.inverted_elems().is_empty());
+        assert!(mesh.elems().all(|e| e.contains_edge([3, 4])));
+        assert!(mesh.etags().all(|t| t == 1));
+
+        Ok(())
+    }
+}
@@ -0,0 +1,384 @@
+use crate::{
+    mesh::{get_face_to_elem, Elem, GElem, SimplexMesh},
+    Idx,
+};
+use log::debug;
+use rustc_hash::FxHashMap;
+use std::fmt;
+
+/// Violation of the mesh validity rules found by `SimplexMesh::validate`. The faces that do not
+/// exist in the mesh are given by their (sorted) vertex indices
+#[derive(Clone, Debug, PartialEq)]
+pub enum MeshViolation {
+    /// An element references a vertex that does not exist
+    ElemVertexOutOfRange { elem: Idx, vert: Idx },
+    /// A face references a vertex that does not exist
+    FaceVertexOutOfRange { face: Idx, vert: Idx },
+    /// An element has a negative or zero volume
+    InvalidVolume { elem: Idx, vol: f64 },
+    /// Two vertices have the same coordinates
+    DuplicatedVertices(Idx, Idx),
+    /// Two elements have the same vertices
+    DuplicatedElements(Idx, Idx),
+    /// Two faces have the same vertices
+    DuplicatedFaces(Idx, Idx),
+    /// A face is not a face of any element
+    FaceNotInMesh(Idx),
+    /// A boundary face is not oriented as the face of its element, i.e. outwards
+    WrongFaceOrientation(Idx),
+    /// A face that belongs to a single element is not tagged
+    MissingBoundaryFace(Vec<Idx>),
+    /// A face between two elements with different tags is not tagged
+    MissingInterfaceFace(Vec<Idx>),
+    /// A face belongs to more than 2 elements
+    NonManifoldFace(Vec<Idx>),
+}
+
+impl fmt::Display for MeshViolation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::ElemVertexOutOfRange { elem, vert } => {
+                write!(
+                    f,
+                    "element {elem} references vertex {vert} that does not exist"
+                )
+            }
+            Self::FaceVertexOutOfRange { face, vert } => {
+                write!(
+                    f,
+                    "face {face} references vertex {vert} that does not exist"
+                )
+            }
+            Self::InvalidVolume { elem, vol } => write!(f, "the volume of element {elem} is {vol}"),
+            Self::DuplicatedVertices(i, j) => write!(f, "vertices {i} and {j} are duplicated"),
+            Self::DuplicatedElements(i, j) => write!(f, "elements {i} and {j} are duplicated"),
+            Self::DuplicatedFaces(i, j) => write!(f, "faces {i} and {j} are duplicated"),
+            Self::FaceNotInMesh(i) => write!(f, "face {i} is not a face of any element"),
+            Self::WrongFaceOrientation(i) => write!(f, "face {i} is not oriented outwards"),
+            Self::MissingBoundaryFace(v) => write!(f, "boundary face {v:?} is not tagged"),
+            Self::MissingInterfaceFace(v) => write!(f, "interface face {v:?} is not tagged"),
+            Self::NonManifoldFace(v) => write!(f, "face {v:?} belongs to more than 2 elements"),
+        }
+    }
+}
+
+/// Check if `a` is an even permutation of `b`, assuming that they contain the same vertices
+pub fn same_orientation<E: Elem>(a: &E, b: &E) -> bool {
+    let pos = a
+        .iter()
+        .map(|i| b.iter().position(|j| j == i).unwrap())
+        .collect::<Vec<_>>();
+    let n_inversions = pos
+        .iter()
+        .enumerate()
+        .map(|(k, p)| pos[k + 1..].iter().filter(|&q| q < p).count())
+        .sum::<usize>();
+    n_inversions % 2 == 0
+}
+
+impl<const D: usize, E: Elem> SimplexMesh<D, E> {
+    /// Perform a comprehensive validation of the mesh and return the list of violations
+    /// (empty if the mesh is valid):
+    ///  - the element and face connectivities reference existing vertices (if not, the other
+    ///    checks are not performed),
+    ///  - the element volumes are positive,
+    ///  - the vertices, elements and faces are not duplicated,
+    ///  - the faces are faces of the mesh elements, and the boundary faces are oriented
+    ///    outwards,
+    ///  - the boundary faces and the faces between elements with different tags are tagged,
+    ///  - the faces belong to at most 2 elements.
+    ///
+    /// Unlike `check`, no connectivity is required
+    #[must_use]
+    pub fn validate(&self) -> Vec<MeshViolation> {
+        debug!("Validate the mesh");
+
+        let mut res = Vec::new();
+
+        let n_verts = self.n_verts();
+        for (i_elem, e) in self.elems().enumerate() {
+            res.extend(e.iter().filter(|&&i| i >= n_verts).map(|&i| {
+                MeshViolation::ElemVertexOutOfRange {
+                    elem: i_elem as Idx,
+                    vert: i,
+                }
+            }));
+        }
+        for (i_face, f) in self.faces().enumerate() {
+            res.extend(f.iter().filter(|&&i| i >= n_verts).map(|&i| {
+                MeshViolation::FaceVertexOutOfRange {
+                    face: i_face as Idx,
+                    vert: i,
+                }
+            }));
+        }
+        if !res.is_empty() {
+            return res;
+        }
+
+        for (i_elem, vol) in self.gelems().map(|ge| ge.vol()).enumerate() {
+            if vol <= 0.0 {
+                res.push(MeshViolation::InvalidVolume {
+                    elem: i_elem as Idx,
+                    vol,
+                });
+            }
+        }
+
+        let mut verts = FxHashMap::default();
+        for (i_vert, p) in self.verts().enumerate() {
+            let key = p.iter().map(|x| x.to_bits()).collect::<Vec<_>>();
+            if let Some(&j) = verts.get(&key) {
+                res.push(MeshViolation::DuplicatedVertices(j, i_vert as Idx));
+            } else {
+                verts.insert(key, i_vert as Idx);
+            }
+        }
+
+        let mut elems = FxHashMap::default();
+        for (i_elem, e) in self.elems().enumerate() {
+            if let Some(&j) = elems.get(&e.sorted()) {
+                res.push(MeshViolation::DuplicatedElements(j, i_elem as Idx));
+            } else {
+                elems.insert(e.sorted(), i_elem as Idx);
+            }
+        }
+
+        let f2e = get_face_to_elem(self.elems());
+        let mut faces = FxHashMap::default();
+        for (i_face, f) in self.faces().enumerate() {
+            let i_face = i_face as Idx;
+            let key = f.sorted();
+            if let Some(&j) = faces.get(&key) {
+                res.push(MeshViolation::DuplicatedFaces(j, i_face));
+                continue;
+            }
+            faces.insert(key, i_face);
+            match f2e.get(&key) {
+                None => res.push(MeshViolation::FaceNotInMesh(i_face)),
+                Some(els) if els.len() == 1 => {
+                    let e = self.elem(els[0]);
+                    let ef = (0..E::N_FACES)
+                        .map(|i| e.face(i))
+                        .find(|ef| ef.sorted() == key)
+                        .unwrap();
+                    if !same_orientation(&f, &ef) {
+                        res.push(MeshViolation::WrongFaceOrientation(i_face));
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        for (f, els) in &f2e {
+            if faces.contains_key(f) {
+                continue;
+            }
+            let v = f.iter().copied().collect::<Vec<_>>();
+            match els.len() {
+                1 => res.push(MeshViolation::MissingBoundaryFace(v)),
+                2 => {
+                    if self.etag(els[0]) != self.etag(els[1]) {
+                        res.push(MeshViolation::MissingInterfaceFace(v));
+                    }
+                }
+                _ => {}
+            }
+        }
+        res.extend(
+            f2e.iter()
+                .filter(|(_, els)| els.len() > 2)
+                .map(|(f, _)| MeshViolation::NonManifoldFace(f.iter().copied().collect())),
+        );
+
+        res
+    }
+
+    /// Make all the element volumes positive, by swapping the first two vertices of the
+    /// inverted elements (for volume meshes only), and orient the boundary faces outwards,
+    /// i.e. as the face of the element they belong to. The faces that are not faces of the
+    /// mesh elements and the internal faces are not modified.
+    ///
+    /// Returns the # of elements and faces that have been flipped. The connectivities are
+    /// cleared if some elements have been flipped
+    pub fn fix_face_orientation(&mut self) -> (Idx, Idx) {
+        debug!("Fix the orientation of the elements and boundary faces");
+
+        let mut n_elems = 0;
+        if E::N_VERTS as usize == D + 1 {
+            let flip = self.gelems().map(|ge| ge.vol() < 0.0).collect::<Vec<_>>();
+            for (e, _) in self.mut_elems().zip(&flip).filter(|(_, &x)| x) {
+                let (i0, i1) = (e[0], e[1]);
+                e[0] = i1;
+                e[1] = i0;
+                n_elems += 1;
+            }
+        }
+
+        let f2e = get_face_to_elem(self.elems());
+        let new_faces = self
+            .faces()
+            .map(|f| {
+                f2e.get(&f.sorted())
+                    .filter(|els| els.len() == 1)
+                    .and_then(|els| {
+                        let e = self.elem(els[0]);
+                        (0..E::N_FACES)
+                            .map(|i| e.face(i))
+                            .find(|ef| ef.sorted() == f.sorted())
+                    })
+                    .filter(|ef| !same_orientation(&f, ef))
+            })
+            .collect::<Vec<_>>();
+        let mut n_faces = 0;
+        for (f, new_f) in self.mut_faces().zip(new_faces) {
+            if let Some(new_f) = new_f {
+                *f = new_f;
+                n_faces += 1;
+            }
+        }
+
+        if n_elems > 0 {
+            self.clear_all();
+        }
+        debug!("{n_elems} elements and {n_faces} faces flipped");
+
+        (n_elems, n_faces)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{same_orientation, MeshViolation};
+    use crate::mesh::{
+        test_meshes::{test_mesh_2d, test_mesh_3d},
+        Edge, Point, SimplexMesh, Triangle,
+    };
+
+    #[test]
+    fn test_same_orientation() {
+        let a = Triangle::new(0, 1, 2);
+        assert!(same_orientation(&a, &Triangle::new(1, 2, 0)));
+        assert!(same_orientation(&a, &Triangle::new(2, 0, 1)));
+        assert!(!same_orientation(&a, &Triangle::new(0, 2, 1)));
+        assert!(!same_orientation(&Edge::new(0, 1), &Edge::new(1, 0)));
+    }
+
+    #[test]
+    fn test_validate_valid() {
+        let mut mesh = test_mesh_2d();
+        mesh.add_boundary_faces();
+        assert!(mesh.validate().is_empty());
+
+        let mut mesh = test_mesh_3d().split();
+        mesh.add_boundary_faces();
+        assert!(mesh.validate().is_empty());
+    }
+
+    #[test]
+    fn test_validate_2d() {
+        let mesh = test_mesh_2d();
+        let mut verts = mesh.verts().collect::<Vec<_>>();
+        let mut elems = mesh.elems().collect::<Vec<_>>();
+        let etags = mesh.etags().collect::<Vec<_>>();
+        let mut faces = mesh.faces().collect::<Vec<_>>();
+        let mut ftags = mesh.ftags().collect::<Vec<_>>();
+
+        // interface face
+        let res = mesh.validate();
+        assert_eq!(res, vec![MeshViolation::MissingInterfaceFace(vec![0, 2])]);
+        faces.push(Edge::new(0, 2));
+        ftags.push(5);
+
+        // out of range vertex
+        let mut tmp = elems.clone();
+        tmp[1] = Triangle::new(0, 2, 4);
+        let mesh = SimplexMesh::new(
+            verts.clone(),
+            tmp,
+            etags.clone(),
+            faces.clone(),
+            ftags.clone(),
+        );
+        assert_eq!(
+            mesh.validate(),
+            vec![MeshViolation::ElemVertexOutOfRange { elem: 1, vert: 4 }]
+        );
+
+        // duplicated vertex, element and face
+        verts.push(Point::<2>::new(1.0, 1.0));
+        elems.push(Triangle::new(2, 0, 1));
+        faces.push(Edge::new(1, 2));
+        let mut tags = etags.clone();
+        tags.push(1);
+        ftags.push(2);
+        let mesh = SimplexMesh::new(verts, elems, tags, faces, ftags);
+        let res = mesh.validate();
+        assert!(res.contains(&MeshViolation::DuplicatedVertices(2, 4)));
+        assert!(res.contains(&MeshViolation::DuplicatedElements(0, 2)));
+        assert!(res.contains(&MeshViolation::DuplicatedFaces(1, 5)));
+        assert!(res.contains(&MeshViolation::NonManifoldFace(vec![0, 2])));
+
+        // inverted element, wrong face orientation and missing face
+        let mesh = test_mesh_2d();
+        let verts = mesh.verts().collect::<Vec<_>>();
+        let elems = vec![Triangle::new(0, 2, 1), Triangle::new(0, 2, 3)];
+        let faces = vec![
+            Edge::new(1, 0),
+            Edge::new(1, 2),
+            Edge::new(3, 0),
+            Edge::new(0, 2),
+        ];
+        let ftags = vec![1, 2, 4, 5];
+        let mesh = SimplexMesh::new(verts, elems, etags, faces, ftags);
+        let res = mesh.validate();
+        assert!(matches!(
+            res[0],
+            MeshViolation::InvalidVolume { elem: 0, vol: _ }
+        ));
+        assert!(res.contains(&MeshViolation::WrongFaceOrientation(1)));
+        assert!(res.contains(&MeshViolation::MissingBoundaryFace(vec![2, 3])));
+        assert!(!res.contains(&MeshViolation::WrongFaceOrientation(0)));
+        assert!(!res.contains(&MeshViolation::WrongFaceOrientation(2)));
+    }
+
+    #[test]
+    fn test_fix_face_orientation() {
+        let mut mesh = test_mesh_2d();
+        mesh.add_boundary_faces();
+        let verts = mesh.verts().collect::<Vec<_>>();
+        let etags = mesh.etags().collect::<Vec<_>>();
+        let ftags = mesh.ftags().collect::<Vec<_>>();
+        let mut elems = mesh.elems().collect::<Vec<_>>();
+        let mut faces = mesh.faces().collect::<Vec<_>>();
+
+        elems[0] = Triangle::new(elems[0][0], elems[0][2], elems[0][1]);
+        let i_face = faces
+            .iter()
+            .position(|f| f.sorted() == Edge::new(0, 1))
+            .unwrap();
+        faces[i_face] = Edge::new(1, 0);
+        let mut mesh = SimplexMesh::new(verts, elems, etags, faces, ftags);
+        assert!(!mesh.validate().is_empty());
+
+        assert_eq!(mesh.fix_face_orientation(), (1, 1));
+        assert!(mesh.validate().is_empty());
+        assert_eq!(mesh.fix_face_orientation(), (0, 0));
+
+        let mut mesh = test_mesh_3d().split();
+        mesh.add_boundary_faces();
+        mesh.mut_elems().for_each(|e| {
+            let (i0, i1) = (e[0], e[1]);
+            e[0] = i1;
+            e[1] = i0;
+        });
+        mesh.mut_faces().step_by(2).for_each(|f| {
+            let (i0, i1) = (f[0], f[1]);
+            f[0] = i1;
+            f[1] = i0;
+        });
+        let n_faces = mesh.n_faces().div_ceil(2);
+        assert_eq!(mesh.fix_face_orientation(), (mesh.n_elems(), n_faces));
+        assert!(mesh.validate().is_empty());
+    }
+}
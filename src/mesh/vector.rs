@@ -29,7 +29,7 @@ impl<const D: usize> FromNativePointer for crate::mesh::Point<D> {
     type PointerType = f64;
     const SIZE: usize = D;
     fn from_ptr(ptr: *const Self::PointerType) -> Self {
-        let s = unsafe { std::slice::from_raw_parts(ptr, 3) };
+        let s = unsafe { std::slice::from_raw_parts(ptr, D) };
         Self::from_row_slice(s)
     }
 }
@@ -141,6 +141,21 @@ impl<T: Copy + FromNativePointer> Vector<T> {
         self.data.len()
     }
 
+    /// Get the data as a flat slice (e.g. D values per vertex), without copy
+    pub fn as_flat_slice(&self) -> &[T::PointerType] {
+        match &self.data {
+            VectorImpl::Std(x) => {
+                assert_eq!(
+                    std::mem::size_of::<T>(),
+                    T::SIZE * std::mem::size_of::<T::PointerType>()
+                );
+                unsafe { std::slice::from_raw_parts(x.as_ptr().cast(), T::SIZE * x.len()) }
+            }
+            VectorImpl::Native((_, 0)) => &[],
+            VectorImpl::Native((p, s)) => unsafe { std::slice::from_raw_parts(*p, T::SIZE * *s) },
+        }
+    }
+
     pub fn iter(&self) -> impl ExactSizeIterator<Item = T> + '_ {
         match &self.data {
             VectorImpl::Std(x) => Iter::Std(x.iter().copied()),
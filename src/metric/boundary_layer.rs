@@ -0,0 +1,129 @@
+use crate::{
+    mesh::{Elem, Point, SimplexMesh},
+    metric::AnisoMetric,
+    Error, Idx, Result, Tag,
+};
+use log::debug;
+use nalgebra::{allocator::Allocator, Const, DefaultAllocator, SMatrix};
+
+impl<const D: usize, E: Elem> SimplexMesh<D, E> {
+    /// Compute a metric field that describes a boundary layer mesh on the faces tagged with one of
+    /// `wall_tags`, with a first cell height `h_0` and `n_layers` layers with a geometric growth
+    /// `growth`. Within the layer, i.e. where the wall distance $`d`$ satisfies
+    /// $`d \le h_0 \frac{g^n - 1}{g - 1}`$, the metric is
+    /// ```math
+    /// \mathcal M = h_t^{-2} (I - n n^T) + h_n^{-2} n n^T
+    /// ```
+    /// where $`n`$ is the gradient of the wall distance, $`h_n = \min(h_0 + (g - 1) d, h_{max})`$
+    /// and $`h_t = h_{max}`$. Outside of the layer, the metric is isotropic with size $`h_{max}`$
+    /// (`apply_metric_gradation` may be used to obtain a smooth transition).
+    ///
+    /// The vertex-to-vertex connectivity is required
+    pub fn boundary_layer_metric<M: AnisoMetric<D>>(
+        &self,
+        wall_tags: &[Tag],
+        h_0: f64,
+        growth: f64,
+        n_layers: Idx,
+        h_max: f64,
+    ) -> Result<Vec<M>>
+    where
+        Const<D>: nalgebra::ToTypenum + nalgebra::DimSub<nalgebra::U1>,
+        DefaultAllocator: Allocator<<Const<D> as nalgebra::DimSub<nalgebra::U1>>::Output>,
+    {
+        debug!(
+            "Compute the boundary layer metric (tags = {wall_tags:?}, h_0 = {h_0}, growth = {growth}, n_layers = {n_layers})"
+        );
+
+        if h_0 <= 0.0 || growth < 1.0 || h_max < h_0 {
+            return Err(Error::from(
+                "invalid boundary layer parameters: 0 < h_0 <= h_max and growth >= 1 are required",
+            ));
+        }
+
+        let (bdy, _) = self.boundary();
+        let wall = bdy.extract(|t| wall_tags.contains(&t)).mesh;
+        if wall.n_elems() == 0 {
+            return Err(Error::from("no face found with the wall tags"));
+        }
+        let tree = wall.compute_element_tree();
+
+        let thickness = if growth - 1.0 < 1e-12 {
+            h_0 * n_layers as f64
+        } else {
+            h_0 * (growth.powi(n_layers as i32) - 1.0) / (growth - 1.0)
+        };
+        debug!("Boundary layer thickness: {thickness:.2e}");
+
+        let proj = self.verts().map(|p| tree.project(&p)).collect::<Vec<_>>();
+        let dist = proj.iter().map(|&(d, _)| d).collect::<Vec<_>>();
+        // used for the vertices on the wall, where the projection does not give the normal
+        let grad = self.gradient(&dist, 2)?;
+
+        let iso = SMatrix::<f64, D, D>::identity() / (h_max * h_max);
+        let res = self
+            .verts()
+            .zip(proj)
+            .zip(grad.chunks(D))
+            .map(|((p, (d, p_wall)), grad)| {
+                if d > thickness {
+                    return M::from_mat(iso);
+                }
+                let n = if d > 1e-3 * h_0 {
+                    (p - p_wall) / d
+                } else {
+                    Point::<D>::from_column_slice(grad)
+                };
+                let nrm = n.norm();
+                if nrm < 1e-12 {
+                    return M::from_mat(iso);
+                }
+                let n = n / nrm;
+                let h_n = f64::min(h_0 + (growth - 1.0) * d, h_max);
+                M::from_mat(iso + (1.0 / (h_n * h_n) - 1.0 / (h_max * h_max)) * n * n.transpose())
+            })
+            .collect();
+
+        Ok(res)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        mesh::{test_meshes::test_mesh_2d, Point},
+        metric::{AnisoMetric2d, Metric},
+        Result,
+    };
+
+    #[test]
+    fn test_boundary_layer_metric_2d() -> Result<()> {
+        let mut mesh = test_mesh_2d().split().split().split().split();
+        mesh.compute_vertex_to_vertices();
+
+        let (h_0, growth, h_max) = (1e-3, 1.2, 0.1);
+        let m = mesh.boundary_layer_metric::<AnisoMetric2d>(&[1], h_0, growth, 20, h_max)?;
+        let thickness = h_0 * (growth.powi(20) - 1.0) / (growth - 1.0);
+
+        let ex = Point::<2>::new(1.0, 0.0);
+        let ey = Point::<2>::new(0.0, 1.0);
+        for (p, m) in mesh.verts().zip(m.iter()) {
+            assert!((m.length(&ex) - 1.0 / h_max).abs() < 1e-6);
+            let h_n = if p[1] > thickness {
+                h_max
+            } else {
+                h_0 + (growth - 1.0) * p[1]
+            };
+            assert!((m.length(&ey) * h_n - 1.0).abs() < 1e-6);
+        }
+
+        assert!(mesh
+            .boundary_layer_metric::<AnisoMetric2d>(&[10], h_0, growth, 20, h_max)
+            .is_err());
+        assert!(mesh
+            .boundary_layer_metric::<AnisoMetric2d>(&[1], h_0, 0.5, 20, h_max)
+            .is_err());
+
+        Ok(())
+    }
+}
@@ -2,7 +2,7 @@ use crate::{
     mesh::GElem,
     mesh::{Elem, SimplexMesh},
     metric::Metric,
-    Idx, Result,
+    Error, Idx, Result,
 };
 use log::debug;
 use rayon::{
@@ -12,8 +12,149 @@ use rayon::{
     },
     slice::ParallelSlice,
 };
+use serde::Serialize;
+use std::fmt;
+
+/// Upper bounds of the bins used for the anisotropy histogram in `MetricStats`
+const ANISO_BINS: [f64; 6] = [2.0, 5.0, 10.0, 100.0, 1000.0, f64::INFINITY];
+
+/// Upper bounds of the bins used for the edge length histogram in `EdgeLengthStats`
+const LENGTH_BINS: [f64; 6] = [
+    0.5,
+    std::f64::consts::FRAC_1_SQRT_2,
+    1.0,
+    std::f64::consts::SQRT_2,
+    2.0,
+    f64::INFINITY,
+];
+
+/// Statistics on a metric field
+#[derive(Clone, Debug, Serialize)]
+pub struct MetricStats {
+    /// The minimum size
+    pub h_min: f64,
+    /// The maximum size
+    pub h_max: f64,
+    /// The mean of the sizes
+    pub h_mean: f64,
+    /// The maximum anisotropy ratio, i.e. the ratio of the largest to the smallest size
+    pub aniso_max: f64,
+    /// The bounds of the anisotropy histogram bins (length n + 1)
+    pub aniso_bins: Vec<f64>,
+    /// The # of vertices in each anisotropy bin (length n)
+    pub aniso_counts: Vec<Idx>,
+    /// The complexity, i.e. the expected # of elements
+    pub complexity: f64,
+}
+
+impl fmt::Display for MetricStats {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "h_min = {:.2e}, h_max = {:.2e}, h_mean = {:.2e}, aniso_max = {:.2e}, complexity = {:.2e}",
+            self.h_min, self.h_max, self.h_mean, self.aniso_max, self.complexity
+        )?;
+        for (i, count) in self.aniso_counts.iter().enumerate() {
+            writeln!(
+                f,
+                "  anisotropy in [{:.0}, {:.0}): {count} vertices",
+                self.aniso_bins[i],
+                self.aniso_bins[i + 1]
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// Statistics on the edge lengths in metric space
+#[derive(Clone, Debug, Serialize)]
+pub struct EdgeLengthStats {
+    /// The minimum length
+    pub l_min: f64,
+    /// The maximum length
+    pub l_max: f64,
+    /// The mean length
+    pub l_mean: f64,
+    /// The bounds of the length histogram bins (length n + 1)
+    pub bins: Vec<f64>,
+    /// The # of edges in each bin (length n)
+    pub counts: Vec<Idx>,
+    /// The fraction of the edges with a length in $`[1/\sqrt{2}, \sqrt{2}]`$, i.e. the edges
+    /// that the remesher considers as unit
+    pub unit_fraction: f64,
+}
+
+impl fmt::Display for EdgeLengthStats {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "l_min = {:.2}, l_max = {:.2}, l_mean = {:.2}, {:.1}% of unit edges",
+            self.l_min,
+            self.l_max,
+            self.l_mean,
+            100.0 * self.unit_fraction
+        )?;
+        for (i, count) in self.counts.iter().enumerate() {
+            writeln!(
+                f,
+                "  length in [{:.2}, {:.2}): {count} edges",
+                self.bins[i],
+                self.bins[i + 1]
+            )?;
+        }
+        Ok(())
+    }
+}
 
 impl<const D: usize, E: Elem> SimplexMesh<D, E> {
+    /// Compute the complexity of a metric field, i.e. the expected number of elements of a mesh
+    /// adapted to it (see `complexity`). The volumes are required
+    pub fn metric_complexity<M: Metric<D>>(&self, m: &[M]) -> Result<f64> {
+        if m.len() != self.n_verts() as usize {
+            return Err(Error::from("invalid metric field size"));
+        }
+        self.get_vertex_volumes()?;
+
+        Ok(self.complexity(m, 0.0, f64::MAX))
+    }
+
+    /// Compute statistics on a metric field (see `MetricStats`). The volumes are required
+    pub fn metric_stats<M: Metric<D>>(&self, m: &[M]) -> Result<MetricStats> {
+        let complexity = self.metric_complexity(m)?;
+
+        let mut h_min = f64::MAX;
+        let mut h_max = 0.0_f64;
+        let mut h_mean = 0.0;
+        let mut aniso_max = 0.0_f64;
+        let mut aniso_counts = vec![0; ANISO_BINS.len()];
+        for s in m.iter().map(Metric::sizes) {
+            h_min = h_min.min(s[0]);
+            h_max = h_max.max(s[D - 1]);
+            h_mean += s.iter().sum::<f64>();
+            let aniso = s[D - 1] / s[0];
+            aniso_max = aniso_max.max(aniso);
+            let i = ANISO_BINS
+                .iter()
+                .position(|&b| aniso < b)
+                .unwrap_or(ANISO_BINS.len() - 1);
+            aniso_counts[i] += 1;
+        }
+        h_mean /= (D * m.len().max(1)) as f64;
+
+        let mut aniso_bins = vec![1.0];
+        aniso_bins.extend(ANISO_BINS);
+
+        Ok(MetricStats {
+            h_min,
+            h_max,
+            h_mean,
+            aniso_max,
+            aniso_bins,
+            aniso_counts,
+            complexity,
+        })
+    }
+
     /// Get the metric information (min/max size, max anisotropy, complexity)
     pub fn metric_info<M: Metric<D>>(&self, m: &[M]) -> (f64, f64, f64, f64) {
         let (h_min, h_max, aniso_max) = m
@@ -24,8 +165,8 @@ impl<const D: usize, E: Elem> SimplexMesh<D, E> {
                 |(a, b, c), d| {
                     (
                         f64::min(a, d[0]),
-                        f64::max(b, d[2]),
-                        f64::max(c, d[2] / d[0]),
+                        f64::max(b, d[D - 1]),
+                        f64::max(c, d[D - 1] / d[0]),
                     )
                 },
             )
@@ -64,6 +205,42 @@ impl<const D: usize, E: Elem> SimplexMesh<D, E> {
             .collect())
     }
 
+    /// Compute statistics on the lengths of the edges in metric space (see `EdgeLengthStats`),
+    /// with a geometric interpolation of the metric along the edges (see `Metric::edge_length`).
+    /// The edges are required
+    pub fn edge_length_stats<M: Metric<D>>(&self, m: &[M]) -> Result<EdgeLengthStats> {
+        if m.len() != self.n_verts() as usize {
+            return Err(Error::from("invalid metric field size"));
+        }
+        let lengths = self.edge_lengths(m)?;
+
+        let mut counts = vec![0; LENGTH_BINS.len()];
+        let mut n_unit = 0;
+        for &l in &lengths {
+            let i = LENGTH_BINS
+                .iter()
+                .position(|&b| l < b)
+                .unwrap_or(LENGTH_BINS.len() - 1);
+            counts[i] += 1;
+            if (LENGTH_BINS[1]..=LENGTH_BINS[3]).contains(&l) {
+                n_unit += 1;
+            }
+        }
+
+        let mut bins = vec![0.0];
+        bins.extend(LENGTH_BINS);
+        let n = lengths.len().max(1) as f64;
+
+        Ok(EdgeLengthStats {
+            l_min: lengths.iter().copied().fold(f64::INFINITY, f64::min),
+            l_max: lengths.iter().copied().fold(0.0, f64::max),
+            l_mean: lengths.iter().sum::<f64>() / n,
+            bins,
+            counts,
+            unit_fraction: f64::from(n_unit) / n,
+        })
+    }
+
     /// Convert a metric field defined at the element centers (P0) to a field defined at the vertices (P1)
     /// using the interpolation method appropriate for the metric type.
     /// vertex-to-element connectivity and volumes are required
@@ -91,7 +268,7 @@ impl<const D: usize, E: Elem> SimplexMesh<D, E> {
                 weights.extend(
                     elems
                         .iter()
-                        .map(|i| elem_vol[*i as usize] / f64::from(E::N_VERTS) / vert_vol),
+                        .map(|i| elem_vol[*i as usize] / E::N_VERTS as f64 / vert_vol),
                 );
                 metrics.extend(elems.iter().map(|&i| v[i as usize]));
                 let wm = weights.iter().copied().zip(metrics.iter());
@@ -113,7 +290,7 @@ impl<const D: usize, E: Elem> SimplexMesh<D, E> {
 
         let mut res = vec![M::default(); n_elems];
 
-        let f = 1. / f64::from(E::N_VERTS);
+        let f = 1. / E::N_VERTS as f64;
 
         res.par_iter_mut()
             .zip(self.par_elems())
@@ -128,6 +305,23 @@ impl<const D: usize, E: Elem> SimplexMesh<D, E> {
         Ok(res)
     }
 
+    /// Intersect two metric fields defined at the vertices, e.g. computed from different solution
+    /// fields, using `Metric::intersect`
+    pub fn intersect_metrics<M: Metric<D>>(&self, m1: &[M], m2: &[M]) -> Result<Vec<M>> {
+        debug!("Intersect two metric fields");
+
+        let n_verts = self.n_verts() as usize;
+        if m1.len() != n_verts || m2.len() != n_verts {
+            return Err(Error::from("invalid metric field sizes"));
+        }
+
+        Ok(m1
+            .par_iter()
+            .zip(m2.par_iter())
+            .map(|(a, b)| a.intersect(b))
+            .collect())
+    }
+
     /// Compute the number of elements corresponding to a metric field based on its D characteristic sizes and min/max constraints
     #[must_use]
     pub fn complexity_from_sizes<M: Metric<D>>(
@@ -201,9 +395,98 @@ mod tests {
     use crate::{
         mesh::test_meshes::{test_mesh_2d, test_mesh_3d},
         mesh::Point,
-        metric::{AnisoMetric2d, AnisoMetric3d, IsoMetric},
+        metric::{AnisoMetric, AnisoMetric2d, AnisoMetric3d, IsoMetric, Metric},
+        Idx, Result,
     };
 
+    #[test]
+    fn test_metric_stats() -> Result<()> {
+        let mut mesh = test_mesh_2d().split().split();
+
+        let m: Vec<_> = mesh
+            .verts()
+            .map(|p| {
+                let v0 = Point::<2>::new(0.1, 0.);
+                let v1 = Point::<2>::new(0.0, if p[0] < 0.5 { 0.1 } else { 0.5 });
+                AnisoMetric2d::from_sizes(&v0, &v1)
+            })
+            .collect();
+        assert!(mesh.metric_stats(&m).is_err());
+
+        mesh.compute_volumes();
+        let stats = mesh.metric_stats(&m)?;
+        assert!((stats.h_min - 0.1).abs() < 1e-8);
+        assert!((stats.h_max - 0.5).abs() < 1e-8);
+        assert!((stats.aniso_max - 5.0).abs() < 1e-6);
+        assert_eq!(stats.aniso_bins.len(), stats.aniso_counts.len() + 1);
+        let n_iso = mesh.verts().filter(|p| p[0] < 0.5).count() as Idx;
+        assert_eq!(stats.aniso_counts[0], n_iso);
+        assert_eq!(stats.aniso_counts.iter().sum::<Idx>(), mesh.n_verts());
+        assert!((stats.complexity - mesh.metric_complexity(&m)?).abs() < 1e-12);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_edge_length_stats() -> Result<()> {
+        let mut mesh = test_mesh_2d();
+        let m = vec![IsoMetric::<2>::from(1.0); mesh.n_verts() as usize];
+        assert!(mesh.edge_length_stats(&m).is_err());
+
+        mesh.compute_edges();
+        let stats = mesh.edge_length_stats(&m)?;
+        assert!((stats.l_min - 1.0).abs() < 1e-12);
+        assert!((stats.l_max - 2.0_f64.sqrt()).abs() < 1e-12);
+        assert!((stats.l_mean - (4.0 + 2.0_f64.sqrt()) / 5.0).abs() < 1e-12);
+        assert_eq!(stats.bins.len(), stats.counts.len() + 1);
+        assert_eq!(stats.counts, vec![0, 0, 0, 4, 1, 0]);
+        assert!((stats.unit_fraction - 1.0).abs() < 1e-12);
+
+        let m = vec![IsoMetric::<2>::from(0.5); mesh.n_verts() as usize];
+        let stats = mesh.edge_length_stats(&m)?;
+        assert_eq!(stats.counts, vec![0, 0, 0, 0, 0, 5]);
+        assert!(stats.unit_fraction < 1e-12);
+
+        // geometric interpolation along the edges
+        let m = mesh
+            .verts()
+            .map(|p| IsoMetric::<2>::from(if p[1] < 0.5 { 1.0 } else { 2.0 }))
+            .collect::<Vec<_>>();
+        let stats = mesh.edge_length_stats(&m)?;
+        assert!((stats.l_min - 0.5).abs() < 1e-12);
+        assert!((stats.l_max - 0.5 * 2.0_f64.sqrt() / 2.0_f64.ln()).abs() < 1e-12);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_intersect_metrics() -> Result<()> {
+        let mesh = test_mesh_2d().split().split();
+        let n_verts = mesh.n_verts() as usize;
+
+        let m1 = vec![AnisoMetric2d::from_iso(&IsoMetric::<2>::from(0.1)); n_verts];
+        let m2: Vec<_> = mesh
+            .verts()
+            .map(|p| {
+                let v0 = Point::<2>::new(0.5 * (1.0 + p[0]), 0.);
+                let v1 = Point::<2>::new(0.0, 0.05);
+                AnisoMetric2d::from_sizes(&v0, &v1)
+            })
+            .collect();
+
+        let m = mesh.intersect_metrics(&m1, &m2)?;
+        assert_eq!(m.len(), n_verts);
+        for m in &m {
+            let s = m.sizes();
+            assert!((s[0] - 0.05).abs() < 1e-8);
+            assert!((s[1] - 0.1).abs() < 1e-8);
+        }
+
+        assert!(mesh.intersect_metrics(&m1[1..], &m2).is_err());
+
+        Ok(())
+    }
+
     #[test]
     fn test_complexity_2d() {
         let mut mesh = test_mesh_2d().split().split();
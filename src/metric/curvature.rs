@@ -1,8 +1,8 @@
 use crate::{
-    geometry::LinearGeometry,
+    geometry::{compute_curvature_tensor, LinearGeometry},
     mesh::{Edge, Point, SimplexMesh, Tetrahedron, Triangle},
-    metric::{AnisoMetric2d, AnisoMetric3d},
-    Result, Tag,
+    metric::{AnisoMetric2d, AnisoMetric3d, Metric},
+    Error, Result, Tag,
 };
 use log::debug;
 use rustc_hash::FxHashSet;
@@ -149,11 +149,69 @@ impl SimplexMesh<2, Triangle> {
     }
 }
 
+impl SimplexMesh<3, Triangle> {
+    /// Compute an anisotropic metric on a surface mesh from the principal curvatures
+    /// $`\kappa_i`$ of the discrete surface (computed on each element tag separately, see
+    /// `compute_curvature_tensor`), such that the chordal error is `chordal_error`, i.e. with
+    /// sizes
+    /// ```math
+    /// h_i = \sqrt{\frac{8 \epsilon}{|\kappa_i|}}
+    /// ```
+    /// in the principal directions, bounded in [h_min, h_max]. The size in the normal direction
+    /// is the minimum of the two. The metric at the vertices is the intersection of the metrics
+    /// of the adjacent elements
+    pub fn surface_curvature_metric(
+        &self,
+        chordal_error: f64,
+        h_min: f64,
+        h_max: f64,
+    ) -> Result<Vec<AnisoMetric3d>> {
+        debug!("Compute the surface curvature metric with chordal error = {chordal_error:.2e}");
+
+        if chordal_error <= 0.0 || h_min <= 0.0 || h_max < h_min {
+            return Err(Error::from(
+                "invalid parameters: chordal_error > 0 and 0 < h_min <= h_max are required",
+            ));
+        }
+
+        let size = |k: f64| f64::sqrt(8.0 * chordal_error / k.abs()).clamp(h_min, h_max);
+
+        let mut elem_metric = vec![AnisoMetric3d::default(); self.n_elems() as usize];
+        let tags: FxHashSet<Tag> = self.etags().collect();
+        for tag in tags {
+            let sub = self.extract_tag(tag);
+            let (elem_u, elem_v) = compute_curvature_tensor(&sub.mesh);
+            for ((mut u, mut v), &i_elem) in elem_u
+                .into_iter()
+                .zip(elem_v)
+                .zip(sub.parent_elem_ids.iter())
+            {
+                let hu = size(u.norm());
+                let hv = size(v.norm());
+                u.normalize_mut();
+                v.normalize_mut();
+                let n = f64::min(hu, hv) * u.cross(&v).normalize();
+                elem_metric[i_elem as usize] = AnisoMetric3d::from_sizes(&n, &(hu * u), &(hv * v));
+            }
+        }
+
+        let mut res = vec![None; self.n_verts() as usize];
+        for (e, m) in self.elems().zip(elem_metric.iter()) {
+            for i in e {
+                let m_vert: &mut Option<AnisoMetric3d> = &mut res[i as usize];
+                *m_vert = Some(m_vert.map_or(*m, |m_vert| m_vert.intersect(m)));
+            }
+        }
+
+        Ok(res.into_iter().map(Option::unwrap_or_default).collect())
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use crate::{
         geometry::LinearGeometry,
-        mesh::test_meshes::test_mesh_3d,
+        mesh::test_meshes::{sphere_mesh_surf, test_mesh_3d},
         mesh::Point,
         metric::{AnisoMetric3d, Metric},
         Result, ANISO_MAX,
@@ -290,4 +348,29 @@ mod tests {
         }
         Ok(())
     }
+
+    #[test]
+    fn test_surface_curvature_metric() -> Result<()> {
+        // unit sphere
+        let mesh = sphere_mesh_surf(4);
+
+        let eps = 1e-3;
+        let h = f64::sqrt(8.0 * eps);
+        let m = mesh.surface_curvature_metric(eps, 1e-4, 1.0)?;
+        for m in &m {
+            let s = m.sizes();
+            assert!(s.iter().all(|&s| (s - h).abs() < 0.2 * h), "{s:?}");
+        }
+
+        // bounded sizes
+        let m = mesh.surface_curvature_metric(eps, 0.1, 1.0)?;
+        for m in &m {
+            let s = m.sizes();
+            assert!(s.iter().all(|&s| s > 0.099), "{s:?}");
+        }
+
+        assert!(mesh.surface_curvature_metric(-1.0, 0.1, 1.0).is_err());
+
+        Ok(())
+    }
 }
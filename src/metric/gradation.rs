@@ -57,6 +57,13 @@ impl<const D: usize, E: Elem> SimplexMesh<D, E> {
     /// applications in aeronautics and aerospace", F. Alauzet & L. Frazza, 2021
     ///
     /// and modified for parallel implementation
+    /// - m: the metric field at every vertex, modified in place
+    /// - beta: the maximum gradation
+    /// - t: mix between metric-space (t = 0) and physical-space (t = 1) gradation (see `Metric::span`)
+    /// - max_iter: the maximum number of iterations
+    ///
+    /// The vertex-to-vertex connectivity is required. The number of vertices where the metric was
+    /// modified during the last iteration (i.e. 0 if the target gradation is achieved) is returned
     pub fn apply_metric_gradation<M: Metric<D>>(
         &self,
         m: &mut [M],
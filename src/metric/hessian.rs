@@ -0,0 +1,229 @@
+use crate::{
+    mesh::{Elem, SimplexMesh},
+    metric::AnisoMetric,
+    Error, Idx, Result,
+};
+use log::debug;
+use nalgebra::{allocator::Allocator, Const, DefaultAllocator, SMatrix};
+use rayon::{
+    iter::{
+        IndexedParallelIterator, IntoParallelIterator, IntoParallelRefMutIterator, ParallelIterator,
+    },
+    slice::ParallelSlice,
+};
+
+impl<const D: usize, E: Elem> SimplexMesh<D, E> {
+    /// Compute the optimal metric to control the interpolation error of a vertex field $`f`$ in
+    /// $`L^p`$ norm with a target complexity $`N`$ (see "Continuous mesh framework part II",
+    /// A. Loseille & F. Alauzet, 2011)
+    /// ```math
+    /// \mathcal M_{L^p} = \alpha \det(|H_f|)^{-\frac{1}{2p + d}} |H_f|
+    /// ```
+    /// where the hessian $`H_f`$ is computed using a least squares approximation (see `hessian`)
+    /// and $`\alpha`$ is chosen such that $`\mathcal C(\mathcal M_{L^p}) = N`$. `p` may be
+    /// `f64::INFINITY`.
+    ///
+    /// The vertex-to-vertex connectivity and the volumes are required
+    pub fn metric_from_field<M: AnisoMetric<D>>(
+        &self,
+        f: &[f64],
+        p: f64,
+        n_elems: Idx,
+    ) -> Result<Vec<M>>
+    where
+        Const<D>: nalgebra::ToTypenum + nalgebra::DimSub<nalgebra::U1>,
+        DefaultAllocator: Allocator<<Const<D> as nalgebra::DimSub<nalgebra::U1>>::Output>,
+    {
+        debug!("Compute the L{p} metric of a vertex field (n_elems = {n_elems})");
+
+        let hessian = self.hessian(f, None, true)?;
+        let m = hessian
+            .par_chunks(D * (D + 1) / 2)
+            .map(|h| M::from_mat(M::slice_to_mat(h)))
+            .collect::<Vec<_>>();
+
+        self.lp_normalization(m, p, n_elems)
+    }
+
+    /// Compute a goal-oriented metric for a functional $`J`$, given the $`n`$ components
+    /// $`u_k`$ of a primal field and the corresponding components $`\varphi_k`$ of the adjoint
+    /// field (both stored as `[u_0(x_0), ..., u_{n-1}(x_0), u_0(x_1), ...]`). The error on
+    /// $`J`$ is estimated as (see "Fully anisotropic goal-oriented mesh adaptation for 3D steady
+    /// Euler equations", A. Loseille, A. Dervieux & F. Alauzet, 2010)
+    /// ```math
+    /// \delta J \approx \int \sum_k |\nabla \varphi_k| \, |u_k - \Pi_h u_k| dx
+    /// ```
+    /// which leads to the optimal metric
+    /// ```math
+    /// \mathcal M_{go} = \alpha \det(|H_{go}|)^{-\frac{1}{2 + d}} |H_{go}|, \quad
+    /// |H_{go}| = \sum_k |\nabla \varphi_k| \, |H_{u_k}|
+    /// ```
+    /// with $`\alpha`$ chosen such that $`\mathcal C(\mathcal M_{go}) = N`$. If
+    /// `use_adjoint_hessian` is true, the term $`\sum_k |\nabla u_k| \, |H_{\varphi_k}|`$, that
+    /// accounts for the interpolation error of the adjoint, is added to $`|H_{go}|`$.
+    ///
+    /// The vertex-to-vertex connectivity and the volumes are required
+    pub fn goal_oriented_metric<M: AnisoMetric<D>>(
+        &self,
+        primal: &[f64],
+        adjoint: &[f64],
+        n_comp: usize,
+        use_adjoint_hessian: bool,
+        n_elems: Idx,
+    ) -> Result<Vec<M>>
+    where
+        Const<D>: nalgebra::ToTypenum + nalgebra::DimSub<nalgebra::U1>,
+        DefaultAllocator: Allocator<<Const<D> as nalgebra::DimSub<nalgebra::U1>>::Output>,
+    {
+        debug!("Compute the goal-oriented metric ({n_comp} components, n_elems = {n_elems})");
+
+        let n_verts = self.n_verts() as usize;
+        if n_comp == 0 || primal.len() != n_comp * n_verts || adjoint.len() != n_comp * n_verts {
+            return Err(Error::from("invalid primal / adjoint field sizes"));
+        }
+
+        let mut h_go = vec![SMatrix::<f64, D, D>::zeros(); n_verts];
+        let mut add_terms = |f: &[f64], g: &[f64]| -> Result<()> {
+            // |grad(g)| |H(f)|
+            let grad = self.gradient(g, 2)?;
+            let hessian = self.hessian(f, None, true)?;
+            h_go.par_iter_mut()
+                .zip(grad.par_chunks(D))
+                .zip(hessian.par_chunks(D * (D + 1) / 2))
+                .for_each(|((h_go, grad), h)| {
+                    let mut eig = M::slice_to_mat(h).symmetric_eigen();
+                    eig.eigenvalues.iter_mut().for_each(|s| *s = s.abs());
+                    let nrm = grad.iter().map(|x| x * x).sum::<f64>().sqrt();
+                    *h_go += nrm * eig.recompose();
+                });
+            Ok(())
+        };
+
+        for k in 0..n_comp {
+            let u_k = primal
+                .iter()
+                .skip(k)
+                .step_by(n_comp)
+                .copied()
+                .collect::<Vec<_>>();
+            let phi_k = adjoint
+                .iter()
+                .skip(k)
+                .step_by(n_comp)
+                .copied()
+                .collect::<Vec<_>>();
+            add_terms(&u_k, &phi_k)?;
+            if use_adjoint_hessian {
+                add_terms(&phi_k, &u_k)?;
+            }
+        }
+
+        let m = h_go.into_par_iter().map(M::from_mat).collect::<Vec<_>>();
+
+        self.lp_normalization(m, 1.0, n_elems)
+    }
+
+    /// Apply the $`L^p`$ normalization to metric $`\mathcal M`$
+    /// ```math
+    /// \mathcal M_{L^p} = \alpha \det(\mathcal M)^{-\frac{1}{2p + d}} \mathcal M
+    /// ```
+    /// where $`\alpha`$ is chosen such that $`\mathcal C(\mathcal M_{L^p}) = N`$
+    fn lp_normalization<M: AnisoMetric<D>>(
+        &self,
+        mut m: Vec<M>,
+        p: f64,
+        n_elems: Idx,
+    ) -> Result<Vec<M>>
+    where
+        Const<D>: nalgebra::ToTypenum + nalgebra::DimSub<nalgebra::U1>,
+        DefaultAllocator: Allocator<<Const<D> as nalgebra::DimSub<nalgebra::U1>>::Output>,
+    {
+        if p <= 0.0 {
+            return Err(Error::from("the Lp norm requires p > 0"));
+        }
+
+        let exponent = -1.0 / (2.0 * p + D as f64);
+        m.par_iter_mut().for_each(|m| {
+            let det = m.vol().powi(-2);
+            *m = M::from_mat(det.powf(exponent) * m.as_mat());
+        });
+
+        self.scale_metric(&mut m, 0.0, f64::MAX, n_elems, None, None, None, 10)?;
+
+        Ok(m)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        mesh::test_meshes::test_mesh_2d,
+        metric::{AnisoMetric2d, Metric},
+        Result,
+    };
+
+    #[test]
+    fn test_metric_from_field_2d() -> Result<()> {
+        let mut mesh = test_mesh_2d().split().split().split();
+        mesh.compute_vertex_to_vertices();
+        mesh.compute_volumes();
+
+        let f = mesh
+            .verts()
+            .map(|p| p[0] * p[0] + 10.0 * p[1] * p[1])
+            .collect::<Vec<_>>();
+
+        for p in [1.0, 2.0, f64::INFINITY] {
+            let m = mesh.metric_from_field::<AnisoMetric2d>(&f, p, 1000)?;
+            assert_eq!(m.len(), mesh.n_verts() as usize);
+
+            let c = mesh.complexity(&m, 0.0, f64::MAX);
+            assert!((c - 1000.0).abs() < 100.0, "{c}");
+
+            // |H| = diag(2, 20) everywhere
+            for m in &m {
+                let s = m.sizes();
+                assert!((s[1] / s[0] - f64::sqrt(10.0)).abs() < 1e-6);
+                assert!(m.length(&[1.0, 0.0].into()) < m.length(&[0.0, 1.0].into()));
+            }
+        }
+
+        assert!(mesh
+            .metric_from_field::<AnisoMetric2d>(&f, 0.0, 1000)
+            .is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_goal_oriented_metric_2d() -> Result<()> {
+        let mut mesh = test_mesh_2d().split().split().split();
+        mesh.compute_vertex_to_vertices();
+        mesh.compute_volumes();
+
+        // 2 components, the adjoint field only depends on the 1st one
+        let primal = mesh
+            .verts()
+            .flat_map(|p| [p[0] * p[0], p[1] * p[1]])
+            .collect::<Vec<_>>();
+        let adjoint = mesh.verts().flat_map(|p| [p[1], 0.0]).collect::<Vec<_>>();
+
+        let m = mesh.goal_oriented_metric::<AnisoMetric2d>(&primal, &adjoint, 2, false, 1000)?;
+        let c = mesh.complexity(&m, 0.0, f64::MAX);
+        assert!((c - 1000.0).abs() < 100.0, "{c}");
+        // |H_go| = diag(2, 0): refinement in the x direction only
+        for m in &m {
+            assert!(m.length(&[1.0, 0.0].into()) > 10.0 * m.length(&[0.0, 1.0].into()));
+        }
+
+        let m = mesh.goal_oriented_metric::<AnisoMetric2d>(&primal, &adjoint, 2, true, 1000)?;
+        let c = mesh.complexity(&m, 0.0, f64::MAX);
+        assert!((c - 1000.0).abs() < 100.0, "{c}");
+
+        assert!(mesh
+            .goal_oriented_metric::<AnisoMetric2d>(&primal[1..], &adjoint, 2, false, 1000)
+            .is_err());
+
+        Ok(())
+    }
+}
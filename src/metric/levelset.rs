@@ -0,0 +1,116 @@
+use crate::{
+    mesh::{Elem, Point, SimplexMesh},
+    metric::AnisoMetric,
+    Error, Result,
+};
+use log::debug;
+use nalgebra::{allocator::Allocator, Const, DefaultAllocator, SMatrix};
+use rayon::{
+    iter::{IndexedParallelIterator, IntoParallelRefIterator, ParallelIterator},
+    slice::ParallelSlice,
+};
+
+impl<const D: usize, E: Elem> SimplexMesh<D, E> {
+    /// Compute a metric field that refines the mesh in the direction normal to the iso-zero of a
+    /// level set function $`\varphi`$ defined at the vertices (e.g. a signed distance or a volume
+    /// fraction minus 0.5). The distance to the interface is estimated as
+    /// $`d = |\varphi| / \|\nabla \varphi\|`$ and, for $`d \le w`$,
+    /// ```math
+    /// \mathcal M = h_{far}^{-2} (I - n n^T) + h_n^{-2} n n^T
+    /// ```
+    /// where $`n = \nabla \varphi / \|\nabla \varphi\|`$ and $`h_n`$ varies linearly from
+    /// $`h_{interface}`$ on the interface to $`h_{far}`$ at $`d = w`$. Outside of this band, and
+    /// where $`\nabla \varphi = 0`$, the metric is isotropic with size $`h_{far}`$.
+    ///
+    /// The vertex-to-vertex connectivity is required
+    pub fn metric_from_levelset<M: AnisoMetric<D>>(
+        &self,
+        phi: &[f64],
+        h_interface: f64,
+        h_far: f64,
+        width: f64,
+    ) -> Result<Vec<M>>
+    where
+        Const<D>: nalgebra::ToTypenum + nalgebra::DimSub<nalgebra::U1>,
+        DefaultAllocator: Allocator<<Const<D> as nalgebra::DimSub<nalgebra::U1>>::Output>,
+    {
+        debug!(
+            "Compute the level set metric (h_interface = {h_interface}, h_far = {h_far}, width = {width})"
+        );
+
+        if phi.len() != self.n_verts() as usize {
+            return Err(Error::from("invalid level set field size"));
+        }
+        if h_interface <= 0.0 || h_far < h_interface || width <= 0.0 {
+            return Err(Error::from(
+                "invalid level set metric parameters: 0 < h_interface <= h_far and width > 0 are required",
+            ));
+        }
+
+        let grad = self.gradient(phi, 2)?;
+
+        let iso = SMatrix::<f64, D, D>::identity() / (h_far * h_far);
+        let res = phi
+            .par_iter()
+            .zip(grad.par_chunks(D))
+            .map(|(&f, grad)| {
+                let n = Point::<D>::from_column_slice(grad);
+                let nrm = n.norm();
+                if nrm < 1e-12 {
+                    return M::from_mat(iso);
+                }
+                let d = f.abs() / nrm;
+                if d > width {
+                    return M::from_mat(iso);
+                }
+                let n = n / nrm;
+                let h_n = h_interface + (h_far - h_interface) * d / width;
+                M::from_mat(iso + (1.0 / (h_n * h_n) - 1.0 / (h_far * h_far)) * n * n.transpose())
+            })
+            .collect();
+
+        Ok(res)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        mesh::{test_meshes::test_mesh_2d, Point},
+        metric::{AnisoMetric2d, Metric},
+        Result,
+    };
+
+    #[test]
+    fn test_metric_from_levelset_2d() -> Result<()> {
+        let mut mesh = test_mesh_2d().split().split().split().split();
+        mesh.compute_vertex_to_vertices();
+
+        // interface x + y = 1
+        let phi = mesh
+            .verts()
+            .map(|p| 2.0 * (p[0] + p[1] - 1.0))
+            .collect::<Vec<_>>();
+        let (h_interface, h_far, width) = (1e-3, 0.1, 0.2);
+        let m = mesh.metric_from_levelset::<AnisoMetric2d>(&phi, h_interface, h_far, width)?;
+
+        let n = Point::<2>::new(1.0, 1.0).normalize();
+        let t = Point::<2>::new(1.0, -1.0).normalize();
+        for (p, m) in mesh.verts().zip(m.iter()) {
+            let d = (p[0] + p[1] - 1.0).abs() / f64::sqrt(2.0);
+            let h_n = if d > width {
+                h_far
+            } else {
+                h_interface + (h_far - h_interface) * d / width
+            };
+            assert!((m.length(&t) * h_far - 1.0).abs() < 1e-6);
+            assert!((m.length(&n) * h_n - 1.0).abs() < 1e-6);
+        }
+
+        assert!(mesh
+            .metric_from_levelset::<AnisoMetric2d>(&phi[1..], h_interface, h_far, width)
+            .is_err());
+
+        Ok(())
+    }
+}
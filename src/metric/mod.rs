@@ -1,11 +1,16 @@
+mod boundary_layer;
 mod complexity;
 mod curvature;
 mod gradation;
+mod hessian;
 mod implied;
+mod levelset;
 mod reduction;
 mod scaling;
 mod smoothing;
 
+pub use complexity::{EdgeLengthStats, MetricStats};
+
 use crate::metric::reduction::{control_step, simultaneous_reduction, step};
 use crate::{mesh::Point, Error, Result};
 use crate::{H_MAX, S_MAX, S_MIN, S_RATIO_MAX};
@@ -34,12 +39,23 @@ pub trait Metric<const D: usize>:
     fn interpolate<'a, I: Iterator<Item = (f64, &'a Self)>>(weights_and_metrics: I) -> Self
     where
         Self: 'a;
+    /// Interpolate between different metrics in log space, i.e. compute
+    /// ```math
+    /// \mathcal M(\sum \alpha_i v_i) = \exp\left(\sum \alpha _i \ln(\mathcal M(v_i))\right)
+    /// ```
+    /// which always returns a valid metric
+    fn interpolate_log(weights_and_metrics: &[(f64, Self)]) -> Self {
+        Self::interpolate(weights_and_metrics.iter().map(|(w, m)| (*w, m)))
+    }
     /// Return the D characteristic sizes of the metric (sorted)
     fn sizes(&self) -> [f64; D];
     /// Scale the metric
     fn scale(&mut self, s: f64);
     /// Scale the metric, applying bounds on the characteristic sizes
     fn scale_with_bounds(&mut self, s: f64, h_min: f64, h_max: f64);
+    /// Bound the characteristic sizes of the metric in [h_min, h_max] and limit its anisotropy
+    /// (the ratio between the largest and smallest sizes) to `max_aniso`
+    fn clamp(&mut self, h_min: f64, h_max: f64, max_aniso: f64);
     /// Intersect with another metric, i.e. return the "largest" metric that is both "smaller" that self and other
     #[must_use]
     fn intersect(&self, other: &Self) -> Self;
@@ -57,6 +73,9 @@ pub trait Metric<const D: usize>:
     /// Limit a metric so the required sizes between 1/f and f times those required by other
     /// The directions are not changed
     fn control_step(&mut self, other: &Self, f: f64);
+    /// Transform the metric by a rotation $`R`$, i.e. compute $`R \mathcal M R^T`$
+    #[must_use]
+    fn rotate(&self, r: &SMatrix<f64, D, D>) -> Self;
     /// Compute the length of an edge in metric space, assuming a geometric variation of the metric sizes along the edge
     ///
     /// The length of $`e = v_1 - v_0`$ in metric space is
@@ -112,6 +131,7 @@ pub trait Metric<const D: usize>:
 /// Isotropic metric in D dimensions
 /// The metric is represented by a single scalar, which represents the characteristic size in all the directions
 #[derive(Clone, Copy, Debug)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
 pub struct IsoMetric<const D: usize>(f64);
 
 impl<const D: usize> IsoMetric<D> {
@@ -177,6 +197,18 @@ impl<const D: usize> Metric<D> for IsoMetric<D> {
         Self(res)
     }
 
+    /// For isotropic metrics, the log-Euclidean interpolation is
+    /// ```math
+    /// h(\sum \alpha_i v_i) = \exp\left(\sum \alpha _i \ln(h(v_i))\right)
+    /// ```
+    fn interpolate_log(weights_and_metrics: &[(f64, Self)]) -> Self {
+        let res = weights_and_metrics
+            .iter()
+            .map(|(w, h)| w * h.0.ln())
+            .sum::<f64>();
+        Self(res.exp())
+    }
+
     fn sizes(&self) -> [f64; D] {
         [self.0; D]
     }
@@ -189,6 +221,10 @@ impl<const D: usize> Metric<D> for IsoMetric<D> {
         self.0 = f64::min(h_max, f64::max(h_min, s * self.0));
     }
 
+    fn clamp(&mut self, h_min: f64, h_max: f64, _max_aniso: f64) {
+        self.0 = self.0.clamp(h_min, h_max);
+    }
+
     fn intersect(&self, other: &Self) -> Self {
         Self(f64::min(self.0, other.0))
     }
@@ -210,6 +246,10 @@ impl<const D: usize> Metric<D> for IsoMetric<D> {
     fn control_step(&mut self, other: &Self, f: f64) {
         self.0 = f64::min(self.0, other.0 * f).max(other.0 / f);
     }
+
+    fn rotate(&self, _r: &SMatrix<f64, D, D>) -> Self {
+        *self
+    }
 }
 
 impl<const D: usize> IntoIterator for IsoMetric<D> {
@@ -430,6 +470,26 @@ where
         self.update_from_mat_and_vol(mat, vol);
     }
 
+    /// The eigenvalues $`\lambda_i`$ are first bounded in $`[h_{max}^{-2}, h_{min}^{-2}]`$, and
+    /// then the smallest ones are increased so that $`\lambda_{max} / \lambda_i \le a_{max}^2`$
+    fn clamp(&mut self, h_min: f64, h_max: f64, max_aniso: f64) {
+        let s_min = 1. / (h_max * h_max);
+        let s_max = 1. / (h_min * h_min);
+
+        let mat = self.as_mat();
+        let mut eig = mat.symmetric_eigen();
+        eig.eigenvalues
+            .iter_mut()
+            .for_each(|i| *i = i.clamp(s_min, s_max));
+        let s_min = eig.eigenvalues.max() / (max_aniso * max_aniso);
+        eig.eigenvalues.iter_mut().for_each(|i| *i = i.max(s_min));
+
+        let mat = eig.recompose();
+        let vol = 1. / eig.eigenvalues.iter().product::<f64>().sqrt();
+
+        self.update_from_mat_and_vol(mat, vol);
+    }
+
     /// The intersection of metrics $`\mathcal M_0`$ and $`\mathcal M_1`$ is obtained
     /// using the simulataneous reduction algorithm
     fn intersect(&self, other: &Self) -> Self {
@@ -491,6 +551,10 @@ where
         }
     }
 
+    fn rotate(&self, r: &SMatrix<f64, D, D>) -> Self {
+        Self::from_mat(r * self.as_mat() * r.transpose())
+    }
+
     fn scale(&mut self, s: f64) {
         self.scale_aniso(s);
     }
@@ -507,6 +571,7 @@ where
 /// NB: the matrix must be positive definite for the metric to be valid
 /// TODO: reuse the eigenvalue solvers ?
 #[derive(Clone, Copy, Debug)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
 pub struct AnisoMetric2d {
     m: [f64; 3],
     v: f64,
@@ -648,6 +713,7 @@ impl fmt::Display for AnisoMetric2d {
 /// NB: the matrix must be positive definite for the metric to be valid
 /// TODO: reuse the eigenvalue solvers?
 #[derive(Clone, Copy, Debug)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
 pub struct AnisoMetric3d {
     m: [f64; 6],
     v: f64,
@@ -1122,4 +1188,22 @@ mod tests {
         assert!(f64::abs(m0.sizes()[1] - 0.2) < 1e-12);
         assert!(f64::abs(m0.sizes()[2] - 5.0) < 1e-12);
     }
+
+    #[test]
+    fn test_rotate_2d() {
+        let v0 = Point::<2>::new(1.0, 0.);
+        let v1 = Point::<2>::new(0., 0.1);
+        let m = AnisoMetric2d::from_sizes(&v0, &v1);
+
+        let (s, c) = f64::sin_cos(0.3);
+        let r = SMatrix::<f64, 2, 2>::new(c, -s, s, c);
+        let m2 = m.rotate(&r);
+        assert!(f64::abs(m2.vol() - m.vol()) < 1e-12);
+        for e in [v0, v1, Point::<2>::new(1.0, 1.0)] {
+            assert!(f64::abs(m2.length(&(r * e)) - m.length(&e)) < 1e-10);
+        }
+
+        let m = IsoMetric::<2>::from(0.1);
+        assert!(f64::abs(m.rotate(&r).h() - 0.1) < 1e-12);
+    }
 }
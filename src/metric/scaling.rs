@@ -51,7 +51,7 @@ impl<const D: usize, E: Elem> SimplexMesh<D, E> {
                 "Iteration {}, complexity = {:.2e}, scale = {:.2e}",
                 iter, c, scale
             );
-            if f64::abs(c - f64::from(n_elems)) < 0.05 * f64::from(n_elems) {
+            if f64::abs(c - n_elems as f64) < 0.05 * n_elems as f64 {
                 return scale;
             }
             if iter == max_iter - 1 {
@@ -61,12 +61,38 @@ impl<const D: usize, E: Elem> SimplexMesh<D, E> {
                 );
                 return -1.0;
             }
-            fac = f64::powf(f64::from(n_elems) / c, -1. / f64::from(E::DIM));
+            fac = f64::powf(n_elems as f64 / c, -1. / E::DIM as f64);
             scale *= fac;
         }
         -1.0
     }
 
+    /// Bound the sizes of a metric field in [h_min, h_max] and limit its anisotropy to
+    /// `max_aniso` (see `Metric::clamp`)
+    pub fn limit_metric<M: Metric<D>>(
+        &self,
+        m: &mut [M],
+        h_min: f64,
+        h_max: f64,
+        max_aniso: f64,
+    ) -> Result<()> {
+        debug!("Limit the metric (h_min = {h_min}, h_max = {h_max}, max_aniso = {max_aniso})");
+
+        if m.len() != self.n_verts() as usize {
+            return Err(Error::from("invalid metric field size"));
+        }
+        if !(h_min > 0.0 && h_min <= h_max) || max_aniso < 1.0 {
+            return Err(Error::from(
+                "invalid metric limits: 0 < h_min <= h_max and max_aniso >= 1 are required",
+            ));
+        }
+
+        m.par_iter_mut()
+            .for_each(|m| m.clamp(h_min, h_max, max_aniso));
+
+        Ok(())
+    }
+
     fn get_bounded_metric<M: Metric<D>>(
         alpha: f64,
         h_min: f64,
@@ -152,7 +178,7 @@ impl<const D: usize, E: Elem> SimplexMesh<D, E> {
 
                 debug!("Complexity of the constrain metric: {}", constrain_c);
 
-                if constrain_c > f64::from(n_elems) {
+                if constrain_c > n_elems as f64 {
                     return Err(Error::from(&format!(
                         "The complexity of the constrain metric is {constrain_c:.2e} > n_elems = {n_elems}"
                     )));
@@ -181,7 +207,7 @@ impl<const D: usize, E: Elem> SimplexMesh<D, E> {
                         return Err(Error::from("Unable to scale the metric (bisection)"));
                     }
 
-                    if c < f64::from(n_elems) {
+                    if c < n_elems as f64 {
                         break;
                     }
                     scale_high *= 1.5;
@@ -198,7 +224,7 @@ impl<const D: usize, E: Elem> SimplexMesh<D, E> {
                         return Err(Error::from("Unable to scale the metric (bisection)"));
                     }
 
-                    if c > f64::from(n_elems) {
+                    if c > n_elems as f64 {
                         break;
                     }
                     scale_low /= 1.5;
@@ -210,13 +236,13 @@ impl<const D: usize, E: Elem> SimplexMesh<D, E> {
                     let tmp_m = m_iter(scale);
                     let c = self.complexity_iter(tmp_m, h_min, h_max);
                     debug!("Iteration {iter}: scale = {scale:.2e}, complexity = {c:.2e}");
-                    if f64::abs(c - f64::from(n_elems)) < 0.05 * f64::from(n_elems) {
+                    if f64::abs(c - n_elems as f64) < 0.05 * n_elems as f64 {
                         break;
                     }
                     if iter == max_iter - 1 {
                         return Err(Error::from("Unable to scale the metric (bisection)"));
                     }
-                    if c < f64::from(n_elems) {
+                    if c < n_elems as f64 {
                         scale_high = scale;
                     } else {
                         scale_low = scale;
@@ -243,10 +269,40 @@ mod tests {
     use crate::{
         mesh::test_meshes::{test_mesh_2d, test_mesh_3d},
         mesh::Point,
-        metric::{AnisoMetric2d, AnisoMetric3d, IsoMetric},
+        metric::{AnisoMetric2d, AnisoMetric3d, IsoMetric, Metric},
         Idx, Result,
     };
 
+    #[test]
+    fn test_limit_metric() -> Result<()> {
+        let mesh = test_mesh_2d().split().split();
+
+        let mut m: Vec<_> = mesh
+            .verts()
+            .map(|p| {
+                let v0 = Point::<2>::new(1e-6 + p[0], 0.);
+                let v1 = Point::<2>::new(0.0, 1e3 * (1.0 + p[1]));
+                AnisoMetric2d::from_sizes(&v0, &v1)
+            })
+            .collect();
+        mesh.limit_metric(&mut m, 1e-3, 10.0, 100.0)?;
+        for m in &m {
+            let s = m.sizes();
+            assert!(s[0] > 1e-3 * (1.0 - 1e-8));
+            assert!(s[1] < 10.0 * (1.0 + 1e-8));
+            assert!(s[1] / s[0] < 100.0 * (1.0 + 1e-8));
+        }
+
+        let mut m = vec![IsoMetric::<2>::from(1e-6); mesh.n_verts() as usize];
+        mesh.limit_metric(&mut m, 1e-3, 10.0, 100.0)?;
+        assert!(m.iter().all(|m| (m.sizes()[0] - 1e-3).abs() < 1e-12));
+
+        assert!(mesh.limit_metric(&mut m, 1e-3, 1e-4, 100.0).is_err());
+        assert!(mesh.limit_metric(&mut m, 1e-3, 10.0, 0.5).is_err());
+
+        Ok(())
+    }
+
     #[test]
     fn test_cscaling_2d() {
         let mut mesh = test_mesh_2d().split().split();
@@ -299,7 +355,7 @@ mod tests {
         let c0 = mesh.scale_metric(&mut m, 0.0, 0.05, n_target, None, None, None, 10)?;
         assert!(c0 > 0.0);
         let c1 = mesh.complexity(&m, 0.0, 0.05);
-        assert!(f64::abs(c1 - f64::from(n_target)) < 0.1 * f64::from(n_target));
+        assert!(f64::abs(c1 - n_target as f64) < 0.1 * n_target as f64);
 
         Ok(())
     }
@@ -325,7 +381,7 @@ mod tests {
         let c0 = mesh.scale_metric(&mut m, 0.0, 0.05, n_target, None, None, None, 10)?;
         assert!(c0 > 0.0);
         let c1 = mesh.complexity(&m, 0.0, 0.05);
-        assert!(f64::abs(c1 - f64::from(n_target)) < 0.1 * f64::from(n_target));
+        assert!(f64::abs(c1 - n_target as f64) < 0.1 * n_target as f64);
         Ok(())
     }
 
@@ -346,7 +402,7 @@ mod tests {
         let c0 = mesh.scale_metric(&mut m, 0.0, 0.05, n_target, Some(&fixed_m), None, None, 10)?;
         assert!(c0 > 0.0);
         let c1 = mesh.complexity(&m, 0.0, 0.05);
-        assert!(f64::abs(c1 - f64::from(n_target)) < 0.1 * f64::from(n_target));
+        assert!(f64::abs(c1 - n_target as f64) < 0.1 * n_target as f64);
 
         Ok(())
     }
@@ -1,10 +1,12 @@
 use crate::{
     mesh::{Elem, SimplexMesh},
     metric::Metric,
-    Idx, Result,
+    Error, Idx, Result,
 };
 use log::debug;
-use rayon::iter::{IndexedParallelIterator, IntoParallelRefMutIterator, ParallelIterator};
+use rayon::iter::{
+    IndexedParallelIterator, IntoParallelRefIterator, IntoParallelRefMutIterator, ParallelIterator,
+};
 
 impl<const D: usize, E: Elem> SimplexMesh<D, E> {
     /// Smooth a metric field to avoid numerical artifacts
@@ -73,6 +75,52 @@ impl<const D: usize, E: Elem> SimplexMesh<D, E> {
 
         Ok(res)
     }
+
+    /// Smooth a metric field using `n_iter` iterations of a Laplacian smoothing in log space:
+    /// the metric at vertex $`i`$ is replaced by the log-Euclidean interpolation (see
+    /// `Metric::interpolate_log`) with equal weights of the metrics at $`i`$ and at its neighbors.
+    /// If `keep_boundary` is true, the metric is not modified at the boundary vertices
+    ///
+    /// The vertex-to-vertex connectivity is required
+    pub fn smooth_metric_laplacian<M: Metric<D>>(
+        &self,
+        m: &[M],
+        n_iter: Idx,
+        keep_boundary: bool,
+    ) -> Result<Vec<M>> {
+        debug!("Apply {n_iter} iterations of Laplacian metric smoothing");
+        let n = self.n_verts() as usize;
+        if m.len() != n {
+            return Err(Error::from("invalid metric field size"));
+        }
+
+        let v2v = self.get_vertex_to_vertices()?;
+        let flg = if keep_boundary {
+            self.boundary_flag()
+        } else {
+            vec![false; n]
+        };
+
+        let mut res = m.to_vec();
+        for _ in 0..n_iter {
+            let tmp = res.clone();
+            res.par_iter_mut()
+                .zip(flg.par_iter())
+                .enumerate()
+                .filter(|(_, (_, &f))| !f)
+                .for_each(|(i_vert, (m_smooth, _))| {
+                    let neighbors = v2v.row(i_vert as Idx);
+                    let w = 1. / (neighbors.len() + 1) as f64;
+                    let wm = std::iter::once(i_vert as Idx)
+                        .chain(neighbors.iter().copied())
+                        .map(|i| (w, tmp[i as usize]))
+                        .collect::<Vec<_>>();
+                    *m_smooth = M::interpolate_log(&wm);
+                });
+        }
+
+        Ok(res)
+    }
 }
 
 #[cfg(test)]
@@ -81,7 +129,7 @@ mod tests {
         mesh::test_meshes::{test_mesh_2d, test_mesh_3d},
         mesh::Point,
         metric::{AnisoMetric2d, AnisoMetric3d, IsoMetric, Metric},
-        min_iter,
+        min_iter, Result,
     };
 
     #[test]
@@ -190,4 +238,32 @@ mod tests {
         assert!(f64::abs(vmin - 0.2) < 1e-6);
         assert!(f64::abs(vmax - 0.2) < 1e-6);
     }
+
+    #[test]
+    fn test_smooth_laplacian_2d() -> Result<()> {
+        let mut mesh = test_mesh_2d().split().split().split();
+        mesh.compute_vertex_to_vertices();
+
+        // oscillating sizes
+        let m: Vec<_> = (0..mesh.n_verts())
+            .map(|i| IsoMetric::<2>::from(if i % 2 == 0 { 0.1 } else { 0.2 }))
+            .collect();
+        let flg = mesh.boundary_flag();
+
+        let m_smooth = mesh.smooth_metric_laplacian(&m, 20, false)?;
+        let h = m_smooth.iter().map(|m| m.sizes()[0]).collect::<Vec<_>>();
+        let h_min = h.iter().copied().fold(f64::MAX, f64::min);
+        let h_max = h.iter().copied().fold(0.0, f64::max);
+        assert!(h_min > 0.1 && h_max < 0.2);
+        assert!(h_max / h_min < 1.5);
+
+        let m_smooth = mesh.smooth_metric_laplacian(&m, 20, true)?;
+        for ((m0, m1), f) in m.iter().zip(m_smooth.iter()).zip(flg) {
+            if f {
+                assert!(!m0.differs_from(m1, 1e-12));
+            }
+        }
+
+        Ok(())
+    }
 }
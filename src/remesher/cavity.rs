@@ -9,6 +9,7 @@ use core::fmt;
 use log::trace;
 use std::cmp::{min, Ordering};
 
+/// Mesh entity from which a cavity is built
 #[derive(Debug, Clone, Copy)]
 pub enum Seed {
     No,
@@ -16,6 +17,8 @@ pub enum Seed {
     Edge([Idx; 2]),
 }
 
+/// Result of the check of a filled cavity: the reason of the failure, or the minimum quality
+/// of the new elements
 #[derive(Debug)]
 pub enum CavityCheckStatus {
     LongEdge,
@@ -66,6 +69,7 @@ pub struct Cavity<const D: usize, E: Elem, M: Metric<D>> {
 
 impl<const D: usize, E: Elem, M: Metric<D>> Cavity<D, E, M> {
     /// Create a new (empty) cavity
+    #[must_use]
     pub const fn new() -> Self {
         Self {
             local2global: Vec::new(),
@@ -86,6 +90,47 @@ impl<const D: usize, E: Elem, M: Metric<D>> Cavity<D, E, M> {
         }
     }
 
+    /// Build the cavity of a vertex or an edge of the remesher (given by their global
+    /// indices), i.e. the elements that contain the seed entity
+    #[must_use]
+    pub fn build(r: &Remesher<D, E, M>, seed: Seed) -> Self {
+        let mut res = Self::new();
+        match seed {
+            Seed::Vertex(i) => res.init_from_vertex(i, r),
+            Seed::Edge(edg) => res.init_from_edge(edg, r),
+            Seed::No => {}
+        }
+        res
+    }
+
+    /// Fill the cavity from a point `p` with metric `m`, by connecting the faces of the
+    /// cavity to `p`: if the cavity was built from a vertex it is moved to `p`, and if it was
+    /// built from an edge a vertex is inserted at `p`
+    #[must_use]
+    pub fn fill(&self, p: Point<D>, m: M) -> FilledCavity<'_, D, E, M> {
+        let ftype = match self.seed {
+            Seed::Vertex(i) => FilledCavityType::MovedVertex((i, p, m)),
+            Seed::Edge(edg) => FilledCavityType::EdgeCenter((edg, p, m)),
+            Seed::No => unreachable!(),
+        };
+        FilledCavity::new(self, ftype)
+    }
+
+    /// Check if the cavity can be filled from a point `p` with metric `m` (see `fill`) such
+    /// that the new edges lengths are in [`l_min`, `l_max`] and the new element qualities are
+    /// larger than `q_min`
+    #[must_use]
+    pub fn try_rebuild(
+        &self,
+        p: Point<D>,
+        m: M,
+        l_min: f64,
+        l_max: f64,
+        q_min: f64,
+    ) -> CavityCheckStatus {
+        self.fill(p, m).check(l_min, l_max, q_min)
+    }
+
     /// Clear the cavity data
     pub fn clear(&mut self) {
         self.local2global.clear();
@@ -106,6 +151,7 @@ impl<const D: usize, E: Elem, M: Metric<D>> Cavity<D, E, M> {
     }
 
     /// Get the local vertex index from a global vertex index
+    #[must_use]
     pub fn get_local_index(&self, i: Idx) -> Option<Idx> {
         let res = self
             .local2global
@@ -158,6 +204,7 @@ impl<const D: usize, E: Elem, M: Metric<D>> Cavity<D, E, M> {
 
     /// Return the coordinate and the metric of the barycenter of the points used
     /// to generate this cavity (ex: 2 points after using `init_from_edge`)
+    #[must_use]
     pub fn seed_barycenter(&self) -> (Point<D>, M) {
         let local_ids = match &self.seed {
             Seed::No => unreachable!(),
@@ -292,16 +339,19 @@ impl<const D: usize, E: Elem, M: Metric<D>> Cavity<D, E, M> {
     }
 
     /// Get the number of vertices in the cavity
+    #[must_use]
     pub fn n_verts(&self) -> Idx {
         self.points.len() as Idx
     }
 
     /// Get the number of elements in the cavity
+    #[must_use]
     pub fn n_elems(&self) -> Idx {
         self.elems.len() as Idx
     }
 
     /// Get the i-the vertex & the associated tag and metric
+    #[must_use]
     pub fn vert(&self, i: Idx) -> (&Point<D>, TopoTag, &M) {
         (
             &self.points[i as usize],
@@ -311,6 +361,7 @@ impl<const D: usize, E: Elem, M: Metric<D>> Cavity<D, E, M> {
     }
 
     /// Get the i-th geometrical element
+    #[must_use]
     pub fn gelem(&self, i: Idx) -> E::Geom<D, M> {
         E::Geom::from_verts(
             self.elems[i as usize]
@@ -321,6 +372,7 @@ impl<const D: usize, E: Elem, M: Metric<D>> Cavity<D, E, M> {
     }
 
     /// Get the i-th geometrical face
+    #[must_use]
     pub fn gface(&self, face: &E::Face) -> <<E as Elem>::Geom<D, M> as GElem<D, M>>::Face {
         <<E as Elem>::Geom<D, M> as GElem<D, M>>::Face::from_verts(
             face.iter()
@@ -330,6 +382,7 @@ impl<const D: usize, E: Elem, M: Metric<D>> Cavity<D, E, M> {
     }
 
     /// Convert a face from local to global vertex numbering
+    #[must_use]
     pub fn global_elem<EE: Elem>(&self, face: &EE) -> EE {
         EE::from_iter(face.iter().map(|&i| self.local2global[i as usize]))
     }
@@ -351,6 +404,7 @@ impl<const D: usize, E: Elem, M: Metric<D>> Cavity<D, E, M> {
 
     /// Convert the filled cavity to a `SimplexMesh` to export it (for debug)
     #[allow(dead_code)]
+    #[must_use]
     pub fn to_mesh(&self) -> SimplexMesh<D, E> {
         let mut faces = Vec::new();
         let mut ftags = Vec::new();
@@ -382,6 +436,12 @@ impl<const D: usize, E: Elem, M: Metric<D>> Cavity<D, E, M> {
     }
 }
 
+impl<const D: usize, E: Elem, M: Metric<D>> Default for Cavity<D, E, M> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl<const D: usize, E: Elem, M: Metric<D>> fmt::Display for Cavity<D, E, M> {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         writeln!(f, "Vertices")?;
@@ -431,10 +491,12 @@ pub struct FilledCavity<'a, const D: usize, E: Elem, M: Metric<D>> {
 
 impl<'a, const D: usize, E: Elem, M: Metric<D>> FilledCavity<'a, D, E, M> {
     /// Construct a `FilledCavity`
+    #[must_use]
     pub const fn new(cavity: &'a Cavity<D, E, M>, ftype: FilledCavityType<D, M>) -> Self {
         Self { cavity, ftype }
     }
 
+    #[must_use]
     pub fn is_same(&self) -> bool {
         if let FilledCavityType::ExistingVertex(i) = self.ftype {
             self.cavity.elems.iter().all(|e| e.contains_vertex(i))
@@ -477,6 +539,7 @@ impl<'a, const D: usize, E: Elem, M: Metric<D>> FilledCavity<'a, D, E, M> {
     }
 
     /// Check that the tagged faces are not already present (useful for collapse)
+    #[must_use]
     pub fn check_tagged_faces(&self, r: &Remesher<D, E, M>) -> bool {
         if let FilledCavityType::ExistingVertex(i) = self.ftype {
             let i = self.cavity.local2global[i as usize];
@@ -494,6 +557,7 @@ impl<'a, const D: usize, E: Elem, M: Metric<D>> FilledCavity<'a, D, E, M> {
 
     /// Convert the filled cavity to a `SimplexMesh` to export it (for debug)
     #[allow(dead_code)]
+    #[must_use]
     pub fn to_mesh(&self) -> SimplexMesh<D, E> {
         let mut verts = self.cavity.points.clone();
         if let FilledCavityType::EdgeCenter((_, x, _)) = self.ftype {
@@ -535,6 +599,7 @@ impl<'a, const D: usize, E: Elem, M: Metric<D>> FilledCavity<'a, D, E, M> {
         }
     }
 
+    #[must_use]
     pub fn check(&self, l_min: f64, l_max: f64, q_min: f64) -> CavityCheckStatus {
         let (p0, m0) = self.point();
         let mut min_quality = 1.;
@@ -571,6 +636,7 @@ impl<'a, const D: usize, E: Elem, M: Metric<D>> FilledCavity<'a, D, E, M> {
 
     /// Check the the angle between the normal of the boundary faces and the normal given by the geometry is smaller than a threshold
     /// This is only required for swaps in 3D
+    #[must_use]
     pub fn check_boundary_normals<G: Geometry<D>>(
         &self,
         topo: &Topology,
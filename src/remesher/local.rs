@@ -0,0 +1,208 @@
+use crate::{
+    geometry::Geometry,
+    mesh::{Elem, SimplexMesh},
+    metric::Metric,
+    remesher::{Remesher, RemesherParams},
+    Error, Idx, Result,
+};
+use log::debug;
+use rustc_hash::FxHashSet;
+
+impl<const D: usize, E: Elem> SimplexMesh<D, E> {
+    /// Remesh only the elements `i` for which `elem_filter(i)` is true, e.g. the elements with
+    /// a given tag or with their center in a bounding box.
+    ///
+    /// The faces between the region and the rest of the mesh are frozen (using negative tags,
+    /// as for the partition interfaces in `ParallelRemesher`), so that the elements outside of
+    /// the region, their vertices and their metrics are copied unchanged at the beginning of
+    /// the new mesh. The tags of the faces already present on the region boundary are kept.
+    ///
+    /// Returns the new mesh and metric. The mesh topology is required; it is not computed for
+    /// the new mesh
+    pub fn remesh_region<M: Metric<D>, G: Geometry<D>, F: FnMut(Idx) -> bool>(
+        &self,
+        m: &[M],
+        geom: &G,
+        params: RemesherParams,
+        mut elem_filter: F,
+    ) -> Result<(Self, Vec<M>)> {
+        debug!("Remesh a region of the mesh");
+
+        let topo = self.get_topology()?.clone();
+        if m.len() != self.n_verts() as usize {
+            return Err(Error::from("invalid metric size"));
+        }
+
+        let flag = (0..self.n_elems())
+            .map(&mut elem_filter)
+            .collect::<Vec<_>>();
+        let n_flagged = flag.iter().filter(|&&x| x).count();
+        debug!("{n_flagged} / {} elements to remesh", self.n_elems());
+        if n_flagged == 0 {
+            return Ok((self.clone(), m.to_vec()));
+        }
+
+        // Tag the elements 1 in the region and 2 outside, and add the interface faces
+        let etags = self.etags().collect::<Vec<_>>();
+        let mut tmp = self.clone();
+        tmp.mut_etags()
+            .zip(flag.iter())
+            .for_each(|(t, &x)| *t = if x { 1 } else { 2 });
+        let (bdy_tags, ifc_tags) = tmp.add_boundary_faces();
+        if !bdy_tags.is_empty() {
+            return Err(Error::from("the boundary faces are not all present"));
+        }
+        let ifc_tags = ifc_tags.keys().copied().collect::<FxHashSet<_>>();
+
+        // Freeze the interface faces, including the existing ones
+        let f2e = tmp.get_face_to_elems()?;
+        let on_interface = tmp
+            .faces()
+            .map(|f| {
+                f2e.get(&f.sorted())
+                    .is_some_and(|v| v.len() == 2 && flag[v[0] as usize] != flag[v[1] as usize])
+            })
+            .collect::<Vec<_>>();
+        tmp.mut_ftags()
+            .zip(on_interface.iter())
+            .filter(|(_, &x)| x)
+            .for_each(|(t, _)| *t = -*t);
+
+        // Remesh the region, with the original element tags
+        let region = tmp.extract(|t| t == 1);
+        let mut local_mesh = region.mesh;
+        local_mesh
+            .mut_etags()
+            .zip(region.parent_elem_ids.iter())
+            .for_each(|(t, &i)| *t = etags[i as usize]);
+        local_mesh.compute_topology_from(topo);
+        let local_m = region
+            .parent_vert_ids
+            .iter()
+            .map(|&i| m[i as usize])
+            .collect::<Vec<_>>();
+        let mut remesher = Remesher::new(&local_mesh, &local_m, geom)?;
+        remesher.remesh(params, geom)?;
+        let local_mesh = remesher.to_mesh(false);
+        let local_m = remesher.metrics();
+
+        // Copy the elements outside of the region and merge the remeshed region
+        let outside = tmp.extract(|t| t == 2);
+        let mut res = outside.mesh;
+        res.mut_etags()
+            .zip(outside.parent_elem_ids.iter())
+            .for_each(|(t, &i)| *t = etags[i as usize]);
+        let mut res_m = outside
+            .parent_vert_ids
+            .iter()
+            .map(|&i| m[i as usize])
+            .collect::<Vec<_>>();
+
+        let (ids, _, _) = res.add(&local_mesh, |_| true, |t| t > 0, Some(1e-12));
+        res_m.extend(ids.iter().map(|&i| local_m[i as usize]));
+
+        res.remove_faces(|t| ifc_tags.contains(&-t));
+        res.mut_ftags().for_each(|t| *t = t.abs());
+
+        Ok((res, res_m))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        geometry::NoGeometry,
+        mesh::{test_meshes::test_mesh_2d, Point, SimplexMesh, Triangle},
+        metric::IsoMetric,
+        remesher::RemesherParams,
+        Idx, Result,
+    };
+
+    fn elem_center(mesh: &SimplexMesh<2, Triangle>, i_elem: Idx) -> Point<2> {
+        mesh.elem(i_elem)
+            .iter()
+            .fold(Point::<2>::zeros(), |a, &i| a + mesh.vert(i))
+            / 3.0
+    }
+
+    fn check_outside(
+        mesh: &SimplexMesh<2, Triangle>,
+        new_mesh: &SimplexMesh<2, Triangle>,
+        outside: &[Idx],
+    ) {
+        for (i_new, &i_elem) in outside.iter().enumerate() {
+            let e = mesh.elem(i_elem);
+            let new_e = new_mesh.elem(i_new as Idx);
+            for (&i, &j) in e.iter().zip(new_e.iter()) {
+                let p = mesh.vert(i);
+                let q = new_mesh.vert(j);
+                assert!(p
+                    .iter()
+                    .zip(q.iter())
+                    .all(|(a, b)| a.to_bits() == b.to_bits()));
+            }
+        }
+    }
+
+    #[test]
+    fn test_remesh_region_box() -> Result<()> {
+        let mut mesh = test_mesh_2d().split().split().split();
+        mesh.compute_topology();
+
+        let m = vec![IsoMetric::<2>::from(0.02); mesh.n_verts() as usize];
+        let is_inside = |p: Point<2>| p[0] > 0.25 && p[0] < 0.75 && p[1] < 0.5;
+        let outside = (0..mesh.n_elems())
+            .filter(|&i| !is_inside(elem_center(&mesh, i)))
+            .collect::<Vec<_>>();
+
+        let (mut new_mesh, new_m) =
+            mesh.remesh_region(&m, &NoGeometry(), RemesherParams::default(), |i| {
+                is_inside(elem_center(&mesh, i))
+            })?;
+        assert_eq!(new_m.len(), new_mesh.n_verts() as usize);
+        assert!(new_mesh.n_elems() > 2 * mesh.n_elems());
+
+        check_outside(&mesh, &new_mesh, &outside);
+
+        new_mesh.compute_face_to_elems();
+        new_mesh.check()?;
+        assert!((new_mesh.vol() - 1.0).abs() < 1e-12);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_remesh_region_tag() -> Result<()> {
+        let mut mesh = test_mesh_2d().split().split();
+        let centers = (0..mesh.n_elems())
+            .map(|i| elem_center(&mesh, i))
+            .collect::<Vec<_>>();
+        mesh.mut_etags()
+            .zip(centers.iter())
+            .for_each(|(t, c)| *t = if c[0] < 0.5 { 1 } else { 2 });
+        mesh.add_boundary_faces();
+        let n_ifc = mesh.ftags().filter(|&t| t > 4).count();
+        assert!(n_ifc > 0);
+        mesh.compute_topology();
+
+        let m = vec![IsoMetric::<2>::from(0.05); mesh.n_verts() as usize];
+        let etags = mesh.etags().collect::<Vec<_>>();
+        let outside = (0..mesh.n_elems())
+            .filter(|&i| etags[i as usize] == 2)
+            .collect::<Vec<_>>();
+
+        let (mut new_mesh, _) =
+            mesh.remesh_region(&m, &NoGeometry(), RemesherParams::default(), |i| {
+                etags[i as usize] == 1
+            })?;
+
+        check_outside(&mesh, &new_mesh, &outside);
+        assert!(new_mesh.etags().any(|t| t == 1));
+        assert_eq!(new_mesh.ftags().filter(|&t| t > 4).count(), n_ifc);
+
+        new_mesh.compute_face_to_elems();
+        new_mesh.check()?;
+
+        Ok(())
+    }
+}
@@ -1,7 +1,16 @@
 mod cavity;
+mod local;
+mod out_of_core;
 mod parallel;
+mod periodic;
 mod sequential;
 mod stats;
 
-pub use parallel::{ParallelRemesher, ParallelRemeshingInfo, ParallelRemeshingParams};
-pub use sequential::{Remesher, RemesherParams, SmoothingType};
+pub use cavity::{Cavity, CavityCheckStatus, FilledCavity, FilledCavityType, Seed};
+pub use out_of_core::OutOfCoreRemesher;
+pub use parallel::{
+    ParallelRemesher, ParallelRemeshingInfo, ParallelRemeshingParams, PartitionRemeshingError,
+};
+pub use periodic::{PeriodicPair, PeriodicTransform};
+pub use sequential::{Remesher, RemesherMemoryUsage, RemesherParams, RemeshingMode, SmoothingType};
+pub use stats::{RemesherStats, Stats, StepStats};
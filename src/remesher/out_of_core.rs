@@ -0,0 +1,454 @@
+use crate::{
+    geometry::Geometry,
+    mesh::{get_face_to_elem, Elem, PartitionType, SimplexMesh, Topology},
+    metric::Metric,
+    remesher::{Remesher, RemesherParams},
+    Dim, Error, Idx, Result, Tag,
+};
+use log::debug;
+use rustc_hash::FxHashSet;
+use std::{
+    fs,
+    marker::PhantomData,
+    path::{Path, PathBuf},
+};
+
+/// Tag of the faces between the adapted partitions and the interface region
+const INTERFACE_TAG: Tag = Tag::MIN;
+/// Tag of the faces between the elements of a partition and its ghost elements
+const PARTITION_TAG: Tag = Tag::MIN + 1;
+/// Tag of the faces at the outer boundary of the ghost elements
+const GHOST_TAG: Tag = Tag::MIN + 2;
+/// Element tag of the (frozen) ghost elements during the adaptation of a partition
+const GHOST_ETAG: Tag = Tag::MAX;
+/// File containing the # of elements of each partition (without the ghost elements)
+const INDEX_FILE: &str = "partitions.json";
+
+fn file_name(dir: &Path, name: &str, ext: &str) -> Result<String> {
+    dir.join(format!("{name}.{ext}"))
+        .to_str()
+        .map(String::from)
+        .ok_or_else(|| Error::from("invalid file name"))
+}
+
+fn write<const D: usize, E: Elem, M: Metric<D>>(
+    dir: &Path,
+    name: &str,
+    mesh: &SimplexMesh<D, E>,
+    m: &[M],
+) -> Result<()> {
+    mesh.write_meshb(&file_name(dir, name, "meshb")?)?;
+    let m = m.iter().copied().flatten().collect::<Vec<_>>();
+    mesh.write_solb(&m, &file_name(dir, name, "solb")?)
+}
+
+fn read<const D: usize, E: Elem, M: Metric<D>>(
+    dir: &Path,
+    name: &str,
+) -> Result<(SimplexMesh<D, E>, Vec<M>)> {
+    let mesh = SimplexMesh::<D, E>::read_meshb(&file_name(dir, name, "meshb")?)?;
+    let (m, n) = SimplexMesh::<D, E>::read_solb(&file_name(dir, name, "solb")?)?;
+    if n != M::N || m.len() != n * mesh.n_verts() as usize {
+        return Err(Error::from(&format!("invalid metric for {name}")));
+    }
+    let m = m.chunks(n).map(M::from_slice).collect();
+    Ok((mesh, m))
+}
+
+/// Reorder the elements of partition `tag` extracted with its ghost elements so that the ghost
+/// elements come last, and add the faces between the partition and the ghost elements and at the
+/// outer boundary of the ghost elements. The # of elements of the partition is also returned
+fn add_ghost_faces<const D: usize, E: Elem>(
+    part: &SimplexMesh<D, E>,
+    tag: Tag,
+) -> Result<(SimplexMesh<D, E>, Idx)> {
+    let (elems, ghosts): (Vec<_>, Vec<_>) = part
+        .elems()
+        .zip(part.etags())
+        .zip(part.get_partition()?.iter())
+        .partition(|(_, p)| **p == tag);
+    let n_elems = elems.len() as Idx;
+    let (elems, etags): (Vec<_>, Vec<_>) = elems.into_iter().chain(ghosts).map(|(x, _)| x).unzip();
+    let is_ghost = |i: Idx| i >= n_elems;
+
+    let mut faces = part.faces().collect::<Vec<_>>();
+    let mut ftags = part.ftags().collect::<Vec<_>>();
+    let mut tagged = faces.iter().map(|f| f.sorted()).collect::<FxHashSet<_>>();
+    let f2e = get_face_to_elem(elems.iter().copied());
+    for (i_elem, e) in elems.iter().enumerate() {
+        let ghost = is_ghost(i_elem as Idx);
+        for i_face in 0..E::N_FACES {
+            let f = e.face(i_face);
+            let key = f.sorted();
+            if tagged.contains(&key) {
+                continue;
+            }
+            let neighbors = &f2e[&key];
+            let ftag = match neighbors.len() {
+                1 if ghost => GHOST_TAG,
+                1 => return Err(Error::from("the boundary faces are not all present")),
+                _ if !ghost && neighbors.iter().any(|&i| is_ghost(i)) => PARTITION_TAG,
+                _ => continue,
+            };
+            tagged.insert(key);
+            faces.push(f);
+            ftags.push(ftag);
+        }
+    }
+
+    let mesh = SimplexMesh::new(part.verts().collect(), elems, etags, faces, ftags);
+    Ok((mesh, n_elems))
+}
+
+/// Flag the elements within `n_layers` of the ghost elements (with tag `GHOST_ETAG`). The
+/// flagged region is extended so that its boundary does not contain tagged faces
+fn flag_interface<const D: usize, E: Elem>(mesh: &SimplexMesh<D, E>, n_layers: Idx) -> Vec<bool> {
+    let is_ghost = mesh.etags().map(|t| t == GHOST_ETAG).collect::<Vec<_>>();
+    let mut flag = vec![false; mesh.n_elems() as usize];
+
+    let mut vert_flag = vec![false; mesh.n_verts() as usize];
+    mesh.elems()
+        .zip(is_ghost.iter())
+        .filter(|(_, g)| **g)
+        .flat_map(|(e, _)| e)
+        .for_each(|i| vert_flag[i as usize] = true);
+    for _ in 0..n_layers {
+        mesh.elems()
+            .zip(flag.iter_mut())
+            .zip(is_ghost.iter())
+            .filter(|(_, g)| !**g)
+            .for_each(|((e, f), _)| {
+                if e.iter().any(|&i| vert_flag[i as usize]) {
+                    *f = true;
+                }
+            });
+        mesh.elems()
+            .zip(flag.iter())
+            .filter(|(_, f)| **f)
+            .flat_map(|(e, _)| e)
+            .for_each(|i| vert_flag[i as usize] = true);
+    }
+
+    let f2e = get_face_to_elem(mesh.elems());
+    let tagged = mesh.faces().map(|f| f.sorted()).collect::<Vec<_>>();
+    loop {
+        let mut n_added = 0;
+        for els in tagged.iter().filter_map(|f| f2e.get(f)) {
+            if els.len() == 2 {
+                let (i0, i1) = (els[0] as usize, els[1] as usize);
+                if !is_ghost[i0] && !is_ghost[i1] && flag[i0] != flag[i1] {
+                    flag[i0] = true;
+                    flag[i1] = true;
+                    n_added += 1;
+                }
+            }
+        }
+        if n_added == 0 {
+            break;
+        }
+    }
+
+    flag
+}
+
+/// Out-of-core remesher: the partitions of the mesh and their metric are stored as .meshb /
+/// .solb files in a directory and are adapted one at a time, together with layers of frozen
+/// ghost elements from the neighboring partitions. The elements close to the partition
+/// interfaces are then gathered and adapted in a second pass.
+///
+/// Files `part_{i}.meshb` and `part_{i}.solb` contain the elements of partition `i` followed by
+/// its ghost elements, and `partitions.json` the # of elements of each partition (without the
+/// ghosts); they may be written by `write_partitions` or by another tool. Only one partition,
+/// and then the interface region, are loaded in memory. The element and face tags are kept
+pub struct OutOfCoreRemesher<const D: usize, E: Elem> {
+    dir: PathBuf,
+    n_elems: Vec<Idx>,
+    topo: Topology,
+    ifc_topo: Topology,
+    phantom: PhantomData<E>,
+}
+
+impl<const D: usize, E: Elem> OutOfCoreRemesher<D, E> {
+    /// Partition `mesh` using `partition_type` and write the partitions, with `n_ghost_layers`
+    /// (> 0) layers of ghost elements, and metric `m` to directory `dir` (that must exist). All
+    /// the boundary faces must be present. The # of partitions is returned
+    ///
+    /// NB: the whole mesh is in memory; the partitions of meshes larger than the memory must be
+    /// written by another tool
+    pub fn write_partitions<M: Metric<D>>(
+        mut mesh: SimplexMesh<D, E>,
+        m: &[M],
+        partition_type: PartitionType,
+        n_ghost_layers: Idx,
+        dir: &Path,
+    ) -> Result<usize> {
+        debug!("Write the partitions to {}", dir.display());
+
+        if m.len() != mesh.n_verts() as usize {
+            return Err(Error::from("invalid metric size"));
+        }
+        if n_ghost_layers == 0 {
+            return Err(Error::from(
+                "at least one layer of ghost elements is required",
+            ));
+        }
+
+        mesh.set_partition_to_etags(false);
+        mesh.partition(partition_type)?;
+        let mut tags = mesh.get_partition()?.to_vec();
+        tags.sort_unstable();
+        tags.dedup();
+
+        let mut n_elems = Vec::with_capacity(tags.len());
+        for (i_part, &tag) in tags.iter().enumerate() {
+            let part = mesh.extract_partition(tag, n_ghost_layers);
+            let (part_mesh, n) = add_ghost_faces(&part.mesh, tag)?;
+            let part_m = part
+                .parent_vert_ids
+                .iter()
+                .map(|&i| m[i as usize])
+                .collect::<Vec<_>>();
+            write(dir, &format!("part_{i_part}"), &part_mesh, &part_m)?;
+            n_elems.push(n);
+        }
+        fs::write(dir.join(INDEX_FILE), serde_json::to_string(&n_elems)?)?;
+        debug!("{} partitions written", n_elems.len());
+
+        Ok(n_elems.len())
+    }
+
+    /// Use the partitions stored in directory `dir` (see `write_partitions`). The partitions are
+    /// read one at a time to build the mesh topology
+    pub fn new(dir: &Path) -> Result<Self> {
+        debug!("Initialize the out-of-core remesher in {}", dir.display());
+
+        let n_elems: Vec<Idx> = serde_json::from_str(&fs::read_to_string(dir.join(INDEX_FILE))?)?;
+
+        let mut ifc_topo = Topology::new(E::DIM as Dim);
+        for (i_part, &n) in n_elems.iter().enumerate() {
+            let name = format!("part_{i_part}");
+            let mut mesh = SimplexMesh::<D, E>::read_meshb(&file_name(dir, &name, "meshb")?)?;
+            if mesh.n_elems() < n {
+                return Err(Error::from(&format!("invalid # of elements for {name}")));
+            }
+            mesh.compute_topology_from(ifc_topo);
+            ifc_topo = mesh.get_topology()?.clone();
+        }
+        let mut topo = ifc_topo.clone();
+        topo.clear(|(_, t)| t < 0);
+
+        Ok(Self {
+            dir: dir.to_path_buf(),
+            n_elems,
+            topo,
+            ifc_topo,
+            phantom: PhantomData,
+        })
+    }
+
+    /// Number of partitions
+    #[must_use]
+    pub fn n_parts(&self) -> usize {
+        self.n_elems.len()
+    }
+
+    /// Adapt the partitions one at a time with their ghost elements frozen, then the elements
+    /// within `n_layers` (> 0) of the partition interfaces. The adapted partitions are written to
+    /// files `done_{i}.meshb` and `done_{i}.solb` (if not empty), the elements close to their
+    /// interfaces to `ifc_{i}.meshb` and `ifc_{i}.solb`, and the adapted interface region to
+    /// `ifc.meshb` and `ifc.solb`
+    ///
+    /// NB: `params.frozen_verts` cannot be used as the vertex indices are local to the partitions
+    pub fn remesh<M: Metric<D>, G: Geometry<D>>(
+        &self,
+        geom: &G,
+        params: &RemesherParams,
+        n_layers: Idx,
+    ) -> Result<()> {
+        if n_layers == 0 {
+            return Err(Error::from("at least one interface layer is required"));
+        }
+        if !params.frozen_verts.is_empty() {
+            return Err(Error::from("frozen_verts cannot be used out-of-core"));
+        }
+
+        for (i_part, &n_elems) in self.n_elems.iter().enumerate() {
+            debug!("Remeshing partition {i_part}");
+            let (mut mesh, m) = read::<D, E, M>(&self.dir, &format!("part_{i_part}"))?;
+
+            // Freeze the ghost elements, and use a specific tag to identify them
+            let mut frozen_verts = mesh
+                .elems()
+                .skip(n_elems as usize)
+                .flatten()
+                .collect::<Vec<_>>();
+            frozen_verts.sort_unstable();
+            frozen_verts.dedup();
+            mesh.mut_etags()
+                .skip(n_elems as usize)
+                .for_each(|t| *t = GHOST_ETAG);
+            mesh.compute_topology_from(self.topo.clone());
+
+            let mut remesher = Remesher::new(&mesh, &m, geom)?;
+            let part_params = RemesherParams {
+                frozen_verts,
+                ..params.clone()
+            };
+            remesher.remesh(part_params, geom)?;
+            let mesh = remesher.to_mesh(false);
+            let m = remesher.metrics();
+
+            // Split the partition between the interface region and the rest
+            let ifc_flag = flag_interface(&mesh, n_layers);
+            let done_flag = mesh
+                .etags()
+                .zip(ifc_flag.iter())
+                .map(|(t, &f)| t != GHOST_ETAG && !f)
+                .collect::<Vec<_>>();
+            let f2e = get_face_to_elem(mesh.elems());
+            let mut faces = mesh.faces().collect::<Vec<_>>();
+            let mut ftags = mesh.ftags().collect::<Vec<_>>();
+            for (e, _) in mesh.elems().zip(done_flag.iter()).filter(|(_, f)| **f) {
+                for i_face in 0..E::N_FACES {
+                    let f = e.face(i_face);
+                    if f2e[&f.sorted()].iter().any(|&i| ifc_flag[i as usize]) {
+                        faces.push(f);
+                        ftags.push(INTERFACE_TAG);
+                    }
+                }
+            }
+            let mesh = SimplexMesh::new(
+                mesh.verts().collect(),
+                mesh.elems().collect(),
+                mesh.etags().collect(),
+                faces,
+                ftags,
+            );
+
+            for (name, flag) in [("done", &done_flag), ("ifc", &ifc_flag)] {
+                let sub = mesh.extract_elems(flag);
+                if sub.mesh.n_elems() > 0 {
+                    let sub_m = sub
+                        .parent_vert_ids
+                        .iter()
+                        .map(|&i| m[i as usize])
+                        .collect::<Vec<_>>();
+                    write(&self.dir, &format!("{name}_{i_part}"), &sub.mesh, &sub_m)?;
+                }
+            }
+        }
+
+        debug!("Remeshing the interface region");
+        let mut ifc = SimplexMesh::<D, E>::empty();
+        let mut ifc_m = Vec::new();
+        for i_part in 0..self.n_parts() {
+            let (mesh, m) = read::<D, E, M>(&self.dir, &format!("ifc_{i_part}"))?;
+            let (ids, _, _) = ifc.add(&mesh, |_| true, |_| true, Some(1e-12));
+            ifc_m.extend(ids.iter().map(|&i| m[i as usize]));
+        }
+        ifc.remove_faces(|t| t < 0 && t != INTERFACE_TAG);
+        ifc.compute_topology_from(self.ifc_topo.clone());
+
+        let mut ifc_params = params.clone();
+        ifc_params.frozen_face_tags.push(INTERFACE_TAG);
+        let mut remesher = Remesher::new(&ifc, &ifc_m, geom)?;
+        remesher.remesh(ifc_params, geom)?;
+        write(
+            &self.dir,
+            "ifc",
+            &remesher.to_mesh(false),
+            &remesher.metrics(),
+        )
+    }
+
+    /// Merge the adapted partitions and interface region written by `remesh` into a single
+    /// mesh (that must fit in memory) and get the associated metric
+    pub fn to_mesh<M: Metric<D>>(&self) -> Result<(SimplexMesh<D, E>, Vec<M>)> {
+        let (mut res, mut res_m) = read::<D, E, M>(&self.dir, "ifc")?;
+        for i_part in 0..self.n_parts() {
+            let name = format!("done_{i_part}");
+            if !Path::new(&file_name(&self.dir, &name, "meshb")?).exists() {
+                continue;
+            }
+            let (mesh, m) = read::<D, E, M>(&self.dir, &name)?;
+            let (ids, _, _) = res.add(&mesh, |_| true, |_| true, Some(1e-12));
+            res_m.extend(ids.iter().map(|&i| m[i as usize]));
+        }
+        res.remove_faces(|t| t < 0);
+
+        Ok((res, res_m))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::OutOfCoreRemesher;
+    use crate::{
+        geometry::NoGeometry,
+        mesh::{test_meshes::test_mesh_2d, GElem, PartitionType, Point, SimplexMesh, Triangle},
+        metric::IsoMetric,
+        remesher::RemesherParams,
+        Result, Tag,
+    };
+
+    #[test]
+    fn test_out_of_core_2d() -> Result<()> {
+        let mut mesh = test_mesh_2d().split().split().split().split();
+        let (_, ifc_tags) = mesh.add_boundary_faces();
+        let ifc_tag = *ifc_tags.keys().next().unwrap();
+
+        let h = |p: Point<2>| 0.01 + 0.1 * ((p[0] - 0.5).powi(2) + (p[1] - 0.35).powi(2));
+        let m = mesh
+            .verts()
+            .map(|p| IsoMetric::<2>::from(h(p)))
+            .collect::<Vec<_>>();
+
+        let dir = tempfile::tempdir()?;
+        let n_parts = OutOfCoreRemesher::<2, Triangle>::write_partitions(
+            mesh,
+            &m,
+            PartitionType::Hilbert(4),
+            2,
+            dir.path(),
+        )?;
+        assert_eq!(n_parts, 4);
+
+        let ooc = OutOfCoreRemesher::<2, Triangle>::new(dir.path())?;
+        assert_eq!(ooc.n_parts(), 4);
+        ooc.remesh::<IsoMetric<2>, _>(&NoGeometry(), &RemesherParams::default(), 2)?;
+        for i in 0..4 {
+            assert!(dir.path().join(format!("ifc_{i}.meshb")).exists());
+        }
+
+        let (mut mesh, m): (SimplexMesh<2, Triangle>, Vec<IsoMetric<2>>) = ooc.to_mesh()?;
+        assert_eq!(m.len(), mesh.n_verts() as usize);
+        assert!(mesh.ftags().all(|t| t > 0));
+        assert!(mesh.ftags().any(|t| t == ifc_tag));
+
+        let n = mesh.n_verts();
+        for i in 0..n {
+            let vi = mesh.vert(i);
+            for j in i + 1..n {
+                assert!((mesh.vert(j) - vi).norm() > 1e-8);
+            }
+        }
+
+        mesh.compute_face_to_elems();
+        mesh.check()?;
+        assert!((mesh.vol() - 1.0).abs() < 1e-12);
+
+        // the element tags are kept
+        let vol = |tag: Tag| {
+            mesh.gelems()
+                .zip(mesh.etags())
+                .filter(|(_, t)| *t == tag)
+                .map(|(ge, _)| ge.vol())
+                .sum::<f64>()
+        };
+        assert!(mesh.etags().all(|t| t == 1 || t == 2));
+        assert!((vol(1) - 0.5).abs() < 1e-12);
+        assert!((vol(2) - 0.5).abs() < 1e-12);
+
+        Ok(())
+    }
+}
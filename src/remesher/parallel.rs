@@ -9,8 +9,9 @@ use log::{debug, warn};
 use rayon::iter::{IndexedParallelIterator, IntoParallelRefIterator, ParallelIterator};
 use rustc_hash::FxHashSet;
 use serde::Serialize;
-use std::{sync::Mutex, time::Instant};
+use std::{fmt, sync::Mutex, time::Instant};
 
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
 pub struct ParallelRemeshingParams {
     n_layers: Idx,
     level: Idx,
@@ -111,7 +112,32 @@ impl ParallelRemeshingInfo {
     }
 }
 
-/// Domain decomposition
+/// Error returned by `ParallelRemesher::remesh` when the remeshing of a partition fails. The
+/// partition remeshing errors are not `Send`, so only their message is kept
+#[derive(Debug)]
+pub struct PartitionRemeshingError {
+    /// Domain decomposition level
+    pub level: Idx,
+    /// Partition index
+    pub partition: usize,
+    /// Message of the partition remeshing error
+    pub message: String,
+}
+
+impl fmt::Display for PartitionRemeshingError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "remeshing of partition {} (level {}) failed: {}",
+            self.partition, self.level, self.message
+        )
+    }
+}
+
+impl std::error::Error for PartitionRemeshingError {}
+
+/// Domain decomposition. The speedup depends on the partition balance and on the size of the
+/// interface, which is remeshed after the partitions (see the `remesh_parallel_3d` benchmark)
 pub struct ParallelRemesher<const D: usize, E: Elem> {
     mesh: SimplexMesh<D, E>,
     partition_tags: Vec<Tag>,
@@ -133,10 +159,12 @@ impl<const D: usize, E: Elem> ParallelRemesher<D, E> {
         let now = Instant::now();
         match partition_type {
             PartitionType::Hilbert(n)
+            | PartitionType::Rcb(n)
             | PartitionType::Scotch(n)
             | PartitionType::MetisRecursive(n)
             | PartitionType::MetisKWay(n) => {
                 assert!(n > 1, "Need at least 2 partitions");
+                mesh.set_partition_to_etags(true);
                 mesh.partition(partition_type)?;
             }
             PartitionType::None => {
@@ -244,11 +272,14 @@ impl<const D: usize, E: Elem> ParallelRemesher<D, E> {
         geom: &G,
         params: RemesherParams,
         submesh: SubSimplexMesh<D, E>,
-    ) -> (SimplexMesh<D, E>, Vec<M>) {
+    ) -> Result<(SimplexMesh<D, E>, Vec<M>)> {
         let mut local_mesh = submesh.mesh;
         // to be consistent with the base topology
         local_mesh.mut_etags().for_each(|t| *t = 1);
-        let mut topo = self.mesh.get_topology().unwrap().clone();
+        // the fields are remapped once the partitions are merged
+        local_mesh.vertex_fields_mut().clear();
+        local_mesh.elem_fields_mut().clear();
+        let mut topo = self.mesh.get_topology()?.clone();
         topo.clear(|(_, t)| self.is_partition_bdy(t));
         local_mesh.compute_topology_from(topo);
 
@@ -257,14 +288,18 @@ impl<const D: usize, E: Elem> ParallelRemesher<D, E> {
             .iter()
             .map(|&i| m[i as usize])
             .collect();
-        let mut local_remesher = Remesher::new(&local_mesh, &local_m, geom).unwrap();
+        let mut local_remesher = Remesher::new(&local_mesh, &local_m, geom)?;
 
-        local_remesher.remesh(params, geom).unwrap();
+        local_remesher.remesh(params, geom)?;
 
-        (local_remesher.to_mesh(true), local_remesher.metrics())
+        Ok((local_remesher.to_mesh(true), local_remesher.metrics()))
     }
 
-    /// Remesh using domain decomposition
+    /// Remesh using domain decomposition: the partitions are remeshed in parallel with their
+    /// interfaces frozen, then the elements close to the interfaces are remeshed (possibly
+    /// recursively using domain decomposition). A `PartitionRemeshingError` is returned if the
+    /// remeshing of any partition fails. The fields attached to the mesh are remapped onto the
+    /// result (see `SimplexMesh::remap_fields_from`)
     #[allow(clippy::too_many_lines)]
     pub fn remesh<M: Metric<D>, G: Geometry<D>>(
         &self,
@@ -299,86 +334,102 @@ impl<const D: usize, E: Elem> ParallelRemesher<D, E> {
 
         let now = Instant::now();
 
-        self.par_partitions()
-            .enumerate()
-            .for_each(|(i_part, submesh)| {
-                if self.debug {
-                    let fname = format!("level_{level}_part_{i_part}.vtu");
-                    submesh.mesh.write_vtk(&fname, None, None).unwrap();
-                }
-
-                // Remesh the partition
-                debug!("Remeshing level {level} / partition {i_part}");
-                let n_verts_init = submesh.mesh.n_verts();
-                let now = Instant::now();
-                let (mut local_mesh, local_m) =
-                    self.remesh_submesh(m, geom, params.clone(), submesh);
-
-                // Get the info
-                let mut info = info.lock().unwrap();
-                info.partitions[i_part] = RemeshingInfo {
-                    n_verts_init,
-                    n_verts_final: local_mesh.n_verts(),
-                    time: now.elapsed().as_secs_f64(),
-                };
-                drop(info);
-
-                // Flag elements with n_layers of the interfaces with tag 2, other with tag 1
-                let new_etags = self.flag_interface(&local_mesh, dd_params.n_layers);
-                local_mesh
-                    .mut_etags()
-                    .zip(new_etags.iter())
-                    .for_each(|(t0, t1)| *t0 = *t1);
-                let (bdy_tags, interface_tags) = local_mesh.add_boundary_faces();
-                assert!(bdy_tags.is_empty());
-
-                // Flag the faces between elements tagged 1 and 2 as self.interface_bdy_tag
-                if interface_tags.is_empty() {
-                    warn!("All the elements are in the interface");
-                } else {
-                    assert_eq!(interface_tags.len(), 1);
-                    let tag = interface_tags.keys().next().unwrap();
-                    local_mesh.mut_ftags().for_each(|t| {
-                        if *t == *tag {
-                            *t = self.interface_bdy_tag;
-                        }
-                    });
-                }
-
-                if self.debug {
-                    let fname = format!("level_{level}_part_{i_part}_remeshed.vtu");
-                    local_mesh.write_vtk(&fname, None, None).unwrap();
-                }
+        let remesh_partition = |(i_part, submesh): (usize, SubSimplexMesh<D, E>)| {
+            if self.debug {
+                let fname = format!("level_{level}_part_{i_part}.vtu");
+                submesh.mesh.write_vtk(&fname, None, None).unwrap();
+            }
 
-                // Update res
-                let mut res = res.lock().unwrap();
-                let (ids, _, _) = res.add(&local_mesh, |t| t == 1, |_| true, Some(1e-12));
-                if self.debug {
-                    let fname = format!("level_{level}_part_{i_part}_res.vtu");
-                    res.write_vtk(&fname, None, None).unwrap();
-                }
-                drop(res);
-                let mut res_m = res_m.lock().unwrap();
-                res_m.extend(ids.iter().map(|&i| local_m[i as usize]));
-                drop(res_m);
-
-                // Update ifc
-                let part_tag = 2 + i_part as Tag;
-                local_mesh.mut_etags().for_each(|t| {
-                    if *t == 2 {
-                        *t = part_tag;
+            // Remesh the partition
+            debug!("Remeshing level {level} / partition {i_part}");
+            let n_verts_init = submesh.mesh.n_verts();
+            let now = Instant::now();
+            let (mut local_mesh, local_m) = self
+                .remesh_submesh(m, geom, params.clone(), submesh)
+                .map_err(|e| PartitionRemeshingError {
+                level,
+                partition: i_part,
+                message: e.to_string(),
+            })?;
+
+            // Get the info
+            let mut info = info.lock().unwrap();
+            info.partitions[i_part] = RemeshingInfo {
+                n_verts_init,
+                n_verts_final: local_mesh.n_verts(),
+                time: now.elapsed().as_secs_f64(),
+            };
+            drop(info);
+
+            // Flag elements with n_layers of the interfaces with tag 2, other with tag 1
+            let new_etags = self.flag_interface(&local_mesh, dd_params.n_layers);
+            local_mesh
+                .mut_etags()
+                .zip(new_etags.iter())
+                .for_each(|(t0, t1)| *t0 = *t1);
+            let (bdy_tags, interface_tags) = local_mesh.add_boundary_faces();
+            assert!(bdy_tags.is_empty());
+
+            // Flag the faces between elements tagged 1 and 2 as self.interface_bdy_tag
+            if interface_tags.is_empty() {
+                warn!("All the elements are in the interface");
+            } else {
+                assert_eq!(interface_tags.len(), 1);
+                let tag = interface_tags.keys().next().unwrap();
+                local_mesh.mut_ftags().for_each(|t| {
+                    if *t == *tag {
+                        *t = self.interface_bdy_tag;
                     }
                 });
-                let mut ifc = ifc.lock().unwrap();
-                let (ids, _, _) = ifc.add(&local_mesh, |t| t == part_tag, |_t| true, Some(1e-12));
-                if self.debug {
-                    let fname = format!("level_{level}_part_{i_part}_ifc.vtu");
-                    ifc.write_vtk(&fname, None, None).unwrap();
+            }
+
+            if self.debug {
+                let fname = format!("level_{level}_part_{i_part}_remeshed.vtu");
+                local_mesh.write_vtk(&fname, None, None).unwrap();
+            }
+
+            // Update res
+            let mut res = res.lock().unwrap();
+            let (ids, _, _) = res.add(&local_mesh, |t| t == 1, |_| true, Some(1e-12));
+            if self.debug {
+                let fname = format!("level_{level}_part_{i_part}_res.vtu");
+                res.write_vtk(&fname, None, None).unwrap();
+            }
+            drop(res);
+            let mut res_m = res_m.lock().unwrap();
+            res_m.extend(ids.iter().map(|&i| local_m[i as usize]));
+            drop(res_m);
+
+            // Update ifc
+            let part_tag = 2 + i_part as Tag;
+            local_mesh.mut_etags().for_each(|t| {
+                if *t == 2 {
+                    *t = part_tag;
                 }
-                drop(ifc);
-                let mut ifc_m = ifc_m.lock().unwrap();
-                ifc_m.extend(ids.iter().map(|&i| local_m[i as usize]));
             });
+            let mut ifc = ifc.lock().unwrap();
+            let (ids, _, _) = ifc.add(&local_mesh, |t| t == part_tag, |_t| true, Some(1e-12));
+            if self.debug {
+                let fname = format!("level_{level}_part_{i_part}_ifc.vtu");
+                ifc.write_vtk(&fname, None, None).unwrap();
+            }
+            drop(ifc);
+            let mut ifc_m = ifc_m.lock().unwrap();
+            ifc_m.extend(ids.iter().map(|&i| local_m[i as usize]));
+
+            Ok::<(), PartitionRemeshingError>(())
+        };
+
+        // The order in which the partitions are merged depends on the scheduling
+        if params.deterministic {
+            self.seq_partitions()
+                .enumerate()
+                .try_for_each(remesh_partition)?;
+        } else {
+            self.par_partitions()
+                .enumerate()
+                .try_for_each(remesh_partition)?;
+        }
 
         let mut ifc = ifc.into_inner().unwrap();
         if self.debug {
@@ -462,6 +513,10 @@ impl<const D: usize, E: Elem> ParallelRemesher<D, E> {
             res.write_vtk(&fname, None, None).unwrap();
         }
 
+        if let Err(e) = res.remap_fields_from(&self.mesh) {
+            warn!("Unable to remap the mesh fields: {e}");
+        }
+
         info.info.n_verts_final = res.n_verts();
         info.info.time = now.elapsed().as_secs_f64() + self.partition_time;
 
@@ -472,16 +527,17 @@ impl<const D: usize, E: Elem> ParallelRemesher<D, E> {
 #[cfg(test)]
 mod tests {
 
+    use super::PartitionRemeshingError;
     use crate::{
-        geometry::NoGeometry,
+        geometry::{Geometry, NoGeometry},
         mesh::{
             test_meshes::{test_mesh_2d, test_mesh_3d},
-            PartitionType, Point,
+            PartitionType, Point, Topology,
         },
         metric::IsoMetric,
         remesher::RemesherParams,
         remesher::{ParallelRemesher, ParallelRemeshingParams},
-        Result,
+        Error, Result, TopoTag,
     };
 
     fn test_domain_decomposition_2d(debug: bool, ptype: PartitionType) -> Result<()> {
@@ -558,6 +614,77 @@ mod tests {
         test_domain_decomposition_2d(false, PartitionType::Hilbert(5))
     }
 
+    #[test]
+    fn test_dd_2d_deterministic() -> Result<()> {
+        let mut mesh = test_mesh_2d().split().split().split().split();
+        mesh.mut_etags().for_each(|t| *t = 1);
+        mesh.compute_topology();
+
+        let params = RemesherParams {
+            deterministic: true,
+            ..RemesherParams::default()
+        };
+
+        let mut res = Vec::new();
+        for _ in 0..2 {
+            let dd_params = ParallelRemeshingParams::new(2, 1, 0);
+            let dd = ParallelRemesher::new(mesh.clone(), PartitionType::Hilbert(4))?;
+            let m = (0..dd.n_verts())
+                .map(|i| {
+                    let p = dd.partitionned_mesh().vert(i);
+                    IsoMetric::<2>::from(0.01 + 0.05 * p[0])
+                })
+                .collect::<Vec<_>>();
+            let (mesh, _, _) = dd.remesh(&m, &NoGeometry(), params.clone(), dd_params)?;
+            res.push(
+                mesh.verts()
+                    .flat_map(|p| [p[0].to_bits(), p[1].to_bits()])
+                    .collect::<Vec<_>>(),
+            );
+        }
+        assert_eq!(res[0], res[1]);
+
+        Ok(())
+    }
+
+    /// Geometry that is never consistent with the topology
+    struct InvalidGeometry;
+
+    impl Geometry<2> for InvalidGeometry {
+        fn check(&self, _topo: &Topology) -> Result<()> {
+            Err(Error::from("invalid geometry"))
+        }
+
+        fn project(&self, _pt: &mut Point<2>, _tag: &TopoTag) -> f64 {
+            0.0
+        }
+
+        fn angle(&self, _pt: &Point<2>, _n: &Point<2>, _tag: &TopoTag) -> f64 {
+            0.0
+        }
+    }
+
+    #[test]
+    fn test_dd_2d_error() -> Result<()> {
+        let mut mesh = test_mesh_2d().split().split();
+        mesh.mut_etags().for_each(|t| *t = 1);
+        mesh.compute_topology();
+
+        let dd = ParallelRemesher::new(mesh, PartitionType::Hilbert(2))?;
+        let m = vec![IsoMetric::<2>::from(0.1); dd.n_verts() as usize];
+        let dd_params = ParallelRemeshingParams::new(2, 1, 0);
+        let res = dd.remesh(&m, &InvalidGeometry, RemesherParams::default(), dd_params);
+        let Err(e) = res else {
+            panic!("the remeshing should fail");
+        };
+        let e = e.downcast::<PartitionRemeshingError>().unwrap();
+        assert_eq!(e.level, 0);
+        assert!(e.partition < 2);
+        assert_eq!(e.message, "invalid geometry");
+
+        Ok(())
+    }
+
     #[cfg(feature = "metis")]
     #[test]
     #[should_panic]
@@ -0,0 +1,367 @@
+use crate::{
+    geometry::Geometry,
+    mesh::{Elem, Point, SimplexMesh},
+    metric::Metric,
+    remesher::{Remesher, RemesherParams},
+    spatialindex::PointIndex,
+    Error, Idx, Result, Tag,
+};
+use log::debug;
+use nalgebra::SMatrix;
+use rustc_hash::FxHashSet;
+
+/// Transformation between two periodic surfaces
+#[derive(Clone, Copy, Debug)]
+pub enum PeriodicTransform<const D: usize> {
+    /// Translation by a vector
+    Translation(Point<D>),
+    /// Rotation given by its center and its (orthogonal) matrix
+    Rotation(Point<D>, SMatrix<f64, D, D>),
+}
+
+impl<const D: usize> PeriodicTransform<D> {
+    /// Apply the transformation to a point
+    #[must_use]
+    pub fn apply(&self, p: &Point<D>) -> Point<D> {
+        match self {
+            Self::Translation(v) => p + v,
+            Self::Rotation(c, r) => c + r * (p - c),
+        }
+    }
+
+    /// Apply the inverse transformation to a point
+    #[must_use]
+    pub fn apply_inverse(&self, p: &Point<D>) -> Point<D> {
+        match self {
+            Self::Translation(v) => p - v,
+            Self::Rotation(c, r) => c + r.transpose() * (p - c),
+        }
+    }
+
+    /// Get the rotation matrix of the transformation (the identity for a translation)
+    #[must_use]
+    pub fn rotation(&self) -> SMatrix<f64, D, D> {
+        match self {
+            Self::Translation(_) => SMatrix::identity(),
+            Self::Rotation(_, r) => *r,
+        }
+    }
+}
+
+/// Pair of periodic surfaces: `transform` maps the faces tagged `master` to the faces tagged
+/// `slave`
+#[derive(Clone, Copy, Debug)]
+pub struct PeriodicPair<const D: usize> {
+    /// Tag of the master faces
+    pub master: Tag,
+    /// Tag of the slave faces
+    pub slave: Tag,
+    /// Transformation from the master to the slave faces
+    pub transform: PeriodicTransform<D>,
+}
+
+impl<const D: usize, E: Elem> SimplexMesh<D, E> {
+    /// Get the pairs of periodic vertices `(i, j)` where `i` is on the faces tagged
+    /// `pair.master` and `j` is the vertex on the faces tagged `pair.slave` located at the image
+    /// of `i` by `pair.transform` (up to `tol`). An error is returned if the vertices of the
+    /// two surfaces do not match
+    pub fn periodic_vertices(&self, pair: &PeriodicPair<D>, tol: f64) -> Result<Vec<(Idx, Idx)>> {
+        debug!(
+            "Find the periodic vertices between faces tagged {} and {}",
+            pair.master, pair.slave
+        );
+
+        let tagged_verts = |tag: Tag| {
+            let mut res = self
+                .faces()
+                .zip(self.ftags())
+                .filter(|(_, t)| *t == tag)
+                .flat_map(|(f, _)| f.iter().copied().collect::<Vec<_>>())
+                .collect::<Vec<_>>();
+            res.sort_unstable();
+            res.dedup();
+            res
+        };
+        let master = tagged_verts(pair.master);
+        let slave = tagged_verts(pair.slave);
+        if master.is_empty() || master.len() != slave.len() {
+            return Err(Error::from("the periodic surfaces do not match"));
+        }
+
+        let tree = self.compute_vert_tree();
+        master
+            .iter()
+            .map(|&i| {
+                let p = pair.transform.apply(&self.vert(i));
+                let (j, _) = tree.nearest_vert(&p);
+                if slave.binary_search(&j).is_ok() && (self.vert(j) - p).norm() < tol {
+                    Ok((i, j))
+                } else {
+                    Err(Error::from(&format!(
+                        "no periodic vertex found for vertex {i}"
+                    )))
+                }
+            })
+            .collect()
+    }
+
+    /// Remesh a mesh with the periodic surfaces given by `pair`, such that the vertices on the
+    /// faces tagged `pair.slave` are still the images of the vertices on the faces tagged
+    /// `pair.master` after adaptation. `tol` is used to match the periodic vertices.
+    ///
+    /// The remeshing is performed in two steps:
+    ///   - the layer of elements touching the slave surface is moved next to the master surface
+    ///     using the inverse transformation (the metric being rotated accordingly), so that the
+    ///     master surface is an internal surface that is adapted as such; the other faces of the
+    ///     layer and the interface with the rest of the mesh are frozen,
+    ///   - the layer is moved back and the mesh is remeshed again with both periodic surfaces
+    ///     frozen, to adapt the elements around the interface.
+    ///
+    /// Only one pair of periodic surfaces is supported, the boundaries of the periodic surfaces
+    /// are frozen and `params.frozen_verts` cannot be used. The mesh topology is required; it is
+    /// not computed for the new mesh
+    pub fn remesh_periodic<M: Metric<D>, G: Geometry<D>>(
+        &self,
+        m: &[M],
+        geom: &G,
+        params: RemesherParams,
+        pair: &PeriodicPair<D>,
+        tol: f64,
+    ) -> Result<(Self, Vec<M>)> {
+        debug!(
+            "Periodic remeshing between faces tagged {} and {}",
+            pair.master, pair.slave
+        );
+
+        let topo = self.get_topology()?.clone();
+        if m.len() != self.n_verts() as usize {
+            return Err(Error::from("invalid metric size"));
+        }
+        if !params.frozen_verts.is_empty() {
+            return Err(Error::from(
+                "frozen vertices are not supported for periodic remeshing",
+            ));
+        }
+
+        let periodic = self.periodic_vertices(pair, tol)?;
+        let n_verts = self.n_verts() as usize;
+        let mut on_master = vec![false; n_verts];
+        let mut on_slave = vec![false; n_verts];
+        for &(i, j) in &periodic {
+            on_master[i as usize] = true;
+            on_slave[j as usize] = true;
+        }
+
+        // Layer of elements touching the slave surface
+        let flag = self
+            .elems()
+            .map(|e| e.iter().any(|&i| on_slave[i as usize]))
+            .collect::<Vec<_>>();
+        if self
+            .elems()
+            .zip(flag.iter())
+            .any(|(e, &x)| x && e.iter().any(|&i| on_master[i as usize]))
+        {
+            return Err(Error::from(
+                "the mesh is too coarse between the periodic surfaces",
+            ));
+        }
+        debug!(
+            "{} elements in the periodic layer",
+            flag.iter().filter(|&&x| x).count()
+        );
+
+        // Tag the elements 1 outside and 2 in the layer, and freeze the interface faces
+        let etags = self.etags().collect::<Vec<_>>();
+        let offset = etags.iter().copied().max().unwrap_or(0);
+        let mut tmp = self.clone();
+        tmp.mut_etags()
+            .zip(flag.iter())
+            .for_each(|(t, &x)| *t = if x { 2 } else { 1 });
+        let (bdy_tags, ifc_tags) = tmp.add_boundary_faces();
+        if !bdy_tags.is_empty() {
+            return Err(Error::from("the boundary faces are not all present"));
+        }
+        let ifc_tags = ifc_tags.keys().copied().collect::<FxHashSet<_>>();
+
+        let f2e = tmp.get_face_to_elems()?;
+        let on_interface = tmp
+            .faces()
+            .map(|f| {
+                f2e.get(&f.sorted())
+                    .is_some_and(|v| v.len() == 2 && flag[v[0] as usize] != flag[v[1] as usize])
+            })
+            .collect::<Vec<_>>();
+        tmp.mut_ftags()
+            .zip(on_interface.iter())
+            .filter(|(_, &x)| x)
+            .for_each(|(t, _)| *t = -*t);
+
+        // Move the layer next to the master surface, with its element tags shifted by
+        // `offset` and its faces frozen
+        let rest = tmp.extract(|t| t == 1);
+        let mut mesh = rest.mesh;
+        mesh.mut_etags()
+            .zip(rest.parent_elem_ids.iter())
+            .for_each(|(t, &i)| *t = etags[i as usize]);
+        let mut mesh_m = rest
+            .parent_vert_ids
+            .iter()
+            .map(|&i| m[i as usize])
+            .collect::<Vec<_>>();
+
+        let layer = tmp.extract(|t| t == 2);
+        let mut layer_mesh = layer.mesh;
+        layer_mesh
+            .mut_etags()
+            .zip(layer.parent_elem_ids.iter())
+            .for_each(|(t, &i)| *t = etags[i as usize] + offset);
+        layer_mesh
+            .mut_verts()
+            .for_each(|p| *p = pair.transform.apply_inverse(p));
+        layer_mesh.remove_faces(|t| t == pair.slave);
+        layer_mesh
+            .mut_ftags()
+            .filter(|t| **t > 0)
+            .for_each(|t| *t = -*t);
+        let r_inv = pair.transform.rotation().transpose();
+        let layer_m = layer
+            .parent_vert_ids
+            .iter()
+            .map(|&i| m[i as usize].rotate(&r_inv))
+            .collect::<Vec<_>>();
+
+        let (ids, _, _) = mesh.add(&layer_mesh, |_| true, |_| true, Some(tol));
+        if ids.len() + periodic.len() != layer_mesh.n_verts() as usize {
+            return Err(Error::from("unable to merge the periodic surfaces"));
+        }
+        mesh_m.extend(ids.iter().map(|&i| layer_m[i as usize]));
+
+        // Adapt the master surface
+        mesh.compute_topology();
+        let mut remesher = Remesher::new(&mesh, &mesh_m, geom)?;
+        remesher.remesh(params.clone(), geom)?;
+        let mesh = remesher.to_mesh(false);
+        let mesh_m = remesher.metrics();
+
+        // Move the layer back next to the slave surface
+        let rest = mesh.extract(|t| t <= offset);
+        let mut res = rest.mesh;
+        let mut res_m = rest
+            .parent_vert_ids
+            .iter()
+            .map(|&i| mesh_m[i as usize])
+            .collect::<Vec<_>>();
+
+        let layer = mesh.extract(|t| t > offset);
+        let mut layer_mesh = layer.mesh;
+        layer_mesh.mut_etags().for_each(|t| *t -= offset);
+        layer_mesh
+            .mut_verts()
+            .for_each(|p| *p = pair.transform.apply(p));
+        layer_mesh.mut_ftags().for_each(|t| {
+            if *t == pair.master {
+                *t = pair.slave;
+            } else if *t < 0 && !ifc_tags.contains(&-*t) {
+                *t = -*t;
+            }
+        });
+        let r = pair.transform.rotation();
+
+        let (ids, _, _) = res.add(
+            &layer_mesh,
+            |_| true,
+            |t| !ifc_tags.contains(&-t),
+            Some(tol),
+        );
+        res_m.extend(
+            ids.iter()
+                .map(|&i| mesh_m[layer.parent_vert_ids[i as usize] as usize].rotate(&r)),
+        );
+        res.remove_faces(|t| ifc_tags.contains(&-t));
+
+        let (bdy_tags, _) = res.add_boundary_faces();
+        if !bdy_tags.is_empty() {
+            return Err(Error::from("the boundary faces are not all present"));
+        }
+
+        // Adapt the elements around the interface
+        res.compute_topology_from(topo);
+        let mut params = params;
+        params.frozen_face_tags.extend([pair.master, pair.slave]);
+        let mut remesher = Remesher::new(&res, &res_m, geom)?;
+        remesher.remesh(params, geom)?;
+
+        Ok((remesher.to_mesh(false), remesher.metrics()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{PeriodicPair, PeriodicTransform};
+    use crate::{
+        geometry::NoGeometry,
+        mesh::{test_meshes::test_mesh_2d, Point},
+        metric::IsoMetric,
+        remesher::RemesherParams,
+        Result,
+    };
+    use nalgebra::SMatrix;
+
+    #[test]
+    fn test_transform() {
+        let (s, c) = f64::sin_cos(0.7);
+        let t = PeriodicTransform::Rotation(
+            Point::<2>::new(1.0, 2.0),
+            SMatrix::<f64, 2, 2>::new(c, -s, s, c),
+        );
+        let c = Point::<2>::new(1.0, 2.0);
+        let p = Point::<2>::new(0.3, -0.4);
+        let q = t.apply(&p);
+        assert!(((q - c).norm() - (p - c).norm()).abs() < 1e-12);
+        assert!((t.apply_inverse(&q) - p).norm() < 1e-12);
+
+        let t = PeriodicTransform::Translation(Point::<2>::new(1.0, 0.0));
+        assert!((t.apply(&p) - Point::<2>::new(1.3, -0.4)).norm() < 1e-12);
+        assert!((t.apply_inverse(&t.apply(&p)) - p).norm() < 1e-12);
+    }
+
+    #[test]
+    fn test_periodic_2d() -> Result<()> {
+        let mut mesh = test_mesh_2d().split().split().split();
+        mesh.compute_topology();
+
+        // x = 0 is mapped to x = 1
+        let pair = PeriodicPair {
+            master: 4,
+            slave: 2,
+            transform: PeriodicTransform::Translation(Point::<2>::new(1.0, 0.0)),
+        };
+        assert_eq!(mesh.periodic_vertices(&pair, 1e-8)?.len(), 9);
+
+        let m = mesh
+            .verts()
+            .map(|p| IsoMetric::<2>::from(0.02 + 0.1 * p[1]))
+            .collect::<Vec<_>>();
+        let (mut new_mesh, new_m) =
+            mesh.remesh_periodic(&m, &NoGeometry(), RemesherParams::default(), &pair, 1e-8)?;
+        assert_eq!(new_m.len(), new_mesh.n_verts() as usize);
+        assert!(new_mesh.n_verts() > 2 * mesh.n_verts());
+
+        let res = new_mesh.periodic_vertices(&pair, 1e-8)?;
+        assert!(res.len() > 9);
+
+        new_mesh.compute_face_to_elems();
+        new_mesh.check()?;
+        assert!((new_mesh.vol() - 1.0).abs() < 1e-12);
+
+        let pair = PeriodicPair {
+            master: 4,
+            slave: 2,
+            transform: PeriodicTransform::Translation(Point::<2>::new(0.9, 0.0)),
+        };
+        assert!(mesh.periodic_vertices(&pair, 1e-8).is_err());
+
+        Ok(())
+    }
+}
@@ -5,16 +5,17 @@ use super::stats::{
 use crate::{
     geometry::Geometry,
     max_iter,
-    mesh::{get_face_to_elem, AsSliceF64, Elem, GElem, Point, SimplexMesh, Topology},
+    mesh::{hash_map_bytes, AsSliceF64, Elem, GElem, Point, SimplexMesh, Topology},
     metric::Metric,
     min_iter, Dim, Error, Idx, Result, Tag, TopoTag,
 };
-use log::{debug, trace};
+use log::{debug, trace, warn};
 #[cfg(feature = "nlopt")]
 use nlopt::{Algorithm, Nlopt, Target};
-use rustc_hash::FxHashMap;
+use rustc_hash::{FxHashMap, FxHashSet};
+use serde::Serialize;
 use sorted_vec::SortedVec;
-use std::{cmp::Ordering, fs::File, io::Write, time::Instant};
+use std::{cmp::Ordering, fmt, fs::File, io::Write, ops::ControlFlow, time::Instant};
 
 // /// Get edged indices such that they are sorted by increasing tag dimension and then by
 // /// increasing edge length
@@ -76,6 +77,9 @@ pub struct Remesher<const D: usize, E: Elem, M: Metric<D>> {
     elems: FxHashMap<Idx, ElemInfo<E>>,
     /// Edges
     edges: FxHashMap<[Idx; 2], i16>,
+    /// Face-to-element connectivity, with sorted faces (the second element is `Idx::MAX` for
+    /// the boundary faces)
+    faces: FxHashMap<E::Face, [Idx; 2]>,
     /// Tagged faces
     tagged_faces: FxHashMap<E::Face, Tag>,
     /// Next vertex Id
@@ -84,6 +88,28 @@ pub struct Remesher<const D: usize, E: Elem, M: Metric<D>> {
     next_elem: Idx,
     /// Statistics
     stats: Vec<StepStats>,
+    /// Copy of the input mesh with its fields, if any, to remap them in `to_mesh`
+    fields_src: Option<SimplexMesh<D, E>>,
+}
+
+/// Remeshing pass
+#[derive(Clone, Copy)]
+enum Pass {
+    Collapse,
+    Split(f64),
+    Swap(f64),
+    Smooth,
+}
+
+impl Pass {
+    /// Check if the pass is performed in remeshing mode `mode`
+    const fn is_allowed(self, mode: RemeshingMode) -> bool {
+        match mode {
+            RemeshingMode::Full => true,
+            RemeshingMode::CoarsenOnly => matches!(self, Self::Collapse),
+            RemeshingMode::RefineOnly => matches!(self, Self::Split(_)),
+        }
+    }
 }
 
 enum TrySwapResult {
@@ -115,20 +141,46 @@ enum TrySwapResult {
 /// \tilde v_i = (1 - \omega) v_i + \omega \frac{\sum_{j \in N(i)} ||v_j - v_i||_M v_j}{\sum_{j \in N(i)} ||v_j - v_i||_M}
 /// ```
 /// with $`\omega = {1, 1/2, 1/4, ...}`$
-#[derive(Clone, Copy)]
+///
+/// For `Optimization` (and `NLOpt`), the location of interior vertices is chosen to maximize the
+/// quality of the worst element of the vertex ball, using a compass search in the metric space
+/// (the Laplacian smoothing is used for the boundary vertices).
+///
+/// For all methods, the new location is accepted only if the minimum quality in the vertex ball
+/// does not decrease, and boundary vertices are projected onto the geometry
+#[derive(Clone, Copy, Debug)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
 pub enum SmoothingType {
     Laplacian,
     Avro,
     #[cfg(feature = "nlopt")]
     NLOpt,
     Laplacian2,
+    Optimization,
+}
+
+/// Remeshing modes
+///  - `Full`: collapses, splits, swaps and smoothing are used
+///  - `CoarsenOnly`: only collapses are used, so that the vertices of the new mesh are a subset
+///    of the vertices of the input mesh (e.g. to build coarse multigrid levels)
+///  - `RefineOnly`: only splits are used, so that the input mesh is nested in the new mesh
+///    (except on curved boundaries, where the new vertices are projected onto the geometry)
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
+pub enum RemeshingMode {
+    Full,
+    CoarsenOnly,
+    RefineOnly,
 }
 
 /// Remesher parameters
 #[derive(Clone)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
 pub struct RemesherParams {
     /// Number of collapse - split - swap - smooth loops
     pub num_iter: u32,
+    /// Remeshing mode
+    pub mode: RemeshingMode,
     /// Perform a first loop targetting only the longest edges
     pub two_steps: bool,
     /// Max. number of loops through the mesh edges during the split step
@@ -171,6 +223,21 @@ pub struct RemesherParams {
     pub smooth_keep_local_minima: bool,
     /// Max angle between the normals of the new faces and the geometry (in degrees)
     pub max_angle: f64,
+    /// If set, max. distance between the boundary edges and the geometry: the metric sizes at
+    /// the boundary vertices are bounded using the geometry curvature (see
+    /// `Remesher::bound_sizes_by_curvature`)
+    pub chordal_error: Option<f64>,
+    /// Frozen vertices (indices in the mesh used to create the remesher): they are not
+    /// collapsed nor smoothed, and the edges between two frozen vertices are not split nor
+    /// swapped
+    pub frozen_verts: Vec<Idx>,
+    /// Face tags whose vertices are frozen, so that the faces are kept unchanged
+    pub frozen_face_tags: Vec<Tag>,
+    /// Element tags whose vertices are frozen, so that the elements are kept unchanged
+    pub frozen_elem_tags: Vec<Tag>,
+    /// Reproducible remeshing: the edges and vertices are processed in a fixed order and the
+    /// partitions are remeshed sequentially by `ParallelRemesher` (slower)
+    pub deterministic: bool,
     /// Debug mode
     pub debug: bool,
 }
@@ -179,6 +246,7 @@ impl Default for RemesherParams {
     fn default() -> Self {
         Self {
             num_iter: 4,
+            mode: RemeshingMode::Full,
             two_steps: true,
             split_max_iter: 1,
             split_min_l_rel: 1.0,
@@ -200,11 +268,70 @@ impl Default for RemesherParams {
             smooth_relax: vec![0.5, 0.25, 0.125],
             smooth_keep_local_minima: false,
             max_angle: 20.0,
+            chordal_error: None,
+            frozen_verts: Vec::new(),
+            frozen_face_tags: Vec::new(),
+            frozen_elem_tags: Vec::new(),
+            deterministic: false,
             debug: false,
         }
     }
 }
 
+/// Memory used by the data structures of a `Remesher`, in bytes (approximate for the hash
+/// maps)
+#[derive(Clone, Debug, Default, Serialize)]
+pub struct RemesherMemoryUsage {
+    /// Vertex coordinates, tags and metrics
+    pub verts: usize,
+    /// Vertex-to-element connectivity
+    pub vertex_to_elems: usize,
+    /// Element connectivity, tags and qualities
+    pub elems: usize,
+    /// Edges
+    pub edges: usize,
+    /// Face-to-element connectivity
+    pub face_to_elems: usize,
+    /// Tagged faces
+    pub tagged_faces: usize,
+    /// Copy of the input mesh used to remap its fields
+    pub fields: usize,
+}
+
+impl RemesherMemoryUsage {
+    /// Get the total memory used, in bytes
+    #[must_use]
+    pub const fn total(&self) -> usize {
+        self.verts
+            + self.vertex_to_elems
+            + self.elems
+            + self.edges
+            + self.face_to_elems
+            + self.tagged_faces
+            + self.fields
+    }
+}
+
+impl fmt::Display for RemesherMemoryUsage {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "total: {:.1} MB", self.total() as f64 / 1e6)?;
+        for (name, bytes) in [
+            ("vertices", self.verts),
+            ("vertex_to_elems", self.vertex_to_elems),
+            ("elements", self.elems),
+            ("edges", self.edges),
+            ("face_to_elems", self.face_to_elems),
+            ("tagged_faces", self.tagged_faces),
+            ("fields", self.fields),
+        ] {
+            if bytes > 0 {
+                writeln!(f, "  {name}: {:.1} MB", bytes as f64 / 1e6)?;
+            }
+        }
+        Ok(())
+    }
+}
+
 impl<const D: usize, E: Elem, M: Metric<D>> Remesher<D, E, M> {
     /// Initialize the remesher
     pub fn new<G: Geometry<D>>(mesh: &SimplexMesh<D, E>, m: &[M], geom: &G) -> Result<Self> {
@@ -226,6 +353,11 @@ impl<const D: usize, E: Elem, M: Metric<D>> Remesher<D, E, M> {
             E::NAME
         );
         assert_eq!(metric.len(), mesh.n_verts() as usize);
+        if E::N_VERTS > E::DIM + 1 {
+            return Err(Error::from(
+                "the remesher only supports first order elements",
+            ));
+        }
 
         // Get the topology
         let topo = mesh.get_topology()?;
@@ -242,11 +374,26 @@ impl<const D: usize, E: Elem, M: Metric<D>> Remesher<D, E, M> {
             elems,
             tagged_faces: FxHashMap::default(),
             edges: FxHashMap::default(),
+            faces: FxHashMap::default(),
             next_vert: 0,
             next_elem: 0,
             stats: Vec::new(),
+            fields_src: None,
         };
 
+        if !mesh.vertex_fields().is_empty() || !mesh.elem_fields().is_empty() {
+            let mut src = SimplexMesh::new(
+                mesh.verts().collect(),
+                mesh.elems().collect(),
+                mesh.etags().collect(),
+                Vec::new(),
+                Vec::new(),
+            );
+            *src.vertex_fields_mut() = mesh.vertex_fields().clone();
+            *src.elem_fields_mut() = mesh.elem_fields().clone();
+            res.fields_src = Some(src);
+        }
+
         // Insert the vertices
         assert_eq!(mesh.n_verts() as usize, vtag.len());
         for ((p, tag), m) in mesh.verts().zip(vtag.iter()).zip(metric) {
@@ -311,17 +458,11 @@ impl<const D: usize, E: Elem, M: Metric<D>> Remesher<D, E, M> {
         for i_face in 0..E::N_FACES {
             let f = e.el.face(i_face);
 
-            // filter the other elements containing the face
+            // the other element containing the face
             let iels = self
-                .verts
-                .get(&f[0])
-                .unwrap()
-                .els
+                .face_elements(&f)
                 .iter()
-                .filter(|i| {
-                    let other = self.elems.get(i).unwrap().el;
-                    **i != i_elem && f.iter().all(|j| other.contains_vertex(*j))
-                })
+                .filter(|&&i| i != i_elem)
                 .collect::<Vec<_>>();
 
             let ftag = self.face_tag(&f);
@@ -352,10 +493,65 @@ impl<const D: usize, E: Elem, M: Metric<D>> Remesher<D, E, M> {
         Ok(())
     }
 
+    /// Check that the vertex-to-element, edge and face-to-element connectivities, that are
+    /// updated incrementally, are identical to the ones rebuilt from the elements
+    fn check_connectivity(&self) -> Result<()> {
+        let mut v2e = FxHashMap::<Idx, Vec<Idx>>::default();
+        let mut edges = FxHashMap::<[Idx; 2], i16>::default();
+        let mut faces = FxHashMap::<E::Face, Vec<Idx>>::default();
+        for (&i_elem, e) in &self.elems {
+            for &i in e.el.iter() {
+                v2e.entry(i).or_default().push(i_elem);
+            }
+            for i_edge in 0..E::N_EDGES {
+                let mut edg = e.el.edge(i_edge);
+                edg.sort_unstable();
+                *edges.entry(edg).or_default() += 1;
+            }
+            for i_face in 0..E::N_FACES {
+                faces
+                    .entry(e.el.face(i_face).sorted())
+                    .or_default()
+                    .push(i_elem);
+            }
+        }
+
+        for (i, v) in &self.verts {
+            let mut els = v2e.remove(i).unwrap_or_default();
+            els.sort_unstable();
+            if !v.els.iter().eq(els.iter()) {
+                return Err(Error::from("Invalid vertex to element connectivity"));
+            }
+        }
+        if !v2e.is_empty() {
+            return Err(Error::from("Invalid vertex to element (missing vertex)"));
+        }
+
+        if edges != self.edges {
+            return Err(Error::from("Invalid edge connectivity"));
+        }
+
+        if faces.len() != self.faces.len() {
+            return Err(Error::from("Invalid face to element connectivity"));
+        }
+        for (f, mut els) in faces {
+            els.sort_unstable();
+            let mut other = self.face_elements(&f).to_vec();
+            other.sort_unstable();
+            if els != other {
+                return Err(Error::from("Invalid face to element connectivity"));
+            }
+        }
+
+        Ok(())
+    }
+
     /// Check that the remesher holds a valid mesh
     pub fn check(&self) -> Result<()> {
         debug!("Check the consistency of the remesher data");
 
+        self.check_connectivity()?;
+
         for (&i_elem, e) in &self.elems {
             // Is element-to-vertex and vertex-to-element info consistent?
             self.check_vert_to_elems(i_elem, &e.el)?;
@@ -411,7 +607,9 @@ impl<const D: usize, E: Elem, M: Metric<D>> Remesher<D, E, M> {
         Ok(())
     }
 
-    /// Create a `SimplexMesh`
+    /// Create a `SimplexMesh`. The fields attached to the input mesh are remapped onto the
+    /// new mesh (see `SimplexMesh::remap_fields_from`); they are dropped with a warning if
+    /// this fails
     #[must_use]
     pub fn to_mesh(&self, only_bdy_faces: bool) -> SimplexMesh<D, E> {
         debug!("Build a mesh");
@@ -425,24 +623,26 @@ impl<const D: usize, E: Elem, M: Metric<D>> Remesher<D, E, M> {
 
         let verts = self.verts.values().map(|v| v.vx).collect();
 
+        let mut eidx = FxHashMap::default();
+        eidx.reserve(self.n_elems() as usize);
         let mut elems = Vec::with_capacity(self.n_elems() as usize);
         let mut etags = Vec::with_capacity(self.n_elems() as usize);
-        for e in self.elems.values() {
+        for (i_elem, (k, e)) in self.elems.iter().enumerate() {
+            eidx.insert(*k, i_elem as Idx);
             elems.push(E::from_iter(e.el.iter().map(|&i| *vidx.get(&i).unwrap())));
             etags.push(e.tag);
         }
 
-        let f2e = get_face_to_elem(elems.iter().copied());
         let mut faces = Vec::new();
         let mut ftags = Vec::new();
 
-        for (face, &ftag) in &self.tagged_faces {
-            let face = E::Face::from_iter(face.iter().map(|&i| *vidx.get(&i).unwrap()));
+        for (global_face, &ftag) in &self.tagged_faces {
+            let face = E::Face::from_iter(global_face.iter().map(|&i| *vidx.get(&i).unwrap()));
             let face = face.sorted();
-            let iels = f2e.get(&face).unwrap();
+            let iels = self.face_elements(global_face);
             if iels.len() == 1 {
                 // Orient the face outwards
-                let elem = elems[iels[0] as usize];
+                let elem = elems[eidx[&iels[0]] as usize];
                 let mut ok = false;
                 for i_face in 0..E::N_FACES {
                     let mut f = elem.face(i_face);
@@ -463,7 +663,32 @@ impl<const D: usize, E: Elem, M: Metric<D>> Remesher<D, E, M> {
             }
         }
 
-        SimplexMesh::<D, E>::new(verts, elems, etags, faces, ftags)
+        let mut res = SimplexMesh::<D, E>::new(verts, elems, etags, faces, ftags);
+
+        // use the connectivities maintained during the remeshing instead of rebuilding them
+        let eidx = &eidx;
+        res.set_vertex_to_elems(
+            self.verts
+                .values()
+                .map(|v| v.els.iter().map(move |i| eidx[i])),
+        );
+        res.set_face_to_elems(self.faces.iter().map(|(f, els)| {
+            let face = E::Face::from_iter(f.iter().map(|i| vidx[i])).sorted();
+            let (i0, i1) = (eidx[&els[0]], eidx.get(&els[1]).copied());
+            let (i0, i1) = match i1 {
+                Some(i1) if i1 < i0 => (i1, Some(i0)),
+                _ => (i0, i1),
+            };
+            (face, std::iter::once(i0).chain(i1))
+        }));
+
+        if let Some(src) = &self.fields_src {
+            if let Err(e) = res.remap_fields_from(src) {
+                warn!("Unable to remap the mesh fields: {e}");
+            }
+        }
+
+        res
     }
 
     /// Insert a new vertex, and get its index
@@ -512,6 +737,38 @@ impl<const D: usize, E: Elem, M: Metric<D>> Remesher<D, E, M> {
         self.verts.len() as Idx
     }
 
+    /// Get the memory used by the remesher, e.g. to check the peak memory when adapting large
+    /// meshes
+    #[must_use]
+    pub fn memory_usage(&self) -> RemesherMemoryUsage {
+        RemesherMemoryUsage {
+            verts: hash_map_bytes::<Idx, VtxInfo<D, M>>(self.verts.capacity()),
+            vertex_to_elems: self
+                .verts
+                .values()
+                .map(|v| v.els.capacity() * std::mem::size_of::<Idx>())
+                .sum(),
+            elems: hash_map_bytes::<Idx, ElemInfo<E>>(self.elems.capacity()),
+            edges: hash_map_bytes::<[Idx; 2], i16>(self.edges.capacity()),
+            face_to_elems: hash_map_bytes::<E::Face, [Idx; 2]>(self.faces.capacity()),
+            tagged_faces: hash_map_bytes::<E::Face, Tag>(self.tagged_faces.capacity()),
+            fields: self
+                .fields_src
+                .as_ref()
+                .map_or(0, |m| m.memory_usage().total()),
+        }
+    }
+
+    /// Return indices of the elements (1 or 2) that contain a face
+    #[must_use]
+    pub fn face_elements(&self, face: &E::Face) -> &[Idx] {
+        match self.faces.get(&face.sorted()) {
+            Some(els) if els[1] == Idx::MAX => &els[..1],
+            Some(els) => &els[..],
+            None => &[],
+        }
+    }
+
     /// Get the number of elements that contain an edge
     #[must_use]
     pub fn elem_count(&self, edg: [Idx; 2]) -> i16 {
@@ -550,6 +807,21 @@ impl<const D: usize, E: Elem, M: Metric<D>> Remesher<D, E, M> {
                 self.edges.insert(edg, 1);
             }
         }
+
+        // update the faces
+        for i_face in 0..E::N_FACES {
+            let els = self
+                .faces
+                .entry(el.face(i_face).sorted())
+                .or_insert([Idx::MAX; 2]);
+            if els[0] == Idx::MAX {
+                els[0] = self.next_elem;
+            } else if els[1] == Idx::MAX {
+                els[1] = self.next_elem;
+            } else {
+                return Err(Error::from("A face belongs to more than 2 elements"));
+            }
+        }
         self.next_elem += 1;
         Ok(())
     }
@@ -578,6 +850,19 @@ impl<const D: usize, E: Elem, M: Metric<D>> Remesher<D, E, M> {
                 *e -= 1;
             }
         }
+
+        // update the faces
+        for i_face in 0..E::N_FACES {
+            let f = el.face(i_face).sorted();
+            let els = self.faces.get_mut(&f).unwrap();
+            if els[0] == idx {
+                els[0] = els[1];
+            }
+            els[1] = Idx::MAX;
+            if els[0] == Idx::MAX {
+                self.faces.remove(&f);
+            }
+        }
         self.elems.remove(&idx);
         Ok(())
     }
@@ -641,7 +926,7 @@ impl<const D: usize, E: Elem, M: Metric<D>> Remesher<D, E, M> {
     #[must_use]
     pub fn complexity(&self) -> f64 {
         let mut c = 0.0;
-        let weights = vec![1. / f64::from(E::N_VERTS); E::N_VERTS as usize];
+        let weights = vec![1. / E::N_VERTS as f64; E::N_VERTS as usize];
 
         for e in self.elems.values() {
             let ge = self.gelem(&e.el);
@@ -684,13 +969,6 @@ impl<const D: usize, E: Elem, M: Metric<D>> Remesher<D, E, M> {
         self.edges.keys().map(|k| self.scaled_edge_length(*k))
     }
 
-    /// Get an iterator over edge topo dimension and length
-    fn dims_and_lengths_iter(&self) -> impl Iterator<Item = (Dim, f64)> + '_ {
-        self.edges
-            .keys()
-            .map(|k| self.dim_and_scaled_edge_length(*k))
-    }
-
     /// Get the edge lengths
     #[must_use]
     pub fn lengths(&self) -> Vec<f64> {
@@ -714,6 +992,40 @@ impl<const D: usize, E: Elem, M: Metric<D>> Remesher<D, E, M> {
         self.qualities_iter().collect()
     }
 
+    /// Get the edges, sorted if `params.deterministic` so that the order does not depend on
+    /// the hash map
+    fn sorted_edges(&self, params: &RemesherParams) -> Vec<[Idx; 2]> {
+        let mut res = self.edges.keys().copied().collect::<Vec<_>>();
+        if params.deterministic {
+            res.sort_unstable();
+        }
+        res
+    }
+
+    /// Get the frozen vertices given by `params.frozen_verts`, `params.frozen_face_tags` and
+    /// `params.frozen_elem_tags`
+    fn frozen_verts(&self, params: &RemesherParams) -> FxHashSet<Idx> {
+        let mut res = params
+            .frozen_verts
+            .iter()
+            .copied()
+            .filter(|i| self.verts.contains_key(i))
+            .collect::<FxHashSet<_>>();
+        if !params.frozen_face_tags.is_empty() {
+            self.tagged_faces
+                .iter()
+                .filter(|(_, t)| params.frozen_face_tags.contains(t))
+                .for_each(|(f, _)| res.extend(f.iter().copied()));
+        }
+        if !params.frozen_elem_tags.is_empty() {
+            self.elems
+                .values()
+                .filter(|e| params.frozen_elem_tags.contains(&e.tag))
+                .for_each(|e| res.extend(e.el.iter().copied()));
+        }
+        res
+    }
+
     /// Loop over the edges and split them if
     /// - their length is larger that `l_0`
     /// - no edge smaller than
@@ -735,16 +1047,17 @@ impl<const D: usize, E: Elem, M: Metric<D>> Remesher<D, E, M> {
         let q_min = params.split_min_q_abs;
         debug!("min. allowed quality: {:.2}", q_min);
 
+        let frozen = self.frozen_verts(params);
+
         let mut n_iter = 0;
         let mut cavity = Cavity::new();
         loop {
             n_iter += 1;
 
-            let mut edges = Vec::with_capacity(self.edges.len());
-            edges.extend(self.edges.keys().copied());
+            let edges = self.sorted_edges(params);
 
-            let mut dims_and_lengths = Vec::with_capacity(self.edges.len());
-            dims_and_lengths.extend(self.dims_and_lengths_iter());
+            let mut dims_and_lengths = Vec::with_capacity(edges.len());
+            dims_and_lengths.extend(edges.iter().map(|&e| self.dim_and_scaled_edge_length(e)));
 
             // loop through the edges by increasing dimension and decreasing length
             let indices = argsort_edges_decreasing_length(&dims_and_lengths);
@@ -755,6 +1068,9 @@ impl<const D: usize, E: Elem, M: Metric<D>> Remesher<D, E, M> {
                 let edg = edges[i_edge];
                 let length = dims_and_lengths[i_edge].1;
                 if length > l_0 {
+                    if edg.iter().all(|i| frozen.contains(i)) {
+                        continue;
+                    }
                     trace!("Try to split edge {:?}, l = {}", edg, length);
                     cavity.init_from_edge(edg, self);
                     // TODO: move to Cavity?
@@ -982,17 +1298,22 @@ impl<const D: usize, E: Elem, M: Metric<D>> Remesher<D, E, M> {
     ) -> Result<u32> {
         debug!("Swap edges: target quality = {}", q_target);
 
+        let frozen = self.frozen_verts(params);
+
         let mut n_iter = 0;
         let mut cavity = Cavity::new();
         loop {
             n_iter += 1;
-            let mut edges = Vec::with_capacity(self.edges.len());
-            edges.extend(self.edges.keys().copied());
+            let edges = self.sorted_edges(params);
 
             let mut n_swaps = 0;
             let mut n_fails = 0;
             let mut n_ok = 0;
             for edg in edges {
+                if edg.iter().all(|i| frozen.contains(i)) {
+                    n_ok += 1;
+                    continue;
+                }
                 let res =
                     self.try_swap(edg, q_target, params, params.max_angle, &mut cavity, geom)?;
                 match res {
@@ -1036,13 +1357,18 @@ impl<const D: usize, E: Elem, M: Metric<D>> Remesher<D, E, M> {
         let q_min = params.collapse_min_q_abs;
         debug!("min. allowed quality: {:.2}", q_min);
 
+        let frozen = self.frozen_verts(params);
+
         let mut n_iter = 0;
         let mut cavity = Cavity::new();
         loop {
             n_iter += 1;
 
-            let edges: Vec<_> = self.edges.keys().copied().collect();
-            let dims_and_lengths: Vec<_> = self.dims_and_lengths_iter().collect();
+            let edges = self.sorted_edges(params);
+            let dims_and_lengths: Vec<_> = edges
+                .iter()
+                .map(|&e| self.dim_and_scaled_edge_length(e))
+                .collect();
             let indices = argsort_edges_increasing_length(&dims_and_lengths);
 
             let mut n_collapses = 0;
@@ -1084,6 +1410,15 @@ impl<const D: usize, E: Elem, M: Metric<D>> Remesher<D, E, M> {
                             continue;
                         }
                     }
+                    if frozen.contains(&i0) {
+                        if !frozen.contains(&i1) && topo_1 == tag {
+                            trace!("Swap vertices");
+                            std::mem::swap(&mut i1, &mut i0);
+                        } else {
+                            trace!("Cannot collapse, frozen vertex");
+                            continue;
+                        }
+                    }
                     cavity.init_from_vertex(i0, self);
                     let local_i1 = cavity.get_local_index(i1).unwrap();
 
@@ -1263,6 +1598,65 @@ impl<const D: usize, E: Elem, M: Metric<D>> Remesher<D, E, M> {
         p0_new
     }
 
+    fn smooth_optimization(cavity: &Cavity<D, E, M>, neighbors: &[Idx]) -> Point<D> {
+        let Seed::Vertex(i0) = cavity.seed else {
+            unreachable!()
+        };
+        let (p0, t0, m0) = cavity.vert(i0);
+        if t0.0 != E::DIM as Dim {
+            return Self::smooth_laplacian(cavity, neighbors);
+        }
+
+        let gfaces: Vec<_> = cavity.faces().map(|(f, _)| cavity.gface(&f)).collect();
+        let q_min = |p: &Point<D>| {
+            min_iter(gfaces.iter().map(|gf| {
+                let ge = E::Geom::from_vert_and_face(p, m0, gf);
+                if ge.vol() > 0.0 {
+                    ge.quality()
+                } else {
+                    -1.0
+                }
+            }))
+        };
+
+        let mut p_best = *p0;
+        let mut q_best = q_min(p0);
+        let p_lap = Self::smooth_laplacian(cavity, neighbors);
+        let q_lap = q_min(&p_lap);
+        if q_lap > q_best {
+            p_best = p_lap;
+            q_best = q_lap;
+        }
+
+        let mut step = 0.25
+            * neighbors
+                .iter()
+                .map(|&i1| (cavity.points[i1 as usize] - p0).norm())
+                .sum::<f64>()
+            / neighbors.len() as f64;
+        for _ in 0..20 {
+            let mut improved = false;
+            for dir in 0..D {
+                for sgn in [-1.0, 1.0] {
+                    let mut p = p_best;
+                    p[dir] += sgn * step;
+                    let q = q_min(&p);
+                    if q > q_best {
+                        p_best = p;
+                        q_best = q;
+                        improved = true;
+                    }
+                }
+            }
+            if !improved {
+                step *= 0.5;
+            }
+        }
+        trace!("Optimization: q_min = {q_best}");
+
+        p_best
+    }
+
     #[cfg(feature = "nlopt")]
     fn smooth_nlopt(cavity: &Cavity<D, E, M>, neighbors: &[Idx]) -> Point<D> {
         let Seed::Vertex(i0) = cavity.seed else {
@@ -1369,6 +1763,7 @@ impl<const D: usize, E: Elem, M: Metric<D>> Remesher<D, E, M> {
                 SmoothingType::Laplacian => Self::smooth_laplacian(cavity, &neighbors),
                 SmoothingType::Laplacian2 => Self::smooth_laplacian_2(cavity, &neighbors),
                 SmoothingType::Avro => Self::smooth_avro(cavity, &neighbors),
+                SmoothingType::Optimization => Self::smooth_optimization(cavity, &neighbors),
                 #[cfg(feature = "nlopt")]
                 SmoothingType::NLOpt => Self::smooth_nlopt(cavity, &neighbors),
             };
@@ -1452,7 +1847,16 @@ impl<const D: usize, E: Elem, M: Metric<D>> Remesher<D, E, M> {
         // We modify the vertices while iterating over them so we must copy
         // the keys. Apart from going unsafe the only way to avoid this would be
         // to have one RefCell for each VtxInfo but copying self.verts is cheaper.
-        let verts = self.verts.keys().copied().collect::<Vec<_>>();
+        let frozen = self.frozen_verts(params);
+        let mut verts = self
+            .verts
+            .keys()
+            .copied()
+            .filter(|i| !frozen.contains(i))
+            .collect::<Vec<_>>();
+        if params.deterministic {
+            verts.sort_unstable();
+        }
 
         let mut cavity = Cavity::new();
         for iter in 0..params.smooth_iter {
@@ -1469,11 +1873,194 @@ impl<const D: usize, E: Elem, M: Metric<D>> Remesher<D, E, M> {
         }
     }
 
-    /// Perform a remeshing iteration
+    /// Perform `n_iter` smoothing steps using `method`, with the default values of the other
+    /// smoothing parameters (see `RemesherParams`)
+    pub fn smooth_vertices<G: Geometry<D>>(
+        &mut self,
+        method: SmoothingType,
+        n_iter: u32,
+        geom: &G,
+    ) {
+        let params = RemesherParams {
+            smooth_type: method,
+            smooth_iter: n_iter,
+            ..RemesherParams::default()
+        };
+        self.smooth(&params, geom);
+    }
+
+    /// Rebuild `cavity`, that must have been built from the current state of the remesher
+    /// using `Cavity::build`, from a point `p` with metric `m` (see `Cavity::fill`) if all the
+    /// new elements have a quality larger than `q_min`. The point is not projected onto the
+    /// geometry and the tagged faces are updated.
+    ///
+    /// Returns the index of the vertex located at `p` if the cavity was rebuilt, or `None` if
+    /// the seed entity cannot be modified (frozen or corner vertex) or the check failed
+    pub fn rebuild_cavity(
+        &mut self,
+        cavity: &Cavity<D, E, M>,
+        p: Point<D>,
+        m: M,
+        q_min: f64,
+    ) -> Result<Option<Idx>> {
+        let tag = match cavity.seed {
+            Seed::Vertex(i) => cavity.tags[i as usize],
+            Seed::Edge(edg) => self
+                .topo
+                .parent(cavity.tags[edg[0] as usize], cavity.tags[edg[1] as usize])
+                .ok_or_else(|| Error::from("no parent topology entity for the edge"))?,
+            Seed::No => return Err(Error::from("empty cavity")),
+        };
+        // tag < 0 on fixed boundaries
+        if tag.0 == 0 || tag.1 < 0 {
+            return Ok(None);
+        }
+        if !matches!(
+            cavity.try_rebuild(p, m, 0.0, f64::MAX, q_min),
+            CavityCheckStatus::Ok(_)
+        ) {
+            return Ok(None);
+        }
+
+        let filled_cavity = cavity.fill(p, m);
+        let ip = if let Seed::Vertex(i) = cavity.seed {
+            let ip = cavity.local2global[i as usize];
+            let vert = self.verts.get_mut(&ip).unwrap();
+            vert.vx = p;
+            vert.m = m;
+            for i_elem in &cavity.global_elem_ids {
+                let q = self.gelem(&self.elems[i_elem].el).quality();
+                self.elems.get_mut(i_elem).unwrap().q = q;
+            }
+            ip
+        } else {
+            for i in &cavity.global_elem_ids {
+                self.remove_elem(*i)?;
+            }
+            let ip = self.insert_vertex(p, &tag, m);
+            for (face, t) in filled_cavity.faces() {
+                let f = cavity.global_elem(&face);
+                self.insert_elem(E::from_vertex_and_face(ip, &f), t)?;
+            }
+            for (f, _) in cavity.global_tagged_faces() {
+                self.remove_tagged_face(f)?;
+            }
+            for (b, t) in filled_cavity.tagged_faces_boundary_global() {
+                self.add_tagged_face(E::Face::from_vertex_and_face(ip, &b), t)?;
+            }
+            ip
+        };
+
+        Ok(Some(ip))
+    }
+
+    /// Perform a remeshing iteration (using only the passes allowed by `params.mode`)
     pub fn remesh<G: Geometry<D>>(&mut self, params: RemesherParams, geom: &G) -> Result<()> {
+        self.remesh_with_callback(params, geom, |_| ControlFlow::Continue(()))?;
+        Ok(())
+    }
+
+    /// Bound the metric sizes at the boundary vertices so that the distance between the
+    /// boundary edges and the geometry (chordal error) is lower than `chordal_error`, i.e.
+    /// ```math
+    /// h \le \sqrt{\frac{8 \varepsilon}{\kappa}}
+    /// ```
+    /// where `kappa` is the largest principal curvature given by `Geometry::curvature`. The
+    /// vertices where the curvature is not available are not modified.
+    ///
+    /// Returns the number of modified metrics
+    pub fn bound_sizes_by_curvature<G: Geometry<D>>(
+        &mut self,
+        chordal_error: f64,
+        geom: &G,
+    ) -> Idx {
+        debug!("Bound the metric sizes for a chordal error of {chordal_error}");
+
+        let mut modified = Vec::new();
+        for (&i, v) in &mut self.verts {
+            if v.tag.0 != D as Dim - 1 {
+                continue;
+            }
+            let Ok((u, w)) = geom.curvature(&v.vx, &v.tag) else {
+                continue;
+            };
+            let kappa = w.map_or(u.norm(), |w| u.norm().max(w.norm()));
+            if kappa < f64::EPSILON {
+                continue;
+            }
+            let h_max = (8.0 * chordal_error / kappa).sqrt();
+            if v.m.sizes().iter().any(|&h| h > h_max) {
+                v.m.scale_with_bounds(1.0, 0.0, h_max);
+                modified.push(i);
+            }
+        }
+        debug!("{} metrics modified", modified.len());
+
+        // update the qualities of the elements around the modified vertices
+        for i in &modified {
+            for i_elem in self.vertex_elements(*i).to_vec() {
+                let q = self.gelem(&self.elems[&i_elem].el).quality();
+                self.elems.get_mut(&i_elem).unwrap().q = q;
+            }
+        }
+
+        modified.len() as Idx
+    }
+
+    /// Perform the remeshing passes in `passes` and call `on_pass` with the stats after each
+    /// of them
+    fn run_passes<G: Geometry<D>, F: FnMut(&StepStats) -> ControlFlow<()>>(
+        &mut self,
+        passes: &[Pass],
+        params: &RemesherParams,
+        geom: &G,
+        on_pass: &mut F,
+    ) -> Result<ControlFlow<()>> {
+        for &pass in passes.iter().filter(|p| p.is_allowed(params.mode)) {
+            match pass {
+                Pass::Collapse => {
+                    self.collapse(params, geom)?;
+                }
+                Pass::Split(l_0) => {
+                    self.split(l_0, params, geom)?;
+                }
+                Pass::Swap(q_target) => {
+                    self.swap(q_target, params, geom)?;
+                }
+                Pass::Smooth => self.smooth(params, geom),
+            }
+            if cfg!(debug_assertions) {
+                self.check_connectivity()?;
+            }
+            if let Some(stats) = self.stats.last() {
+                if on_pass(stats).is_break() {
+                    debug!("Remeshing interrupted");
+                    return Ok(ControlFlow::Break(()));
+                }
+            }
+        }
+        Ok(ControlFlow::Continue(()))
+    }
+
+    /// Perform a remeshing iteration, calling `on_pass` after each collapse, split, swap or
+    /// smoothing pass with the stats of the last step (step type, number of operations and
+    /// failures, worst quality, number of elements, ...). If `on_pass` returns
+    /// `ControlFlow::Break`, the remeshing stops and the remesher holds the current valid mesh.
+    ///
+    /// Returns `false` if the remeshing was interrupted
+    pub fn remesh_with_callback<G: Geometry<D>, F: FnMut(&StepStats) -> ControlFlow<()>>(
+        &mut self,
+        params: RemesherParams,
+        geom: &G,
+        mut on_pass: F,
+    ) -> Result<bool> {
         debug!("Adapt the mesh");
         let now = Instant::now();
 
+        if let Some(chordal_error) = params.chordal_error {
+            self.bound_sizes_by_curvature(chordal_error, geom);
+        }
+
         if params.two_steps {
             let l_max = max_iter(self.lengths_iter());
             if l_max > 2.0 * f64::sqrt(2.0) {
@@ -1484,41 +2071,57 @@ impl<const D: usize, E: Elem, M: Metric<D>> Remesher<D, E, M> {
                     split_min_l_abs: 0.0,
                     ..params.clone()
                 };
+                let passes = [
+                    Pass::Collapse,
+                    Pass::Split(l_0),
+                    Pass::Swap(0.4),
+                    Pass::Swap(0.8),
+                    Pass::Smooth,
+                ];
                 for _ in 0..params.num_iter {
-                    self.collapse(&first_step_params, geom)?;
-
-                    self.split(l_0, &first_step_params, geom)?;
-
-                    self.swap(0.4, &first_step_params, geom)?;
-
-                    self.swap(0.8, &first_step_params, geom)?;
-
-                    self.smooth(&first_step_params, geom);
+                    if self
+                        .run_passes(&passes, &first_step_params, geom, &mut on_pass)?
+                        .is_break()
+                    {
+                        return Ok(false);
+                    }
                 }
             } else {
                 debug!("l_max = {l_max}, no first step required");
             }
         }
 
+        let passes = [
+            Pass::Collapse,
+            Pass::Split(f64::sqrt(2.0)),
+            Pass::Swap(0.4),
+            Pass::Swap(0.8),
+            Pass::Smooth,
+        ];
         for _ in 0..params.num_iter {
-            self.collapse(&params, geom)?;
-
-            self.split(f64::sqrt(2.0), &params, geom)?;
-
-            self.swap(0.4, &params, geom)?;
-
-            self.swap(0.8, &params, geom)?;
-
-            self.smooth(&params, geom);
+            if self
+                .run_passes(&passes, &params, geom, &mut on_pass)?
+                .is_break()
+            {
+                return Ok(false);
+            }
         }
 
-        self.swap(0.4, &params, geom)?;
-
-        self.swap(0.8, &params, geom)?;
+        if self
+            .run_passes(
+                &[Pass::Swap(0.4), Pass::Swap(0.8)],
+                &params,
+                geom,
+                &mut on_pass,
+            )?
+            .is_break()
+        {
+            return Ok(false);
+        }
 
         debug!("Done in {}s", now.elapsed().as_secs_f32());
         self.print_stats();
-        Ok(())
+        Ok(true)
     }
 
     /// Print length and quality stats on the mesh / metric
@@ -1575,6 +2178,40 @@ impl<const D: usize, E: Elem, M: Metric<D>> Remesher<D, E, M> {
     }
 }
 
+impl<const D: usize, E: Elem> SimplexMesh<D, E> {
+    /// Smooth the mesh vertices without modifying the connectivity (see
+    /// `Remesher::smooth_vertices`). The boundary vertices are projected onto `geom` and the
+    /// metric `m` is interpolated at the new vertex locations.
+    ///
+    /// Returns the new mesh and metric. The mesh topology is required
+    pub fn smooth_vertices<M: Metric<D>, G: Geometry<D>>(
+        &self,
+        method: SmoothingType,
+        n_iter: u32,
+        m: &[M],
+        geom: &G,
+    ) -> Result<(Self, Vec<M>)> {
+        let mut remesher = Remesher::new(self, m, geom)?;
+        remesher.smooth_vertices(method, n_iter, geom);
+        Ok((remesher.to_mesh(false), remesher.metrics()))
+    }
+
+    /// Swap the mesh edges to improve the quality of the elements below `q_target` (see
+    /// `Remesher::swap`), without adding or removing vertices: the metric in unchanged.
+    ///
+    /// Returns the new mesh and metric. The mesh topology is required
+    pub fn swap_edges<M: Metric<D>, G: Geometry<D>>(
+        &self,
+        q_target: f64,
+        m: &[M],
+        geom: &G,
+    ) -> Result<(Self, Vec<M>)> {
+        let mut remesher = Remesher::new(self, m, geom)?;
+        remesher.swap(q_target, &RemesherParams::default(), geom)?;
+        Ok((remesher.to_mesh(false), remesher.metrics()))
+    }
+}
+
 #[cfg(test)]
 mod tests_topo {
     use crate::{
@@ -1608,7 +2245,7 @@ mod tests_topo {
         for tag in ftags {
             if let Entry::Vacant(v) = stats.entry(tag) {
                 let smsh = bdy.extract_tag(tag).mesh;
-                let center = smsh.verts().sum::<Point<2>>() / f64::from(smsh.n_verts());
+                let center = smsh.verts().sum::<Point<2>>() / smsh.n_verts() as f64;
                 v.insert((smsh.vol(), center));
             }
         }
@@ -1632,7 +2269,7 @@ mod tests_topo {
         for (&tag, (vol, center)) in &stats {
             let smsh = bdy.extract_tag(tag).mesh;
             assert!((vol - smsh.vol()).abs() < 1e-10 * vol);
-            let new_center = smsh.verts().sum::<Point<2>>() / f64::from(smsh.n_verts());
+            let new_center = smsh.verts().sum::<Point<2>>() / smsh.n_verts() as f64;
             assert!((center - new_center).norm() < 1e-10);
         }
     }
@@ -1710,7 +2347,7 @@ mod tests_topo {
         for tag in ftags {
             if let Entry::Vacant(v) = stats.entry(tag) {
                 let smsh = bdy.extract_tag(tag).mesh;
-                let center = smsh.verts().sum::<Point<3>>() / f64::from(smsh.n_verts());
+                let center = smsh.verts().sum::<Point<3>>() / smsh.n_verts() as f64;
                 v.insert((smsh.vol(), center));
             }
         }
@@ -1729,7 +2366,7 @@ mod tests_topo {
         for (&tag, (vol, center)) in &stats {
             let smsh = bdy.extract_tag(tag).mesh;
             assert!((vol - smsh.vol()).abs() < 1e-10 * vol);
-            let new_center = smsh.verts().sum::<Point<3>>() / f64::from(smsh.n_verts());
+            let new_center = smsh.verts().sum::<Point<3>>() / smsh.n_verts() as f64;
             assert!((center - new_center).norm() < 1e-10);
         }
     }
@@ -1775,13 +2412,15 @@ mod tests {
                 h_2d, h_3d, sphere_mesh, test_mesh_2d, test_mesh_3d, test_mesh_3d_single_tet,
                 test_mesh_3d_two_tets, test_mesh_moon_2d, GeomHalfCircle2d, SphereGeometry,
             },
-            Edge, Elem, GElem, Point, SimplexMesh, Tetrahedron, Triangle,
+            AsSliceF64, Edge, Elem, GElem, Point, SimplexMesh, Tetrahedron, Triangle,
         },
         metric::{AnisoMetric, AnisoMetric2d, AnisoMetric3d, IsoMetric, Metric},
-        remesher::{Remesher, SmoothingType},
-        Result,
+        remesher::{
+            Cavity, CavityCheckStatus, Remesher, RemeshingMode, Seed, SmoothingType, StepStats,
+        },
+        Idx, Result,
     };
-    use std::f64::consts::PI;
+    use std::{f64::consts::PI, ops::ControlFlow};
 
     #[test]
     fn test_init() -> Result<()> {
@@ -1921,6 +2560,111 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn test_connectivity_3d() -> Result<()> {
+        let mut mesh = test_mesh_3d().split();
+        mesh.compute_topology();
+
+        let h: Vec<_> = mesh
+            .verts()
+            .map(|p| IsoMetric::<3>::from(h_3d(&p)))
+            .collect();
+        let geom = NoGeometry();
+        let mut remesher = Remesher::new(&mesh, &h, &geom)?;
+        remesher.remesh(RemesherParams::default(), &geom)?;
+
+        // the connectivities updated by the split / collapse / swap operators
+        remesher.check_connectivity()?;
+        for (&i_elem, e) in &remesher.elems {
+            for i_face in 0..Tetrahedron::N_FACES {
+                let els = remesher.face_elements(&e.el.face(i_face));
+                assert!(els.contains(&i_elem));
+                assert!(els.len() == 1 || els.len() == 2);
+            }
+        }
+
+        let mut mesh = remesher.to_mesh(true);
+        let v2e = mesh.get_vertex_to_elems()?.clone();
+        let n_faces = mesh.get_face_to_elems()?.len();
+        mesh.clear_vertex_to_elems();
+        mesh.clear_face_to_elems();
+        let other = mesh.compute_vertex_to_elems();
+        assert_eq!(v2e.ptr, other.ptr);
+        assert_eq!(v2e.indices, other.indices);
+        assert_eq!(mesh.compute_face_to_elems().len(), n_faces);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_memory_usage() -> Result<()> {
+        let mut mesh = test_mesh_3d().split();
+        mesh.compute_topology();
+
+        let h: Vec<_> = mesh
+            .verts()
+            .map(|p| IsoMetric::<3>::from(h_3d(&p)))
+            .collect();
+        let geom = NoGeometry();
+        let mut remesher = Remesher::new(&mesh, &h, &geom)?;
+        remesher.remesh(RemesherParams::default(), &geom)?;
+
+        let usage = remesher.memory_usage();
+        assert!(usage.face_to_elems > 2 * remesher.n_elems() as usize);
+        assert!(usage.vertex_to_elems >= 4 * 4 * remesher.n_elems() as usize);
+        assert_eq!(usage.fields, 0);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_rebuild_cavity_2d() -> Result<()> {
+        let mut mesh = test_mesh_2d().split().split();
+        mesh.add_boundary_faces();
+        mesh.compute_topology();
+
+        let h = vec![IsoMetric::<2>::from(0.25); mesh.n_verts() as usize];
+        let mut remesher = Remesher::new(&mesh, &h, &NoGeometry())?;
+        let n_verts = remesher.n_verts();
+        let n_elems = remesher.n_elems();
+
+        // insert a vertex at the center of an internal edge
+        let center = Point::<2>::new(0.5, 0.5);
+        let i0 = (0..n_verts)
+            .find(|&i| (remesher.get_vertex(i).unwrap().0 - center).norm() < 1e-12)
+            .unwrap();
+        let i1 = remesher
+            .edges
+            .keys()
+            .find(|e| e.contains(&i0))
+            .map(|e| if e[0] == i0 { e[1] } else { e[0] })
+            .unwrap();
+        let cavity = Cavity::build(&remesher, Seed::Edge([i0, i1]));
+        let (p, m) = cavity.seed_barycenter();
+        let ip = remesher.rebuild_cavity(&cavity, p, m, 0.0)?.unwrap();
+        assert_eq!(remesher.n_verts(), n_verts + 1);
+        assert_eq!(remesher.n_elems(), n_elems + 2);
+        remesher.check()?;
+
+        // move the new vertex
+        let cavity = Cavity::build(&remesher, Seed::Vertex(ip));
+        let p_new = p + 0.1 * (center - p);
+        assert!(remesher.rebuild_cavity(&cavity, p_new, m, 0.0)?.is_some());
+        assert!((remesher.get_vertex(ip).unwrap().0 - p_new).norm() < 1e-12);
+        remesher.check()?;
+
+        // invalid elements
+        let cavity = Cavity::build(&remesher, Seed::Vertex(ip));
+        let p_out = Point::<2>::new(2.0, 2.0);
+        assert!(matches!(
+            cavity.try_rebuild(p_out, m, 0.0, f64::MAX, 0.0),
+            CavityCheckStatus::Invalid
+        ));
+        assert!(remesher.rebuild_cavity(&cavity, p_out, m, 0.0)?.is_none());
+
+        Ok(())
+    }
+
     #[test]
     fn test_swap_2d() -> Result<()> {
         let mut mesh = test_mesh_2d().split().split();
@@ -1962,6 +2706,246 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn test_swap_edges_2d() -> Result<()> {
+        // flat quad split along its longest diagonal
+        let coords = vec![
+            Point::<2>::new(0., 0.),
+            Point::<2>::new(1., -0.2),
+            Point::<2>::new(2., 0.),
+            Point::<2>::new(1., 0.2),
+        ];
+        let elems = vec![
+            Triangle::from_slice(&[0, 1, 2]),
+            Triangle::from_slice(&[0, 2, 3]),
+        ];
+        let etags = vec![1, 1];
+        let faces = vec![
+            Edge::from_slice(&[0, 1]),
+            Edge::from_slice(&[1, 2]),
+            Edge::from_slice(&[2, 3]),
+            Edge::from_slice(&[3, 0]),
+        ];
+        let ftags = vec![1, 1, 1, 1];
+
+        let mut mesh = SimplexMesh::new(coords, elems, etags, faces, ftags);
+        mesh.compute_topology();
+        let q_min = |mesh: &SimplexMesh<2, Triangle>| {
+            mesh.gelems()
+                .map(|ge| ge.quality())
+                .fold(f64::INFINITY, f64::min)
+        };
+
+        // such that the new edge length satisfies the default constraints
+        let h = vec![IsoMetric::<2>::from(0.6); mesh.n_verts() as usize];
+        let geom = NoGeometry();
+        let (new_mesh, new_h) = mesh.swap_edges(0.8, &h, &geom)?;
+        assert_eq!(new_mesh.n_verts(), 4);
+        assert_eq!(new_mesh.n_elems(), 2);
+        assert_eq!(new_h.len(), 4);
+        assert!((new_mesh.vol() - mesh.vol()).abs() < 1e-12);
+        assert!(q_min(&new_mesh) > 1.5 * q_min(&mesh));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_frozen_2d() -> Result<()> {
+        let mut mesh = test_mesh_2d().split().split().split();
+        let centers = mesh.gelems().map(|ge| ge.center()).collect::<Vec<_>>();
+        mesh.mut_etags()
+            .zip(centers.iter())
+            .for_each(|(t, c)| *t = if c[0] < 0.5 { 2 } else { 1 });
+        mesh.add_boundary_faces();
+        mesh.compute_topology();
+
+        let bits = |p: Point<2>| [p[0].to_bits(), p[1].to_bits()];
+        let frozen_elems = |mesh: &SimplexMesh<2, Triangle>| {
+            let mut res = mesh
+                .elems()
+                .zip(mesh.etags())
+                .filter(|(_, t)| *t == 2)
+                .map(|(e, _)| {
+                    let mut pts = e.iter().map(|&i| bits(mesh.vert(i))).collect::<Vec<_>>();
+                    pts.sort_unstable();
+                    pts
+                })
+                .collect::<Vec<_>>();
+            res.sort_unstable();
+            res
+        };
+        let bottom_verts = |mesh: &SimplexMesh<2, Triangle>| {
+            let mut res = mesh
+                .faces()
+                .zip(mesh.ftags())
+                .filter(|(_, t)| *t == 1)
+                .flat_map(|(f, _)| f.iter().map(|&i| bits(mesh.vert(i))).collect::<Vec<_>>())
+                .collect::<Vec<_>>();
+            res.sort_unstable();
+            res.dedup();
+            res
+        };
+        let i_frozen = (0..mesh.n_verts())
+            .find(|&i| {
+                let p = mesh.vert(i);
+                p[0] > 0.6 && p[0] < 0.9 && p[1] > 0.6 && p[1] < 0.9
+            })
+            .unwrap();
+        let p_frozen = bits(mesh.vert(i_frozen));
+
+        let h = vec![IsoMetric::<2>::from(0.03); mesh.n_verts() as usize];
+        let geom = NoGeometry();
+        let mut remesher = Remesher::new(&mesh, &h, &geom)?;
+        let params = RemesherParams {
+            frozen_verts: vec![i_frozen],
+            frozen_face_tags: vec![1],
+            frozen_elem_tags: vec![2],
+            ..RemesherParams::default()
+        };
+        remesher.remesh(params, &geom)?;
+        remesher.check()?;
+        let new_mesh = remesher.to_mesh(true);
+
+        assert!(new_mesh.n_elems() > 2 * mesh.n_elems());
+        assert_eq!(frozen_elems(&new_mesh), frozen_elems(&mesh));
+        assert_eq!(bottom_verts(&new_mesh), bottom_verts(&mesh));
+        assert!(new_mesh.verts().any(|p| bits(p) == p_frozen));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_coarsen_only_2d() -> Result<()> {
+        let mut mesh = test_mesh_2d().split().split().split().split();
+        mesh.compute_topology();
+        let h = vec![IsoMetric::<2>::from(0.2); mesh.n_verts() as usize];
+        let geom = NoGeometry();
+
+        let mut remesher = Remesher::new(&mesh, &h, &geom)?;
+        let params = RemesherParams {
+            mode: RemeshingMode::CoarsenOnly,
+            ..RemesherParams::default()
+        };
+        remesher.remesh(params, &geom)?;
+        remesher.check()?;
+        let new_mesh = remesher.to_mesh(true);
+        assert!(new_mesh.n_verts() < mesh.n_verts() / 2);
+
+        let bits = |p: Point<2>| [p[0].to_bits(), p[1].to_bits()];
+        let verts = mesh.verts().map(bits).collect::<Vec<_>>();
+        assert!(new_mesh.verts().all(|p| verts.contains(&bits(p))));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_refine_only_2d() -> Result<()> {
+        let mut mesh = test_mesh_2d().split().split();
+        mesh.compute_topology();
+        let h = vec![IsoMetric::<2>::from(0.05); mesh.n_verts() as usize];
+        let geom = NoGeometry();
+
+        let mut remesher = Remesher::new(&mesh, &h, &geom)?;
+        let params = RemesherParams {
+            mode: RemeshingMode::RefineOnly,
+            ..RemesherParams::default()
+        };
+        remesher.remesh(params, &geom)?;
+        remesher.check()?;
+        let new_mesh = remesher.to_mesh(true);
+        assert!(new_mesh.n_elems() > 10 * mesh.n_elems());
+
+        // each new element is inside an element of the input mesh
+        let tree = mesh.compute_element_tree();
+        for e in new_mesh.elems() {
+            let ge = new_mesh.gelem(e);
+            let (i_elem, _) = tree.locate(&ge.center()).unwrap();
+            let ge_ref = mesh.gelem(mesh.elem(i_elem));
+            for &i in e.iter() {
+                let x = ge_ref.bcoords(&new_mesh.vert(i));
+                assert!(x.as_slice_f64().iter().all(|&c| c > -1e-10));
+            }
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_remesh_fields_2d() -> Result<()> {
+        let mut mesh = test_mesh_2d().split().split();
+        mesh.compute_topology();
+        let f = |p: Point<2>| [p[0] + 2.0 * p[1], 1.0 - p[0]];
+        mesh.add_vertex_field::<f64, 2>("linear", mesh.verts().flat_map(f).collect())?;
+        let tags = mesh.etags().map(|t| t as f64).collect();
+        mesh.add_elem_field::<f64, 1>("tag", tags)?;
+        let tags = mesh.etags().map(i64::from).collect();
+        mesh.add_elem_field::<i64, 1>("itag", tags)?;
+
+        let h = vec![IsoMetric::<2>::from(0.1); mesh.n_verts() as usize];
+        let geom = NoGeometry();
+        let mut remesher = Remesher::new(&mesh, &h, &geom)?;
+        remesher.remesh(RemesherParams::default(), &geom)?;
+        let new_mesh = remesher.to_mesh(true);
+        assert!(new_mesh.n_verts() > mesh.n_verts());
+
+        let v = new_mesh.get_vertex_field::<f64, 2>("linear")?;
+        for (p, x) in new_mesh.verts().zip(v.chunks(2)) {
+            let [a, b] = f(p);
+            assert!(f64::abs(x[0] - a) < 1e-10);
+            assert!(f64::abs(x[1] - b) < 1e-10);
+        }
+        let tag = new_mesh.get_elem_field::<f64, 1>("tag")?;
+        let itag = new_mesh.get_elem_field::<i64, 1>("itag")?;
+        for ((t, &x), &i) in new_mesh.etags().zip(tag).zip(itag) {
+            assert!(f64::abs(x - t as f64) < 1e-10);
+            assert_eq!(i, i64::from(t));
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_remesh_callback() -> Result<()> {
+        let mut mesh = test_mesh_2d().split().split();
+        mesh.compute_topology();
+        let h = vec![IsoMetric::<2>::from(0.05); mesh.n_verts() as usize];
+        let geom = NoGeometry();
+
+        let mut names = Vec::new();
+        let mut remesher = Remesher::new(&mesh, &h, &geom)?;
+        let done = remesher.remesh_with_callback(RemesherParams::default(), &geom, |s| {
+            names.push(s.name());
+            assert!(s.remesher_stats().n_elems() > 0);
+            assert!(s.remesher_stats().q_min() > 0.0);
+            ControlFlow::Continue(())
+        })?;
+        assert!(done);
+        assert_eq!(
+            &names[..5],
+            &["collapse", "split", "swap", "swap", "smooth"]
+        );
+
+        // stop after the first split
+        let mut n_passes = 0;
+        let mut n_splits = 0;
+        let mut remesher = Remesher::new(&mesh, &h, &geom)?;
+        let done = remesher.remesh_with_callback(RemesherParams::default(), &geom, |s| {
+            n_passes += 1;
+            if let StepStats::Split(_) = s {
+                n_splits = s.n_ops().0;
+                return ControlFlow::Break(());
+            }
+            ControlFlow::Continue(())
+        })?;
+        assert!(!done);
+        assert_eq!(n_passes, 2);
+        assert!(n_splits > 0);
+        remesher.check()?;
+        assert!(remesher.n_elems() > mesh.n_elems());
+
+        Ok(())
+    }
+
     #[test]
     fn test_collapse_2d() -> Result<()> {
         let mut mesh = test_mesh_2d().split().split();
@@ -2037,6 +3021,49 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn test_smooth_optimization_2d() -> Result<()> {
+        let coords = vec![
+            Point::<2>::new(0., 0.),
+            Point::<2>::new(1., 0.),
+            Point::<2>::new(1., 1.),
+            Point::<2>::new(0., 1.),
+            Point::<2>::new(0.1, 0.1),
+        ];
+        let elems = vec![
+            Triangle::from_slice(&[0, 1, 4]),
+            Triangle::from_slice(&[1, 2, 4]),
+            Triangle::from_slice(&[2, 3, 4]),
+            Triangle::from_slice(&[3, 0, 4]),
+        ];
+        let etags = vec![1, 1, 1, 1];
+        let faces = vec![
+            Edge::from_slice(&[0, 1]),
+            Edge::from_slice(&[1, 2]),
+            Edge::from_slice(&[2, 3]),
+            Edge::from_slice(&[3, 0]),
+        ];
+        let ftags = vec![1, 2, 3, 4];
+
+        let mut mesh = SimplexMesh::new(coords, elems, etags, faces, ftags);
+        mesh.compute_topology();
+
+        let h = vec![IsoMetric::<2>::from(1.); mesh.n_verts() as usize];
+        let geom = NoGeometry();
+        let (new_mesh, new_h) = mesh.smooth_vertices(SmoothingType::Optimization, 6, &h, &geom)?;
+        assert_eq!(new_mesh.n_verts(), mesh.n_verts());
+        assert_eq!(new_mesh.n_elems(), mesh.n_elems());
+        assert_eq!(new_h.len(), h.len());
+
+        let center = Point::<2>::new(0.5, 0.5);
+        assert!(new_mesh.verts().any(|pt| (pt - center).norm() < 0.05));
+        for (i, pt) in mesh.verts().take(4).enumerate() {
+            assert!(new_mesh.verts().any(|p| (p - pt).norm() < 1e-12), "{i}");
+        }
+
+        Ok(())
+    }
+
     #[test]
     #[cfg(feature = "nlopt")]
     fn test_smooth_nlopt_2d() -> Result<()> {
@@ -2644,6 +3671,43 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn test_chordal_error() -> Result<()> {
+        let mesh = sphere_mesh(2);
+        let h = vec![IsoMetric::<3>::from(1.0); mesh.n_verts() as usize];
+        let geom = SphereGeometry;
+        let vtags = mesh.get_vertex_tags()?;
+
+        let mut remesher = Remesher::new(&mesh, &h, &geom)?;
+        let n_bdy = vtags.iter().filter(|t| t.0 == 2).count();
+        assert_eq!(
+            remesher.bound_sizes_by_curvature(0.01, &geom) as usize,
+            n_bdy
+        );
+
+        let h_max = f64::sqrt(0.08);
+        for (i, tag) in vtags.iter().enumerate() {
+            let (_, _, m) = remesher.get_vertex(i as Idx).unwrap();
+            let expected = if tag.0 == 2 { h_max } else { 1.0 };
+            assert!((m.sizes()[0] - expected).abs() < 1e-12);
+        }
+        // the qualities are updated with the new metrics
+        for e in remesher.elems.values() {
+            assert!((remesher.gelem(&e.el).quality() - e.q).abs() < 1e-12);
+        }
+        assert_eq!(remesher.bound_sizes_by_curvature(0.01, &geom), 0);
+
+        // no curvature available
+        let mut mesh = test_mesh_2d();
+        mesh.add_boundary_faces();
+        mesh.compute_topology();
+        let h = vec![IsoMetric::<2>::from(1.); mesh.n_verts() as usize];
+        let mut remesher = Remesher::new(&mesh, &h, &NoGeometry())?;
+        assert_eq!(remesher.bound_sizes_by_curvature(0.01, &NoGeometry()), 0);
+
+        Ok(())
+    }
+
     #[test]
     fn test_complexity_2d() -> Result<()> {
         let mut mesh = test_mesh_2d().split().split();
@@ -117,6 +117,42 @@ impl RemesherStats {
             stats_q: Stats::new(r.qualities_iter(), &[0.4, 0.6, 0.8]),
         }
     }
+
+    /// Get the # of vertices in the mesh
+    #[must_use]
+    pub const fn n_verts(&self) -> Idx {
+        self.n_verts
+    }
+
+    /// Get the # of elements in the mesh
+    #[must_use]
+    pub const fn n_elems(&self) -> Idx {
+        self.n_elems
+    }
+
+    /// Get the # of edges in the mesh
+    #[must_use]
+    pub const fn n_edges(&self) -> Idx {
+        self.n_edges
+    }
+
+    /// Get the edge length stats
+    #[must_use]
+    pub const fn lengths(&self) -> &Stats {
+        &self.stats_l
+    }
+
+    /// Get the element quality stats
+    #[must_use]
+    pub const fn qualities(&self) -> &Stats {
+        &self.stats_q
+    }
+
+    /// Get the worst element quality
+    #[must_use]
+    pub fn q_min(&self) -> f64 {
+        self.stats_q.bins[0]
+    }
 }
 
 /// Statistics for each remeshing step that include `RemesherStats` and additional step-dependent info
@@ -129,6 +165,44 @@ pub enum StepStats {
     Smooth(SmoothStats),
 }
 
+impl StepStats {
+    /// Get the name of the step
+    #[must_use]
+    pub const fn name(&self) -> &'static str {
+        match self {
+            Self::Init(_) => "init",
+            Self::Split(_) => "split",
+            Self::Swap(_) => "swap",
+            Self::Collapse(_) => "collapse",
+            Self::Smooth(_) => "smooth",
+        }
+    }
+
+    /// Get the # of successful operations (splits, swaps, collapses) and the # of failures
+    #[must_use]
+    pub const fn n_ops(&self) -> (Idx, Idx) {
+        match self {
+            Self::Init(_) => (0, 0),
+            Self::Split(s) => (s.n_splits, s.n_fails),
+            Self::Swap(s) => (s.n_swaps, s.n_fails),
+            Self::Collapse(s) => (s.n_collapses, s.n_fails),
+            Self::Smooth(s) => (0, s.n_fails),
+        }
+    }
+
+    /// Get the stats on the remesher state at the end of the step
+    #[must_use]
+    pub const fn remesher_stats(&self) -> &RemesherStats {
+        match self {
+            Self::Init(s) => &s.r_stats,
+            Self::Split(s) => &s.r_stats,
+            Self::Swap(s) => &s.r_stats,
+            Self::Collapse(s) => &s.r_stats,
+            Self::Smooth(s) => &s.r_stats,
+        }
+    }
+}
+
 #[derive(Serialize, Clone)]
 pub struct InitStats {
     r_stats: RemesherStats,
@@ -81,7 +81,7 @@ mod parry2d {
             let data = mesh
                 .elems()
                 .enumerate()
-                .map(|(i, e)| (i as u32, Self::local_aabb(mesh, &e)));
+                .map(|(i, e)| (super::parry_index(i as Idx), Self::local_aabb(mesh, &e)));
             tree.clear_and_rebuild(data, 0.);
             let mut elems = Vec::with_capacity((mesh.n_elems() as usize) * (E::N_VERTS as usize));
             for e in mesh.elems() {
@@ -324,7 +324,7 @@ mod parry3d {
             let data = mesh
                 .elems()
                 .enumerate()
-                .map(|(i, e)| (i as u32, Self::local_aabb(mesh, &e)));
+                .map(|(i, e)| (super::parry_index(i as Idx), Self::local_aabb(mesh, &e)));
             tree.clear_and_rebuild(data, 0.);
             let mut elems = Vec::with_capacity((mesh.n_elems() as usize) * (E::N_VERTS as usize));
             for e in mesh.elems() {
@@ -421,6 +421,12 @@ enum ParryImpl<const D: usize> {
     Tetra(parry3d::MeshShape<D, parry3d::TetraToTetra>),
 }
 
+/// Parry uses 32 bit indices, even with the `large-indices` feature
+#[allow(clippy::unnecessary_fallible_conversions)]
+fn parry_index(i: Idx) -> u32 {
+    u32::try_from(i).expect("parry only supports meshes with less than 2^32 vertices / elements")
+}
+
 impl<const D: usize> super::ObjectIndex<D> for ObjectIndex<D> {
     fn new<E: Elem>(mesh: &SimplexMesh<D, E>) -> Self {
         if D == 3 && E::N_VERTS == 3 {
@@ -432,7 +438,7 @@ impl<const D: usize> super::ObjectIndex<D> for ObjectIndex<D> {
                 .elems()
                 .map(|e| {
                     let mut i = e.iter();
-                    std::array::from_fn(|_| *i.next().unwrap())
+                    std::array::from_fn(|_| parry_index(*i.next().unwrap()))
                 })
                 .collect();
             Self {
@@ -459,7 +465,7 @@ impl<const D: usize> super::ObjectIndex<D> for ObjectIndex<D> {
                 .elems()
                 .map(|e| {
                     let mut i = e.iter();
-                    std::array::from_fn(|_| *i.next().unwrap())
+                    std::array::from_fn(|_| parry_index(*i.next().unwrap()))
                 })
                 .collect();
             Self {
@@ -477,21 +483,21 @@ impl<const D: usize> super::ObjectIndex<D> for ObjectIndex<D> {
                     &nalgebra::Point3::new(pt[0], pt[1], pt[2]),
                     true,
                 );
-                id
+                id as Idx
             }
             ParryImpl::Edge2D(shape) => {
                 let (_, (id, _)) = shape.project_local_point_and_get_location(
                     &nalgebra::Point2::new(pt[0], pt[1]),
                     true,
                 );
-                id
+                id as Idx
             }
             ParryImpl::Tetra(shape) => {
                 let (_, (id, _)) = shape.project_local_point_and_get_location(
                     &nalgebra::Point3::new(pt[0], pt[1], pt[2]),
                     true,
                 );
-                id
+                id as Idx
             }
             ParryImpl::Edge3D(_) => todo!(),
             ParryImpl::Tria2D(shape) => {
@@ -499,7 +505,7 @@ impl<const D: usize> super::ObjectIndex<D> for ObjectIndex<D> {
                     &nalgebra::Point2::new(pt[0], pt[1]),
                     true,
                 );
-                id
+                id as Idx
             }
         }
     }
@@ -543,3 +549,13 @@ impl<const D: usize> super::ObjectIndex<D> for ObjectIndex<D> {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    #[cfg(feature = "large-indices")]
+    #[test]
+    #[should_panic]
+    fn test_parry_index_overflow() {
+        super::parry_index(crate::Idx::from(u32::MAX) + 1);
+    }
+}
@@ -0,0 +1,90 @@
+! Smoke test of the Fortran interface: remesh the unit square with an isotropic metric (built
+! and run in CI, see .github/workflows/test.yml)
+program test_remesh
+  use, intrinsic :: iso_c_binding
+  use tucanos_capi
+  implicit none
+
+  real(c_double) :: coords(8) = [0.0d0, 0.0d0, 1.0d0, 0.0d0, 1.0d0, 1.0d0, 0.0d0, 1.0d0]
+  integer(c_int64_t) :: elems(6) = [1, 2, 3, 1, 3, 4]
+  integer(c_int32_t) :: etags(2) = [1, 1]
+  integer(c_int64_t) :: faces(8) = [1, 2, 2, 3, 3, 4, 4, 1]
+  integer(c_int32_t) :: ftags(4) = [1, 2, 3, 4]
+  real(c_double) :: metric(4) = 0.1d0
+  type(tucanos_capi_params_t), target :: params
+  type(c_ptr) :: mesh
+  integer(c_int32_t) :: dim, n_comp
+  integer(c_int64_t) :: n_verts, n_elems, n_faces
+  real(c_double), allocatable :: new_coords(:), new_metric(:)
+  integer(c_int64_t), allocatable :: new_elems(:)
+  integer(c_int32_t), allocatable :: new_etags(:)
+
+  call check(tucanos_capi_init_log())
+  call check(tucanos_capi_init_log())
+
+  call check(tucanos_capi_mesh_new(2, 4_c_int64_t, coords, 2_c_int64_t, elems, etags, &
+    4_c_int64_t, faces, ftags, 1_c_int64_t, mesh))
+  call check(tucanos_capi_mesh_set_metric(mesh, 1, metric))
+
+  call check(tucanos_capi_params_init(params))
+  params%num_iter = 2
+  call check(tucanos_capi_mesh_remesh(mesh, c_loc(params)))
+
+  call check(tucanos_capi_mesh_sizes(mesh, dim, n_verts, n_elems, n_faces))
+  if (dim /= 2 .or. n_verts <= 4 .or. n_elems <= 2 .or. n_faces <= 4) then
+    print *, "invalid mesh sizes", dim, n_verts, n_elems, n_faces
+    stop 1
+  end if
+
+  allocate(new_coords(2 * n_verts), new_elems(3 * n_elems), new_etags(n_elems))
+  call check(tucanos_capi_mesh_get_verts(mesh, new_coords))
+  call check(tucanos_capi_mesh_get_elems(mesh, new_elems, new_etags))
+  if (any(new_coords < -1.0d-12) .or. any(new_coords > 1.0d0 + 1.0d-12)) then
+    print *, "invalid coordinates"
+    stop 1
+  end if
+  if (minval(new_elems) /= 1 .or. maxval(new_elems) /= n_verts .or. any(new_etags /= 1)) then
+    print *, "invalid elements"
+    stop 1
+  end if
+
+  call check(tucanos_capi_mesh_metric_comp(mesh, n_comp))
+  if (n_comp /= 1) then
+    print *, "invalid # of metric components", n_comp
+    stop 1
+  end if
+  allocate(new_metric(n_verts))
+  call check(tucanos_capi_mesh_get_metric(mesh, new_metric))
+  if (any(abs(new_metric - 0.1d0) > 1.0d-12)) then
+    print *, "invalid metric"
+    stop 1
+  end if
+
+  ! invalid # of metric components
+  if (tucanos_capi_mesh_set_metric(mesh, 2, new_metric) /= TUCANOS_ERR_INVALID_ARGUMENT) then
+    print *, "expected TUCANOS_ERR_INVALID_ARGUMENT"
+    stop 1
+  end if
+
+  call check(tucanos_capi_mesh_delete(mesh))
+  print *, "remeshed the unit square:", n_verts, "vertices,", n_elems, "elements"
+
+contains
+
+  subroutine check(status)
+    integer(c_int), intent(in) :: status
+    character(kind=c_char) :: buf(256)
+    integer :: i
+
+    if (status /= TUCANOS_OK) then
+      if (tucanos_capi_last_error(buf, int(size(buf), c_size_t)) == TUCANOS_OK) then
+        do i = 1, size(buf)
+          if (buf(i) == c_null_char) exit
+        end do
+        print *, "error ", status, ": ", buf(1:i - 1)
+      end if
+      stop 1
+    end if
+  end subroutine check
+
+end program test_remesh
@@ -0,0 +1,144 @@
+! Fortran interface to the Tucanos C API (libtucanos_capi), see tucanos-capi/src/lib.rs for the
+! documentation of the functions. All the functions return a status code (TUCANOS_OK on
+! success).
+module tucanos_capi
+  use, intrinsic :: iso_c_binding
+  implicit none
+
+  integer(c_int), parameter :: TUCANOS_CAPI_VERSION = 1
+
+  integer(c_int), parameter :: TUCANOS_OK = 0
+  integer(c_int), parameter :: TUCANOS_ERR_NULL_POINTER = 1
+  integer(c_int), parameter :: TUCANOS_ERR_INVALID_ARGUMENT = 2
+  integer(c_int), parameter :: TUCANOS_ERR_NO_METRIC = 3
+  integer(c_int), parameter :: TUCANOS_ERR_FAILED = 4
+  integer(c_int), parameter :: TUCANOS_ERR_INTERNAL = 5
+
+  type, bind(C) :: tucanos_capi_params_t
+    integer(c_int32_t) :: num_iter
+    integer(c_int32_t) :: two_steps
+    integer(c_int32_t) :: split_max_iter
+    integer(c_int32_t) :: collapse_max_iter
+    integer(c_int32_t) :: swap_max_iter
+    integer(c_int32_t) :: smooth_iter
+    real(c_double) :: max_angle
+    integer(c_int32_t) :: debug
+  end type tucanos_capi_params_t
+
+  interface
+    function tucanos_capi_params_init(params) result(status) &
+        bind(C, name="tucanos_capi_params_init")
+      import :: c_int, tucanos_capi_params_t
+      type(tucanos_capi_params_t), intent(out) :: params
+      integer(c_int) :: status
+    end function tucanos_capi_params_init
+
+    function tucanos_capi_mesh_new(dim, n_verts, coords, n_elems, elems, etags, n_faces, &
+        faces, ftags, index_base, mesh) result(status) bind(C, name="tucanos_capi_mesh_new")
+      import :: c_int, c_int32_t, c_int64_t, c_double, c_ptr
+      integer(c_int32_t), value :: dim
+      integer(c_int64_t), value :: n_verts
+      real(c_double), intent(in) :: coords(*)
+      integer(c_int64_t), value :: n_elems
+      integer(c_int64_t), intent(in) :: elems(*)
+      integer(c_int32_t), intent(in) :: etags(*)
+      integer(c_int64_t), value :: n_faces
+      integer(c_int64_t), intent(in) :: faces(*)
+      integer(c_int32_t), intent(in) :: ftags(*)
+      integer(c_int64_t), value :: index_base
+      type(c_ptr), intent(out) :: mesh
+      integer(c_int) :: status
+    end function tucanos_capi_mesh_new
+
+    function tucanos_capi_mesh_delete(mesh) result(status) &
+        bind(C, name="tucanos_capi_mesh_delete")
+      import :: c_int, c_ptr
+      type(c_ptr), value :: mesh
+      integer(c_int) :: status
+    end function tucanos_capi_mesh_delete
+
+    function tucanos_capi_mesh_sizes(mesh, dim, n_verts, n_elems, n_faces) result(status) &
+        bind(C, name="tucanos_capi_mesh_sizes")
+      import :: c_int, c_int32_t, c_int64_t, c_ptr
+      type(c_ptr), value :: mesh
+      integer(c_int32_t), intent(out) :: dim
+      integer(c_int64_t), intent(out) :: n_verts
+      integer(c_int64_t), intent(out) :: n_elems
+      integer(c_int64_t), intent(out) :: n_faces
+      integer(c_int) :: status
+    end function tucanos_capi_mesh_sizes
+
+    function tucanos_capi_mesh_get_verts(mesh, coords) result(status) &
+        bind(C, name="tucanos_capi_mesh_get_verts")
+      import :: c_int, c_double, c_ptr
+      type(c_ptr), value :: mesh
+      real(c_double), intent(out) :: coords(*)
+      integer(c_int) :: status
+    end function tucanos_capi_mesh_get_verts
+
+    function tucanos_capi_mesh_get_elems(mesh, elems, etags) result(status) &
+        bind(C, name="tucanos_capi_mesh_get_elems")
+      import :: c_int, c_int32_t, c_int64_t, c_ptr
+      type(c_ptr), value :: mesh
+      integer(c_int64_t), intent(out) :: elems(*)
+      integer(c_int32_t), intent(out) :: etags(*)
+      integer(c_int) :: status
+    end function tucanos_capi_mesh_get_elems
+
+    function tucanos_capi_mesh_get_faces(mesh, faces, ftags) result(status) &
+        bind(C, name="tucanos_capi_mesh_get_faces")
+      import :: c_int, c_int32_t, c_int64_t, c_ptr
+      type(c_ptr), value :: mesh
+      integer(c_int64_t), intent(out) :: faces(*)
+      integer(c_int32_t), intent(out) :: ftags(*)
+      integer(c_int) :: status
+    end function tucanos_capi_mesh_get_faces
+
+    function tucanos_capi_mesh_set_metric(mesh, n_comp, metric) result(status) &
+        bind(C, name="tucanos_capi_mesh_set_metric")
+      import :: c_int, c_int32_t, c_double, c_ptr
+      type(c_ptr), value :: mesh
+      integer(c_int32_t), value :: n_comp
+      real(c_double), intent(in) :: metric(*)
+      integer(c_int) :: status
+    end function tucanos_capi_mesh_set_metric
+
+    function tucanos_capi_mesh_metric_comp(mesh, n_comp) result(status) &
+        bind(C, name="tucanos_capi_mesh_metric_comp")
+      import :: c_int, c_int32_t, c_ptr
+      type(c_ptr), value :: mesh
+      integer(c_int32_t), intent(out) :: n_comp
+      integer(c_int) :: status
+    end function tucanos_capi_mesh_metric_comp
+
+    function tucanos_capi_mesh_get_metric(mesh, metric) result(status) &
+        bind(C, name="tucanos_capi_mesh_get_metric")
+      import :: c_int, c_double, c_ptr
+      type(c_ptr), value :: mesh
+      real(c_double), intent(out) :: metric(*)
+      integer(c_int) :: status
+    end function tucanos_capi_mesh_get_metric
+
+    ! params may be c_null_ptr to use the default parameters, or c_loc(params)
+    function tucanos_capi_mesh_remesh(mesh, params) result(status) &
+        bind(C, name="tucanos_capi_mesh_remesh")
+      import :: c_int, c_ptr
+      type(c_ptr), value :: mesh
+      type(c_ptr), value :: params
+      integer(c_int) :: status
+    end function tucanos_capi_mesh_remesh
+
+    function tucanos_capi_last_error(buf, len) result(status) &
+        bind(C, name="tucanos_capi_last_error")
+      import :: c_int, c_char, c_size_t
+      character(kind=c_char), intent(out) :: buf(*)
+      integer(c_size_t), value :: len
+      integer(c_int) :: status
+    end function tucanos_capi_last_error
+
+    function tucanos_capi_init_log() result(status) bind(C, name="tucanos_capi_init_log")
+      import :: c_int
+      integer(c_int) :: status
+    end function tucanos_capi_init_log
+  end interface
+end module tucanos_capi
@@ -0,0 +1,748 @@
+#![allow(non_camel_case_types)]
+#![allow(clippy::missing_safety_doc)]
+#![allow(clippy::doc_markdown)]
+use log::warn;
+use std::{
+    cell::RefCell,
+    ffi::{c_char, c_int},
+    panic::{catch_unwind, AssertUnwindSafe},
+};
+use tucanos::{
+    geometry::{Geometry, LinearGeometry},
+    mesh::{Elem, Point, SimplexMesh, Tetrahedron, Triangle},
+    metric::{AnisoMetric2d, AnisoMetric3d, IsoMetric, Metric},
+    remesher::{Remesher, RemesherParams},
+    Idx, Tag,
+};
+
+/// Version of the API, incremented on every incompatible change
+pub const TUCANOS_CAPI_VERSION: c_int = 1;
+
+/// Success
+pub const TUCANOS_OK: c_int = 0;
+/// A required pointer is null
+pub const TUCANOS_ERR_NULL_POINTER: c_int = 1;
+/// Invalid argument (dimension, size, vertex index or tag)
+pub const TUCANOS_ERR_INVALID_ARGUMENT: c_int = 2;
+/// No metric has been set
+pub const TUCANOS_ERR_NO_METRIC: c_int = 3;
+/// Error reported by Tucanos (e.g. during remeshing)
+pub const TUCANOS_ERR_FAILED: c_int = 4;
+/// Unexpected internal error (panic)
+pub const TUCANOS_ERR_INTERNAL: c_int = 5;
+
+thread_local! {
+    static LAST_ERROR: RefCell<String> = const { RefCell::new(String::new()) };
+}
+
+/// Error returned through the API as a status code
+struct CapiError {
+    code: c_int,
+    msg: String,
+}
+
+impl CapiError {
+    fn new(code: c_int, msg: &str) -> Self {
+        Self {
+            code,
+            msg: msg.to_string(),
+        }
+    }
+
+    fn invalid(msg: &str) -> Self {
+        Self::new(TUCANOS_ERR_INVALID_ARGUMENT, msg)
+    }
+}
+
+impl From<Box<dyn std::error::Error>> for CapiError {
+    fn from(e: Box<dyn std::error::Error>) -> Self {
+        Self::new(TUCANOS_ERR_FAILED, &e.to_string())
+    }
+}
+
+type CapiResult<T> = std::result::Result<T, CapiError>;
+
+/// Run `f`, converting its errors and panics to status codes
+fn run<F: FnOnce() -> CapiResult<()>>(f: F) -> c_int {
+    let err = match catch_unwind(AssertUnwindSafe(f)) {
+        Ok(Ok(())) => return TUCANOS_OK,
+        Ok(Err(e)) => e,
+        Err(p) => {
+            let msg = p
+                .downcast_ref::<&str>()
+                .map(|s| (*s).to_string())
+                .or_else(|| p.downcast_ref::<String>().cloned())
+                .unwrap_or_else(|| "unknown error".to_string());
+            CapiError::new(TUCANOS_ERR_INTERNAL, &msg)
+        }
+    };
+    warn!("{}", err.msg);
+    LAST_ERROR.with(|e| *e.borrow_mut() = err.msg);
+    err.code
+}
+
+/// Get a reference from a pointer
+unsafe fn as_ref<'a, T>(ptr: *const T) -> CapiResult<&'a T> {
+    ptr.as_ref()
+        .ok_or_else(|| CapiError::new(TUCANOS_ERR_NULL_POINTER, "null pointer"))
+}
+
+/// Get a mutable reference from a pointer
+unsafe fn as_mut<'a, T>(ptr: *mut T) -> CapiResult<&'a mut T> {
+    ptr.as_mut()
+        .ok_or_else(|| CapiError::new(TUCANOS_ERR_NULL_POINTER, "null pointer"))
+}
+
+/// Get a slice from a pointer and a size (the pointer may be null if `n == 0`)
+unsafe fn as_slice<'a, T>(ptr: *const T, n: usize) -> CapiResult<&'a [T]> {
+    if n == 0 {
+        Ok(&[])
+    } else if ptr.is_null() {
+        Err(CapiError::new(TUCANOS_ERR_NULL_POINTER, "null pointer"))
+    } else {
+        Ok(std::slice::from_raw_parts(ptr, n))
+    }
+}
+
+/// Get a mutable slice from a pointer and a size (the pointer may be null if `n == 0`)
+unsafe fn as_slice_mut<'a, T>(ptr: *mut T, n: usize) -> CapiResult<&'a mut [T]> {
+    if n == 0 {
+        Ok(&mut [])
+    } else if ptr.is_null() {
+        Err(CapiError::new(TUCANOS_ERR_NULL_POINTER, "null pointer"))
+    } else {
+        Ok(std::slice::from_raw_parts_mut(ptr, n))
+    }
+}
+
+/// Convert a size
+fn to_size(n: i64) -> CapiResult<usize> {
+    usize::try_from(n).map_err(|_| CapiError::invalid("negative size"))
+}
+
+/// Convert a tag
+fn to_tag(t: i32) -> CapiResult<Tag> {
+    let t64 = i64::from(t);
+    if t64 < Tag::MIN as i64 || t64 > Tag::MAX as i64 {
+        return Err(CapiError::invalid(&format!("tag {t} out of range")));
+    }
+    Ok(t as Tag)
+}
+
+/// A mesh of triangles in 2D or tetrahedra in 3D
+enum MeshImpl {
+    D2(SimplexMesh<2, Triangle>),
+    D3(SimplexMesh<3, Tetrahedron>),
+}
+
+/// Apply `$body` to the `SimplexMesh` in a `MeshImpl`
+macro_rules! dispatch {
+    ($m: expr, $mesh: ident => $body: expr) => {
+        match $m {
+            MeshImpl::D2($mesh) => $body,
+            MeshImpl::D3($mesh) => $body,
+        }
+    };
+}
+
+/// Opaque mesh handle, with the metric defined at its vertices
+pub struct tucanos_capi_mesh_t {
+    mesh: MeshImpl,
+    /// The metric, with `metric_comp` components per vertex
+    metric: Vec<f64>,
+    metric_comp: usize,
+    /// The offset of the vertex indices
+    index_base: i64,
+}
+
+/// Remeshing parameters (see `RemesherParams`), using `int` for the flags to be usable from
+/// Fortran
+#[repr(C)]
+pub struct tucanos_capi_params_t {
+    /// Number of collapse - split - swap - smooth loops
+    pub num_iter: i32,
+    /// Perform a first loop targetting only the longest edges (0 / 1)
+    pub two_steps: i32,
+    /// Max. number of loops through the mesh edges during the split step
+    pub split_max_iter: i32,
+    /// Max. number of loops through the mesh edges during the collapse step
+    pub collapse_max_iter: i32,
+    /// Max. number of loops through the mesh edges during the swap step
+    pub swap_max_iter: i32,
+    /// Number of smoothing steps
+    pub smooth_iter: i32,
+    /// Max angle between the normals of the new faces and the geometry (in degrees)
+    pub max_angle: f64,
+    /// Debug mode (0 / 1)
+    pub debug: i32,
+}
+
+/// Convert the remeshing parameters
+fn to_params(params: &tucanos_capi_params_t) -> CapiResult<RemesherParams> {
+    let iter = |n: i32| u32::try_from(n).map_err(|_| CapiError::invalid("negative parameter"));
+    Ok(RemesherParams {
+        num_iter: iter(params.num_iter)?,
+        two_steps: params.two_steps != 0,
+        split_max_iter: iter(params.split_max_iter)?,
+        collapse_max_iter: iter(params.collapse_max_iter)?,
+        swap_max_iter: iter(params.swap_max_iter)?,
+        smooth_iter: iter(params.smooth_iter)?,
+        max_angle: params.max_angle,
+        debug: params.debug != 0,
+        ..RemesherParams::default()
+    })
+}
+
+/// @brief Initialize `params` with the default values
+#[no_mangle]
+pub unsafe extern "C" fn tucanos_capi_params_init(params: *mut tucanos_capi_params_t) -> c_int {
+    run(|| {
+        let params = as_mut(params)?;
+        let default = RemesherParams::default();
+        *params = tucanos_capi_params_t {
+            num_iter: default.num_iter as i32,
+            two_steps: i32::from(default.two_steps),
+            split_max_iter: default.split_max_iter as i32,
+            collapse_max_iter: default.collapse_max_iter as i32,
+            swap_max_iter: default.swap_max_iter as i32,
+            smooth_iter: default.smooth_iter as i32,
+            max_angle: default.max_angle,
+            debug: i32::from(default.debug),
+        };
+        Ok(())
+    })
+}
+
+/// Create a `SimplexMesh` from flat arrays, checking the vertex indices and tags
+fn new_mesh<const D: usize, E: Elem>(
+    coords: &[f64],
+    elems: &[i64],
+    etags: &[i32],
+    faces: &[i64],
+    ftags: &[i32],
+    index_base: i64,
+) -> CapiResult<SimplexMesh<D, E>> {
+    let n_verts = (coords.len() / D) as i64;
+    let idx = |i: i64| {
+        let i = i - index_base;
+        if i < 0 || i >= n_verts {
+            return Err(CapiError::invalid(&format!(
+                "vertex index {} out of range",
+                i + index_base
+            )));
+        }
+        Ok(i as Idx)
+    };
+    let to_elems = |conn: &[i64], n: Idx| {
+        conn.chunks(n as usize)
+            .map(|e| e.iter().map(|&i| idx(i)).collect::<CapiResult<Vec<_>>>())
+            .collect::<CapiResult<Vec<_>>>()
+    };
+
+    let verts = coords
+        .chunks(D)
+        .map(Point::<D>::from_column_slice)
+        .collect();
+    let elems = to_elems(elems, E::N_VERTS)?
+        .iter()
+        .map(|e| E::from_slice(e))
+        .collect();
+    let etags = etags
+        .iter()
+        .map(|&t| to_tag(t))
+        .collect::<CapiResult<_>>()?;
+    let faces = to_elems(faces, E::Face::N_VERTS)?
+        .iter()
+        .map(|f| E::Face::from_slice(f))
+        .collect();
+    let ftags = ftags
+        .iter()
+        .map(|&t| to_tag(t))
+        .collect::<CapiResult<_>>()?;
+
+    Ok(SimplexMesh::new(verts, elems, etags, faces, ftags))
+}
+
+/// @brief Create a mesh of triangles (`dim = 2`) or tetrahedra (`dim = 3`) from flat arrays.
+/// The data is copied
+///
+/// @param coords The vertex coordinates (`dim * n_verts` values)
+/// @param elems The element connectivity (`(dim + 1) * n_elems` vertex indices)
+/// @param etags The element tags (`n_elems` values)
+/// @param faces The boundary face connectivity (`dim * n_faces` vertex indices)
+/// @param ftags The face tags (`n_faces` values)
+/// @param index_base The index of the first vertex (0 in C, usually 1 in Fortran), used for
+/// all the connectivities passed to or returned by the API
+/// @param mesh The created mesh, to be freed with `tucanos_capi_mesh_delete`
+#[no_mangle]
+pub unsafe extern "C" fn tucanos_capi_mesh_new(
+    dim: i32,
+    n_verts: i64,
+    coords: *const f64,
+    n_elems: i64,
+    elems: *const i64,
+    etags: *const i32,
+    n_faces: i64,
+    faces: *const i64,
+    ftags: *const i32,
+    index_base: i64,
+    mesh: *mut *mut tucanos_capi_mesh_t,
+) -> c_int {
+    run(|| {
+        let out = as_mut(mesh)?;
+        let (n_verts, n_elems, n_faces) = (to_size(n_verts)?, to_size(n_elems)?, to_size(n_faces)?);
+        let d = match dim {
+            2 => 2,
+            3 => 3,
+            _ => return Err(CapiError::invalid(&format!("invalid dimension {dim}"))),
+        };
+        let coords = as_slice(coords, d * n_verts)?;
+        let elems = as_slice(elems, (d + 1) * n_elems)?;
+        let etags = as_slice(etags, n_elems)?;
+        let faces = as_slice(faces, d * n_faces)?;
+        let ftags = as_slice(ftags, n_faces)?;
+        let implem = if d == 2 {
+            MeshImpl::D2(new_mesh(coords, elems, etags, faces, ftags, index_base)?)
+        } else {
+            MeshImpl::D3(new_mesh(coords, elems, etags, faces, ftags, index_base)?)
+        };
+        *out = Box::into_raw(Box::new(tucanos_capi_mesh_t {
+            mesh: implem,
+            metric: Vec::new(),
+            metric_comp: 0,
+            index_base,
+        }));
+        Ok(())
+    })
+}
+
+/// @brief Free a mesh created by `tucanos_capi_mesh_new` (a null pointer is ignored)
+#[no_mangle]
+pub unsafe extern "C" fn tucanos_capi_mesh_delete(mesh: *mut tucanos_capi_mesh_t) -> c_int {
+    run(|| {
+        if !mesh.is_null() {
+            drop(Box::from_raw(mesh));
+        }
+        Ok(())
+    })
+}
+
+/// @brief Get the dimension and the # of vertices, elements and faces of a mesh
+#[no_mangle]
+pub unsafe extern "C" fn tucanos_capi_mesh_sizes(
+    mesh: *const tucanos_capi_mesh_t,
+    dim: *mut i32,
+    n_verts: *mut i64,
+    n_elems: *mut i64,
+    n_faces: *mut i64,
+) -> c_int {
+    run(|| {
+        let mesh = as_ref(mesh)?;
+        *as_mut(dim)? = match mesh.mesh {
+            MeshImpl::D2(_) => 2,
+            MeshImpl::D3(_) => 3,
+        };
+        dispatch!(&mesh.mesh, m => {
+            *as_mut(n_verts)? = m.n_verts() as i64;
+            *as_mut(n_elems)? = m.n_elems() as i64;
+            *as_mut(n_faces)? = m.n_faces() as i64;
+        });
+        Ok(())
+    })
+}
+
+/// Write the vertex coordinates to a flat array
+unsafe fn write_verts<const D: usize, E: Elem>(
+    mesh: &SimplexMesh<D, E>,
+    coords: *mut f64,
+) -> CapiResult<()> {
+    let coords = as_slice_mut(coords, D * mesh.n_verts() as usize)?;
+    coords
+        .chunks_mut(D)
+        .zip(mesh.verts())
+        .for_each(|(x, p)| x.copy_from_slice(p.as_slice()));
+    Ok(())
+}
+
+/// Write `n` element connectivities and tags to flat arrays
+unsafe fn write_elems<E: Elem>(
+    n: usize,
+    elems: impl Iterator<Item = E>,
+    tags: impl Iterator<Item = Tag>,
+    index_base: i64,
+    out: *mut i64,
+    out_tags: *mut i32,
+) -> CapiResult<()> {
+    let out = as_slice_mut(out, n * E::N_VERTS as usize)?;
+    let out_tags = as_slice_mut(out_tags, n)?;
+    out.iter_mut()
+        .zip(elems.flatten())
+        .for_each(|(x, i)| *x = i as i64 + index_base);
+    out_tags
+        .iter_mut()
+        .zip(tags)
+        .for_each(|(x, t)| *x = t as i32);
+    Ok(())
+}
+
+/// @brief Get the vertex coordinates (`dim * n_verts` values)
+#[no_mangle]
+pub unsafe extern "C" fn tucanos_capi_mesh_get_verts(
+    mesh: *const tucanos_capi_mesh_t,
+    coords: *mut f64,
+) -> c_int {
+    run(|| {
+        let mesh = as_ref(mesh)?;
+        dispatch!(&mesh.mesh, m => write_verts(m, coords))
+    })
+}
+
+/// @brief Get the element connectivity (`(dim + 1) * n_elems` values) and tags (`n_elems`
+/// values)
+#[no_mangle]
+pub unsafe extern "C" fn tucanos_capi_mesh_get_elems(
+    mesh: *const tucanos_capi_mesh_t,
+    elems: *mut i64,
+    etags: *mut i32,
+) -> c_int {
+    run(|| {
+        let mesh = as_ref(mesh)?;
+        dispatch!(&mesh.mesh, m => {
+            let n = m.n_elems() as usize;
+            write_elems(n, m.elems(), m.etags(), mesh.index_base, elems, etags)
+        })
+    })
+}
+
+/// @brief Get the face connectivity (`dim * n_faces` values) and tags (`n_faces` values)
+#[no_mangle]
+pub unsafe extern "C" fn tucanos_capi_mesh_get_faces(
+    mesh: *const tucanos_capi_mesh_t,
+    faces: *mut i64,
+    ftags: *mut i32,
+) -> c_int {
+    run(|| {
+        let mesh = as_ref(mesh)?;
+        dispatch!(&mesh.mesh, m => {
+            let n = m.n_faces() as usize;
+            write_elems(n, m.faces(), m.ftags(), mesh.index_base, faces, ftags)
+        })
+    })
+}
+
+/// @brief Set the metric at the vertices of a mesh, with `n_comp` values per vertex: 1 for an
+/// isotropic metric (the size), 3 in 2D or 6 in 3D for an anisotropic metric (the upper
+/// triangular part of the symmetric matrix, see `AnisoMetric2d` / `AnisoMetric3d`)
+#[no_mangle]
+pub unsafe extern "C" fn tucanos_capi_mesh_set_metric(
+    mesh: *mut tucanos_capi_mesh_t,
+    n_comp: i32,
+    metric: *const f64,
+) -> c_int {
+    run(|| {
+        let mesh = as_mut(mesh)?;
+        let (n_verts, n_aniso) = match &mesh.mesh {
+            MeshImpl::D2(m) => (m.n_verts() as usize, AnisoMetric2d::N),
+            MeshImpl::D3(m) => (m.n_verts() as usize, AnisoMetric3d::N),
+        };
+        let n_comp = to_size(i64::from(n_comp))?;
+        if n_comp != 1 && n_comp != n_aniso {
+            return Err(CapiError::invalid(&format!(
+                "invalid # of metric components {n_comp}"
+            )));
+        }
+        let metric = as_slice(metric, n_comp * n_verts)?;
+        if metric.iter().any(|x| !x.is_finite()) {
+            return Err(CapiError::invalid("invalid metric values"));
+        }
+        mesh.metric = metric.to_vec();
+        mesh.metric_comp = n_comp;
+        Ok(())
+    })
+}
+
+/// @brief Get the # of metric components per vertex (0 if no metric has been set)
+#[no_mangle]
+pub unsafe extern "C" fn tucanos_capi_mesh_metric_comp(
+    mesh: *const tucanos_capi_mesh_t,
+    n_comp: *mut i32,
+) -> c_int {
+    run(|| {
+        *as_mut(n_comp)? = as_ref(mesh)?.metric_comp as i32;
+        Ok(())
+    })
+}
+
+/// @brief Get the metric at the vertices (`n_comp * n_verts` values). After remeshing, the
+/// metric is interpolated on the new vertices
+#[no_mangle]
+pub unsafe extern "C" fn tucanos_capi_mesh_get_metric(
+    mesh: *const tucanos_capi_mesh_t,
+    metric: *mut f64,
+) -> c_int {
+    run(|| {
+        let mesh = as_ref(mesh)?;
+        as_slice_mut(metric, mesh.metric.len())?.copy_from_slice(&mesh.metric);
+        Ok(())
+    })
+}
+
+/// Remesh `mesh` with metric `m` and replace it (and `m`) by the result
+fn remesh<const D: usize, E: Elem, M: Metric<D>, G: Geometry<D>>(
+    mesh: &mut SimplexMesh<D, E>,
+    m: &mut Vec<f64>,
+    geom: &G,
+    params: RemesherParams,
+) -> CapiResult<()> {
+    let metric = m.chunks(M::N).map(M::from_slice).collect::<Vec<_>>();
+    let mut remesher = Remesher::new(mesh, &metric, geom)?;
+    remesher.remesh(params, geom)?;
+    *mesh = remesher.to_mesh(false);
+    *m = remesher.metrics().into_iter().flatten().collect();
+    Ok(())
+}
+
+/// @brief Remesh a mesh to match its metric (see `tucanos_capi_mesh_set_metric`). The
+/// geometry is defined by the boundary faces of the input mesh. On success, the mesh and the
+/// metric are replaced by the adapted mesh and the metric interpolated on it
+///
+/// @param params The remeshing parameters, or null to use the default values
+#[no_mangle]
+pub unsafe extern "C" fn tucanos_capi_mesh_remesh(
+    mesh: *mut tucanos_capi_mesh_t,
+    params: *const tucanos_capi_params_t,
+) -> c_int {
+    run(|| {
+        let mesh = as_mut(mesh)?;
+        let params = params
+            .as_ref()
+            .map_or_else(|| Ok(RemesherParams::default()), to_params)?;
+        if mesh.metric_comp == 0 {
+            return Err(CapiError::new(TUCANOS_ERR_NO_METRIC, "no metric set"));
+        }
+        let (metric, iso) = (&mut mesh.metric, mesh.metric_comp == 1);
+        match &mut mesh.mesh {
+            MeshImpl::D2(m) => {
+                m.compute_topology();
+                let geom = LinearGeometry::new(m, m.boundary().0)?;
+                if iso {
+                    remesh::<2, _, IsoMetric<2>, _>(m, metric, &geom, params)
+                } else {
+                    remesh::<2, _, AnisoMetric2d, _>(m, metric, &geom, params)
+                }
+            }
+            MeshImpl::D3(m) => {
+                m.compute_topology();
+                let geom = LinearGeometry::new(m, m.boundary().0)?;
+                if iso {
+                    remesh::<3, _, IsoMetric<3>, _>(m, metric, &geom, params)
+                } else {
+                    remesh::<3, _, AnisoMetric3d, _>(m, metric, &geom, params)
+                }
+            }
+        }
+    })
+}
+
+/// @brief Copy the last error message of the current thread in `buf` (truncated to `len - 1`
+/// characters and null terminated)
+#[no_mangle]
+pub unsafe extern "C" fn tucanos_capi_last_error(buf: *mut c_char, len: usize) -> c_int {
+    run(|| {
+        let buf = as_slice_mut(buf, len)?;
+        if let Some((last, buf)) = buf.split_last_mut() {
+            LAST_ERROR.with(|e| {
+                let e = e.borrow();
+                let n = e.len().min(buf.len());
+                for (x, &y) in buf.iter_mut().zip(e.as_bytes()) {
+                    *x = y as c_char;
+                }
+                buf[n..].fill(0);
+            });
+            *last = 0;
+        }
+        Ok(())
+    })
+}
+
+/// @brief Enable Rust logger (the default level is `warn`). Calling it more than once has no
+/// effect
+///
+/// See <https://docs.rs/env_logger/latest/env_logger/#enabling-logging> for details
+#[no_mangle]
+pub unsafe extern "C" fn tucanos_capi_init_log() -> c_int {
+    run(|| {
+        // fails only if a logger is already set
+        let _ = env_logger::Builder::from_env(env_logger::Env::default().default_filter_or("warn"))
+            .format_timestamp(None)
+            .try_init();
+        Ok(())
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::ffi::CStr;
+
+    /// Unit square with 1-based indices
+    unsafe fn square() -> *mut tucanos_capi_mesh_t {
+        let coords = [0.0, 0.0, 1.0, 0.0, 1.0, 1.0, 0.0, 1.0];
+        let elems = [1, 2, 3, 1, 3, 4];
+        let etags = [1, 1];
+        let faces = [1, 2, 2, 3, 3, 4, 4, 1];
+        let ftags = [1, 2, 3, 4];
+        let mut mesh = std::ptr::null_mut();
+        let status = tucanos_capi_mesh_new(
+            2,
+            4,
+            coords.as_ptr(),
+            2,
+            elems.as_ptr(),
+            etags.as_ptr(),
+            4,
+            faces.as_ptr(),
+            ftags.as_ptr(),
+            1,
+            &mut mesh,
+        );
+        assert_eq!(status, TUCANOS_OK);
+        mesh
+    }
+
+    unsafe fn last_error() -> String {
+        let mut buf = [0; 256];
+        assert_eq!(
+            tucanos_capi_last_error(buf.as_mut_ptr(), buf.len()),
+            TUCANOS_OK
+        );
+        CStr::from_ptr(buf.as_ptr()).to_str().unwrap().to_string()
+    }
+
+    unsafe fn sizes(mesh: *const tucanos_capi_mesh_t) -> (i32, i64, i64, i64) {
+        let (mut dim, mut n_verts, mut n_elems, mut n_faces) = (0, 0, 0, 0);
+        let status =
+            tucanos_capi_mesh_sizes(mesh, &mut dim, &mut n_verts, &mut n_elems, &mut n_faces);
+        assert_eq!(status, TUCANOS_OK);
+        (dim, n_verts, n_elems, n_faces)
+    }
+
+    #[test]
+    fn test_remesh_2d() {
+        unsafe {
+            let mesh = square();
+            assert_eq!(sizes(mesh), (2, 4, 2, 4));
+
+            let mut params = std::mem::zeroed();
+            assert_eq!(tucanos_capi_params_init(&mut params), TUCANOS_OK);
+            assert_eq!(params.num_iter, 4);
+            assert_eq!(
+                tucanos_capi_mesh_remesh(mesh, &params),
+                TUCANOS_ERR_NO_METRIC
+            );
+
+            let metric = [0.2; 4];
+            assert_eq!(
+                tucanos_capi_mesh_set_metric(mesh, 1, metric.as_ptr()),
+                TUCANOS_OK
+            );
+            assert_eq!(tucanos_capi_mesh_remesh(mesh, &params), TUCANOS_OK);
+
+            let (_, n_verts, n_elems, n_faces) = sizes(mesh);
+            assert!(n_elems > 20);
+            let mut coords = vec![0.0; 2 * n_verts as usize];
+            assert_eq!(
+                tucanos_capi_mesh_get_verts(mesh, coords.as_mut_ptr()),
+                TUCANOS_OK
+            );
+            assert!(coords.iter().all(|&x| (-1e-12..=1.0 + 1e-12).contains(&x)));
+
+            let mut elems = vec![0; 3 * n_elems as usize];
+            let mut etags = vec![0; n_elems as usize];
+            let status = tucanos_capi_mesh_get_elems(mesh, elems.as_mut_ptr(), etags.as_mut_ptr());
+            assert_eq!(status, TUCANOS_OK);
+            assert!(elems.iter().all(|&i| (1..=n_verts).contains(&i)));
+            assert!(etags.iter().all(|&t| t == 1));
+
+            let mut faces = vec![0; 2 * n_faces as usize];
+            let mut ftags = vec![0; n_faces as usize];
+            let status = tucanos_capi_mesh_get_faces(mesh, faces.as_mut_ptr(), ftags.as_mut_ptr());
+            assert_eq!(status, TUCANOS_OK);
+            assert!(faces.iter().all(|&i| (1..=n_verts).contains(&i)));
+            assert!(ftags.iter().all(|&t| (1..=4).contains(&t)));
+
+            let mut n_comp = 0;
+            assert_eq!(tucanos_capi_mesh_metric_comp(mesh, &mut n_comp), TUCANOS_OK);
+            assert_eq!(n_comp, 1);
+            let mut metric = vec![0.0; n_verts as usize];
+            assert_eq!(
+                tucanos_capi_mesh_get_metric(mesh, metric.as_mut_ptr()),
+                TUCANOS_OK
+            );
+            assert!(metric.iter().all(|&h| (h - 0.2).abs() < 1e-12));
+
+            assert_eq!(tucanos_capi_mesh_delete(mesh), TUCANOS_OK);
+        }
+    }
+
+    #[test]
+    fn test_errors() {
+        unsafe {
+            let coords = [0.0, 0.0, 1.0, 0.0, 1.0, 1.0];
+            let elems = [0, 1, 3];
+            let etags = [1];
+            let mut mesh = std::ptr::null_mut();
+            let status = tucanos_capi_mesh_new(
+                2,
+                3,
+                coords.as_ptr(),
+                1,
+                elems.as_ptr(),
+                etags.as_ptr(),
+                0,
+                std::ptr::null(),
+                std::ptr::null(),
+                0,
+                &mut mesh,
+            );
+            assert_eq!(status, TUCANOS_ERR_INVALID_ARGUMENT);
+            assert!(mesh.is_null());
+            assert_eq!(last_error(), "vertex index 3 out of range");
+
+            let status = tucanos_capi_mesh_new(
+                4,
+                3,
+                coords.as_ptr(),
+                1,
+                elems.as_ptr(),
+                etags.as_ptr(),
+                0,
+                std::ptr::null(),
+                std::ptr::null(),
+                0,
+                &mut mesh,
+            );
+            assert_eq!(status, TUCANOS_ERR_INVALID_ARGUMENT);
+
+            let mut n = 0;
+            let status = tucanos_capi_mesh_metric_comp(std::ptr::null(), &mut n);
+            assert_eq!(status, TUCANOS_ERR_NULL_POINTER);
+
+            let mesh = square();
+            let metric = [0.2; 12];
+            let status = tucanos_capi_mesh_set_metric(mesh, 2, metric.as_ptr());
+            assert_eq!(status, TUCANOS_ERR_INVALID_ARGUMENT);
+            let status = tucanos_capi_mesh_set_metric(mesh, 3, std::ptr::null());
+            assert_eq!(status, TUCANOS_ERR_NULL_POINTER);
+            assert_eq!(tucanos_capi_mesh_delete(mesh), TUCANOS_OK);
+        }
+    }
+
+    #[test]
+    fn test_init_log() {
+        unsafe {
+            assert_eq!(tucanos_capi_init_log(), TUCANOS_OK);
+            assert_eq!(tucanos_capi_init_log(), TUCANOS_OK);
+        }
+    }
+}
@@ -7,6 +7,10 @@ const TUCANOS_TAG: Option<&str> = Some("#define TUCANOS_TAG_64");
 const TUCANOS_TAG: Option<&str> = Some("#define TUCANOS_TAG_32");
 #[cfg(not(any(feature = "32bit-tags", feature = "64bit-tags")))]
 const TUCANOS_TAG: Option<&str> = None;
+#[cfg(feature = "large-indices")]
+const TUCANOS_IDX: Option<&str> = Some("#define TUCANOS_IDX_64");
+#[cfg(not(feature = "large-indices"))]
+const TUCANOS_IDX: Option<&str> = None;
 
 fn main() {
     println!("cargo:rerun-if-env-changed=DEP_TUCANOS_RPATH");
@@ -24,7 +28,11 @@ fn main() {
         usize_is_size_t: true,
         language: cbindgen::Language::C,
         style: cbindgen::Style::Both,
-        after_includes: TUCANOS_TAG.map(std::convert::Into::into),
+        after_includes: [TUCANOS_TAG, TUCANOS_IDX]
+            .into_iter()
+            .flatten()
+            .reduce(|a, b| format!("{a}\n{b}"))
+            .map(std::convert::Into::into),
         ..Default::default()
     };
     cbindgen::Builder::new()
@@ -34,6 +42,7 @@ fn main() {
         .with_crate(".")
         .with_define("feature", "64bit-tags", "TUCANOS_TAG_64")
         .with_define("feature", "32bit-tags", "TUCANOS_TAG_32")
+        .with_define("feature", "large-indices", "TUCANOS_IDX_64")
         .generate()
         .expect("Unable to generate bindings")
         .write_to_file(out_file);
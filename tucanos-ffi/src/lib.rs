@@ -38,6 +38,11 @@ pub type tucanos_tag_t = i32;
 #[cfg(not(any(feature = "32bit-tags", feature = "64bit-tags")))]
 pub type tucanos_tag_t = i16;
 
+#[cfg(feature = "large-indices")]
+pub type tucanos_idx_t = u64;
+#[cfg(not(feature = "large-indices"))]
+pub type tucanos_idx_t = u32;
+
 #[repr(C)]
 pub struct tucanos_params_t {
     /// Number of collapse - split - swap - smooth loops
@@ -163,7 +168,7 @@ pub unsafe extern "C" fn tucanos_geom3d_delete(geom: *mut tucanos_geom3d_t) {
 
 unsafe fn new_metric<const D: usize, MT: Metric<D>>(
     metric: *const f64,
-    num_points: u32,
+    num_points: tucanos_idx_t,
 ) -> impl ExactSizeIterator<Item = MT> {
     let metric = std::slice::from_raw_parts(metric, num_points as usize * MT::N);
     metric.chunks(MT::N).map(MT::from_slice)
@@ -277,10 +282,10 @@ pub extern "C" fn tucanos_mesh33_new(
     num_verts: usize,
     verts: *const f64,
     num_elements: usize,
-    elems: *const u32,
+    elems: *const tucanos_idx_t,
     tags: *const tucanos_tag_t,
     num_faces: usize,
-    faces: *const u32,
+    faces: *const tucanos_idx_t,
     ftags: *const tucanos_tag_t,
 ) -> *mut tucanos_mesh33_t {
     let mut m = SimplexMesh::new_with_vector(
@@ -295,12 +300,12 @@ pub extern "C" fn tucanos_mesh33_new(
 }
 
 #[no_mangle]
-pub unsafe extern "C" fn tucanos_mesh33_num_verts(m: *const tucanos_mesh33_t) -> u32 {
+pub unsafe extern "C" fn tucanos_mesh33_num_verts(m: *const tucanos_mesh33_t) -> tucanos_idx_t {
     (*m).implem.n_verts()
 }
 
 #[no_mangle]
-pub unsafe extern "C" fn tucanos_mesh33_num_elems(m: *const tucanos_mesh33_t) -> u32 {
+pub unsafe extern "C" fn tucanos_mesh33_num_elems(m: *const tucanos_mesh33_t) -> tucanos_idx_t {
     (*m).implem.n_elems()
 }
 
@@ -308,8 +313,8 @@ pub unsafe extern "C" fn tucanos_mesh33_num_elems(m: *const tucanos_mesh33_t) ->
 pub unsafe extern "C" fn tucanos_mesh33_verts(
     m: *const tucanos_mesh33_t,
     out: *mut f64,
-    first: u32,
-    last: u32,
+    first: tucanos_idx_t,
+    last: tucanos_idx_t,
 ) {
     let out = std::slice::from_raw_parts_mut(out, (last - first) as usize);
     let m = &((*m).implem);
@@ -323,9 +328,9 @@ pub unsafe extern "C" fn tucanos_mesh33_verts(
 #[no_mangle]
 pub unsafe extern "C" fn tucanos_mesh33_elems(
     m: *const tucanos_mesh33_t,
-    out: *mut u32,
-    first: u32,
-    last: u32,
+    out: *mut tucanos_idx_t,
+    first: tucanos_idx_t,
+    last: tucanos_idx_t,
 ) {
     let out = std::slice::from_raw_parts_mut(out, (last - first) as usize);
     let m = &((*m).implem);